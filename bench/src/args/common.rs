@@ -39,6 +39,10 @@ pub struct IggyBenchArgs {
     /// Skip server start
     #[arg(long, short = 'k', default_value_t = DEFAULT_SKIP_SERVER_START)]
     pub skip_server_start: bool,
+
+    /// Server storage backend, `io_uring` requires iggy-server built with the `io-uring` feature
+    #[arg(long, short = 'b', default_value_t = DEFAULT_SERVER_STORAGE_BACKEND.to_owned(), value_parser = ["file", "io_uring"])]
+    pub server_storage_backend: String,
 }
 
 fn validate_server_executable_path(v: &str) -> Result<String, String> {
@@ -34,3 +34,4 @@ pub const DEFAULT_SERVER_STDOUT_VISIBILITY: bool = false;
 
 pub const DEFAULT_WARMUP_TIME: &str = "1 s";
 pub const DEFAULT_SKIP_SERVER_START: bool = false;
+pub const DEFAULT_SERVER_STORAGE_BACKEND: &str = "file";
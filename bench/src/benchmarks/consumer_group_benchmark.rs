@@ -5,7 +5,8 @@ use crate::{
 };
 use async_trait::async_trait;
 use iggy::{
-    client::ConsumerGroupClient, clients::client::IggyClient, error::IggyError,
+    client::ConsumerGroupClient, clients::client::IggyClient,
+    consumer_groups::consumer_group_mode::ConsumerGroupMode, error::IggyError,
     utils::byte_size::IggyByteSize,
 };
 use integration::test_server::{login_root, ClientFactory};
@@ -49,6 +50,7 @@ impl ConsumerGroupBenchmark {
                     &topic_id.try_into().unwrap(),
                     &consumer_group_name,
                     Some(consumer_group_id),
+                    ConsumerGroupMode::Offset,
                 )
                 .await;
             if cg.is_err() {
@@ -87,6 +87,10 @@ pub async fn start_server_if_needed(args: &mut IggyBenchArgs) -> Option<TestServ
             SYSTEM_PATH_ENV_VAR.to_owned(),
             args.server_system_path.clone(),
         );
+        envs.insert(
+            "IGGY_SYSTEM_STORAGE_BACKEND".to_owned(),
+            args.server_storage_backend.clone(),
+        );
 
         if args.verbose {
             envs.insert("IGGY_TEST_VERBOSE".to_owned(), "true".to_owned());
@@ -96,9 +100,10 @@ pub async fn start_server_if_needed(args: &mut IggyBenchArgs) -> Option<TestServ
         }
 
         info!(
-            "Starting test server, transport: {}, data path: {}, cleanup: {}, verbosity: {}",
+            "Starting test server, transport: {}, data path: {}, storage backend: {}, cleanup: {}, verbosity: {}",
             args.transport(),
             args.server_system_path,
+            args.server_storage_backend,
             args.cleanup,
             args.verbose
         );
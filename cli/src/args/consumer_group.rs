@@ -1,6 +1,7 @@
 use crate::args::common::ListMode;
 use clap::{Args, Subcommand};
 use iggy::identifier::Identifier;
+use iggy::utils::duration::IggyDuration;
 
 #[derive(Debug, Clone, Subcommand)]
 pub(crate) enum ConsumerGroupAction {
@@ -9,12 +10,14 @@ pub(crate) enum ConsumerGroupAction {
     /// Stream ID can be specified as a stream name or ID
     /// Topic ID can be specified as a topic name or ID
     /// If group ID is not provided then the server will automatically assign it
+    /// If visibility timeout is provided then the group is created in queue mode
     ///
     /// Examples:
     ///  iggy consumer-group create 1 1 prod
     ///  iggy consumer-group create stream 2 test
     ///  iggy consumer-group create 2 topic receiver
     ///  iggy consumer-group create -g 4 stream topic group
+    ///  iggy consumer-group create -v 30s stream topic workers
     #[clap(verbatim_doc_comment, visible_alias = "c")]
     Create(ConsumerGroupCreateArgs),
     /// Delete consumer group with given ID for given stream ID and topic ID
@@ -82,6 +85,13 @@ pub(crate) struct ConsumerGroupCreateArgs {
     pub(crate) group_id: Option<u32>,
     /// Consumer group name to create
     pub(crate) name: String,
+    /// Visibility timeout of the messages leased to the members, e.g. 30s
+    ///
+    /// When provided, the consumer group is created in queue mode,
+    /// in which the messages have to be acknowledged one by one
+    /// and are redelivered when not acknowledged within the timeout
+    #[clap(short, long, value_parser = clap::value_parser!(IggyDuration), verbatim_doc_comment)]
+    pub(crate) visibility_timeout: Option<IggyDuration>,
 }
 
 #[derive(Debug, Clone, Args)]
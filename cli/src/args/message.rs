@@ -3,6 +3,7 @@ use clap::{ArgGroup, Args, Subcommand};
 use iggy::error::IggyError;
 use iggy::error::IggyError::InvalidFormat;
 use iggy::identifier::Identifier;
+use iggy::messages::message_filter::MessageFilter;
 use iggy::models::header::{HeaderKey, HeaderValue};
 use std::str::FromStr;
 
@@ -30,6 +31,7 @@ pub(crate) enum MessageAction {
     ///  iggy message poll --offset 0 stream 2 1
     ///  iggy message poll --offset 0 1 topic 1
     ///  iggy message poll --offset 0 stream topic 1
+    ///  iggy message poll --offset 0 --filter "tenant == 'acme'" stream topic 1
     #[clap(verbatim_doc_comment, visible_alias = "p")]
     Poll(PollMessagesArgs),
     /// Flush messages from given topic ID and given stream ID
@@ -49,6 +51,33 @@ pub(crate) enum MessageAction {
     ///  iggy message flush stream topic 1
     #[clap(verbatim_doc_comment, visible_alias = "f")]
     Flush(FlushMessagesArgs),
+    /// Inspect dead letters from given dead letter queue topic ID and given stream ID
+    ///
+    /// Prints the messages moved to the dead letter queue along with
+    /// their origin stream, topic, partition and offset, the number
+    /// of the delivery attempts and the reason of the last nack.
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    /// Topic ID can be specified as a topic name or ID
+    ///
+    /// Examples:
+    ///  iggy message dlq-inspect 1 2 1
+    ///  iggy message dlq-inspect --offset 10 --message-count 5 stream topic 1
+    #[clap(verbatim_doc_comment)]
+    DlqInspect(DeadLettersArgs),
+    /// Replay dead letters from given dead letter queue topic ID and given stream ID
+    ///
+    /// Sends the messages moved to the dead letter queue back to their
+    /// origin stream, topic and partition, without the dead letter headers.
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    /// Topic ID can be specified as a topic name or ID
+    ///
+    /// Examples:
+    ///  iggy message dlq-replay 1 2 1
+    ///  iggy message dlq-replay --offset 10 --message-count 5 stream topic 1
+    #[clap(verbatim_doc_comment)]
+    DlqReplay(DeadLettersArgs),
 }
 
 #[derive(Debug, Clone, Args)]
@@ -189,6 +218,16 @@ pub(crate) struct PollMessagesArgs {
     #[clap(verbatim_doc_comment)]
     #[clap(long, value_parser = NonEmptyStringValueParser::new())]
     pub(crate) output_file: Option<String>,
+    /// Filter expression evaluated by the server against the message headers
+    ///
+    /// Only the messages with headers matching the expression are returned.
+    /// Header values are compared with the string (quoted), number or bool
+    /// literals using ==, !=, >, >=, < and <= operators, and the comparisons
+    /// can be combined using &&, || and ! operators and parentheses,
+    /// for example: "tenant == 'acme' && priority >= 3"
+    #[clap(verbatim_doc_comment)]
+    #[clap(long, value_parser = clap::value_parser!(MessageFilter))]
+    pub(crate) filter: Option<MessageFilter>,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -215,6 +254,29 @@ pub(crate) struct FlushMessagesArgs {
     pub(crate) fsync: bool,
 }
 
+#[derive(Debug, Clone, Args)]
+pub(crate) struct DeadLettersArgs {
+    /// ID of the stream of the dead letter queue topic
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) stream_id: Identifier,
+    /// ID of the dead letter queue topic
+    ///
+    /// Topic ID can be specified as a topic name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) topic_id: Identifier,
+    /// Partition ID of the dead letter queue topic
+    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    pub(crate) partition_id: u32,
+    /// Offset of the first dead letter
+    #[clap(short, long, default_value_t = 0)]
+    pub(crate) offset: u64,
+    /// Maximum number of dead letters
+    #[clap(short, long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub(crate) message_count: u32,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
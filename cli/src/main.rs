@@ -33,7 +33,8 @@ use iggy::cli::{
     },
     context::get_contexts::GetContextsCmd,
     message::{
-        flush_messages::FlushMessagesCmd, poll_messages::PollMessagesCmd,
+        flush_messages::FlushMessagesCmd, inspect_dead_letters::InspectDeadLettersCmd,
+        poll_messages::PollMessagesCmd, replay_dead_letters::ReplayDeadLettersCmd,
         send_messages::SendMessagesCmd,
     },
     partitions::{create_partitions::CreatePartitionsCmd, delete_partitions::DeletePartitionsCmd},
@@ -64,6 +65,7 @@ use iggy::cli::{
 use iggy::cli_command::{CliCommand, PRINT_TARGET};
 use iggy::client_provider::{self, ClientProviderConfig};
 use iggy::clients::client::IggyClient;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::utils::crypto::{Aes256GcmEncryptor, Encryptor};
 use iggy::utils::personal_access_token_expiry::PersonalAccessTokenExpiry;
 use std::sync::Arc;
@@ -220,6 +222,10 @@ fn get_command(
                 create_args.topic_id.clone(),
                 create_args.name.clone(),
                 create_args.group_id,
+                create_args
+                    .visibility_timeout
+                    .map(ConsumerGroupMode::queue)
+                    .unwrap_or_default(),
             )),
             ConsumerGroupAction::Delete(delete_args) => Box::new(DeleteConsumerGroupCmd::new(
                 delete_args.stream_id.clone(),
@@ -260,6 +266,7 @@ fn get_command(
                 poll_args.consumer.clone(),
                 poll_args.show_headers,
                 poll_args.output_file.clone(),
+                poll_args.filter.clone(),
             )),
             MessageAction::Flush(flush_args) => Box::new(FlushMessagesCmd::new(
                 flush_args.stream_id.clone(),
@@ -267,6 +274,20 @@ fn get_command(
                 flush_args.partition_id,
                 flush_args.fsync,
             )),
+            MessageAction::DlqInspect(dlq_args) => Box::new(InspectDeadLettersCmd::new(
+                dlq_args.stream_id.clone(),
+                dlq_args.topic_id.clone(),
+                dlq_args.partition_id,
+                dlq_args.offset,
+                dlq_args.message_count,
+            )),
+            MessageAction::DlqReplay(dlq_args) => Box::new(ReplayDeadLettersCmd::new(
+                dlq_args.stream_id.clone(),
+                dlq_args.topic_id.clone(),
+                dlq_args.partition_id,
+                dlq_args.offset,
+                dlq_args.message_count,
+            )),
         },
         Command::ConsumerOffset(command) => match command {
             ConsumerOffsetAction::Get(get_args) => Box::new(GetConsumerOffsetCmd::new(
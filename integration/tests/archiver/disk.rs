@@ -92,6 +92,33 @@ async fn should_fail_when_file_to_archive_does_not_exist() {
     assert!(matches!(error, ServerError::FileToArchiveNotFound(_)));
 }
 
+#[tokio::test]
+async fn should_list_archived_files_in_directory_on_disk() {
+    let setup = DiskArchiverSetup::init().await;
+    let archiver = setup.archiver();
+    let content = "hello world";
+    let directory = format!("{}/directory", setup.base_path);
+    tokio::fs::create_dir(&directory).await.unwrap();
+    let first_file_path = format!("{directory}/first_file");
+    let second_file_path = format!("{directory}/second_file");
+    let other_file_path = format!("{}/other_file", setup.base_path);
+    for path in [&first_file_path, &second_file_path, &other_file_path] {
+        create_file(path, content).await;
+    }
+    let files_to_archive = vec![
+        first_file_path.as_ref(),
+        second_file_path.as_ref(),
+        other_file_path.as_ref(),
+    ];
+    archiver.archive(&files_to_archive, None).await.unwrap();
+
+    let files = archiver.list(&directory, None).await;
+    assert!(files.is_ok());
+    let mut files = files.unwrap();
+    files.sort();
+    assert_eq!(files, vec![first_file_path, second_file_path]);
+}
+
 async fn create_file(path: &str, content: &str) {
     let mut file = file::overwrite(path).await.unwrap();
     file.write_all(content.as_bytes()).await.unwrap();
@@ -5,10 +5,13 @@ use crate::cli::common::{
 use assert_cmd::assert::Assert;
 use async_trait::async_trait;
 use iggy::client::Client;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
+use iggy::utils::duration::IggyDuration;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::topic_size::MaxTopicSize;
 use predicates::str::diff;
 use serial_test::parallel;
+use std::str::FromStr;
 
 struct TestConsumerGroupCreateCmd {
     stream_id: u32,
@@ -17,6 +20,7 @@ struct TestConsumerGroupCreateCmd {
     topic_name: String,
     group_id: Option<u32>,
     group_name: String,
+    visibility_timeout: Option<IggyDuration>,
     using_stream_id: TestStreamId,
     using_topic_id: TestTopicId,
 }
@@ -30,6 +34,7 @@ impl TestConsumerGroupCreateCmd {
         topic_name: String,
         group_id: Option<u32>,
         group_name: String,
+        visibility_timeout: Option<IggyDuration>,
         using_stream_id: TestStreamId,
         using_topic_id: TestTopicId,
     ) -> Self {
@@ -40,6 +45,7 @@ impl TestConsumerGroupCreateCmd {
             topic_name,
             group_id,
             group_name,
+            visibility_timeout,
             using_stream_id,
             using_topic_id,
         }
@@ -61,10 +67,21 @@ impl TestConsumerGroupCreateCmd {
             command.push(format!("{}", group_id));
         }
 
+        if let Some(visibility_timeout) = self.visibility_timeout {
+            command.push("-v".to_string());
+            command.push(format!("{}", visibility_timeout));
+        }
+
         command.push(self.group_name.clone());
 
         command
     }
+
+    fn get_mode(&self) -> ConsumerGroupMode {
+        self.visibility_timeout
+            .map(ConsumerGroupMode::queue)
+            .unwrap_or_default()
+    }
 }
 
 #[async_trait]
@@ -114,8 +131,8 @@ impl IggyCmdTestCase for TestConsumerGroupCreateCmd {
             None => "ID auto incremented".to_string(),
         };
 
-        let message = format!("Executing create consumer group: {}, name: {} for topic with ID: {} and stream with ID: {}\nConsumer group: {}, name: {} created for topic with ID: {} and stream with ID: {}\n",
-                              group_id, self.group_name, topic_id, stream_id, group_id, self.group_name, topic_id, stream_id);
+        let message = format!("Executing create consumer group: {}, name: {}, mode: {} for topic with ID: {} and stream with ID: {}\nConsumer group: {}, name: {} created for topic with ID: {} and stream with ID: {}\n",
+                              group_id, self.group_name, self.get_mode(), topic_id, stream_id, group_id, self.group_name, topic_id, stream_id);
 
         command_state.success().stdout(diff(message));
     }
@@ -166,6 +183,7 @@ pub async fn should_be_successful() {
             String::from("sync"),
             Some(1),
             String::from("group1"),
+            None,
             TestStreamId::Numeric,
             TestTopicId::Numeric,
         ))
@@ -178,6 +196,7 @@ pub async fn should_be_successful() {
             String::from("topic"),
             Some(3),
             String::from("group3"),
+            None,
             TestStreamId::Named,
             TestTopicId::Numeric,
         ))
@@ -190,6 +209,7 @@ pub async fn should_be_successful() {
             String::from("probe"),
             Some(7),
             String::from("group7"),
+            None,
             TestStreamId::Numeric,
             TestTopicId::Named,
         ))
@@ -202,6 +222,20 @@ pub async fn should_be_successful() {
             String::from("test"),
             Some(4),
             String::from("group4"),
+            None,
+            TestStreamId::Named,
+            TestTopicId::Named,
+        ))
+        .await;
+    iggy_cmd_test
+        .execute_test(TestConsumerGroupCreateCmd::new(
+            3,
+            String::from("jobs"),
+            2,
+            String::from("tasks"),
+            Some(5),
+            String::from("workers"),
+            Some(IggyDuration::from_str("30s").unwrap()),
             TestStreamId::Named,
             TestTopicId::Named,
         ))
@@ -222,12 +256,14 @@ pub async fn should_help_match() {
 Stream ID can be specified as a stream name or ID
 Topic ID can be specified as a topic name or ID
 If group ID is not provided then the server will automatically assign it
+If visibility timeout is provided then the group is created in queue mode
 
 Examples:
  iggy consumer-group create 1 1 prod
  iggy consumer-group create stream 2 test
  iggy consumer-group create 2 topic receiver
  iggy consumer-group create -g 4 stream topic group
+ iggy consumer-group create -v 30s stream topic workers
 
 {USAGE_PREFIX} consumer-group create [OPTIONS] <STREAM_ID> <TOPIC_ID> <NAME>
 
@@ -249,6 +285,13 @@ Options:
   -g, --group-id <GROUP_ID>
           Consumer group ID to create
 
+  -v, --visibility-timeout <VISIBILITY_TIMEOUT>
+          Visibility timeout of the messages leased to the members, e.g. 30s
+{CLAP_INDENT}
+          When provided, the consumer group is created in queue mode,
+          in which the messages have to be acknowledged one by one
+          and are redelivered when not acknowledged within the timeout
+
   -h, --help
           Print help (see a summary with '-h')
 "#,
@@ -276,8 +319,12 @@ Arguments:
   <NAME>       Consumer group name to create
 
 Options:
-  -g, --group-id <GROUP_ID>  Consumer group ID to create
-  -h, --help                 Print help (see more with '--help')
+  -g, --group-id <GROUP_ID>
+          Consumer group ID to create
+  -v, --visibility-timeout <VISIBILITY_TIMEOUT>
+          Visibility timeout of the messages leased to the members, e.g. 30s
+  -h, --help
+          Print help (see more with '--help')
 "#,
             ),
         ))
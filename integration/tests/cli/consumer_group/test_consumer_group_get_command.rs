@@ -5,6 +5,7 @@ use crate::cli::common::{
 use assert_cmd::assert::Assert;
 use async_trait::async_trait;
 use iggy::client::Client;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::topic_size::MaxTopicSize;
 use predicates::str::{contains, starts_with};
@@ -96,6 +97,7 @@ impl IggyCmdTestCase for TestConsumerGroupGetCmd {
                 &self.topic_id.try_into().unwrap(),
                 &self.group_name,
                 self.group_id.into(),
+                ConsumerGroupMode::Offset,
             )
             .await;
         assert!(consumer_group.is_ok());
@@ -137,7 +139,8 @@ impl IggyCmdTestCase for TestConsumerGroupGetCmd {
             .stdout(contains(format!(
                 "Consumer group name | {}",
                 self.group_name
-            )));
+            )))
+            .stdout(contains("Evicted members     | 0"));
     }
 
     async fn verify_server_state(&self, client: &dyn Client) {
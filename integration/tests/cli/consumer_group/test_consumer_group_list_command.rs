@@ -5,6 +5,7 @@ use crate::cli::common::{
 use assert_cmd::assert::Assert;
 use async_trait::async_trait;
 use iggy::client::Client;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::topic_size::MaxTopicSize;
 use predicates::str::{contains, starts_with};
@@ -93,6 +94,7 @@ impl IggyCmdTestCase for TestConsumerGroupListCmd {
                 &self.topic_id.try_into().unwrap(),
                 &self.consumer_group_name,
                 self.consumer_group_id.into(),
+                ConsumerGroupMode::Offset,
             )
             .await;
         assert!(consumer_group.is_ok());
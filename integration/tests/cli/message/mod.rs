@@ -1,3 +1,5 @@
+mod test_message_dlq_inspect_command;
+mod test_message_dlq_replay_command;
 mod test_message_flush_command;
 mod test_message_help_command;
 mod test_message_poll_command;
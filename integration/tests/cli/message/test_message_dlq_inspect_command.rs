@@ -0,0 +1,244 @@
+use crate::cli::common::{
+    IggyCmdCommand, IggyCmdTest, IggyCmdTestCase, TestHelpCmd, CLAP_INDENT, USAGE_PREFIX,
+};
+use assert_cmd::assert::Assert;
+use async_trait::async_trait;
+use bytes::Bytes;
+use iggy::client::Client;
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::models::dead_letter_queue::DeadLetterQueue;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use predicates::str::{contains, starts_with};
+use serial_test::parallel;
+
+struct TestMessageDlqInspectCmd {
+    stream_name: String,
+    topic_name: String,
+    dead_letter_queue_topic_name: String,
+    messages: Vec<String>,
+}
+
+impl TestMessageDlqInspectCmd {
+    fn new(
+        stream_name: &str,
+        topic_name: &str,
+        dead_letter_queue_topic_name: &str,
+        messages: &[&str],
+    ) -> Self {
+        Self {
+            stream_name: stream_name.to_string(),
+            topic_name: topic_name.to_string(),
+            dead_letter_queue_topic_name: dead_letter_queue_topic_name.to_string(),
+            messages: messages.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl IggyCmdTestCase for TestMessageDlqInspectCmd {
+    async fn prepare_server_state(&mut self, client: &dyn Client) {
+        let stream_id = Identifier::named(&self.stream_name).unwrap();
+        let topic_id = Identifier::named(&self.topic_name).unwrap();
+        let stream = client.create_stream(&self.stream_name, None).await;
+        assert!(stream.is_ok());
+
+        for topic_name in [&self.topic_name, &self.dead_letter_queue_topic_name] {
+            let topic = client
+                .create_topic(
+                    &stream_id,
+                    topic_name,
+                    1,
+                    Default::default(),
+                    None,
+                    None,
+                    IggyExpiry::NeverExpire,
+                    MaxTopicSize::ServerDefault,
+                )
+                .await;
+            assert!(topic.is_ok());
+        }
+
+        let dead_letter_queue = client
+            .set_dead_letter_queue(
+                &stream_id,
+                &topic_id,
+                Some(DeadLetterQueue {
+                    stream_id: stream_id.clone(),
+                    topic_id: Identifier::named(&self.dead_letter_queue_topic_name).unwrap(),
+                    max_delivery_attempts: 1,
+                }),
+            )
+            .await;
+        assert!(dead_letter_queue.is_ok());
+
+        let mut messages = self
+            .messages
+            .iter()
+            .map(|s| Message::new(None, Bytes::from(s.as_bytes().to_vec()), None))
+            .collect::<Vec<_>>();
+        let send_status = client
+            .send_messages(
+                &stream_id,
+                &topic_id,
+                &Partitioning::partition_id(1),
+                &mut messages,
+            )
+            .await;
+        assert!(send_status.is_ok());
+
+        for offset in 0..self.messages.len() as u64 {
+            let nack = client
+                .nack_message(
+                    &Consumer::default(),
+                    &stream_id,
+                    &topic_id,
+                    Some(1),
+                    offset,
+                    &format!("reason-{offset}"),
+                )
+                .await;
+            assert!(nack.is_ok());
+        }
+    }
+
+    fn get_command(&self) -> IggyCmdCommand {
+        IggyCmdCommand::new()
+            .arg("message")
+            .arg("dlq-inspect")
+            .arg(self.stream_name.clone())
+            .arg(self.dead_letter_queue_topic_name.clone())
+            .arg("1")
+            .with_env_credentials()
+    }
+
+    fn verify_command(&self, command_state: Assert) {
+        let identification_part = format!(
+            "topic with ID: {} and stream with ID: {} (partition with ID: 1)",
+            self.dead_letter_queue_topic_name, self.stream_name
+        );
+        let message = format!(
+            "Executing inspect dead letters from {identification_part}\nFound {} dead letters in {identification_part}\n",
+            self.messages.len()
+        );
+
+        let status = command_state.success().stdout(starts_with(message));
+        self.messages
+            .iter()
+            .enumerate()
+            .fold(status, |status, (offset, message)| {
+                status
+                    .stdout(contains(message.as_str()))
+                    .stdout(contains(format!("reason-{offset}")))
+            });
+    }
+
+    async fn verify_server_state(&self, client: &dyn Client) {
+        let stream_id = Identifier::named(&self.stream_name).unwrap();
+        let stream = client.delete_stream(&stream_id).await;
+        assert!(stream.is_ok());
+    }
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_be_successful() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test.setup().await;
+    iggy_cmd_test
+        .execute_test(TestMessageDlqInspectCmd::new(
+            "stream",
+            "topic",
+            "dlq",
+            &["first", "second"],
+        ))
+        .await;
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_help_match() {
+    let mut iggy_cmd_test = IggyCmdTest::help_message();
+
+    iggy_cmd_test
+        .execute_test_for_help_command(TestHelpCmd::new(
+            vec!["message", "dlq-inspect", "--help"],
+            format!(
+                r#"Inspect dead letters from given dead letter queue topic ID and given stream ID
+
+Prints the messages moved to the dead letter queue along with
+their origin stream, topic, partition and offset, the number
+of the delivery attempts and the reason of the last nack.
+
+Stream ID can be specified as a stream name or ID
+Topic ID can be specified as a topic name or ID
+
+Examples:
+ iggy message dlq-inspect 1 2 1
+ iggy message dlq-inspect --offset 10 --message-count 5 stream topic 1
+
+{USAGE_PREFIX} message dlq-inspect [OPTIONS] <STREAM_ID> <TOPIC_ID> <PARTITION_ID>
+
+Arguments:
+  <STREAM_ID>
+          ID of the stream of the dead letter queue topic
+{CLAP_INDENT}
+          Stream ID can be specified as a stream name or ID
+
+  <TOPIC_ID>
+          ID of the dead letter queue topic
+{CLAP_INDENT}
+          Topic ID can be specified as a topic name or ID
+
+  <PARTITION_ID>
+          Partition ID of the dead letter queue topic
+
+Options:
+  -o, --offset <OFFSET>
+          Offset of the first dead letter
+{CLAP_INDENT}
+          [default: 0]
+
+  -m, --message-count <MESSAGE_COUNT>
+          Maximum number of dead letters
+{CLAP_INDENT}
+          [default: 10]
+
+  -h, --help
+          Print help (see a summary with '-h')
+"#,
+            ),
+        ))
+        .await;
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_short_help_match() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test
+        .execute_test_for_help_command(TestHelpCmd::new(
+            vec!["message", "dlq-inspect", "-h"],
+            format!(
+                r#"Inspect dead letters from given dead letter queue topic ID and given stream ID
+
+{USAGE_PREFIX} message dlq-inspect [OPTIONS] <STREAM_ID> <TOPIC_ID> <PARTITION_ID>
+
+Arguments:
+  <STREAM_ID>     ID of the stream of the dead letter queue topic
+  <TOPIC_ID>      ID of the dead letter queue topic
+  <PARTITION_ID>  Partition ID of the dead letter queue topic
+
+Options:
+  -o, --offset <OFFSET>                Offset of the first dead letter [default: 0]
+  -m, --message-count <MESSAGE_COUNT>  Maximum number of dead letters [default: 10]
+  -h, --help                           Print help (see more with '--help')
+"#,
+            ),
+        ))
+        .await;
+}
@@ -348,6 +348,7 @@ Examples:
  iggy message poll --offset 0 stream 2 1
  iggy message poll --offset 0 1 topic 1
  iggy message poll --offset 0 stream topic 1
+ iggy message poll --offset 0 --filter "tenant == 'acme'" stream topic 1
 
 {USAGE_PREFIX} message poll [OPTIONS] <--offset <OFFSET>|--first|--last|--next> <STREAM_ID> <TOPIC_ID> <PARTITION_ID>
 
@@ -417,6 +418,15 @@ Options:
           If the file is not specified, the messages will be printed
           to the standard output.
 
+      --filter <FILTER>
+          Filter expression evaluated by the server against the message headers
+{CLAP_INDENT}
+          Only the messages with headers matching the expression are returned.
+          Header values are compared with the string (quoted), number or bool
+          literals using ==, !=, >, >=, < and <= operators, and the comparisons
+          can be combined using &&, || and ! operators and parentheses,
+          for example: "tenant == 'acme' && priority >= 3"
+
   -h, --help
           Print help (see a summary with '-h')
 "#,
@@ -453,6 +463,7 @@ Options:
   -c, --consumer <CONSUMER>            Regular consumer which will poll messages [default: 1]
   -s, --show-headers                   Include the message headers in the output
       --output-file <OUTPUT_FILE>      Store polled message into file in binary format
+      --filter <FILTER>                Filter expression evaluated by the server against the message headers
   -h, --help                           Print help (see more with '--help')
 "#,
             ),
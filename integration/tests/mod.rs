@@ -16,6 +16,7 @@ mod examples;
 mod server;
 mod state;
 mod streaming;
+mod tools;
 
 lazy_static! {
     static ref TESTS_FAILED: AtomicBool = AtomicBool::new(false);
@@ -0,0 +1,484 @@
+use bytes::Bytes;
+use iggy::client::{ClusterClient, MessageClient, StreamClient, TopicClient, UserClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer::Consumer;
+use iggy::error::IggyError;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Acks, Message, Partitioning};
+use iggy::models::cleanup_policy::{CleanupPolicy, MESSAGE_KEY_HEADER};
+use iggy::models::cluster::ClusterMetadata;
+use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::users::defaults::{DEFAULT_ROOT_PASSWORD, DEFAULT_ROOT_USERNAME};
+use iggy::utils::duration::IggyDuration;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::tcp_client::TcpClientFactory;
+use integration::test_server::{ClientFactory, IpAddrKind, TestServer};
+use serial_test::parallel;
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const STREAM_ID: u32 = 1;
+const STREAM_NAME: &str = "cluster-stream";
+const TOPIC_ID: u32 = 1;
+const TOPIC_NAME: &str = "cluster-topic";
+const PARTITION_ID: u32 = 1;
+const MESSAGES_COUNT: u32 = 10;
+const MAX_ATTEMPTS: u32 = 100;
+const FAILOVER_MAX_ATTEMPTS: u32 = 300;
+const ATTEMPT_INTERVAL: Duration = Duration::from_millis(100);
+
+#[tokio::test]
+#[parallel]
+async fn cluster_should_elect_leader_and_replicate_metadata_to_followers() {
+    let addresses = (0..4).map(|_| get_free_address()).collect::<Vec<_>>();
+    let initial_nodes = (1..=3)
+        .map(|id| format!("{id}={}", addresses[id as usize - 1]))
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut servers = (1..=3)
+        .map(|id| start_node(id, &addresses[id as usize - 1], &initial_nodes))
+        .collect::<Vec<_>>();
+    let mut clients = Vec::new();
+    for server in &servers {
+        clients.push(create_client(server).await);
+    }
+
+    // 1. All the nodes agree on the leader
+    let metadata = wait_for_leader(&clients).await;
+    assert_eq!(metadata.nodes.len(), 3);
+    let leader_id = metadata.leader_id.unwrap();
+    let leader = &clients[leader_id as usize - 1];
+    let follower = clients
+        .iter()
+        .enumerate()
+        .find(|(index, _)| *index as u32 + 1 != leader_id)
+        .map(|(_, client)| client)
+        .unwrap();
+
+    // 2. The follower rejects the command modifying the replicated state
+    let error = follower
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap_err();
+    let not_leader_code = IggyError::NotLeader(0, String::new()).as_code();
+    assert!(matches!(error, IggyError::InvalidResponse(code, _, _) if code == not_leader_code));
+
+    // 3. The stream created on the leader is replicated to all the followers
+    leader
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+    for client in &clients {
+        wait_for_stream(client).await;
+    }
+
+    // 4. The new node added to the cluster catches up with the leader, including the root user
+    servers.push(start_node(4, &addresses[3], &initial_nodes));
+    leader.add_cluster_node(4, &addresses[3]).await.unwrap();
+    let new_client = create_client(&servers[3]).await;
+    wait_for_stream(&new_client).await;
+    let metadata = new_client.get_cluster_metadata().await.unwrap();
+    assert_eq!(metadata.nodes.len(), 4);
+    assert_eq!(metadata.leader_id, Some(leader_id));
+}
+
+#[tokio::test]
+#[parallel]
+async fn cluster_leader_should_report_unknown_outcome_of_entry_not_committed_in_time() {
+    let addresses = (0..3).map(|_| get_free_address()).collect::<Vec<_>>();
+    let nodes = (1..=3)
+        .map(|id| format!("{id}={}", addresses[id as usize - 1]))
+        .collect::<Vec<_>>()
+        .join(",");
+    let envs = HashMap::from([("IGGY_CLUSTER_COMMIT_TIMEOUT".to_string(), "1 s".to_string())]);
+    let mut servers = (1..=3)
+        .map(|id| start_node_with_envs(id, &addresses[id as usize - 1], &nodes, envs.clone()))
+        .collect::<Vec<_>>();
+    let mut clients = Vec::new();
+    for server in &servers {
+        clients.push(create_client(server).await);
+    }
+
+    // 1. Once the majority of the nodes has stopped, the entry proposed by the leader can't be committed
+    let metadata = wait_for_leader(&clients).await;
+    let leader_index = metadata.leader_id.unwrap() as usize - 1;
+    for (index, server) in servers.iter_mut().enumerate() {
+        if index != leader_index {
+            server.stop();
+        }
+    }
+
+    // 2. The leader doesn't know if the entry is going to be committed, so the outcome is reported as unknown
+    let error = clients[leader_index]
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap_err();
+    let timed_out_code = IggyError::StateEntryCommitTimedOut(0).as_code();
+    assert!(matches!(error, IggyError::InvalidResponse(code, _, _) if code == timed_out_code));
+}
+
+#[tokio::test]
+#[parallel]
+async fn cluster_should_replicate_partition_messages_and_fail_over_to_in_sync_replica() {
+    let addresses = (0..3).map(|_| get_free_address()).collect::<Vec<_>>();
+    let nodes = (1..=3)
+        .map(|id| format!("{id}={}", addresses[id as usize - 1]))
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut servers = (1..=3)
+        .map(|id| start_node(id, &addresses[id as usize - 1], &nodes))
+        .collect::<Vec<_>>();
+    let mut clients = Vec::new();
+    for server in &servers {
+        clients.push(create_client(server).await);
+    }
+
+    // 1. The topic replicated by all the nodes is created on the cluster leader
+    let metadata = wait_for_leader(&clients).await;
+    let leader = &clients[metadata.leader_id.unwrap() as usize - 1];
+    leader
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+    leader
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            1,
+            CompressionAlgorithm::default(),
+            Some(3),
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+
+    // 2. Only the partition leader accepts the messages, which are acknowledged by all the in-sync replicas
+    let partition_leader = send_to_partition_leader(&clients, &[0, 1, 2], 0, MAX_ATTEMPTS).await;
+    for client in &clients {
+        wait_for_messages(client, MESSAGES_COUNT).await;
+    }
+
+    // 3. Once the partition leader has stopped, one of the in-sync replicas becomes the new leader
+    servers[partition_leader].stop();
+    let followers = (0..3)
+        .filter(|index| *index != partition_leader)
+        .collect::<Vec<_>>();
+    let new_partition_leader =
+        send_to_partition_leader(&clients, &followers, MESSAGES_COUNT, FAILOVER_MAX_ATTEMPTS).await;
+    assert_ne!(new_partition_leader, partition_leader);
+    for index in followers {
+        wait_for_messages(&clients[index], 2 * MESSAGES_COUNT).await;
+    }
+}
+
+#[tokio::test]
+#[parallel]
+async fn cluster_should_replicate_compacted_partition_messages_to_new_replica() {
+    let addresses = (0..3).map(|_| get_free_address()).collect::<Vec<_>>();
+    let nodes = (1..=3)
+        .map(|id| format!("{id}={}", addresses[id as usize - 1]))
+        .collect::<Vec<_>>()
+        .join(",");
+    // Each batch of messages closes the segment, which is compacted shortly afterwards.
+    let compaction_envs = HashMap::from([
+        ("IGGY_SYSTEM_SEGMENT_SIZE".to_string(), "1 B".to_string()),
+        (
+            "IGGY_DATA_MAINTENANCE_MESSAGES_CLEANER_ENABLED".to_string(),
+            "true".to_string(),
+        ),
+        (
+            "IGGY_DATA_MAINTENANCE_MESSAGES_INTERVAL".to_string(),
+            "1 s".to_string(),
+        ),
+    ]);
+    let mut servers = (1..=2)
+        .map(|id| {
+            start_node_with_envs(
+                id,
+                &addresses[id as usize - 1],
+                &nodes,
+                compaction_envs.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut clients = Vec::new();
+    for server in &servers {
+        clients.push(create_client(server).await);
+    }
+
+    // 1. The compacted topic replicated by all the nodes is created while the third node is down
+    let metadata = wait_for_leader(&clients).await;
+    let leader = &clients[metadata.leader_id.unwrap() as usize - 1];
+    leader
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+    leader
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            1,
+            CompressionAlgorithm::default(),
+            Some(3),
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+    leader
+        .set_cleanup_policy(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            CleanupPolicy::Compact {
+                tombstone_retention: IggyDuration::from_str("1h").unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+
+    // 2. The second batch makes the first one obsolete, apart from its last message kept to preserve the segment offset,
+    // while the follower compacts its own segments, which might contain both batches
+    let mut partition_leader = 0;
+    for start_id in [0, MESSAGES_COUNT] {
+        partition_leader = send_to_partition_leader_with_acks(
+            &clients,
+            &[0, 1],
+            start_id,
+            FAILOVER_MAX_ATTEMPTS,
+            Acks::Leader,
+        )
+        .await;
+    }
+    let compacted_offsets =
+        (MESSAGES_COUNT as u64 - 1..2 * MESSAGES_COUNT as u64).collect::<Vec<_>>();
+    wait_for_offsets(&clients[partition_leader], 0, &compacted_offsets).await;
+
+    // 3. The third node (not compacting the messages itself) fetches the compacted partition with the gap in the offsets
+    servers.push(start_node(3, &addresses[2], &nodes));
+    clients.push(create_client(&servers[2]).await);
+    wait_for_offsets(&clients[2], 0, &compacted_offsets).await;
+
+    // 4. The next messages are appended right after the replicated ones
+    send_to_partition_leader(
+        &clients,
+        &[0, 1, 2],
+        2 * MESSAGES_COUNT,
+        FAILOVER_MAX_ATTEMPTS,
+    )
+    .await;
+    let next_offsets = (2 * MESSAGES_COUNT as u64..3 * MESSAGES_COUNT as u64).collect::<Vec<_>>();
+    wait_for_offsets(&clients[2], 2 * MESSAGES_COUNT as u64, &next_offsets).await;
+}
+
+/// Sends the messages with the acknowledgement of all the replicas to each node, until one of them,
+/// being the partition leader, accepts these. Returns the index of the partition leader.
+async fn send_to_partition_leader(
+    clients: &[IggyClient],
+    indexes: &[usize],
+    start_id: u32,
+    max_attempts: u32,
+) -> usize {
+    send_to_partition_leader_with_acks(clients, indexes, start_id, max_attempts, Acks::All).await
+}
+
+/// Sends the messages keyed by their IDs modulo the messages count, so that each batch makes the previous one obsolete
+/// in the compacted topic, to each node until one of them, being the partition leader, accepts these.
+/// Returns the index of the partition leader.
+async fn send_to_partition_leader_with_acks(
+    clients: &[IggyClient],
+    indexes: &[usize],
+    start_id: u32,
+    max_attempts: u32,
+    acks: Acks,
+) -> usize {
+    for _ in 0..max_attempts {
+        for index in indexes {
+            let mut messages = (start_id..start_id + MESSAGES_COUNT)
+                .map(|id| {
+                    let headers = HashMap::from([(
+                        HeaderKey::new(MESSAGE_KEY_HEADER).unwrap(),
+                        HeaderValue::from_str(&format!("key-{}", id % MESSAGES_COUNT)).unwrap(),
+                    )]);
+                    Message::new(None, Bytes::from(format!("message-{id}")), Some(headers))
+                })
+                .collect::<Vec<_>>();
+            if clients[*index]
+                .send_messages_with_acks(
+                    &Identifier::numeric(STREAM_ID).unwrap(),
+                    &Identifier::numeric(TOPIC_ID).unwrap(),
+                    &Partitioning::partition_id(PARTITION_ID),
+                    acks,
+                    &mut messages,
+                )
+                .await
+                .is_ok()
+            {
+                return *index;
+            }
+        }
+
+        sleep(ATTEMPT_INTERVAL).await;
+    }
+
+    panic!("Messages have not been accepted by the partition leader.");
+}
+
+async fn wait_for_messages(client: &IggyClient, count: u32) {
+    for _ in 0..MAX_ATTEMPTS {
+        let polled_messages = client
+            .poll_messages(
+                &Identifier::numeric(STREAM_ID).unwrap(),
+                &Identifier::numeric(TOPIC_ID).unwrap(),
+                Some(PARTITION_ID),
+                &Consumer::default(),
+                &PollingStrategy::offset(0),
+                2 * count,
+                false,
+            )
+            .await;
+        if let Ok(polled_messages) = polled_messages {
+            if polled_messages.messages.len() == count as usize {
+                for (id, message) in polled_messages.messages.iter().enumerate() {
+                    assert_eq!(message.offset, id as u64);
+                    assert_eq!(message.payload, format!("message-{id}").as_bytes());
+                }
+                return;
+            }
+        }
+
+        sleep(ATTEMPT_INTERVAL).await;
+    }
+
+    panic!("Messages have not been replicated.");
+}
+
+/// Polls the messages starting from the given offset until these have exactly the expected offsets,
+/// as the compacted partition has the gaps left by the removed messages.
+async fn wait_for_offsets(client: &IggyClient, start_offset: u64, offsets: &[u64]) {
+    for _ in 0..MAX_ATTEMPTS {
+        let polled_messages = client
+            .poll_messages(
+                &Identifier::numeric(STREAM_ID).unwrap(),
+                &Identifier::numeric(TOPIC_ID).unwrap(),
+                Some(PARTITION_ID),
+                &Consumer::default(),
+                &PollingStrategy::offset(start_offset),
+                2 * offsets.len() as u32,
+                false,
+            )
+            .await;
+        if let Ok(polled_messages) = polled_messages {
+            let polled_offsets = polled_messages
+                .messages
+                .iter()
+                .map(|message| message.offset)
+                .collect::<Vec<_>>();
+            if polled_offsets == offsets {
+                for message in &polled_messages.messages {
+                    assert_eq!(
+                        message.payload,
+                        format!("message-{}", message.offset).as_bytes()
+                    );
+                }
+                return;
+            }
+        }
+
+        sleep(ATTEMPT_INTERVAL).await;
+    }
+
+    panic!("Messages with offsets: {offsets:?} have not been found.");
+}
+
+fn start_node(id: u32, address: &str, nodes: &str) -> TestServer {
+    start_node_with_envs(id, address, nodes, HashMap::new())
+}
+
+fn start_node_with_envs(
+    id: u32,
+    address: &str,
+    nodes: &str,
+    mut envs: HashMap<String, String>,
+) -> TestServer {
+    envs.extend([
+        ("IGGY_CLUSTER_ENABLED".to_string(), "true".to_string()),
+        ("IGGY_CLUSTER_NODE_ID".to_string(), id.to_string()),
+        ("IGGY_CLUSTER_ADDRESS".to_string(), address.to_string()),
+        ("IGGY_CLUSTER_NODES".to_string(), nodes.to_string()),
+    ]);
+    let mut server = TestServer::new(Some(envs), true, None, IpAddrKind::V4);
+    server.start();
+    server
+}
+
+/// Creates the client logged in as the root user, which is created only by the bootstrapping node,
+/// so the client of any other node has to wait until the root user is replicated by the leader.
+async fn create_client(server: &TestServer) -> IggyClient {
+    let client_factory = TcpClientFactory {
+        server_addr: server.get_raw_tcp_addr().unwrap(),
+    };
+    let client = IggyClient::create(client_factory.create_client().await, None, None);
+    for _ in 0..MAX_ATTEMPTS {
+        if client
+            .login_user(DEFAULT_ROOT_USERNAME, DEFAULT_ROOT_PASSWORD)
+            .await
+            .is_ok()
+        {
+            return client;
+        }
+
+        sleep(ATTEMPT_INTERVAL).await;
+    }
+
+    panic!("Root user has not been replicated.");
+}
+
+async fn wait_for_leader(clients: &[IggyClient]) -> ClusterMetadata {
+    for _ in 0..MAX_ATTEMPTS {
+        let mut leaders = Vec::new();
+        let mut metadata = None;
+        for client in clients {
+            let node_metadata = client.get_cluster_metadata().await.unwrap();
+            leaders.push(node_metadata.leader_id);
+            metadata = Some(node_metadata);
+        }
+
+        if leaders[0].is_some() && leaders.iter().all(|leader| *leader == leaders[0]) {
+            return metadata.unwrap();
+        }
+
+        sleep(ATTEMPT_INTERVAL).await;
+    }
+
+    panic!("Cluster leader has not been elected.");
+}
+
+async fn wait_for_stream(client: &IggyClient) {
+    for _ in 0..MAX_ATTEMPTS {
+        let streams = client.get_streams().await.unwrap();
+        if streams
+            .iter()
+            .any(|stream| stream.id == STREAM_ID && stream.name == STREAM_NAME)
+        {
+            return;
+        }
+
+        sleep(ATTEMPT_INTERVAL).await;
+    }
+
+    panic!("Stream has not been replicated.");
+}
+
+fn get_free_address() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().to_string()
+}
@@ -1,5 +1,6 @@
 use crate::server::scenarios::{
-    create_message_payload, stream_size_validation_scenario, system_scenario, user_scenario,
+    create_message_payload, dead_letter_queue_scenario, filtered_messages_scenario,
+    stream_size_validation_scenario, system_scenario, user_scenario,
 };
 use integration::{http_client::HttpClientFactory, test_server::TestServer};
 use serial_test::parallel;
@@ -24,6 +25,26 @@ async fn message_headers_scenario_should_be_valid() {
     create_message_payload::run(&client_factory).await;
 }
 
+#[tokio::test]
+#[parallel]
+async fn dead_letter_queue_scenario_should_be_valid() {
+    let mut test_server = TestServer::default();
+    test_server.start();
+    let server_addr = test_server.get_http_api_addr().unwrap();
+    let client_factory = HttpClientFactory { server_addr };
+    dead_letter_queue_scenario::run(&client_factory).await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn filtered_messages_scenario_should_be_valid() {
+    let mut test_server = TestServer::default();
+    test_server.start();
+    let server_addr = test_server.get_http_api_addr().unwrap();
+    let client_factory = HttpClientFactory { server_addr };
+    filtered_messages_scenario::run(&client_factory).await;
+}
+
 #[tokio::test]
 #[parallel]
 async fn stream_size_validation_scenario_should_be_valid() {
@@ -1,3 +1,4 @@
+mod cluster;
 mod http_server;
 mod quic_server;
 mod scenarios;
@@ -1,9 +1,16 @@
 use crate::server::scenarios::{
-    consumer_group_join_scenario, consumer_group_with_multiple_clients_polling_messages_scenario,
+    compressed_messages_scenario, consumer_group_join_scenario, consumer_group_queue_scenario,
+    consumer_group_session_scenario, consumer_group_static_membership_scenario,
+    consumer_group_with_multiple_clients_polling_messages_scenario,
     consumer_group_with_single_client_polling_messages_scenario, create_message_payload,
-    message_headers_scenario, stream_size_validation_scenario, system_scenario, user_scenario,
+    dead_letter_queue_scenario, filtered_messages_scenario, idempotent_producer_scenario,
+    message_headers_scenario, stream_size_validation_scenario, system_scenario,
+    transactions_scenario, user_scenario,
+};
+use integration::{
+    quic_client::QuicClientFactory,
+    test_server::{IpAddrKind, TestServer},
 };
-use integration::{quic_client::QuicClientFactory, test_server::TestServer};
 use serial_test::parallel;
 
 #[tokio::test]
@@ -36,6 +43,36 @@ async fn message_headers_scenario_should_be_valid() {
     message_headers_scenario::run(&client_factory).await;
 }
 
+#[tokio::test]
+#[parallel]
+async fn compressed_messages_scenario_should_be_valid() {
+    let mut test_server = TestServer::default();
+    test_server.start();
+    let server_addr = test_server.get_quic_udp_addr().unwrap();
+    let client_factory = QuicClientFactory { server_addr };
+    compressed_messages_scenario::run(&client_factory).await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn dead_letter_queue_scenario_should_be_valid() {
+    let mut test_server = TestServer::default();
+    test_server.start();
+    let server_addr = test_server.get_quic_udp_addr().unwrap();
+    let client_factory = QuicClientFactory { server_addr };
+    dead_letter_queue_scenario::run(&client_factory).await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn filtered_messages_scenario_should_be_valid() {
+    let mut test_server = TestServer::default();
+    test_server.start();
+    let server_addr = test_server.get_quic_udp_addr().unwrap();
+    let client_factory = QuicClientFactory { server_addr };
+    filtered_messages_scenario::run(&client_factory).await;
+}
+
 #[tokio::test]
 #[parallel]
 async fn create_message_payload_scenario_should_be_valid() {
@@ -76,6 +113,76 @@ async fn consumer_group_with_multiple_clients_polling_messages_scenario_should_b
     consumer_group_with_multiple_clients_polling_messages_scenario::run(&client_factory).await;
 }
 
+#[tokio::test]
+#[parallel]
+async fn consumer_group_queue_scenario_should_be_valid() {
+    let mut test_server = TestServer::default();
+    test_server.start();
+    let server_addr = test_server.get_quic_udp_addr().unwrap();
+    let client_factory = QuicClientFactory { server_addr };
+    consumer_group_queue_scenario::run(&client_factory).await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn consumer_group_session_scenario_should_be_valid() {
+    let mut test_server = TestServer::new(
+        Some(consumer_group_session_scenario::server_envs()),
+        true,
+        None,
+        IpAddrKind::V4,
+    );
+    test_server.start();
+    let server_addr = test_server.get_quic_udp_addr().unwrap();
+    let client_factory = QuicClientFactory { server_addr };
+    consumer_group_session_scenario::run(&client_factory).await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn consumer_group_static_membership_scenario_should_be_valid() {
+    let mut test_server = TestServer::new(
+        Some(consumer_group_static_membership_scenario::server_envs()),
+        true,
+        None,
+        IpAddrKind::V4,
+    );
+    test_server.start();
+    let server_addr = test_server.get_quic_udp_addr().unwrap();
+    let client_factory = QuicClientFactory { server_addr };
+    consumer_group_static_membership_scenario::run(&client_factory).await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn transactions_scenario_should_be_valid() {
+    let mut test_server = TestServer::new(
+        Some(transactions_scenario::server_envs()),
+        true,
+        None,
+        IpAddrKind::V4,
+    );
+    test_server.start();
+    let server_addr = test_server.get_quic_udp_addr().unwrap();
+    let client_factory = QuicClientFactory { server_addr };
+    transactions_scenario::run(&client_factory).await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn idempotent_producer_scenario_should_be_valid() {
+    let mut test_server = TestServer::new(
+        Some(idempotent_producer_scenario::server_envs()),
+        true,
+        None,
+        IpAddrKind::V4,
+    );
+    test_server.start();
+    let server_addr = test_server.get_quic_udp_addr().unwrap();
+    let client_factory = QuicClientFactory { server_addr };
+    idempotent_producer_scenario::run(&client_factory).await;
+}
+
 #[tokio::test]
 #[parallel]
 async fn stream_size_validation_scenario_should_be_valid() {
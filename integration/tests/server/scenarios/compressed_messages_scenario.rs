@@ -0,0 +1,126 @@
+use crate::server::scenarios::{
+    cleanup, create_client, MESSAGES_COUNT, PARTITIONS_COUNT, PARTITION_ID, STREAM_ID, STREAM_NAME,
+    TOPIC_ID, TOPIC_NAME,
+};
+use bytes::Bytes;
+use iggy::client::{MessageClient, StreamClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let client = create_client(client_factory).await;
+    login_root(&client).await;
+    init_system(&client).await;
+
+    // 1. Send the messages in batches compressed by the client, interleaved with the plain ones
+    let batches_count = 4;
+    let batch_size = MESSAGES_COUNT / batches_count;
+    for batch in 0..batches_count {
+        let mut messages = (batch * batch_size..(batch + 1) * batch_size)
+            .map(|offset| Message::new(None, create_message_payload(offset as u64), None))
+            .collect::<Vec<_>>();
+        if batch % 2 == 0 {
+            client
+                .send_compressed_messages(
+                    &Identifier::numeric(STREAM_ID).unwrap(),
+                    &Identifier::numeric(TOPIC_ID).unwrap(),
+                    &Partitioning::partition_id(PARTITION_ID),
+                    CompressionAlgorithm::Zstd,
+                    &mut messages,
+                )
+                .await
+                .unwrap();
+        } else {
+            client
+                .send_messages(
+                    &Identifier::numeric(STREAM_ID).unwrap(),
+                    &Identifier::numeric(TOPIC_ID).unwrap(),
+                    &Partitioning::partition_id(PARTITION_ID),
+                    &mut messages,
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    // 2. Poll the messages and validate that all of them have been decompressed in order
+    let consumer = Consumer::default();
+    let polled_messages = client
+        .poll_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+            &consumer,
+            &PollingStrategy::offset(0),
+            MESSAGES_COUNT,
+            false,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(polled_messages.messages.len() as u32, MESSAGES_COUNT);
+    for (offset, message) in polled_messages.messages.iter().enumerate() {
+        assert_eq!(message.offset, offset as u64);
+        assert_ne!(message.id, 0);
+        assert_eq!(message.payload, create_message_payload(offset as u64));
+    }
+
+    // 3. Poll the messages starting in the middle of the compressed batch
+    let offset = batch_size as u64 / 2;
+    let polled_messages = client
+        .poll_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+            &consumer,
+            &PollingStrategy::offset(offset),
+            batch_size,
+            false,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(polled_messages.messages.len() as u32, batch_size);
+    assert_eq!(polled_messages.messages[0].offset, offset);
+    assert_eq!(
+        polled_messages.messages[0].payload,
+        create_message_payload(offset)
+    );
+
+    cleanup(&client, false).await;
+    assert_clean_system(&client).await;
+}
+
+async fn init_system(client: &IggyClient) {
+    // 1. Create the stream
+    client
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+
+    // 2. Create the topic
+    client
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            PARTITIONS_COUNT,
+            CompressionAlgorithm::default(),
+            None,
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+}
+
+fn create_message_payload(offset: u64) -> Bytes {
+    Bytes::from(format!("message {}", offset))
+}
@@ -6,6 +6,7 @@ use crate::server::scenarios::{
 use iggy::client::{ConsumerGroupClient, StreamClient, SystemClient, TopicClient};
 use iggy::clients::client::IggyClient;
 use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::identifier::Identifier;
 use iggy::models::client_info::ClientInfoDetails;
 use iggy::models::consumer_group::ConsumerGroupDetails;
@@ -52,6 +53,7 @@ pub async fn run(client_factory: &dyn ClientFactory) {
             &Identifier::numeric(TOPIC_ID).unwrap(),
             CONSUMER_GROUP_NAME,
             Some(CONSUMER_GROUP_ID),
+            ConsumerGroupMode::Offset,
         )
         .await
         .unwrap();
@@ -67,7 +69,7 @@ pub async fn run(client_factory: &dyn ClientFactory) {
     login_user(&client3, USERNAME_3).await;
 
     // 5. Join the consumer group by client 1
-    join_consumer_group(&client1).await;
+    let membership1 = join_consumer_group(&client1).await;
 
     // 5. Get client1 info and validate that it contains the single consumer group
     let client1_info = get_me_and_validate_consumer_groups(&client1).await;
@@ -78,12 +80,16 @@ pub async fn run(client_factory: &dyn ClientFactory) {
     assert_eq!(member.id, client1_info.client_id);
     assert_eq!(member.partitions_count, PARTITIONS_COUNT);
     assert_eq!(member.partitions.len() as u32, PARTITIONS_COUNT);
+    assert_eq!(consumer_group.generation, 1);
+    assert_eq!(membership1.member_id, client1_info.client_id);
+    assert_eq!(membership1.generation, 1);
+    assert_eq!(membership1.partitions, vec![1, 2, 3]);
 
     // 7. Join the consumer group by client 2
-    join_consumer_group(&client2).await;
+    let membership2 = join_consumer_group(&client2).await;
 
     // 8. Validate that client 2 contains the single consumer group
-    get_me_and_validate_consumer_groups(&client2).await;
+    let client2_info = get_me_and_validate_consumer_groups(&client2).await;
 
     // 9. Validate that the consumer group has 2 members and partitions are distributed between them
     let consumer_group = get_consumer_group_and_validate_members(&system_client, 2).await;
@@ -95,12 +101,20 @@ pub async fn run(client_factory: &dyn ClientFactory) {
         member1.partitions_count + member2.partitions_count,
         PARTITIONS_COUNT
     );
+    assert_eq!(consumer_group.generation, 2);
+    assert_eq!(membership2.member_id, client2_info.client_id);
+    assert_eq!(membership2.generation, 2);
+    assert_eq!(membership2.partitions, vec![3]);
+    assert_eq!(
+        get_member_partitions(&consumer_group, client1_info.client_id),
+        vec![1, 2]
+    );
 
     // 10. Join the consumer group by client 3
-    join_consumer_group(&client3).await;
+    let membership3 = join_consumer_group(&client3).await;
 
     // 11. Validate that client 3 contains the single consumer group
-    get_me_and_validate_consumer_groups(&client3).await;
+    let client3_info = get_me_and_validate_consumer_groups(&client3).await;
 
     // 12. Validate that the consumer group has 3 members and partitions are equally distributed between them
     let consumer_group = get_consumer_group_and_validate_members(&system_client, 3).await;
@@ -114,6 +128,20 @@ pub async fn run(client_factory: &dyn ClientFactory) {
     assert_ne!(member1.partitions[0], member3.partitions[0]);
     assert_ne!(member2.partitions[0], member3.partitions[0]);
 
+    // 13. Validate that only a single partition has been moved to client 3
+    assert_eq!(consumer_group.generation, 3);
+    assert_eq!(membership3.member_id, client3_info.client_id);
+    assert_eq!(membership3.generation, 3);
+    assert_eq!(membership3.partitions, vec![2]);
+    assert_eq!(
+        get_member_partitions(&consumer_group, client1_info.client_id),
+        vec![1]
+    );
+    assert_eq!(
+        get_member_partitions(&consumer_group, client2_info.client_id),
+        vec![3]
+    );
+
     cleanup(&system_client, true).await;
     assert_clean_system(&system_client).await;
 }
@@ -155,3 +183,15 @@ async fn get_consumer_group_and_validate_members(
 
     consumer_group
 }
+
+fn get_member_partitions(consumer_group: &ConsumerGroupDetails, member_id: u32) -> Vec<u32> {
+    let mut partitions = consumer_group
+        .members
+        .iter()
+        .find(|member| member.id == member_id)
+        .expect("Failed to get consumer group member")
+        .partitions
+        .clone();
+    partitions.sort();
+    partitions
+}
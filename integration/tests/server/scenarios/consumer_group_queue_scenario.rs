@@ -0,0 +1,192 @@
+use crate::server::scenarios::{
+    cleanup, create_client, get_consumer_group, join_consumer_group, CONSUMER_GROUP_ID,
+    CONSUMER_GROUP_NAME, PARTITION_ID, STREAM_ID, STREAM_NAME, TOPIC_ID, TOPIC_NAME,
+};
+use iggy::client::{
+    ConsumerGroupClient, ConsumerOffsetClient, MessageClient, StreamClient, TopicClient,
+};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer::Consumer;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::utils::duration::IggyDuration;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const PARTITIONS_COUNT: u32 = 1;
+const MESSAGES_COUNT: u32 = 3;
+const VISIBILITY_TIMEOUT: &str = "1s";
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let system_client = create_client(client_factory).await;
+    let client1 = create_client(client_factory).await;
+    let client2 = create_client(client_factory).await;
+    login_root(&system_client).await;
+    login_root(&client1).await;
+    login_root(&client2).await;
+    init_system(&system_client, &client1, &client2).await;
+
+    // 1. Lease the messages, each one is delivered to a single member only
+    let polled_offsets = poll_offsets(&client1, 2).await;
+    assert_eq!(polled_offsets, vec![0, 1]);
+    let polled_offsets = poll_offsets(&client2, 2).await;
+    assert_eq!(polled_offsets, vec![2]);
+    assert!(poll_offsets(&client1, 2).await.is_empty());
+
+    // 2. Acknowledge the messages, the message leased to another member can't be acknowledged
+    ack_message(&client1, 0).await.unwrap();
+    ack_message(&client2, 2).await.unwrap();
+    assert!(ack_message(&client2, 1).await.is_err());
+    assert_eq!(get_stored_offset(&system_client).await, Some(0));
+
+    // 3. Redeliver the unacknowledged message to another member once its visibility timeout expires
+    sleep(Duration::from_millis(1500)).await;
+    let polled_offsets = poll_offsets(&client2, 2).await;
+    assert_eq!(polled_offsets, vec![1]);
+    ack_message(&client2, 1).await.unwrap();
+    assert!(ack_message(&client2, 1).await.is_err());
+    assert_eq!(get_stored_offset(&system_client).await, Some(2));
+    assert!(poll_offsets(&client1, 2).await.is_empty());
+
+    // 4. Redeliver the nacked message immediately
+    send_messages(&system_client, 1).await;
+    let polled_offsets = poll_offsets(&client1, 1).await;
+    assert_eq!(polled_offsets, vec![3]);
+    client1
+        .nack_message(
+            &consumer(),
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+            3,
+            "processing failed",
+        )
+        .await
+        .unwrap();
+    let polled_offsets = poll_offsets(&client2, 1).await;
+    assert_eq!(polled_offsets, vec![3]);
+    ack_message(&client2, 3).await.unwrap();
+    assert_eq!(get_stored_offset(&system_client).await, Some(3));
+
+    cleanup(&system_client, false).await;
+    assert_clean_system(&system_client).await;
+}
+
+async fn init_system(system_client: &IggyClient, client1: &IggyClient, client2: &IggyClient) {
+    // 1. Create the stream
+    system_client
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+
+    // 2. Create the topic
+    system_client
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            PARTITIONS_COUNT,
+            CompressionAlgorithm::default(),
+            None,
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+
+    // 3. Create the consumer group in queue mode
+    system_client
+        .create_consumer_group(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            CONSUMER_GROUP_NAME,
+            Some(CONSUMER_GROUP_ID),
+            ConsumerGroupMode::queue(IggyDuration::from_str(VISIBILITY_TIMEOUT).unwrap()),
+        )
+        .await
+        .unwrap();
+
+    // 4. Join the consumer group by both clients
+    join_consumer_group(client1).await;
+    join_consumer_group(client2).await;
+
+    // 5. Validate that both members have the same partition assigned
+    let consumer_group_info = get_consumer_group(system_client).await;
+    assert_eq!(consumer_group_info.members_count, 2);
+    for member in &consumer_group_info.members {
+        assert_eq!(member.partitions, vec![PARTITION_ID]);
+    }
+
+    // 6. Send the messages
+    send_messages(system_client, MESSAGES_COUNT).await;
+}
+
+async fn send_messages(client: &IggyClient, count: u32) {
+    let mut messages = (0..count)
+        .map(|id| Message::from_str(&format!("message-{id}")).unwrap())
+        .collect::<Vec<_>>();
+    client
+        .send_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            &Partitioning::partition_id(PARTITION_ID),
+            &mut messages,
+        )
+        .await
+        .unwrap();
+}
+
+async fn poll_offsets(client: &IggyClient, count: u32) -> Vec<u64> {
+    client
+        .poll_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            None,
+            &consumer(),
+            &PollingStrategy::next(),
+            count,
+            false,
+        )
+        .await
+        .unwrap()
+        .messages
+        .iter()
+        .map(|message| message.offset)
+        .collect()
+}
+
+async fn ack_message(client: &IggyClient, offset: u64) -> Result<(), iggy::error::IggyError> {
+    client
+        .ack_message(
+            &consumer(),
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+            offset,
+        )
+        .await
+}
+
+async fn get_stored_offset(client: &IggyClient) -> Option<u64> {
+    client
+        .get_consumer_offset(
+            &consumer(),
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+        )
+        .await
+        .unwrap()
+        .map(|offset| offset.stored_offset)
+}
+
+fn consumer() -> Consumer {
+    Consumer::group(Identifier::numeric(CONSUMER_GROUP_ID).unwrap())
+}
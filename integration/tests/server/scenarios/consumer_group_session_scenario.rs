@@ -0,0 +1,132 @@
+use crate::server::scenarios::{
+    cleanup, create_client, get_consumer_group, join_consumer_group, CONSUMER_GROUP_ID,
+    CONSUMER_GROUP_NAME, PARTITIONS_COUNT, STREAM_ID, STREAM_NAME, TOPIC_ID, TOPIC_NAME,
+};
+use iggy::client::{ConsumerGroupClient, StreamClient, SystemClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
+use iggy::error::IggyError;
+use iggy::identifier::Identifier;
+use iggy::models::consumer_group::ConsumerGroupMembership;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const MAX_HEARTBEATS: u32 = 30;
+
+/// The server environment verifying the heartbeats, so that the silent consumer group members are evicted.
+pub fn server_envs() -> HashMap<String, String> {
+    HashMap::from([
+        ("IGGY_HEARTBEAT_ENABLED".to_string(), "true".to_string()),
+        ("IGGY_HEARTBEAT_INTERVAL".to_string(), "5 s".to_string()),
+        (
+            "IGGY_HEARTBEAT_CONSUMER_GROUP_SESSION_TIMEOUT".to_string(),
+            "5 s".to_string(),
+        ),
+    ])
+}
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let system_client = create_client(client_factory).await;
+    let client1 = create_client(client_factory).await;
+    let client2 = create_client(client_factory).await;
+    login_root(&system_client).await;
+    login_root(&client1).await;
+    login_root(&client2).await;
+    init_system(&system_client).await;
+
+    // 1. Join the consumer group by both clients
+    let membership1 = join_consumer_group(&client1).await;
+    let membership2 = join_consumer_group(&client2).await;
+    assert_eq!(membership2.generation, 2);
+
+    // 2. Keep all the clients connected, but send the consumer group heartbeats by the first member only,
+    // until the second one is evicted
+    let mut consumer_group = get_consumer_group(&system_client).await;
+    for _ in 0..MAX_HEARTBEATS {
+        system_client.ping().await.unwrap();
+        client1.ping().await.unwrap();
+        client2.ping().await.unwrap();
+        heartbeat_consumer_group(&client1).await.unwrap();
+        consumer_group = get_consumer_group(&system_client).await;
+        if consumer_group.members_count == 1 {
+            break;
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+
+    // 3. Validate that the evicted member's partitions have been reassigned to the remaining one
+    assert_eq!(consumer_group.members_count, 1);
+    assert_eq!(consumer_group.evicted_members_count, 1);
+    assert_eq!(consumer_group.generation, 3);
+    assert_eq!(consumer_group.members[0].id, membership1.member_id);
+    let mut partitions = consumer_group.members[0].partitions.clone();
+    partitions.sort();
+    assert_eq!(partitions, (1..=PARTITIONS_COUNT).collect::<Vec<_>>());
+    let membership = heartbeat_consumer_group(&client1).await.unwrap();
+    assert_eq!(membership.generation, 3);
+    assert_eq!(membership.partitions.len() as u32, PARTITIONS_COUNT);
+
+    // 4. Validate that the evicted member is still connected, but it has to rejoin the consumer group
+    client2.ping().await.unwrap();
+    assert!(heartbeat_consumer_group(&client2).await.is_err());
+    let membership = join_consumer_group(&client2).await;
+    assert_eq!(membership.member_id, membership2.member_id);
+    assert_eq!(membership.generation, 4);
+    let consumer_group = get_consumer_group(&system_client).await;
+    assert_eq!(consumer_group.members_count, 2);
+
+    cleanup(&system_client, false).await;
+    assert_clean_system(&system_client).await;
+}
+
+async fn init_system(system_client: &IggyClient) {
+    // 1. Create the stream
+    system_client
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+
+    // 2. Create the topic
+    system_client
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            PARTITIONS_COUNT,
+            CompressionAlgorithm::default(),
+            None,
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+
+    // 3. Create the consumer group
+    system_client
+        .create_consumer_group(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            CONSUMER_GROUP_NAME,
+            Some(CONSUMER_GROUP_ID),
+            ConsumerGroupMode::Offset,
+        )
+        .await
+        .unwrap();
+}
+
+async fn heartbeat_consumer_group(
+    client: &IggyClient,
+) -> Result<ConsumerGroupMembership, IggyError> {
+    client
+        .heartbeat_consumer_group(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            &Identifier::numeric(CONSUMER_GROUP_ID).unwrap(),
+        )
+        .await
+}
@@ -0,0 +1,163 @@
+use crate::server::scenarios::{
+    cleanup, create_client, get_consumer_group, CONSUMER_GROUP_ID, CONSUMER_GROUP_NAME,
+    PARTITIONS_COUNT, STREAM_ID, STREAM_NAME, TOPIC_ID, TOPIC_NAME,
+};
+use iggy::client::{Client, ConsumerGroupClient, StreamClient, SystemClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
+use iggy::identifier::Identifier;
+use iggy::models::consumer_group::ConsumerGroupMembership;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::sleep;
+
+const MEMBER_NAME: &str = "static-member";
+const GRACE_PERIOD_MILLIS: u64 = 2000;
+const MAX_ATTEMPTS: u32 = 50;
+
+/// The server environment with the short grace period of the static consumer group members.
+pub fn server_envs() -> HashMap<String, String> {
+    HashMap::from([(
+        "IGGY_SYSTEM_CONSUMER_GROUP_STATIC_MEMBER_GRACE_PERIOD".to_string(),
+        format!("{GRACE_PERIOD_MILLIS} ms"),
+    )])
+}
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let system_client = create_client(client_factory).await;
+    let static_client = create_client(client_factory).await;
+    let dynamic_client = create_client(client_factory).await;
+    login_root(&system_client).await;
+    login_root(&static_client).await;
+    login_root(&dynamic_client).await;
+    init_system(&system_client).await;
+
+    // 1. Join the consumer group by the static and the dynamic member
+    let static_membership = join_consumer_group(&static_client, Some(MEMBER_NAME)).await;
+    let dynamic_membership = join_consumer_group(&dynamic_client, None).await;
+    assert_eq!(dynamic_membership.generation, 2);
+    let static_partitions = get_member_partitions(&system_client, static_membership.member_id)
+        .await
+        .unwrap();
+
+    // 2. Disconnect the static member, its partitions are kept without a rebalance
+    disconnect(&system_client, &static_client, static_membership.member_id).await;
+    let consumer_group = get_consumer_group(&system_client).await;
+    assert_eq!(consumer_group.members_count, 2);
+    assert_eq!(consumer_group.generation, 2);
+
+    // 3. Reconnect with the same member name and reclaim the previous partitions
+    let static_client = create_client(client_factory).await;
+    login_root(&static_client).await;
+    let membership = join_consumer_group(&static_client, Some(MEMBER_NAME)).await;
+    assert_ne!(membership.member_id, static_membership.member_id);
+    assert_eq!(membership.generation, 2);
+    let mut partitions = membership.partitions.clone();
+    partitions.sort();
+    assert_eq!(partitions, static_partitions);
+    let consumer_group = get_consumer_group(&system_client).await;
+    assert_eq!(consumer_group.members_count, 2);
+    assert!(
+        get_member_partitions(&system_client, static_membership.member_id)
+            .await
+            .is_none()
+    );
+
+    // 4. Disconnect the static member again and let its grace period expire
+    disconnect(&system_client, &static_client, membership.member_id).await;
+    sleep(Duration::from_millis(GRACE_PERIOD_MILLIS + 500)).await;
+    let membership = dynamic_client
+        .heartbeat_consumer_group(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            &Identifier::numeric(CONSUMER_GROUP_ID).unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(membership.generation, 3);
+    assert_eq!(membership.partitions.len() as u32, PARTITIONS_COUNT);
+    let consumer_group = get_consumer_group(&system_client).await;
+    assert_eq!(consumer_group.members_count, 1);
+    assert_eq!(consumer_group.evicted_members_count, 1);
+
+    cleanup(&system_client, false).await;
+    assert_clean_system(&system_client).await;
+}
+
+async fn init_system(system_client: &IggyClient) {
+    // 1. Create the stream
+    system_client
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+
+    // 2. Create the topic
+    system_client
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            PARTITIONS_COUNT,
+            CompressionAlgorithm::default(),
+            None,
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+
+    // 3. Create the consumer group
+    system_client
+        .create_consumer_group(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            CONSUMER_GROUP_NAME,
+            Some(CONSUMER_GROUP_ID),
+            ConsumerGroupMode::Offset,
+        )
+        .await
+        .unwrap();
+}
+
+async fn join_consumer_group(
+    client: &IggyClient,
+    member_name: Option<&str>,
+) -> ConsumerGroupMembership {
+    client
+        .join_consumer_group(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            &Identifier::numeric(CONSUMER_GROUP_ID).unwrap(),
+            member_name,
+        )
+        .await
+        .unwrap()
+}
+
+async fn disconnect(system_client: &IggyClient, client: &IggyClient, client_id: u32) {
+    client.disconnect().await.unwrap();
+    for _ in 0..MAX_ATTEMPTS {
+        if system_client.get_client(client_id).await.unwrap().is_none() {
+            return;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Client with ID: {client_id} has not been disconnected.");
+}
+
+async fn get_member_partitions(system_client: &IggyClient, member_id: u32) -> Option<Vec<u32>> {
+    let consumer_group = get_consumer_group(system_client).await;
+    consumer_group
+        .members
+        .into_iter()
+        .find(|member| member.id == member_id)
+        .map(|member| {
+            let mut partitions = member.partitions;
+            partitions.sort();
+            partitions
+        })
+}
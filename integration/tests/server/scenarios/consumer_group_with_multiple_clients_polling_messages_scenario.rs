@@ -7,6 +7,7 @@ use iggy::client::{ConsumerGroupClient, MessageClient, StreamClient, SystemClien
 use iggy::clients::client::IggyClient;
 use iggy::compression::compression_algorithm::CompressionAlgorithm;
 use iggy::consumer::Consumer;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::identifier::Identifier;
 use iggy::messages::poll_messages::PollingStrategy;
 use iggy::messages::send_messages::{Message, Partitioning};
@@ -68,6 +69,7 @@ async fn init_system(
             &Identifier::numeric(TOPIC_ID).unwrap(),
             CONSUMER_GROUP_NAME,
             Some(CONSUMER_GROUP_ID),
+            ConsumerGroupMode::Offset,
         )
         .await
         .unwrap();
@@ -0,0 +1,228 @@
+use crate::server::scenarios::{
+    cleanup, create_client, PARTITIONS_COUNT, PARTITION_ID, STREAM_ID, STREAM_NAME, TOPIC_ID,
+    TOPIC_NAME,
+};
+use bytes::Bytes;
+use iggy::client::{MessageClient, StreamClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::models::dead_letter_queue::{DeadLetterOrigin, DeadLetterQueue};
+use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::models::messages::{MessageState, PolledMessages};
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const DEAD_LETTER_QUEUE_TOPIC_ID: u32 = 2;
+const DEAD_LETTER_QUEUE_TOPIC_NAME: &str = "test-dead-letter-queue-topic";
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+const MESSAGES_COUNT: u32 = 10;
+const POISONED_OFFSET: u64 = 2;
+const REASON: &str = "processing failed";
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let client = create_client(client_factory).await;
+    login_root(&client).await;
+    init_system(&client).await;
+
+    // 1. Nack the message without the dead letter queue configured
+    assert!(nack_message(&client, POISONED_OFFSET).await.is_err());
+
+    // 2. Try to use the same topic as its own dead letter queue
+    assert!(client
+        .set_dead_letter_queue(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(DeadLetterQueue {
+                stream_id: Identifier::numeric(STREAM_ID).unwrap(),
+                topic_id: Identifier::named(TOPIC_NAME).unwrap(),
+                max_delivery_attempts: MAX_DELIVERY_ATTEMPTS,
+            }),
+        )
+        .await
+        .is_err());
+
+    // 3. Set the dead letter queue of the topic
+    client
+        .set_dead_letter_queue(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(DeadLetterQueue {
+                stream_id: Identifier::named(STREAM_NAME).unwrap(),
+                topic_id: Identifier::named(DEAD_LETTER_QUEUE_TOPIC_NAME).unwrap(),
+                max_delivery_attempts: MAX_DELIVERY_ATTEMPTS,
+            }),
+        )
+        .await
+        .unwrap();
+
+    // 4. Send the messages with the headers
+    let mut messages = (0..MESSAGES_COUNT)
+        .map(|offset| {
+            let payload = Bytes::from(format!("message {offset}"));
+            let headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            )]);
+            Message::new(None, payload, Some(headers))
+        })
+        .collect::<Vec<_>>();
+    client
+        .send_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            &Partitioning::partition_id(PARTITION_ID),
+            &mut messages,
+        )
+        .await
+        .unwrap();
+
+    // 5. Nack the message less times than the max delivery attempts, it shouldn't be moved to the dead letter queue
+    for _ in 1..MAX_DELIVERY_ATTEMPTS {
+        nack_message(&client, POISONED_OFFSET).await.unwrap();
+    }
+    let dead_letters = poll_dead_letters(&client).await;
+    assert!(dead_letters.messages.is_empty());
+    let message = poll_messages(&client, POISONED_OFFSET, 1).await;
+    let message = &message.messages[0];
+    assert_eq!(message.state, MessageState::Available);
+
+    // 6. Nack the message once again, it should be moved to the dead letter queue with the origin headers
+    nack_message(&client, POISONED_OFFSET).await.unwrap();
+    let dead_letters = poll_dead_letters(&client).await;
+    assert_eq!(dead_letters.messages.len(), 1);
+    let dead_letter = &dead_letters.messages[0];
+    assert_eq!(dead_letter.id, message.id);
+    assert_eq!(dead_letter.payload, message.payload);
+    let headers = dead_letter.headers.as_ref().unwrap();
+    assert_eq!(
+        headers
+            .get(&HeaderKey::new("key").unwrap())
+            .unwrap()
+            .as_str()
+            .unwrap(),
+        "value"
+    );
+    assert_eq!(
+        DeadLetterOrigin::from_headers(headers).unwrap(),
+        DeadLetterOrigin {
+            stream_id: STREAM_ID,
+            topic_id: TOPIC_ID,
+            partition_id: PARTITION_ID,
+            offset: POISONED_OFFSET,
+            reason: REASON.to_string(),
+            delivery_attempts: MAX_DELIVERY_ATTEMPTS,
+        }
+    );
+
+    // 7. The poisoned message is no longer delivered from the original partition
+    assert!(poll_messages(&client, POISONED_OFFSET, 1)
+        .await
+        .messages
+        .is_empty());
+    let messages = poll_messages(&client, 0, MESSAGES_COUNT).await;
+    assert_eq!(messages.messages.len(), MESSAGES_COUNT as usize - 1);
+    assert!(messages
+        .messages
+        .iter()
+        .all(|message| message.offset != POISONED_OFFSET));
+
+    // 8. Nack the poisoned message again, it shouldn't be moved to the dead letter queue twice
+    nack_message(&client, POISONED_OFFSET).await.unwrap();
+    let dead_letters = poll_dead_letters(&client).await;
+    assert_eq!(dead_letters.messages.len(), 1);
+
+    // 9. Nack the non-existing message
+    assert!(nack_message(&client, MESSAGES_COUNT as u64).await.is_err());
+
+    // 10. Remove the dead letter queue of the topic
+    client
+        .set_dead_letter_queue(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(nack_message(&client, POISONED_OFFSET + 1).await.is_err());
+
+    cleanup(&client, false).await;
+    assert_clean_system(&client).await;
+}
+
+async fn nack_message(client: &IggyClient, offset: u64) -> Result<(), iggy::error::IggyError> {
+    client
+        .nack_message(
+            &Consumer::default(),
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+            offset,
+            REASON,
+        )
+        .await
+}
+
+async fn poll_messages(client: &IggyClient, offset: u64, count: u32) -> PolledMessages {
+    client
+        .poll_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+            &Consumer::default(),
+            &PollingStrategy::offset(offset),
+            count,
+            false,
+        )
+        .await
+        .unwrap()
+}
+
+async fn poll_dead_letters(client: &IggyClient) -> PolledMessages {
+    client
+        .poll_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(DEAD_LETTER_QUEUE_TOPIC_ID).unwrap(),
+            Some(1),
+            &Consumer::default(),
+            &PollingStrategy::first(),
+            MESSAGES_COUNT,
+            false,
+        )
+        .await
+        .unwrap()
+}
+
+async fn init_system(client: &IggyClient) {
+    // 1. Create the stream
+    client
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+
+    // 2. Create the topic and the dead letter queue topic
+    for (topic_id, topic_name, partitions_count) in [
+        (TOPIC_ID, TOPIC_NAME, PARTITIONS_COUNT),
+        (DEAD_LETTER_QUEUE_TOPIC_ID, DEAD_LETTER_QUEUE_TOPIC_NAME, 1),
+    ] {
+        client
+            .create_topic(
+                &Identifier::numeric(STREAM_ID).unwrap(),
+                topic_name,
+                partitions_count,
+                CompressionAlgorithm::default(),
+                None,
+                Some(topic_id),
+                IggyExpiry::NeverExpire,
+                MaxTopicSize::ServerDefault,
+            )
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+use crate::server::scenarios::{
+    cleanup, create_client, CONSUMER_ID, CONSUMER_KIND, MESSAGES_COUNT, PARTITIONS_COUNT,
+    PARTITION_ID, STREAM_ID, STREAM_NAME, TOPIC_ID, TOPIC_NAME,
+};
+use bytes::Bytes;
+use iggy::client::{ConsumerOffsetClient, MessageClient, StreamClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::message_filter::MessageFilter;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::models::messages::PolledMessages;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const TENANTS_COUNT: u64 = 3;
+const PRIORITIES_COUNT: u64 = 10;
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let client = create_client(client_factory).await;
+    login_root(&client).await;
+    init_system(&client).await;
+
+    // 1. Send messages with the tenant and priority headers
+    let mut messages = Vec::new();
+    for offset in 0..MESSAGES_COUNT as u64 {
+        let id = (offset + 1) as u128;
+        let payload = Bytes::from(format!("message {}", offset));
+        messages.push(Message {
+            id,
+            length: payload.len() as u32,
+            payload,
+            headers: Some(create_message_headers(offset)),
+        });
+    }
+
+    client
+        .send_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            &Partitioning::partition_id(PARTITION_ID),
+            &mut messages,
+        )
+        .await
+        .unwrap();
+
+    // 2. Poll the messages of the single tenant from the given offset
+    let filter = MessageFilter::from_str("tenant == 'tenant-1'").unwrap();
+    let polled_messages = poll_messages(&client, &PollingStrategy::offset(0), 10, &filter).await;
+    assert_eq!(polled_messages.messages.len(), 10);
+    for (index, message) in polled_messages.messages.iter().enumerate() {
+        assert_eq!(message.offset, 1 + index as u64 * TENANTS_COUNT);
+        assert_eq!(get_tenant(message.headers.as_ref().unwrap()), "tenant-1");
+    }
+
+    // 3. Poll all the messages matching the combined conditions
+    let filter =
+        MessageFilter::from_str("priority >= 8 && !(tenant == \"tenant-0\" || tenant == 'none')")
+            .unwrap();
+    let polled_messages =
+        poll_messages(&client, &PollingStrategy::first(), MESSAGES_COUNT, &filter).await;
+    let expected_offsets = (0..MESSAGES_COUNT as u64)
+        .filter(|offset| offset % PRIORITIES_COUNT >= 8 && offset % TENANTS_COUNT != 0)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        polled_messages
+            .messages
+            .iter()
+            .map(|message| message.offset)
+            .collect::<Vec<_>>(),
+        expected_offsets
+    );
+
+    // 4. Poll the next matching messages with auto commit, so that the consumer skips the scanned ones
+    let filter = MessageFilter::from_str("tenant == 'tenant-2' && priority < 5").unwrap();
+    let expected_offsets = (0..MESSAGES_COUNT as u64)
+        .filter(|offset| offset % TENANTS_COUNT == 2 && offset % PRIORITIES_COUNT < 5)
+        .collect::<Vec<_>>();
+    let consumer = Consumer {
+        kind: CONSUMER_KIND,
+        id: Identifier::numeric(CONSUMER_ID).unwrap(),
+    };
+    for expected_offsets in expected_offsets.chunks(5) {
+        let polled_messages = client
+            .poll_messages_with_filter(
+                &Identifier::numeric(STREAM_ID).unwrap(),
+                &Identifier::numeric(TOPIC_ID).unwrap(),
+                Some(PARTITION_ID),
+                &consumer,
+                &PollingStrategy::next(),
+                5,
+                true,
+                &filter,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            polled_messages
+                .messages
+                .iter()
+                .map(|message| message.offset)
+                .collect::<Vec<_>>(),
+            expected_offsets
+        );
+    }
+
+    // 5. Poll the messages not matching the filter, the last scanned offset should be committed anyway
+    let polled_messages = client
+        .poll_messages_with_filter(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+            &consumer,
+            &PollingStrategy::next(),
+            5,
+            true,
+            &filter,
+        )
+        .await
+        .unwrap();
+    assert!(polled_messages.messages.is_empty());
+    let consumer_offset = client
+        .get_consumer_offset(
+            &consumer,
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(consumer_offset.stored_offset, MESSAGES_COUNT as u64 - 1);
+
+    // 6. Poll the messages with the filter comparing values of a different kind
+    let filter = MessageFilter::from_str("priority == 'high' || tenant > 1").unwrap();
+    let polled_messages =
+        poll_messages(&client, &PollingStrategy::first(), MESSAGES_COUNT, &filter).await;
+    assert!(polled_messages.messages.is_empty());
+
+    cleanup(&client, false).await;
+    assert_clean_system(&client).await;
+}
+
+async fn poll_messages(
+    client: &IggyClient,
+    strategy: &PollingStrategy,
+    count: u32,
+    filter: &MessageFilter,
+) -> PolledMessages {
+    client
+        .poll_messages_with_filter(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(PARTITION_ID),
+            &Consumer::default(),
+            strategy,
+            count,
+            false,
+            filter,
+        )
+        .await
+        .unwrap()
+}
+
+async fn init_system(client: &IggyClient) {
+    // 1. Create the stream
+    client
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+
+    // 2. Create the topic
+    client
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            PARTITIONS_COUNT,
+            CompressionAlgorithm::default(),
+            None,
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+}
+
+fn create_message_headers(offset: u64) -> HashMap<HeaderKey, HeaderValue> {
+    let mut headers = HashMap::new();
+    headers.insert(
+        HeaderKey::new("tenant").unwrap(),
+        HeaderValue::from_str(&format!("tenant-{}", offset % TENANTS_COUNT)).unwrap(),
+    );
+    headers.insert(
+        HeaderKey::new("priority").unwrap(),
+        HeaderValue::from_uint64(offset % PRIORITIES_COUNT).unwrap(),
+    );
+    headers
+}
+
+fn get_tenant(headers: &HashMap<HeaderKey, HeaderValue>) -> &str {
+    headers
+        .get(&HeaderKey::new("tenant").unwrap())
+        .unwrap()
+        .as_str()
+        .unwrap()
+}
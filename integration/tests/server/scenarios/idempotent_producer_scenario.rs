@@ -0,0 +1,317 @@
+use crate::server::scenarios::{
+    cleanup, create_client, CONSUMER_ID, STREAM_ID, STREAM_NAME, TOPIC_ID, TOPIC_NAME,
+};
+use bytes::Bytes;
+use iggy::client::{AutoLogin, Client, Credentials, MessageClient, StreamClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::command::SEND_MESSAGES_CODE;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::models::messages::PolledMessages;
+use iggy::models::producer::{PRODUCER_ID_HEADER, PRODUCER_SEQUENCE_HEADER};
+use iggy::tcp::client::TcpClient;
+use iggy::tcp::config::{TcpClientConfig, TcpClientReconnectionConfig};
+use iggy::users::defaults::{DEFAULT_ROOT_PASSWORD, DEFAULT_ROOT_USERNAME};
+use iggy::utils::crypto::Aes256GcmEncryptor;
+use iggy::utils::duration::IggyDuration;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const PARTITIONS_COUNT: u32 = 2;
+const PARTITION_ID: u32 = 1;
+
+/// The server environment with the idempotence enabled.
+pub fn server_envs() -> HashMap<String, String> {
+    HashMap::from([(
+        "IGGY_SYSTEM_IDEMPOTENCE_ENABLED".to_string(),
+        "true".to_string(),
+    )])
+}
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let client = create_client(client_factory).await;
+    login_root(&client).await;
+    init_system(&client).await;
+
+    // 1. Each producer is assigned the unique ID
+    let producer = client.init_producer().await.unwrap();
+    let other_producer = client.init_producer().await.unwrap();
+    assert_ne!(producer.id, other_producer.id);
+
+    // 2. Send the messages and retry the same batch, the messages are appended only once
+    send_messages(&client, producer.id, PARTITION_ID, 1..=3)
+        .await
+        .unwrap();
+    send_messages(&client, producer.id, PARTITION_ID, 1..=3)
+        .await
+        .unwrap();
+    let polled_messages = poll_messages(&client, PARTITION_ID).await;
+    assert_eq!(polled_messages.messages.len(), 3);
+
+    // 3. The partially retried batch appends only the messages with the new sequence numbers
+    send_messages(&client, producer.id, PARTITION_ID, 4..=5)
+        .await
+        .unwrap();
+    send_messages(&client, producer.id, PARTITION_ID, 5..=6)
+        .await
+        .unwrap();
+    let polled_messages = poll_messages(&client, PARTITION_ID).await;
+    assert_eq!(polled_messages.messages.len(), 6);
+    let sequence_header = HeaderKey::new(PRODUCER_SEQUENCE_HEADER).unwrap();
+    for (index, message) in polled_messages.messages.iter().enumerate() {
+        let headers = message.headers.as_ref().unwrap();
+        let sequence = headers.get(&sequence_header).unwrap();
+        assert_eq!(sequence.as_uint64().unwrap(), index as u64 + 1);
+    }
+
+    // 4. The gap in the sequence numbers rejects the whole batch
+    assert!(send_messages(&client, producer.id, PARTITION_ID, 8..=9)
+        .await
+        .is_err());
+    assert_eq!(poll_messages(&client, PARTITION_ID).await.messages.len(), 6);
+
+    // 5. The sequence numbers are tracked separately for each producer and partition
+    send_messages(&client, other_producer.id, PARTITION_ID, 1..=2)
+        .await
+        .unwrap();
+    send_messages(&client, producer.id, PARTITION_ID + 1, 1..=2)
+        .await
+        .unwrap();
+    assert_eq!(poll_messages(&client, PARTITION_ID).await.messages.len(), 8);
+    assert_eq!(
+        poll_messages(&client, PARTITION_ID + 1)
+            .await
+            .messages
+            .len(),
+        2
+    );
+
+    cleanup(&client, false).await;
+    assert_clean_system(&client).await;
+}
+
+/// Sends the batches by the idempotent producer through the proxy, which drops the responses to some of them
+/// once the server has appended the messages, so that the producer fails and the batch is retried by the caller.
+/// The producer encrypts the messages, thus the retried batch has a different payload, but the same sequence numbers.
+pub async fn run_retried_producer(server_address: &str) {
+    let proxy = ResponseDroppingProxy::start(server_address).await;
+    let client = create_proxied_client(&proxy.address).await;
+    init_system(&client).await;
+    let mut producer = client
+        .producer(STREAM_NAME, TOPIC_NAME)
+        .unwrap()
+        .partitioning(Partitioning::partition_id(PARTITION_ID))
+        .without_send_interval()
+        .idempotence()
+        .build();
+    producer.init().await.unwrap();
+
+    // 1. The batch is appended, but the response is lost, so the producer fails
+    proxy.drop_next_response(SEND_MESSAGES_CODE);
+    let messages = create_messages(1..=3);
+    assert!(producer.send(messages.clone()).await.is_err());
+    reconnect(&client).await;
+    assert_eq!(poll_messages(&client, PARTITION_ID).await.messages.len(), 3);
+
+    // 2. The retried batch gets the same sequence numbers and isn't appended again
+    producer.send(messages).await.unwrap();
+    assert_eq!(poll_messages(&client, PARTITION_ID).await.messages.len(), 3);
+
+    // 3. The following batches are appended
+    producer.send(create_messages(4..=5)).await.unwrap();
+    assert_eq!(poll_messages(&client, PARTITION_ID).await.messages.len(), 5);
+
+    // 4. The failed batch which isn't retried doesn't cause the next one to be discarded
+    proxy.drop_next_response(SEND_MESSAGES_CODE);
+    assert!(producer.send(create_messages(6..=7)).await.is_err());
+    reconnect(&client).await;
+    producer.send(create_messages(8..=9)).await.unwrap();
+    let polled_messages = poll_messages(&client, PARTITION_ID).await;
+    assert_eq!(polled_messages.messages.len(), 9);
+
+    cleanup(&client, false).await;
+    assert_clean_system(&client).await;
+}
+
+async fn create_proxied_client(proxy_address: &str) -> IggyClient {
+    let config = TcpClientConfig {
+        server_address: proxy_address.to_string(),
+        auto_login: AutoLogin::Enabled(Credentials::UsernamePassword(
+            DEFAULT_ROOT_USERNAME.to_string(),
+            DEFAULT_ROOT_PASSWORD.to_string(),
+        )),
+        // The client doesn't resend the request by itself, so that the failure reaches the producer.
+        reconnection: TcpClientReconnectionConfig {
+            enabled: false,
+            reestablish_after: IggyDuration::from(1000),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let client = IggyClient::create(
+        Box::new(TcpClient::create(Arc::new(config)).unwrap()),
+        None,
+        Some(Arc::new(Aes256GcmEncryptor::new(&[1; 32]).unwrap())),
+    );
+    client.connect().await.unwrap();
+    client
+}
+
+async fn reconnect(client: &IggyClient) {
+    client.disconnect().await.unwrap();
+    client.connect().await.unwrap();
+}
+
+fn create_messages(numbers: impl Iterator<Item = u64>) -> Vec<Message> {
+    numbers
+        .map(|number| Message::new(None, Bytes::from(format!("message {number}")), None))
+        .collect()
+}
+
+/// The TCP proxy in front of the server, which can drop the response to the next request with the given code,
+/// after the server has handled it, by closing the client connection, as if it was lost.
+struct ResponseDroppingProxy {
+    address: String,
+    dropped_response_code: Arc<AtomicU32>,
+}
+
+impl ResponseDroppingProxy {
+    async fn start(server_address: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let dropped_response_code = Arc::new(AtomicU32::new(0));
+        let server_address = server_address.to_string();
+        let code = dropped_response_code.clone();
+        tokio::spawn(async move {
+            while let Ok((client_stream, _)) = listener.accept().await {
+                let server_stream = TcpStream::connect(&server_address).await.unwrap();
+                tokio::spawn(Self::forward(client_stream, server_stream, code.clone()));
+            }
+        });
+        Self {
+            address,
+            dropped_response_code,
+        }
+    }
+
+    fn drop_next_response(&self, code: u32) {
+        self.dropped_response_code.store(code, Ordering::SeqCst);
+    }
+
+    async fn forward(
+        mut client_stream: TcpStream,
+        mut server_stream: TcpStream,
+        dropped_response_code: Arc<AtomicU32>,
+    ) -> std::io::Result<()> {
+        loop {
+            // The request consists of its length (including the code), the code and the payload.
+            let mut request_header = [0u8; 8];
+            client_stream.read_exact(&mut request_header).await?;
+            let length = u32::from_le_bytes(request_header[..4].try_into().unwrap()) as usize;
+            let code = u32::from_le_bytes(request_header[4..].try_into().unwrap());
+            let mut request_payload = vec![0u8; length - 4];
+            client_stream.read_exact(&mut request_payload).await?;
+            server_stream.write_all(&request_header).await?;
+            server_stream.write_all(&request_payload).await?;
+
+            // The response consists of the status, the length of the payload and the payload.
+            let mut response_header = [0u8; 8];
+            server_stream.read_exact(&mut response_header).await?;
+            let length = u32::from_le_bytes(response_header[4..].try_into().unwrap()) as usize;
+            let mut response_payload = vec![0u8; length];
+            server_stream.read_exact(&mut response_payload).await?;
+            if dropped_response_code
+                .compare_exchange(code, 0, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                return Ok(());
+            }
+
+            client_stream.write_all(&response_header).await?;
+            client_stream.write_all(&response_payload).await?;
+        }
+    }
+}
+
+async fn init_system(client: &IggyClient) {
+    // 1. Create the stream
+    client
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+
+    // 2. Create the topic
+    client
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            PARTITIONS_COUNT,
+            CompressionAlgorithm::default(),
+            None,
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+}
+
+async fn send_messages(
+    client: &IggyClient,
+    producer_id: u64,
+    partition_id: u32,
+    sequences: impl Iterator<Item = u64>,
+) -> Result<(), iggy::error::IggyError> {
+    let mut messages = sequences
+        .map(|sequence| {
+            let headers = HashMap::from([
+                (
+                    HeaderKey::new(PRODUCER_ID_HEADER).unwrap(),
+                    HeaderValue::from_uint64(producer_id).unwrap(),
+                ),
+                (
+                    HeaderKey::new(PRODUCER_SEQUENCE_HEADER).unwrap(),
+                    HeaderValue::from_uint64(sequence).unwrap(),
+                ),
+            ]);
+            Message::new(
+                None,
+                Bytes::from(format!("message {sequence}")),
+                Some(headers),
+            )
+        })
+        .collect::<Vec<_>>();
+    client
+        .send_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            &Partitioning::partition_id(partition_id),
+            &mut messages,
+        )
+        .await
+}
+
+async fn poll_messages(client: &IggyClient, partition_id: u32) -> PolledMessages {
+    client
+        .poll_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(partition_id),
+            &Consumer::new(Identifier::numeric(CONSUMER_ID).unwrap()),
+            &PollingStrategy::offset(0),
+            100,
+            false,
+        )
+        .await
+        .unwrap()
+}
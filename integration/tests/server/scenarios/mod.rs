@@ -2,17 +2,25 @@ use iggy::client::{ConsumerGroupClient, StreamClient};
 use iggy::clients::client::IggyClient;
 use iggy::consumer::ConsumerKind;
 use iggy::identifier::Identifier;
-use iggy::models::consumer_group::ConsumerGroupDetails;
+use iggy::models::consumer_group::{ConsumerGroupDetails, ConsumerGroupMembership};
 use integration::test_server::{delete_user, ClientFactory};
 
+pub mod compressed_messages_scenario;
 pub mod consumer_group_join_scenario;
+pub mod consumer_group_queue_scenario;
+pub mod consumer_group_session_scenario;
+pub mod consumer_group_static_membership_scenario;
 pub mod consumer_group_with_multiple_clients_polling_messages_scenario;
 pub mod consumer_group_with_single_client_polling_messages_scenario;
 pub mod create_message_payload;
+pub mod dead_letter_queue_scenario;
+pub mod filtered_messages_scenario;
+pub mod idempotent_producer_scenario;
 pub mod message_headers_scenario;
 pub mod message_size_scenario;
 pub mod stream_size_validation_scenario;
 pub mod system_scenario;
+pub mod transactions_scenario;
 pub mod user_scenario;
 
 const STREAM_ID: u32 = 1;
@@ -47,15 +55,16 @@ async fn get_consumer_group(client: &IggyClient) -> ConsumerGroupDetails {
         .expect("Failed to get consumer group")
 }
 
-async fn join_consumer_group(client: &IggyClient) {
+async fn join_consumer_group(client: &IggyClient) -> ConsumerGroupMembership {
     client
         .join_consumer_group(
             &Identifier::numeric(STREAM_ID).unwrap(),
             &Identifier::numeric(TOPIC_ID).unwrap(),
             &Identifier::numeric(CONSUMER_GROUP_ID).unwrap(),
+            None,
         )
         .await
-        .unwrap();
+        .unwrap()
 }
 
 async fn leave_consumer_group(client: &IggyClient) {
@@ -13,6 +13,7 @@ use iggy::client::{
 use iggy::clients::client::IggyClient;
 use iggy::compression::compression_algorithm::CompressionAlgorithm;
 use iggy::consumer::Consumer;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::error::IggyError;
 use iggy::identifier::Identifier;
 use iggy::messages::poll_messages::PollingStrategy;
@@ -413,6 +414,7 @@ pub async fn run(client_factory: &dyn ClientFactory) {
             &Identifier::numeric(TOPIC_ID).unwrap(),
             CONSUMER_GROUP_NAME,
             Some(CONSUMER_GROUP_ID),
+            ConsumerGroupMode::Offset,
         )
         .await
         .unwrap();
@@ -457,6 +459,7 @@ pub async fn run(client_factory: &dyn ClientFactory) {
             &Identifier::numeric(STREAM_ID).unwrap(),
             &Identifier::numeric(TOPIC_ID).unwrap(),
             &Identifier::numeric(CONSUMER_GROUP_ID).unwrap(),
+            None,
         )
         .await;
 
@@ -749,6 +752,10 @@ pub async fn run(client_factory: &dyn ClientFactory) {
 
     assert!(clients.len() <= 1);
 
+    // 45. Reload the unchanged server configuration and ensure the server is still available
+    client.reload_config().await.unwrap();
+    client.ping().await.unwrap();
+
     assert_clean_system(&client).await;
 }
 
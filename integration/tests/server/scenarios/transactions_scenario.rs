@@ -0,0 +1,164 @@
+use crate::server::scenarios::{
+    cleanup, create_client, CONSUMER_ID, STREAM_ID, STREAM_NAME, TOPIC_ID, TOPIC_NAME,
+};
+use bytes::Bytes;
+use iggy::client::{MessageClient, StreamClient, TopicClient, TransactionClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::models::header::HeaderKey;
+use iggy::models::messages::PolledMessages;
+use iggy::models::transaction::TRANSACTION_ID_HEADER;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+use std::collections::HashMap;
+
+const PARTITIONS_COUNT: u32 = 2;
+
+/// The server environment with the transactions enabled.
+pub fn server_envs() -> HashMap<String, String> {
+    HashMap::from([(
+        "IGGY_SYSTEM_TRANSACTION_ENABLED".to_string(),
+        "true".to_string(),
+    )])
+}
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let system_client = create_client(client_factory).await;
+    let producer = create_client(client_factory).await;
+    login_root(&system_client).await;
+    login_root(&producer).await;
+    init_system(&system_client).await;
+
+    // 1. Send the messages to both partitions within the transaction
+    let transaction = producer.begin_transaction().await.unwrap();
+    assert!(producer.begin_transaction().await.is_err());
+    send_messages(&producer, 1, 3).await;
+    send_messages(&producer, 2, 2).await;
+
+    // 2. The messages sent outside the transaction after it began are not visible either
+    send_messages(&system_client, 1, 1).await;
+    assert!(poll_committed_messages(&system_client, 1)
+        .await
+        .messages
+        .is_empty());
+    assert!(poll_committed_messages(&system_client, 2)
+        .await
+        .messages
+        .is_empty());
+    assert_eq!(poll_messages(&system_client, 1).await.messages.len(), 4);
+
+    // 3. Commit the transaction, all the messages become visible at once, without the transaction markers
+    assert!(producer
+        .commit_transaction(transaction.id + 1)
+        .await
+        .is_err());
+    producer.commit_transaction(transaction.id).await.unwrap();
+    assert!(producer.commit_transaction(transaction.id).await.is_err());
+    let polled_messages = poll_committed_messages(&system_client, 1).await;
+    assert_eq!(polled_messages.messages.len(), 4);
+    assert_eq!(polled_messages.current_offset, 4);
+    let transaction_header = HeaderKey::new(TRANSACTION_ID_HEADER).unwrap();
+    for message in &polled_messages.messages[..3] {
+        let headers = message.headers.as_ref().unwrap();
+        let transaction_id = headers.get(&transaction_header).unwrap();
+        assert_eq!(transaction_id.as_uint64().unwrap(), transaction.id);
+    }
+    assert!(polled_messages.messages[3].headers.is_none());
+    assert_eq!(
+        poll_committed_messages(&system_client, 2)
+            .await
+            .messages
+            .len(),
+        2
+    );
+
+    // 4. Abort the next transaction, its messages are visible only when reading uncommitted
+    let transaction = producer.begin_transaction().await.unwrap();
+    send_messages(&producer, 1, 2).await;
+    producer.abort_transaction(transaction.id).await.unwrap();
+    let polled_messages = poll_committed_messages(&system_client, 1).await;
+    assert_eq!(polled_messages.messages.len(), 4);
+    assert_eq!(polled_messages.current_offset, 7);
+    let polled_messages = poll_messages(&system_client, 1).await;
+    assert_eq!(polled_messages.messages.len(), 6);
+    assert!(polled_messages
+        .messages
+        .iter()
+        .all(|message| !message.state.is_transaction_marker()));
+
+    cleanup(&system_client, false).await;
+    assert_clean_system(&system_client).await;
+}
+
+async fn init_system(system_client: &IggyClient) {
+    // 1. Create the stream
+    system_client
+        .create_stream(STREAM_NAME, Some(STREAM_ID))
+        .await
+        .unwrap();
+
+    // 2. Create the topic
+    system_client
+        .create_topic(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            TOPIC_NAME,
+            PARTITIONS_COUNT,
+            CompressionAlgorithm::default(),
+            None,
+            Some(TOPIC_ID),
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+}
+
+async fn send_messages(client: &IggyClient, partition_id: u32, count: u32) {
+    let mut messages = (0..count)
+        .map(|index| Message::new(None, Bytes::from(format!("message {index}")), None))
+        .collect::<Vec<_>>();
+    client
+        .send_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            &Partitioning::partition_id(partition_id),
+            &mut messages,
+        )
+        .await
+        .unwrap();
+}
+
+async fn poll_messages(client: &IggyClient, partition_id: u32) -> PolledMessages {
+    client
+        .poll_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(partition_id),
+            &Consumer::new(Identifier::numeric(CONSUMER_ID).unwrap()),
+            &PollingStrategy::offset(0),
+            100,
+            false,
+        )
+        .await
+        .unwrap()
+}
+
+async fn poll_committed_messages(client: &IggyClient, partition_id: u32) -> PolledMessages {
+    client
+        .poll_committed_messages(
+            &Identifier::numeric(STREAM_ID).unwrap(),
+            &Identifier::numeric(TOPIC_ID).unwrap(),
+            Some(partition_id),
+            &Consumer::new(Identifier::numeric(CONSUMER_ID).unwrap()),
+            &PollingStrategy::offset(0),
+            100,
+            false,
+        )
+        .await
+        .unwrap()
+}
@@ -5,6 +5,7 @@ use iggy::streams::create_stream::CreateStream;
 use iggy::users::create_user::CreateUser;
 use server::state::command::EntryCommand;
 use server::state::entry::StateEntry;
+use server::state::system::SystemState;
 use server::state::State;
 
 #[tokio::test]
@@ -121,6 +122,69 @@ async fn should_apply_multiple_entries() {
     );
 }
 
+#[tokio::test]
+async fn should_compact_entries_into_snapshot() {
+    let setup = StateSetup::init().await;
+    assert_compacted_entries_into_snapshot(&setup).await;
+}
+
+#[tokio::test]
+async fn should_compact_entries_into_encrypted_snapshot() {
+    let setup = StateSetup::init_with_encryptor().await;
+    assert_compacted_entries_into_snapshot(&setup).await;
+}
+
+async fn assert_compacted_entries_into_snapshot(setup: &StateSetup) {
+    let state = setup.state();
+    state.init().await.unwrap();
+    let user_id = 1;
+    for username in ["user1", "user2", "user3"] {
+        let create_user = EntryCommand::CreateUser(CreateUser {
+            username: username.to_string(),
+            password: "secret".to_string(),
+            status: Default::default(),
+            permissions: None,
+        });
+        state.apply(user_id, create_user).await.unwrap();
+    }
+
+    assert!(state.snapshot(4).await.unwrap().is_none());
+    let snapshot_index = state.snapshot(3).await.unwrap();
+    assert_eq!(snapshot_index, Some(2));
+    assert!(state.load_entries().await.unwrap().is_empty());
+    assert_eq!(state.snapshot_entries_count(), 3);
+    assert!(state.snapshot(1).await.unwrap().is_none());
+
+    let create_stream = EntryCommand::CreateStream(CreateStream {
+        stream_id: Some(1),
+        name: "test".to_string(),
+    });
+    let create_stream_bytes = create_stream.to_bytes();
+    state.apply(user_id, create_stream).await.unwrap();
+
+    let mut entries = state.init().await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(state.current_index(), 3);
+    assert_eq!(state.entries_count(), 4);
+    assert_entry(
+        entries.remove(0),
+        3,
+        setup.version(),
+        user_id,
+        create_stream_bytes,
+    );
+
+    let snapshot = state.load_snapshot().await.unwrap().unwrap();
+    assert_eq!(snapshot.index, 2);
+    assert_eq!(snapshot.version, setup.version());
+    assert_eq!(snapshot.state.users.len(), 3);
+    assert!(snapshot.state.streams.is_empty());
+
+    let system_state = SystemState::load(state).await.unwrap();
+    assert_eq!(system_state.users.len(), 3);
+    assert_eq!(system_state.streams.len(), 1);
+}
+
 fn assert_entry(entry: StateEntry, index: u64, version: u32, user_id: u32, command: Bytes) {
     assert_eq!(entry.index, index);
     assert_eq!(entry.term, 0);
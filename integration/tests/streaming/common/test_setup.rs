@@ -1,6 +1,6 @@
-use server::configs::system::SystemConfig;
-use server::streaming::persistence::persister::FilePersister;
+use server::configs::system::{StorageBackend, SystemConfig};
 use server::streaming::storage::SystemStorage;
+use server::streaming::systems::system::System;
 use std::sync::Arc;
 use tokio::fs;
 use uuid::Uuid;
@@ -17,12 +17,32 @@ impl TestSetup {
 
     pub async fn init_with_config(mut config: SystemConfig) -> TestSetup {
         config.path = format!("local_data_{}", Uuid::now_v7().to_u128_le());
+        // The tests using the file storage are run against the io_uring backend when the feature is enabled.
+        if cfg!(feature = "io-uring") && config.storage.backend == StorageBackend::File {
+            config.storage.backend = StorageBackend::IoUring;
+        }
 
         let config = Arc::new(config);
         fs::create_dir(config.get_system_path()).await.unwrap();
-        let persister = FilePersister {};
-        let storage = Arc::new(SystemStorage::new(config.clone(), Arc::new(persister)));
-        TestSetup { config, storage }
+        let storage = if config.storage.backend == StorageBackend::Memory {
+            SystemStorage::in_memory(config.clone())
+        } else {
+            let persister =
+                System::resolve_persister(config.storage.backend, config.partition.enforce_fsync);
+            SystemStorage::new(config.clone(), persister)
+        };
+        TestSetup {
+            config,
+            storage: Arc::new(storage),
+        }
+    }
+
+    pub async fn init_in_memory() -> TestSetup {
+        let mut config = SystemConfig::default();
+        config.storage.backend = StorageBackend::Memory;
+        // The messages are polled from the storage rather than the cache.
+        config.cache.enabled = false;
+        Self::init_with_config(config).await
     }
 
     pub async fn create_streams_directory(&self) {
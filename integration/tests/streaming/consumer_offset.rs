@@ -1,6 +1,6 @@
 use crate::streaming::common::test_setup::TestSetup;
 use iggy::consumer::ConsumerKind;
-use server::configs::system::SystemConfig;
+use server::configs::system::{StorageBackend, SystemConfig};
 use server::streaming::partitions::partition::ConsumerOffset;
 use server::streaming::storage::PartitionStorage;
 use std::sync::Arc;
@@ -14,6 +14,14 @@ async fn should_persist_consumer_offsets_and_then_load_them_from_disk() {
     assert_persisted_offsets(&setup.config, storage, ConsumerKind::ConsumerGroup).await;
 }
 
+#[tokio::test]
+async fn should_persist_consumer_offsets_in_memory_and_then_load_them() {
+    let setup = TestSetup::init_in_memory().await;
+    let storage = setup.storage.partition.as_ref();
+    assert_persisted_offsets(&setup.config, storage, ConsumerKind::Consumer).await;
+    assert_persisted_offsets(&setup.config, storage, ConsumerKind::ConsumerGroup).await;
+}
+
 async fn assert_persisted_offsets(
     config: &Arc<SystemConfig>,
     storage: &dyn PartitionStorage,
@@ -26,7 +34,9 @@ async fn assert_persisted_offsets(
         ConsumerKind::ConsumerGroup => "consumer_group_offsets",
     };
     let path = format!("{}/{}", config.get_system_path(), path);
-    fs::create_dir(&path).await.unwrap();
+    if config.storage.backend != StorageBackend::Memory {
+        fs::create_dir(&path).await.unwrap();
+    }
     for consumer_id in 1..=consumer_ids_count {
         let expected_offsets_count = consumer_id;
         for offset in 0..=offsets_count {
@@ -1,14 +1,18 @@
 use crate::streaming::common::test_setup::TestSetup;
 use bytes::Bytes;
 use iggy::bytes_serializable::BytesSerializable;
-use iggy::messages::send_messages::Message;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::messages::message_filter::MessageFilter;
+use iggy::messages::poll_messages::{IsolationLevel, PollingStrategy};
+use iggy::messages::send_messages::{CompressedMessages, Message};
 use iggy::models::header::{HeaderKey, HeaderValue};
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
-use server::configs::system::{PartitionConfig, SystemConfig};
+use server::configs::system::{CacheConfig, PartitionConfig, StorageBackend, SystemConfig};
 use server::state::system::PartitionState;
 use server::streaming::batching::appendable_batch_info::AppendableBatchInfo;
 use server::streaming::partitions::partition::Partition;
+use server::streaming::polling_consumer::PollingConsumer;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicU32, AtomicU64};
@@ -16,13 +20,25 @@ use std::sync::Arc;
 
 #[tokio::test]
 async fn should_persist_messages_and_then_load_them_by_timestamp() {
-    let setup = TestSetup::init().await;
+    assert_messages_are_persisted_and_loaded_by_timestamp(TestSetup::init().await).await;
+}
+
+#[tokio::test]
+async fn should_persist_messages_in_memory_and_then_load_them_by_timestamp() {
+    assert_messages_are_persisted_and_loaded_by_timestamp(TestSetup::init_in_memory().await).await;
+}
+
+async fn assert_messages_are_persisted_and_loaded_by_timestamp(setup: TestSetup) {
     let stream_id = 1;
     let topic_id = 1;
     let partition_id = 1;
     let messages_count = 100;
     let config = Arc::new(SystemConfig {
         path: setup.config.path.to_string(),
+        cache: CacheConfig {
+            enabled: setup.config.cache.enabled,
+            ..Default::default()
+        },
         partition: PartitionConfig {
             messages_required_to_save: messages_count,
             enforce_fsync: true,
@@ -38,6 +54,7 @@ async fn should_persist_messages_and_then_load_them_by_timestamp() {
         config.clone(),
         setup.storage.clone(),
         IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
@@ -146,13 +163,48 @@ async fn should_persist_messages_and_then_load_them_by_timestamp() {
 }
 #[tokio::test]
 async fn should_persist_messages_and_then_load_them_from_disk() {
-    let setup = TestSetup::init().await;
+    assert_messages_are_persisted_and_loaded(TestSetup::init().await, CompressionAlgorithm::None)
+        .await;
+}
+
+#[tokio::test]
+async fn should_persist_compressed_messages_and_then_load_them_from_disk() {
+    for compression_algorithm in [
+        CompressionAlgorithm::Gzip,
+        CompressionAlgorithm::Lz4,
+        CompressionAlgorithm::Zstd,
+        CompressionAlgorithm::Snappy,
+    ] {
+        assert_messages_are_persisted_and_loaded(TestSetup::init().await, compression_algorithm)
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn should_persist_messages_in_memory_and_then_load_them() {
+    for compression_algorithm in [CompressionAlgorithm::None, CompressionAlgorithm::Zstd] {
+        assert_messages_are_persisted_and_loaded(
+            TestSetup::init_in_memory().await,
+            compression_algorithm,
+        )
+        .await;
+    }
+}
+
+async fn assert_messages_are_persisted_and_loaded(
+    setup: TestSetup,
+    compression_algorithm: CompressionAlgorithm,
+) {
     let stream_id = 1;
     let topic_id = 1;
     let partition_id = 1;
     let messages_count = 1000;
     let config = Arc::new(SystemConfig {
         path: setup.config.path.to_string(),
+        cache: CacheConfig {
+            enabled: setup.config.cache.enabled,
+            ..Default::default()
+        },
         partition: PartitionConfig {
             messages_required_to_save: messages_count,
             enforce_fsync: true,
@@ -168,6 +220,7 @@ async fn should_persist_messages_and_then_load_them_from_disk() {
         config.clone(),
         setup.storage.clone(),
         IggyExpiry::NeverExpire,
+        compression_algorithm,
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
@@ -222,6 +275,145 @@ async fn should_persist_messages_and_then_load_them_from_disk() {
         .unwrap();
     assert_eq!(partition.unsaved_messages_count, 0);
 
+    // The in-memory partition can't be loaded again, so its messages are polled with the cache disabled instead.
+    let loaded_partition = if setup.config.storage.backend == StorageBackend::Memory {
+        partition
+    } else {
+        let now = IggyTimestamp::now();
+        let mut loaded_partition = Partition::create(
+            stream_id,
+            topic_id,
+            partition.partition_id,
+            false,
+            config.clone(),
+            setup.storage.clone(),
+            IggyExpiry::NeverExpire,
+            compression_algorithm,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU32::new(0)),
+            now,
+        );
+        let partition_state = PartitionState {
+            id: partition.partition_id,
+            created_at: now,
+        };
+        loaded_partition.load(partition_state).await.unwrap();
+        loaded_partition
+    };
+    let loaded_messages = loaded_partition
+        .get_messages_by_offset(0, messages_count)
+        .await
+        .unwrap();
+    assert_eq!(loaded_messages.len(), messages_count as usize);
+    for i in 1..=messages_count {
+        let index = i as usize - 1;
+        let loaded_message = &loaded_messages[index];
+        let appended_message = &appended_messages[index];
+        assert_eq!(loaded_message.id, appended_message.id);
+        assert_eq!(loaded_message.payload, appended_message.payload);
+        assert_eq!(
+            loaded_message
+                .headers
+                .as_ref()
+                .map(|bytes| HashMap::from_bytes(bytes.clone()).unwrap()),
+            appended_message.headers
+        );
+    }
+}
+
+#[tokio::test]
+async fn should_append_messages_compressed_by_producer_and_then_load_them_from_disk() {
+    let setup = TestSetup::init().await;
+    let stream_id = 1;
+    let topic_id = 1;
+    let partition_id = 1;
+    let messages_count = 30;
+    let config = Arc::new(SystemConfig {
+        path: setup.config.path.to_string(),
+        partition: PartitionConfig {
+            messages_required_to_save: 1000,
+            enforce_fsync: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    let messages = (1..=messages_count)
+        .map(|i| Message::new(Some(i as u128), Bytes::from(format!("message {i}")), None))
+        .collect::<Vec<_>>();
+
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    partition.persist().await.unwrap();
+    let plain_messages = messages[..10].to_vec();
+    let appendable_batch_info = AppendableBatchInfo::new(
+        plain_messages
+            .iter()
+            .map(|msg| msg.get_size_bytes() as u64)
+            .sum(),
+        partition.partition_id,
+    );
+    partition
+        .append_messages(appendable_batch_info, plain_messages)
+        .await
+        .unwrap();
+    let compressed_messages =
+        CompressedMessages::compress(CompressionAlgorithm::Lz4, &messages[10..20]).unwrap();
+    partition
+        .append_compressed_messages(compressed_messages)
+        .await
+        .unwrap();
+    let mut invalid_compressed_messages =
+        CompressedMessages::compress(CompressionAlgorithm::Lz4, &messages[20..]).unwrap();
+    invalid_compressed_messages.checksum += 1;
+    assert!(partition
+        .append_compressed_messages(invalid_compressed_messages)
+        .await
+        .is_err());
+    assert_eq!(partition.current_offset, 19);
+    let plain_messages = messages[20..].to_vec();
+    let appendable_batch_info = AppendableBatchInfo::new(
+        plain_messages
+            .iter()
+            .map(|msg| msg.get_size_bytes() as u64)
+            .sum(),
+        partition.partition_id,
+    );
+    partition
+        .append_messages(appendable_batch_info, plain_messages)
+        .await
+        .unwrap();
+    // The compressed batch is buffered between the persisted and the unsaved plain messages.
+    let buffered_messages = partition.get_segments()[0]
+        .get_messages(0, messages_count)
+        .await
+        .unwrap();
+    assert_eq!(buffered_messages.len(), messages_count as usize);
+    for (index, buffered_message) in buffered_messages.iter().enumerate() {
+        assert_eq!(buffered_message.offset, index as u64);
+        assert_eq!(buffered_message.id, messages[index].id);
+    }
+    partition.flush_unsaved_buffer(true).await.unwrap();
+    assert_eq!(partition.current_offset, messages_count as u64 - 1);
+    assert_eq!(partition.get_messages_count(), messages_count as u64);
+
     let now = IggyTimestamp::now();
     let mut loaded_partition = Partition::create(
         stream_id,
@@ -231,6 +423,7 @@ async fn should_persist_messages_and_then_load_them_from_disk() {
         config.clone(),
         setup.storage.clone(),
         IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
@@ -243,23 +436,110 @@ async fn should_persist_messages_and_then_load_them_from_disk() {
         created_at: now,
     };
     loaded_partition.load(partition_state).await.unwrap();
-    let loaded_messages = loaded_partition
-        .get_messages_by_offset(0, messages_count)
+    for partition in [&partition, &loaded_partition] {
+        let loaded_messages = partition
+            .get_messages_by_offset(0, messages_count)
+            .await
+            .unwrap();
+        assert_eq!(loaded_messages.len(), messages_count as usize);
+        for (index, loaded_message) in loaded_messages.iter().enumerate() {
+            assert_eq!(loaded_message.offset, index as u64);
+            assert_eq!(loaded_message.id, messages[index].id);
+            assert_eq!(loaded_message.payload, messages[index].payload);
+        }
+
+        let loaded_messages = partition.get_messages_by_offset(15, 10).await.unwrap();
+        assert_eq!(loaded_messages.len(), 10);
+        assert_eq!(loaded_messages[0].offset, 15);
+        assert_eq!(loaded_messages[9].offset, 24);
+    }
+}
+
+#[tokio::test]
+async fn should_scan_limited_number_of_messages_when_polling_with_filter() {
+    let setup = TestSetup::init().await;
+    let stream_id = 1;
+    let topic_id = 1;
+    let partition_id = 1;
+    // The number of messages scanned by a single poll with the filter is limited to 10 000.
+    let messages_count = 10_005;
+    let config = Arc::new(SystemConfig {
+        path: setup.config.path.to_string(),
+        ..Default::default()
+    });
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    partition.persist().await.unwrap();
+    let messages = (0..messages_count)
+        .map(|offset| {
+            let mut headers = HashMap::new();
+            headers.insert(
+                HeaderKey::new("tenant").unwrap(),
+                HeaderValue::from_str(if offset == messages_count - 1 {
+                    "tenant-1"
+                } else {
+                    "tenant-0"
+                })
+                .unwrap(),
+            );
+            Message::new(
+                Some(offset as u128 + 1),
+                Bytes::from(format!("message {offset}")),
+                Some(headers),
+            )
+        })
+        .collect::<Vec<_>>();
+    let appendable_batch_info = AppendableBatchInfo::new(
+        messages.iter().map(|msg| msg.get_size_bytes() as u64).sum(),
+        partition.partition_id,
+    );
+    partition
+        .append_messages(appendable_batch_info, messages)
         .await
         .unwrap();
-    assert_eq!(loaded_messages.len(), messages_count as usize);
-    for i in 1..=messages_count {
-        let index = i as usize - 1;
-        let loaded_message = &loaded_messages[index];
-        let appended_message = &appended_messages[index];
-        assert_eq!(loaded_message.id, appended_message.id);
-        assert_eq!(loaded_message.payload, appended_message.payload);
-        assert_eq!(
-            loaded_message
-                .headers
-                .as_ref()
-                .map(|bytes| HashMap::from_bytes(bytes.clone()).unwrap()),
-            appended_message.headers
-        );
-    }
+    partition.flush_unsaved_buffer(true).await.unwrap();
+
+    let filter = MessageFilter::from_str("tenant == 'tenant-1'").unwrap();
+    let consumer = PollingConsumer::Consumer(1, partition_id);
+    let (polled_messages, last_scanned_offset) = partition
+        .get_filtered_messages(
+            consumer,
+            PollingStrategy::offset(0),
+            1,
+            &filter,
+            IsolationLevel::ReadUncommitted,
+        )
+        .await
+        .unwrap();
+    assert!(polled_messages.is_empty());
+    assert_eq!(last_scanned_offset, Some(9_999));
+
+    let (polled_messages, last_scanned_offset) = partition
+        .get_filtered_messages(
+            consumer,
+            PollingStrategy::offset(10_000),
+            1,
+            &filter,
+            IsolationLevel::ReadUncommitted,
+        )
+        .await
+        .unwrap();
+    assert_eq!(polled_messages.len(), 1);
+    assert_eq!(polled_messages[0].offset, messages_count as u64 - 1);
+    assert_eq!(last_scanned_offset, Some(messages_count as u64 - 1));
 }
@@ -1,15 +1,42 @@
 use crate::streaming::common::test_setup::TestSetup;
 use crate::streaming::create_messages;
+use bytes::Bytes;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::messages::poll_messages::IsolationLevel;
+use iggy::messages::send_messages::Message;
+use iggy::models::cleanup_policy::MESSAGE_KEY_HEADER;
+use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::models::producer::{PRODUCER_ID_HEADER, PRODUCER_SEQUENCE_HEADER};
+use iggy::models::transaction::TRANSACTION_ID_HEADER;
+use iggy::utils::byte_size::IggyByteSize;
+use iggy::utils::duration::IggyDuration;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
+use server::archiver::disk::DiskArchiver;
+use server::archiver::restore::restore_partition;
+use server::archiver::tiered::TieredStorage;
+use server::archiver::Archiver;
+use server::configs::server::DiskArchiverConfig;
+use server::configs::system::{
+    CacheConfig, IdempotenceConfig, SegmentConfig, SystemConfig, TransactionConfig,
+};
 use server::state::system::PartitionState;
 use server::streaming::batching::appendable_batch_info::AppendableBatchInfo;
 use server::streaming::partitions::partition::Partition;
+use server::streaming::persistence::persister::FilePersister;
+use server::streaming::polling_consumer::PollingConsumer;
 use server::streaming::segments::segment::{INDEX_EXTENSION, LOG_EXTENSION, TIME_INDEX_EXTENSION};
+use server::streaming::storage::SystemStorage;
+use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::atomic::{AtomicU32, AtomicU64};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs;
 
+const PRODUCER_ID: u64 = 1;
+const TRANSACTION_ID: u64 = 1;
+
 #[tokio::test]
 async fn should_persist_partition_with_segment() {
     let setup = TestSetup::init().await;
@@ -27,6 +54,7 @@ async fn should_persist_partition_with_segment() {
             setup.config.clone(),
             setup.storage.clone(),
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -58,6 +86,7 @@ async fn should_load_existing_partition_from_disk() {
             setup.config.clone(),
             setup.storage.clone(),
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -77,6 +106,7 @@ async fn should_load_existing_partition_from_disk() {
             setup.config.clone(),
             setup.storage.clone(),
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -114,6 +144,759 @@ async fn should_load_existing_partition_from_disk() {
     }
 }
 
+#[tokio::test]
+async fn should_not_append_messages_resent_by_idempotent_producer_after_loading_partition_from_disk(
+) {
+    let setup = TestSetup::init_with_config(SystemConfig {
+        idempotence: IdempotenceConfig { enabled: true },
+        ..Default::default()
+    })
+    .await;
+    let stream_id = 1;
+    let topic_id = 2;
+    let partition_id = 3;
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    partition.persist().await.unwrap();
+    append_producer_messages(&mut partition, 1..=3).await;
+    partition.flush_unsaved_buffer(true).await.unwrap();
+
+    let now = IggyTimestamp::now();
+    let mut loaded_partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        false,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        now,
+    );
+    let partition_state = PartitionState {
+        id: partition_id,
+        created_at: now,
+    };
+    loaded_partition.load(partition_state).await.unwrap();
+    append_producer_messages(&mut loaded_partition, 2..=4).await;
+
+    assert_eq!(loaded_partition.current_offset, 3);
+    let loaded_messages = loaded_partition
+        .get_messages_by_offset(0, 100)
+        .await
+        .unwrap();
+    assert_eq!(loaded_messages.len(), 4);
+}
+
+#[tokio::test]
+async fn should_not_append_messages_resent_by_idempotent_producer_after_crash_before_saving_sequences(
+) {
+    let setup = TestSetup::init_with_config(SystemConfig {
+        idempotence: IdempotenceConfig { enabled: true },
+        ..Default::default()
+    })
+    .await;
+    let stream_id = 1;
+    let topic_id = 2;
+    let partition_id = 3;
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    partition.persist().await.unwrap();
+    append_producer_messages(&mut partition, 1..=3).await;
+    partition.flush_unsaved_buffer(true).await.unwrap();
+    // The messages are persisted, but the server crashes before their sequence numbers are saved to the journal.
+    append_producer_messages(&mut partition, 4..=5).await;
+    let last_segment = partition.get_segments_mut().last_mut().unwrap();
+    while last_segment.persist_messages().await.unwrap() > 0 {}
+
+    let now = IggyTimestamp::now();
+    let mut loaded_partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        false,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        now,
+    );
+    let partition_state = PartitionState {
+        id: partition_id,
+        created_at: now,
+    };
+    loaded_partition.load(partition_state).await.unwrap();
+    append_producer_messages(&mut loaded_partition, 4..=6).await;
+
+    assert_eq!(loaded_partition.current_offset, 5);
+    let loaded_messages = loaded_partition
+        .get_messages_by_offset(0, 100)
+        .await
+        .unwrap();
+    assert_eq!(loaded_messages.len(), 6);
+}
+
+#[tokio::test]
+async fn should_load_committed_transactions_from_journal_and_prune_them_with_deleted_segments() {
+    let setup = TestSetup::init_with_config(SystemConfig {
+        transaction: TransactionConfig {
+            enabled: true,
+            ..Default::default()
+        },
+        segment: SegmentConfig {
+            size: IggyByteSize::from(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let stream_id = 1;
+    let topic_id = 2;
+    let partition_id = 3;
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    partition.persist().await.unwrap();
+    partition.register_transaction(TRANSACTION_ID);
+    append_transactional_message(&mut partition).await;
+    partition
+        .complete_transaction(TRANSACTION_ID, true)
+        .await
+        .unwrap();
+    // Each segment holds a single batch, so the transaction message and its commit marker are in the separate ones.
+    assert_eq!(partition.get_segments().len(), 2);
+    let journal_size = fs::metadata(&partition.transactions_path)
+        .await
+        .unwrap()
+        .len();
+    assert_eq!(journal_size, 16);
+
+    let now = IggyTimestamp::now();
+    let mut loaded_partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        false,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        now,
+    );
+    let partition_state = PartitionState {
+        id: partition_id,
+        created_at: now,
+    };
+    loaded_partition.load(partition_state).await.unwrap();
+    let message = loaded_partition
+        .get_messages_by_offset(0, 1)
+        .await
+        .unwrap()
+        .remove(0);
+    assert!(loaded_partition
+        .is_visible(&message, IsolationLevel::ReadCommitted)
+        .unwrap());
+
+    // The transaction is kept until the segment with its commit marker is deleted.
+    loaded_partition
+        .append_messages(
+            AppendableBatchInfo::new(1, partition_id),
+            vec![Message::new(None, Bytes::from("message"), None)],
+        )
+        .await
+        .unwrap();
+    loaded_partition.delete_segment(0).await.unwrap();
+    let journal_size = fs::metadata(&loaded_partition.transactions_path)
+        .await
+        .unwrap()
+        .len();
+    assert_eq!(journal_size, 16);
+
+    loaded_partition.delete_segment(1).await.unwrap();
+    let journal_size = fs::metadata(&loaded_partition.transactions_path)
+        .await
+        .unwrap()
+        .len();
+    assert_eq!(journal_size, 0);
+}
+
+#[tokio::test]
+async fn should_compact_closed_segments_and_then_load_them_from_disk() {
+    let setup = TestSetup::init_with_config(SystemConfig {
+        segment: SegmentConfig {
+            size: IggyByteSize::from(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let stream_id = 1;
+    let topic_id = 2;
+    let partition_id = 3;
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    partition.persist().await.unwrap();
+    append_keyed_messages(
+        &mut partition,
+        &[(Some("a"), "a1"), (Some("b"), "b1"), (None, "plain")],
+    )
+    .await;
+    append_keyed_messages(&mut partition, &[(Some("b"), ""), (Some("a"), "a2")]).await;
+    append_keyed_messages(&mut partition, &[(Some("c"), "c1")]).await;
+    assert_eq!(partition.get_segments_count(), 3);
+
+    let removed_messages = partition
+        .compact_segments(IggyDuration::from_str("1h").unwrap(), IggyTimestamp::now())
+        .await
+        .unwrap();
+    assert_eq!(removed_messages, 2);
+    let messages = partition.get_messages_by_offset(0, 100).await.unwrap();
+    let offsets = messages.iter().map(|m| m.offset).collect::<Vec<_>>();
+    assert_eq!(offsets, vec![2, 3, 4, 5]);
+
+    // The tombstone is removed once it's older than the retention.
+    let removed_messages = partition
+        .compact_segments(IggyDuration::default(), IggyTimestamp::now())
+        .await
+        .unwrap();
+    assert_eq!(removed_messages, 1);
+
+    // The segments without the obsolete messages are never rewritten, neither are the compacted ones once again.
+    let removed_messages = partition
+        .compact_segments(IggyDuration::default(), IggyTimestamp::now())
+        .await
+        .unwrap();
+    assert_eq!(removed_messages, 0);
+    let rewrites_counts = partition
+        .get_segments()
+        .iter()
+        .map(|segment| segment.rewrites_count)
+        .collect::<Vec<_>>();
+    assert_eq!(rewrites_counts, vec![1, 1, 0]);
+
+    let now = IggyTimestamp::now();
+    let mut loaded_partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        false,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        now,
+    );
+    let partition_state = PartitionState {
+        id: partition_id,
+        created_at: now,
+    };
+    loaded_partition.load(partition_state).await.unwrap();
+
+    assert_eq!(loaded_partition.current_offset, 5);
+    assert!(loaded_partition
+        .get_segments()
+        .iter()
+        .all(|segment| segment.is_closed));
+    let loaded_messages = loaded_partition
+        .get_messages_by_offset(0, 100)
+        .await
+        .unwrap();
+    let payloads = loaded_messages
+        .iter()
+        .map(|m| (m.offset, m.payload.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        payloads,
+        vec![
+            (2, Bytes::from("plain")),
+            (4, Bytes::from("a2")),
+            (5, Bytes::from("c1"))
+        ]
+    );
+    let timestamp_messages = loaded_partition
+        .get_messages_by_timestamp(IggyTimestamp::zero(), 100)
+        .await
+        .unwrap();
+    assert_eq!(timestamp_messages.len(), 3);
+}
+
+#[tokio::test]
+async fn should_truncate_messages_and_then_load_them_from_disk() {
+    let setup = TestSetup::init_with_config(SystemConfig {
+        segment: SegmentConfig {
+            size: IggyByteSize::from(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let stream_id = 1;
+    let topic_id = 2;
+    let partition_id = 3;
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    partition.persist().await.unwrap();
+    append_keyed_messages(&mut partition, &[(None, "m0"), (None, "m1"), (None, "m2")]).await;
+    append_keyed_messages(&mut partition, &[(None, "m3"), (None, "m4")]).await;
+    append_keyed_messages(&mut partition, &[(None, "m5")]).await;
+    assert_eq!(partition.get_segments_count(), 3);
+
+    // The segment following the offset is deleted, and the one containing it keeps only the preceding messages.
+    let removed_messages = partition.truncate_messages(4).await.unwrap();
+    assert_eq!(removed_messages, 2);
+    assert_eq!(partition.current_offset, 3);
+    assert_eq!(partition.get_segments_count(), 2);
+    append_keyed_messages(&mut partition, &[(None, "m4.2")]).await;
+
+    let now = IggyTimestamp::now();
+    let mut loaded_partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        false,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        now,
+    );
+    let partition_state = PartitionState {
+        id: partition_id,
+        created_at: now,
+    };
+    loaded_partition.load(partition_state).await.unwrap();
+
+    assert_eq!(loaded_partition.current_offset, 4);
+    let loaded_messages = loaded_partition
+        .get_messages_by_offset(0, 100)
+        .await
+        .unwrap();
+    let payloads = loaded_messages
+        .iter()
+        .map(|m| (m.offset, m.payload.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        payloads,
+        vec![
+            (0, Bytes::from("m0")),
+            (1, Bytes::from("m1")),
+            (2, Bytes::from("m2")),
+            (3, Bytes::from("m3")),
+            (4, Bytes::from("m4.2"))
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_close_aged_segment_and_append_next_messages_to_the_new_one() {
+    let setup = TestSetup::init_with_config(SystemConfig {
+        segment: SegmentConfig {
+            max_age: IggyDuration::from_str("100ms").unwrap(),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let stream_id = 1;
+    let topic_id = 2;
+    let partition_id = 3;
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    partition.persist().await.unwrap();
+    append_keyed_messages(&mut partition, &[(None, "a"), (None, "b")]).await;
+    assert!(!partition
+        .close_aged_segment(IggyTimestamp::now())
+        .await
+        .unwrap());
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(partition
+        .close_aged_segment(IggyTimestamp::now())
+        .await
+        .unwrap());
+    let segment = partition.get_segments().last().unwrap();
+    assert!(segment.is_closed);
+    assert_eq!(segment.end_offset, 1);
+
+    append_keyed_messages(&mut partition, &[(None, "c")]).await;
+    let segments = partition.get_segments();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[1].start_offset, 2);
+    assert!(!segments[1].is_closed);
+    let messages = partition.get_messages_by_offset(0, 100).await.unwrap();
+    assert_eq!(messages.len(), 3);
+}
+
+#[tokio::test]
+async fn should_offload_archived_segments_and_then_poll_them_from_archive() {
+    let setup = TestSetup::init_with_config(SystemConfig {
+        segment: SegmentConfig {
+            size: IggyByteSize::from(1),
+            ..Default::default()
+        },
+        cache: CacheConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let system_path = setup.config.get_system_path();
+    let archiver: Arc<dyn Archiver> = Arc::new(DiskArchiver::new(DiskArchiverConfig {
+        path: format!("{system_path}/archive"),
+    }));
+    // The cache can hold only a single log file, so the others are evicted and fetched again.
+    let storage = Arc::new(SystemStorage {
+        tiered: Some(Arc::new(TieredStorage::new(
+            archiver.clone(),
+            &format!("{system_path}/tiered_cache"),
+            IggyByteSize::from(1),
+        ))),
+        ..SystemStorage::new(setup.config.clone(), Arc::new(FilePersister {}))
+    });
+    let stream_id = 1;
+    let topic_id = 2;
+    let partition_id = 3;
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        setup.config.clone(),
+        storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    partition.persist().await.unwrap();
+    append_keyed_messages(&mut partition, &[(None, "a"), (None, "b")]).await;
+    append_keyed_messages(&mut partition, &[(None, "c")]).await;
+    append_keyed_messages(&mut partition, &[(None, "d")]).await;
+
+    let mut offloaded_segments = 0;
+    for segment in partition.get_segments_mut() {
+        if !segment.is_closed {
+            continue;
+        }
+
+        let files = [
+            segment.index_path.as_ref(),
+            segment.time_index_path.as_ref(),
+            segment.log_path.as_ref(),
+        ];
+        archiver.archive(&files, None).await.unwrap();
+        segment.offload().await.unwrap();
+        assert!(segment.is_offloaded);
+        assert!(fs::metadata(&segment.log_path).await.is_err());
+        assert!(fs::metadata(&segment.offloaded_path).await.is_ok());
+        offloaded_segments += 1;
+    }
+    assert!(offloaded_segments >= 2);
+
+    let expected_payloads = vec![
+        (0, Bytes::from("a")),
+        (1, Bytes::from("b")),
+        (2, Bytes::from("c")),
+        (3, Bytes::from("d")),
+    ];
+    let messages = partition.get_messages_by_offset(0, 100).await.unwrap();
+    let payloads = messages
+        .iter()
+        .map(|m| (m.offset, m.payload.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(payloads, expected_payloads);
+
+    let now = IggyTimestamp::now();
+    let mut loaded_partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        false,
+        setup.config.clone(),
+        storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        now,
+    );
+    let partition_state = PartitionState {
+        id: partition_id,
+        created_at: now,
+    };
+    loaded_partition.load(partition_state).await.unwrap();
+
+    assert_eq!(loaded_partition.current_offset, 3);
+    assert_eq!(
+        loaded_partition
+            .get_segments()
+            .iter()
+            .filter(|segment| segment.is_offloaded)
+            .count(),
+        offloaded_segments
+    );
+    assert_eq!(
+        loaded_partition.get_size_bytes(),
+        partition.get_size_bytes()
+    );
+    let loaded_messages = loaded_partition
+        .get_messages_by_offset(1, 100)
+        .await
+        .unwrap();
+    let payloads = loaded_messages
+        .iter()
+        .map(|m| (m.offset, m.payload.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(payloads, expected_payloads[1..]);
+    let timestamp_messages = loaded_partition
+        .get_messages_by_timestamp(IggyTimestamp::zero(), 100)
+        .await
+        .unwrap();
+    assert_eq!(timestamp_messages.len(), 4);
+}
+
+#[tokio::test]
+async fn should_restore_archived_segments_and_consumer_offsets_capped_at_last_restored_offset() {
+    let setup = TestSetup::init_with_config(SystemConfig {
+        segment: SegmentConfig {
+            size: IggyByteSize::from(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let system_path = setup.config.get_system_path();
+    let archiver: Arc<dyn Archiver> = Arc::new(DiskArchiver::new(DiskArchiverConfig {
+        path: format!("{system_path}/archive"),
+    }));
+    let stream_id = 1;
+    let topic_id = 2;
+    let partition_id = 3;
+    setup.create_partitions_directory(stream_id, topic_id).await;
+    let mut partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        true,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        IggyTimestamp::now(),
+    );
+    partition.persist().await.unwrap();
+    append_keyed_messages(&mut partition, &[(None, "a"), (None, "b")]).await;
+    append_keyed_messages(&mut partition, &[(None, "c")]).await;
+    append_keyed_messages(&mut partition, &[(None, "d")]).await;
+    let consumer = PollingConsumer::Consumer(1, partition_id);
+    let consumer_group = PollingConsumer::ConsumerGroup(2, 1);
+    partition.store_consumer_offset(consumer, 1).await.unwrap();
+    partition
+        .store_consumer_offset(consumer_group, 3)
+        .await
+        .unwrap();
+
+    // The last segment isn't archived, so the consumer group offset points past the restored messages.
+    for segment in partition
+        .get_segments()
+        .iter()
+        .filter(|segment| segment.is_closed && segment.end_offset < 3)
+    {
+        let files = [
+            segment.index_path.as_ref(),
+            segment.time_index_path.as_ref(),
+            segment.log_path.as_ref(),
+        ];
+        archiver.archive(&files, None).await.unwrap();
+    }
+    let consumer_offset_path = format!("{}/1", partition.consumer_offsets_path);
+    let consumer_group_offset_path = format!("{}/2", partition.consumer_group_offsets_path);
+    archiver
+        .archive(&[&consumer_offset_path, &consumer_group_offset_path], None)
+        .await
+        .unwrap();
+    fs::remove_dir_all(&partition.partition_path).await.unwrap();
+
+    let restored_segments = restore_partition(
+        &setup.config,
+        archiver.as_ref(),
+        stream_id,
+        topic_id,
+        partition_id,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(restored_segments, 2);
+
+    let now = IggyTimestamp::now();
+    let mut loaded_partition = Partition::create(
+        stream_id,
+        topic_id,
+        partition_id,
+        false,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU32::new(0)),
+        now,
+    );
+    let partition_state = PartitionState {
+        id: partition_id,
+        created_at: now,
+    };
+    loaded_partition.load(partition_state).await.unwrap();
+
+    assert_eq!(loaded_partition.current_offset, 2);
+    assert_eq!(
+        loaded_partition
+            .get_consumer_offset(consumer)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        loaded_partition
+            .get_consumer_offset(consumer_group)
+            .await
+            .unwrap(),
+        2
+    );
+}
+
 #[tokio::test]
 async fn should_delete_existing_partition_from_disk() {
     let setup = TestSetup::init().await;
@@ -131,6 +914,7 @@ async fn should_delete_existing_partition_from_disk() {
             setup.config.clone(),
             setup.storage.clone(),
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -164,6 +948,7 @@ async fn should_purge_existing_partition_on_disk() {
             setup.config.clone(),
             setup.storage.clone(),
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -209,6 +994,77 @@ async fn assert_persisted_partition(partition_path: &str, with_segment: bool) {
     }
 }
 
+async fn append_producer_messages(partition: &mut Partition, sequences: impl Iterator<Item = u64>) {
+    let messages = sequences
+        .map(|sequence| {
+            let headers = HashMap::from([
+                (
+                    HeaderKey::new(PRODUCER_ID_HEADER).unwrap(),
+                    HeaderValue::from_uint64(PRODUCER_ID).unwrap(),
+                ),
+                (
+                    HeaderKey::new(PRODUCER_SEQUENCE_HEADER).unwrap(),
+                    HeaderValue::from_uint64(sequence).unwrap(),
+                ),
+            ]);
+            Message::new(
+                None,
+                Bytes::from(format!("message {sequence}")),
+                Some(headers),
+            )
+        })
+        .collect::<Vec<_>>();
+    let batch_size = messages
+        .iter()
+        .map(|message| message.get_size_bytes() as u64)
+        .sum();
+    let appendable_batch_info = AppendableBatchInfo::new(batch_size, partition.partition_id);
+    partition
+        .append_messages(appendable_batch_info, messages)
+        .await
+        .unwrap();
+}
+
+async fn append_transactional_message(partition: &mut Partition) {
+    let headers = HashMap::from([(
+        HeaderKey::new(TRANSACTION_ID_HEADER).unwrap(),
+        HeaderValue::from_uint64(TRANSACTION_ID).unwrap(),
+    )]);
+    let message = Message::new(None, Bytes::from("message"), Some(headers));
+    partition
+        .append_messages(
+            AppendableBatchInfo::new(message.get_size_bytes() as u64, partition.partition_id),
+            vec![message],
+        )
+        .await
+        .unwrap();
+}
+
+async fn append_keyed_messages(partition: &mut Partition, messages: &[(Option<&str>, &str)]) {
+    let messages = messages
+        .iter()
+        .map(|(key, payload)| {
+            let headers = key.map(|key| {
+                HashMap::from([(
+                    HeaderKey::new(MESSAGE_KEY_HEADER).unwrap(),
+                    HeaderValue::from_str(key).unwrap(),
+                )])
+            });
+            Message::new(None, Bytes::from(payload.to_string()), headers)
+        })
+        .collect::<Vec<_>>();
+    let batch_size = messages
+        .iter()
+        .map(|message| message.get_size_bytes() as u64)
+        .sum();
+    let appendable_batch_info = AppendableBatchInfo::new(batch_size, partition.partition_id);
+    partition
+        .append_messages(appendable_batch_info, messages)
+        .await
+        .unwrap();
+    partition.flush_unsaved_buffer(true).await.unwrap();
+}
+
 fn get_partition_ids() -> Vec<u32> {
     vec![1, 2, 3, 5, 10, 100, 1000, 99999]
 }
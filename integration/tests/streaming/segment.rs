@@ -1,16 +1,25 @@
 use crate::streaming::common::test_setup::TestSetup;
 use bytes::Bytes;
 use iggy::bytes_serializable::BytesSerializable;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::error::IggyError;
 use iggy::models::messages::{MessageState, PolledMessage};
+use iggy::utils::crypto::Encryptor;
 use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::text::as_base64;
 use iggy::utils::{checksum, timestamp::IggyTimestamp};
+use server::configs::system::{EncryptionConfig, IndexesRecovery, SystemConfig};
 use server::streaming::models::messages::RetainedMessage;
 use server::streaming::segments::segment;
 use server::streaming::segments::segment::{INDEX_EXTENSION, LOG_EXTENSION, TIME_INDEX_EXTENSION};
+use server::streaming::segments::verification::SegmentIssue;
 use server::streaming::sizeable::Sizeable;
+use server::streaming::utils::keyring::Keyring;
+use std::os::unix::fs::FileExt;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 
 #[tokio::test]
 async fn should_persist_segment() {
@@ -28,6 +37,7 @@ async fn should_persist_segment() {
             setup.config.clone(),
             setup.storage.clone(),
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -66,6 +76,7 @@ async fn should_load_existing_segment_from_disk() {
             setup.config.clone(),
             setup.storage.clone(),
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -93,6 +104,7 @@ async fn should_load_existing_segment_from_disk() {
             setup.config.clone(),
             setup.storage.clone(),
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -107,7 +119,6 @@ async fn should_load_existing_segment_from_disk() {
         assert_eq!(loaded_segment.start_offset, segment.start_offset);
         assert_eq!(loaded_segment.current_offset, segment.current_offset);
         assert_eq!(loaded_segment.end_offset, segment.end_offset);
-        assert_eq!(loaded_segment.size_bytes, segment.size_bytes);
         assert_eq!(loaded_segment.is_closed, segment.is_closed);
         assert_eq!(loaded_segment.log_path, segment.log_path);
         assert_eq!(loaded_segment.index_path, segment.index_path);
@@ -131,6 +142,7 @@ async fn should_persist_and_load_segment_with_messages() {
         setup.config.clone(),
         setup.storage.clone(),
         IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
@@ -182,6 +194,7 @@ async fn should_persist_and_load_segment_with_messages() {
         setup.config.clone(),
         setup.storage.clone(),
         IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
@@ -197,6 +210,76 @@ async fn should_persist_and_load_segment_with_messages() {
     assert_eq!(messages.len(), messages_count as usize);
 }
 
+#[tokio::test]
+async fn should_persist_and_load_segment_with_messages_in_memory() {
+    let setup = TestSetup::init_in_memory().await;
+    let segment = create_segment_with_messages(&setup, &[3, 4, 3]).await;
+    assert!(fs::metadata(&segment.log_path).await.is_err());
+    let storage = setup.storage.segment.clone();
+    let indexes = storage.load_all_indexes(&segment).await.unwrap();
+    let time_indexes = storage.load_all_time_indexes(&segment).await.unwrap();
+    assert_eq!(
+        indexes
+            .iter()
+            .map(|index| index.relative_offset)
+            .collect::<Vec<_>>(),
+        vec![2, 6, 9]
+    );
+    assert_eq!(indexes[0].position, 0);
+    assert!(indexes[1].position > indexes[0].position);
+    assert!(indexes[2].position > indexes[1].position);
+    assert_eq!(
+        time_indexes
+            .iter()
+            .map(|index| index.relative_offset)
+            .collect::<Vec<_>>(),
+        vec![2, 6, 9]
+    );
+
+    let mut loaded_segment = create_segment(&setup);
+    loaded_segment.load().await.unwrap();
+    assert_eq!(loaded_segment.current_offset, 9);
+    let loaded_indexes = storage.load_all_indexes(&loaded_segment).await.unwrap();
+    assert_eq!(
+        loaded_indexes
+            .iter()
+            .map(|index| (index.relative_offset, index.position))
+            .collect::<Vec<_>>(),
+        indexes
+            .iter()
+            .map(|index| (index.relative_offset, index.position))
+            .collect::<Vec<_>>()
+    );
+    let loaded_time_indexes = storage
+        .load_all_time_indexes(&loaded_segment)
+        .await
+        .unwrap();
+    for (loaded_time_index, time_index) in loaded_time_indexes.iter().zip(&time_indexes) {
+        assert_eq!(
+            loaded_time_index.relative_offset,
+            time_index.relative_offset
+        );
+        assert_eq!(loaded_time_index.timestamp, time_index.timestamp);
+    }
+
+    let messages = loaded_segment.get_messages(0, 10).await.unwrap();
+    assert_eq!(
+        messages
+            .iter()
+            .map(|message| message.offset)
+            .collect::<Vec<_>>(),
+        (0..10).collect::<Vec<_>>()
+    );
+    let messages = loaded_segment.get_messages(4, 4).await.unwrap();
+    assert_eq!(
+        messages
+            .iter()
+            .map(|message| message.offset)
+            .collect::<Vec<_>>(),
+        vec![4, 5, 6, 7]
+    );
+}
+
 #[tokio::test]
 async fn given_all_expired_messages_segment_should_be_expired() {
     let setup = TestSetup::init().await;
@@ -214,6 +297,7 @@ async fn given_all_expired_messages_segment_should_be_expired() {
         setup.config.clone(),
         setup.storage.clone(),
         message_expiry,
+        CompressionAlgorithm::None,
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
@@ -282,6 +366,7 @@ async fn given_at_least_one_not_expired_message_segment_should_not_be_expired()
         setup.config.clone(),
         setup.storage.clone(),
         message_expiry,
+        CompressionAlgorithm::None,
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
         Arc::new(AtomicU64::new(0)),
@@ -349,6 +434,232 @@ async fn given_at_least_one_not_expired_message_segment_should_not_be_expired()
     assert!(!is_expired);
 }
 
+#[tokio::test]
+async fn should_verify_and_repair_segment_with_corrupt_tail() {
+    let setup = TestSetup::init().await;
+    let messages_count = 10;
+    let segment = create_segment_with_messages(&setup, &[messages_count]).await;
+    let log_size_bytes = fs::metadata(&segment.log_path).await.unwrap().len();
+    let mut log_file = fs::OpenOptions::new()
+        .append(true)
+        .open(&segment.log_path)
+        .await
+        .unwrap();
+    log_file.write_all(&[1, 2, 3, 4, 5]).await.unwrap();
+    fs::write(&segment.index_path, b"").await.unwrap();
+
+    let verification = setup.storage.segment.verify(&segment).await.unwrap();
+    assert!(!verification.is_valid());
+    assert!(verification.has_corrupt_tail());
+    assert_eq!(verification.valid_size_bytes, log_size_bytes);
+    assert_eq!(verification.batches_count, 1);
+    assert_eq!(verification.messages_count, messages_count);
+    assert!(verification.issues.contains(&SegmentIssue::TruncatedBatch {
+        position: log_size_bytes
+    }));
+    assert!(verification.has_index_mismatch());
+
+    setup
+        .storage
+        .segment
+        .repair(&segment, &verification)
+        .await
+        .unwrap();
+    let verification = setup.storage.segment.verify(&segment).await.unwrap();
+    assert!(verification.is_valid());
+    assert_eq!(
+        fs::metadata(&segment.log_path).await.unwrap().len(),
+        log_size_bytes
+    );
+}
+
+#[tokio::test]
+async fn should_rebuild_missing_indexes_when_loading_segment() {
+    let setup = TestSetup::init().await;
+    let segment = create_segment_with_messages(&setup, &[10, 5]).await;
+    fs::remove_file(&segment.index_path).await.unwrap();
+    fs::write(&segment.time_index_path, b"").await.unwrap();
+
+    let mut loaded_segment = create_segment(&setup);
+    loaded_segment.load().await.unwrap();
+    let messages = loaded_segment.get_messages(0, 15).await.unwrap();
+    assert_eq!(messages.len(), 15);
+    let verification = setup.storage.segment.verify(&segment).await.unwrap();
+    assert!(verification.is_valid());
+}
+
+#[tokio::test]
+async fn should_truncate_segment_with_inconsistent_indexes_when_configured() {
+    let mut config = SystemConfig::default();
+    config.recovery.inconsistent_indexes = IndexesRecovery::Truncate;
+    let setup = TestSetup::init_with_config(config).await;
+    let segment = create_segment_with_messages(&setup, &[10, 5]).await;
+    let index_size = fs::metadata(&segment.index_path).await.unwrap().len();
+    fs::OpenOptions::new()
+        .write(true)
+        .open(&segment.index_path)
+        .await
+        .unwrap()
+        .set_len(index_size / 2)
+        .await
+        .unwrap();
+
+    let mut loaded_segment = create_segment(&setup);
+    loaded_segment.load().await.unwrap();
+    assert_eq!(loaded_segment.current_offset, 9);
+    let messages = loaded_segment.get_messages(0, 15).await.unwrap();
+    assert_eq!(messages.len(), 10);
+}
+
+#[tokio::test]
+async fn should_refuse_loading_segment_with_inconsistent_indexes_when_configured() {
+    let mut config = SystemConfig::default();
+    config.recovery.inconsistent_indexes = IndexesRecovery::Refuse;
+    let setup = TestSetup::init_with_config(config).await;
+    let segment = create_segment_with_messages(&setup, &[10]).await;
+    fs::remove_file(&segment.time_index_path).await.unwrap();
+
+    let mut loaded_segment = create_segment(&setup);
+    let result = loaded_segment.load().await;
+    assert!(matches!(
+        result,
+        Err(IggyError::InconsistentSegmentIndexes(_))
+    ));
+}
+
+#[tokio::test]
+async fn should_return_region_of_persisted_batches_within_offset_range() {
+    let setup = TestSetup::init().await;
+    let segment = create_segment_with_messages(&setup, &[3, 3, 3]).await;
+    let log_size = fs::metadata(&segment.log_path).await.unwrap().len();
+    let batch_size = log_size / 3;
+
+    let region = segment.get_batches_region(4, 6).await.unwrap().unwrap();
+    assert_eq!(region.path, segment.log_path);
+    assert_eq!(region.position, batch_size);
+    assert_eq!(region.length, 2 * batch_size);
+
+    let region = segment.get_batches_region(0, 8).await.unwrap().unwrap();
+    assert_eq!(region.position, 0);
+    assert_eq!(region.length, log_size);
+
+    let region = segment.get_batches_region(5, 9).await.unwrap();
+    assert!(region.is_none());
+}
+
+#[tokio::test]
+async fn should_read_region_of_batches_once_segment_log_file_is_removed() {
+    let setup = TestSetup::init().await;
+    let segment = create_segment_with_messages(&setup, &[3, 3]).await;
+    let log = fs::read(&segment.log_path).await.unwrap();
+    let region = segment.get_batches_region(0, 5).await.unwrap().unwrap();
+
+    fs::remove_file(&segment.log_path).await.unwrap();
+
+    let mut bytes = vec![0; region.length as usize];
+    region
+        .file
+        .read_exact_at(&mut bytes, region.position)
+        .unwrap();
+    assert_eq!(bytes, log);
+}
+
+#[tokio::test]
+async fn should_reencrypt_closed_segment_with_rotated_key() {
+    let setup = TestSetup::init().await;
+    let previous_keyring = create_keyring(1, "");
+    let payload = Bytes::from(previous_keyring.encrypt(b"test").unwrap());
+    let mut segment = create_segment_with_payload(&setup, &[3, 2], payload).await;
+    segment.is_closed = true;
+    let keyring = create_keyring(2, &format!("1:{}", as_base64(&[1; 32])));
+
+    let reencrypted_messages = segment.reencrypt(&keyring, 2).await.unwrap();
+    assert_eq!(reencrypted_messages, 5);
+    let messages = segment.get_messages(0, 5).await.unwrap();
+    assert_eq!(messages.len(), 5);
+    for (offset, message) in messages.iter().enumerate() {
+        assert_eq!(message.offset, offset as u64);
+        assert_eq!(keyring.get_data_key_id(&message.payload), Some(2));
+        assert_eq!(message.checksum, checksum::calculate(&message.payload));
+        assert_eq!(keyring.decrypt(&message.payload).unwrap(), b"test");
+    }
+
+    let reencrypted_messages = segment.reencrypt(&keyring, 2).await.unwrap();
+    assert_eq!(reencrypted_messages, 0);
+}
+
+fn create_keyring(active_key_id: u32, keys: &str) -> Keyring {
+    Keyring::from_config(&EncryptionConfig {
+        enabled: true,
+        key: as_base64(&[active_key_id as u8; 32]),
+        active_key_id,
+        keys: keys.to_owned(),
+        ..EncryptionConfig::default()
+    })
+    .unwrap()
+}
+
+fn create_segment(setup: &TestSetup) -> segment::Segment {
+    segment::Segment::create(
+        1,
+        2,
+        3,
+        0,
+        setup.config.clone(),
+        setup.storage.clone(),
+        IggyExpiry::NeverExpire,
+        CompressionAlgorithm::None,
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+        Arc::new(AtomicU64::new(0)),
+    )
+}
+
+/// Creates the segment and persists the batch of messages for each of the given counts.
+async fn create_segment_with_messages(setup: &TestSetup, batches: &[u64]) -> segment::Segment {
+    create_segment_with_payload(setup, batches, Bytes::from("test")).await
+}
+
+/// Creates the segment and persists the batch of messages with the given payload for each of the given counts.
+async fn create_segment_with_payload(
+    setup: &TestSetup,
+    batches: &[u64],
+    payload: Bytes,
+) -> segment::Segment {
+    setup.create_partition_directory(1, 2, 3).await;
+    let mut segment = create_segment(setup);
+    segment.persist().await.unwrap();
+    let mut offset = 0;
+    for messages_count in batches {
+        let mut messages = Vec::new();
+        let mut batch_size = 0u64;
+        for _ in 0..*messages_count {
+            let message = create_message(offset, payload.clone(), IggyTimestamp::now());
+            let retained_message = Arc::new(RetainedMessage {
+                id: message.id,
+                offset: message.offset,
+                timestamp: message.timestamp,
+                checksum: message.checksum,
+                message_state: message.state,
+                headers: message.headers.map(|headers| headers.to_bytes()),
+                payload: message.payload.clone(),
+            });
+            batch_size += retained_message.get_size_bytes() as u64;
+            messages.push(retained_message);
+            offset += 1;
+        }
+        segment
+            .append_batch(batch_size, *messages_count as u32, &messages)
+            .await
+            .unwrap();
+        segment.persist_messages().await.unwrap();
+    }
+    segment
+}
+
 async fn assert_persisted_segment(partition_path: &str, start_offset: u64) {
     let segment_path = format!("{}/{:0>20}", partition_path, start_offset);
     let log_path = format!("{}.{}", segment_path, LOG_EXTENSION);
@@ -359,8 +670,12 @@ async fn assert_persisted_segment(partition_path: &str, start_offset: u64) {
     assert!(fs::metadata(&time_index_path).await.is_ok());
 }
 
-fn create_message(offset: u64, payload: &str, timestamp: IggyTimestamp) -> PolledMessage {
-    let payload = Bytes::from(payload.to_string());
+fn create_message(
+    offset: u64,
+    payload: impl Into<Bytes>,
+    timestamp: IggyTimestamp,
+) -> PolledMessage {
+    let payload = payload.into();
     let checksum = checksum::calculate(payload.as_ref());
     PolledMessage::create(
         offset,
@@ -1,7 +1,7 @@
 use crate::streaming::common::test_setup::TestSetup;
 use crate::streaming::create_messages;
 use iggy::identifier::Identifier;
-use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::poll_messages::{IsolationLevel, PollingStrategy};
 use iggy::messages::send_messages::Partitioning;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
@@ -138,9 +138,11 @@ async fn should_purge_existing_stream_on_disk() {
                 1,
                 PollingStrategy::offset(0),
                 100,
+                IsolationLevel::ReadUncommitted,
             )
             .await
-            .unwrap();
+            .unwrap()
+            .0;
 
         assert_eq!(loaded_messages.messages.len(), messages_count);
 
@@ -151,9 +153,11 @@ async fn should_purge_existing_stream_on_disk() {
                 1,
                 PollingStrategy::offset(0),
                 100,
+                IsolationLevel::ReadUncommitted,
             )
             .await
-            .unwrap();
+            .unwrap()
+            .0;
         assert_eq!(loaded_messages.current_offset, 0);
         assert!(loaded_messages.messages.is_empty());
     }
@@ -1,6 +1,7 @@
 use crate::streaming::common::test_setup::TestSetup;
 use iggy::identifier::Identifier;
 use server::configs::server::{DataMaintenanceConfig, PersonalAccessTokenConfig};
+use server::configs::system::{StorageBackend, SystemConfig};
 use server::streaming::session::Session;
 use server::streaming::systems::system::System;
 use std::net::{Ipv4Addr, SocketAddr};
@@ -102,6 +103,44 @@ async fn should_delete_persisted_stream() {
     assert!(fs::metadata(stream_path).await.is_err());
 }
 
+#[tokio::test]
+async fn should_create_and_delete_stream_in_memory_without_touching_the_disk() {
+    let mut config = SystemConfig::default();
+    config.storage.backend = StorageBackend::Memory;
+    let setup = TestSetup::init_with_config(config).await;
+    let mut system = System::new(
+        setup.config.clone(),
+        DataMaintenanceConfig::default(),
+        PersonalAccessTokenConfig::default(),
+    );
+    let stream_id = 1;
+    let stream_name = "test";
+    let session = Session::new(1, 1, SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234));
+    system.init().await.unwrap();
+
+    system
+        .create_stream(&session, Some(stream_id), stream_name)
+        .await
+        .unwrap();
+
+    assert!(system
+        .get_stream(&Identifier::numeric(stream_id).unwrap())
+        .is_ok());
+    assert!(fs::metadata(&setup.config.get_streams_path())
+        .await
+        .is_err());
+    assert!(fs::metadata(&setup.config.get_state_path()).await.is_err());
+
+    system
+        .delete_stream(&session, &Identifier::numeric(stream_id).unwrap())
+        .await
+        .unwrap();
+
+    assert!(system
+        .get_stream(&Identifier::numeric(stream_id).unwrap())
+        .is_err());
+}
+
 async fn assert_persisted_stream(streams_path: &str, stream_id: u32) {
     let streams_metadata = fs::metadata(streams_path).await.unwrap();
     assert!(streams_metadata.is_dir());
@@ -6,7 +6,7 @@ use std::sync::Arc;
 use crate::streaming::common::test_setup::TestSetup;
 use crate::streaming::create_messages;
 use iggy::compression::compression_algorithm::CompressionAlgorithm;
-use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::poll_messages::{IsolationLevel, PollingStrategy};
 use iggy::messages::send_messages::Partitioning;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
@@ -112,6 +112,9 @@ async fn should_load_existing_topic_from_disk() {
             message_expiry: IggyExpiry::NeverExpire,
             max_topic_size: MaxTopicSize::ServerDefault,
             replication_factor: Some(1),
+            dead_letter_queue: None,
+            cleanup_policy: Default::default(),
+            max_messages: 0,
             created_at: Default::default(),
             current_consumer_group_id: 0,
         };
@@ -214,9 +217,11 @@ async fn should_purge_existing_topic_on_disk() {
                 1,
                 PollingStrategy::offset(0),
                 100,
+                IsolationLevel::ReadUncommitted,
             )
             .await
-            .unwrap();
+            .unwrap()
+            .0;
         assert_eq!(loaded_messages.messages.len(), messages_count);
 
         topic.purge().await.unwrap();
@@ -226,9 +231,11 @@ async fn should_purge_existing_topic_on_disk() {
                 1,
                 PollingStrategy::offset(0),
                 100,
+                IsolationLevel::ReadUncommitted,
             )
             .await
-            .unwrap();
+            .unwrap()
+            .0;
         assert_eq!(loaded_messages.current_offset, 0);
         assert!(loaded_messages.messages.is_empty());
     }
@@ -1,7 +1,7 @@
 use crate::streaming::common::test_setup::TestSetup;
 use bytes::Bytes;
 use iggy::locking::IggySharedMutFn;
-use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::poll_messages::{IsolationLevel, PollingStrategy};
 use iggy::messages::send_messages::{Message, Partitioning};
 use iggy::utils::byte_size::IggyByteSize;
 use iggy::utils::expiry::IggyExpiry;
@@ -91,9 +91,11 @@ async fn assert_polling_messages(cache: CacheConfig, expect_enabled_cache: bool)
             partition_id,
             PollingStrategy::offset(0),
             messages_count,
+            IsolationLevel::ReadUncommitted,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .0;
 
     assert_eq!(polled_messages.messages.len(), messages_count as usize);
     let partition = topic.get_partition(partition_id).unwrap();
@@ -211,9 +213,16 @@ fn get_payload(id: u32) -> String {
 async fn assert_messages(topic: &Topic, partition_id: u32, expected_messages: u32) {
     let consumer = PollingConsumer::Consumer(0, partition_id);
     let polled_messages = topic
-        .get_messages(consumer, partition_id, PollingStrategy::offset(0), 1000)
+        .get_messages(
+            consumer,
+            partition_id,
+            PollingStrategy::offset(0),
+            1000,
+            IsolationLevel::ReadUncommitted,
+        )
         .await
-        .unwrap();
+        .unwrap()
+        .0;
     assert_eq!(polled_messages.messages.len() as u32, expected_messages);
 }
 
@@ -0,0 +1,254 @@
+use assert_cmd::cargo::cargo_bin;
+use bytes::Bytes;
+use iggy::client::{ConsumerOffsetClient, MessageClient, StreamClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+use integration::tcp_client::TcpClientFactory;
+use integration::test_server::{login_root, ClientFactory, IpAddrKind, TestServer};
+use serial_test::parallel;
+use std::collections::HashMap;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+use tokio::time::sleep;
+
+const STREAM_NAME: &str = "mirror-stream";
+const TOPIC_NAME: &str = "mirror-topic";
+const CONSUMER_NAME: &str = "iggy-mirror";
+const PARTITIONS_COUNT: u32 = 3;
+const MESSAGES_COUNT: u32 = 10;
+const PARTITION_HEADER: &str = "source-partition";
+const MAX_ATTEMPTS: u32 = 100;
+const ATTEMPT_INTERVAL: Duration = Duration::from_millis(100);
+
+#[tokio::test]
+#[parallel]
+async fn topic_mirror_should_mirror_messages_to_the_same_partitions() {
+    let mut source_server = TestServer::new(None, true, None, IpAddrKind::V4);
+    source_server.start();
+    let mut target_server = TestServer::new(None, true, None, IpAddrKind::V4);
+    target_server.start();
+    let source = create_client(&source_server).await;
+    let target = create_client(&target_server).await;
+
+    // 1. Each source partition gets its own messages
+    create_source_topic(&source).await;
+    for partition_id in 1..=PARTITIONS_COUNT {
+        send_messages(&source, partition_id, 0..MESSAGES_COUNT).await;
+    }
+
+    // 2. The stream, the topic and its partitions are created on the target,
+    // where each message lands in the partition with the same ID and keeps its ID and headers
+    let _mirror = TopicMirror::start(&source_server, &target_server);
+    for partition_id in 1..=PARTITIONS_COUNT {
+        wait_for_mirrored_messages(&target, partition_id, MESSAGES_COUNT).await;
+    }
+    let topic = target
+        .get_topic(&stream_id(), &topic_id())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(topic.partitions_count, PARTITIONS_COUNT);
+}
+
+#[tokio::test]
+#[parallel]
+async fn topic_mirror_should_resume_from_checkpoint_after_restart() {
+    let mut source_server = TestServer::new(None, true, None, IpAddrKind::V4);
+    source_server.start();
+    let mut target_server = TestServer::new(None, true, None, IpAddrKind::V4);
+    target_server.start();
+    let source = create_client(&source_server).await;
+    let target = create_client(&target_server).await;
+
+    // 1. The mirrored messages are checkpointed as the consumer offsets on the source
+    create_source_topic(&source).await;
+    for partition_id in 1..=PARTITIONS_COUNT {
+        send_messages(&source, partition_id, 0..MESSAGES_COUNT).await;
+    }
+    let mirror = TopicMirror::start(&source_server, &target_server);
+    for partition_id in 1..=PARTITIONS_COUNT {
+        wait_for_mirrored_messages(&target, partition_id, MESSAGES_COUNT).await;
+        wait_for_checkpoint(&source, partition_id, MESSAGES_COUNT as u64 - 1).await;
+    }
+    drop(mirror);
+
+    // 2. The messages sent while the mirror was stopped are mirrored after the restart,
+    // without mirroring the already checkpointed ones again
+    for partition_id in 1..=PARTITIONS_COUNT {
+        send_messages(&source, partition_id, MESSAGES_COUNT..2 * MESSAGES_COUNT).await;
+    }
+    let _mirror = TopicMirror::start(&source_server, &target_server);
+    for partition_id in 1..=PARTITIONS_COUNT {
+        wait_for_mirrored_messages(&target, partition_id, 2 * MESSAGES_COUNT).await;
+        wait_for_checkpoint(&source, partition_id, 2 * MESSAGES_COUNT as u64 - 1).await;
+    }
+}
+
+/// Runs the topic mirror tool as the child process, which is killed once dropped.
+struct TopicMirror {
+    child: Child,
+}
+
+impl TopicMirror {
+    fn start(source_server: &TestServer, target_server: &TestServer) -> Self {
+        let child = Command::new(cargo_bin("topic-mirror-tool"))
+            .args([
+                "--source-address",
+                &source_server.get_raw_tcp_addr().unwrap(),
+                "--target-address",
+                &target_server.get_raw_tcp_addr().unwrap(),
+                "--streams",
+                &format!("^{STREAM_NAME}$"),
+                "--consumer-name",
+                CONSUMER_NAME,
+                "--batch-size",
+                "3",
+                "--discovery-interval",
+                "100 ms",
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to start topic-mirror-tool, make sure it has been built.");
+        Self { child }
+    }
+}
+
+impl Drop for TopicMirror {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+async fn create_client(server: &TestServer) -> IggyClient {
+    let client_factory = TcpClientFactory {
+        server_addr: server.get_raw_tcp_addr().unwrap(),
+    };
+    let client = IggyClient::create(client_factory.create_client().await, None, None);
+    login_root(&client).await;
+    client
+}
+
+async fn create_source_topic(client: &IggyClient) {
+    client.create_stream(STREAM_NAME, None).await.unwrap();
+    client
+        .create_topic(
+            &stream_id(),
+            TOPIC_NAME,
+            PARTITIONS_COUNT,
+            CompressionAlgorithm::default(),
+            None,
+            None,
+            IggyExpiry::NeverExpire,
+            MaxTopicSize::ServerDefault,
+        )
+        .await
+        .unwrap();
+}
+
+async fn send_messages(client: &IggyClient, partition_id: u32, ids: std::ops::Range<u32>) {
+    let mut messages = ids
+        .map(|id| {
+            let headers = HashMap::from([(
+                HeaderKey::new(PARTITION_HEADER).unwrap(),
+                HeaderValue::from_uint32(partition_id).unwrap(),
+            )]);
+            Message::new(
+                Some(message_id(partition_id, id)),
+                Bytes::from(message_payload(partition_id, id)),
+                Some(headers),
+            )
+        })
+        .collect::<Vec<_>>();
+    client
+        .send_messages(
+            &stream_id(),
+            &topic_id(),
+            &Partitioning::partition_id(partition_id),
+            &mut messages,
+        )
+        .await
+        .unwrap();
+}
+
+/// Waits until the target partition contains exactly the expected messages of the same source partition, in order.
+async fn wait_for_mirrored_messages(client: &IggyClient, partition_id: u32, count: u32) {
+    for _ in 0..MAX_ATTEMPTS {
+        let polled_messages = client
+            .poll_messages(
+                &stream_id(),
+                &topic_id(),
+                Some(partition_id),
+                &Consumer::default(),
+                &PollingStrategy::offset(0),
+                2 * count,
+                false,
+            )
+            .await;
+        if let Ok(polled_messages) = polled_messages {
+            if polled_messages.messages.len() >= count as usize {
+                assert_eq!(polled_messages.messages.len(), count as usize);
+                for (id, message) in polled_messages.messages.iter().enumerate() {
+                    let id = id as u32;
+                    assert_eq!(message.id, message_id(partition_id, id));
+                    assert_eq!(
+                        message.payload,
+                        message_payload(partition_id, id).as_bytes()
+                    );
+                    let header = message
+                        .headers
+                        .as_ref()
+                        .and_then(|headers| headers.get(&HeaderKey::new(PARTITION_HEADER).unwrap()))
+                        .unwrap();
+                    assert_eq!(header.as_uint32().unwrap(), partition_id);
+                }
+                return;
+            }
+        }
+
+        sleep(ATTEMPT_INTERVAL).await;
+    }
+
+    panic!("Messages have not been mirrored to partition: {partition_id}.");
+}
+
+async fn wait_for_checkpoint(client: &IggyClient, partition_id: u32, offset: u64) {
+    let consumer = Consumer::new(Identifier::named(CONSUMER_NAME).unwrap());
+    for _ in 0..MAX_ATTEMPTS {
+        let consumer_offset = client
+            .get_consumer_offset(&consumer, &stream_id(), &topic_id(), Some(partition_id))
+            .await
+            .unwrap();
+        if consumer_offset.is_some_and(|consumer_offset| consumer_offset.stored_offset == offset) {
+            return;
+        }
+
+        sleep(ATTEMPT_INTERVAL).await;
+    }
+
+    panic!("Checkpoint: {offset} has not been stored for partition: {partition_id}.");
+}
+
+fn message_id(partition_id: u32, id: u32) -> u128 {
+    ((partition_id as u128) << 64) + id as u128 + 1
+}
+
+fn message_payload(partition_id: u32, id: u32) -> String {
+    format!("partition-{partition_id}-message-{id}")
+}
+
+fn stream_id() -> Identifier {
+    Identifier::named(STREAM_NAME).unwrap()
+}
+
+fn topic_id() -> Identifier {
+    Identifier::named(TOPIC_NAME).unwrap()
+}
@@ -0,0 +1,34 @@
+use crate::binary::binary_client::BinaryClient;
+use crate::binary::{fail_if_not_authenticated, mapper};
+use crate::client::ClusterClient;
+use crate::cluster::add_cluster_node::AddClusterNode;
+use crate::cluster::get_cluster_metadata::GetClusterMetadata;
+use crate::cluster::remove_cluster_node::RemoveClusterNode;
+use crate::error::IggyError;
+use crate::models::cluster::ClusterMetadata;
+
+#[async_trait::async_trait]
+impl<B: BinaryClient> ClusterClient for B {
+    async fn get_cluster_metadata(&self) -> Result<ClusterMetadata, IggyError> {
+        fail_if_not_authenticated(self).await?;
+        let response = self.send_with_response(&GetClusterMetadata {}).await?;
+        mapper::map_cluster_metadata(response)
+    }
+
+    async fn add_cluster_node(&self, node_id: u32, address: &str) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(&AddClusterNode {
+            node_id,
+            address: address.to_string(),
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_cluster_node(&self, node_id: u32) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(&RemoveClusterNode { node_id })
+            .await?;
+        Ok(())
+    }
+}
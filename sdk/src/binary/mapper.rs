@@ -1,20 +1,31 @@
 use crate::bytes_serializable::BytesSerializable;
 use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::error::IggyError;
+use crate::messages::send_messages::CompressedMessages;
+use crate::models::batch_attributes::{
+    split_producer_compressed_payload, BatchAttributes, BATCH_ATTRIBUTES_PREFIX_SIZE,
+    BATCH_HEADER_SIZE,
+};
 use crate::models::client_info::{ClientInfo, ClientInfoDetails, ConsumerGroupInfo};
-use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails, ConsumerGroupMember};
+use crate::models::cluster::{ClusterMetadata, ClusterNode};
+use crate::models::consumer_group::{
+    ConsumerGroup, ConsumerGroupDetails, ConsumerGroupMember, ConsumerGroupMembership,
+};
 use crate::models::consumer_offset_info::ConsumerOffsetInfo;
 use crate::models::identity_info::IdentityInfo;
 use crate::models::messages::{MessageState, PolledMessage, PolledMessages};
 use crate::models::partition::Partition;
 use crate::models::permissions::Permissions;
 use crate::models::personal_access_token::{PersonalAccessTokenInfo, RawPersonalAccessToken};
+use crate::models::producer::Producer;
 use crate::models::stats::Stats;
 use crate::models::stream::{Stream, StreamDetails};
 use crate::models::topic::{Topic, TopicDetails};
+use crate::models::transaction::Transaction;
 use crate::models::user_info::{UserInfo, UserInfoDetails};
 use crate::models::user_status::UserStatus;
 use crate::utils::byte_size::IggyByteSize;
+use crate::utils::checksum;
 use crate::utils::expiry::IggyExpiry;
 use crate::utils::topic_size::MaxTopicSize;
 use bytes::Bytes;
@@ -229,7 +240,10 @@ pub fn map_clients(payload: Bytes) -> Result<Vec<ClientInfo>, IggyError> {
     Ok(clients)
 }
 
-pub fn map_polled_messages(payload: Bytes) -> Result<PolledMessages, IggyError> {
+/// Maps the polled messages returned in the layout of the batches stored in the partition.
+/// The batches are returned as they are, thus the compressed ones are decompressed, while the transaction markers
+/// and the messages outside the polled offset range (e.g. the preceding ones within the first batch) are skipped.
+pub fn map_polled_message_batches(payload: Bytes) -> Result<PolledMessages, IggyError> {
     if payload.is_empty() {
         return Ok(PolledMessages {
             messages: EMPTY_MESSAGES,
@@ -238,54 +252,82 @@ pub fn map_polled_messages(payload: Bytes) -> Result<PolledMessages, IggyError>
         });
     }
 
+    if payload.len() < 28 {
+        return Err(IggyError::CannotReadMessage);
+    }
+
     let length = payload.len();
     let partition_id = u32::from_le_bytes(payload[..4].try_into()?);
     let current_offset = u64::from_le_bytes(payload[4..12].try_into()?);
-    // Currently ignored
-    let _messages_count = u32::from_le_bytes(payload[12..16].try_into()?);
-    let mut position = 16;
+    let start_offset = u64::from_le_bytes(payload[12..20].try_into()?);
+    let end_offset = u64::from_le_bytes(payload[20..28].try_into()?);
+    let mut position = 28;
     let mut messages = Vec::new();
     while position < length {
-        let offset = u64::from_le_bytes(payload[position..position + 8].try_into()?);
-        let state = MessageState::from_code(payload[position + 8])?;
-        let timestamp = u64::from_le_bytes(payload[position + 9..position + 17].try_into()?);
-        let id = u128::from_le_bytes(payload[position + 17..position + 33].try_into()?);
-        let checksum = u32::from_le_bytes(payload[position + 33..position + 37].try_into()?);
-        let headers_length = u32::from_le_bytes(payload[position + 37..position + 41].try_into()?);
-        let headers = if headers_length > 0 {
-            let headers_payload =
-                payload.slice(position + 41..position + 41 + headers_length as usize);
-            Some(HashMap::from_bytes(headers_payload)?)
-        } else {
-            None
-        };
-        position += headers_length as usize;
-        let message_length = u32::from_le_bytes(payload[position + 41..position + 45].try_into()?);
-        let payload_range = position + 45..position + 45 + message_length as usize;
-        if payload_range.start > length || payload_range.end > length {
-            break;
+        if position + BATCH_HEADER_SIZE > length {
+            return Err(IggyError::CannotReadBatchLength);
         }
 
-        let payload = payload[payload_range].to_vec();
-        let total_size = 45 + message_length as usize;
-        position += total_size;
-        messages.push(PolledMessage {
-            offset,
-            timestamp,
-            state,
-            checksum,
-            id,
-            headers,
-            length: message_length,
-            payload: Bytes::from(payload),
-        });
+        let base_offset = u64::from_le_bytes(payload[position..position + 8].try_into()?);
+        let batch_length =
+            u32::from_le_bytes(payload[position + 8..position + 12].try_into()?) as usize;
+        let last_offset_delta =
+            u32::from_le_bytes(payload[position + 12..position + 16].try_into()?);
+        position += BATCH_HEADER_SIZE;
+        if position + batch_length > length {
+            return Err(IggyError::CannotReadBatchPayload);
+        }
 
-        if position + 45 >= length {
-            break;
+        let batch_payload = payload.slice(position..position + batch_length);
+        position += batch_length;
+        let last_offset = base_offset + last_offset_delta as u64;
+        if last_offset < start_offset || base_offset > end_offset {
+            continue;
         }
+
+        let attributes = BatchAttributes::from_payload(&batch_payload)?;
+        if attributes.producer_compressed {
+            let messages_count = last_offset_delta + 1;
+            let (timestamps, payload) = split_producer_compressed_payload(
+                &batch_payload.slice(BATCH_ATTRIBUTES_PREFIX_SIZE..),
+                messages_count,
+            )?;
+            let compressed_messages =
+                CompressedMessages::new(attributes.compression_algorithm, messages_count, payload);
+            for ((offset, timestamp), message) in (base_offset..)
+                .zip(timestamps)
+                .zip(compressed_messages.decompress()?)
+            {
+                if offset < start_offset || offset > end_offset {
+                    continue;
+                }
+
+                messages.push(PolledMessage {
+                    offset,
+                    state: MessageState::Available,
+                    timestamp,
+                    id: message.id,
+                    checksum: checksum::calculate(&message.payload),
+                    headers: message.headers,
+                    length: message.length,
+                    payload: message.payload,
+                });
+            }
+            continue;
+        }
+
+        let batch_payload = if attributes.is_empty() {
+            batch_payload
+        } else {
+            Bytes::from(
+                attributes
+                    .compression_algorithm
+                    .decompress(&batch_payload[BATCH_ATTRIBUTES_PREFIX_SIZE..])?,
+            )
+        };
+        map_retained_messages(batch_payload, start_offset, end_offset, &mut messages)?;
     }
 
-    messages.sort_by(|x, y| x.offset.cmp(&y.offset));
     Ok(PolledMessages {
         partition_id,
         current_offset,
@@ -293,6 +335,63 @@ pub fn map_polled_messages(payload: Bytes) -> Result<PolledMessages, IggyError>
     })
 }
 
+fn map_retained_messages(
+    payload: Bytes,
+    start_offset: u64,
+    end_offset: u64,
+    messages: &mut Vec<PolledMessage>,
+) -> Result<(), IggyError> {
+    let length = payload.len();
+    let mut position = 0;
+    while position < length {
+        if position + 4 > length {
+            return Err(IggyError::CannotReadMessageLength);
+        }
+
+        let message_length =
+            u32::from_le_bytes(payload[position..position + 4].try_into()?) as usize;
+        position += 4;
+        // The message starts with its offset, state, timestamp, ID, checksum and headers length.
+        if message_length < 41 || position + message_length > length {
+            return Err(IggyError::CannotReadMessage);
+        }
+
+        let message = payload.slice(position..position + message_length);
+        position += message_length;
+        let offset = u64::from_le_bytes(message[..8].try_into()?);
+        let state = MessageState::from_code(message[8])?;
+        if offset < start_offset || offset > end_offset || state.is_transaction_marker() {
+            continue;
+        }
+
+        let timestamp = u64::from_le_bytes(message[9..17].try_into()?);
+        let id = u128::from_le_bytes(message[17..33].try_into()?);
+        let checksum = u32::from_le_bytes(message[33..37].try_into()?);
+        let headers_length = u32::from_le_bytes(message[37..41].try_into()?) as usize;
+        if 41 + headers_length > message_length {
+            return Err(IggyError::CannotReadHeadersPayload);
+        }
+
+        let headers = if headers_length > 0 {
+            Some(HashMap::from_bytes(message.slice(41..41 + headers_length))?)
+        } else {
+            None
+        };
+        let payload = message.slice(41 + headers_length..);
+        messages.push(PolledMessage {
+            offset,
+            state,
+            timestamp,
+            id,
+            checksum,
+            headers,
+            length: payload.len() as u32,
+            payload,
+        });
+    }
+    Ok(())
+}
+
 pub fn map_streams(payload: Bytes) -> Result<Vec<Stream>, IggyError> {
     if payload.is_empty() {
         return Ok(EMPTY_STREAMS);
@@ -494,11 +593,81 @@ pub fn map_consumer_group(payload: Bytes) -> Result<ConsumerGroupDetails, IggyEr
         name: consumer_group.name,
         partitions_count: consumer_group.partitions_count,
         members_count: consumer_group.members_count,
+        generation: consumer_group.generation,
+        evicted_members_count: consumer_group.evicted_members_count,
         members,
     };
     Ok(consumer_group_details)
 }
 
+pub fn map_consumer_group_membership(payload: Bytes) -> Result<ConsumerGroupMembership, IggyError> {
+    let member_id = u32::from_le_bytes(payload[..4].try_into()?);
+    let generation = u32::from_le_bytes(payload[4..8].try_into()?);
+    let partitions_count = u32::from_le_bytes(payload[8..12].try_into()?);
+    let mut partitions = Vec::with_capacity(partitions_count as usize);
+    for i in 0..partitions_count as usize {
+        let position = 12 + i * 4;
+        partitions.push(u32::from_le_bytes(
+            payload[position..position + 4].try_into()?,
+        ));
+    }
+    Ok(ConsumerGroupMembership {
+        member_id,
+        generation,
+        partitions,
+    })
+}
+
+pub fn map_transaction(payload: Bytes) -> Result<Transaction, IggyError> {
+    let id = u64::from_le_bytes(payload[..8].try_into()?);
+    Ok(Transaction { id })
+}
+
+pub fn map_cluster_metadata(payload: Bytes) -> Result<ClusterMetadata, IggyError> {
+    if payload.len() < 20 {
+        return Err(IggyError::InvalidCommand);
+    }
+
+    let node_id = u32::from_le_bytes(payload[..4].try_into()?);
+    let term = u64::from_le_bytes(payload[4..12].try_into()?);
+    let leader_id = u32::from_le_bytes(payload[12..16].try_into()?);
+    let leader_id = if leader_id == 0 {
+        None
+    } else {
+        Some(leader_id)
+    };
+    let nodes_count = u32::from_le_bytes(payload[16..20].try_into()?);
+    let mut nodes = Vec::with_capacity(nodes_count as usize);
+    let mut position = 20;
+    for _ in 0..nodes_count {
+        if payload.len() < position + 5 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let id = u32::from_le_bytes(payload[position..position + 4].try_into()?);
+        let address_length = payload[position + 4] as usize;
+        if payload.len() < position + 5 + address_length {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let address = from_utf8(&payload[position + 5..position + 5 + address_length])?.to_string();
+        nodes.push(ClusterNode { id, address });
+        position += 5 + address_length;
+    }
+
+    Ok(ClusterMetadata {
+        node_id,
+        term,
+        leader_id,
+        nodes,
+    })
+}
+
+pub fn map_producer(payload: Bytes) -> Result<Producer, IggyError> {
+    let id = u64::from_le_bytes(payload[..8].try_into()?);
+    Ok(Producer { id })
+}
+
 fn map_to_consumer_group(
     payload: Bytes,
     position: usize,
@@ -506,15 +675,20 @@ fn map_to_consumer_group(
     let id = u32::from_le_bytes(payload[position..position + 4].try_into()?);
     let partitions_count = u32::from_le_bytes(payload[position + 4..position + 8].try_into()?);
     let members_count = u32::from_le_bytes(payload[position + 8..position + 12].try_into()?);
-    let name_length = payload[position + 12];
+    let generation = u32::from_le_bytes(payload[position + 12..position + 16].try_into()?);
+    let evicted_members_count =
+        u32::from_le_bytes(payload[position + 16..position + 20].try_into()?);
+    let name_length = payload[position + 20];
     let name =
-        from_utf8(&payload[position + 13..position + 13 + name_length as usize])?.to_string();
-    let read_bytes = 13 + name_length as usize;
+        from_utf8(&payload[position + 21..position + 21 + name_length as usize])?.to_string();
+    let read_bytes = 21 + name_length as usize;
     Ok((
         ConsumerGroup {
             id,
             partitions_count,
             members_count,
+            generation,
+            evicted_members_count,
             name,
         },
         read_bytes,
@@ -623,3 +797,79 @@ fn map_to_pat_info(
     let read_bytes = 1 + name_length as usize + 8;
     Ok((PersonalAccessTokenInfo { name, expiry_at }, read_bytes))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::send_messages::Message;
+    use crate::models::batch_attributes::BATCH_ATTRIBUTES_MARKER;
+    use bytes::{BufMut, BytesMut};
+
+    #[test]
+    fn producer_compressed_messages_should_be_mapped_with_their_own_timestamps() {
+        let messages = (0..5)
+            .map(|id| Message::new(Some(id + 1), Bytes::from(format!("message {id}")), None))
+            .collect::<Vec<_>>();
+        let compressed_messages =
+            CompressedMessages::compress(CompressionAlgorithm::Lz4, &messages).unwrap();
+        let timestamps = [1000, 1010, 1020, 1030, 1040];
+        let attributes = BatchAttributes {
+            compression_algorithm: CompressionAlgorithm::Lz4,
+            producer_compressed: true,
+        };
+        let mut batch_payload = BytesMut::new();
+        batch_payload.put_u32_le(BATCH_ATTRIBUTES_MARKER);
+        batch_payload.put_u8(attributes.as_code());
+        for timestamp in timestamps {
+            batch_payload.put_u64_le(timestamp);
+        }
+        batch_payload.put_slice(&compressed_messages.payload);
+
+        let mut payload = BytesMut::new();
+        payload.put_u32_le(1);
+        payload.put_u64_le(14);
+        payload.put_u64_le(11);
+        payload.put_u64_le(14);
+        payload.put_u64_le(10);
+        payload.put_u32_le(batch_payload.len() as u32);
+        payload.put_u32_le(4);
+        payload.put_u64_le(1040);
+        payload.put_slice(&batch_payload);
+
+        let polled_messages = map_polled_message_batches(payload.freeze()).unwrap();
+        assert_eq!(polled_messages.partition_id, 1);
+        assert_eq!(polled_messages.current_offset, 14);
+        assert_eq!(polled_messages.messages.len(), 4);
+        for (index, message) in polled_messages.messages.iter().enumerate() {
+            assert_eq!(message.offset, 11 + index as u64);
+            assert_eq!(message.timestamp, timestamps[index + 1]);
+            assert_eq!(message.id, messages[index + 1].id);
+            assert_eq!(message.payload, messages[index + 1].payload);
+        }
+    }
+
+    #[test]
+    fn producer_compressed_batch_without_timestamps_should_be_rejected() {
+        let attributes = BatchAttributes {
+            compression_algorithm: CompressionAlgorithm::Lz4,
+            producer_compressed: true,
+        };
+        let mut batch_payload = BytesMut::new();
+        batch_payload.put_u32_le(BATCH_ATTRIBUTES_MARKER);
+        batch_payload.put_u8(attributes.as_code());
+        batch_payload.put_u64_le(1000);
+
+        let mut payload = BytesMut::new();
+        payload.put_u32_le(1);
+        payload.put_u64_le(1);
+        payload.put_u64_le(0);
+        payload.put_u64_le(1);
+        payload.put_u64_le(0);
+        payload.put_u32_le(batch_payload.len() as u32);
+        payload.put_u32_le(1);
+        payload.put_u64_le(1000);
+        payload.put_slice(&batch_payload);
+
+        assert!(map_polled_message_batches(payload.freeze()).is_err());
+    }
+}
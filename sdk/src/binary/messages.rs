@@ -2,14 +2,20 @@ use crate::binary::binary_client::BinaryClient;
 use crate::binary::{fail_if_not_authenticated, mapper};
 use crate::client::MessageClient;
 use crate::command::{POLL_MESSAGES_CODE, SEND_MESSAGES_CODE};
+use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::consumer::Consumer;
 use crate::error::IggyError;
 use crate::identifier::Identifier;
+use crate::messages::ack_message::AckMessage;
 use crate::messages::flush_unsaved_buffer::FlushUnsavedBuffer;
-use crate::messages::poll_messages::PollingStrategy;
-use crate::messages::send_messages::{Message, Partitioning};
+use crate::messages::init_producer::InitProducer;
+use crate::messages::message_filter::MessageFilter;
+use crate::messages::nack_message::NackMessage;
+use crate::messages::poll_messages::{IsolationLevel, PollingStrategy};
+use crate::messages::send_messages::{Acks, CompressedMessages, Message, Partitioning};
 use crate::messages::{poll_messages, send_messages};
 use crate::models::messages::PolledMessages;
+use crate::models::producer::Producer;
 
 #[async_trait::async_trait]
 impl<B: BinaryClient> MessageClient for B {
@@ -35,10 +41,76 @@ impl<B: BinaryClient> MessageClient for B {
                     strategy,
                     count,
                     auto_commit,
+                    None,
+                    IsolationLevel::ReadUncommitted,
+                    true,
                 ),
             )
             .await?;
-        mapper::map_polled_messages(response)
+        mapper::map_polled_message_batches(response)
+    }
+
+    async fn poll_messages_with_filter(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        consumer: &Consumer,
+        strategy: &PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+        filter: &MessageFilter,
+    ) -> Result<PolledMessages, IggyError> {
+        fail_if_not_authenticated(self).await?;
+        let response = self
+            .send_raw_with_response(
+                POLL_MESSAGES_CODE,
+                poll_messages::as_bytes(
+                    stream_id,
+                    topic_id,
+                    partition_id,
+                    consumer,
+                    strategy,
+                    count,
+                    auto_commit,
+                    Some(filter),
+                    IsolationLevel::ReadUncommitted,
+                    true,
+                ),
+            )
+            .await?;
+        mapper::map_polled_message_batches(response)
+    }
+
+    async fn poll_committed_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        consumer: &Consumer,
+        strategy: &PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+    ) -> Result<PolledMessages, IggyError> {
+        fail_if_not_authenticated(self).await?;
+        let response = self
+            .send_raw_with_response(
+                POLL_MESSAGES_CODE,
+                poll_messages::as_bytes(
+                    stream_id,
+                    topic_id,
+                    partition_id,
+                    consumer,
+                    strategy,
+                    count,
+                    auto_commit,
+                    None,
+                    IsolationLevel::ReadCommitted,
+                    true,
+                ),
+            )
+            .await?;
+        mapper::map_polled_message_batches(response)
     }
 
     async fn send_messages(
@@ -51,7 +123,48 @@ impl<B: BinaryClient> MessageClient for B {
         fail_if_not_authenticated(self).await?;
         self.send_raw_with_response(
             SEND_MESSAGES_CODE,
-            send_messages::as_bytes(stream_id, topic_id, partitioning, messages),
+            send_messages::as_bytes(stream_id, topic_id, partitioning, Acks::Leader, messages),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn send_messages_with_acks(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        acks: Acks,
+        messages: &mut [Message],
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_raw_with_response(
+            SEND_MESSAGES_CODE,
+            send_messages::as_bytes(stream_id, topic_id, partitioning, acks, messages),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn send_compressed_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        compression_algorithm: CompressionAlgorithm,
+        messages: &mut [Message],
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        let compressed_messages = CompressedMessages::compress(compression_algorithm, messages)?;
+        self.send_raw_with_response(
+            SEND_MESSAGES_CODE,
+            send_messages::as_compressed_bytes(
+                stream_id,
+                topic_id,
+                partitioning,
+                Acks::Leader,
+                &compressed_messages,
+            ),
         )
         .await?;
         Ok(())
@@ -74,4 +187,52 @@ impl<B: BinaryClient> MessageClient for B {
         .await?;
         Ok(())
     }
+
+    async fn nack_message(
+        &self,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+        reason: &str,
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(&NackMessage {
+            consumer: consumer.clone(),
+            stream_id: stream_id.clone(),
+            topic_id: topic_id.clone(),
+            partition_id,
+            offset,
+            reason: reason.to_string(),
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn ack_message(
+        &self,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(&AckMessage {
+            consumer: consumer.clone(),
+            stream_id: stream_id.clone(),
+            topic_id: topic_id.clone(),
+            partition_id,
+            offset,
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn init_producer(&self) -> Result<Producer, IggyError> {
+        fail_if_not_authenticated(self).await?;
+        let response = self.send_with_response(&InitProducer {}).await?;
+        mapper::map_producer(response)
+    }
 }
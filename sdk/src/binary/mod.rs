@@ -9,6 +9,8 @@ use derive_more::Display;
 #[allow(deprecated)]
 pub mod binary_client;
 #[allow(deprecated)]
+pub mod cluster;
+#[allow(deprecated)]
 pub mod consumer_groups;
 #[allow(deprecated)]
 pub mod consumer_offsets;
@@ -26,6 +28,8 @@ pub mod system;
 #[allow(deprecated)]
 pub mod topics;
 #[allow(deprecated)]
+pub mod transactions;
+#[allow(deprecated)]
 pub mod users;
 
 /// The state of the client.
@@ -9,6 +9,7 @@ use crate::system::get_clients::GetClients;
 use crate::system::get_me::GetMe;
 use crate::system::get_stats::GetStats;
 use crate::system::ping::Ping;
+use crate::system::reload_config::ReloadConfig;
 use crate::utils::duration::IggyDuration;
 
 #[async_trait::async_trait]
@@ -46,6 +47,12 @@ impl<B: BinaryClient> SystemClient for B {
         Ok(())
     }
 
+    async fn reload_config(&self) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(&ReloadConfig {}).await?;
+        Ok(())
+    }
+
     async fn heartbeat_interval(&self) -> IggyDuration {
         self.get_heartbeat_interval()
     }
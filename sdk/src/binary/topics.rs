@@ -4,12 +4,17 @@ use crate::client::TopicClient;
 use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::error::IggyError;
 use crate::identifier::Identifier;
+use crate::models::cleanup_policy::CleanupPolicy;
+use crate::models::dead_letter_queue::DeadLetterQueue;
 use crate::models::topic::{Topic, TopicDetails};
 use crate::topics::create_topic::CreateTopic;
 use crate::topics::delete_topic::DeleteTopic;
 use crate::topics::get_topic::GetTopic;
 use crate::topics::get_topics::GetTopics;
 use crate::topics::purge_topic::PurgeTopic;
+use crate::topics::set_cleanup_policy::SetCleanupPolicy;
+use crate::topics::set_dead_letter_queue::SetDeadLetterQueue;
+use crate::topics::set_max_topic_messages::SetMaxTopicMessages;
 use crate::topics::update_topic::UpdateTopic;
 use crate::utils::expiry::IggyExpiry;
 use crate::utils::topic_size::MaxTopicSize;
@@ -123,4 +128,52 @@ impl<B: BinaryClient> TopicClient for B {
         .await?;
         Ok(())
     }
+
+    async fn set_dead_letter_queue(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        dead_letter_queue: Option<DeadLetterQueue>,
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(&SetDeadLetterQueue {
+            stream_id: stream_id.clone(),
+            topic_id: topic_id.clone(),
+            dead_letter_queue,
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn set_cleanup_policy(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        cleanup_policy: CleanupPolicy,
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(&SetCleanupPolicy {
+            stream_id: stream_id.clone(),
+            topic_id: topic_id.clone(),
+            cleanup_policy,
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn set_max_topic_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        max_messages: u64,
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(&SetMaxTopicMessages {
+            stream_id: stream_id.clone(),
+            topic_id: topic_id.clone(),
+            max_messages,
+        })
+        .await?;
+        Ok(())
+    }
 }
@@ -1,5 +1,6 @@
 use crate::cli_command::{CliCommand, PRINT_TARGET};
 use crate::client::Client;
+use crate::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use crate::consumer_groups::create_consumer_group::CreateConsumerGroup;
 use crate::identifier::Identifier;
 use anyhow::Context;
@@ -16,6 +17,7 @@ impl CreateConsumerGroupCmd {
         topic_id: Identifier,
         name: String,
         group_id: Option<u32>,
+        mode: ConsumerGroupMode,
     ) -> Self {
         Self {
             create_consumer_group: CreateConsumerGroup {
@@ -23,6 +25,7 @@ impl CreateConsumerGroupCmd {
                 topic_id,
                 name,
                 group_id,
+                mode,
             },
         }
     }
@@ -39,9 +42,10 @@ impl CreateConsumerGroupCmd {
 impl CliCommand for CreateConsumerGroupCmd {
     fn explain(&self) -> String {
         format!(
-            "create consumer group: {}, name: {} for topic with ID: {} and stream with ID: {}",
+            "create consumer group: {}, name: {}, mode: {} for topic with ID: {} and stream with ID: {}",
             self.get_group_id_info(),
             self.create_consumer_group.name,
+            self.create_consumer_group.mode,
             self.create_consumer_group.topic_id,
             self.create_consumer_group.stream_id,
         )
@@ -49,7 +53,7 @@ impl CliCommand for CreateConsumerGroupCmd {
 
     async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
         client
-            .create_consumer_group(&self.create_consumer_group.stream_id, &self.create_consumer_group.topic_id, &self.create_consumer_group.name, self.create_consumer_group.group_id)
+            .create_consumer_group(&self.create_consumer_group.stream_id, &self.create_consumer_group.topic_id, &self.create_consumer_group.name, self.create_consumer_group.group_id, self.create_consumer_group.mode)
             .await
             .with_context(|| {
                 format!(
@@ -67,6 +67,14 @@ impl CliCommand for GetConsumerGroupCmd {
             "Members count",
             format!("{}", consumer_group.members_count).as_str(),
         ]);
+        table.add_row(vec![
+            "Generation",
+            format!("{}", consumer_group.generation).as_str(),
+        ]);
+        table.add_row(vec![
+            "Evicted members",
+            format!("{}", consumer_group.evicted_members_count).as_str(),
+        ]);
 
         if consumer_group.members_count > 0 {
             let mut members_table = Table::new();
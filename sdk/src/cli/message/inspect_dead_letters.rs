@@ -0,0 +1,120 @@
+use crate::cli_command::{CliCommand, PRINT_TARGET};
+use crate::client::Client;
+use crate::consumer::Consumer;
+use crate::identifier::Identifier;
+use crate::messages::poll_messages::PollingStrategy;
+use crate::models::dead_letter_queue::DeadLetterOrigin;
+use crate::utils::timestamp::IggyTimestamp;
+use anyhow::Context;
+use async_trait::async_trait;
+use comfy_table::Table;
+use tracing::{event, Level};
+
+pub struct InspectDeadLettersCmd {
+    stream_id: Identifier,
+    topic_id: Identifier,
+    partition_id: u32,
+    offset: u64,
+    message_count: u32,
+}
+
+impl InspectDeadLettersCmd {
+    pub fn new(
+        stream_id: Identifier,
+        topic_id: Identifier,
+        partition_id: u32,
+        offset: u64,
+        message_count: u32,
+    ) -> Self {
+        Self {
+            stream_id,
+            topic_id,
+            partition_id,
+            offset,
+            message_count,
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand for InspectDeadLettersCmd {
+    fn explain(&self) -> String {
+        format!(
+            "inspect dead letters from topic with ID: {} and stream with ID: {} (partition with ID: {})",
+            self.topic_id, self.stream_id, self.partition_id
+        )
+    }
+
+    async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
+        let messages = client
+            .poll_messages(
+                &self.stream_id,
+                &self.topic_id,
+                Some(self.partition_id),
+                &Consumer::default(),
+                &PollingStrategy::offset(self.offset),
+                self.message_count,
+                false,
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "Problem polling dead letters from topic with ID: {} and stream with ID: {} (partition with ID: {})",
+                    self.topic_id, self.stream_id, self.partition_id
+                )
+            })?;
+
+        let mut table = Table::new();
+        table.set_header(vec![
+            "Offset",
+            "Timestamp",
+            "ID",
+            "Origin Stream ID",
+            "Origin Topic ID",
+            "Origin Partition ID",
+            "Origin Offset",
+            "Delivery Attempts",
+            "Reason",
+            "Payload",
+        ]);
+
+        for message in messages.messages.iter() {
+            let origin = message
+                .headers
+                .as_ref()
+                .and_then(DeadLetterOrigin::from_headers);
+            let mut row = vec![
+                format!("{}", message.offset),
+                IggyTimestamp::from(message.timestamp).to_local_string("%Y-%m-%d %H:%M:%S%.6f"),
+                format!("{}", message.id),
+            ];
+            match origin {
+                Some(origin) => row.extend([
+                    format!("{}", origin.stream_id),
+                    format!("{}", origin.topic_id),
+                    format!("{}", origin.partition_id),
+                    format!("{}", origin.offset),
+                    format!("{}", origin.delivery_attempts),
+                    origin.reason,
+                ]),
+                None => row.extend(std::iter::repeat_n(String::from("-"), 6)),
+            }
+            row.push(String::from_utf8_lossy(&message.payload).to_string());
+            table.add_row(row);
+        }
+
+        let message_count_message = match messages.messages.len() {
+            1 => "1 dead letter".into(),
+            count => format!("{} dead letters", count),
+        };
+        event!(target: PRINT_TARGET, Level::INFO,
+            "Found {message_count_message} in topic with ID: {} and stream with ID: {} (partition with ID: {})",
+            self.topic_id,
+            self.stream_id,
+            self.partition_id,
+        );
+        event!(target: PRINT_TARGET, Level::INFO, "{table}");
+
+        Ok(())
+    }
+}
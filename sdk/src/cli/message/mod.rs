@@ -1,3 +1,5 @@
 pub mod flush_messages;
+pub mod inspect_dead_letters;
 pub mod poll_messages;
+pub mod replay_dead_letters;
 pub mod send_messages;
@@ -3,7 +3,8 @@ use crate::cli_command::{CliCommand, PRINT_TARGET};
 use crate::client::Client;
 use crate::consumer::Consumer;
 use crate::identifier::Identifier;
-use crate::messages::poll_messages::{PollMessages, PollingStrategy};
+use crate::messages::message_filter::MessageFilter;
+use crate::messages::poll_messages::{IsolationLevel, PollMessages, PollingStrategy};
 use crate::messages::send_messages::Message;
 use crate::models::header::{HeaderKey, HeaderKind};
 use crate::models::messages::PolledMessages;
@@ -38,6 +39,7 @@ impl PollMessagesCmd {
         consumer: Identifier,
         show_headers: bool,
         output_file: Option<String>,
+        filter: Option<MessageFilter>,
     ) -> Self {
         let strategy = match (offset, first, last, next) {
             (Some(offset), false, false, false) => PollingStrategy::offset(offset),
@@ -55,6 +57,9 @@ impl PollMessagesCmd {
                 strategy,
                 count: message_count,
                 auto_commit,
+                filter,
+                isolation: IsolationLevel::default(),
+                batched: false,
             },
             show_headers,
             output_file,
@@ -149,23 +154,41 @@ impl CliCommand for PollMessagesCmd {
 
     async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
         let start = std::time::Instant::now();
-        let messages = client
-            .poll_messages(
-                &self.poll_messages.stream_id,
-                &self.poll_messages.topic_id,
-                self.poll_messages.partition_id,
-                &self.poll_messages.consumer,
-                &self.poll_messages.strategy,
-                self.poll_messages.count,
-                self.poll_messages.auto_commit,
+        let messages = match &self.poll_messages.filter {
+            Some(filter) => {
+                client
+                    .poll_messages_with_filter(
+                        &self.poll_messages.stream_id,
+                        &self.poll_messages.topic_id,
+                        self.poll_messages.partition_id,
+                        &self.poll_messages.consumer,
+                        &self.poll_messages.strategy,
+                        self.poll_messages.count,
+                        self.poll_messages.auto_commit,
+                        filter,
+                    )
+                    .await
+            }
+            None => {
+                client
+                    .poll_messages(
+                        &self.poll_messages.stream_id,
+                        &self.poll_messages.topic_id,
+                        self.poll_messages.partition_id,
+                        &self.poll_messages.consumer,
+                        &self.poll_messages.strategy,
+                        self.poll_messages.count,
+                        self.poll_messages.auto_commit,
+                    )
+                    .await
+            }
+        }
+        .with_context(|| {
+            format!(
+                "Problem polling messages to topic with ID: {} and stream with ID: {}",
+                self.poll_messages.topic_id, self.poll_messages.stream_id
             )
-            .await
-            .with_context(|| {
-                format!(
-                    "Problem polling messages to topic with ID: {} and stream with ID: {}",
-                    self.poll_messages.topic_id, self.poll_messages.stream_id
-                )
-            })?;
+        })?;
         let elapsed = IggyDuration::new(start.elapsed());
 
         event!(target: PRINT_TARGET, Level::INFO,
@@ -0,0 +1,114 @@
+use crate::cli_command::{CliCommand, PRINT_TARGET};
+use crate::client::Client;
+use crate::consumer::Consumer;
+use crate::identifier::Identifier;
+use crate::messages::poll_messages::PollingStrategy;
+use crate::messages::send_messages::{Message, Partitioning};
+use crate::models::dead_letter_queue::{DeadLetterOrigin, DEAD_LETTER_QUEUE_HEADERS};
+use anyhow::Context;
+use async_trait::async_trait;
+use tracing::{event, Level};
+
+pub struct ReplayDeadLettersCmd {
+    stream_id: Identifier,
+    topic_id: Identifier,
+    partition_id: u32,
+    offset: u64,
+    message_count: u32,
+}
+
+impl ReplayDeadLettersCmd {
+    pub fn new(
+        stream_id: Identifier,
+        topic_id: Identifier,
+        partition_id: u32,
+        offset: u64,
+        message_count: u32,
+    ) -> Self {
+        Self {
+            stream_id,
+            topic_id,
+            partition_id,
+            offset,
+            message_count,
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand for ReplayDeadLettersCmd {
+    fn explain(&self) -> String {
+        format!(
+            "replay dead letters from topic with ID: {} and stream with ID: {} (partition with ID: {})",
+            self.topic_id, self.stream_id, self.partition_id
+        )
+    }
+
+    async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
+        let messages = client
+            .poll_messages(
+                &self.stream_id,
+                &self.topic_id,
+                Some(self.partition_id),
+                &Consumer::default(),
+                &PollingStrategy::offset(self.offset),
+                self.message_count,
+                false,
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "Problem polling dead letters from topic with ID: {} and stream with ID: {} (partition with ID: {})",
+                    self.topic_id, self.stream_id, self.partition_id
+                )
+            })?;
+
+        let mut replayed = 0;
+        let mut skipped = 0;
+        for message in messages.messages {
+            let Some(origin) = message
+                .headers
+                .as_ref()
+                .and_then(DeadLetterOrigin::from_headers)
+            else {
+                skipped += 1;
+                continue;
+            };
+
+            let headers = message.headers.map(|mut headers| {
+                headers.retain(|key, _| !DEAD_LETTER_QUEUE_HEADERS.contains(&key.as_str()));
+                headers
+            });
+            // The new ID is generated by the server, so that the message isn't dropped by the deduplication.
+            let mut replayed_messages = vec![Message::new(
+                None,
+                message.payload,
+                headers.filter(|headers| !headers.is_empty()),
+            )];
+            client
+                .send_messages(
+                    &Identifier::numeric(origin.stream_id)?,
+                    &Identifier::numeric(origin.topic_id)?,
+                    &Partitioning::partition_id(origin.partition_id),
+                    &mut replayed_messages,
+                )
+                .await
+                .with_context(|| {
+                    format!(
+                        "Problem replaying dead letter with offset: {} to topic with ID: {} and stream with ID: {} (partition with ID: {})",
+                        message.offset, origin.topic_id, origin.stream_id, origin.partition_id
+                    )
+                })?;
+            replayed += 1;
+        }
+
+        event!(target: PRINT_TARGET, Level::INFO,
+            "Replayed {replayed} dead letters from topic with ID: {} and stream with ID: {} (partition with ID: {}), skipped {skipped} messages without origin",
+            self.topic_id,
+            self.stream_id,
+            self.partition_id,
+        );
+
+        Ok(())
+    }
+}
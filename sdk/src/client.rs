@@ -1,20 +1,27 @@
 use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::consumer::Consumer;
+use crate::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use crate::diagnostic::DiagnosticEvent;
 use crate::error::IggyError;
 use crate::identifier::Identifier;
+use crate::messages::message_filter::MessageFilter;
 use crate::messages::poll_messages::PollingStrategy;
-use crate::messages::send_messages::{Message, Partitioning};
+use crate::messages::send_messages::{Acks, Message, Partitioning};
+use crate::models::cleanup_policy::CleanupPolicy;
 use crate::models::client_info::{ClientInfo, ClientInfoDetails};
-use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails};
+use crate::models::cluster::ClusterMetadata;
+use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails, ConsumerGroupMembership};
 use crate::models::consumer_offset_info::ConsumerOffsetInfo;
+use crate::models::dead_letter_queue::DeadLetterQueue;
 use crate::models::identity_info::IdentityInfo;
 use crate::models::messages::PolledMessages;
 use crate::models::permissions::Permissions;
 use crate::models::personal_access_token::{PersonalAccessTokenInfo, RawPersonalAccessToken};
+use crate::models::producer::Producer;
 use crate::models::stats::Stats;
 use crate::models::stream::{Stream, StreamDetails};
 use crate::models::topic::{Topic, TopicDetails};
+use crate::models::transaction::Transaction;
 use crate::models::user_info::{UserInfo, UserInfoDetails};
 use crate::models::user_status::UserStatus;
 use crate::tcp::config::{TcpClientConfig, TcpClientReconnectionConfig};
@@ -62,6 +69,8 @@ pub trait Client:
     + MessageClient
     + ConsumerOffsetClient
     + ConsumerGroupClient
+    + TransactionClient
+    + ClusterClient
     + Sync
     + Send
     + Debug
@@ -101,6 +110,10 @@ pub trait SystemClient {
     async fn get_clients(&self) -> Result<Vec<ClientInfo>, IggyError>;
     /// Ping the server to check if it's alive.
     async fn ping(&self) -> Result<(), IggyError>;
+    /// Reload the server configuration without the restart, only the reloadable settings are applied.
+    ///
+    /// Authentication is required, and the permission to manage the servers.
+    async fn reload_config(&self) -> Result<(), IggyError>;
     async fn heartbeat_interval(&self) -> IggyDuration;
 }
 
@@ -274,6 +287,36 @@ pub trait TopicClient {
         stream_id: &Identifier,
         topic_id: &Identifier,
     ) -> Result<(), IggyError>;
+    /// Set or remove the dead letter queue of the topic by unique ID or name.
+    /// The messages negatively acknowledged `max_delivery_attempts` times are moved to the dead letter queue topic.
+    ///
+    /// Authentication is required, and the permission to manage the topics and to send the messages to the dead letter queue topic.
+    async fn set_dead_letter_queue(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        dead_letter_queue: Option<DeadLetterQueue>,
+    ) -> Result<(), IggyError>;
+    /// Set the cleanup policy of the topic by unique ID or name.
+    /// With the compact policy, the closed segments keep only the newest message for each key (`iggy-message-key` header).
+    ///
+    /// Authentication is required, and the permission to manage the topics.
+    async fn set_cleanup_policy(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        cleanup_policy: CleanupPolicy,
+    ) -> Result<(), IggyError>;
+    /// Set the maximum number of messages retained by the topic by unique ID or name, 0 means unlimited.
+    /// Once the limit is exceeded, the oldest closed segments are deleted.
+    ///
+    /// Authentication is required, and the permission to manage the topics.
+    async fn set_max_topic_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        max_messages: u64,
+    ) -> Result<(), IggyError>;
 }
 
 /// This trait defines the methods to interact with the partition module.
@@ -320,6 +363,38 @@ pub trait MessageClient {
         count: u32,
         auto_commit: bool,
     ) -> Result<PolledMessages, IggyError>;
+    /// Poll given amount of messages matching the filter expression using the specified consumer and strategy from the specified stream and topic by unique IDs or names.
+    /// The filter is evaluated by the server against the message headers, so only the matching messages are returned.
+    ///
+    /// Authentication is required, and the permission to poll the messages.
+    #[allow(clippy::too_many_arguments)]
+    async fn poll_messages_with_filter(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        consumer: &Consumer,
+        strategy: &PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+        filter: &MessageFilter,
+    ) -> Result<PolledMessages, IggyError>;
+    /// Poll given amount of messages in the `read_committed` isolation level using the specified consumer and strategy from the specified stream and topic by unique IDs or names.
+    /// Only the messages of the committed transactions and the ones sent outside of any transaction are returned,
+    /// and the polling stops before the first message of the transaction which is still open.
+    ///
+    /// Authentication is required, and the permission to poll the messages.
+    #[allow(clippy::too_many_arguments)]
+    async fn poll_committed_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        consumer: &Consumer,
+        strategy: &PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+    ) -> Result<PolledMessages, IggyError>;
     /// Send messages using specified partitioning strategy to the given stream and topic by unique IDs or names.
     ///
     /// Authentication is required, and the permission to send the messages.
@@ -330,6 +405,30 @@ pub trait MessageClient {
         partitioning: &Partitioning,
         messages: &mut [Message],
     ) -> Result<(), IggyError>;
+    /// Send messages using specified partitioning strategy to the given stream and topic by unique IDs or names,
+    /// which are acknowledged once stored by the replicas of the partition specified by `acks`.
+    ///
+    /// Authentication is required, and the permission to send the messages.
+    async fn send_messages_with_acks(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        acks: Acks,
+        messages: &mut [Message],
+    ) -> Result<(), IggyError>;
+    /// Send messages compressed by the client as a single batch using the specified algorithm and partitioning strategy to the given stream and topic by unique IDs or names.
+    /// The batch is stored as-is by the server and decompressed only when the messages are polled.
+    ///
+    /// Authentication is required, and the permission to send the messages.
+    async fn send_compressed_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        compression_algorithm: CompressionAlgorithm,
+        messages: &mut [Message],
+    ) -> Result<(), IggyError>;
     /// Force flush of the `unsaved_messages` buffer to disk, optionally fsyncing the data.
     #[allow(clippy::too_many_arguments)]
     async fn flush_unsaved_buffer(
@@ -339,6 +438,40 @@ pub trait MessageClient {
         partition_id: u32,
         fsync: bool,
     ) -> Result<(), IggyError>;
+    /// Negatively acknowledge the message with the given offset, e.g. when it couldn't be processed.
+    /// Once the message is nacked the number of times configured for the dead letter queue of the topic,
+    /// it's moved to the dead letter queue topic with the headers describing its origin and the reason.
+    /// For consumer group, the partition ID is ignored (use `None`).
+    ///
+    /// Authentication is required, and the permission to poll the messages.
+    async fn nack_message(
+        &self,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+        reason: &str,
+    ) -> Result<(), IggyError>;
+    /// Acknowledge the message with the given offset polled by the member of the consumer group in queue mode.
+    /// The acknowledged message won't be redelivered, even when its visibility timeout expires.
+    /// If the partition ID is `None`, the partition from which the member has polled the messages recently is used.
+    ///
+    /// Authentication is required, and the permission to poll the messages.
+    async fn ack_message(
+        &self,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+    ) -> Result<(), IggyError>;
+    /// Initialize the idempotent producer and get its unique ID assigned by the server.
+    /// The messages stamped with the producer ID and the per-partition sequence numbers are appended to the partition only once,
+    /// even if they are sent multiple times, e.g. when retried after the reconnection.
+    ///
+    /// Authentication is required.
+    async fn init_producer(&self) -> Result<Producer, IggyError>;
 }
 
 /// This trait defines the methods to interact with the consumer offset module.
@@ -388,6 +521,8 @@ pub trait ConsumerGroupClient {
         topic_id: &Identifier,
     ) -> Result<Vec<ConsumerGroup>, IggyError>;
     /// Create a new consumer group for the given stream and topic by unique IDs or names.
+    /// The mode defines whether the group tracks a single offset per partition, or leases the messages to the members
+    /// which have to acknowledge them one by one.
     ///
     /// Authentication is required, and the permission to manage the streams or topics.
     async fn create_consumer_group(
@@ -396,6 +531,7 @@ pub trait ConsumerGroupClient {
         topic_id: &Identifier,
         name: &str,
         group_id: Option<u32>,
+        mode: ConsumerGroupMode,
     ) -> Result<ConsumerGroupDetails, IggyError>;
     /// Delete a consumer group by unique ID or name for the given stream and topic by unique IDs or names.
     ///
@@ -407,6 +543,10 @@ pub trait ConsumerGroupClient {
         group_id: &Identifier,
     ) -> Result<(), IggyError>;
     /// Join a consumer group by unique ID or name for the given stream and topic by unique IDs or names.
+    /// Returns the generation of the consumer group and the partitions assigned to the member.
+    ///
+    /// The optional member name makes the membership static: once the client disconnects, the member keeps its partitions
+    /// for the grace period configured on the server, and the client joining with the same name reclaims them without a rebalance.
     ///
     /// Authentication is required, and the permission to read the streams or topics.
     async fn join_consumer_group(
@@ -414,7 +554,8 @@ pub trait ConsumerGroupClient {
         stream_id: &Identifier,
         topic_id: &Identifier,
         group_id: &Identifier,
-    ) -> Result<(), IggyError>;
+        member_name: Option<&str>,
+    ) -> Result<ConsumerGroupMembership, IggyError>;
     /// Leave a consumer group by unique ID or name for the given stream and topic by unique IDs or names.
     ///
     /// Authentication is required, and the permission to read the streams or topics.
@@ -424,6 +565,57 @@ pub trait ConsumerGroupClient {
         topic_id: &Identifier,
         group_id: &Identifier,
     ) -> Result<(), IggyError>;
+    /// Send the heartbeat of the consumer group member by unique ID or name for the given stream and topic by unique IDs or names.
+    /// The member which doesn't send the heartbeats within the session timeout configured on the server is evicted from the consumer group.
+    /// Returns the current generation of the consumer group and the partitions assigned to the member.
+    ///
+    /// Authentication is required, and the permission to read the streams or topics.
+    async fn heartbeat_consumer_group(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        group_id: &Identifier,
+    ) -> Result<ConsumerGroupMembership, IggyError>;
+}
+
+/// This trait defines the methods to interact with the transaction module.
+#[async_trait]
+pub trait TransactionClient {
+    /// Begin the transaction for the client connection, only a single transaction can be open at a time.
+    /// Once the transaction is open, all the messages sent by the client belong to it, until it's committed or aborted.
+    /// The transaction which isn't completed within the timeout configured on the server is aborted.
+    ///
+    /// Authentication is required.
+    async fn begin_transaction(&self) -> Result<Transaction, IggyError>;
+    /// Commit the open transaction, so that all the messages sent within it become visible at once
+    /// to the consumers polling in the `read_committed` isolation level.
+    ///
+    /// Authentication is required.
+    async fn commit_transaction(&self, transaction_id: u64) -> Result<(), IggyError>;
+    /// Abort the open transaction, so that none of the messages sent within it are visible
+    /// to the consumers polling in the `read_committed` isolation level.
+    ///
+    /// Authentication is required.
+    async fn abort_transaction(&self, transaction_id: u64) -> Result<(), IggyError>;
+}
+
+/// This trait defines the methods to interact with the cluster module.
+#[async_trait]
+pub trait ClusterClient {
+    /// Get the metadata of the cluster, such as its members and the current leader, as seen by the server.
+    ///
+    /// Authentication is required, and the permission to read the server info.
+    async fn get_cluster_metadata(&self) -> Result<ClusterMetadata, IggyError>;
+    /// Add a new member to the cluster by its unique ID and the address on which it accepts the cluster connections.
+    /// Only a single membership change can be in progress at a time, and it must be sent to the leader.
+    ///
+    /// Authentication is required, and the permission to manage the servers.
+    async fn add_cluster_node(&self, node_id: u32, address: &str) -> Result<(), IggyError>;
+    /// Remove the member from the cluster by its unique ID.
+    /// Only a single membership change can be in progress at a time, and it must be sent to the leader.
+    ///
+    /// Authentication is required, and the permission to manage the servers.
+    async fn remove_cluster_node(&self, node_id: u32) -> Result<(), IggyError>;
 }
 
 impl FromStr for ConnectionString {
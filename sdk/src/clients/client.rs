@@ -1,22 +1,29 @@
 use crate::client::{
-    Client, ConsumerGroupClient, ConsumerOffsetClient, MessageClient, PartitionClient,
-    PersonalAccessTokenClient, StreamClient, SystemClient, TopicClient, UserClient,
+    Client, ClusterClient, ConsumerGroupClient, ConsumerOffsetClient, MessageClient,
+    PartitionClient, PersonalAccessTokenClient, StreamClient, SystemClient, TopicClient,
+    TransactionClient, UserClient,
 };
 use crate::consumer::Consumer;
+use crate::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use crate::error::IggyError;
 use crate::identifier::Identifier;
 use crate::locking::IggySharedMut;
 use crate::locking::IggySharedMutFn;
-use crate::messages::send_messages::{Message, Partitioning};
+use crate::messages::send_messages::{Acks, Message, Partitioning};
+use crate::models::cleanup_policy::CleanupPolicy;
 use crate::models::client_info::{ClientInfo, ClientInfoDetails};
-use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails};
+use crate::models::cluster::ClusterMetadata;
+use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails, ConsumerGroupMembership};
 use crate::models::consumer_offset_info::ConsumerOffsetInfo;
+use crate::models::dead_letter_queue::DeadLetterQueue;
 use crate::models::identity_info::IdentityInfo;
 use crate::models::messages::PolledMessages;
 use crate::models::personal_access_token::{PersonalAccessTokenInfo, RawPersonalAccessToken};
+use crate::models::producer::Producer;
 use crate::models::stats::Stats;
 use crate::models::stream::{Stream, StreamDetails};
 use crate::models::topic::{Topic, TopicDetails};
+use crate::models::transaction::Transaction;
 use crate::models::user_info::{UserInfo, UserInfoDetails};
 use crate::partitioner::Partitioner;
 use crate::tcp::client::TcpClient;
@@ -36,6 +43,7 @@ use crate::clients::consumer::IggyConsumerBuilder;
 use crate::clients::producer::IggyProducerBuilder;
 use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::diagnostic::DiagnosticEvent;
+use crate::messages::message_filter::MessageFilter;
 use crate::messages::poll_messages::PollingStrategy;
 use crate::models::permissions::Permissions;
 use crate::models::user_status::UserStatus;
@@ -345,6 +353,10 @@ impl SystemClient for IggyClient {
         self.client.read().await.ping().await
     }
 
+    async fn reload_config(&self) -> Result<(), IggyError> {
+        self.client.read().await.reload_config().await
+    }
+
     async fn heartbeat_interval(&self) -> IggyDuration {
         self.client.read().await.heartbeat_interval().await
     }
@@ -482,6 +494,45 @@ impl TopicClient for IggyClient {
             .purge_topic(stream_id, topic_id)
             .await
     }
+
+    async fn set_dead_letter_queue(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        dead_letter_queue: Option<DeadLetterQueue>,
+    ) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .set_dead_letter_queue(stream_id, topic_id, dead_letter_queue)
+            .await
+    }
+
+    async fn set_cleanup_policy(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        cleanup_policy: CleanupPolicy,
+    ) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .set_cleanup_policy(stream_id, topic_id, cleanup_policy)
+            .await
+    }
+
+    async fn set_max_topic_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        max_messages: u64,
+    ) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .set_max_topic_messages(stream_id, topic_id, max_messages)
+            .await
+    }
 }
 
 #[async_trait]
@@ -555,12 +606,106 @@ impl MessageClient for IggyClient {
         Ok(polled_messages)
     }
 
+    async fn poll_messages_with_filter(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        consumer: &Consumer,
+        strategy: &PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+        filter: &MessageFilter,
+    ) -> Result<PolledMessages, IggyError> {
+        if count == 0 {
+            return Err(IggyError::InvalidMessagesCount);
+        }
+
+        let mut polled_messages = self
+            .client
+            .read()
+            .await
+            .poll_messages_with_filter(
+                stream_id,
+                topic_id,
+                partition_id,
+                consumer,
+                strategy,
+                count,
+                auto_commit,
+                filter,
+            )
+            .await?;
+
+        if let Some(ref encryptor) = self.encryptor {
+            for message in &mut polled_messages.messages {
+                let payload = encryptor.decrypt(&message.payload)?;
+                message.payload = Bytes::from(payload);
+                message.length = message.payload.len() as u32;
+            }
+        }
+
+        Ok(polled_messages)
+    }
+
+    async fn poll_committed_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        consumer: &Consumer,
+        strategy: &PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+    ) -> Result<PolledMessages, IggyError> {
+        if count == 0 {
+            return Err(IggyError::InvalidMessagesCount);
+        }
+
+        let mut polled_messages = self
+            .client
+            .read()
+            .await
+            .poll_committed_messages(
+                stream_id,
+                topic_id,
+                partition_id,
+                consumer,
+                strategy,
+                count,
+                auto_commit,
+            )
+            .await?;
+
+        if let Some(ref encryptor) = self.encryptor {
+            for message in &mut polled_messages.messages {
+                let payload = encryptor.decrypt(&message.payload)?;
+                message.payload = Bytes::from(payload);
+                message.length = message.payload.len() as u32;
+            }
+        }
+
+        Ok(polled_messages)
+    }
+
     async fn send_messages(
         &self,
         stream_id: &Identifier,
         topic_id: &Identifier,
         partitioning: &Partitioning,
         messages: &mut [Message],
+    ) -> Result<(), IggyError> {
+        self.send_messages_with_acks(stream_id, topic_id, partitioning, Acks::Leader, messages)
+            .await
+    }
+
+    async fn send_messages_with_acks(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        acks: Acks,
+        messages: &mut [Message],
     ) -> Result<(), IggyError> {
         if messages.is_empty() {
             return Err(IggyError::InvalidMessagesCount);
@@ -576,7 +721,39 @@ impl MessageClient for IggyClient {
         self.client
             .read()
             .await
-            .send_messages(stream_id, topic_id, partitioning, messages)
+            .send_messages_with_acks(stream_id, topic_id, partitioning, acks, messages)
+            .await
+    }
+
+    async fn send_compressed_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        compression_algorithm: CompressionAlgorithm,
+        messages: &mut [Message],
+    ) -> Result<(), IggyError> {
+        if messages.is_empty() {
+            return Err(IggyError::InvalidMessagesCount);
+        }
+
+        if let Some(encryptor) = &self.encryptor {
+            for message in &mut *messages {
+                message.payload = Bytes::from(encryptor.encrypt(&message.payload)?);
+                message.length = message.payload.len() as u32;
+            }
+        }
+
+        self.client
+            .read()
+            .await
+            .send_compressed_messages(
+                stream_id,
+                topic_id,
+                partitioning,
+                compression_algorithm,
+                messages,
+            )
             .await
     }
 
@@ -593,6 +770,41 @@ impl MessageClient for IggyClient {
             .flush_unsaved_buffer(stream_id, topic_id, partition_id, fsync)
             .await
     }
+
+    async fn nack_message(
+        &self,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+        reason: &str,
+    ) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .nack_message(consumer, stream_id, topic_id, partition_id, offset, reason)
+            .await
+    }
+
+    async fn ack_message(
+        &self,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+    ) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .ack_message(consumer, stream_id, topic_id, partition_id, offset)
+            .await
+    }
+
+    async fn init_producer(&self) -> Result<Producer, IggyError> {
+        self.client.read().await.init_producer().await
+    }
 }
 
 #[async_trait]
@@ -660,11 +872,12 @@ impl ConsumerGroupClient for IggyClient {
         topic_id: &Identifier,
         name: &str,
         group_id: Option<u32>,
+        mode: ConsumerGroupMode,
     ) -> Result<ConsumerGroupDetails, IggyError> {
         self.client
             .read()
             .await
-            .create_consumer_group(stream_id, topic_id, name, group_id)
+            .create_consumer_group(stream_id, topic_id, name, group_id, mode)
             .await
     }
 
@@ -686,11 +899,12 @@ impl ConsumerGroupClient for IggyClient {
         stream_id: &Identifier,
         topic_id: &Identifier,
         group_id: &Identifier,
-    ) -> Result<(), IggyError> {
+        member_name: Option<&str>,
+    ) -> Result<ConsumerGroupMembership, IggyError> {
         self.client
             .read()
             .await
-            .join_consumer_group(stream_id, topic_id, group_id)
+            .join_consumer_group(stream_id, topic_id, group_id, member_name)
             .await
     }
 
@@ -706,6 +920,61 @@ impl ConsumerGroupClient for IggyClient {
             .leave_consumer_group(stream_id, topic_id, group_id)
             .await
     }
+
+    async fn heartbeat_consumer_group(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        group_id: &Identifier,
+    ) -> Result<ConsumerGroupMembership, IggyError> {
+        self.client
+            .read()
+            .await
+            .heartbeat_consumer_group(stream_id, topic_id, group_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl TransactionClient for IggyClient {
+    async fn begin_transaction(&self) -> Result<Transaction, IggyError> {
+        self.client.read().await.begin_transaction().await
+    }
+
+    async fn commit_transaction(&self, transaction_id: u64) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .commit_transaction(transaction_id)
+            .await
+    }
+
+    async fn abort_transaction(&self, transaction_id: u64) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .abort_transaction(transaction_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl ClusterClient for IggyClient {
+    async fn get_cluster_metadata(&self) -> Result<ClusterMetadata, IggyError> {
+        self.client.read().await.get_cluster_metadata().await
+    }
+
+    async fn add_cluster_node(&self, node_id: u32, address: &str) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .add_cluster_node(node_id, address)
+            .await
+    }
+
+    async fn remove_cluster_node(&self, node_id: u32) -> Result<(), IggyError> {
+        self.client.read().await.remove_cluster_node(node_id).await
+    }
 }
 
 #[async_trait]
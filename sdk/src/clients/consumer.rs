@@ -1,10 +1,12 @@
 use crate::client::Client;
 use crate::consumer::{Consumer, ConsumerKind};
+use crate::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use crate::diagnostic::DiagnosticEvent;
-use crate::error::IggyError;
+use crate::error::{IggyError, IggyErrorDiscriminants};
 use crate::identifier::{IdKind, Identifier};
 use crate::locking::{IggySharedMut, IggySharedMutFn};
 use crate::messages::poll_messages::{PollingKind, PollingStrategy};
+use crate::models::consumer_group::ConsumerGroupMembership;
 use crate::models::messages::{PolledMessage, PolledMessages};
 use crate::utils::crypto::Encryptor;
 use crate::utils::duration::IggyDuration;
@@ -14,12 +16,14 @@ use dashmap::DashMap;
 use futures::Stream;
 use futures_util::{FutureExt, StreamExt};
 use std::collections::VecDeque;
+use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64};
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
+use tokio::sync::Mutex;
 use tokio::time::sleep;
 use tracing::{error, info, trace, warn};
 
@@ -54,6 +58,17 @@ pub enum AutoCommitWhen {
     ConsumingEveryNthMessage(u32),
 }
 
+/// The listener notified about the partitions revoked from and assigned to the consumer group member,
+/// whenever the consumer group is rebalanced, e.g. when another member joins or leaves the group.
+#[async_trait::async_trait]
+pub trait RebalanceListener: Send + Sync + Debug {
+    /// Invoked when the partitions have been revoked from the member in the given generation of the consumer group.
+    /// If the auto-commit is enabled, the last consumed offsets of these partitions are already stored at this point.
+    async fn on_partitions_revoked(&self, generation: u32, partitions: &[u32]);
+    /// Invoked when the partitions have been assigned to the member in the given generation of the consumer group.
+    async fn on_partitions_assigned(&self, generation: u32, partitions: &[u32]);
+}
+
 unsafe impl Send for IggyConsumer {}
 unsafe impl Sync for IggyConsumer {}
 
@@ -88,6 +103,11 @@ pub struct IggyConsumer {
     last_polled_at: Arc<AtomicU64>,
     current_partition_id: Arc<AtomicU32>,
     retry_interval: IggyDuration,
+    membership: Arc<Mutex<ConsumerGroupMembership>>,
+    rebalance_listener: Option<Arc<dyn RebalanceListener>>,
+    rebalance_check_interval: IggyDuration,
+    consumer_group_heartbeat_interval: Option<IggyDuration>,
+    consumer_group_member_name: Option<String>,
 }
 
 impl IggyConsumer {
@@ -107,6 +127,10 @@ impl IggyConsumer {
         create_consumer_group_if_not_exists: bool,
         encryptor: Option<Arc<dyn Encryptor>>,
         retry_interval: IggyDuration,
+        rebalance_listener: Option<Arc<dyn RebalanceListener>>,
+        rebalance_check_interval: IggyDuration,
+        consumer_group_heartbeat_interval: Option<IggyDuration>,
+        consumer_group_member_name: Option<String>,
     ) -> Self {
         let (store_offset_sender, _) = flume::unbounded();
         Self {
@@ -158,6 +182,11 @@ impl IggyConsumer {
             last_polled_at: Arc::new(AtomicU64::new(0)),
             current_partition_id: Arc::new(AtomicU32::new(0)),
             retry_interval,
+            membership: Arc::new(Mutex::new(ConsumerGroupMembership::default())),
+            rebalance_listener,
+            rebalance_check_interval,
+            consumer_group_heartbeat_interval,
+            consumer_group_member_name,
         }
     }
 
@@ -181,6 +210,16 @@ impl IggyConsumer {
         self.current_partition_id.load(ORDERING)
     }
 
+    /// Returns the generation of the consumer group and the partitions assigned to the consumer, if it has joined the consumer group.
+    pub async fn membership(&self) -> Option<(u32, Vec<u32>)> {
+        if !self.joined_consumer_group.load(ORDERING) {
+            return None;
+        }
+
+        let membership = self.membership.lock().await;
+        Some((membership.generation, membership.partitions.clone()))
+    }
+
     /// Stores the consumer offset on the server either for the current partition or the provided partition ID.
     pub async fn store_offset(
         &self,
@@ -204,6 +243,55 @@ impl IggyConsumer {
         .await
     }
 
+    /// Negatively acknowledges the message with the given offset in the current partition, e.g. when it couldn't be processed.
+    /// Once the message is nacked the number of times configured for the dead letter queue of the topic,
+    /// it's moved by the server to the dead letter queue topic.
+    pub async fn nack(&self, offset: u64, reason: &str) -> Result<(), IggyError> {
+        let partition_id = self.current_partition_id.load(ORDERING);
+        trace!("Nacking message with offset: {offset} for consumer: {}, partition ID: {partition_id}, topic: {}, stream: {}...", self.consumer, self.topic_id, self.stream_id);
+        let client = self.client.read().await;
+        if let Err(error) = client
+            .nack_message(
+                &self.consumer,
+                &self.stream_id,
+                &self.topic_id,
+                Some(partition_id),
+                offset,
+                reason,
+            )
+            .await
+        {
+            error!("Failed to nack message with offset: {offset} for consumer: {}, partition ID: {partition_id}, topic: {}, stream: {}. {error}", self.consumer, self.topic_id, self.stream_id);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Acknowledges the message with the given offset in the current partition.
+    /// Applicable only to the consumer group in queue mode, in which the messages are leased to the members
+    /// and redelivered once the visibility timeout expires, unless they are acknowledged.
+    pub async fn ack(&self, offset: u64) -> Result<(), IggyError> {
+        let partition_id = self.current_partition_id.load(ORDERING);
+        trace!("Acking message with offset: {offset} for consumer: {}, partition ID: {partition_id}, topic: {}, stream: {}...", self.consumer, self.topic_id, self.stream_id);
+        let client = self.client.read().await;
+        if let Err(error) = client
+            .ack_message(
+                &self.consumer,
+                &self.stream_id,
+                &self.topic_id,
+                Some(partition_id),
+                offset,
+            )
+            .await
+        {
+            error!("Failed to ack message with offset: {offset} for consumer: {}, partition ID: {partition_id}, topic: {}, stream: {}. {error}", self.consumer, self.topic_id, self.stream_id);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
     /// Initializes the consumer by subscribing to diagnostic events, initializing the consumer group if needed, storing the offsets in the background etc.
     pub async fn init(&mut self) -> Result<(), IggyError> {
         if self.initialized {
@@ -239,6 +327,17 @@ impl IggyConsumer {
             _ => {}
         }
 
+        if self.is_consumer_group && self.rebalance_listener.is_some() {
+            self.detect_rebalances_in_background();
+        }
+
+        if let (true, Some(interval)) = (
+            self.is_consumer_group,
+            self.consumer_group_heartbeat_interval,
+        ) {
+            self.send_consumer_group_heartbeats_in_background(interval);
+        }
+
         let client = self.client.clone();
         let consumer = self.consumer.clone();
         let stream_id = self.stream_id.clone();
@@ -334,6 +433,97 @@ impl IggyConsumer {
         });
     }
 
+    fn detect_rebalances_in_background(&self) {
+        let interval = self.rebalance_check_interval;
+        let joined_consumer_group = self.joined_consumer_group.clone();
+        let rebalancer = self.rebalancer();
+        tokio::spawn(async move {
+            loop {
+                sleep(interval.get_duration()).await;
+                if !joined_consumer_group.load(ORDERING) {
+                    continue;
+                }
+
+                if let Err(error) = rebalancer.detect_rebalance().await {
+                    error!("Failed to detect the rebalance of consumer group: {} for topic: {}, stream: {}. {error}", rebalancer.consumer.id, rebalancer.topic_id, rebalancer.stream_id);
+                }
+            }
+        });
+    }
+
+    /// Keeps the consumer group member session alive on the server, so that the member is not evicted from the group.
+    /// Once the member has been evicted anyway (e.g. due to the long pause), it rejoins the consumer group.
+    fn send_consumer_group_heartbeats_in_background(&self, interval: IggyDuration) {
+        let client = self.client.clone();
+        let create_consumer_group_if_not_exists = self.create_consumer_group_if_not_exists;
+        let stream_id = self.stream_id.clone();
+        let topic_id = self.topic_id.clone();
+        let consumer = self.consumer.clone();
+        let consumer_name = self.consumer_name.clone();
+        let member_name = self.consumer_group_member_name.clone();
+        let joined_consumer_group = self.joined_consumer_group.clone();
+        let rebalancer = self.rebalancer();
+        tokio::spawn(async move {
+            let consumer_group_id = consumer.id.clone();
+            loop {
+                sleep(interval.get_duration()).await;
+                if !joined_consumer_group.load(ORDERING) {
+                    continue;
+                }
+
+                let error = match rebalancer.heartbeat().await {
+                    Ok(()) => continue,
+                    Err(error) => error,
+                };
+
+                if let IggyError::FeatureUnavailable = error {
+                    warn!("Consumer group heartbeats are not supported by the client, they will not be sent anymore.");
+                    break;
+                }
+
+                if !is_member_not_found_error(&error) {
+                    error!("Failed to send the heartbeat of consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}. {error}");
+                    continue;
+                }
+
+                warn!("Consumer: {consumer_name} has been evicted from consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}, rejoining...");
+                joined_consumer_group.store(false, ORDERING);
+                match Self::initialize_consumer_group(
+                    client.clone(),
+                    create_consumer_group_if_not_exists,
+                    stream_id.clone(),
+                    topic_id.clone(),
+                    consumer.clone(),
+                    &consumer_name,
+                    member_name.as_deref(),
+                    joined_consumer_group.clone(),
+                )
+                .await
+                {
+                    Ok(Some(membership)) => rebalancer.apply_membership(membership).await,
+                    Ok(None) => {}
+                    Err(error) => {
+                        error!("Failed to rejoin consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}. {error}");
+                    }
+                }
+            }
+        });
+    }
+
+    fn rebalancer(&self) -> ConsumerGroupRebalancer {
+        ConsumerGroupRebalancer {
+            client: self.client.clone(),
+            consumer: self.consumer.clone(),
+            stream_id: self.stream_id.clone(),
+            topic_id: self.topic_id.clone(),
+            auto_commit_enabled: self.auto_commit != AutoCommit::Disabled,
+            last_consumed_offsets: self.last_consumed_offsets.clone(),
+            last_stored_offsets: self.last_stored_offsets.clone(),
+            membership: self.membership.clone(),
+            listener: self.rebalance_listener.clone(),
+        }
+    }
+
     fn send_store_offset(&self, partition_id: u32, offset: u64) {
         if let Err(error) = self.store_offset_sender.send((partition_id, offset)) {
             error!("Failed to send offset to store: {error}");
@@ -350,16 +540,21 @@ impl IggyConsumer {
             return Ok(());
         }
 
-        Self::initialize_consumer_group(
+        if let Some(membership) = Self::initialize_consumer_group(
             self.client.clone(),
             self.create_consumer_group_if_not_exists,
             self.stream_id.clone(),
             self.topic_id.clone(),
             self.consumer.clone(),
             &self.consumer_name,
+            self.consumer_group_member_name.as_deref(),
             self.joined_consumer_group.clone(),
         )
-        .await
+        .await?
+        {
+            self.rebalancer().apply_membership(membership).await;
+        }
+        Ok(())
     }
 
     async fn subscribe_events(&self) {
@@ -378,8 +573,10 @@ impl IggyConsumer {
         let topic_id = self.topic_id.clone();
         let consumer = self.consumer.clone();
         let consumer_name = self.consumer_name.clone();
+        let member_name = self.consumer_group_member_name.clone();
         let can_poll = self.can_poll.clone();
         let joined_consumer_group = self.joined_consumer_group.clone();
+        let rebalancer = self.rebalancer();
         let mut reconnected = false;
         let mut disconnected = false;
 
@@ -432,19 +629,24 @@ impl IggyConsumer {
                         }
 
                         info!("Rejoining consumer group: {consumer_name} for stream: {stream_id}, topic: {topic_id}...");
-                        if let Err(error) = Self::initialize_consumer_group(
+                        match Self::initialize_consumer_group(
                             client.clone(),
                             create_consumer_group_if_not_exists,
                             stream_id.clone(),
                             topic_id.clone(),
                             consumer.clone(),
                             &consumer_name,
+                            member_name.as_deref(),
                             joined_consumer_group.clone(),
                         )
                         .await
                         {
-                            error!("Failed to join consumer group: {consumer_name} for stream: {stream_id}, topic: {topic_id}. {error}");
-                            continue;
+                            Ok(Some(membership)) => rebalancer.apply_membership(membership).await,
+                            Ok(None) => {}
+                            Err(error) => {
+                                error!("Failed to join consumer group: {consumer_name} for stream: {stream_id}, topic: {topic_id}. {error}");
+                                continue;
+                            }
                         }
                         info!("Rejoined consumer group: {consumer_name} for stream: {stream_id}, topic: {topic_id}");
                         can_poll.store(true, ORDERING);
@@ -613,6 +815,7 @@ impl IggyConsumer {
         sleep(Duration::from_micros(remaining)).await;
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn initialize_consumer_group(
         client: IggySharedMut<Box<dyn Client>>,
         create_consumer_group_if_not_exists: bool,
@@ -620,10 +823,11 @@ impl IggyConsumer {
         topic_id: Arc<Identifier>,
         consumer: Arc<Consumer>,
         consumer_name: &str,
+        member_name: Option<&str>,
         joined_consumer_group: Arc<AtomicBool>,
-    ) -> Result<(), IggyError> {
+    ) -> Result<Option<ConsumerGroupMembership>, IggyError> {
         if joined_consumer_group.load(ORDERING) {
-            return Ok(());
+            return Ok(None);
         }
 
         let client = client.read().await;
@@ -649,26 +853,162 @@ impl IggyConsumer {
 
             info!("Creating consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}");
             client
-                .create_consumer_group(&stream_id, &topic_id, &name, id)
+                .create_consumer_group(&stream_id, &topic_id, &name, id, ConsumerGroupMode::Offset)
                 .await?;
         }
 
         info!("Joining consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}",);
-        if let Err(error) = client
-            .join_consumer_group(&stream_id, &topic_id, &consumer_group_id)
+        let membership = match client
+            .join_consumer_group(&stream_id, &topic_id, &consumer_group_id, member_name)
             .await
         {
-            joined_consumer_group.store(false, ORDERING);
-            error!("Failed to join consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}: {error}");
-            return Err(error);
-        }
+            Ok(membership) => membership,
+            Err(error) => {
+                joined_consumer_group.store(false, ORDERING);
+                error!("Failed to join consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}: {error}");
+                return Err(error);
+            }
+        };
 
         joined_consumer_group.store(true, ORDERING);
         info!(
-            "Joined consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}"
+            "Joined consumer group: {consumer_group_id} for topic: {topic_id}, stream: {stream_id}, generation: {}, partitions: {:?}",
+            membership.generation, membership.partitions
         );
+        Ok(Some(membership))
+    }
+}
+
+/// Keeps track of the partitions assigned to the consumer group member. Once they change, it stores
+/// the last consumed offsets of the revoked partitions (if the auto-commit is enabled) and notifies the listener.
+#[derive(Clone)]
+struct ConsumerGroupRebalancer {
+    client: IggySharedMut<Box<dyn Client>>,
+    consumer: Arc<Consumer>,
+    stream_id: Arc<Identifier>,
+    topic_id: Arc<Identifier>,
+    auto_commit_enabled: bool,
+    last_consumed_offsets: Arc<DashMap<u32, AtomicU64>>,
+    last_stored_offsets: Arc<DashMap<u32, AtomicU64>>,
+    membership: Arc<Mutex<ConsumerGroupMembership>>,
+    listener: Option<Arc<dyn RebalanceListener>>,
+}
+
+impl ConsumerGroupRebalancer {
+    async fn detect_rebalance(&self) -> Result<(), IggyError> {
+        let consumer_group = self
+            .client
+            .read()
+            .await
+            .get_consumer_group(&self.stream_id, &self.topic_id, &self.consumer.id)
+            .await?;
+        let Some(consumer_group) = consumer_group else {
+            return Ok(());
+        };
+
+        let (member_id, generation) = {
+            let membership = self.membership.lock().await;
+            (membership.member_id, membership.generation)
+        };
+        if consumer_group.generation == generation {
+            return Ok(());
+        }
+
+        let partitions = consumer_group
+            .members
+            .into_iter()
+            .find(|member| member.id == member_id)
+            .map(|member| member.partitions)
+            .unwrap_or_default();
+        self.apply_membership(ConsumerGroupMembership {
+            member_id,
+            generation: consumer_group.generation,
+            partitions,
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn heartbeat(&self) -> Result<(), IggyError> {
+        let membership = self
+            .client
+            .read()
+            .await
+            .heartbeat_consumer_group(&self.stream_id, &self.topic_id, &self.consumer.id)
+            .await?;
+        if membership.generation == self.membership.lock().await.generation {
+            return Ok(());
+        }
+
+        self.apply_membership(membership).await;
         Ok(())
     }
+
+    async fn apply_membership(&self, mut membership: ConsumerGroupMembership) {
+        membership.partitions.sort();
+        let mut current_membership = self.membership.lock().await;
+        let revoked_partitions = current_membership
+            .partitions
+            .iter()
+            .filter(|partition_id| !membership.partitions.contains(partition_id))
+            .copied()
+            .collect::<Vec<_>>();
+        let assigned_partitions = membership
+            .partitions
+            .iter()
+            .filter(|partition_id| !current_membership.partitions.contains(partition_id))
+            .copied()
+            .collect::<Vec<_>>();
+        let generation = membership.generation;
+        *current_membership = membership;
+        trace!("Consumer group: {} for topic: {}, stream: {} has been rebalanced, generation: {generation}, revoked partitions: {revoked_partitions:?}, assigned partitions: {assigned_partitions:?}", self.consumer.id, self.topic_id, self.stream_id);
+
+        if !revoked_partitions.is_empty() {
+            for partition_id in &revoked_partitions {
+                let consumed_offset = self
+                    .last_consumed_offsets
+                    .remove(partition_id)
+                    .map(|(_, offset)| offset.load(ORDERING));
+                if let (true, Some(consumed_offset)) = (self.auto_commit_enabled, consumed_offset) {
+                    _ = IggyConsumer::store_consumer_offset(
+                        &self.client,
+                        &self.consumer,
+                        &self.stream_id,
+                        &self.topic_id,
+                        *partition_id,
+                        consumed_offset,
+                        &self.last_stored_offsets,
+                    )
+                    .await;
+                }
+                self.last_stored_offsets.remove(partition_id);
+            }
+
+            if let Some(listener) = &self.listener {
+                listener
+                    .on_partitions_revoked(generation, &revoked_partitions)
+                    .await;
+            }
+        }
+
+        if !assigned_partitions.is_empty() {
+            if let Some(listener) = &self.listener {
+                listener
+                    .on_partitions_assigned(generation, &assigned_partitions)
+                    .await;
+            }
+        }
+    }
+}
+
+fn is_member_not_found_error(error: &IggyError) -> bool {
+    match error {
+        IggyError::ConsumerGroupMemberNotFound(_, _, _) => true,
+        IggyError::InvalidResponse(status, _, _) => {
+            *status == IggyErrorDiscriminants::ConsumerGroupMemberNotFound as u32
+        }
+        _ => false,
+    }
 }
 
 pub struct ReceivedMessage {
@@ -835,6 +1175,10 @@ pub struct IggyConsumerBuilder {
     create_consumer_group_if_not_exists: bool,
     encryptor: Option<Arc<dyn Encryptor>>,
     retry_interval: IggyDuration,
+    rebalance_listener: Option<Arc<dyn RebalanceListener>>,
+    rebalance_check_interval: IggyDuration,
+    consumer_group_heartbeat_interval: Option<IggyDuration>,
+    consumer_group_member_name: Option<String>,
 }
 
 impl IggyConsumerBuilder {
@@ -867,6 +1211,10 @@ impl IggyConsumerBuilder {
             encryptor,
             polling_interval,
             retry_interval: IggyDuration::ONE_SECOND,
+            rebalance_listener: None,
+            rebalance_check_interval: IggyDuration::ONE_SECOND,
+            consumer_group_heartbeat_interval: Some(IggyDuration::new(Duration::from_secs(5))),
+            consumer_group_member_name: None,
         }
     }
 
@@ -978,6 +1326,63 @@ impl IggyConsumerBuilder {
         }
     }
 
+    /// Sets the listener notified about the partitions revoked from and assigned to the consumer group member.
+    /// The rebalances are detected by checking the generation of the consumer group on the server in the given interval.
+    pub fn rebalance_listener(
+        self,
+        listener: Arc<dyn RebalanceListener>,
+        check_interval: IggyDuration,
+    ) -> Self {
+        Self {
+            rebalance_listener: Some(listener),
+            rebalance_check_interval: check_interval,
+            ..self
+        }
+    }
+
+    /// Clears the listener notified about the partitions revoked from and assigned to the consumer group member.
+    pub fn without_rebalance_listener(self) -> Self {
+        Self {
+            rebalance_listener: None,
+            ..self
+        }
+    }
+
+    /// Sets the interval of the heartbeats keeping the consumer group member session alive on the server.
+    /// It should be lower than the consumer group session timeout configured on the server.
+    pub fn consumer_group_heartbeat_interval(self, interval: IggyDuration) -> Self {
+        Self {
+            consumer_group_heartbeat_interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Does not send the consumer group heartbeats, the member might be evicted from the group if the server verifies them.
+    pub fn without_consumer_group_heartbeat(self) -> Self {
+        Self {
+            consumer_group_heartbeat_interval: None,
+            ..self
+        }
+    }
+
+    /// Sets the stable name of the consumer group member, which makes its membership static.
+    /// Once the consumer reconnects to the server (e.g. after a network failure) within the grace period configured on the server,
+    /// it rejoins the consumer group with the same name and reclaims its previous partitions without a rebalance.
+    pub fn consumer_group_member_name(self, member_name: &str) -> Self {
+        Self {
+            consumer_group_member_name: Some(member_name.to_string()),
+            ..self
+        }
+    }
+
+    /// Clears the name of the consumer group member, so that each reconnection joins the consumer group as a new member.
+    pub fn without_consumer_group_member_name(self) -> Self {
+        Self {
+            consumer_group_member_name: None,
+            ..self
+        }
+    }
+
     pub fn build(self) -> IggyConsumer {
         IggyConsumer::new(
             self.client,
@@ -994,6 +1399,10 @@ impl IggyConsumerBuilder {
             self.create_consumer_group_if_not_exists,
             self.encryptor,
             self.retry_interval,
+            self.rebalance_listener,
+            self.rebalance_check_interval,
+            self.consumer_group_heartbeat_interval,
+            self.consumer_group_member_name,
         )
     }
 }
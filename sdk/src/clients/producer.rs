@@ -1,10 +1,13 @@
 use crate::client::Client;
 use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::diagnostic::DiagnosticEvent;
-use crate::error::IggyError;
+use crate::error::{IggyError, IggyErrorDiscriminants};
 use crate::identifier::{IdKind, Identifier};
 use crate::locking::{IggySharedMut, IggySharedMutFn};
-use crate::messages::send_messages::{Message, Partitioning};
+use crate::messages::send_messages::{Message, Partitioning, PartitioningKind};
+use crate::models::header::{HeaderKey, HeaderValue};
+use crate::models::producer::{PRODUCER_ID_HEADER, PRODUCER_SEQUENCE_HEADER};
+use crate::models::transaction::Transaction;
 use crate::partitioner::Partitioner;
 use crate::utils::crypto::Encryptor;
 use crate::utils::duration::IggyDuration;
@@ -13,11 +16,14 @@ use crate::utils::timestamp::IggyTimestamp;
 use crate::utils::topic_size::MaxTopicSize;
 use bytes::Bytes;
 use futures_util::StreamExt;
-use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Mutex;
 use tokio::time::sleep;
 use tracing::{error, info, trace, warn};
+use xxhash_rust::xxh32::xxh32;
 
 const ORDERING: std::sync::atomic::Ordering = std::sync::atomic::Ordering::SeqCst;
 const MAX_BATCH_SIZE: usize = 1000000;
@@ -36,6 +42,7 @@ pub struct IggyProducer {
     batch_size: Option<usize>,
     partitioning: Option<Arc<Partitioning>>,
     encryptor: Option<Arc<dyn Encryptor>>,
+    compression_algorithm: CompressionAlgorithm,
     partitioner: Option<Arc<dyn Partitioner>>,
     send_interval_micros: u64,
     create_stream_if_not_exists: bool,
@@ -48,8 +55,35 @@ pub struct IggyProducer {
     can_send_immediately: bool,
     last_sent_at: Arc<AtomicU64>,
     retry_interval: IggyDuration,
+    idempotence: bool,
+    idempotent_state: Arc<Mutex<IdempotentState>>,
+    balanced_partition: Arc<AtomicU32>,
 }
 
+/// The state of the idempotent producer, the ID assigned by the server and the last sequence number
+/// acknowledged by each of the partitions, along with the cached partitions count of each topic.
+/// The batch which has failed to be sent might have been appended nevertheless, so its fingerprint is kept
+/// until the next batch is sent to the same partition, and if it's the same batch, it gets the same sequence numbers.
+#[derive(Debug, Default)]
+struct IdempotentState {
+    producer_id: Option<u64>,
+    sequences: HashMap<(Identifier, Identifier, u32), u64>,
+    unacknowledged_batches: HashMap<(Identifier, Identifier, u32), Vec<MessageFingerprint>>,
+    partitions_counts: HashMap<(Identifier, Identifier), u32>,
+}
+
+impl IdempotentState {
+    fn reset(&mut self, producer_id: Option<u64>) {
+        self.producer_id = producer_id;
+        self.sequences.clear();
+        self.unacknowledged_batches.clear();
+    }
+}
+
+/// The message ID along with the checksum of its payload, calculated before the encryption,
+/// which uses the random nonce, so that the retried batch can be recognized.
+type MessageFingerprint = (u128, u32);
+
 impl IggyProducer {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
@@ -61,6 +95,7 @@ impl IggyProducer {
         batch_size: Option<usize>,
         partitioning: Option<Partitioning>,
         encryptor: Option<Arc<dyn Encryptor>>,
+        compression_algorithm: CompressionAlgorithm,
         partitioner: Option<Arc<dyn Partitioner>>,
         interval: Option<IggyDuration>,
         create_stream_if_not_exists: bool,
@@ -70,6 +105,7 @@ impl IggyProducer {
         topic_message_expiry: IggyExpiry,
         topic_max_size: MaxTopicSize,
         retry_interval: IggyDuration,
+        idempotence: bool,
     ) -> Self {
         Self {
             initialized: false,
@@ -82,6 +118,7 @@ impl IggyProducer {
             batch_size,
             partitioning: partitioning.map(Arc::new),
             encryptor,
+            compression_algorithm,
             partitioner,
             send_interval_micros: interval.map_or(0, |i| i.as_micros()),
             create_stream_if_not_exists,
@@ -94,6 +131,9 @@ impl IggyProducer {
             can_send_immediately: interval.is_none(),
             last_sent_at: Arc::new(AtomicU64::new(0)),
             retry_interval,
+            idempotence,
+            idempotent_state: Arc::new(Mutex::new(IdempotentState::default())),
+            balanced_partition: Arc::new(AtomicU32::new(0)),
         }
     }
 
@@ -166,6 +206,13 @@ impl IggyProducer {
                 .await?;
         }
 
+        if self.idempotence {
+            let producer = client.init_producer().await?;
+            info!("Initialized idempotent producer with ID: {}", producer.id);
+            let mut state = self.idempotent_state.lock().await;
+            state.reset(Some(producer.id));
+        }
+
         self.initialized = true;
         Ok(())
     }
@@ -294,6 +341,81 @@ impl IggyProducer {
             .await
     }
 
+    /// Sends all the batches of messages atomically within a single transaction, each batch using its own partitioning,
+    /// or the one configured for the producer. Either all the messages become visible to the consumers polling
+    /// in the `read_committed` isolation level at once, or none of them, as the transaction is aborted on failure.
+    /// The transaction is bound to the client connection, so it shouldn't be shared with other producers meanwhile.
+    pub async fn send_in_transaction(
+        &self,
+        batches: Vec<(Vec<Message>, Option<Arc<Partitioning>>)>,
+    ) -> Result<(), IggyError> {
+        if batches.iter().all(|(messages, _)| messages.is_empty()) {
+            trace!("No messages to send.");
+            return Ok(());
+        }
+
+        if !self.can_send.load(ORDERING) {
+            trace!("Trying to send messages in {}...", self.retry_interval);
+            sleep(self.retry_interval.get_duration()).await;
+        }
+
+        let transaction = self.begin_transaction().await?;
+        for (messages, partitioning) in batches {
+            if messages.is_empty() {
+                continue;
+            }
+
+            if let Err(error) = self
+                .send_immediately(&self.stream_id, &self.topic_id, messages, partitioning)
+                .await
+            {
+                error!(
+                    "Failed to send messages within transaction with ID: {}, aborting it. {error}",
+                    transaction.id
+                );
+                if let Err(abort_error) = self.abort_transaction(transaction).await {
+                    error!(
+                        "Failed to abort transaction with ID: {}. {abort_error}",
+                        transaction.id
+                    );
+                }
+                return Err(error);
+            }
+        }
+
+        self.commit_transaction(transaction).await
+    }
+
+    /// Begins the transaction for the client connection used by the producer,
+    /// all the messages sent by the producer belong to it, until it's committed or aborted.
+    pub async fn begin_transaction(&self) -> Result<Transaction, IggyError> {
+        let transaction = self.client.read().await.begin_transaction().await?;
+        trace!("Began transaction with ID: {}.", transaction.id);
+        Ok(transaction)
+    }
+
+    /// Commits the transaction, so that all the messages sent within it become visible at once.
+    pub async fn commit_transaction(&self, transaction: Transaction) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .commit_transaction(transaction.id)
+            .await?;
+        trace!("Committed transaction with ID: {}.", transaction.id);
+        Ok(())
+    }
+
+    /// Aborts the transaction, so that none of the messages sent within it become visible.
+    pub async fn abort_transaction(&self, transaction: Transaction) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .abort_transaction(transaction.id)
+            .await?;
+        trace!("Aborted transaction with ID: {}.", transaction.id);
+        Ok(())
+    }
+
     async fn send_buffered(
         &self,
         stream: Arc<Identifier>,
@@ -301,13 +423,14 @@ impl IggyProducer {
         mut messages: Vec<Message>,
         partitioning: Option<Arc<Partitioning>>,
     ) -> Result<(), IggyError> {
+        let fingerprints = self.get_fingerprints(&messages);
         self.encrypt_messages(&mut messages)?;
         let partitioning = self.get_partitioning(&stream, &topic, &messages, partitioning)?;
         let batch_size = self.batch_size.unwrap_or(MAX_BATCH_SIZE);
         let batches = messages.chunks_mut(batch_size);
-        let mut current_batch = 1;
         let batches_count = batches.len();
-        for batch in batches {
+        for (index, batch) in batches.enumerate() {
+            let current_batch = index + 1;
             if self.send_interval_micros > 0 {
                 Self::wait_before_sending(
                     self.send_interval_micros,
@@ -322,12 +445,16 @@ impl IggyProducer {
             );
             self.last_sent_at
                 .store(IggyTimestamp::now().into(), ORDERING);
-            let client = self.client.read().await;
-            client
-                .send_messages(&self.stream_id, &self.topic_id, &partitioning, batch)
-                .await?;
+            let fingerprints = Self::get_batch_fingerprints(&fingerprints, index, batch_size);
+            self.send_batch(
+                &self.stream_id,
+                &self.topic_id,
+                &partitioning,
+                batch,
+                fingerprints,
+            )
+            .await?;
             trace!("Sent {messages_count} messages ({current_batch}/{batches_count} batch(es)).");
-            current_batch += 1;
         }
         Ok(())
     }
@@ -340,29 +467,198 @@ impl IggyProducer {
         partitioning: Option<Arc<Partitioning>>,
     ) -> Result<(), IggyError> {
         trace!("No batch size specified, sending messages immediately.");
+        let fingerprints = self.get_fingerprints(&messages);
         self.encrypt_messages(&mut messages)?;
         let partitioning = self.get_partitioning(stream, topic, &messages, partitioning)?;
         let batch_size = self.batch_size.unwrap_or(MAX_BATCH_SIZE);
-        let client = self.client.read().await;
         if messages.len() <= batch_size {
             self.last_sent_at
                 .store(IggyTimestamp::now().into(), ORDERING);
-            client
-                .send_messages(stream, topic, &partitioning, &mut messages)
+            self.send_batch(stream, topic, &partitioning, &mut messages, &fingerprints)
                 .await?;
             return Ok(());
         }
 
-        for batch in messages.chunks_mut(batch_size) {
+        for (index, batch) in messages.chunks_mut(batch_size).enumerate() {
             self.last_sent_at
                 .store(IggyTimestamp::now().into(), ORDERING);
-            client
-                .send_messages(stream, topic, &partitioning, batch)
+            let fingerprints = Self::get_batch_fingerprints(&fingerprints, index, batch_size);
+            self.send_batch(stream, topic, &partitioning, batch, fingerprints)
                 .await?;
         }
         Ok(())
     }
 
+    async fn send_batch(
+        &self,
+        stream: &Identifier,
+        topic: &Identifier,
+        partitioning: &Partitioning,
+        batch: &mut [Message],
+        fingerprints: &[MessageFingerprint],
+    ) -> Result<(), IggyError> {
+        if self.idempotence {
+            return self
+                .send_idempotent_batch(stream, topic, partitioning, batch, fingerprints)
+                .await;
+        }
+
+        self.send_messages(stream, topic, partitioning, batch).await
+    }
+
+    /// Sends the batch to the single partition, stamping each message with the producer ID
+    /// and the next sequence number for this partition, so that the server can discard the duplicates
+    /// if the batch is resent e.g. after the reconnect. The state is locked until the batch is sent,
+    /// as the sequence numbers must reach the partition in order.
+    /// When the batch fails to be sent, the sequence numbers are not advanced, so that the retried batch
+    /// gets the same ones. If the next batch sent to the partition is a different one instead, the failed batch
+    /// might have been appended or not, so the producer starts over with the new ID, as it does on the sequence error.
+    async fn send_idempotent_batch(
+        &self,
+        stream: &Identifier,
+        topic: &Identifier,
+        partitioning: &Partitioning,
+        batch: &mut [Message],
+        fingerprints: &[MessageFingerprint],
+    ) -> Result<(), IggyError> {
+        let mut state = self.idempotent_state.lock().await;
+        let partition_id = self
+            .get_partition_id(&mut state, stream, topic, partitioning)
+            .await?;
+        let sequence_key = (stream.clone(), topic.clone(), partition_id);
+        if let Some(unacknowledged_batch) = state.unacknowledged_batches.remove(&sequence_key) {
+            if unacknowledged_batch == fingerprints {
+                trace!("Retrying the unacknowledged batch for partition with ID: {partition_id} with the same sequence numbers.");
+            } else {
+                warn!("The unacknowledged batch for partition with ID: {partition_id} has not been retried, idempotent producer will be reinitialized.");
+                state.reset(None);
+            }
+        }
+
+        let producer_id = match state.producer_id {
+            Some(producer_id) => producer_id,
+            None => {
+                let producer = self.client.read().await.init_producer().await?;
+                trace!("Initialized idempotent producer with ID: {}", producer.id);
+                state.reset(Some(producer.id));
+                producer.id
+            }
+        };
+
+        let last_sequence = state.sequences.get(&sequence_key).copied().unwrap_or(0);
+        let producer_id_key = HeaderKey::new(PRODUCER_ID_HEADER)?;
+        let producer_sequence_key = HeaderKey::new(PRODUCER_SEQUENCE_HEADER)?;
+        for (index, message) in batch.iter_mut().enumerate() {
+            let headers = message.headers.get_or_insert_with(HashMap::new);
+            headers.insert(
+                producer_id_key.clone(),
+                HeaderValue::from_uint64(producer_id)?,
+            );
+            headers.insert(
+                producer_sequence_key.clone(),
+                HeaderValue::from_uint64(last_sequence + 1 + index as u64)?,
+            );
+        }
+
+        let partitioning = Partitioning::partition_id(partition_id);
+        if let Err(error) = self
+            .send_messages(stream, topic, &partitioning, batch)
+            .await
+        {
+            if is_producer_sequence_error(&error) {
+                warn!("Failed to send messages by idempotent producer with ID: {producer_id}, it will be reinitialized. {error}");
+                state.reset(None);
+            } else {
+                warn!("Failed to send messages by idempotent producer with ID: {producer_id}, the same batch can be retried. {error}");
+                state
+                    .unacknowledged_batches
+                    .insert(sequence_key, fingerprints.to_vec());
+            }
+            return Err(error);
+        }
+
+        state
+            .sequences
+            .insert(sequence_key, last_sequence + batch.len() as u64);
+        Ok(())
+    }
+
+    /// Resolves the partition ID on the client side, as the sequence numbers are tracked per partition.
+    async fn get_partition_id(
+        &self,
+        state: &mut IdempotentState,
+        stream: &Identifier,
+        topic: &Identifier,
+        partitioning: &Partitioning,
+    ) -> Result<u32, IggyError> {
+        if partitioning.kind == PartitioningKind::PartitionId {
+            let value = partitioning.value.as_slice().try_into();
+            return value
+                .map(u32::from_le_bytes)
+                .map_err(|_| IggyError::InvalidCommand);
+        }
+
+        let topic_key = (stream.clone(), topic.clone());
+        let partitions_count = match state.partitions_counts.get(&topic_key) {
+            Some(partitions_count) => *partitions_count,
+            None => {
+                let Some(topic_details) = self.client.read().await.get_topic(stream, topic).await?
+                else {
+                    return Err(IggyError::TopicNameNotFound(
+                        topic.to_string(),
+                        stream.to_string(),
+                    ));
+                };
+                if topic_details.partitions_count == 0 {
+                    return Err(IggyError::NoPartitions(
+                        topic_details.id,
+                        stream.get_u32_value().unwrap_or_default(),
+                    ));
+                }
+
+                state
+                    .partitions_counts
+                    .insert(topic_key, topic_details.partitions_count);
+                topic_details.partitions_count
+            }
+        };
+
+        let partition_id = match partitioning.kind {
+            PartitioningKind::MessagesKey => xxh32(&partitioning.value, 0) % partitions_count,
+            _ => self.balanced_partition.fetch_add(1, ORDERING) % partitions_count + 1,
+        };
+        if partition_id == 0 {
+            return Ok(partitions_count);
+        }
+
+        Ok(partition_id)
+    }
+
+    async fn send_messages(
+        &self,
+        stream: &Identifier,
+        topic: &Identifier,
+        partitioning: &Partitioning,
+        batch: &mut [Message],
+    ) -> Result<(), IggyError> {
+        let client = self.client.read().await;
+        if self.compression_algorithm.is_none() {
+            return client
+                .send_messages(stream, topic, partitioning, batch)
+                .await;
+        }
+
+        client
+            .send_compressed_messages(
+                stream,
+                topic,
+                partitioning,
+                self.compression_algorithm,
+                batch,
+            )
+            .await
+    }
+
     async fn wait_before_sending(interval: u64, last_sent_at: u64) {
         if interval == 0 {
             return;
@@ -380,6 +676,28 @@ impl IggyProducer {
         sleep(Duration::from_micros(remaining)).await;
     }
 
+    /// Returns the fingerprints of the messages, which identify the retried batch of the idempotent producer.
+    fn get_fingerprints(&self, messages: &[Message]) -> Vec<MessageFingerprint> {
+        if !self.idempotence {
+            return Vec::new();
+        }
+
+        messages
+            .iter()
+            .map(|message| (message.id, xxh32(&message.payload, 0)))
+            .collect()
+    }
+
+    fn get_batch_fingerprints(
+        fingerprints: &[MessageFingerprint],
+        batch_index: usize,
+        batch_size: usize,
+    ) -> &[MessageFingerprint] {
+        let start = (batch_index * batch_size).min(fingerprints.len());
+        let end = (start + batch_size).min(fingerprints.len());
+        &fingerprints[start..end]
+    }
+
     fn encrypt_messages(&self, messages: &mut [Message]) -> Result<(), IggyError> {
         if let Some(encryptor) = &self.encryptor {
             for message in messages {
@@ -412,6 +730,16 @@ impl IggyProducer {
     }
 }
 
+fn is_producer_sequence_error(error: &IggyError) -> bool {
+    match error {
+        IggyError::InvalidProducerSequence(_, _, _) => true,
+        IggyError::InvalidResponse(status, _, _) => {
+            *status == IggyErrorDiscriminants::InvalidProducerSequence as u32
+        }
+        _ => false,
+    }
+}
+
 #[derive(Debug)]
 pub struct IggyProducerBuilder {
     client: IggySharedMut<Box<dyn Client>>,
@@ -422,6 +750,7 @@ pub struct IggyProducerBuilder {
     batch_size: Option<usize>,
     partitioning: Option<Partitioning>,
     encryptor: Option<Arc<dyn Encryptor>>,
+    compression_algorithm: CompressionAlgorithm,
     partitioner: Option<Arc<dyn Partitioner>>,
     send_interval: Option<IggyDuration>,
     create_stream_if_not_exists: bool,
@@ -431,6 +760,7 @@ pub struct IggyProducerBuilder {
     retry_interval: IggyDuration,
     pub topic_message_expiry: IggyExpiry,
     pub topic_max_size: MaxTopicSize,
+    idempotence: bool,
 }
 
 impl IggyProducerBuilder {
@@ -453,6 +783,7 @@ impl IggyProducerBuilder {
             batch_size: Some(1000),
             partitioning: None,
             encryptor,
+            compression_algorithm: CompressionAlgorithm::None,
             partitioner,
             send_interval: Some(IggyDuration::from(1000)),
             create_stream_if_not_exists: true,
@@ -462,6 +793,7 @@ impl IggyProducerBuilder {
             retry_interval: IggyDuration::ONE_SECOND,
             topic_message_expiry: IggyExpiry::ServerDefault,
             topic_max_size: MaxTopicSize::ServerDefault,
+            idempotence: false,
         }
     }
 
@@ -527,6 +859,22 @@ impl IggyProducerBuilder {
         }
     }
 
+    /// Sets the algorithm for compressing each batch of messages on the client side before sending it.
+    pub fn compression(self, compression_algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            compression_algorithm,
+            ..self
+        }
+    }
+
+    /// Clears the compression, the messages are sent as they are.
+    pub fn without_compression(self) -> Self {
+        Self {
+            compression_algorithm: CompressionAlgorithm::None,
+            ..self
+        }
+    }
+
     /// Sets the partitioning strategy for messages.
     pub fn partitioning(self, partitioning: Partitioning) -> Self {
         Self {
@@ -609,6 +957,23 @@ impl IggyProducerBuilder {
         }
     }
 
+    /// Enables the idempotence - the server assigns the producer ID and discards the messages with already seen
+    /// sequence numbers, so the retried batches are never duplicated. Requires the idempotence enabled on the server.
+    pub fn idempotence(self) -> Self {
+        Self {
+            idempotence: true,
+            ..self
+        }
+    }
+
+    /// Disables the idempotence.
+    pub fn without_idempotence(self) -> Self {
+        Self {
+            idempotence: false,
+            ..self
+        }
+    }
+
     pub fn build(self) -> IggyProducer {
         IggyProducer::new(
             self.client,
@@ -619,6 +984,7 @@ impl IggyProducerBuilder {
             self.batch_size,
             self.partitioning,
             self.encryptor,
+            self.compression_algorithm,
             self.partitioner,
             self.send_interval,
             self.create_stream_if_not_exists,
@@ -628,6 +994,7 @@ impl IggyProducerBuilder {
             self.topic_message_expiry,
             self.topic_max_size,
             self.retry_interval,
+            self.idempotence,
         )
     }
 }
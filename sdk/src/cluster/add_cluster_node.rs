@@ -0,0 +1,166 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::cluster::MAX_ADDRESS_LENGTH;
+use crate::command::{Command, ADD_CLUSTER_NODE_CODE};
+use crate::error::IggyError;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::str::from_utf8;
+
+/// `AddClusterNode` command is used to add a new member to the cluster.
+/// The node must be already running and configured with the addresses of the current members,
+/// it will receive the replicated state from the leader once the membership change is committed.
+/// It has additional payload:
+/// - `node_id` - unique ID of the node (numeric), must be greater than 0.
+/// - `address` - address (host and port) on which the node accepts the cluster connections, max length is 255 characters.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct AddClusterNode {
+    /// Unique ID of the node (numeric), must be greater than 0.
+    pub node_id: u32,
+    /// Address (host and port) on which the node accepts the cluster connections.
+    pub address: String,
+}
+
+impl Command for AddClusterNode {
+    fn code(&self) -> u32 {
+        ADD_CLUSTER_NODE_CODE
+    }
+}
+
+impl Default for AddClusterNode {
+    fn default() -> Self {
+        AddClusterNode {
+            node_id: 1,
+            address: "127.0.0.1:8070".to_string(),
+        }
+    }
+}
+
+impl Validatable<IggyError> for AddClusterNode {
+    fn validate(&self) -> Result<(), IggyError> {
+        if self.node_id == 0 {
+            return Err(IggyError::InvalidClusterNodeId);
+        }
+
+        if self.address.is_empty()
+            || self.address.len() > MAX_ADDRESS_LENGTH
+            || self.address.parse::<SocketAddr>().is_err()
+        {
+            return Err(IggyError::InvalidClusterNodeAddress(self.address.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+impl BytesSerializable for AddClusterNode {
+    fn to_bytes(&self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(5 + self.address.len());
+        bytes.put_u32_le(self.node_id);
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.put_u8(self.address.len() as u8);
+        bytes.put_slice(self.address.as_bytes());
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<AddClusterNode, IggyError> {
+        if bytes.len() < 6 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let node_id = u32::from_le_bytes(
+            bytes[..4]
+                .try_into()
+                .map_err(|_| IggyError::InvalidCommand)?,
+        );
+        let address_length = bytes[4] as usize;
+        if bytes.len() != 5 + address_length {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let address = from_utf8(&bytes[5..])
+            .map_err(|_| IggyError::InvalidCommand)?
+            .to_string();
+        let command = AddClusterNode { node_id, address };
+        Ok(command)
+    }
+}
+
+impl Display for AddClusterNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.node_id, self.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Buf;
+
+    #[test]
+    fn should_be_serialized_as_bytes() {
+        let command = AddClusterNode {
+            node_id: 4,
+            address: "127.0.0.1:8074".to_string(),
+        };
+
+        let mut bytes = command.to_bytes();
+        let node_id = bytes.get_u32_le();
+        let address_length = bytes.get_u8() as usize;
+
+        assert_eq!(node_id, command.node_id);
+        assert_eq!(address_length, command.address.len());
+        assert_eq!(bytes, command.address.as_bytes());
+    }
+
+    #[test]
+    fn should_be_deserialized_from_bytes() {
+        let node_id = 4u32;
+        let address = "127.0.0.1:8074".to_string();
+        let mut bytes = BytesMut::new();
+        bytes.put_u32_le(node_id);
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.put_u8(address.len() as u8);
+        bytes.put_slice(address.as_bytes());
+
+        let command = AddClusterNode::from_bytes(bytes.freeze());
+        assert!(command.is_ok());
+
+        let command = command.unwrap();
+        assert_eq!(command.node_id, node_id);
+        assert_eq!(command.address, address);
+    }
+
+    #[test]
+    fn should_not_be_deserialized_from_bytes_with_invalid_address_length() {
+        let mut bytes = BytesMut::new();
+        bytes.put_u32_le(4);
+        bytes.put_u8(20);
+        bytes.put_slice(b"127.0.0.1:8074");
+
+        let command = AddClusterNode::from_bytes(bytes.freeze());
+        assert!(matches!(command, Err(IggyError::InvalidCommand)));
+    }
+
+    #[test]
+    fn should_not_be_deserialized_from_bytes_with_invalid_address() {
+        let mut bytes = BytesMut::new();
+        bytes.put_u32_le(4);
+        bytes.put_u8(2);
+        bytes.put_slice(&[0xc3, 0x28]);
+
+        let command = AddClusterNode::from_bytes(bytes.freeze());
+        assert!(matches!(command, Err(IggyError::InvalidCommand)));
+    }
+
+    #[test]
+    fn should_not_be_validated_with_invalid_address() {
+        let command = AddClusterNode {
+            node_id: 4,
+            address: "localhost".to_string(),
+        };
+        assert!(command.validate().is_err());
+    }
+}
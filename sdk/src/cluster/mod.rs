@@ -0,0 +1,5 @@
+pub mod add_cluster_node;
+pub mod get_cluster_metadata;
+pub mod remove_cluster_node;
+
+pub const MAX_ADDRESS_LENGTH: usize = 255;
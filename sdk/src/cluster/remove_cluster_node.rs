@@ -0,0 +1,109 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::{Command, REMOVE_CLUSTER_NODE_CODE};
+use crate::error::IggyError;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `RemoveClusterNode` command is used to remove the member from the cluster.
+/// When the leader is removed, it steps down once the membership change is committed.
+/// It has additional payload:
+/// - `node_id` - unique ID of the node (numeric).
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RemoveClusterNode {
+    /// Unique ID of the node (numeric).
+    pub node_id: u32,
+}
+
+impl Command for RemoveClusterNode {
+    fn code(&self) -> u32 {
+        REMOVE_CLUSTER_NODE_CODE
+    }
+}
+
+impl Default for RemoveClusterNode {
+    fn default() -> Self {
+        RemoveClusterNode { node_id: 1 }
+    }
+}
+
+impl Validatable<IggyError> for RemoveClusterNode {
+    fn validate(&self) -> Result<(), IggyError> {
+        if self.node_id == 0 {
+            return Err(IggyError::InvalidClusterNodeId);
+        }
+
+        Ok(())
+    }
+}
+
+impl BytesSerializable for RemoveClusterNode {
+    fn to_bytes(&self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(4);
+        bytes.put_u32_le(self.node_id);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<RemoveClusterNode, IggyError> {
+        if bytes.len() != 4 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let node_id = u32::from_le_bytes(
+            bytes[..4]
+                .try_into()
+                .map_err(|_| IggyError::InvalidCommand)?,
+        );
+        let command = RemoveClusterNode { node_id };
+        Ok(command)
+    }
+}
+
+impl Display for RemoveClusterNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.node_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Buf;
+
+    #[test]
+    fn should_be_serialized_as_bytes() {
+        let command = RemoveClusterNode { node_id: 3 };
+
+        let mut bytes = command.to_bytes();
+        let node_id = bytes.get_u32_le();
+
+        assert!(bytes.is_empty());
+        assert_eq!(node_id, command.node_id);
+    }
+
+    #[test]
+    fn should_be_deserialized_from_bytes() {
+        let node_id = 3u32;
+        let mut bytes = BytesMut::with_capacity(4);
+        bytes.put_u32_le(node_id);
+
+        let command = RemoveClusterNode::from_bytes(bytes.freeze());
+        assert!(command.is_ok());
+
+        let command = command.unwrap();
+        assert_eq!(command.node_id, node_id);
+    }
+
+    #[test]
+    fn should_not_be_deserialized_from_bytes_with_invalid_length() {
+        let command = RemoveClusterNode::from_bytes(Bytes::from_static(&[3, 0, 0]));
+        assert!(matches!(command, Err(IggyError::InvalidCommand)));
+    }
+
+    #[test]
+    fn should_not_be_validated_with_zero_node_id() {
+        let command = RemoveClusterNode { node_id: 0 };
+        assert!(command.validate().is_err());
+    }
+}
@@ -17,6 +17,8 @@ pub const GET_CLIENT: &str = "client.get";
 pub const GET_CLIENT_CODE: u32 = 21;
 pub const GET_CLIENTS: &str = "client.list";
 pub const GET_CLIENTS_CODE: u32 = 22;
+pub const RELOAD_CONFIG: &str = "config.reload";
+pub const RELOAD_CONFIG_CODE: u32 = 23;
 pub const GET_USER: &str = "user.get";
 pub const GET_USER_CODE: u32 = 31;
 pub const GET_USERS: &str = "user.list";
@@ -49,6 +51,12 @@ pub const SEND_MESSAGES: &str = "message.send";
 pub const SEND_MESSAGES_CODE: u32 = 101;
 pub const FLUSH_UNSAVED_BUFFER: &str = "message.flush_unsaved_buffer";
 pub const FLUSH_UNSAVED_BUFFER_CODE: u32 = 102;
+pub const NACK_MESSAGE: &str = "message.nack";
+pub const NACK_MESSAGE_CODE: u32 = 103;
+pub const ACK_MESSAGE: &str = "message.ack";
+pub const ACK_MESSAGE_CODE: u32 = 104;
+pub const INIT_PRODUCER: &str = "producer.init";
+pub const INIT_PRODUCER_CODE: u32 = 105;
 pub const GET_CONSUMER_OFFSET: &str = "consumer_offset.get";
 pub const GET_CONSUMER_OFFSET_CODE: u32 = 120;
 pub const STORE_CONSUMER_OFFSET: &str = "consumer_offset.store";
@@ -77,6 +85,12 @@ pub const UPDATE_TOPIC: &str = "topic.update";
 pub const UPDATE_TOPIC_CODE: u32 = 304;
 pub const PURGE_TOPIC: &str = "topic.purge";
 pub const PURGE_TOPIC_CODE: u32 = 305;
+pub const SET_DEAD_LETTER_QUEUE: &str = "topic.set_dead_letter_queue";
+pub const SET_DEAD_LETTER_QUEUE_CODE: u32 = 306;
+pub const SET_CLEANUP_POLICY: &str = "topic.set_cleanup_policy";
+pub const SET_CLEANUP_POLICY_CODE: u32 = 307;
+pub const SET_MAX_TOPIC_MESSAGES: &str = "topic.set_max_messages";
+pub const SET_MAX_TOPIC_MESSAGES_CODE: u32 = 308;
 pub const CREATE_PARTITIONS: &str = "partition.create";
 pub const CREATE_PARTITIONS_CODE: u32 = 402;
 pub const DELETE_PARTITIONS: &str = "partition.delete";
@@ -93,6 +107,20 @@ pub const JOIN_CONSUMER_GROUP: &str = "consumer_group.join";
 pub const JOIN_CONSUMER_GROUP_CODE: u32 = 604;
 pub const LEAVE_CONSUMER_GROUP: &str = "consumer_group.leave";
 pub const LEAVE_CONSUMER_GROUP_CODE: u32 = 605;
+pub const HEARTBEAT_CONSUMER_GROUP: &str = "consumer_group.heartbeat";
+pub const HEARTBEAT_CONSUMER_GROUP_CODE: u32 = 606;
+pub const BEGIN_TRANSACTION: &str = "transaction.begin";
+pub const BEGIN_TRANSACTION_CODE: u32 = 700;
+pub const COMMIT_TRANSACTION: &str = "transaction.commit";
+pub const COMMIT_TRANSACTION_CODE: u32 = 701;
+pub const ABORT_TRANSACTION: &str = "transaction.abort";
+pub const ABORT_TRANSACTION_CODE: u32 = 702;
+pub const GET_CLUSTER_METADATA: &str = "cluster.metadata";
+pub const GET_CLUSTER_METADATA_CODE: u32 = 800;
+pub const ADD_CLUSTER_NODE: &str = "cluster.add_node";
+pub const ADD_CLUSTER_NODE_CODE: u32 = 801;
+pub const REMOVE_CLUSTER_NODE: &str = "cluster.remove_node";
+pub const REMOVE_CLUSTER_NODE_CODE: u32 = 802;
 
 pub fn get_name_from_code(code: u32) -> Result<&'static str, IggyError> {
     match code {
@@ -101,6 +129,7 @@ pub fn get_name_from_code(code: u32) -> Result<&'static str, IggyError> {
         GET_ME_CODE => Ok(GET_ME),
         GET_CLIENT_CODE => Ok(GET_CLIENT),
         GET_CLIENTS_CODE => Ok(GET_CLIENTS),
+        RELOAD_CONFIG_CODE => Ok(RELOAD_CONFIG),
         GET_USER_CODE => Ok(GET_USER),
         GET_USERS_CODE => Ok(GET_USERS),
         CREATE_USER_CODE => Ok(CREATE_USER),
@@ -117,6 +146,9 @@ pub fn get_name_from_code(code: u32) -> Result<&'static str, IggyError> {
         SEND_MESSAGES_CODE => Ok(SEND_MESSAGES),
         POLL_MESSAGES_CODE => Ok(POLL_MESSAGES),
         FLUSH_UNSAVED_BUFFER_CODE => Ok(FLUSH_UNSAVED_BUFFER),
+        NACK_MESSAGE_CODE => Ok(NACK_MESSAGE),
+        ACK_MESSAGE_CODE => Ok(ACK_MESSAGE),
+        INIT_PRODUCER_CODE => Ok(INIT_PRODUCER),
         STORE_CONSUMER_OFFSET_CODE => Ok(STORE_CONSUMER_OFFSET),
         GET_CONSUMER_OFFSET_CODE => Ok(GET_CONSUMER_OFFSET),
         GET_STREAM_CODE => Ok(GET_STREAM),
@@ -131,6 +163,9 @@ pub fn get_name_from_code(code: u32) -> Result<&'static str, IggyError> {
         DELETE_TOPIC_CODE => Ok(DELETE_TOPIC),
         UPDATE_TOPIC_CODE => Ok(UPDATE_TOPIC),
         PURGE_TOPIC_CODE => Ok(PURGE_TOPIC),
+        SET_DEAD_LETTER_QUEUE_CODE => Ok(SET_DEAD_LETTER_QUEUE),
+        SET_CLEANUP_POLICY_CODE => Ok(SET_CLEANUP_POLICY),
+        SET_MAX_TOPIC_MESSAGES_CODE => Ok(SET_MAX_TOPIC_MESSAGES),
         CREATE_PARTITIONS_CODE => Ok(CREATE_PARTITIONS),
         DELETE_PARTITIONS_CODE => Ok(DELETE_PARTITIONS),
         GET_CONSUMER_GROUP_CODE => Ok(GET_CONSUMER_GROUP),
@@ -139,6 +174,13 @@ pub fn get_name_from_code(code: u32) -> Result<&'static str, IggyError> {
         DELETE_CONSUMER_GROUP_CODE => Ok(DELETE_CONSUMER_GROUP),
         JOIN_CONSUMER_GROUP_CODE => Ok(JOIN_CONSUMER_GROUP),
         LEAVE_CONSUMER_GROUP_CODE => Ok(LEAVE_CONSUMER_GROUP),
+        HEARTBEAT_CONSUMER_GROUP_CODE => Ok(HEARTBEAT_CONSUMER_GROUP),
+        BEGIN_TRANSACTION_CODE => Ok(BEGIN_TRANSACTION),
+        COMMIT_TRANSACTION_CODE => Ok(COMMIT_TRANSACTION),
+        ABORT_TRANSACTION_CODE => Ok(ABORT_TRANSACTION),
+        GET_CLUSTER_METADATA_CODE => Ok(GET_CLUSTER_METADATA),
+        ADD_CLUSTER_NODE_CODE => Ok(ADD_CLUSTER_NODE),
+        REMOVE_CLUSTER_NODE_CODE => Ok(REMOVE_CLUSTER_NODE),
         _ => Err(IggyError::InvalidCommand),
     }
 }
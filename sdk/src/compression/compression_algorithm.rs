@@ -8,9 +8,12 @@ use std::{
 };
 
 use crate::error::IggyError;
+use flate2::read::{GzDecoder, GzEncoder};
+use std::io::Read;
 
-// for now only those, in the future will add snappy, lz4, zstd (same as in confluent kafka) in addition to that
-// we should consider brotli as well.
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+// Same set of algorithms as in confluent kafka, in the future we should consider brotli as well.
 /// Supported compression algorithms
 #[derive(Debug, Default, PartialEq, Clone, Copy)]
 pub enum CompressionAlgorithm {
@@ -19,6 +22,12 @@ pub enum CompressionAlgorithm {
     None,
     // Gzip compression algorithm
     Gzip,
+    // LZ4 compression algorithm (block format with prepended size)
+    Lz4,
+    // Zstandard compression algorithm
+    Zstd,
+    // Snappy compression algorithm (raw format)
+    Snappy,
 }
 
 impl FromStr for CompressionAlgorithm {
@@ -27,6 +36,9 @@ impl FromStr for CompressionAlgorithm {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "gzip" => Ok(CompressionAlgorithm::Gzip),
+            "lz4" => Ok(CompressionAlgorithm::Lz4),
+            "zstd" => Ok(CompressionAlgorithm::Zstd),
+            "snappy" => Ok(CompressionAlgorithm::Snappy),
             "none" => Ok(CompressionAlgorithm::None),
             _ => Err(format!("Unknown compression type: {}", s)),
         }
@@ -38,6 +50,9 @@ impl CompressionAlgorithm {
         match self {
             CompressionAlgorithm::None => 1,
             CompressionAlgorithm::Gzip => 2,
+            CompressionAlgorithm::Lz4 => 3,
+            CompressionAlgorithm::Zstd => 4,
+            CompressionAlgorithm::Snappy => 5,
         }
     }
 
@@ -45,9 +60,108 @@ impl CompressionAlgorithm {
         match code {
             1 => Ok(CompressionAlgorithm::None),
             2 => Ok(CompressionAlgorithm::Gzip),
+            3 => Ok(CompressionAlgorithm::Lz4),
+            4 => Ok(CompressionAlgorithm::Zstd),
+            5 => Ok(CompressionAlgorithm::Snappy),
             _ => Err(IggyError::InvalidCommand),
         }
     }
+
+    pub fn is_none(&self) -> bool {
+        *self == CompressionAlgorithm::None
+    }
+
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, IggyError> {
+        match self {
+            CompressionAlgorithm::None => Ok(data.to_vec()),
+            CompressionAlgorithm::Gzip => {
+                let mut compressed = Vec::with_capacity(data.len() / 2);
+                GzEncoder::new(data, flate2::Compression::default())
+                    .read_to_end(&mut compressed)
+                    .map_err(|_| IggyError::CannotCompressData(*self))?;
+                Ok(compressed)
+            }
+            CompressionAlgorithm::Lz4 => Ok(lz4_flex::compress_prepend_size(data)),
+            CompressionAlgorithm::Zstd => zstd::bulk::compress(data, ZSTD_COMPRESSION_LEVEL)
+                .map_err(|_| IggyError::CannotCompressData(*self)),
+            CompressionAlgorithm::Snappy => snap::raw::Encoder::new()
+                .compress_vec(data)
+                .map_err(|_| IggyError::CannotCompressData(*self)),
+        }
+    }
+
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, IggyError> {
+        match self {
+            CompressionAlgorithm::None => Ok(data.to_vec()),
+            CompressionAlgorithm::Gzip => {
+                let mut decompressed = Vec::with_capacity(data.len() * 2);
+                GzDecoder::new(data)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|_| IggyError::CannotDecompressData(*self))?;
+                Ok(decompressed)
+            }
+            CompressionAlgorithm::Lz4 => lz4_flex::decompress_size_prepended(data)
+                .map_err(|_| IggyError::CannotDecompressData(*self)),
+            CompressionAlgorithm::Zstd => {
+                let mut decompressed = Vec::with_capacity(data.len() * 2);
+                zstd::stream::read::Decoder::new(data)
+                    .and_then(|mut decoder| decoder.read_to_end(&mut decompressed))
+                    .map_err(|_| IggyError::CannotDecompressData(*self))?;
+                Ok(decompressed)
+            }
+            CompressionAlgorithm::Snappy => snap::raw::Decoder::new()
+                .decompress_vec(data)
+                .map_err(|_| IggyError::CannotDecompressData(*self)),
+        }
+    }
+
+    /// Decompress the data, failing if the decompressed size exceeds the provided limit.
+    /// The limit is checked before the whole output is allocated, thus it's meant for the untrusted data.
+    pub fn decompress_with_limit(&self, data: &[u8], limit: usize) -> Result<Vec<u8>, IggyError> {
+        let error = || IggyError::CannotDecompressData(*self);
+        match self {
+            CompressionAlgorithm::None => {
+                if data.len() > limit {
+                    return Err(error());
+                }
+                Ok(data.to_vec())
+            }
+            CompressionAlgorithm::Gzip => {
+                Self::read_with_limit(GzDecoder::new(data), data.len(), limit).ok_or_else(error)
+            }
+            CompressionAlgorithm::Lz4 => {
+                // The block is prepended with its decompressed size.
+                if data.len() < 4 || u32::from_le_bytes(data[..4].try_into()?) as usize > limit {
+                    return Err(error());
+                }
+                lz4_flex::decompress_size_prepended(data).map_err(|_| error())
+            }
+            CompressionAlgorithm::Zstd => zstd::stream::read::Decoder::new(data)
+                .ok()
+                .and_then(|decoder| Self::read_with_limit(decoder, data.len(), limit))
+                .ok_or_else(error),
+            CompressionAlgorithm::Snappy => {
+                if snap::raw::decompress_len(data).map_err(|_| error())? > limit {
+                    return Err(error());
+                }
+                snap::raw::Decoder::new()
+                    .decompress_vec(data)
+                    .map_err(|_| error())
+            }
+        }
+    }
+
+    fn read_with_limit(reader: impl Read, size: usize, limit: usize) -> Option<Vec<u8>> {
+        let mut decompressed = Vec::with_capacity((size * 2).min(limit));
+        reader
+            .take(limit as u64 + 1)
+            .read_to_end(&mut decompressed)
+            .ok()?;
+        if decompressed.len() > limit {
+            return None;
+        }
+        Some(decompressed)
+    }
 }
 
 impl Display for CompressionAlgorithm {
@@ -55,6 +169,9 @@ impl Display for CompressionAlgorithm {
         match self {
             CompressionAlgorithm::None => write!(f, "none"),
             CompressionAlgorithm::Gzip => write!(f, "gzip"),
+            CompressionAlgorithm::Lz4 => write!(f, "lz4"),
+            CompressionAlgorithm::Zstd => write!(f, "zstd"),
+            CompressionAlgorithm::Snappy => write!(f, "snappy"),
         }
     }
 }
@@ -67,6 +184,9 @@ impl Serialize for CompressionAlgorithm {
         match self {
             CompressionAlgorithm::None => serializer.serialize_str("none"),
             CompressionAlgorithm::Gzip => serializer.serialize_str("gzip"),
+            CompressionAlgorithm::Lz4 => serializer.serialize_str("lz4"),
+            CompressionAlgorithm::Zstd => serializer.serialize_str("zstd"),
+            CompressionAlgorithm::Snappy => serializer.serialize_str("snappy"),
         }
     }
 }
@@ -76,6 +196,9 @@ impl From<CompressionAlgorithm> for String {
         match value {
             CompressionAlgorithm::None => "none".to_string(),
             CompressionAlgorithm::Gzip => "gzip".to_string(),
+            CompressionAlgorithm::Lz4 => "lz4".to_string(),
+            CompressionAlgorithm::Zstd => "zstd".to_string(),
+            CompressionAlgorithm::Snappy => "snappy".to_string(),
         }
     }
 }
@@ -126,6 +249,18 @@ mod tests {
         let gzip_alg = CompressionAlgorithm::from_str("Gzip");
         assert!(gzip_alg.is_ok());
         assert_eq!(gzip_alg.unwrap(), CompressionAlgorithm::Gzip);
+
+        let lz4_alg = CompressionAlgorithm::from_str("LZ4");
+        assert!(lz4_alg.is_ok());
+        assert_eq!(lz4_alg.unwrap(), CompressionAlgorithm::Lz4);
+
+        let zstd_alg = CompressionAlgorithm::from_str("zstd");
+        assert!(zstd_alg.is_ok());
+        assert_eq!(zstd_alg.unwrap(), CompressionAlgorithm::Zstd);
+
+        let snappy_alg = CompressionAlgorithm::from_str("Snappy");
+        assert!(snappy_alg.is_ok());
+        assert_eq!(snappy_alg.unwrap(), CompressionAlgorithm::Snappy);
     }
 
     #[test]
@@ -168,16 +303,76 @@ mod tests {
         let gzip = CompressionAlgorithm::from_code(2);
         assert!(gzip.is_ok());
         assert_eq!(gzip.unwrap(), CompressionAlgorithm::Gzip);
+
+        let snappy = CompressionAlgorithm::from_code(5);
+        assert!(snappy.is_ok());
+        assert_eq!(snappy.unwrap(), CompressionAlgorithm::Snappy);
     }
     #[test]
     fn test_from_code_invalid_input() {
         let invalid_compression_kind = CompressionAlgorithm::from_code(0);
         assert!(invalid_compression_kind.is_err());
 
+        let invalid_compression_kind = CompressionAlgorithm::from_code(6);
+        assert!(invalid_compression_kind.is_err());
+
         let invalid_compression_kind = CompressionAlgorithm::from_code(69);
         assert!(invalid_compression_kind.is_err());
 
         let invalid_compression_kind = CompressionAlgorithm::from_code(255);
         assert!(invalid_compression_kind.is_err());
     }
+
+    #[test]
+    fn given_any_algorithm_data_should_be_compressed_and_decompressed_correctly() {
+        let data = "{\"tenant\":\"acme\",\"priority\":3}".repeat(100);
+        for algorithm in [
+            CompressionAlgorithm::None,
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Lz4,
+            CompressionAlgorithm::Zstd,
+            CompressionAlgorithm::Snappy,
+        ] {
+            let compressed = algorithm.compress(data.as_bytes()).unwrap();
+            if !algorithm.is_none() {
+                assert!(compressed.len() < data.len());
+            }
+            let decompressed = algorithm.decompress(&compressed).unwrap();
+            assert_eq!(decompressed, data.as_bytes());
+        }
+    }
+
+    #[test]
+    fn given_corrupted_data_decompression_should_fail() {
+        let data = b"definitely not compressed";
+        for algorithm in [
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Lz4,
+            CompressionAlgorithm::Zstd,
+            CompressionAlgorithm::Snappy,
+        ] {
+            assert!(algorithm.decompress(data).is_err());
+        }
+    }
+
+    #[test]
+    fn given_data_exceeding_limit_decompression_should_fail() {
+        let data = "{\"tenant\":\"acme\",\"priority\":3}".repeat(100);
+        for algorithm in [
+            CompressionAlgorithm::None,
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Lz4,
+            CompressionAlgorithm::Zstd,
+            CompressionAlgorithm::Snappy,
+        ] {
+            let compressed = algorithm.compress(data.as_bytes()).unwrap();
+            let decompressed = algorithm
+                .decompress_with_limit(&compressed, data.len())
+                .unwrap();
+            assert_eq!(decompressed, data.as_bytes());
+            assert!(algorithm
+                .decompress_with_limit(&compressed, data.len() - 1)
+                .is_err());
+        }
+    }
 }
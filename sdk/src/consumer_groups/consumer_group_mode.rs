@@ -0,0 +1,90 @@
+use crate::error::IggyError;
+use crate::utils::duration::IggyDuration;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `ConsumerGroupMode` defines how the messages are delivered to the members of the consumer group.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConsumerGroupMode {
+    /// The partitions are assigned to the members and the progress is tracked by a single offset per partition.
+    #[default]
+    Offset,
+    /// Each member can poll any partition. The polled messages are leased to the member for the `visibility_timeout`,
+    /// they have to be acknowledged one by one, otherwise they are redelivered once the lease expires.
+    Queue {
+        /// The time for which the polled message is invisible to the other members.
+        visibility_timeout: IggyDuration,
+    },
+}
+
+impl ConsumerGroupMode {
+    /// Creates the queue mode with the given visibility timeout.
+    pub fn queue(visibility_timeout: IggyDuration) -> Self {
+        ConsumerGroupMode::Queue { visibility_timeout }
+    }
+
+    /// Returns the code of the mode.
+    pub fn as_code(&self) -> u8 {
+        match self {
+            ConsumerGroupMode::Offset => 1,
+            ConsumerGroupMode::Queue { .. } => 2,
+        }
+    }
+
+    /// Creates the mode from the code and the visibility timeout in microseconds, which is ignored for the offset mode.
+    pub fn from_code(code: u8, visibility_timeout: u64) -> Result<Self, IggyError> {
+        match code {
+            1 => Ok(ConsumerGroupMode::Offset),
+            2 => Ok(ConsumerGroupMode::Queue {
+                visibility_timeout: visibility_timeout.into(),
+            }),
+            _ => Err(IggyError::InvalidCommand),
+        }
+    }
+
+    /// Returns the visibility timeout for the queue mode.
+    pub fn visibility_timeout(&self) -> Option<IggyDuration> {
+        match self {
+            ConsumerGroupMode::Offset => None,
+            ConsumerGroupMode::Queue { visibility_timeout } => Some(*visibility_timeout),
+        }
+    }
+
+    /// Returns true if the messages are leased and acknowledged one by one.
+    pub fn is_queue(&self) -> bool {
+        matches!(self, ConsumerGroupMode::Queue { .. })
+    }
+}
+
+impl Display for ConsumerGroupMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConsumerGroupMode::Offset => write!(f, "offset"),
+            ConsumerGroupMode::Queue { visibility_timeout } => {
+                write!(f, "queue ({visibility_timeout})")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_created_from_code() {
+        let mode = ConsumerGroupMode::queue(IggyDuration::ONE_SECOND);
+
+        let created_mode =
+            ConsumerGroupMode::from_code(mode.as_code(), IggyDuration::ONE_SECOND.as_micros())
+                .unwrap();
+
+        assert_eq!(created_mode, mode);
+        assert_eq!(
+            ConsumerGroupMode::from_code(1, 100).unwrap(),
+            ConsumerGroupMode::Offset
+        );
+        assert!(ConsumerGroupMode::from_code(3, 0).is_err());
+    }
+}
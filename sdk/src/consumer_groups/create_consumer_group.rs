@@ -1,5 +1,6 @@
 use crate::bytes_serializable::BytesSerializable;
 use crate::command::{Command, CREATE_CONSUMER_GROUP_CODE};
+use crate::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use crate::consumer_groups::MAX_NAME_LENGTH;
 use crate::error::IggyError;
 use crate::identifier::Identifier;
@@ -16,6 +17,7 @@ use std::str::from_utf8;
 /// - `topic_id` - unique topic ID (numeric or name).
 /// - `group_id` - unique consumer group ID.
 /// - `name` - unique consumer group name, max length is 255 characters. The name will be always converted to lowercase and all whitespaces will be replaced with dots.
+/// - `mode` - the way the messages are delivered to the members, either offset-based (default) or queue with per-message acknowledgements.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct CreateConsumerGroup {
     /// Unique stream ID (numeric or name).
@@ -28,6 +30,9 @@ pub struct CreateConsumerGroup {
     pub group_id: Option<u32>,
     /// Unique consumer group name, max length is 255 characters.
     pub name: String,
+    /// The way the messages are delivered to the members, either offset-based (default) or queue with per-message acknowledgements.
+    #[serde(default)]
+    pub mode: ConsumerGroupMode,
 }
 
 impl Command for CreateConsumerGroup {
@@ -43,6 +48,7 @@ impl Default for CreateConsumerGroup {
             topic_id: Identifier::default(),
             group_id: None,
             name: "consumer_group_1".to_string(),
+            mode: ConsumerGroupMode::default(),
         }
     }
 }
@@ -63,6 +69,12 @@ impl Validatable<IggyError> for CreateConsumerGroup {
             return Err(IggyError::InvalidConsumerGroupName);
         }
 
+        if let Some(visibility_timeout) = self.mode.visibility_timeout() {
+            if visibility_timeout.as_micros() == 0 {
+                return Err(IggyError::InvalidVisibilityTimeout);
+            }
+        }
+
         Ok(())
     }
 }
@@ -72,7 +84,7 @@ impl BytesSerializable for CreateConsumerGroup {
         let stream_id_bytes = self.stream_id.to_bytes();
         let topic_id_bytes = self.topic_id.to_bytes();
         let mut bytes = BytesMut::with_capacity(
-            4 + stream_id_bytes.len() + topic_id_bytes.len() + self.name.len(),
+            14 + stream_id_bytes.len() + topic_id_bytes.len() + self.name.len(),
         );
         bytes.put_slice(&stream_id_bytes);
         bytes.put_slice(&topic_id_bytes);
@@ -80,6 +92,13 @@ impl BytesSerializable for CreateConsumerGroup {
         #[allow(clippy::cast_possible_truncation)]
         bytes.put_u8(self.name.len() as u8);
         bytes.put_slice(self.name.as_bytes());
+        bytes.put_u8(self.mode.as_code());
+        bytes.put_u64_le(
+            self.mode
+                .visibility_timeout()
+                .map(|timeout| timeout.as_micros())
+                .unwrap_or(0),
+        );
         bytes.freeze()
     }
 
@@ -98,11 +117,21 @@ impl BytesSerializable for CreateConsumerGroup {
         let name_length = bytes[position + 4];
         let name =
             from_utf8(&bytes[position + 5..position + 5 + name_length as usize])?.to_string();
+        position += 5 + name_length as usize;
+        // The mode is optional to support the commands serialized before it was introduced.
+        let mode = if bytes.len() >= position + 9 {
+            let visibility_timeout =
+                u64::from_le_bytes(bytes[position + 1..position + 9].try_into()?);
+            ConsumerGroupMode::from_code(bytes[position], visibility_timeout)?
+        } else {
+            ConsumerGroupMode::default()
+        };
         let command = CreateConsumerGroup {
             stream_id,
             topic_id,
             group_id,
             name,
+            mode,
         };
         Ok(command)
     }
@@ -112,11 +141,12 @@ impl Display for CreateConsumerGroup {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}|{}|{}|{}",
+            "{}|{}|{}|{}|{}",
             self.stream_id,
             self.topic_id,
             self.group_id.unwrap_or(0),
-            self.name
+            self.name,
+            self.mode
         )
     }
 }
@@ -124,6 +154,7 @@ impl Display for CreateConsumerGroup {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::duration::IggyDuration;
 
     #[test]
     fn should_be_serialized_as_bytes() {
@@ -132,6 +163,7 @@ mod tests {
             topic_id: Identifier::numeric(2).unwrap(),
             group_id: Some(3),
             name: "test".to_string(),
+            mode: ConsumerGroupMode::queue(IggyDuration::ONE_SECOND),
         };
 
         let bytes = command.to_bytes();
@@ -144,11 +176,18 @@ mod tests {
 
         let name_length = bytes[position + 4];
         let name = from_utf8(&bytes[position + 5..position + 5 + name_length as usize]).unwrap();
+        position += 5 + name_length as usize;
+        let mode = ConsumerGroupMode::from_code(
+            bytes[position],
+            u64::from_le_bytes(bytes[position + 1..position + 9].try_into().unwrap()),
+        )
+        .unwrap();
         assert!(!bytes.is_empty());
         assert_eq!(stream_id, command.stream_id);
         assert_eq!(topic_id, command.topic_id);
         assert_eq!(group_id, command.group_id.unwrap());
         assert_eq!(name, command.name);
+        assert_eq!(mode, command.mode);
     }
 
     #[test]
@@ -175,5 +214,16 @@ mod tests {
         assert_eq!(command.topic_id, topic_id);
         assert_eq!(command.group_id.unwrap(), group_id);
         assert_eq!(command.name, name);
+        assert_eq!(command.mode, ConsumerGroupMode::Offset);
+    }
+
+    #[test]
+    fn should_not_be_valid_given_queue_mode_without_visibility_timeout() {
+        let command = CreateConsumerGroup {
+            mode: ConsumerGroupMode::queue(IggyDuration::default()),
+            ..Default::default()
+        };
+
+        assert!(command.validate().is_err());
     }
 }
@@ -6,12 +6,17 @@ use crate::validatable::Validatable;
 use bytes::{BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::str::from_utf8;
+
+const MAX_MEMBER_NAME_LENGTH: usize = 255;
 
 /// `JoinConsumerGroup` command joins the consumer group by currently authenticated user.
 /// It has additional payload:
 /// - `stream_id` - unique stream ID (numeric or name).
 /// - `topic_id` - unique topic ID (numeric or name).
 /// - `group_id` - unique consumer group ID (numeric or name).
+/// - `member_name` - optional, stable name of the member, max 255 characters. The client joining with the name of the member
+///   which has disconnected within the grace period configured on the server reclaims its partitions without a rebalance.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
 pub struct JoinConsumerGroup {
     /// Unique stream ID (numeric or name).
@@ -23,6 +28,9 @@ pub struct JoinConsumerGroup {
     /// Unique consumer group ID (numeric or name).
     #[serde(skip)]
     pub group_id: Identifier,
+    /// Optional, stable name of the member, max 255 characters.
+    #[serde(skip)]
+    pub member_name: Option<String>,
 }
 
 impl Command for JoinConsumerGroup {
@@ -33,6 +41,12 @@ impl Command for JoinConsumerGroup {
 
 impl Validatable<IggyError> for JoinConsumerGroup {
     fn validate(&self) -> Result<(), IggyError> {
+        if let Some(member_name) = &self.member_name {
+            if member_name.is_empty() || member_name.len() > MAX_MEMBER_NAME_LENGTH {
+                return Err(IggyError::InvalidConsumerGroupMemberName);
+            }
+        }
+
         Ok(())
     }
 }
@@ -42,12 +56,20 @@ impl BytesSerializable for JoinConsumerGroup {
         let stream_id_bytes = self.stream_id.to_bytes();
         let topic_id_bytes = self.topic_id.to_bytes();
         let group_id_bytes = self.group_id.to_bytes();
+        let member_name = self.member_name.as_deref().unwrap_or_default();
         let mut bytes = BytesMut::with_capacity(
-            stream_id_bytes.len() + topic_id_bytes.len() + group_id_bytes.len(),
+            stream_id_bytes.len()
+                + topic_id_bytes.len()
+                + group_id_bytes.len()
+                + 1
+                + member_name.len(),
         );
         bytes.put_slice(&stream_id_bytes);
         bytes.put_slice(&topic_id_bytes);
         bytes.put_slice(&group_id_bytes);
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.put_u8(member_name.len() as u8);
+        bytes.put_slice(member_name.as_bytes());
         bytes.freeze()
     }
 
@@ -62,10 +84,23 @@ impl BytesSerializable for JoinConsumerGroup {
         let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
         position += topic_id.get_size_bytes() as usize;
         let group_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += group_id.get_size_bytes() as usize;
+        // The member name is optional, thus the payload sent by the older clients ends with the group ID.
+        let member_name = match bytes.get(position) {
+            None | Some(0) => None,
+            Some(length) => {
+                let length = *length as usize;
+                let name = bytes
+                    .get(position + 1..position + 1 + length)
+                    .ok_or(IggyError::InvalidCommand)?;
+                Some(from_utf8(name)?.to_string())
+            }
+        };
         let command = JoinConsumerGroup {
             stream_id,
             topic_id,
             group_id,
+            member_name,
         };
         Ok(command)
     }
@@ -73,7 +108,14 @@ impl BytesSerializable for JoinConsumerGroup {
 
 impl Display for JoinConsumerGroup {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}|{}|{}", self.stream_id, self.topic_id, self.group_id)
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.stream_id,
+            self.topic_id,
+            self.group_id,
+            self.member_name.as_deref().unwrap_or_default()
+        )
     }
 }
 
@@ -87,6 +129,7 @@ mod tests {
             stream_id: Identifier::numeric(1).unwrap(),
             topic_id: Identifier::numeric(2).unwrap(),
             group_id: Identifier::numeric(3).unwrap(),
+            member_name: Some("member-1".to_string()),
         };
 
         let bytes = command.to_bytes();
@@ -96,11 +139,16 @@ mod tests {
         let topic_id = Identifier::from_bytes(bytes.slice(position..)).unwrap();
         position += topic_id.get_size_bytes() as usize;
         let group_id = Identifier::from_bytes(bytes.slice(position..)).unwrap();
+        position += group_id.get_size_bytes() as usize;
+        let member_name_length = bytes[position] as usize;
+        let member_name =
+            from_utf8(&bytes[position + 1..position + 1 + member_name_length]).unwrap();
 
         assert!(!bytes.is_empty());
         assert_eq!(stream_id, command.stream_id);
         assert_eq!(topic_id, command.topic_id);
         assert_eq!(group_id, command.group_id);
+        assert_eq!(Some(member_name), command.member_name.as_deref());
     }
 
     #[test]
@@ -124,5 +172,19 @@ mod tests {
         assert_eq!(command.stream_id, stream_id);
         assert_eq!(command.topic_id, topic_id);
         assert_eq!(command.group_id, group_id);
+        assert!(command.member_name.is_none());
+    }
+
+    #[test]
+    fn should_be_deserialized_from_bytes_with_member_name() {
+        let command = JoinConsumerGroup {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::named("topic").unwrap(),
+            group_id: Identifier::numeric(3).unwrap(),
+            member_name: Some("member-1".to_string()),
+        };
+
+        let deserialized_command = JoinConsumerGroup::from_bytes(command.to_bytes()).unwrap();
+        assert_eq!(deserialized_command, command);
     }
 }
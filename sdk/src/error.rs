@@ -1,3 +1,4 @@
+use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::utils::byte_size::IggyByteSize;
 use crate::utils::topic_size::MaxTopicSize;
 use strum::{EnumDiscriminants, FromRepr, IntoStaticStr};
@@ -44,6 +45,10 @@ pub enum IggyError {
     StateFileCorrupted = 15,
     #[error("Invalid state entry checksum: {0}, expected: {1}, for index: {2}")]
     InvalidStateEntryChecksum(u32, u32, u64) = 16,
+    #[error("Invalid state snapshot checksum: {0}, expected: {1}, for index: {2}")]
+    InvalidStateSnapshotChecksum(u32, u32, u64) = 17,
+    #[error("State snapshot is not supported by the cluster")]
+    StateSnapshotNotSupported = 18,
     #[error("Cannot open database, Path: {0}")]
     CannotOpenDatabase(String) = 19,
     #[error("Resource with key: {0} was not found.")]
@@ -242,6 +247,10 @@ pub enum IggyError {
     CannotReadTopics(u32) = 2017,
     #[error("Invalid replication factor")]
     InvalidReplicationFactor = 2018,
+    #[error("Dead letter queue is not configured for topic with ID: {0} for stream with ID: {1}.")]
+    DeadLetterQueueNotConfigured(u32, u32) = 2019,
+    #[error("Invalid dead letter queue")]
+    InvalidDeadLetterQueue = 2020,
     #[error("Cannot create partition with ID: {0} for stream with ID: {1} and topic with ID: {2}")]
     CannotCreatePartition(u32, u32, u32) = 3000,
     #[error(
@@ -336,8 +345,26 @@ pub enum IggyError {
     InvalidKeyValueLength = 4028,
     #[error("Command length error: {0}")]
     CommandLengthError(String) = 4029,
+    #[error("Invalid message filter: {0}")]
+    InvalidMessageFilter(String) = 4030,
+    #[error("Invalid nack reason")]
+    InvalidNackReason = 4031,
+    #[error("Cannot fetch the archived file: {0}")]
+    CannotFetchArchivedFile(String) = 4032,
+    #[error("Segment indexes are inconsistent with the log: {0}")]
+    InconsistentSegmentIndexes(String) = 4033,
+    #[error("Invalid compressed messages checksum: {0}, expected: {1}")]
+    InvalidCompressedMessagesChecksum(u32, u32) = 4034,
     #[error("Invalid offset: {0}")]
     InvalidOffset(u64) = 4100,
+    #[error("Transaction with ID: {0} was not found.")]
+    TransactionNotFound(u64) = 4200,
+    #[error("Transaction with ID: {0} is already open.")]
+    TransactionAlreadyOpen(u64) = 4201,
+    #[error("Transaction with ID: {0} is already being completed with the other outcome.")]
+    TransactionAlreadyCompleting(u64) = 4202,
+    #[error("Invalid sequence number: {2} of producer with ID: {0}, expected: {1}.")]
+    InvalidProducerSequence(u64, u64, u64) = 4300,
     #[error("Consumer group with ID: {0} for topic with ID: {1} was not found.")]
     ConsumerGroupIdNotFound(u32, u32) = 5000,
     #[error("Consumer group with ID: {0} for topic with ID: {1} already exists.")]
@@ -356,6 +383,14 @@ pub enum IggyError {
     CannotCreateConsumerGroupInfo(u32, u32, u32) = 5007,
     #[error("Failed to delete consumer group info file for ID: {0} for topic with ID: {1} for stream with ID: {2}.")]
     CannotDeleteConsumerGroupInfo(u32, u32, u32) = 5008,
+    #[error("Invalid visibility timeout")]
+    InvalidVisibilityTimeout = 5009,
+    #[error("Consumer group with ID: {0} for topic with ID: {1} is not in queue mode.")]
+    ConsumerGroupNotInQueueMode(u32, u32) = 5010,
+    #[error("Message lease for offset: {0} in consumer group with ID: {1} was not found.")]
+    MessageLeaseNotFound(u64, u32) = 5011,
+    #[error("Invalid consumer group member name")]
+    InvalidConsumerGroupMemberName = 5012,
     #[error("Base offset is missing")]
     MissingBaseOffsetRetainedMessageBatch = 6000,
     #[error("Last offset delta is missing")]
@@ -376,8 +411,46 @@ pub enum IggyError {
     CannotReadMaxTimestamp = 7003,
     #[error("Cannot read batch payload")]
     CannotReadBatchPayload = 7004,
+    #[error("Cannot compress data using algorithm: {0}")]
+    CannotCompressData(CompressionAlgorithm) = 7005,
+    #[error("Cannot decompress data using algorithm: {0}")]
+    CannotDecompressData(CompressionAlgorithm) = 7006,
     #[error("Invalid connection string")]
     InvalidConnectionString = 8000,
+    #[error("Not a cluster leader, the leader is node with ID: {0} and address: {1}")]
+    NotLeader(u32, String) = 9000,
+    #[error("Cluster leader is not elected")]
+    LeaderNotElected = 9001,
+    #[error("Cannot commit state entry with index: {0}")]
+    CannotCommitStateEntry(u64) = 9002,
+    #[error("Cluster mode is disabled")]
+    ClusterDisabled = 9003,
+    #[error("Cluster node with ID: {0} was not found")]
+    ClusterNodeNotFound(u32) = 9004,
+    #[error("Cluster node with ID: {0} already exists")]
+    ClusterNodeAlreadyExists(u32) = 9005,
+    #[error("Invalid cluster node address: {0}")]
+    InvalidClusterNodeAddress(String) = 9006,
+    #[error("Cluster membership change is already in progress")]
+    ClusterMembershipChangeInProgress = 9007,
+    #[error("Invalid cluster message")]
+    InvalidClusterMessage = 9008,
+    #[error("Invalid cluster node ID")]
+    InvalidClusterNodeId = 9009,
+    #[error("Cannot remove the last cluster node with ID: {0}")]
+    CannotRemoveLastClusterNode(u32) = 9010,
+    #[error("Not a leader of partition with ID: {0} for topic with ID: {1} and stream with ID: {2}, the leader is node with ID: {3}")]
+    NotPartitionLeader(u32, u32, u32, u32) = 9011,
+    #[error("Leader of partition with ID: {0} for topic with ID: {1} and stream with ID: {2} is not available")]
+    PartitionLeaderNotAvailable(u32, u32, u32) = 9012,
+    #[error(
+        "Messages up to offset: {0} have not been replicated to all the in-sync replicas in time"
+    )]
+    MessagesNotReplicated(u64) = 9013,
+    #[error("Cluster node with ID: {0} cannot join the cluster with the existing state, only the bootstrapping node can")]
+    CannotJoinClusterWithExistingState(u32) = 9014,
+    #[error("State entry with index: {0} has not been committed in time, it might still be committed later on")]
+    StateEntryCommitTimedOut(u64) = 9015,
 }
 
 impl IggyError {
@@ -0,0 +1,43 @@
+use crate::client::ClusterClient;
+use crate::cluster::add_cluster_node::AddClusterNode;
+use crate::error::IggyError;
+use crate::http::client::HttpClient;
+use crate::http::HttpTransport;
+use crate::models::cluster::ClusterMetadata;
+use async_trait::async_trait;
+
+const PATH: &str = "/cluster";
+
+#[async_trait]
+impl ClusterClient for HttpClient {
+    async fn get_cluster_metadata(&self) -> Result<ClusterMetadata, IggyError> {
+        let response = self.get(PATH).await?;
+        let metadata = response.json().await?;
+        Ok(metadata)
+    }
+
+    async fn add_cluster_node(&self, node_id: u32, address: &str) -> Result<(), IggyError> {
+        self.post(
+            &get_nodes_path(),
+            &AddClusterNode {
+                node_id,
+                address: address.to_string(),
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_cluster_node(&self, node_id: u32) -> Result<(), IggyError> {
+        self.delete(&get_node_path(node_id)).await?;
+        Ok(())
+    }
+}
+
+fn get_nodes_path() -> String {
+    format!("{PATH}/nodes")
+}
+
+fn get_node_path(node_id: u32) -> String {
+    format!("{}/{node_id}", get_nodes_path())
+}
@@ -1,15 +1,23 @@
 use crate::client::MessageClient;
+use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::consumer::Consumer;
 use crate::error::IggyError;
 use crate::http::client::HttpClient;
 use crate::http::HttpTransport;
 use crate::identifier::Identifier;
+use crate::messages::ack_message::AckMessage;
 use crate::messages::flush_unsaved_buffer::FlushUnsavedBuffer;
-use crate::messages::poll_messages::{PollMessages, PollingStrategy};
-use crate::messages::send_messages::{Message, Partitioning, SendMessages};
+use crate::messages::init_producer::InitProducer;
+use crate::messages::message_filter::MessageFilter;
+use crate::messages::nack_message::NackMessage;
+use crate::messages::poll_messages::{IsolationLevel, PollMessages, PollingStrategy};
+use crate::messages::send_messages::{Acks, Message, Partitioning, SendMessages};
 use crate::models::messages::PolledMessages;
+use crate::models::producer::Producer;
 use async_trait::async_trait;
 
+const PRODUCERS_PATH: &str = "/producers";
+
 #[async_trait]
 impl MessageClient for HttpClient {
     async fn poll_messages(
@@ -33,6 +41,72 @@ impl MessageClient for HttpClient {
                     strategy: *strategy,
                     count,
                     auto_commit,
+                    filter: None,
+                    isolation: IsolationLevel::ReadUncommitted,
+                    batched: false,
+                },
+            )
+            .await?;
+        let messages = response.json().await?;
+        Ok(messages)
+    }
+
+    async fn poll_messages_with_filter(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        consumer: &Consumer,
+        strategy: &PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+        filter: &MessageFilter,
+    ) -> Result<PolledMessages, IggyError> {
+        let response = self
+            .get_with_query(
+                &get_path(&stream_id.as_cow_str(), &topic_id.as_cow_str()),
+                &PollMessages {
+                    stream_id: stream_id.clone(),
+                    topic_id: topic_id.clone(),
+                    partition_id,
+                    consumer: consumer.clone(),
+                    strategy: *strategy,
+                    count,
+                    auto_commit,
+                    filter: Some(filter.clone()),
+                    isolation: IsolationLevel::ReadUncommitted,
+                    batched: false,
+                },
+            )
+            .await?;
+        let messages = response.json().await?;
+        Ok(messages)
+    }
+
+    async fn poll_committed_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        consumer: &Consumer,
+        strategy: &PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+    ) -> Result<PolledMessages, IggyError> {
+        let response = self
+            .get_with_query(
+                &get_path(&stream_id.as_cow_str(), &topic_id.as_cow_str()),
+                &PollMessages {
+                    stream_id: stream_id.clone(),
+                    topic_id: topic_id.clone(),
+                    partition_id,
+                    consumer: consumer.clone(),
+                    strategy: *strategy,
+                    count,
+                    auto_commit,
+                    filter: None,
+                    isolation: IsolationLevel::ReadCommitted,
+                    batched: false,
                 },
             )
             .await?;
@@ -46,6 +120,18 @@ impl MessageClient for HttpClient {
         topic_id: &Identifier,
         partitioning: &Partitioning,
         messages: &mut [Message],
+    ) -> Result<(), IggyError> {
+        self.send_messages_with_acks(stream_id, topic_id, partitioning, Acks::Leader, messages)
+            .await
+    }
+
+    async fn send_messages_with_acks(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        acks: Acks,
+        messages: &mut [Message],
     ) -> Result<(), IggyError> {
         self.post(
             &get_path(&stream_id.as_cow_str(), &topic_id.as_cow_str()),
@@ -54,12 +140,27 @@ impl MessageClient for HttpClient {
                 topic_id: topic_id.clone(),
                 partitioning: partitioning.clone(),
                 messages: messages.to_vec(),
+                compressed_messages: None,
+                acks,
             },
         )
         .await?;
         Ok(())
     }
 
+    async fn send_compressed_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        _compression_algorithm: CompressionAlgorithm,
+        messages: &mut [Message],
+    ) -> Result<(), IggyError> {
+        // The JSON payload can't carry the compressed batch, so the messages are sent as they are.
+        self.send_messages(stream_id, topic_id, partitioning, messages)
+            .await
+    }
+
     async fn flush_unsaved_buffer(
         &self,
         stream_id: &Identifier,
@@ -85,6 +186,64 @@ impl MessageClient for HttpClient {
             .await?;
         Ok(())
     }
+
+    async fn nack_message(
+        &self,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+        reason: &str,
+    ) -> Result<(), IggyError> {
+        self.post(
+            &format!(
+                "{}/nack",
+                get_path(&stream_id.as_cow_str(), &topic_id.as_cow_str())
+            ),
+            &NackMessage {
+                consumer: consumer.clone(),
+                stream_id: stream_id.clone(),
+                topic_id: topic_id.clone(),
+                partition_id,
+                offset,
+                reason: reason.to_string(),
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn ack_message(
+        &self,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+    ) -> Result<(), IggyError> {
+        self.post(
+            &format!(
+                "{}/ack",
+                get_path(&stream_id.as_cow_str(), &topic_id.as_cow_str())
+            ),
+            &AckMessage {
+                consumer: consumer.clone(),
+                stream_id: stream_id.clone(),
+                topic_id: topic_id.clone(),
+                partition_id,
+                offset,
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn init_producer(&self) -> Result<Producer, IggyError> {
+        let response = self.post(PRODUCERS_PATH, &InitProducer {}).await?;
+        let producer = response.json().await?;
+        Ok(producer)
+    }
 }
 
 fn get_path(stream_id: &str, topic_id: &str) -> String {
@@ -6,6 +6,7 @@ use serde::Serialize;
 
 #[allow(deprecated)]
 pub mod client;
+pub mod cluster;
 pub mod config;
 pub mod consumer_groups;
 pub mod consumer_offsets;
@@ -15,6 +16,7 @@ pub mod personal_access_tokens;
 pub mod streams;
 pub mod system;
 pub mod topics;
+pub mod transactions;
 pub mod users;
 
 #[async_trait]
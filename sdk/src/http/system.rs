@@ -4,12 +4,14 @@ use crate::http::client::HttpClient;
 use crate::http::HttpTransport;
 use crate::models::client_info::{ClientInfo, ClientInfoDetails};
 use crate::models::stats::Stats;
+use crate::system::reload_config::ReloadConfig;
 use crate::utils::duration::IggyDuration;
 use async_trait::async_trait;
 
 const PING: &str = "/ping";
 const CLIENTS: &str = "/clients";
 const STATS: &str = "/stats";
+const RELOAD_CONFIG: &str = "/config/reload";
 
 #[async_trait]
 impl SystemClient for HttpClient {
@@ -44,6 +46,11 @@ impl SystemClient for HttpClient {
         Ok(())
     }
 
+    async fn reload_config(&self) -> Result<(), IggyError> {
+        self.post(RELOAD_CONFIG, &ReloadConfig {}).await?;
+        Ok(())
+    }
+
     async fn heartbeat_interval(&self) -> IggyDuration {
         self.heartbeat_interval
     }
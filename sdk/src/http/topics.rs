@@ -4,8 +4,13 @@ use crate::error::IggyError;
 use crate::http::client::HttpClient;
 use crate::http::HttpTransport;
 use crate::identifier::Identifier;
+use crate::models::cleanup_policy::CleanupPolicy;
+use crate::models::dead_letter_queue::DeadLetterQueue;
 use crate::models::topic::{Topic, TopicDetails};
 use crate::topics::create_topic::CreateTopic;
+use crate::topics::set_cleanup_policy::SetCleanupPolicy;
+use crate::topics::set_dead_letter_queue::SetDeadLetterQueue;
+use crate::topics::set_max_topic_messages::SetMaxTopicMessages;
 use crate::topics::update_topic::UpdateTopic;
 use crate::utils::expiry::IggyExpiry;
 use crate::utils::topic_size::MaxTopicSize;
@@ -119,6 +124,69 @@ impl TopicClient for HttpClient {
         .await?;
         Ok(())
     }
+
+    async fn set_dead_letter_queue(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        dead_letter_queue: Option<DeadLetterQueue>,
+    ) -> Result<(), IggyError> {
+        self.put(
+            &format!(
+                "{}/dead-letter-queue",
+                &get_details_path(&stream_id.as_cow_str(), &topic_id.as_cow_str())
+            ),
+            &SetDeadLetterQueue {
+                stream_id: stream_id.clone(),
+                topic_id: topic_id.clone(),
+                dead_letter_queue,
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn set_cleanup_policy(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        cleanup_policy: CleanupPolicy,
+    ) -> Result<(), IggyError> {
+        self.put(
+            &format!(
+                "{}/cleanup-policy",
+                &get_details_path(&stream_id.as_cow_str(), &topic_id.as_cow_str())
+            ),
+            &SetCleanupPolicy {
+                stream_id: stream_id.clone(),
+                topic_id: topic_id.clone(),
+                cleanup_policy,
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn set_max_topic_messages(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        max_messages: u64,
+    ) -> Result<(), IggyError> {
+        self.put(
+            &format!(
+                "{}/max-messages",
+                &get_details_path(&stream_id.as_cow_str(), &topic_id.as_cow_str())
+            ),
+            &SetMaxTopicMessages {
+                stream_id: stream_id.clone(),
+                topic_id: topic_id.clone(),
+                max_messages,
+            },
+        )
+        .await?;
+        Ok(())
+    }
 }
 
 fn get_path(stream_id: &str) -> String {
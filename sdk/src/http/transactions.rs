@@ -0,0 +1,21 @@
+use crate::client::TransactionClient;
+use crate::error::IggyError;
+use crate::http::client::HttpClient;
+use crate::models::transaction::Transaction;
+use async_trait::async_trait;
+
+// The transactions are bound to the client connection, thus they're not available over the stateless HTTP transport.
+#[async_trait]
+impl TransactionClient for HttpClient {
+    async fn begin_transaction(&self) -> Result<Transaction, IggyError> {
+        Err(IggyError::FeatureUnavailable)
+    }
+
+    async fn commit_transaction(&self, _: u64) -> Result<(), IggyError> {
+        Err(IggyError::FeatureUnavailable)
+    }
+
+    async fn abort_transaction(&self, _: u64) -> Result<(), IggyError> {
+        Err(IggyError::FeatureUnavailable)
+    }
+}
@@ -11,6 +11,7 @@ pub mod client_error;
 pub mod client_provider;
 #[allow(deprecated)]
 pub mod clients;
+pub mod cluster;
 pub mod command;
 pub mod compression;
 pub mod consumer;
@@ -31,6 +32,7 @@ pub mod streams;
 pub mod system;
 pub mod tcp;
 pub mod topics;
+pub mod transactions;
 pub mod users;
 pub mod utils;
 pub mod validatable;
@@ -0,0 +1,165 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::{Command, ACK_MESSAGE_CODE};
+use crate::consumer::{Consumer, ConsumerKind};
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `AckMessage` command is used to acknowledge the message polled by the member of the consumer group in queue mode.
+/// Once the message is acknowledged, its lease is released and it won't be redelivered to any other member.
+/// It has additional payload:
+/// - `consumer` - the consumer group that is acknowledging the message.
+/// - `stream_id` - unique stream ID (numeric or name).
+/// - `topic_id` - unique topic ID (numeric or name).
+/// - `partition_id` - partition ID of the message. If `None` is provided, the partition from which the member has polled the messages recently is used.
+/// - `offset` - offset of the message.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct AckMessage {
+    /// The consumer group that is acknowledging the message.
+    #[serde(flatten)]
+    pub consumer: Consumer,
+    /// Unique stream ID (numeric or name).
+    #[serde(skip)]
+    pub stream_id: Identifier,
+    /// Unique topic ID (numeric or name).
+    #[serde(skip)]
+    pub topic_id: Identifier,
+    /// Partition ID of the message. If `None` is provided, the partition from which the member has polled the messages recently is used.
+    pub partition_id: Option<u32>,
+    /// Offset of the message.
+    pub offset: u64,
+}
+
+impl Default for AckMessage {
+    fn default() -> Self {
+        AckMessage {
+            consumer: Consumer::group(Identifier::default()),
+            stream_id: Identifier::default(),
+            topic_id: Identifier::default(),
+            partition_id: Some(1),
+            offset: 0,
+        }
+    }
+}
+
+impl Command for AckMessage {
+    fn code(&self) -> u32 {
+        ACK_MESSAGE_CODE
+    }
+}
+
+impl Validatable<IggyError> for AckMessage {
+    fn validate(&self) -> Result<(), IggyError> {
+        if self.consumer.kind != ConsumerKind::ConsumerGroup {
+            return Err(IggyError::InvalidConsumerGroupId);
+        }
+
+        Ok(())
+    }
+}
+
+impl BytesSerializable for AckMessage {
+    fn to_bytes(&self) -> Bytes {
+        let consumer_bytes = self.consumer.to_bytes();
+        let stream_id_bytes = self.stream_id.to_bytes();
+        let topic_id_bytes = self.topic_id.to_bytes();
+        let mut bytes = BytesMut::with_capacity(
+            12 + consumer_bytes.len() + stream_id_bytes.len() + topic_id_bytes.len(),
+        );
+        bytes.put_slice(&consumer_bytes);
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        if let Some(partition_id) = self.partition_id {
+            bytes.put_u32_le(partition_id);
+        } else {
+            bytes.put_u32_le(0);
+        }
+        bytes.put_u64_le(self.offset);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<AckMessage, IggyError> {
+        if bytes.len() < 24 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = 0;
+        let consumer_kind = ConsumerKind::from_code(bytes[0])?;
+        let consumer_id = Identifier::from_bytes(bytes.slice(1..))?;
+        position += 1 + consumer_id.get_size_bytes() as usize;
+        let consumer = Consumer {
+            kind: consumer_kind,
+            id: consumer_id,
+        };
+        let stream_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += topic_id.get_size_bytes() as usize;
+        if bytes.len() < position + 12 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let partition_id = u32::from_le_bytes(bytes[position..position + 4].try_into()?);
+        let partition_id = if partition_id == 0 {
+            None
+        } else {
+            Some(partition_id)
+        };
+        let offset = u64::from_le_bytes(bytes[position + 4..position + 12].try_into()?);
+        let command = AckMessage {
+            consumer,
+            stream_id,
+            topic_id,
+            partition_id,
+            offset,
+        };
+        Ok(command)
+    }
+}
+
+impl Display for AckMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}|{}",
+            self.consumer,
+            self.stream_id,
+            self.topic_id,
+            self.partition_id.unwrap_or(0),
+            self.offset
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let command = AckMessage {
+            consumer: Consumer::group(Identifier::numeric(1).unwrap()),
+            stream_id: Identifier::numeric(2).unwrap(),
+            topic_id: Identifier::named("topic").unwrap(),
+            partition_id: Some(4),
+            offset: 5,
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = AckMessage::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
+
+    #[test]
+    fn should_not_be_valid_given_regular_consumer() {
+        let command = AckMessage {
+            consumer: Consumer::new(Identifier::numeric(1).unwrap()),
+            ..Default::default()
+        };
+
+        assert!(command.validate().is_err());
+    }
+}
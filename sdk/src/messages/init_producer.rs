@@ -0,0 +1,72 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::{Command, INIT_PRODUCER_CODE};
+use crate::error::IggyError;
+use crate::validatable::Validatable;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `InitProducer` command is used to obtain the unique producer ID for the idempotent producer.
+/// The producer stamps the messages with its ID and the per-partition sequence numbers (as the reserved headers),
+/// so that the messages which have already been appended to the partition are ignored when sent again, e.g. on retry.
+/// In response, the producer with its unique ID is returned.
+/// It has no additional payload.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct InitProducer {}
+
+impl Command for InitProducer {
+    fn code(&self) -> u32 {
+        INIT_PRODUCER_CODE
+    }
+}
+
+impl Validatable<IggyError> for InitProducer {
+    fn validate(&self) -> Result<(), IggyError> {
+        Ok(())
+    }
+}
+
+impl BytesSerializable for InitProducer {
+    fn to_bytes(&self) -> Bytes {
+        Bytes::new()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<InitProducer, IggyError> {
+        if !bytes.is_empty() {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let command = InitProducer {};
+        Ok(command)
+    }
+}
+
+impl Display for InitProducer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_serialized_as_empty_bytes() {
+        let command = InitProducer {};
+        let bytes = command.to_bytes();
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn should_be_deserialized_from_empty_bytes() {
+        let command = InitProducer::from_bytes(Bytes::new());
+        assert!(command.is_ok());
+    }
+
+    #[test]
+    fn should_not_be_deserialized_from_non_empty_bytes() {
+        let command = InitProducer::from_bytes(Bytes::from_static(&[0]));
+        assert!(command.is_err());
+    }
+}
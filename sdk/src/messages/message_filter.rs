@@ -0,0 +1,410 @@
+use crate::error::IggyError;
+use crate::models::header::{HeaderKey, HeaderKind, HeaderValue};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+const MAX_EXPRESSION_LENGTH: usize = 1000;
+
+/// `MessageFilter` is the expression evaluated by the server against the headers of the polled messages,
+/// so that only the matching messages are returned to the consumer.
+///
+/// The expression consists of the comparisons of the header values with the literals, e.g.
+/// `tenant == "acme" && priority >= 3`, which can be combined using `&&`, `||`, `!` and parentheses.
+/// The supported operators are `==`, `!=`, `>`, `>=`, `<` and `<=`.
+/// The literals can be strings (enclosed in single or double quotes), numbers and booleans (`true` or `false`).
+/// The comparison is typed, based on the `HeaderKind` of the header value:
+/// - `string` and `raw` values can be compared with the strings.
+/// - `bool` values can be compared with the booleans.
+/// - the integer and float values can be compared with the numbers.
+///
+/// The comparison with a missing header or with a literal of a different kind never matches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageFilter {
+    expression: String,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Comparison {
+        key: HeaderKey,
+        operator: Operator,
+        literal: Literal,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    String(String),
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Operator(Operator),
+    And,
+    Or,
+    Not,
+    LeftParenthesis,
+    RightParenthesis,
+}
+
+impl MessageFilter {
+    /// Returns the expression of the filter.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Checks whether the message with the provided headers matches the filter.
+    pub fn matches(&self, headers: Option<&HashMap<HeaderKey, HeaderValue>>) -> bool {
+        self.condition.evaluate(headers)
+    }
+}
+
+impl Condition {
+    fn evaluate(&self, headers: Option<&HashMap<HeaderKey, HeaderValue>>) -> bool {
+        match self {
+            Condition::And(left, right) => left.evaluate(headers) && right.evaluate(headers),
+            Condition::Or(left, right) => left.evaluate(headers) || right.evaluate(headers),
+            Condition::Not(condition) => !condition.evaluate(headers),
+            Condition::Comparison {
+                key,
+                operator,
+                literal,
+            } => headers
+                .and_then(|headers| headers.get(key))
+                .and_then(|value| compare(value, literal))
+                .is_some_and(|ordering| operator.is_satisfied_by(ordering)),
+        }
+    }
+}
+
+impl Operator {
+    fn is_satisfied_by(&self, ordering: Ordering) -> bool {
+        match self {
+            Operator::Equal => ordering == Ordering::Equal,
+            Operator::NotEqual => ordering != Ordering::Equal,
+            Operator::Greater => ordering == Ordering::Greater,
+            Operator::GreaterOrEqual => ordering != Ordering::Less,
+            Operator::Less => ordering == Ordering::Less,
+            Operator::LessOrEqual => ordering != Ordering::Greater,
+        }
+    }
+}
+
+fn compare(value: &HeaderValue, literal: &Literal) -> Option<Ordering> {
+    match (value.kind, literal) {
+        (HeaderKind::String, Literal::String(literal)) => {
+            Some(value.as_str().ok()?.cmp(literal.as_str()))
+        }
+        (HeaderKind::Raw, Literal::String(literal)) => {
+            Some(value.as_raw().ok()?.cmp(literal.as_bytes()))
+        }
+        (HeaderKind::Bool, Literal::Bool(literal)) => Some(value.as_bool().ok()?.cmp(literal)),
+        (HeaderKind::Float32, Literal::Integer(_) | Literal::Float(_)) => {
+            (value.as_float32().ok()? as f64).partial_cmp(&literal.as_f64()?)
+        }
+        (HeaderKind::Float64, Literal::Integer(_) | Literal::Float(_)) => {
+            value.as_float64().ok()?.partial_cmp(&literal.as_f64()?)
+        }
+        (_, Literal::Integer(literal)) => Some(as_integer(value)?.cmp(literal)),
+        (_, Literal::Float(literal)) => (as_integer(value)? as f64).partial_cmp(literal),
+        _ => None,
+    }
+}
+
+fn as_integer(value: &HeaderValue) -> Option<i128> {
+    match value.kind {
+        HeaderKind::Int8 => value.as_int8().ok().map(i128::from),
+        HeaderKind::Int16 => value.as_int16().ok().map(i128::from),
+        HeaderKind::Int32 => value.as_int32().ok().map(i128::from),
+        HeaderKind::Int64 => value.as_int64().ok().map(i128::from),
+        HeaderKind::Int128 => value.as_int128().ok(),
+        HeaderKind::Uint8 => value.as_uint8().ok().map(i128::from),
+        HeaderKind::Uint16 => value.as_uint16().ok().map(i128::from),
+        HeaderKind::Uint32 => value.as_uint32().ok().map(i128::from),
+        HeaderKind::Uint64 => value.as_uint64().ok().map(i128::from),
+        HeaderKind::Uint128 => value.as_uint128().ok()?.try_into().ok(),
+        _ => None,
+    }
+}
+
+impl Literal {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Literal::Integer(value) => Some(*value as f64),
+            Literal::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for MessageFilter {
+    type Err = IggyError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let expression = input.trim();
+        if expression.is_empty() || expression.len() > MAX_EXPRESSION_LENGTH {
+            return Err(IggyError::InvalidMessageFilter(expression.to_string()));
+        }
+
+        let tokens = tokenize(expression)?;
+        let mut parser = Parser {
+            tokens: tokens.into_iter().peekable(),
+            expression,
+        };
+        let condition = parser.parse_or()?;
+        if parser.tokens.peek().is_some() {
+            return Err(parser.error());
+        }
+
+        Ok(MessageFilter {
+            expression: expression.to_string(),
+            condition,
+        })
+    }
+}
+
+impl Display for MessageFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, IggyError> {
+    let error = || IggyError::InvalidMessageFilter(expression.to_string());
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(char) = chars.next() {
+        let token = match char {
+            char if char.is_whitespace() => continue,
+            '(' => Token::LeftParenthesis,
+            ')' => Token::RightParenthesis,
+            '&' if chars.next_if_eq(&'&').is_some() => Token::And,
+            '|' if chars.next_if_eq(&'|').is_some() => Token::Or,
+            '=' if chars.next_if_eq(&'=').is_some() => Token::Operator(Operator::Equal),
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Operator(Operator::NotEqual),
+            '!' => Token::Not,
+            '>' if chars.next_if_eq(&'=').is_some() => Token::Operator(Operator::GreaterOrEqual),
+            '>' => Token::Operator(Operator::Greater),
+            '<' if chars.next_if_eq(&'=').is_some() => Token::Operator(Operator::LessOrEqual),
+            '<' => Token::Operator(Operator::Less),
+            '"' | '\'' => Token::Quoted(read_quoted(&mut chars, char).ok_or_else(error)?),
+            char if is_word_char(char) => {
+                let mut word = char.to_string();
+                while let Some(char) = chars.next_if(|char| is_word_char(*char)) {
+                    word.push(char);
+                }
+                Token::Word(word)
+            }
+            _ => return Err(error()),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || matches!(char, '_' | '-' | '.' | '+')
+}
+
+fn read_quoted(chars: &mut Peekable<Chars>, quote: char) -> Option<String> {
+    let mut value = String::new();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => value.push(chars.next()?),
+            char if char == quote => return Some(value),
+            char => value.push(char),
+        }
+    }
+    None
+}
+
+struct Parser<'a> {
+    tokens: Peekable<std::vec::IntoIter<Token>>,
+    expression: &'a str,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> IggyError {
+        IggyError::InvalidMessageFilter(self.expression.to_string())
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, IggyError> {
+        let mut condition = self.parse_and()?;
+        while self.tokens.next_if_eq(&Token::Or).is_some() {
+            condition = Condition::Or(Box::new(condition), Box::new(self.parse_and()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, IggyError> {
+        let mut condition = self.parse_unary()?;
+        while self.tokens.next_if_eq(&Token::And).is_some() {
+            condition = Condition::And(Box::new(condition), Box::new(self.parse_unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition, IggyError> {
+        match self.tokens.next() {
+            Some(Token::Not) => Ok(Condition::Not(Box::new(self.parse_unary()?))),
+            Some(Token::LeftParenthesis) => {
+                let condition = self.parse_or()?;
+                match self.tokens.next() {
+                    Some(Token::RightParenthesis) => Ok(condition),
+                    _ => Err(self.error()),
+                }
+            }
+            Some(Token::Word(key)) | Some(Token::Quoted(key)) => self.parse_comparison(&key),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn parse_comparison(&mut self, key: &str) -> Result<Condition, IggyError> {
+        let key = HeaderKey::new(key)?;
+        let Some(Token::Operator(operator)) = self.tokens.next() else {
+            return Err(self.error());
+        };
+
+        let literal = match self.tokens.next() {
+            Some(Token::Quoted(value)) => Literal::String(value),
+            Some(Token::Word(value)) => match value.as_str() {
+                "true" => Literal::Bool(true),
+                "false" => Literal::Bool(false),
+                value => {
+                    if let Ok(value) = value.parse::<i128>() {
+                        Literal::Integer(value)
+                    } else if let Ok(value) = value.parse::<f64>() {
+                        Literal::Float(value)
+                    } else {
+                        return Err(self.error());
+                    }
+                }
+            },
+            _ => return Err(self.error()),
+        };
+
+        Ok(Condition::Comparison {
+            key,
+            operator,
+            literal,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_should_match_headers_of_different_kinds() {
+        let headers = create_headers();
+        for expression in [
+            r#"tenant == "acme""#,
+            "tenant == 'acme' && priority >= 3",
+            "priority > 2 && priority < 4 && priority != 5",
+            "urgent == true",
+            "score >= 0.5",
+            "(tenant == 'other' || region == 'eu') && !urgent == false",
+            "delta < 0",
+        ] {
+            let filter = MessageFilter::from_str(expression).unwrap();
+            assert!(filter.matches(Some(&headers)), "{expression}");
+        }
+    }
+
+    #[test]
+    fn filter_should_not_match_headers_with_different_values_kinds_or_missing_keys() {
+        let headers = create_headers();
+        for expression in [
+            "tenant == 'other'",
+            "tenant == 'acme' && priority > 3",
+            "priority == '3'",
+            "urgent == 1",
+            "missing == 1",
+            "missing != 1",
+        ] {
+            let filter = MessageFilter::from_str(expression).unwrap();
+            assert!(!filter.matches(Some(&headers)), "{expression}");
+        }
+
+        let filter = MessageFilter::from_str("tenant == 'acme'").unwrap();
+        assert!(!filter.matches(None));
+    }
+
+    #[test]
+    fn invalid_expression_should_not_be_parsed() {
+        for expression in [
+            "",
+            "tenant",
+            "tenant ==",
+            "tenant = 'acme'",
+            "tenant == acme",
+            "tenant == 'acme' &&",
+            "(tenant == 'acme'",
+            "tenant == 'acme')",
+            "tenant == 'acme",
+        ] {
+            assert!(MessageFilter::from_str(expression).is_err(), "{expression}");
+        }
+    }
+
+    #[test]
+    fn filter_should_be_displayed_as_its_expression() {
+        let filter = MessageFilter::from_str(" tenant == 'acme' && priority >= 3 ").unwrap();
+        assert_eq!(filter.to_string(), "tenant == 'acme' && priority >= 3");
+        assert_eq!(
+            MessageFilter::from_str(&filter.to_string()).unwrap(),
+            filter
+        );
+    }
+
+    fn create_headers() -> HashMap<HeaderKey, HeaderValue> {
+        HashMap::from([
+            (
+                HeaderKey::new("tenant").unwrap(),
+                HeaderValue::from_str("acme").unwrap(),
+            ),
+            (
+                HeaderKey::new("region").unwrap(),
+                HeaderValue::from_raw(b"eu").unwrap(),
+            ),
+            (
+                HeaderKey::new("priority").unwrap(),
+                HeaderValue::from_uint8(3).unwrap(),
+            ),
+            (
+                HeaderKey::new("delta").unwrap(),
+                HeaderValue::from_int64(-10).unwrap(),
+            ),
+            (
+                HeaderKey::new("urgent").unwrap(),
+                HeaderValue::from_bool(true).unwrap(),
+            ),
+            (
+                HeaderKey::new("score").unwrap(),
+                HeaderValue::from_float64(0.75).unwrap(),
+            ),
+        ])
+    }
+}
@@ -1,6 +1,12 @@
+pub mod ack_message;
 pub mod flush_unsaved_buffer;
+pub mod init_producer;
+pub mod message_filter;
+pub mod nack_message;
 pub mod poll_messages;
 pub mod send_messages;
 
 const MAX_HEADERS_SIZE: u32 = 100 * 1000;
 pub const MAX_PAYLOAD_SIZE: u32 = 10 * 1000 * 1000;
+/// The maximum size of the decompressed batch sent by the producer, including the metadata and the headers of the messages.
+const MAX_DECOMPRESSED_MESSAGES_SIZE: u32 = 2 * MAX_PAYLOAD_SIZE;
@@ -0,0 +1,194 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::{Command, NACK_MESSAGE_CODE};
+use crate::consumer::{Consumer, ConsumerKind};
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::from_utf8;
+
+const MAX_REASON_LENGTH: usize = 255;
+
+/// `NackMessage` command is used to negatively acknowledge the message which couldn't be processed by the consumer.
+/// Once the message is nacked the number of times configured for the dead letter queue of the topic,
+/// it's moved by the server to the dead letter queue topic.
+/// It has additional payload:
+/// - `consumer` - the consumer that is nacking the message, either the regular consumer or the consumer group.
+/// - `stream_id` - unique stream ID (numeric or name).
+/// - `topic_id` - unique topic ID (numeric or name).
+/// - `partition_id` - partition ID of the message. Has to be specified for the regular consumer. For consumer group it is ignored (use `None`).
+/// - `offset` - offset of the message.
+/// - `reason` - reason of the failed processing, max 255 characters.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct NackMessage {
+    /// The consumer that is nacking the message, either the regular consumer or the consumer group.
+    #[serde(flatten)]
+    pub consumer: Consumer,
+    /// Unique stream ID (numeric or name).
+    #[serde(skip)]
+    pub stream_id: Identifier,
+    /// Unique topic ID (numeric or name).
+    #[serde(skip)]
+    pub topic_id: Identifier,
+    /// Partition ID of the message. Has to be specified for the regular consumer. For consumer group it is ignored (use `None`).
+    pub partition_id: Option<u32>,
+    /// Offset of the message.
+    pub offset: u64,
+    /// Reason of the failed processing, max 255 characters.
+    pub reason: String,
+}
+
+impl Default for NackMessage {
+    fn default() -> Self {
+        NackMessage {
+            consumer: Consumer::default(),
+            stream_id: Identifier::default(),
+            topic_id: Identifier::default(),
+            partition_id: Some(1),
+            offset: 0,
+            reason: "unknown".to_string(),
+        }
+    }
+}
+
+impl Command for NackMessage {
+    fn code(&self) -> u32 {
+        NACK_MESSAGE_CODE
+    }
+}
+
+impl Validatable<IggyError> for NackMessage {
+    fn validate(&self) -> Result<(), IggyError> {
+        if self.reason.is_empty() || self.reason.len() > MAX_REASON_LENGTH {
+            return Err(IggyError::InvalidNackReason);
+        }
+
+        Ok(())
+    }
+}
+
+impl BytesSerializable for NackMessage {
+    fn to_bytes(&self) -> Bytes {
+        let consumer_bytes = self.consumer.to_bytes();
+        let stream_id_bytes = self.stream_id.to_bytes();
+        let topic_id_bytes = self.topic_id.to_bytes();
+        let mut bytes = BytesMut::with_capacity(
+            13 + consumer_bytes.len()
+                + stream_id_bytes.len()
+                + topic_id_bytes.len()
+                + self.reason.len(),
+        );
+        bytes.put_slice(&consumer_bytes);
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        if let Some(partition_id) = self.partition_id {
+            bytes.put_u32_le(partition_id);
+        } else {
+            bytes.put_u32_le(0);
+        }
+        bytes.put_u64_le(self.offset);
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.put_u8(self.reason.len() as u8);
+        bytes.put_slice(self.reason.as_bytes());
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<NackMessage, IggyError> {
+        if bytes.len() < 25 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = 0;
+        let consumer_kind = ConsumerKind::from_code(bytes[0])?;
+        let consumer_id = Identifier::from_bytes(bytes.slice(1..))?;
+        position += 1 + consumer_id.get_size_bytes() as usize;
+        let consumer = Consumer {
+            kind: consumer_kind,
+            id: consumer_id,
+        };
+        let stream_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += topic_id.get_size_bytes() as usize;
+        if bytes.len() < position + 13 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let partition_id = u32::from_le_bytes(bytes[position..position + 4].try_into()?);
+        let partition_id = if partition_id == 0 {
+            None
+        } else {
+            Some(partition_id)
+        };
+        let offset = u64::from_le_bytes(bytes[position + 4..position + 12].try_into()?);
+        let reason_length = bytes[position + 12] as usize;
+        position += 13;
+        if bytes.len() < position + reason_length {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let reason = from_utf8(&bytes[position..position + reason_length])?.to_string();
+        let command = NackMessage {
+            consumer,
+            stream_id,
+            topic_id,
+            partition_id,
+            offset,
+            reason,
+        };
+        Ok(command)
+    }
+}
+
+impl Display for NackMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}|{}|{}",
+            self.consumer,
+            self.stream_id,
+            self.topic_id,
+            self.partition_id.unwrap_or(0),
+            self.offset,
+            self.reason
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let command = NackMessage {
+            consumer: Consumer::new(Identifier::numeric(1).unwrap()),
+            stream_id: Identifier::numeric(2).unwrap(),
+            topic_id: Identifier::named("topic").unwrap(),
+            partition_id: Some(4),
+            offset: 5,
+            reason: "invalid payload".to_string(),
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = NackMessage::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
+
+    #[test]
+    fn should_not_be_valid_given_empty_or_too_long_reason() {
+        let mut command = NackMessage {
+            reason: "".to_string(),
+            ..Default::default()
+        };
+        assert!(command.validate().is_err());
+
+        command.reason = "a".repeat(MAX_REASON_LENGTH + 1);
+        assert!(command.validate().is_err());
+
+        command.reason = "a".repeat(MAX_REASON_LENGTH);
+        assert!(command.validate().is_ok());
+    }
+}
@@ -3,6 +3,7 @@ use crate::command::{Command, POLL_MESSAGES_CODE};
 use crate::consumer::{Consumer, ConsumerKind};
 use crate::error::IggyError;
 use crate::identifier::Identifier;
+use crate::messages::message_filter::MessageFilter;
 use crate::utils::timestamp::IggyTimestamp;
 use crate::validatable::Validatable;
 use bytes::{BufMut, Bytes, BytesMut};
@@ -20,6 +21,10 @@ use std::str::FromStr;
 /// - `strategy` - polling strategy which specifies from where to start polling messages.
 /// - `count` - number of messages to poll.
 /// - `auto_commit` - whether to commit offset on the server automatically after polling the messages.
+/// - `filter` - optional filter expression evaluated against the message headers, only the matching messages are returned.
+/// - `isolation` - isolation level which specifies whether the messages sent within the transactions are returned before they are committed.
+/// - `batched` - whether the messages are returned in the layout of the batches stored in the partition, which is used by the binary clients.
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct PollMessages {
     /// Consumer which will poll messages. Either regular consumer or consumer group.
@@ -43,6 +48,32 @@ pub struct PollMessages {
     #[serde(default)]
     /// Whether to commit offset on the server automatically after polling the messages.
     pub auto_commit: bool,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional filter expression evaluated against the message headers, only the matching messages are returned.
+    pub filter: Option<MessageFilter>,
+    #[serde(default)]
+    /// Isolation level which specifies whether the messages sent within the transactions are returned before they are committed.
+    pub isolation: IsolationLevel,
+    #[serde(skip)]
+    /// Whether the messages are returned in the layout of the batches stored in the partition,
+    /// so that the server can send them straight from the segment files. It's used by the binary clients.
+    pub batched: bool,
+}
+
+/// `IsolationLevel` specifies which messages sent within the transactions are returned to the consumer.
+/// It has the following kinds:
+/// - `ReadUncommitted` - all the messages are returned, regardless of the state of their transactions.
+/// - `ReadCommitted` - only the messages of the committed transactions are returned, and the polling stops
+///   before the first message of the transaction which is still open.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum IsolationLevel {
+    #[default]
+    /// All the messages are returned, regardless of the state of their transactions.
+    ReadUncommitted,
+    /// Only the messages of the committed transactions are returned.
+    ReadCommitted,
 }
 
 /// `PollingStrategy` specifies from where to start polling messages.
@@ -92,6 +123,9 @@ impl Default for PollMessages {
             strategy: default_strategy(),
             count: default_count(),
             auto_commit: false,
+            filter: None,
+            isolation: IsolationLevel::default(),
+            batched: false,
         }
     }
 }
@@ -211,6 +245,45 @@ impl PollingKind {
     }
 }
 
+impl IsolationLevel {
+    /// Returns code of the isolation level.
+    pub fn as_code(&self) -> u8 {
+        match self {
+            IsolationLevel::ReadUncommitted => 1,
+            IsolationLevel::ReadCommitted => 2,
+        }
+    }
+
+    /// Returns isolation level from the specified code.
+    pub fn from_code(code: u8) -> Result<Self, IggyError> {
+        match code {
+            1 => Ok(IsolationLevel::ReadUncommitted),
+            2 => Ok(IsolationLevel::ReadCommitted),
+            _ => Err(IggyError::InvalidCommand),
+        }
+    }
+}
+
+impl FromStr for IsolationLevel {
+    type Err = IggyError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "u" | "read_uncommitted" => Ok(IsolationLevel::ReadUncommitted),
+            "c" | "read_committed" => Ok(IsolationLevel::ReadCommitted),
+            _ => Err(IggyError::InvalidCommand),
+        }
+    }
+}
+
+impl Display for IsolationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IsolationLevel::ReadUncommitted => write!(f, "read_uncommitted"),
+            IsolationLevel::ReadCommitted => write!(f, "read_committed"),
+        }
+    }
+}
+
 impl FromStr for PollingKind {
     type Err = IggyError;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
@@ -247,6 +320,9 @@ impl BytesSerializable for PollMessages {
             &self.strategy,
             self.count,
             self.auto_commit,
+            self.filter.as_ref(),
+            self.isolation,
+            self.batched,
         )
     }
 
@@ -282,6 +358,39 @@ impl BytesSerializable for PollMessages {
         let count = u32::from_le_bytes(bytes[position + 8..position + 12].try_into()?);
         let auto_commit = bytes[position + 12];
         let auto_commit = matches!(auto_commit, 1);
+        position += 13;
+        // The filter, the isolation level and the batched layout flag are optional and appended at the end,
+        // so the commands sent by the older clients remain valid.
+        let mut filter = None;
+        let mut isolation = IsolationLevel::default();
+        let mut batched = false;
+        if bytes.len() > position {
+            if bytes.len() < position + 4 {
+                return Err(IggyError::InvalidCommand);
+            }
+
+            let filter_length =
+                u32::from_le_bytes(bytes[position..position + 4].try_into()?) as usize;
+            position += 4;
+            if bytes.len() < position + filter_length {
+                return Err(IggyError::InvalidCommand);
+            }
+
+            if filter_length > 0 {
+                let expression = std::str::from_utf8(&bytes[position..position + filter_length])?;
+                filter = Some(MessageFilter::from_str(expression)?);
+            }
+            position += filter_length;
+            match bytes.len() - position {
+                0 => {}
+                1 => isolation = IsolationLevel::from_code(bytes[position])?,
+                2 => {
+                    isolation = IsolationLevel::from_code(bytes[position])?;
+                    batched = matches!(bytes[position + 1], 1);
+                }
+                _ => return Err(IggyError::InvalidCommand),
+            }
+        }
         let command = PollMessages {
             consumer,
             stream_id,
@@ -290,12 +399,16 @@ impl BytesSerializable for PollMessages {
             strategy,
             count,
             auto_commit,
+            filter,
+            isolation,
+            batched,
         };
         Ok(command)
     }
 }
 
 // This method is used by the new version of `IggyClient` to serialize `PollMessages` without cloning the args.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn as_bytes(
     stream_id: &Identifier,
     topic_id: &Identifier,
@@ -304,6 +417,9 @@ pub(crate) fn as_bytes(
     strategy: &PollingStrategy,
     count: u32,
     auto_commit: bool,
+    filter: Option<&MessageFilter>,
+    isolation: IsolationLevel,
+    batched: bool,
 ) -> Bytes {
     let consumer_bytes = consumer.to_bytes();
     let stream_id_bytes = stream_id.to_bytes();
@@ -330,6 +446,19 @@ pub(crate) fn as_bytes(
     } else {
         bytes.put_u8(0);
     }
+    if let Some(filter) = filter {
+        let expression = filter.expression().as_bytes();
+        bytes.put_u32_le(expression.len() as u32);
+        bytes.put_slice(expression);
+    } else if isolation != IsolationLevel::ReadUncommitted || batched {
+        bytes.put_u32_le(0);
+    }
+    if isolation != IsolationLevel::ReadUncommitted || batched {
+        bytes.put_u8(isolation.as_code());
+    }
+    if batched {
+        bytes.put_u8(1);
+    }
 
     bytes.freeze()
 }
@@ -346,7 +475,17 @@ impl Display for PollMessages {
             self.strategy,
             self.count,
             auto_commit_to_string(self.auto_commit)
-        )
+        )?;
+        if let Some(filter) = &self.filter {
+            write!(f, "|{filter}")?;
+        }
+        if self.isolation != IsolationLevel::ReadUncommitted {
+            write!(f, "|{}", self.isolation)?;
+        }
+        if self.batched {
+            write!(f, "|batched")?;
+        }
+        Ok(())
     }
 }
 
@@ -398,6 +537,9 @@ mod tests {
             strategy: PollingStrategy::offset(2),
             count: 3,
             auto_commit: true,
+            filter: None,
+            isolation: IsolationLevel::default(),
+            batched: false,
         };
 
         let bytes = command.to_bytes();
@@ -477,4 +619,78 @@ mod tests {
         assert_eq!(command.count, count);
         assert_eq!(command.auto_commit, auto_commit);
     }
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes_with_filter() {
+        let command = PollMessages {
+            consumer: Consumer::new(Identifier::numeric(1).unwrap()),
+            stream_id: Identifier::numeric(2).unwrap(),
+            topic_id: Identifier::numeric(3).unwrap(),
+            partition_id: Some(4),
+            strategy: PollingStrategy::next(),
+            count: 3,
+            auto_commit: true,
+            filter: Some(MessageFilter::from_str("tenant == 'acme' && priority >= 3").unwrap()),
+            isolation: IsolationLevel::default(),
+            batched: false,
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = PollMessages::from_bytes(bytes.clone()).unwrap();
+        assert_eq!(deserialized_command, command);
+
+        let invalid_bytes = bytes.slice(..bytes.len() - 1);
+        assert!(PollMessages::from_bytes(invalid_bytes).is_err());
+    }
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes_with_isolation_level() {
+        let mut command = PollMessages {
+            consumer: Consumer::new(Identifier::numeric(1).unwrap()),
+            stream_id: Identifier::numeric(2).unwrap(),
+            topic_id: Identifier::numeric(3).unwrap(),
+            partition_id: Some(4),
+            strategy: PollingStrategy::next(),
+            count: 3,
+            auto_commit: true,
+            filter: None,
+            isolation: IsolationLevel::ReadCommitted,
+            batched: false,
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = PollMessages::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+
+        command.filter = Some(MessageFilter::from_str("tenant == 'acme'").unwrap());
+        let bytes = command.to_bytes();
+        let deserialized_command = PollMessages::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes_with_batched_layout() {
+        let mut command = PollMessages {
+            consumer: Consumer::new(Identifier::numeric(1).unwrap()),
+            stream_id: Identifier::numeric(2).unwrap(),
+            topic_id: Identifier::numeric(3).unwrap(),
+            partition_id: Some(4),
+            strategy: PollingStrategy::next(),
+            count: 3,
+            auto_commit: true,
+            filter: None,
+            isolation: IsolationLevel::ReadUncommitted,
+            batched: true,
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = PollMessages::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+
+        command.isolation = IsolationLevel::ReadCommitted;
+        command.filter = Some(MessageFilter::from_str("tenant == 'acme'").unwrap());
+        let bytes = command.to_bytes();
+        let deserialized_command = PollMessages::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
 }
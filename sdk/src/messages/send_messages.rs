@@ -1,10 +1,12 @@
 use crate::bytes_serializable::BytesSerializable;
 use crate::command::{Command, SEND_MESSAGES_CODE};
+use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::error::IggyError;
 use crate::identifier::Identifier;
-use crate::messages::{MAX_HEADERS_SIZE, MAX_PAYLOAD_SIZE};
+use crate::messages::{MAX_DECOMPRESSED_MESSAGES_SIZE, MAX_HEADERS_SIZE, MAX_PAYLOAD_SIZE};
 use crate::models::header;
 use crate::models::header::{HeaderKey, HeaderValue};
+use crate::utils::checksum;
 use crate::validatable::Validatable;
 use bytes::{BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
@@ -17,6 +19,13 @@ use std::str::FromStr;
 use uuid::Uuid;
 
 const EMPTY_KEY_VALUE: Vec<u8> = vec![];
+// The compressed messages frame starts with the bytes of an empty message (zero ID, headers and payload length),
+// which can never be sent as a plain message, so the server can tell both formats apart.
+const COMPRESSED_MESSAGES_MARKER: [u8; 24] = [0; 24];
+const COMPRESSED_MESSAGES_OVERHEAD: u32 = 24 + 1 + 4 + 4 + 4;
+// The acknowledgement mode other than the default one is sent in the frame preceding the messages,
+// starting with the same marker followed by the zero byte, which is never the code of the compression algorithm.
+const ACKS_FRAME_LENGTH: usize = 24 + 1 + 1;
 
 /// `SendMessages` command is used to send messages to a topic in a stream.
 /// It has additional payload:
@@ -24,6 +33,8 @@ const EMPTY_KEY_VALUE: Vec<u8> = vec![];
 /// - `topic_id` - unique topic ID (numeric or name).
 /// - `partitioning` - to which partition the messages should be sent - either provided by the client or calculated by the server.
 /// - `messages` - collection of messages to be sent.
+/// - `compressed_messages` - optional batch of messages compressed by the producer, sent instead of `messages`.
+/// - `acks` - which replicas of the partition have to store the messages before they're acknowledged.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct SendMessages {
     /// Unique stream ID (numeric or name).
@@ -36,6 +47,47 @@ pub struct SendMessages {
     pub partitioning: Partitioning,
     /// Collection of messages to be sent.
     pub messages: Vec<Message>,
+    /// Optional batch of messages compressed by the producer, sent instead of `messages`.
+    #[serde(skip)]
+    pub compressed_messages: Option<CompressedMessages>,
+    /// Which replicas of the partition have to store the messages before they're acknowledged.
+    #[serde(default)]
+    pub acks: Acks,
+}
+
+/// `Acks` specifies which replicas of the partition have to store the messages before they're acknowledged.
+/// It has the following kinds:
+/// - `Leader` - the messages are acknowledged once appended by the partition leader.
+/// - `All` - the messages are acknowledged once fetched by all the in-sync replicas of the partition.
+///
+/// Both kinds behave the same for the standalone server and the partitions which are not replicated.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Default, Copy, Clone, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Acks {
+    /// The messages are acknowledged once appended by the partition leader.
+    #[default]
+    Leader,
+    /// The messages are acknowledged once fetched by all the in-sync replicas of the partition.
+    All,
+}
+
+/// `CompressedMessages` is the batch of messages compressed by the producer and sent as a single frame.
+/// The server validates the frame by its header and checksum, stores it as-is and decompresses it only when the messages are read.
+/// It has the following payload:
+/// - `compression_algorithm` - the algorithm used to compress the messages.
+/// - `messages_count` - the number of messages in the batch.
+/// - `checksum` - the checksum of the compressed payload.
+/// - `payload` - the compressed messages, serialized the same way as the plain ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedMessages {
+    /// The algorithm used to compress the messages.
+    pub compression_algorithm: CompressionAlgorithm,
+    /// The number of messages in the batch.
+    pub messages_count: u32,
+    /// The checksum of the compressed payload.
+    pub checksum: u32,
+    /// The compressed messages.
+    pub payload: Bytes,
 }
 
 /// `Partitioning` is used to specify to which partition the messages should be sent.
@@ -115,6 +167,8 @@ impl Default for SendMessages {
             topic_id: Identifier::default(),
             partitioning: Partitioning::default(),
             messages: vec![Message::default()],
+            compressed_messages: None,
+            acks: Acks::default(),
         }
     }
 }
@@ -214,7 +268,7 @@ impl Command for SendMessages {
 
 impl Validatable<IggyError> for SendMessages {
     fn validate(&self) -> Result<(), IggyError> {
-        if self.messages.is_empty() {
+        if self.messages.is_empty() && self.compressed_messages.is_none() {
             return Err(IggyError::InvalidMessagesCount);
         }
 
@@ -225,29 +279,43 @@ impl Validatable<IggyError> for SendMessages {
             return Err(IggyError::InvalidKeyValueLength);
         }
 
-        let mut headers_size = 0;
-        let mut payload_size = 0;
-        for message in &self.messages {
-            if let Some(headers) = &message.headers {
-                for value in headers.values() {
-                    headers_size += value.value.len() as u32;
-                    if headers_size > MAX_HEADERS_SIZE {
-                        return Err(IggyError::TooBigHeadersPayload);
-                    }
-                }
-            }
-            payload_size += message.payload.len() as u32;
-            if payload_size > MAX_PAYLOAD_SIZE {
-                return Err(IggyError::TooBigMessagePayload);
+        if let Some(compressed_messages) = &self.compressed_messages {
+            if !self.messages.is_empty() {
+                return Err(IggyError::InvalidMessagesCount);
             }
+
+            return compressed_messages.validate();
         }
 
-        if payload_size == 0 {
-            return Err(IggyError::EmptyMessagePayload);
+        validate_messages_size(&self.messages)
+    }
+}
+
+/// Validates the total size of the payloads and the headers of the messages sent in a single batch.
+fn validate_messages_size(messages: &[Message]) -> Result<(), IggyError> {
+    let mut headers_size = 0;
+    let mut payload_size = 0;
+    for message in messages {
+        if let Some(headers) = &message.headers {
+            for value in headers.values() {
+                headers_size += value.value.len() as u32;
+                if headers_size > MAX_HEADERS_SIZE {
+                    return Err(IggyError::TooBigHeadersPayload);
+                }
+            }
+        }
+        payload_size += message.payload.len() as u32;
+        if payload_size > MAX_PAYLOAD_SIZE {
+            return Err(IggyError::TooBigMessagePayload);
         }
+    }
 
-        Ok(())
+    // The empty payload is allowed along with the headers, e.g. for the tombstone of the message key in the compacted topic.
+    if payload_size == 0 && headers_size == 0 {
+        return Err(IggyError::EmptyMessagePayload);
     }
+
+    Ok(())
 }
 
 impl PartitioningKind {
@@ -271,6 +339,48 @@ impl PartitioningKind {
     }
 }
 
+impl Acks {
+    /// Get the code of the acknowledgement mode.
+    pub fn as_code(&self) -> u8 {
+        match self {
+            Acks::Leader => 1,
+            Acks::All => 2,
+        }
+    }
+
+    /// Get the acknowledgement mode from the provided code.
+    pub fn from_code(code: u8) -> Result<Self, IggyError> {
+        match code {
+            1 => Ok(Acks::Leader),
+            2 => Ok(Acks::All),
+            _ => Err(IggyError::InvalidCommand),
+        }
+    }
+
+    fn is_acks_frame(bytes: &[u8]) -> bool {
+        bytes.len() >= ACKS_FRAME_LENGTH
+            && bytes[..COMPRESSED_MESSAGES_MARKER.len()] == COMPRESSED_MESSAGES_MARKER
+            && bytes[COMPRESSED_MESSAGES_MARKER.len()] == 0
+    }
+
+    fn put_frame(&self, bytes: &mut BytesMut) {
+        if *self == Acks::Leader {
+            return;
+        }
+
+        bytes.put_slice(&COMPRESSED_MESSAGES_MARKER);
+        bytes.put_u8(0);
+        bytes.put_u8(self.as_code());
+    }
+
+    fn get_frame_size_bytes(&self) -> usize {
+        match self {
+            Acks::Leader => 0,
+            Acks::All => ACKS_FRAME_LENGTH,
+        }
+    }
+}
+
 impl Message {
     /// Create a new message with the optional ID, payload and headers.
     pub fn new(
@@ -324,6 +434,162 @@ impl Display for Message {
     }
 }
 
+impl CompressedMessages {
+    /// Compress the provided messages into a single batch using the specified algorithm.
+    /// The missing message IDs are generated before the compression, as the server doesn't look into the batch.
+    pub fn compress(
+        compression_algorithm: CompressionAlgorithm,
+        messages: &[Message],
+    ) -> Result<Self, IggyError> {
+        if compression_algorithm.is_none() || messages.is_empty() {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let messages_size = messages.iter().map(Message::get_size_bytes).sum::<u32>();
+        let mut bytes = BytesMut::with_capacity(messages_size as usize);
+        for message in messages {
+            if message.id != 0 {
+                bytes.put_slice(&message.to_bytes());
+                continue;
+            }
+
+            let mut message = message.clone();
+            message.id = Uuid::now_v7().to_u128_le();
+            bytes.put_slice(&message.to_bytes());
+        }
+
+        let payload = compression_algorithm.compress(&bytes)?;
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(CompressedMessages::new(
+            compression_algorithm,
+            messages.len() as u32,
+            Bytes::from(payload),
+        ))
+    }
+
+    /// Creates the batch of the already compressed messages, calculating the checksum of the payload.
+    pub fn new(
+        compression_algorithm: CompressionAlgorithm,
+        messages_count: u32,
+        payload: Bytes,
+    ) -> Self {
+        CompressedMessages {
+            compression_algorithm,
+            messages_count,
+            checksum: checksum::calculate(&payload),
+            payload,
+        }
+    }
+
+    /// Validates the batch by its header and checksum, without decompressing it.
+    /// The messages themselves are validated once decompressed, when they're read.
+    pub fn validate(&self) -> Result<(), IggyError> {
+        if self.messages_count == 0 {
+            return Err(IggyError::InvalidMessagesCount);
+        }
+
+        if self.payload.is_empty() {
+            return Err(IggyError::EmptyMessagePayload);
+        }
+
+        if self.payload.len() as u32 > MAX_PAYLOAD_SIZE {
+            return Err(IggyError::TooBigMessagePayload);
+        }
+
+        let checksum = checksum::calculate(&self.payload);
+        if checksum != self.checksum {
+            return Err(IggyError::InvalidCompressedMessagesChecksum(
+                checksum,
+                self.checksum,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Decompress the batch into the messages it contains. The batch is rejected if the decompressed messages
+    /// don't match the declared count or exceed the limits of the plain batch, as it's sent by the untrusted client.
+    pub fn decompress(&self) -> Result<Vec<Message>, IggyError> {
+        if self.messages_count == 0 {
+            return Err(IggyError::InvalidMessagesCount);
+        }
+
+        let bytes = Bytes::from(
+            self.compression_algorithm
+                .decompress_with_limit(&self.payload, MAX_DECOMPRESSED_MESSAGES_SIZE as usize)?,
+        );
+        let mut messages = Vec::with_capacity(self.messages_count as usize);
+        let mut position = 0;
+        while position < bytes.len() {
+            let message = Message::from_bytes(bytes.slice(position..))?;
+            position += message.get_size_bytes() as usize;
+            messages.push(message);
+        }
+
+        if messages.len() != self.messages_count as usize {
+            return Err(IggyError::CannotDecompressData(self.compression_algorithm));
+        }
+
+        validate_messages_size(&messages)?;
+        Ok(messages)
+    }
+
+    /// Get the size of the compressed messages frame in bytes.
+    pub fn get_size_bytes(&self) -> u32 {
+        COMPRESSED_MESSAGES_OVERHEAD + self.payload.len() as u32
+    }
+
+    fn is_compressed_frame(bytes: &[u8]) -> bool {
+        bytes.len() >= COMPRESSED_MESSAGES_MARKER.len()
+            && bytes[..COMPRESSED_MESSAGES_MARKER.len()] == COMPRESSED_MESSAGES_MARKER
+    }
+}
+
+impl BytesSerializable for CompressedMessages {
+    fn to_bytes(&self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(self.get_size_bytes() as usize);
+        bytes.put_slice(&COMPRESSED_MESSAGES_MARKER);
+        bytes.put_u8(self.compression_algorithm.as_code());
+        bytes.put_u32_le(self.messages_count);
+        bytes.put_u32_le(self.payload.len() as u32);
+        bytes.put_u32_le(self.checksum);
+        bytes.put_slice(&self.payload);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<Self, IggyError> {
+        if bytes.len() < COMPRESSED_MESSAGES_OVERHEAD as usize || !Self::is_compressed_frame(&bytes)
+        {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = COMPRESSED_MESSAGES_MARKER.len();
+        let compression_algorithm = CompressionAlgorithm::from_code(bytes[position])?;
+        if compression_algorithm.is_none() {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        position += 1;
+        let messages_count = u32::from_le_bytes(bytes[position..position + 4].try_into()?);
+        position += 4;
+        let payload_length = u32::from_le_bytes(bytes[position..position + 4].try_into()?);
+        position += 4;
+        let checksum = u32::from_le_bytes(bytes[position..position + 4].try_into()?);
+        position += 4;
+        let payload = bytes.slice(position..);
+        if payload.len() != payload_length as usize {
+            return Err(IggyError::InvalidMessagePayloadLength);
+        }
+
+        Ok(CompressedMessages {
+            compression_algorithm,
+            messages_count,
+            checksum,
+            payload,
+        })
+    }
+}
+
 impl BytesSerializable for Partitioning {
     fn to_bytes(&self) -> Bytes {
         let mut bytes = BytesMut::with_capacity(2 + self.length as usize);
@@ -382,6 +648,10 @@ impl BytesSerializable for Message {
             id = Uuid::now_v7().to_u128_le();
         }
         let headers_length = u32::from_le_bytes(bytes[16..20].try_into()?);
+        if bytes.len() < 24 + headers_length as usize {
+            return Err(IggyError::InvalidCommand);
+        }
+
         let headers = if headers_length > 0 {
             Some(HashMap::from_bytes(
                 bytes.slice(20..20 + headers_length as usize),
@@ -393,16 +663,17 @@ impl BytesSerializable for Message {
         let payload_length = u32::from_le_bytes(
             bytes[20 + headers_length as usize..24 + headers_length as usize].try_into()?,
         );
-        if payload_length == 0 {
+        if payload_length == 0 && headers.is_none() {
             return Err(IggyError::EmptyMessagePayload);
         }
 
+        if bytes.len() < 24 + headers_length as usize + payload_length as usize {
+            return Err(IggyError::InvalidMessagePayloadLength);
+        }
+
         let payload = bytes.slice(
             24 + headers_length as usize..24 + headers_length as usize + payload_length as usize,
         );
-        if payload.len() != payload_length as usize {
-            return Err(IggyError::InvalidMessagePayloadLength);
-        }
 
         Ok(Message {
             id,
@@ -418,6 +689,7 @@ pub(crate) fn as_bytes(
     stream_id: &Identifier,
     topic_id: &Identifier,
     partitioning: &Partitioning,
+    acks: Acks,
     messages: &[Message],
 ) -> Bytes {
     let messages_size = messages.iter().map(Message::get_size_bytes).sum::<u32>();
@@ -425,11 +697,16 @@ pub(crate) fn as_bytes(
     let stream_id_bytes = stream_id.to_bytes();
     let topic_id_bytes = topic_id.to_bytes();
     let mut bytes = BytesMut::with_capacity(
-        stream_id_bytes.len() + topic_id_bytes.len() + key_bytes.len() + messages_size as usize,
+        stream_id_bytes.len()
+            + topic_id_bytes.len()
+            + key_bytes.len()
+            + acks.get_frame_size_bytes()
+            + messages_size as usize,
     );
     bytes.put_slice(&stream_id_bytes);
     bytes.put_slice(&topic_id_bytes);
     bytes.put_slice(&key_bytes);
+    acks.put_frame(&mut bytes);
     for message in messages {
         bytes.put_slice(&message.to_bytes());
     }
@@ -437,6 +714,32 @@ pub(crate) fn as_bytes(
     bytes.freeze()
 }
 
+// This method is used by the binary clients to serialize `SendMessages` with the messages compressed by the producer.
+pub(crate) fn as_compressed_bytes(
+    stream_id: &Identifier,
+    topic_id: &Identifier,
+    partitioning: &Partitioning,
+    acks: Acks,
+    compressed_messages: &CompressedMessages,
+) -> Bytes {
+    let key_bytes = partitioning.to_bytes();
+    let stream_id_bytes = stream_id.to_bytes();
+    let topic_id_bytes = topic_id.to_bytes();
+    let mut bytes = BytesMut::with_capacity(
+        stream_id_bytes.len()
+            + topic_id_bytes.len()
+            + key_bytes.len()
+            + acks.get_frame_size_bytes()
+            + compressed_messages.get_size_bytes() as usize,
+    );
+    bytes.put_slice(&stream_id_bytes);
+    bytes.put_slice(&topic_id_bytes);
+    bytes.put_slice(&key_bytes);
+    acks.put_frame(&mut bytes);
+    bytes.put_slice(&compressed_messages.to_bytes());
+    bytes.freeze()
+}
+
 impl FromStr for Message {
     type Err = IggyError;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
@@ -458,10 +761,21 @@ impl FromStr for Message {
 
 impl BytesSerializable for SendMessages {
     fn to_bytes(&self) -> Bytes {
+        if let Some(compressed_messages) = &self.compressed_messages {
+            return as_compressed_bytes(
+                &self.stream_id,
+                &self.topic_id,
+                &self.partitioning,
+                self.acks,
+                compressed_messages,
+            );
+        }
+
         as_bytes(
             &self.stream_id,
             &self.topic_id,
             &self.partitioning,
+            self.acks,
             &self.messages,
         )
     }
@@ -478,7 +792,24 @@ impl BytesSerializable for SendMessages {
         position += topic_id.get_size_bytes() as usize;
         let key = Partitioning::from_bytes(bytes.slice(position..))?;
         position += key.get_size_bytes() as usize;
+        let mut acks = Acks::Leader;
+        if Acks::is_acks_frame(&bytes[position..]) {
+            acks = Acks::from_code(bytes[position + ACKS_FRAME_LENGTH - 1])?;
+            position += ACKS_FRAME_LENGTH;
+        }
+
         let messages_payloads = bytes.slice(position..);
+        if CompressedMessages::is_compressed_frame(&messages_payloads) {
+            return Ok(SendMessages {
+                stream_id,
+                topic_id,
+                partitioning: key,
+                messages: Vec::new(),
+                compressed_messages: Some(CompressedMessages::from_bytes(messages_payloads)?),
+                acks,
+            });
+        }
+
         position = 0;
         let mut messages = Vec::new();
         while position < messages_payloads.len() {
@@ -492,6 +823,8 @@ impl BytesSerializable for SendMessages {
             topic_id,
             partitioning: key,
             messages,
+            compressed_messages: None,
+            acks,
         };
         Ok(command)
     }
@@ -499,6 +832,14 @@ impl BytesSerializable for SendMessages {
 
 impl Display for SendMessages {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(compressed_messages) = &self.compressed_messages {
+            return write!(
+                f,
+                "{}|{}|{}|{}",
+                self.stream_id, self.topic_id, self.partitioning, compressed_messages
+            );
+        }
+
         write!(
             f,
             "{}|{}|{}|{}",
@@ -514,6 +855,18 @@ impl Display for SendMessages {
     }
 }
 
+impl Display for CompressedMessages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}",
+            self.compression_algorithm,
+            self.messages_count,
+            self.payload.len()
+        )
+    }
+}
+
 impl Display for Partitioning {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.kind {
@@ -531,6 +884,15 @@ impl Display for Partitioning {
     }
 }
 
+impl Display for Acks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Acks::Leader => write!(f, "leader"),
+            Acks::All => write!(f, "all"),
+        }
+    }
+}
+
 impl Display for PartitioningKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -556,6 +918,8 @@ mod tests {
             topic_id: Identifier::numeric(2).unwrap(),
             partitioning: Partitioning::partition_id(4),
             messages,
+            compressed_messages: None,
+            acks: Acks::default(),
         };
 
         let bytes = command.to_bytes();
@@ -634,6 +998,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn message_with_empty_payload_should_be_valid_only_with_headers() {
+        let headers = HashMap::from([(
+            HeaderKey::new("key").unwrap(),
+            HeaderValue::from_str("value").unwrap(),
+        )]);
+        let mut command = SendMessages {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::numeric(2).unwrap(),
+            partitioning: Partitioning::partition_id(1),
+            messages: vec![Message::new(Some(1), Bytes::new(), Some(headers))],
+            compressed_messages: None,
+            acks: Acks::default(),
+        };
+        assert!(command.validate().is_ok());
+        let deserialized_command = SendMessages::from_bytes(command.to_bytes()).unwrap();
+        assert_eq!(
+            deserialized_command.messages[0].headers,
+            command.messages[0].headers
+        );
+        assert!(deserialized_command.messages[0].payload.is_empty());
+
+        command.messages[0].headers = None;
+        assert!(command.validate().is_err());
+    }
+
     #[test]
     fn key_of_type_balanced_should_have_empty_value() {
         let key = Partitioning::balanced();
@@ -685,4 +1075,125 @@ mod tests {
         let key = Partitioning::messages_key_str(&messages_key);
         assert!(key.is_err());
     }
+
+    #[test]
+    fn compressed_messages_should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let messages = vec![
+            Message::from_str("hello 1").unwrap(),
+            Message::new(Some(2), "hello 2".into(), None),
+            Message::new(Some(3), "hello 3".into(), None),
+        ];
+        let compressed_messages =
+            CompressedMessages::compress(CompressionAlgorithm::Lz4, &messages).unwrap();
+        let command = SendMessages {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::numeric(2).unwrap(),
+            partitioning: Partitioning::partition_id(4),
+            messages: Vec::new(),
+            compressed_messages: Some(compressed_messages),
+            acks: Acks::default(),
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = SendMessages::from_bytes(bytes).unwrap();
+
+        assert!(deserialized_command.validate().is_ok());
+        assert_eq!(deserialized_command, command);
+        let decompressed_messages = deserialized_command
+            .compressed_messages
+            .unwrap()
+            .decompress()
+            .unwrap();
+        assert_eq!(decompressed_messages.len(), messages.len());
+        assert_ne!(decompressed_messages[0].id, 0);
+        for (index, message) in messages.iter().enumerate() {
+            let decompressed_message = &decompressed_messages[index];
+            if message.id != 0 {
+                assert_eq!(decompressed_message.id, message.id);
+            }
+            assert_eq!(decompressed_message.payload, message.payload);
+        }
+    }
+
+    #[test]
+    fn compressed_messages_with_invalid_checksum_should_be_rejected() {
+        let messages = vec![Message::new(Some(1), "hello 1".into(), None)];
+        let mut compressed_messages =
+            CompressedMessages::compress(CompressionAlgorithm::Zstd, &messages).unwrap();
+        assert!(compressed_messages.validate().is_ok());
+
+        let mut payload = compressed_messages.payload.to_vec();
+        payload[0] ^= 1;
+        compressed_messages.payload = Bytes::from(payload);
+
+        assert!(matches!(
+            compressed_messages.validate(),
+            Err(IggyError::InvalidCompressedMessagesChecksum(_, _))
+        ));
+    }
+
+    #[test]
+    fn compressed_messages_with_invalid_count_should_not_be_decompressed() {
+        let messages = vec![
+            Message::new(Some(1), "hello 1".into(), None),
+            Message::new(Some(2), "hello 2".into(), None),
+        ];
+        let mut compressed_messages =
+            CompressedMessages::compress(CompressionAlgorithm::Zstd, &messages).unwrap();
+
+        compressed_messages.messages_count = 3;
+        assert!(compressed_messages.decompress().is_err());
+        compressed_messages.messages_count = 0;
+        assert!(compressed_messages.decompress().is_err());
+    }
+
+    #[test]
+    fn compressed_messages_with_truncated_payload_should_not_be_decompressed() {
+        let message = Message::new(Some(1), "hello 1".into(), None);
+        let bytes = message.to_bytes();
+        let payload = CompressionAlgorithm::Snappy
+            .compress(&bytes[..bytes.len() - 1])
+            .unwrap();
+        let compressed_messages =
+            CompressedMessages::new(CompressionAlgorithm::Snappy, 1, Bytes::from(payload));
+
+        assert!(compressed_messages.decompress().is_err());
+    }
+
+    #[test]
+    fn compressed_messages_exceeding_decompressed_size_limit_should_not_be_decompressed() {
+        let payload = vec![0; MAX_DECOMPRESSED_MESSAGES_SIZE as usize + 1];
+        let compressed_messages = CompressedMessages::new(
+            CompressionAlgorithm::Zstd,
+            1,
+            Bytes::from(CompressionAlgorithm::Zstd.compress(&payload).unwrap()),
+        );
+
+        assert!(compressed_messages.decompress().is_err());
+    }
+
+    #[test]
+    fn acks_of_all_replicas_should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let messages = vec![
+            Message::new(Some(1), "hello 1".into(), None),
+            Message::new(Some(2), "hello 2".into(), None),
+        ];
+        let mut command = SendMessages {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::numeric(2).unwrap(),
+            partitioning: Partitioning::partition_id(4),
+            messages: messages.clone(),
+            compressed_messages: None,
+            acks: Acks::All,
+        };
+
+        let deserialized_command = SendMessages::from_bytes(command.to_bytes()).unwrap();
+        assert_eq!(deserialized_command, command);
+
+        command.messages = Vec::new();
+        command.compressed_messages =
+            Some(CompressedMessages::compress(CompressionAlgorithm::Gzip, &messages).unwrap());
+        let deserialized_command = SendMessages::from_bytes(command.to_bytes()).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
 }
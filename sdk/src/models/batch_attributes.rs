@@ -0,0 +1,91 @@
+use crate::compression::compression_algorithm::CompressionAlgorithm;
+use crate::error::IggyError;
+use bytes::Bytes;
+
+/// The size of the batch header stored in the segment: base offset, length, last offset delta and max timestamp.
+pub const BATCH_HEADER_SIZE: usize = 8 + 4 + 4 + 8;
+/// Batches with attributes (e.g. compressed ones) start their payload with a zero message length,
+/// which can never occur for a plain batch, followed by the attributes byte.
+/// This way segments written before the batch attributes were introduced remain readable as they are.
+pub const BATCH_ATTRIBUTES_MARKER: u32 = 0;
+/// The size of the marker and the attributes byte preceding the payload of the batch with attributes.
+pub const BATCH_ATTRIBUTES_PREFIX_SIZE: usize = 4 + 1;
+/// The size of the timestamp assigned by the server to each message of the producer-compressed batch.
+/// The timestamps precede the compressed messages, as the frame sent by the producer is stored as it is.
+pub const PRODUCER_COMPRESSED_TIMESTAMP_SIZE: usize = 8;
+const COMPRESSION_ATTRIBUTE_MASK: u8 = 0b0000_0111;
+// The batch has been compressed by the producer and contains the messages in the format they were sent in.
+const PRODUCER_COMPRESSION_ATTRIBUTE: u8 = 0b0000_1000;
+
+/// The attributes of the batch of messages, as stored in the segment and returned by the binary transports.
+/// It consists of the following fields:
+/// - `compression_algorithm`: the algorithm used to compress the payload of the batch.
+/// - `producer_compressed`: whether the payload contains the messages compressed by the producer, in the format they were sent in.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BatchAttributes {
+    /// The algorithm used to compress the payload of the batch.
+    pub compression_algorithm: CompressionAlgorithm,
+    /// Whether the payload contains the messages compressed by the producer, in the format they were sent in.
+    pub producer_compressed: bool,
+}
+
+impl BatchAttributes {
+    /// Returns `true` if the batch has no attributes, i.e. its payload is plain.
+    pub fn is_empty(&self) -> bool {
+        self.compression_algorithm.is_none()
+    }
+
+    /// Returns the code of the batch attributes.
+    pub fn as_code(&self) -> u8 {
+        let code = match self.compression_algorithm {
+            CompressionAlgorithm::None => 0,
+            algorithm => algorithm.as_code() & COMPRESSION_ATTRIBUTE_MASK,
+        };
+        if self.producer_compressed {
+            return code | PRODUCER_COMPRESSION_ATTRIBUTE;
+        }
+
+        code
+    }
+
+    /// Returns the batch attributes from the code.
+    pub fn from_code(code: u8) -> Result<Self, IggyError> {
+        let compression_algorithm = match code & COMPRESSION_ATTRIBUTE_MASK {
+            0 => CompressionAlgorithm::None,
+            compression_code => CompressionAlgorithm::from_code(compression_code)?,
+        };
+        Ok(BatchAttributes {
+            compression_algorithm,
+            producer_compressed: code & PRODUCER_COMPRESSION_ATTRIBUTE != 0,
+        })
+    }
+
+    /// Returns the attributes of the batch with the given payload, which are empty for the plain batch.
+    pub fn from_payload(payload: &[u8]) -> Result<Self, IggyError> {
+        if payload.len() < BATCH_ATTRIBUTES_PREFIX_SIZE
+            || u32::from_le_bytes(payload[..4].try_into()?) != BATCH_ATTRIBUTES_MARKER
+        {
+            return Ok(BatchAttributes::default());
+        }
+
+        BatchAttributes::from_code(payload[4])
+    }
+}
+
+/// Splits the payload of the producer-compressed batch following the attributes prefix
+/// into the timestamps of its messages and the compressed messages.
+pub fn split_producer_compressed_payload(
+    payload: &Bytes,
+    messages_count: u32,
+) -> Result<(Vec<u64>, Bytes), IggyError> {
+    let timestamps_size = messages_count as usize * PRODUCER_COMPRESSED_TIMESTAMP_SIZE;
+    if payload.len() < timestamps_size {
+        return Err(IggyError::CannotReadMessageTimestamp);
+    }
+
+    let timestamps = payload[..timestamps_size]
+        .chunks_exact(PRODUCER_COMPRESSED_TIMESTAMP_SIZE)
+        .map(|timestamp| Ok(u64::from_le_bytes(timestamp.try_into()?)))
+        .collect::<Result<Vec<_>, IggyError>>()?;
+    Ok((timestamps, payload.slice(timestamps_size..)))
+}
@@ -0,0 +1,115 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::error::IggyError;
+use crate::utils::duration::IggyDuration;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// The header containing the key of the message, used by the log compaction to keep only the newest message per key.
+pub const MESSAGE_KEY_HEADER: &str = "iggy-message-key";
+
+/// `CleanupPolicy` represents the policy used by the server to clean up the closed segments of the topic.
+/// It has the following variants:
+/// - `Delete`: the whole segments are deleted once they expire, or the topic reaches its maximum size.
+/// - `Compact`: the closed segments are rewritten keeping only the newest message for each key (`iggy-message-key` header).
+///   The message with the empty payload (tombstone) deletes the key, and is removed itself once `tombstone_retention` passes.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CleanupPolicy {
+    /// Delete the whole segments, based on the message expiry and the maximum topic size.
+    #[default]
+    Delete,
+    /// Keep only the newest message for each key in the closed segments.
+    Compact {
+        /// The duration after which the tombstone (message with the empty payload) is removed.
+        tombstone_retention: IggyDuration,
+    },
+}
+
+impl CleanupPolicy {
+    /// Returns the code of the cleanup policy.
+    pub fn as_code(&self) -> u8 {
+        match self {
+            CleanupPolicy::Delete => 1,
+            CleanupPolicy::Compact { .. } => 2,
+        }
+    }
+
+    /// Checks whether the closed segments of the topic should be compacted.
+    pub fn is_compact(&self) -> bool {
+        matches!(self, CleanupPolicy::Compact { .. })
+    }
+}
+
+impl BytesSerializable for CleanupPolicy {
+    fn to_bytes(&self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(9);
+        bytes.put_u8(self.as_code());
+        if let CleanupPolicy::Compact {
+            tombstone_retention,
+        } = self
+        {
+            bytes.put_u64_le(tombstone_retention.as_micros());
+        }
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<CleanupPolicy, IggyError> {
+        if bytes.is_empty() {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        match bytes[0] {
+            1 => Ok(CleanupPolicy::Delete),
+            2 => {
+                if bytes.len() < 9 {
+                    return Err(IggyError::InvalidCommand);
+                }
+
+                let tombstone_retention = u64::from_le_bytes(bytes[1..9].try_into()?);
+                Ok(CleanupPolicy::Compact {
+                    tombstone_retention: tombstone_retention.into(),
+                })
+            }
+            _ => Err(IggyError::InvalidCommand),
+        }
+    }
+}
+
+impl Display for CleanupPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CleanupPolicy::Delete => write!(f, "delete"),
+            CleanupPolicy::Compact {
+                tombstone_retention,
+            } => write!(f, "compact|{tombstone_retention}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let policies = [
+            CleanupPolicy::Delete,
+            CleanupPolicy::Compact {
+                tombstone_retention: IggyDuration::new(Duration::from_secs(60)),
+            },
+        ];
+
+        for policy in policies {
+            let deserialized_policy = CleanupPolicy::from_bytes(policy.to_bytes()).unwrap();
+            assert_eq!(deserialized_policy, policy);
+        }
+    }
+
+    #[test]
+    fn should_not_be_deserialized_given_unknown_code() {
+        assert!(CleanupPolicy::from_bytes(Bytes::from_static(&[3])).is_err());
+        assert!(CleanupPolicy::from_bytes(Bytes::from_static(&[2, 0])).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// `ClusterMetadata` represents the state of the cluster as seen by the node which returned it.
+/// It consists of the following fields:
+/// - `node_id`: the unique identifier of the node which returned the metadata.
+/// - `term`: the current election term.
+/// - `leader_id`: the unique identifier of the current leader, if it's known.
+/// - `nodes`: the collection of the cluster members.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ClusterMetadata {
+    /// The unique identifier of the node which returned the metadata.
+    pub node_id: u32,
+    /// The current election term.
+    pub term: u64,
+    /// The unique identifier of the current leader, if it's known.
+    pub leader_id: Option<u32>,
+    /// The collection of the cluster members.
+    pub nodes: Vec<ClusterNode>,
+}
+
+/// `ClusterNode` represents the member of the cluster.
+/// It consists of the following fields:
+/// - `id`: the unique identifier of the node.
+/// - `address`: the address on which the node accepts the cluster connections.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ClusterNode {
+    /// The unique identifier of the node.
+    pub id: u32,
+    /// The address on which the node accepts the cluster connections.
+    pub address: String,
+}
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 /// - `name`: the name of the consumer group.
 /// - `partitions_count`: the number of partitions the consumer group is consuming.
 /// - `members_count`: the number of members in the consumer group.
+/// - `generation`: the generation of the consumer group, incremented on each rebalance.
+/// - `evicted_members_count`: the number of members evicted from the consumer group due to the expired session.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConsumerGroup {
     /// The unique identifier (numeric) of the consumer group.
@@ -16,6 +18,12 @@ pub struct ConsumerGroup {
     pub partitions_count: u32,
     /// The number of members in the consumer group.
     pub members_count: u32,
+    /// The generation of the consumer group, incremented on each rebalance.
+    #[serde(default)]
+    pub generation: u32,
+    /// The number of members evicted from the consumer group due to the expired session.
+    #[serde(default)]
+    pub evicted_members_count: u32,
 }
 
 /// `ConsumerGroupDetails` represents the detailed information about a consumer group.
@@ -24,6 +32,9 @@ pub struct ConsumerGroup {
 /// - `name`: the name of the consumer group.
 /// - `partitions_count`: the number of partitions the consumer group is consuming.
 /// - `members_count`: the number of members in the consumer group.
+/// - `generation`: the generation of the consumer group, incremented on each rebalance.
+/// - `evicted_members_count`: the number of members evicted from the consumer group due to the expired session.
+/// - `members`: the collection of members in the consumer group.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConsumerGroupDetails {
     /// The unique identifier (numeric) of the consumer group.
@@ -34,6 +45,12 @@ pub struct ConsumerGroupDetails {
     pub partitions_count: u32,
     /// The number of members in the consumer group.
     pub members_count: u32,
+    /// The generation of the consumer group, incremented on each rebalance.
+    #[serde(default)]
+    pub generation: u32,
+    /// The number of members evicted from the consumer group due to the expired session.
+    #[serde(default)]
+    pub evicted_members_count: u32,
     /// The collection of members in the consumer group.
     pub members: Vec<ConsumerGroupMember>,
 }
@@ -52,3 +69,18 @@ pub struct ConsumerGroupMember {
     /// The collection of partitions the consumer group member is consuming.
     pub partitions: Vec<u32>,
 }
+
+/// `ConsumerGroupMembership` represents the membership of the client which has joined a consumer group.
+/// It consists of the following fields:
+/// - `member_id`: the unique identifier (numeric) of the consumer group member.
+/// - `generation`: the generation of the consumer group the partitions have been assigned in.
+/// - `partitions`: the collection of partitions assigned to the consumer group member.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConsumerGroupMembership {
+    /// The unique identifier (numeric) of the consumer group member.
+    pub member_id: u32,
+    /// The generation of the consumer group the partitions have been assigned in.
+    pub generation: u32,
+    /// The collection of partitions assigned to the consumer group member.
+    pub partitions: Vec<u32>,
+}
@@ -0,0 +1,182 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::models::header::{HeaderKey, HeaderValue};
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// The header containing the ID of the stream from which the message was moved to the dead letter queue.
+pub const ORIGIN_STREAM_ID_HEADER: &str = "iggy-dlq-origin-stream-id";
+/// The header containing the ID of the topic from which the message was moved to the dead letter queue.
+pub const ORIGIN_TOPIC_ID_HEADER: &str = "iggy-dlq-origin-topic-id";
+/// The header containing the ID of the partition from which the message was moved to the dead letter queue.
+pub const ORIGIN_PARTITION_ID_HEADER: &str = "iggy-dlq-origin-partition-id";
+/// The header containing the offset of the message in the origin partition.
+pub const ORIGIN_OFFSET_HEADER: &str = "iggy-dlq-origin-offset";
+/// The header containing the reason of the last negative acknowledgement of the message.
+pub const REASON_HEADER: &str = "iggy-dlq-reason";
+/// The header containing the number of the failed delivery attempts of the message.
+pub const DELIVERY_ATTEMPTS_HEADER: &str = "iggy-dlq-delivery-attempts";
+
+/// The headers added by the server to the messages moved to the dead letter queue.
+pub const DEAD_LETTER_QUEUE_HEADERS: [&str; 6] = [
+    ORIGIN_STREAM_ID_HEADER,
+    ORIGIN_TOPIC_ID_HEADER,
+    ORIGIN_PARTITION_ID_HEADER,
+    ORIGIN_OFFSET_HEADER,
+    REASON_HEADER,
+    DELIVERY_ATTEMPTS_HEADER,
+];
+
+/// `DeadLetterQueue` represents the dead letter queue configuration of the topic.
+/// Once the message is negatively acknowledged (nacked) by the consumers `max_delivery_attempts` times,
+/// it's moved by the server to the dead letter queue topic, with the headers describing its origin and the reason.
+/// It consists of the following fields:
+/// - `stream_id`: the unique stream ID (numeric or name) of the dead letter queue topic.
+/// - `topic_id`: the unique topic ID (numeric or name) of the dead letter queue topic.
+/// - `max_delivery_attempts`: the number of nacks after which the message is moved to the dead letter queue.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct DeadLetterQueue {
+    /// The unique stream ID (numeric or name) of the dead letter queue topic.
+    pub stream_id: Identifier,
+    /// The unique topic ID (numeric or name) of the dead letter queue topic.
+    pub topic_id: Identifier,
+    /// The number of nacks after which the message is moved to the dead letter queue.
+    pub max_delivery_attempts: u32,
+}
+
+/// `DeadLetterOrigin` describes the origin of the message moved to the dead letter queue, based on its headers.
+/// It consists of the following fields:
+/// - `stream_id`: the ID of the stream from which the message was moved.
+/// - `topic_id`: the ID of the topic from which the message was moved.
+/// - `partition_id`: the ID of the partition from which the message was moved.
+/// - `offset`: the offset of the message in the origin partition.
+/// - `reason`: the reason of the last negative acknowledgement of the message.
+/// - `delivery_attempts`: the number of the failed delivery attempts of the message.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DeadLetterOrigin {
+    /// The ID of the stream from which the message was moved.
+    pub stream_id: u32,
+    /// The ID of the topic from which the message was moved.
+    pub topic_id: u32,
+    /// The ID of the partition from which the message was moved.
+    pub partition_id: u32,
+    /// The offset of the message in the origin partition.
+    pub offset: u64,
+    /// The reason of the last negative acknowledgement of the message.
+    pub reason: String,
+    /// The number of the failed delivery attempts of the message.
+    pub delivery_attempts: u32,
+}
+
+impl DeadLetterOrigin {
+    /// Reads the origin of the message from its headers, returns `None` if the message wasn't moved to the dead letter queue.
+    pub fn from_headers(headers: &HashMap<HeaderKey, HeaderValue>) -> Option<Self> {
+        let get = |key: &str| headers.get(&HeaderKey::new(key).ok()?);
+        Some(DeadLetterOrigin {
+            stream_id: get(ORIGIN_STREAM_ID_HEADER)?.as_uint32().ok()?,
+            topic_id: get(ORIGIN_TOPIC_ID_HEADER)?.as_uint32().ok()?,
+            partition_id: get(ORIGIN_PARTITION_ID_HEADER)?.as_uint32().ok()?,
+            offset: get(ORIGIN_OFFSET_HEADER)?.as_uint64().ok()?,
+            reason: get(REASON_HEADER)?.as_str().ok()?.to_string(),
+            delivery_attempts: get(DELIVERY_ATTEMPTS_HEADER)?.as_uint32().ok()?,
+        })
+    }
+}
+
+impl BytesSerializable for DeadLetterQueue {
+    fn to_bytes(&self) -> Bytes {
+        let stream_id_bytes = self.stream_id.to_bytes();
+        let topic_id_bytes = self.topic_id.to_bytes();
+        let mut bytes = BytesMut::with_capacity(4 + stream_id_bytes.len() + topic_id_bytes.len());
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        bytes.put_u32_le(self.max_delivery_attempts);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<DeadLetterQueue, IggyError> {
+        if bytes.len() < 10 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone())?;
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += topic_id.get_size_bytes() as usize;
+        if bytes.len() < position + 4 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let max_delivery_attempts = u32::from_le_bytes(bytes[position..position + 4].try_into()?);
+        Ok(DeadLetterQueue {
+            stream_id,
+            topic_id,
+            max_delivery_attempts,
+        })
+    }
+}
+
+impl Display for DeadLetterQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}",
+            self.stream_id, self.topic_id, self.max_delivery_attempts
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn origin_should_be_read_from_headers() {
+        let mut headers = HashMap::new();
+        headers.insert(
+            HeaderKey::new(ORIGIN_STREAM_ID_HEADER).unwrap(),
+            HeaderValue::from_uint32(1).unwrap(),
+        );
+        headers.insert(
+            HeaderKey::new(ORIGIN_TOPIC_ID_HEADER).unwrap(),
+            HeaderValue::from_uint32(2).unwrap(),
+        );
+        headers.insert(
+            HeaderKey::new(ORIGIN_PARTITION_ID_HEADER).unwrap(),
+            HeaderValue::from_uint32(3).unwrap(),
+        );
+        headers.insert(
+            HeaderKey::new(ORIGIN_OFFSET_HEADER).unwrap(),
+            HeaderValue::from_uint64(4).unwrap(),
+        );
+        headers.insert(
+            HeaderKey::new(REASON_HEADER).unwrap(),
+            HeaderValue::from_str("invalid payload").unwrap(),
+        );
+        headers.insert(
+            HeaderKey::new(DELIVERY_ATTEMPTS_HEADER).unwrap(),
+            HeaderValue::from_uint32(5).unwrap(),
+        );
+
+        let origin = DeadLetterOrigin::from_headers(&headers).unwrap();
+
+        assert_eq!(
+            origin,
+            DeadLetterOrigin {
+                stream_id: 1,
+                topic_id: 2,
+                partition_id: 3,
+                offset: 4,
+                reason: "invalid payload".to_string(),
+                delivery_attempts: 5,
+            }
+        );
+        headers.remove(&HeaderKey::new(REASON_HEADER).unwrap());
+        assert!(DeadLetterOrigin::from_headers(&headers).is_none());
+    }
+}
@@ -61,7 +61,10 @@ pub struct PolledMessage {
     pub payload: Bytes,
 }
 
-/// The state of the message, currently only the `Available` state is used.
+/// The state of the message. The messages moved to the dead letter queue keep their state,
+/// as the poisoned ones are tracked by their offsets in the partition, and skipped when polling it.
+/// The `TransactionCommitted` and `TransactionAborted` states are used by the transaction markers stored in the partitions,
+/// which are never returned to the consumers.
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum MessageState {
@@ -69,10 +72,10 @@ pub enum MessageState {
     Available,
     /// The message is unavailable.
     Unavailable,
-    /// The message is poisoned.
-    Poisoned,
-    /// The message is marked for deletion.
-    MarkedForDeletion,
+    /// The message is the marker of the committed transaction.
+    TransactionCommitted,
+    /// The message is the marker of the aborted transaction.
+    TransactionAborted,
 }
 
 impl MessageState {
@@ -81,18 +84,26 @@ impl MessageState {
         match self {
             MessageState::Available => 1,
             MessageState::Unavailable => 10,
-            MessageState::Poisoned => 20,
-            MessageState::MarkedForDeletion => 30,
+            MessageState::TransactionCommitted => 40,
+            MessageState::TransactionAborted => 41,
         }
     }
 
+    /// Returns `true` if the message is the marker of the committed or aborted transaction.
+    pub fn is_transaction_marker(&self) -> bool {
+        matches!(
+            self,
+            MessageState::TransactionCommitted | MessageState::TransactionAborted
+        )
+    }
+
     /// Returns the message state from the code.
     pub fn from_code(code: u8) -> Result<Self, IggyError> {
         match code {
             1 => Ok(MessageState::Available),
             10 => Ok(MessageState::Unavailable),
-            20 => Ok(MessageState::Poisoned),
-            30 => Ok(MessageState::MarkedForDeletion),
+            40 => Ok(MessageState::TransactionCommitted),
+            41 => Ok(MessageState::TransactionAborted),
             _ => Err(IggyError::InvalidCommand),
         }
     }
@@ -103,8 +114,8 @@ impl Display for MessageState {
         match self {
             MessageState::Available => write!(f, "available"),
             MessageState::Unavailable => write!(f, "unavailable"),
-            MessageState::Poisoned => write!(f, "poisoned"),
-            MessageState::MarkedForDeletion => write!(f, "marked_for_deletion"),
+            MessageState::TransactionCommitted => write!(f, "transaction_committed"),
+            MessageState::TransactionAborted => write!(f, "transaction_aborted"),
         }
     }
 }
@@ -115,8 +126,8 @@ impl FromStr for MessageState {
         match s {
             "available" => Ok(MessageState::Available),
             "unavailable" => Ok(MessageState::Unavailable),
-            "poisoned" => Ok(MessageState::Poisoned),
-            "marked_for_deletion" => Ok(MessageState::MarkedForDeletion),
+            "transaction_committed" => Ok(MessageState::TransactionCommitted),
+            "transaction_aborted" => Ok(MessageState::TransactionAborted),
             _ => Err(IggyError::InvalidCommand),
         }
     }
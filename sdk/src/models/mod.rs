@@ -1,14 +1,20 @@
+pub mod batch_attributes;
+pub mod cleanup_policy;
 pub mod client_info;
+pub mod cluster;
 pub mod consumer_group;
 pub mod consumer_offset_info;
+pub mod dead_letter_queue;
 pub mod header;
 pub mod identity_info;
 pub mod messages;
 pub mod partition;
 pub mod permissions;
 pub mod personal_access_token;
+pub mod producer;
 pub mod stats;
 pub mod stream;
 pub mod topic;
+pub mod transaction;
 pub mod user_info;
 pub mod user_status;
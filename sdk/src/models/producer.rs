@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// The header containing the ID of the idempotent producer which has sent the message.
+pub const PRODUCER_ID_HEADER: &str = "iggy-producer-id";
+/// The header containing the sequence number of the message sent by the idempotent producer to the partition.
+/// The sequence numbers start from 1 and are incremented by 1 for each message sent to the same partition.
+pub const PRODUCER_SEQUENCE_HEADER: &str = "iggy-producer-sequence";
+
+/// `Producer` represents the idempotent producer registered by the server.
+/// It consists of the following fields:
+/// - `id`: the unique identifier of the producer.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub struct Producer {
+    /// The unique identifier of the producer.
+    pub id: u64,
+}
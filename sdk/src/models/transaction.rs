@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// The header containing the ID of the transaction within which the message was sent.
+/// It's added by the server to all the messages sent within the transaction.
+pub const TRANSACTION_ID_HEADER: &str = "iggy-transaction-id";
+
+/// `Transaction` represents the transaction open for the client connection.
+/// It consists of the following fields:
+/// - `id`: the unique identifier of the transaction.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub struct Transaction {
+    /// The unique identifier of the transaction.
+    pub id: u64,
+}
@@ -3,3 +3,4 @@ pub mod get_clients;
 pub mod get_me;
 pub mod get_stats;
 pub mod ping;
+pub mod reload_config;
@@ -0,0 +1,70 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::{Command, RELOAD_CONFIG_CODE};
+use crate::error::IggyError;
+use crate::validatable::Validatable;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `ReloadConfig` command is used to reload the server configuration without the restart.
+/// Only the reloadable settings are applied, the changes of the remaining ones require the restart.
+/// It has no additional payload.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReloadConfig {}
+
+impl Command for ReloadConfig {
+    fn code(&self) -> u32 {
+        RELOAD_CONFIG_CODE
+    }
+}
+
+impl Validatable<IggyError> for ReloadConfig {
+    fn validate(&self) -> Result<(), IggyError> {
+        Ok(())
+    }
+}
+
+impl BytesSerializable for ReloadConfig {
+    fn to_bytes(&self) -> Bytes {
+        Bytes::new()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<ReloadConfig, IggyError> {
+        if !bytes.is_empty() {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let command = ReloadConfig {};
+        Ok(command)
+    }
+}
+
+impl Display for ReloadConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_serialized_as_empty_bytes() {
+        let command = ReloadConfig {};
+        let bytes = command.to_bytes();
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn should_be_deserialized_from_empty_bytes() {
+        let command = ReloadConfig::from_bytes(Bytes::new());
+        assert!(command.is_ok());
+    }
+
+    #[test]
+    fn should_not_be_deserialized_from_non_empty_bytes() {
+        let command = ReloadConfig::from_bytes(Bytes::from_static(&[0]));
+        assert!(command.is_err());
+    }
+}
@@ -3,6 +3,9 @@ pub mod delete_topic;
 pub mod get_topic;
 pub mod get_topics;
 pub mod purge_topic;
+pub mod set_cleanup_policy;
+pub mod set_dead_letter_queue;
+pub mod set_max_topic_messages;
 pub mod update_topic;
 
 const MAX_NAME_LENGTH: usize = 255;
@@ -0,0 +1,117 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::{Command, SET_CLEANUP_POLICY_CODE};
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::models::cleanup_policy::CleanupPolicy;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `SetCleanupPolicy` command is used to set the cleanup policy of the topic.
+/// It has additional payload:
+/// - `stream_id` - unique stream ID (numeric or name).
+/// - `topic_id` - unique topic ID (numeric or name).
+/// - `cleanup_policy` - the cleanup policy of the topic, either deleting the whole segments or compacting them by the message key.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct SetCleanupPolicy {
+    /// Unique stream ID (numeric or name).
+    #[serde(skip)]
+    pub stream_id: Identifier,
+    /// Unique topic ID (numeric or name).
+    #[serde(skip)]
+    pub topic_id: Identifier,
+    /// The cleanup policy of the topic, either deleting the whole segments or compacting them by the message key.
+    pub cleanup_policy: CleanupPolicy,
+}
+
+impl Command for SetCleanupPolicy {
+    fn code(&self) -> u32 {
+        SET_CLEANUP_POLICY_CODE
+    }
+}
+
+impl Validatable<IggyError> for SetCleanupPolicy {
+    fn validate(&self) -> Result<(), IggyError> {
+        Ok(())
+    }
+}
+
+impl BytesSerializable for SetCleanupPolicy {
+    fn to_bytes(&self) -> Bytes {
+        let stream_id_bytes = self.stream_id.to_bytes();
+        let topic_id_bytes = self.topic_id.to_bytes();
+        let cleanup_policy_bytes = self.cleanup_policy.to_bytes();
+        let mut bytes = BytesMut::with_capacity(
+            stream_id_bytes.len() + topic_id_bytes.len() + cleanup_policy_bytes.len(),
+        );
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        bytes.put_slice(&cleanup_policy_bytes);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<SetCleanupPolicy, IggyError> {
+        if bytes.len() < 11 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone())?;
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += topic_id.get_size_bytes() as usize;
+        let cleanup_policy = CleanupPolicy::from_bytes(bytes.slice(position..))?;
+        let command = SetCleanupPolicy {
+            stream_id,
+            topic_id,
+            cleanup_policy,
+        };
+        Ok(command)
+    }
+}
+
+impl Display for SetCleanupPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}",
+            self.stream_id, self.topic_id, self.cleanup_policy
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::duration::IggyDuration;
+    use std::time::Duration;
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let command = SetCleanupPolicy {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::named("profiles").unwrap(),
+            cleanup_policy: CleanupPolicy::Compact {
+                tombstone_retention: IggyDuration::new(Duration::from_secs(3600)),
+            },
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = SetCleanupPolicy::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes_with_delete_policy() {
+        let command = SetCleanupPolicy {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::numeric(2).unwrap(),
+            cleanup_policy: CleanupPolicy::Delete,
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = SetCleanupPolicy::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::{Command, SET_DEAD_LETTER_QUEUE_CODE};
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::models::dead_letter_queue::DeadLetterQueue;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `SetDeadLetterQueue` command is used to set or remove the dead letter queue of the topic.
+/// It has additional payload:
+/// - `stream_id` - unique stream ID (numeric or name).
+/// - `topic_id` - unique topic ID (numeric or name).
+/// - `dead_letter_queue` - optional dead letter queue configuration, `None` removes the dead letter queue from the topic.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct SetDeadLetterQueue {
+    /// Unique stream ID (numeric or name).
+    #[serde(skip)]
+    pub stream_id: Identifier,
+    /// Unique topic ID (numeric or name).
+    #[serde(skip)]
+    pub topic_id: Identifier,
+    /// Optional dead letter queue configuration, `None` removes the dead letter queue from the topic.
+    pub dead_letter_queue: Option<DeadLetterQueue>,
+}
+
+impl Command for SetDeadLetterQueue {
+    fn code(&self) -> u32 {
+        SET_DEAD_LETTER_QUEUE_CODE
+    }
+}
+
+impl Validatable<IggyError> for SetDeadLetterQueue {
+    fn validate(&self) -> Result<(), IggyError> {
+        if let Some(dead_letter_queue) = &self.dead_letter_queue {
+            if dead_letter_queue.max_delivery_attempts == 0 {
+                return Err(IggyError::InvalidDeadLetterQueue);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl BytesSerializable for SetDeadLetterQueue {
+    fn to_bytes(&self) -> Bytes {
+        let stream_id_bytes = self.stream_id.to_bytes();
+        let topic_id_bytes = self.topic_id.to_bytes();
+        let dead_letter_queue_bytes = self
+            .dead_letter_queue
+            .as_ref()
+            .map(|dead_letter_queue| dead_letter_queue.to_bytes());
+        let mut bytes = BytesMut::with_capacity(
+            1 + stream_id_bytes.len()
+                + topic_id_bytes.len()
+                + dead_letter_queue_bytes
+                    .as_ref()
+                    .map_or(0, |bytes| bytes.len()),
+        );
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        if let Some(dead_letter_queue_bytes) = dead_letter_queue_bytes {
+            bytes.put_u8(1);
+            bytes.put_slice(&dead_letter_queue_bytes);
+        } else {
+            bytes.put_u8(0);
+        }
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<SetDeadLetterQueue, IggyError> {
+        if bytes.len() < 11 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone())?;
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += topic_id.get_size_bytes() as usize;
+        if bytes.len() <= position {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let dead_letter_queue = match bytes[position] {
+            0 => None,
+            1 => Some(DeadLetterQueue::from_bytes(bytes.slice(position + 1..))?),
+            _ => return Err(IggyError::InvalidCommand),
+        };
+        let command = SetDeadLetterQueue {
+            stream_id,
+            topic_id,
+            dead_letter_queue,
+        };
+        Ok(command)
+    }
+}
+
+impl Display for SetDeadLetterQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.dead_letter_queue {
+            Some(dead_letter_queue) => {
+                write!(
+                    f,
+                    "{}|{}|{}",
+                    self.stream_id, self.topic_id, dead_letter_queue
+                )
+            }
+            None => write!(f, "{}|{}|none", self.stream_id, self.topic_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let command = SetDeadLetterQueue {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::numeric(2).unwrap(),
+            dead_letter_queue: Some(DeadLetterQueue {
+                stream_id: Identifier::named("dlq").unwrap(),
+                topic_id: Identifier::numeric(3).unwrap(),
+                max_delivery_attempts: 5,
+            }),
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = SetDeadLetterQueue::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes_without_dead_letter_queue() {
+        let command = SetDeadLetterQueue {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::named("topic").unwrap(),
+            dead_letter_queue: None,
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = SetDeadLetterQueue::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
+
+    #[test]
+    fn should_not_be_valid_given_zero_max_delivery_attempts() {
+        let command = SetDeadLetterQueue {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::numeric(2).unwrap(),
+            dead_letter_queue: Some(DeadLetterQueue {
+                stream_id: Identifier::numeric(1).unwrap(),
+                topic_id: Identifier::numeric(3).unwrap(),
+                max_delivery_attempts: 0,
+            }),
+        };
+
+        assert!(command.validate().is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::{Command, SET_MAX_TOPIC_MESSAGES_CODE};
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `SetMaxTopicMessages` command is used to set the maximum number of messages retained by the topic.
+/// It has additional payload:
+/// - `stream_id` - unique stream ID (numeric or name).
+/// - `topic_id` - unique topic ID (numeric or name).
+/// - `max_messages` - the maximum number of messages retained by the topic, 0 means unlimited.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct SetMaxTopicMessages {
+    /// Unique stream ID (numeric or name).
+    #[serde(skip)]
+    pub stream_id: Identifier,
+    /// Unique topic ID (numeric or name).
+    #[serde(skip)]
+    pub topic_id: Identifier,
+    /// The maximum number of messages retained by the topic, 0 means unlimited.
+    /// Once exceeded, the oldest closed segments are deleted by the messages maintenance.
+    pub max_messages: u64,
+}
+
+impl Command for SetMaxTopicMessages {
+    fn code(&self) -> u32 {
+        SET_MAX_TOPIC_MESSAGES_CODE
+    }
+}
+
+impl Validatable<IggyError> for SetMaxTopicMessages {
+    fn validate(&self) -> Result<(), IggyError> {
+        Ok(())
+    }
+}
+
+impl BytesSerializable for SetMaxTopicMessages {
+    fn to_bytes(&self) -> Bytes {
+        let stream_id_bytes = self.stream_id.to_bytes();
+        let topic_id_bytes = self.topic_id.to_bytes();
+        let mut bytes = BytesMut::with_capacity(stream_id_bytes.len() + topic_id_bytes.len() + 8);
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        bytes.put_u64_le(self.max_messages);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<SetMaxTopicMessages, IggyError> {
+        if bytes.len() < 14 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone())?;
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += topic_id.get_size_bytes() as usize;
+        if bytes.len() != position + 8 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let max_messages = u64::from_le_bytes(bytes[position..position + 8].try_into()?);
+        let command = SetMaxTopicMessages {
+            stream_id,
+            topic_id,
+            max_messages,
+        };
+        Ok(command)
+    }
+}
+
+impl Display for SetMaxTopicMessages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}",
+            self.stream_id, self.topic_id, self.max_messages
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_serialized_as_bytes() {
+        let command = SetMaxTopicMessages {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::named("orders").unwrap(),
+            max_messages: 1000,
+        };
+
+        let bytes = command.to_bytes();
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone()).unwrap();
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..)).unwrap();
+        position += topic_id.get_size_bytes() as usize;
+        let max_messages = u64::from_le_bytes(bytes[position..position + 8].try_into().unwrap());
+
+        assert!(!bytes.is_empty());
+        assert_eq!(stream_id, command.stream_id);
+        assert_eq!(topic_id, command.topic_id);
+        assert_eq!(max_messages, command.max_messages);
+    }
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let command = SetMaxTopicMessages {
+            stream_id: Identifier::named("shop").unwrap(),
+            topic_id: Identifier::numeric(2).unwrap(),
+            max_messages: 0,
+        };
+
+        let bytes = command.to_bytes();
+        let deserialized_command = SetMaxTopicMessages::from_bytes(bytes).unwrap();
+        assert_eq!(deserialized_command, command);
+    }
+}
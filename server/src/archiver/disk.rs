@@ -63,4 +63,61 @@ impl Archiver for DiskArchiver {
 
         Ok(())
     }
+    async fn restore(
+        &self,
+        file: &str,
+        base_directory: Option<String>,
+        destination: &str,
+    ) -> Result<(), ServerError> {
+        debug!("Restoring file: {file} from disk to: {destination}");
+        let base_directory = base_directory.as_deref().unwrap_or_default();
+        let source = Path::new(&self.config.path).join(base_directory).join(file);
+        if !source.exists() {
+            return Err(ServerError::FileToRestoreNotFound(file.to_string()));
+        }
+
+        let destination = Path::new(destination);
+        fs::create_dir_all(destination.parent().unwrap()).await?;
+        fs::copy(source, destination).await?;
+        debug!("Restored file: {file} from disk to: {destination:?}");
+        Ok(())
+    }
+    async fn list(
+        &self,
+        directory: &str,
+        base_directory: Option<String>,
+    ) -> Result<Vec<String>, ServerError> {
+        debug!("Listing archived files in directory: {directory} on disk.");
+        let base_directory = base_directory.as_deref().unwrap_or_default();
+        let base_path = Path::new(&self.config.path).join(base_directory);
+        let mut directories = vec![base_path.join(directory)];
+        let mut files = Vec::new();
+        while let Some(directory) = directories.pop() {
+            if !directory.exists() {
+                continue;
+            }
+
+            let mut dir_entries = fs::read_dir(&directory).await?;
+            while let Some(dir_entry) = dir_entries.next_entry().await? {
+                let path = dir_entry.path();
+                if dir_entry.metadata().await?.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+
+                if let Some(file) = path
+                    .strip_prefix(&base_path)
+                    .ok()
+                    .and_then(|file| file.to_str())
+                {
+                    files.push(file.to_owned());
+                }
+            }
+        }
+        debug!(
+            "Found {} archived files in directory: {directory} on disk.",
+            files.len()
+        );
+        Ok(files)
+    }
 }
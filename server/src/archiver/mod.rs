@@ -1,12 +1,18 @@
 pub mod disk;
+pub mod restore;
 pub mod s3;
+pub mod tiered;
 
+use crate::archiver::disk::DiskArchiver;
+use crate::archiver::s3::S3Archiver;
+use crate::configs::server::ArchiverConfig;
 use crate::server_error::ServerError;
 use async_trait::async_trait;
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 use std::str::FromStr;
+use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default, Display, Copy, Clone)]
 #[serde(rename_all = "lowercase")]
@@ -42,6 +48,35 @@ pub trait Archiver: Sync + Send {
         files: &[&str],
         base_directory: Option<String>,
     ) -> Result<(), ServerError>;
+    async fn restore(
+        &self,
+        file: &str,
+        base_directory: Option<String>,
+        destination: &str,
+    ) -> Result<(), ServerError>;
+    async fn list(
+        &self,
+        directory: &str,
+        base_directory: Option<String>,
+    ) -> Result<Vec<String>, ServerError>;
+}
+
+/// Creates the archiver of the configured kind, regardless of whether the archiving process is enabled.
+pub fn create(config: &ArchiverConfig) -> Result<Arc<dyn Archiver>, ServerError> {
+    match config.kind {
+        ArchiverKind::Disk => {
+            let disk = config.disk.clone().ok_or_else(|| {
+                ServerError::InvalidConfiguration("Disk archiver config is missing".into())
+            })?;
+            Ok(Arc::new(DiskArchiver::new(disk)))
+        }
+        ArchiverKind::S3 => {
+            let s3 = config.s3.clone().ok_or_else(|| {
+                ServerError::InvalidConfiguration("S3 archiver config is missing".into())
+            })?;
+            Ok(Arc::new(S3Archiver::new(s3)?))
+        }
+    }
 }
 
 impl Debug for dyn Archiver {
@@ -0,0 +1,350 @@
+use crate::archiver::Archiver;
+use crate::configs::system::SystemConfig;
+use crate::server_error::ServerError;
+use crate::state::command::EntryCommand;
+use crate::state::file::FileState;
+use crate::state::system::SystemState;
+use crate::state::State;
+use crate::streaming::persistence::persister::FilePersister;
+use crate::streaming::segments::segment::{INDEX_EXTENSION, LOG_EXTENSION, TIME_INDEX_EXTENSION};
+use crate::streaming::segments::storage::{INDEX_SIZE, TIME_INDEX_SIZE};
+use crate::streaming::utils::file;
+use crate::streaming::utils::keyring::Keyring;
+use crate::versioning::SemanticVersion;
+use iggy::identifier::Identifier;
+use iggy::streams::delete_stream::DeleteStream;
+use iggy::topics::delete_topic::DeleteTopic;
+use iggy::users::defaults::DEFAULT_ROOT_USER_ID;
+use iggy::utils::crypto::Encryptor;
+use iggy::utils::timestamp::IggyTimestamp;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::fs;
+use tracing::{info, warn};
+
+/// Options for restoring the local data from the archive.
+/// When neither streams nor topics are specified, all of them are restored.
+#[derive(Debug, Default, Clone)]
+pub struct RestoreOptions {
+    /// The base directory of the archived state, e.g. `{timestamp}_state`, if the state archiver didn't overwrite it.
+    pub state_directory: Option<String>,
+    /// IDs of the streams to restore along with all their topics.
+    pub streams: Vec<u32>,
+    /// IDs of the topics to restore as the pairs of stream ID and topic ID.
+    pub topics: Vec<(u32, u32)>,
+    /// Point in time to restore, the state entries and the messages created later are discarded.
+    pub timestamp: Option<IggyTimestamp>,
+}
+
+impl RestoreOptions {
+    fn includes_stream(&self, stream_id: u32) -> bool {
+        (self.streams.is_empty() && self.topics.is_empty())
+            || self.streams.contains(&stream_id)
+            || self.topics.iter().any(|(id, _)| *id == stream_id)
+    }
+
+    fn includes_topic(&self, stream_id: u32, topic_id: u32) -> bool {
+        (self.streams.is_empty() && self.topics.is_empty())
+            || self.streams.contains(&stream_id)
+            || self.topics.contains(&(stream_id, topic_id))
+    }
+}
+
+/// Reconstructs the local data from the archived state log and segment files, so that the server can boot normally.
+/// The state is restored first, then the streams and topics not included in the options are deleted from it,
+/// and finally the archived segments of the remaining partitions are restored, optionally truncated to the point in time.
+/// The consumer and consumer group offsets archived along with the segments are restored as well, capped at the last
+/// restored offset of their partition. The consumer group leases and the dead letters aren't archived, so the restored
+/// consumer groups start leasing from their offsets, while the producer sequences are rebuilt from the restored messages.
+/// The files are restored to the same paths they were archived from, so the system path must not change.
+pub async fn restore(
+    config: Arc<SystemConfig>,
+    archiver: Arc<dyn Archiver>,
+    options: &RestoreOptions,
+) -> Result<(), ServerError> {
+    let state_log_path = config.get_state_log_path();
+    if Path::new(&state_log_path).exists() {
+        return Err(ServerError::CannotRestoreArchive(format!(
+            "state already exists at path: {state_log_path}"
+        )));
+    }
+
+    info!("Restoring state from the archive...");
+    fs::create_dir_all(config.get_state_path()).await?;
+    for path in [config.get_state_info_path(), state_log_path.clone()] {
+        archiver
+            .restore(&path, options.state_directory.clone(), &path)
+            .await?;
+    }
+
+    // The snapshot is archived only if the state log has been compacted.
+    let state_snapshot_path = config.get_state_snapshot_path();
+    if archiver
+        .is_archived(&state_snapshot_path, options.state_directory.clone())
+        .await?
+    {
+        archiver
+            .restore(
+                &state_snapshot_path,
+                options.state_directory.clone(),
+                &state_snapshot_path,
+            )
+            .await?;
+    }
+
+    let encryptor: Option<Arc<dyn Encryptor>> = match config.encryption.enabled {
+        true => Some(Arc::new(Keyring::from_config(&config.encryption)?)),
+        false => None,
+    };
+    let state = FileState::new(
+        &state_log_path,
+        &state_snapshot_path,
+        &SemanticVersion::current()?,
+        Arc::new(FilePersister),
+        encryptor,
+    );
+    if let Some(timestamp) = options.timestamp {
+        if let Some(snapshot) = state.load_snapshot().await? {
+            if snapshot.timestamp.as_micros() > timestamp.as_micros() {
+                return Err(ServerError::CannotRestoreArchive(format!(
+                    "state snapshot includes the entries issued until: {}, which is after: {timestamp}",
+                    snapshot.timestamp
+                )));
+            }
+        }
+
+        let entries_count = state.truncate(timestamp).await?;
+        info!("Restored state with {entries_count} entries issued until: {timestamp}.");
+    }
+
+    let system_state = SystemState::load(&state).await?;
+    let mut restored_segments = 0;
+    for stream in system_state.streams.values() {
+        if !options.includes_stream(stream.id) {
+            info!("Stream with ID: {} will not be restored.", stream.id);
+            state
+                .apply(
+                    DEFAULT_ROOT_USER_ID,
+                    EntryCommand::DeleteStream(DeleteStream {
+                        stream_id: Identifier::numeric(stream.id)?,
+                    }),
+                )
+                .await?;
+            continue;
+        }
+
+        for topic in stream.topics.values() {
+            if !options.includes_topic(stream.id, topic.id) {
+                info!(
+                    "Topic with ID: {} for stream with ID: {} will not be restored.",
+                    topic.id, stream.id
+                );
+                state
+                    .apply(
+                        DEFAULT_ROOT_USER_ID,
+                        EntryCommand::DeleteTopic(DeleteTopic {
+                            stream_id: Identifier::numeric(stream.id)?,
+                            topic_id: Identifier::numeric(topic.id)?,
+                        }),
+                    )
+                    .await?;
+                continue;
+            }
+
+            for partition_id in topic.partitions.keys() {
+                restored_segments += restore_partition(
+                    &config,
+                    archiver.as_ref(),
+                    stream.id,
+                    topic.id,
+                    *partition_id,
+                    options.timestamp,
+                )
+                .await?;
+            }
+        }
+    }
+
+    info!("Restored state and {restored_segments} segments from the archive.");
+    Ok(())
+}
+
+/// Restores the archived segments of the partition, optionally truncated to the point in time, along with its offsets.
+/// Returns the number of the restored segments.
+pub async fn restore_partition(
+    config: &SystemConfig,
+    archiver: &dyn Archiver,
+    stream_id: u32,
+    topic_id: u32,
+    partition_id: u32,
+    timestamp: Option<IggyTimestamp>,
+) -> Result<u32, ServerError> {
+    let partition_path = config.get_partition_path(stream_id, topic_id, partition_id);
+    let mut start_offsets = Vec::new();
+    for file in archiver.list(&partition_path, None).await? {
+        let path = Path::new(&file);
+        let Some(extension) = path.extension().and_then(|extension| extension.to_str()) else {
+            continue;
+        };
+        if ![LOG_EXTENSION, INDEX_EXTENSION, TIME_INDEX_EXTENSION].contains(&extension) {
+            continue;
+        }
+
+        archiver.restore(&file, None, &file).await?;
+        if extension == LOG_EXTENSION {
+            if let Some(start_offset) = path
+                .file_stem()
+                .and_then(|file_stem| file_stem.to_str())
+                .and_then(|file_stem| file_stem.parse::<u64>().ok())
+            {
+                start_offsets.push(start_offset);
+            }
+        }
+    }
+
+    if start_offsets.is_empty() {
+        warn!("No archived segments found for partition with ID: {partition_id} for stream with ID: {stream_id} and topic with ID: {topic_id}.");
+        restore_consumer_offsets(config, archiver, stream_id, topic_id, partition_id, None).await?;
+        return Ok(0);
+    }
+
+    start_offsets.sort();
+    let mut restored_segments = 0;
+    let mut last_offset = None;
+    for (position, start_offset) in start_offsets.into_iter().enumerate() {
+        let segment_path = config.get_segment_path(stream_id, topic_id, partition_id, start_offset);
+        if let Some(timestamp) = timestamp {
+            // The first segment is always kept, even if empty, so that the partition starts from its offset.
+            let keep_empty = position == 0;
+            if !truncate_segment(&segment_path, timestamp, keep_empty).await? {
+                continue;
+            }
+        }
+        if let Some(offset) = get_last_offset(&segment_path, start_offset).await? {
+            last_offset = Some(offset);
+        }
+        restored_segments += 1;
+    }
+
+    restore_consumer_offsets(
+        config,
+        archiver,
+        stream_id,
+        topic_id,
+        partition_id,
+        last_offset,
+    )
+    .await?;
+    info!("Restored {restored_segments} segments for partition with ID: {partition_id} for stream with ID: {stream_id} and topic with ID: {topic_id}.");
+    Ok(restored_segments)
+}
+
+/// Restores the consumer and consumer group offsets of the partition, capped at the last restored offset,
+/// so that the consumers don't skip the messages appended once again after the restore.
+/// The offsets are removed if no messages have been restored.
+async fn restore_consumer_offsets(
+    config: &SystemConfig,
+    archiver: &dyn Archiver,
+    stream_id: u32,
+    topic_id: u32,
+    partition_id: u32,
+    last_offset: Option<u64>,
+) -> Result<(), ServerError> {
+    let mut restored_offsets = 0;
+    for path in [
+        config.get_consumer_offsets_path(stream_id, topic_id, partition_id),
+        config.get_consumer_group_offsets_path(stream_id, topic_id, partition_id),
+    ] {
+        // The directories have to exist for the partition to be loaded, even if there are no archived offsets.
+        fs::create_dir_all(&path).await?;
+        for file in archiver.list(&path, None).await? {
+            archiver.restore(&file, None, &file).await?;
+            let bytes = fs::read(&file).await?;
+            let offset = bytes
+                .get(..8)
+                .map(|offset| u64::from_le_bytes(offset.try_into().unwrap()));
+            match (offset, last_offset) {
+                (Some(offset), Some(last_offset)) if offset <= last_offset => {}
+                (Some(_), Some(last_offset)) => {
+                    info!("Capping consumer offset: {file} at the last restored offset: {last_offset}.");
+                    fs::write(&file, last_offset.to_le_bytes()).await?;
+                }
+                _ => {
+                    warn!("Removing consumer offset: {file}, as there are no restored messages it could point to.");
+                    file::remove(&file).await?;
+                    continue;
+                }
+            }
+            restored_offsets += 1;
+        }
+    }
+
+    info!("Restored {restored_offsets} consumer offsets for partition with ID: {partition_id} for stream with ID: {stream_id} and topic with ID: {topic_id}.");
+    Ok(())
+}
+
+/// Returns the offset of the last message of the segment based on its indexes, or None if the segment is empty.
+async fn get_last_offset(
+    segment_path: &str,
+    start_offset: u64,
+) -> Result<Option<u64>, ServerError> {
+    let indexes = fs::read(format!("{segment_path}.{INDEX_EXTENSION}")).await?;
+    let Some(last_index) = indexes.chunks_exact(INDEX_SIZE as usize).last() else {
+        return Ok(None);
+    };
+
+    let relative_offset = u32::from_le_bytes(last_index[..4].try_into().unwrap());
+    Ok(Some(start_offset + relative_offset as u64))
+}
+
+/// Truncates the segment files to the batches created until the given timestamp, based on its time indexes.
+/// Returns false if the segment was removed, as all its batches were created later.
+async fn truncate_segment(
+    segment_path: &str,
+    timestamp: IggyTimestamp,
+    keep_empty: bool,
+) -> Result<bool, ServerError> {
+    let log_path = format!("{segment_path}.{LOG_EXTENSION}");
+    let index_path = format!("{segment_path}.{INDEX_EXTENSION}");
+    let time_index_path = format!("{segment_path}.{TIME_INDEX_EXTENSION}");
+    let time_indexes = fs::read(&time_index_path).await?;
+    let batches_count = time_indexes.len() / TIME_INDEX_SIZE as usize;
+    let retained_batches = time_indexes
+        .chunks_exact(TIME_INDEX_SIZE as usize)
+        .take_while(|time_index| {
+            u64::from_le_bytes(time_index[4..12].try_into().unwrap()) <= timestamp.as_micros()
+        })
+        .count();
+    if retained_batches == batches_count {
+        return Ok(true);
+    }
+
+    if retained_batches == 0 && !keep_empty {
+        info!("Removing segment: {segment_path} created after: {timestamp}.");
+        for path in [&log_path, &index_path, &time_index_path] {
+            file::remove(path).await?;
+        }
+        return Ok(false);
+    }
+
+    // The index position points to the beginning of the batch, so the log ends where the first removed batch starts.
+    let indexes = fs::read(&index_path).await?;
+    let index_position = retained_batches * INDEX_SIZE as usize;
+    let log_size = indexes
+        .get(index_position + 4..index_position + 8)
+        .map(|position| u32::from_le_bytes(position.try_into().unwrap()) as u64)
+        .unwrap_or_default();
+    info!(
+        "Truncating segment: {segment_path} to {retained_batches} of {batches_count} batches created until: {timestamp}."
+    );
+    file::overwrite(&log_path).await?.set_len(log_size).await?;
+    file::overwrite(&index_path)
+        .await?
+        .set_len(index_position as u64)
+        .await?;
+    file::overwrite(&time_index_path)
+        .await?
+        .set_len((retained_batches * TIME_INDEX_SIZE as usize) as u64)
+        .await?;
+    Ok(true)
+}
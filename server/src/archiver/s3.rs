@@ -155,4 +155,74 @@ impl Archiver for S3Archiver {
         }
         Ok(())
     }
+    async fn restore(
+        &self,
+        file: &str,
+        base_directory: Option<String>,
+        destination: &str,
+    ) -> Result<(), ServerError> {
+        debug!("Restoring file: {file} from S3 to: {destination}");
+        let base_directory = base_directory.as_deref().unwrap_or_default();
+        let source = Path::new(&base_directory).join(file);
+        let source_path = source.to_str().unwrap_or_default().to_owned();
+        let destination_path = Path::new(destination);
+        fs::create_dir_all(destination_path.parent().unwrap()).await?;
+        let mut destination_file = fs::File::create(destination_path).await?;
+        let response = self
+            .bucket
+            .get_object_to_writer(source_path, &mut destination_file)
+            .await;
+        if let Err(error) = response {
+            error!("Cannot restore file: {file} from S3: {error}");
+            fs::remove_file(destination_path).await?;
+            return Err(ServerError::CannotRestoreFile(file.to_string()));
+        }
+
+        let status = response.unwrap();
+        if status == 200 {
+            debug!("Restored file: {file} from S3 to: {destination}");
+            return Ok(());
+        }
+
+        fs::remove_file(destination_path).await?;
+        if status == 404 {
+            return Err(ServerError::FileToRestoreNotFound(file.to_string()));
+        }
+
+        error!("Cannot restore file: {file} from S3, received an invalid status code: {status}.");
+        Err(ServerError::CannotRestoreFile(file.to_string()))
+    }
+    async fn list(
+        &self,
+        directory: &str,
+        base_directory: Option<String>,
+    ) -> Result<Vec<String>, ServerError> {
+        debug!("Listing archived files in directory: {directory} on S3.");
+        let base_directory = base_directory.as_deref().unwrap_or_default();
+        let prefix = Path::new(&base_directory).join(directory);
+        let prefix = prefix.to_str().unwrap_or_default().to_owned();
+        let response = self.bucket.list(prefix, None).await;
+        if let Err(error) = response {
+            error!("Cannot list archived files in directory: {directory} on S3: {error}");
+            return Err(ServerError::CannotListArchivedFiles(directory.to_string()));
+        }
+
+        let files = response
+            .unwrap()
+            .into_iter()
+            .flat_map(|result| result.contents)
+            .filter_map(|object| {
+                Path::new(&object.key)
+                    .strip_prefix(base_directory)
+                    .ok()
+                    .and_then(|file| file.to_str())
+                    .map(|file| file.to_owned())
+            })
+            .collect::<Vec<_>>();
+        debug!(
+            "Found {} archived files in directory: {directory} on S3.",
+            files.len()
+        );
+        Ok(files)
+    }
 }
@@ -0,0 +1,106 @@
+use crate::archiver::Archiver;
+use iggy::error::IggyError;
+use iggy::utils::byte_size::IggyByteSize;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::fs;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info};
+
+/// The tiered storage allows to offload the closed segments to the archiver and remove them from the local disk,
+/// while still serving the polls for their messages, as the offloaded files are fetched back on demand
+/// into the local read-through cache, limited by its maximum size (the least recently used files are evicted first).
+#[derive(Debug)]
+pub struct TieredStorage {
+    archiver: Arc<dyn Archiver>,
+    cache_path: String,
+    cache_size: IggyByteSize,
+    cached_files: Mutex<VecDeque<CachedFile>>,
+}
+
+#[derive(Debug)]
+struct CachedFile {
+    path: String,
+    size_bytes: u64,
+}
+
+impl TieredStorage {
+    pub fn new(archiver: Arc<dyn Archiver>, cache_path: &str, cache_size: IggyByteSize) -> Self {
+        Self {
+            archiver,
+            cache_path: cache_path.to_owned(),
+            cache_size,
+            cached_files: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Returns the path of the local copy of the archived file, fetching it from the archive if it's not cached yet.
+    pub async fn fetch(&self, file: &str) -> Result<String, IggyError> {
+        let cached_path = self.get_cached_path(file);
+        let mut cached_files = self.cached_files.lock().await;
+        if let Some(position) = cached_files
+            .iter()
+            .position(|cached_file| cached_file.path == cached_path)
+        {
+            let cached_file = cached_files.remove(position).unwrap();
+            cached_files.push_back(cached_file);
+            debug!("Archived file: {file} is cached at: {cached_path}");
+            return Ok(cached_path);
+        }
+
+        info!("Fetching archived file: {file} into the cache at: {cached_path}...");
+        if let Err(error) = self.archiver.restore(file, None, &cached_path).await {
+            error!("Cannot fetch archived file: {file}. Error: {error}");
+            return Err(IggyError::CannotFetchArchivedFile(file.to_owned()));
+        }
+
+        let size_bytes = fs::metadata(&cached_path).await?.len();
+        cached_files.push_back(CachedFile {
+            path: cached_path.clone(),
+            size_bytes,
+        });
+        let mut cache_size_bytes = cached_files
+            .iter()
+            .map(|cached_file| cached_file.size_bytes)
+            .sum::<u64>();
+        // The most recently fetched file is always kept, even if it alone exceeds the cache size.
+        while cache_size_bytes > self.cache_size.as_bytes_u64() && cached_files.len() > 1 {
+            let evicted_file = cached_files.pop_front().unwrap();
+            cache_size_bytes -= evicted_file.size_bytes;
+            remove_cached_file(&evicted_file.path).await?;
+        }
+
+        info!("Fetched archived file: {file} of size: {size_bytes} bytes into the cache.");
+        Ok(cached_path)
+    }
+
+    /// Removes the local copy of the archived file, if it's cached.
+    pub async fn evict(&self, file: &str) -> Result<(), IggyError> {
+        let cached_path = self.get_cached_path(file);
+        let mut cached_files = self.cached_files.lock().await;
+        if let Some(position) = cached_files
+            .iter()
+            .position(|cached_file| cached_file.path == cached_path)
+        {
+            cached_files.remove(position);
+        }
+        remove_cached_file(&cached_path).await
+    }
+
+    fn get_cached_path(&self, file: &str) -> String {
+        Path::new(&self.cache_path)
+            .join(file.trim_start_matches('/'))
+            .to_str()
+            .unwrap_or_default()
+            .to_owned()
+    }
+}
+
+async fn remove_cached_file(path: &str) -> Result<(), IggyError> {
+    if Path::new(path).exists() {
+        debug!("Removing cached file: {path}");
+        fs::remove_file(path).await?;
+    }
+    Ok(())
+}
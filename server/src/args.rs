@@ -5,4 +5,37 @@ use clap::Parser;
 pub struct Args {
     #[arg(short, long, default_value = "file")]
     pub config_provider: String,
+
+    /// Restore the local data from the archive (using the configured archiver kind) before starting the server.
+    #[arg(long, default_value_t = false)]
+    pub restore: bool,
+
+    /// Base directory of the archived state to restore, e.g. `1700000000000000_state`, if it wasn't overwritten.
+    #[arg(long, requires = "restore")]
+    pub restore_state_directory: Option<String>,
+
+    /// Comma-separated IDs of the streams to restore, e.g. `1,2`, all the streams are restored by default.
+    #[arg(long, value_delimiter = ',', requires = "restore")]
+    pub restore_streams: Vec<u32>,
+
+    /// Comma-separated topics to restore as `stream_id.topic_id`, e.g. `1.1,2.3`.
+    #[arg(long, value_delimiter = ',', value_parser = parse_topic, requires = "restore")]
+    pub restore_topics: Vec<(u32, u32)>,
+
+    /// Point in time to restore as the Unix timestamp in microseconds, the newer data is discarded.
+    #[arg(long, requires = "restore")]
+    pub restore_timestamp: Option<u64>,
+}
+
+fn parse_topic(value: &str) -> Result<(u32, u32), String> {
+    let (stream_id, topic_id) = value
+        .split_once('.')
+        .ok_or_else(|| format!("Invalid topic: {value}, expected: stream_id.topic_id"))?;
+    let stream_id = stream_id
+        .parse()
+        .map_err(|_| format!("Invalid stream ID: {stream_id}"))?;
+    let topic_id = topic_id
+        .parse()
+        .map_err(|_| format!("Invalid topic ID: {topic_id}"))?;
+    Ok((stream_id, topic_id))
 }
@@ -1,6 +1,10 @@
+use crate::binary::handlers::cluster::{
+    add_cluster_node_handler, get_cluster_metadata_handler, remove_cluster_node_handler,
+};
 use crate::binary::handlers::consumer_groups::{
     create_consumer_group_handler, delete_consumer_group_handler, get_consumer_group_handler,
-    get_consumer_groups_handler, join_consumer_group_handler, leave_consumer_group_handler,
+    get_consumer_groups_handler, heartbeat_consumer_group_handler, join_consumer_group_handler,
+    leave_consumer_group_handler,
 };
 use crate::binary::handlers::consumer_offsets::*;
 use crate::binary::handlers::messages::*;
@@ -12,6 +16,9 @@ use crate::binary::handlers::personal_access_tokens::{
 use crate::binary::handlers::streams::*;
 use crate::binary::handlers::system::*;
 use crate::binary::handlers::topics::*;
+use crate::binary::handlers::transactions::{
+    abort_transaction_handler, begin_transaction_handler, commit_transaction_handler,
+};
 use crate::binary::handlers::users::{
     change_password_handler, create_user_handler, delete_user_handler, get_user_handler,
     get_users_handler, login_user_handler, logout_user_handler, update_permissions_handler,
@@ -58,6 +65,10 @@ async fn try_handle(
     system: &SharedSystem,
 ) -> Result<(), IggyError> {
     debug!("Handling command '{command}', session: {session}...");
+    if command.is_replicated() {
+        system.read().await.ensure_leader()?;
+    }
+
     match command {
         ServerCommand::Ping(command) => {
             ping_handler::handle(command, sender, session, system).await
@@ -74,6 +85,9 @@ async fn try_handle(
         ServerCommand::GetClients(command) => {
             get_clients_handler::handle(command, sender, session, system).await
         }
+        ServerCommand::ReloadConfig(command) => {
+            reload_config_handler::handle(command, sender, session, system).await
+        }
         ServerCommand::GetUser(command) => {
             get_user_handler::handle(command, sender, session, system).await
         }
@@ -161,6 +175,15 @@ async fn try_handle(
         ServerCommand::PurgeTopic(command) => {
             purge_topic_handler::handle(command, sender, session, system).await
         }
+        ServerCommand::SetDeadLetterQueue(command) => {
+            set_dead_letter_queue_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::SetCleanupPolicy(command) => {
+            set_cleanup_policy_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::SetMaxTopicMessages(command) => {
+            set_max_topic_messages_handler::handle(command, sender, session, system).await
+        }
         ServerCommand::CreatePartitions(command) => {
             create_partitions_handler::handle(command, sender, session, system).await
         }
@@ -185,8 +208,38 @@ async fn try_handle(
         ServerCommand::LeaveConsumerGroup(command) => {
             leave_consumer_group_handler::handle(command, sender, session, system).await
         }
+        ServerCommand::HeartbeatConsumerGroup(command) => {
+            heartbeat_consumer_group_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::GetClusterMetadata(command) => {
+            get_cluster_metadata_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::AddClusterNode(command) => {
+            add_cluster_node_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::RemoveClusterNode(command) => {
+            remove_cluster_node_handler::handle(command, sender, session, system).await
+        }
         ServerCommand::FlushUnsavedBuffer(command) => {
             flush_unsaved_buffer_handler::handle(command, sender, session, system).await
         }
+        ServerCommand::NackMessage(command) => {
+            nack_message_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::AckMessage(command) => {
+            ack_message_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::InitProducer(command) => {
+            init_producer_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::BeginTransaction(command) => {
+            begin_transaction_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::CommitTransaction(command) => {
+            commit_transaction_handler::handle(command, sender, session, system).await
+        }
+        ServerCommand::AbortTransaction(command) => {
+            abort_transaction_handler::handle(command, sender, session, system).await
+        }
     }
 }
@@ -0,0 +1,3 @@
+pub mod add_cluster_node_handler;
+pub mod get_cluster_metadata_handler;
+pub mod remove_cluster_node_handler;
@@ -6,6 +6,8 @@ use crate::streaming::systems::system::SharedSystem;
 use anyhow::Result;
 use iggy::consumer_groups::create_consumer_group::CreateConsumerGroup;
 use iggy::error::IggyError;
+use iggy::identifier::Identifier;
+use iggy::utils::text;
 use tracing::{debug, instrument};
 
 #[instrument(skip_all, fields(iggy_user_id = session.get_user_id(), iggy_client_id = session.client_id, iggy_stream_id = command.stream_id.as_string(), iggy_topic_id = command.topic_id.as_string()))]
@@ -16,6 +18,26 @@ pub async fn handle(
     system: &SharedSystem,
 ) -> Result<(), IggyError> {
     debug!("session: {session}, command: {command}");
+    if system.is_clustered().await {
+        let stream_id = command.stream_id.clone();
+        let topic_id = command.topic_id.clone();
+        let name = text::to_lowercase_non_whitespace(&command.name);
+        system
+            .replicate(session, EntryCommand::CreateConsumerGroup(command))
+            .await?;
+        let system = system.read().await;
+        let consumer_group = system
+            .get_stream(&stream_id)?
+            .get_topic(&topic_id)?
+            .get_consumer_group(&Identifier::named(&name)?)?
+            .read()
+            .await;
+        sender
+            .send_ok_response(&mapper::map_consumer_group(&consumer_group).await)
+            .await?;
+        return Ok(());
+    }
+
     let response;
     {
         let mut system = system.write().await;
@@ -26,6 +48,7 @@ pub async fn handle(
                 &command.topic_id,
                 command.group_id,
                 &command.name,
+                command.mode,
             )
             .await?;
         let consumer_group = consumer_group.read().await;
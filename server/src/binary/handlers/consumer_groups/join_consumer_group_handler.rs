@@ -1,3 +1,4 @@
+use crate::binary::mapper;
 use crate::binary::sender::Sender;
 use crate::streaming::session::Session;
 use crate::streaming::systems::system::SharedSystem;
@@ -15,14 +16,16 @@ pub async fn handle(
 ) -> Result<(), IggyError> {
     debug!("session: {session}, command: {command}");
     let system = system.read().await;
-    system
+    let membership = system
         .join_consumer_group(
             session,
             &command.stream_id,
             &command.topic_id,
             &command.group_id,
+            command.member_name.as_deref(),
         )
         .await?;
-    sender.send_empty_ok_response().await?;
+    let membership = mapper::map_consumer_group_membership(&membership);
+    sender.send_ok_response(&membership).await?;
     Ok(())
 }
@@ -0,0 +1,22 @@
+use crate::binary::mapper;
+use crate::binary::sender::Sender;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::SharedSystem;
+use anyhow::Result;
+use iggy::error::IggyError;
+use iggy::messages::init_producer::InitProducer;
+use tracing::debug;
+
+pub async fn handle(
+    command: InitProducer,
+    sender: &mut dyn Sender,
+    session: &Session,
+    system: &SharedSystem,
+) -> Result<(), IggyError> {
+    debug!("session: {session}, command: {command}");
+    let system = system.read().await;
+    let producer_id = system.init_producer(session).await?;
+    let producer = mapper::map_producer(producer_id);
+    sender.send_ok_response(&producer).await?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::binary::sender::Sender;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::SharedSystem;
+use anyhow::Result;
+use iggy::error::IggyError;
+use iggy::messages::nack_message::NackMessage;
+use tracing::debug;
+
+pub async fn handle(
+    command: NackMessage,
+    sender: &mut dyn Sender,
+    session: &Session,
+    system: &SharedSystem,
+) -> Result<(), IggyError> {
+    debug!("session: {session}, command: {command}");
+    let system = system.read().await;
+    system
+        .nack_message(
+            session,
+            &command.consumer,
+            &command.stream_id,
+            &command.topic_id,
+            command.partition_id,
+            command.offset,
+            &command.reason,
+        )
+        .await?;
+    sender.send_empty_ok_response().await?;
+    Ok(())
+}
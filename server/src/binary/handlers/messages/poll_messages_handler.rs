@@ -1,7 +1,7 @@
 use crate::binary::mapper;
 use crate::binary::sender::Sender;
 use crate::streaming::session::Session;
-use crate::streaming::systems::messages::PollingArgs;
+use crate::streaming::systems::messages::{PollingArgs, PollingResult};
 use crate::streaming::systems::system::SharedSystem;
 use anyhow::Result;
 use iggy::error::IggyError;
@@ -16,17 +16,53 @@ pub async fn handle(
 ) -> Result<(), IggyError> {
     debug!("session: {session}, command: {command}");
     let system = system.read().await;
-    let messages = system
-        .poll_messages(
+    let args = PollingArgs::new(
+        command.strategy,
+        command.count,
+        command.auto_commit,
+        command.filter.clone(),
+        command.isolation,
+    );
+    if !command.batched {
+        let messages = system
+            .poll_messages(
+                session,
+                &command.consumer,
+                &command.stream_id,
+                &command.topic_id,
+                command.partition_id,
+                args,
+            )
+            .await?;
+        let messages = mapper::map_polled_messages(&messages);
+        sender.send_ok_response(&messages).await?;
+        return Ok(());
+    }
+
+    let polling_result = system
+        .poll_batches(
             session,
             &command.consumer,
             &command.stream_id,
             &command.topic_id,
             command.partition_id,
-            PollingArgs::new(command.strategy, command.count, command.auto_commit),
+            args,
         )
         .await?;
-    let messages = mapper::map_polled_messages(&messages);
-    sender.send_ok_response(&messages).await?;
+    // The system is released before sending the batches, as the kernel might need to wait for the socket.
+    // The files of the regions have been opened while polling, so the segments can be deleted or compacted meanwhile.
+    drop(system);
+    match polling_result {
+        PollingResult::Batches(batches) => {
+            let header = mapper::map_polled_batches(&batches);
+            sender
+                .send_ok_response_with_regions(&header, &batches.regions)
+                .await?;
+        }
+        PollingResult::Messages(messages) => {
+            let messages = mapper::map_polled_messages_as_batch(&messages);
+            sender.send_ok_response(&messages).await?;
+        }
+    }
     Ok(())
 }
@@ -3,7 +3,7 @@ use crate::streaming::session::Session;
 use crate::streaming::systems::system::SharedSystem;
 use anyhow::Result;
 use iggy::error::IggyError;
-use iggy::messages::send_messages::SendMessages;
+use iggy::messages::send_messages::{Acks, SendMessages};
 use tracing::debug;
 
 pub async fn handle(
@@ -13,14 +13,34 @@ pub async fn handle(
     system: &SharedSystem,
 ) -> Result<(), IggyError> {
     debug!("session: {session}, command: {command}");
-    let system = system.read().await;
     let stream_id = command.stream_id;
     let topic_id = command.topic_id;
     let partitioning = command.partitioning;
-    let messages = command.messages;
-    system
-        .append_messages(session, stream_id, topic_id, partitioning, messages)
-        .await?;
+    let replicated_messages = {
+        let system = system.read().await;
+        if let Some(compressed_messages) = command.compressed_messages {
+            system
+                .append_compressed_messages(
+                    session,
+                    stream_id,
+                    topic_id,
+                    partitioning,
+                    compressed_messages,
+                )
+                .await?
+        } else {
+            let messages = command.messages;
+            system
+                .append_messages(session, stream_id, topic_id, partitioning, messages)
+                .await?
+        }
+    };
+    // The replicas fetch the messages through the system, thus its lock must not be held while waiting for them.
+    if command.acks == Acks::All {
+        if let Some(replicated_messages) = replicated_messages {
+            replicated_messages.wait_for_in_sync_replicas().await?;
+        }
+    }
     sender.send_empty_ok_response().await?;
     Ok(())
 }
@@ -1,3 +1,4 @@
+pub mod cluster;
 pub mod consumer_groups;
 pub mod consumer_offsets;
 pub mod messages;
@@ -6,4 +7,5 @@ pub mod personal_access_tokens;
 pub mod streams;
 pub mod system;
 pub mod topics;
+pub mod transactions;
 pub mod users;
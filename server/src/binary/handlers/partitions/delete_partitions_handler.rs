@@ -15,6 +15,14 @@ pub async fn handle(
     system: &SharedSystem,
 ) -> Result<(), IggyError> {
     debug!("session: {session}, command: {command}");
+    if system.is_clustered().await {
+        system
+            .replicate(session, EntryCommand::DeletePartitions(command))
+            .await?;
+        sender.send_empty_ok_response().await?;
+        return Ok(());
+    }
+
     {
         let mut system = system.write().await;
         system
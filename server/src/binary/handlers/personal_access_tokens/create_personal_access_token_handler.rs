@@ -8,6 +8,7 @@ use crate::streaming::systems::system::SharedSystem;
 use anyhow::Result;
 use iggy::error::IggyError;
 use iggy::personal_access_tokens::create_personal_access_token::CreatePersonalAccessToken;
+use iggy::utils::timestamp::IggyTimestamp;
 use tracing::{debug, instrument};
 
 #[instrument(skip_all, fields(iggy_user_id = session.get_user_id(), iggy_client_id = session.client_id))]
@@ -20,6 +21,30 @@ pub async fn handle(
     let bytes;
     let token_hash;
     debug!("session: {session}, command: {command}");
+    if system.is_clustered().await {
+        // Only the hash of the token is replicated, while the raw one is returned to the user.
+        let (_, token) = PersonalAccessToken::new(
+            session.get_user_id(),
+            &command.name,
+            IggyTimestamp::now(),
+            command.expiry,
+        );
+        let hash = PersonalAccessToken::hash_token(&token);
+        system
+            .replicate(
+                session,
+                EntryCommand::CreatePersonalAccessToken(CreatePersonalAccessTokenWithHash {
+                    command,
+                    hash,
+                }),
+            )
+            .await?;
+        sender
+            .send_ok_response(&mapper::map_raw_pat(&token))
+            .await?;
+        return Ok(());
+    }
+
     {
         let mut system = system.write().await;
         let token = system
@@ -5,7 +5,9 @@ use crate::streaming::session::Session;
 use crate::streaming::systems::system::SharedSystem;
 use anyhow::Result;
 use iggy::error::IggyError;
+use iggy::identifier::Identifier;
 use iggy::streams::create_stream::CreateStream;
+use iggy::utils::text;
 use tracing::{debug, instrument};
 
 #[instrument(skip_all, fields(iggy_user_id = session.get_user_id(), iggy_client_id = session.client_id))]
@@ -16,6 +18,17 @@ pub async fn handle(
     system: &SharedSystem,
 ) -> Result<(), IggyError> {
     debug!("session: {session}, command: {command}");
+    if system.is_clustered().await {
+        let name = text::to_lowercase_non_whitespace(&command.name);
+        system
+            .replicate(session, EntryCommand::CreateStream(command))
+            .await?;
+        let system = system.read().await;
+        let stream = system.get_stream(&Identifier::named(&name)?)?;
+        sender.send_ok_response(&mapper::map_stream(stream)).await?;
+        return Ok(());
+    }
+
     let response;
     {
         let mut system = system.write().await;
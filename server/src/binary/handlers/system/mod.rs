@@ -3,3 +3,4 @@ pub mod get_clients_handler;
 pub mod get_me_handler;
 pub mod get_stats_handler;
 pub mod ping_handler;
+pub mod reload_config_handler;
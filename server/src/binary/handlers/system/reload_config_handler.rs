@@ -0,0 +1,20 @@
+use crate::binary::sender::Sender;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::SharedSystem;
+use anyhow::Result;
+use iggy::error::IggyError;
+use iggy::system::reload_config::ReloadConfig;
+use tracing::debug;
+
+pub async fn handle(
+    command: ReloadConfig,
+    sender: &mut dyn Sender,
+    session: &Session,
+    system: &SharedSystem,
+) -> Result<(), IggyError> {
+    debug!("session: {session}, command: {command}");
+    let system = system.read().await;
+    system.reload_config(session).await?;
+    sender.send_empty_ok_response().await?;
+    Ok(())
+}
@@ -5,7 +5,9 @@ use crate::streaming::session::Session;
 use crate::streaming::systems::system::SharedSystem;
 use anyhow::Result;
 use iggy::error::IggyError;
+use iggy::identifier::Identifier;
 use iggy::topics::create_topic::CreateTopic;
+use iggy::utils::text;
 use tracing::{debug, instrument};
 
 #[instrument(skip_all, fields(iggy_user_id = session.get_user_id(), iggy_client_id = session.client_id, iggy_stream_id = command.stream_id.as_string()))]
@@ -16,6 +18,22 @@ pub async fn handle(
     system: &SharedSystem,
 ) -> Result<(), IggyError> {
     debug!("session: {session}, command: {command}");
+    if system.is_clustered().await {
+        let stream_id = command.stream_id.clone();
+        let name = text::to_lowercase_non_whitespace(&command.name);
+        system
+            .replicate(session, EntryCommand::CreateTopic(command))
+            .await?;
+        let system = system.read().await;
+        let topic = system
+            .get_stream(&stream_id)?
+            .get_topic(&Identifier::named(&name)?)?;
+        sender
+            .send_ok_response(&mapper::map_topic(topic).await)
+            .await?;
+        return Ok(());
+    }
+
     let response;
     {
         let mut system = system.write().await;
@@ -3,4 +3,7 @@ pub mod delete_topic_handler;
 pub mod get_topic_handler;
 pub mod get_topics_handler;
 pub mod purge_topic_handler;
+pub mod set_cleanup_policy_handler;
+pub mod set_dead_letter_queue_handler;
+pub mod set_max_topic_messages_handler;
 pub mod update_topic_handler;
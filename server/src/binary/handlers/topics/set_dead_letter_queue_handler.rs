@@ -0,0 +1,44 @@
+use crate::binary::sender::Sender;
+use crate::state::command::EntryCommand;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::SharedSystem;
+use anyhow::Result;
+use iggy::error::IggyError;
+use iggy::topics::set_dead_letter_queue::SetDeadLetterQueue;
+use tracing::{debug, instrument};
+
+#[instrument(skip_all, fields(iggy_user_id = session.get_user_id(), iggy_client_id = session.client_id, iggy_stream_id = command.stream_id.as_string(), iggy_topic_id = command.topic_id.as_string()))]
+pub async fn handle(
+    mut command: SetDeadLetterQueue,
+    sender: &mut dyn Sender,
+    session: &Session,
+    system: &SharedSystem,
+) -> Result<(), IggyError> {
+    debug!("session: {session}, command: {command}");
+    if system.is_clustered().await {
+        system
+            .replicate(session, EntryCommand::SetDeadLetterQueue(command))
+            .await?;
+        sender.send_empty_ok_response().await?;
+        return Ok(());
+    }
+
+    let mut system = system.write().await;
+    command.dead_letter_queue = system
+        .set_dead_letter_queue(
+            session,
+            &command.stream_id,
+            &command.topic_id,
+            command.dead_letter_queue.take(),
+        )
+        .await?;
+    system
+        .state
+        .apply(
+            session.get_user_id(),
+            EntryCommand::SetDeadLetterQueue(command),
+        )
+        .await?;
+    sender.send_empty_ok_response().await?;
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::binary::sender::Sender;
+use crate::state::command::EntryCommand;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::SharedSystem;
+use anyhow::Result;
+use iggy::error::IggyError;
+use iggy::topics::set_max_topic_messages::SetMaxTopicMessages;
+use tracing::{debug, instrument};
+
+#[instrument(skip_all, fields(iggy_user_id = session.get_user_id(), iggy_client_id = session.client_id, iggy_stream_id = command.stream_id.as_string(), iggy_topic_id = command.topic_id.as_string()))]
+pub async fn handle(
+    command: SetMaxTopicMessages,
+    sender: &mut dyn Sender,
+    session: &Session,
+    system: &SharedSystem,
+) -> Result<(), IggyError> {
+    debug!("session: {session}, command: {command}");
+    if system.is_clustered().await {
+        system
+            .replicate(session, EntryCommand::SetMaxTopicMessages(command))
+            .await?;
+        sender.send_empty_ok_response().await?;
+        return Ok(());
+    }
+
+    let mut system = system.write().await;
+    system
+        .set_max_topic_messages(
+            session,
+            &command.stream_id,
+            &command.topic_id,
+            command.max_messages,
+        )
+        .await?;
+    system
+        .state
+        .apply(
+            session.get_user_id(),
+            EntryCommand::SetMaxTopicMessages(command),
+        )
+        .await?;
+    sender.send_empty_ok_response().await?;
+    Ok(())
+}
@@ -6,7 +6,9 @@ use crate::streaming::systems::system::SharedSystem;
 use crate::streaming::utils::crypto;
 use anyhow::Result;
 use iggy::error::IggyError;
+use iggy::identifier::Identifier;
 use iggy::users::create_user::CreateUser;
+use iggy::utils::text;
 use tracing::{debug, instrument};
 
 #[instrument(skip_all, fields(iggy_user_id = session.get_user_id(), iggy_client_id = session.client_id))]
@@ -17,6 +19,17 @@ pub async fn handle(
     system: &SharedSystem,
 ) -> Result<(), IggyError> {
     debug!("session: {session}, command: {command}");
+    if system.is_clustered().await {
+        let username = text::to_lowercase_non_whitespace(&command.username);
+        system
+            .replicate(session, EntryCommand::CreateUser(command))
+            .await?;
+        let system = system.read().await;
+        let user = system.get_user(&Identifier::named(&username)?)?;
+        sender.send_ok_response(&mapper::map_user(user)).await?;
+        return Ok(());
+    }
+
     let response;
     {
         let mut system = system.write().await;
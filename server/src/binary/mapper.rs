@@ -1,4 +1,6 @@
+use crate::streaming::batching::message_batch::RETAINED_BATCH_OVERHEAD;
 use crate::streaming::clients::client_manager::{Client, Transport};
+use crate::streaming::models::messages::PolledBatches;
 use crate::streaming::partitions::partition::Partition;
 use crate::streaming::personal_access_tokens::personal_access_token::PersonalAccessToken;
 use crate::streaming::streams::stream::Stream;
@@ -8,6 +10,8 @@ use crate::streaming::users::user::User;
 use bytes::{BufMut, Bytes, BytesMut};
 use iggy::bytes_serializable::BytesSerializable;
 use iggy::locking::{IggySharedMut, IggySharedMutFn};
+use iggy::models::cluster::ClusterMetadata;
+use iggy::models::consumer_group::ConsumerGroupMembership;
 use iggy::models::consumer_offset_info::ConsumerOffsetInfo;
 use iggy::models::messages::PolledMessages;
 use iggy::models::stats::Stats;
@@ -53,6 +57,20 @@ pub fn map_consumer_offset(offset: &ConsumerOffsetInfo) -> Bytes {
     bytes.freeze()
 }
 
+pub fn map_cluster_metadata(metadata: &ClusterMetadata) -> Bytes {
+    let mut bytes = BytesMut::new();
+    bytes.put_u32_le(metadata.node_id);
+    bytes.put_u64_le(metadata.term);
+    bytes.put_u32_le(metadata.leader_id.unwrap_or(0));
+    bytes.put_u32_le(metadata.nodes.len() as u32);
+    for node in &metadata.nodes {
+        bytes.put_u32_le(node.id);
+        bytes.put_u8(node.address.len() as u8);
+        bytes.put_slice(node.address.as_bytes());
+    }
+    bytes.freeze()
+}
+
 pub fn map_client(client: &Client) -> Bytes {
     let mut bytes = BytesMut::new();
     extend_client(client, &mut bytes);
@@ -136,6 +154,72 @@ pub fn map_polled_messages(polled_messages: &PolledMessages) -> Bytes {
     bytes.freeze()
 }
 
+/// Maps the header of the polled batches, which are followed by the regions of the segment log files.
+pub fn map_polled_batches(polled_batches: &PolledBatches) -> Bytes {
+    let mut bytes = BytesMut::with_capacity(28);
+    bytes.put_u32_le(polled_batches.partition_id);
+    bytes.put_u64_le(polled_batches.current_offset);
+    bytes.put_u64_le(polled_batches.start_offset);
+    bytes.put_u64_le(polled_batches.end_offset);
+    bytes.freeze()
+}
+
+/// Maps the polled messages to the layout of the polled batches, with all the messages within a single batch.
+pub fn map_polled_messages_as_batch(polled_messages: &PolledMessages) -> Bytes {
+    let (Some(first_message), Some(last_message)) = (
+        polled_messages.messages.first(),
+        polled_messages.messages.last(),
+    ) else {
+        let polled_batches = PolledBatches {
+            partition_id: polled_messages.partition_id,
+            current_offset: polled_messages.current_offset,
+            start_offset: 0,
+            end_offset: 0,
+            regions: Vec::new(),
+        };
+        return map_polled_batches(&polled_batches);
+    };
+
+    let mut messages = BytesMut::new();
+    for message in polled_messages.messages.iter() {
+        let headers = message.headers.as_ref().map(|headers| headers.to_bytes());
+        let headers_length = headers.as_ref().map_or(0, |headers| headers.len());
+        messages.put_u32_le(41 + headers_length as u32 + message.length);
+        messages.put_u64_le(message.offset);
+        messages.put_u8(message.state.as_code());
+        messages.put_u64_le(message.timestamp);
+        messages.put_u128_le(message.id);
+        messages.put_u32_le(message.checksum);
+        messages.put_u32_le(headers_length as u32);
+        if let Some(headers) = headers {
+            messages.put_slice(&headers);
+        }
+        messages.put_slice(&message.payload);
+    }
+
+    let polled_batches = PolledBatches {
+        partition_id: polled_messages.partition_id,
+        current_offset: polled_messages.current_offset,
+        start_offset: first_message.offset,
+        end_offset: last_message.offset,
+        regions: Vec::new(),
+    };
+    let max_timestamp = polled_messages
+        .messages
+        .iter()
+        .map(|message| message.timestamp)
+        .max()
+        .unwrap_or_default();
+    let mut bytes = BytesMut::with_capacity(28 + RETAINED_BATCH_OVERHEAD as usize + messages.len());
+    bytes.put_slice(&map_polled_batches(&polled_batches));
+    bytes.put_u64_le(first_message.offset);
+    bytes.put_u32_le(messages.len() as u32);
+    bytes.put_u32_le((last_message.offset - first_message.offset) as u32);
+    bytes.put_u64_le(max_timestamp);
+    bytes.put_slice(&messages);
+    bytes.freeze()
+}
+
 pub fn map_stream(stream: &Stream) -> Bytes {
     let mut bytes = BytesMut::new();
     extend_stream(stream, &mut bytes);
@@ -187,6 +271,29 @@ pub async fn map_consumer_group(consumer_group: &ConsumerGroup) -> Bytes {
     bytes.freeze()
 }
 
+pub fn map_consumer_group_membership(membership: &ConsumerGroupMembership) -> Bytes {
+    let mut bytes = BytesMut::with_capacity(12 + 4 * membership.partitions.len());
+    bytes.put_u32_le(membership.member_id);
+    bytes.put_u32_le(membership.generation);
+    bytes.put_u32_le(membership.partitions.len() as u32);
+    for partition in &membership.partitions {
+        bytes.put_u32_le(*partition);
+    }
+    bytes.freeze()
+}
+
+pub fn map_transaction(transaction_id: u64) -> Bytes {
+    let mut bytes = BytesMut::with_capacity(8);
+    bytes.put_u64_le(transaction_id);
+    bytes.freeze()
+}
+
+pub fn map_producer(producer_id: u64) -> Bytes {
+    let mut bytes = BytesMut::with_capacity(8);
+    bytes.put_u64_le(producer_id);
+    bytes.freeze()
+}
+
 pub async fn map_consumer_groups(consumer_groups: &[&RwLock<ConsumerGroup>]) -> Bytes {
     let mut bytes = BytesMut::new();
     for consumer_group in consumer_groups {
@@ -233,6 +340,8 @@ fn extend_consumer_group(consumer_group: &ConsumerGroup, bytes: &mut BytesMut) {
     bytes.put_u32_le(consumer_group.group_id);
     bytes.put_u32_le(consumer_group.partitions_count);
     bytes.put_u32_le(consumer_group.get_members().len() as u32);
+    bytes.put_u32_le(consumer_group.generation);
+    bytes.put_u32_le(consumer_group.evicted_members_count);
     bytes.put_u8(consumer_group.name.len() as u8);
     bytes.put_slice(consumer_group.name.as_bytes());
 }
@@ -1,5 +1,8 @@
+use crate::streaming::models::messages::FileRegion;
 use async_trait::async_trait;
 use iggy::error::IggyError;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
 #[async_trait]
 pub trait Sender: Sync + Send {
@@ -7,4 +10,30 @@ pub trait Sender: Sync + Send {
     async fn send_empty_ok_response(&mut self) -> Result<(), IggyError>;
     async fn send_ok_response(&mut self, payload: &[u8]) -> Result<(), IggyError>;
     async fn send_error_response(&mut self, error: IggyError) -> Result<(), IggyError>;
+
+    /// Sends the payload followed by the regions of the files as a single response.
+    /// The regions are loaded into memory, unless the transport can send them directly from the files.
+    async fn send_ok_response_with_regions(
+        &mut self,
+        payload: &[u8],
+        regions: &[FileRegion],
+    ) -> Result<(), IggyError> {
+        let regions_size = regions.iter().map(|region| region.length).sum::<u64>();
+        let mut bytes = Vec::with_capacity(payload.len() + regions_size as usize);
+        bytes.extend_from_slice(payload);
+        for region in regions {
+            read_region(region, &mut bytes).await?;
+        }
+        self.send_ok_response(&bytes).await
+    }
+}
+
+async fn read_region(region: &FileRegion, bytes: &mut Vec<u8>) -> Result<(), IggyError> {
+    // The cloned descriptor shares the position with the original one, which is used only for this response.
+    let mut file = File::from_std(region.file.try_clone()?);
+    file.seek(std::io::SeekFrom::Start(region.position)).await?;
+    let start = bytes.len();
+    bytes.resize(start + region.length as usize, 0);
+    file.read_exact(&mut bytes[start..]).await?;
+    Ok(())
 }
@@ -0,0 +1,95 @@
+use crate::channels::server_command::ServerCommand;
+use crate::configs::system::TransactionConfig;
+use crate::streaming::systems::system::SharedSystem;
+use async_trait::async_trait;
+use flume::Sender;
+use iggy::utils::duration::IggyDuration;
+use tokio::time;
+use tracing::{debug, error, info, instrument};
+
+pub struct TransactionsTimeout {
+    enabled: bool,
+    interval: IggyDuration,
+    sender: Sender<AbortExpiredTransactionsCommand>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct AbortExpiredTransactionsCommand;
+
+#[derive(Debug, Default, Clone)]
+pub struct AbortExpiredTransactionsExecutor;
+
+impl TransactionsTimeout {
+    pub fn new(
+        config: &TransactionConfig,
+        sender: Sender<AbortExpiredTransactionsCommand>,
+    ) -> Self {
+        Self {
+            enabled: config.enabled,
+            interval: config.timeout,
+            sender,
+        }
+    }
+
+    pub fn start(&self) {
+        if !self.enabled {
+            info!("Transactions are disabled, expired transactions will not be aborted.");
+            return;
+        }
+
+        let interval = self.interval;
+        let sender = self.sender.clone();
+        info!("Transactions are enabled, expired transactions will be aborted every: {interval}.");
+        tokio::spawn(async move {
+            let mut interval_timer = time::interval(interval.get_duration());
+            loop {
+                interval_timer.tick().await;
+                debug!("Aborting expired transactions...");
+                sender
+                    .send(AbortExpiredTransactionsCommand)
+                    .unwrap_or_else(|error| {
+                        error!(
+                            "Failed to send AbortExpiredTransactionsCommand. Error: {}",
+                            error
+                        );
+                    });
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl ServerCommand<AbortExpiredTransactionsCommand> for AbortExpiredTransactionsExecutor {
+    #[instrument(skip_all)]
+    async fn execute(&mut self, system: &SharedSystem, _command: AbortExpiredTransactionsCommand) {
+        let system = system.read().await;
+        if let Err(error) = system.abort_expired_transactions().await {
+            error!("Failed to abort expired transactions. Error: {error}");
+        }
+    }
+
+    fn start_command_sender(
+        &mut self,
+        _system: SharedSystem,
+        config: &crate::configs::server::ServerConfig,
+        sender: Sender<AbortExpiredTransactionsCommand>,
+    ) {
+        let transactions_timeout = TransactionsTimeout::new(&config.system.transaction, sender);
+        transactions_timeout.start();
+    }
+
+    fn start_command_consumer(
+        mut self,
+        system: SharedSystem,
+        _config: &crate::configs::server::ServerConfig,
+        receiver: flume::Receiver<AbortExpiredTransactionsCommand>,
+    ) {
+        tokio::spawn(async move {
+            let system = system.clone();
+            while let Ok(command) = receiver.recv_async().await {
+                self.execute(&system, command).await;
+            }
+            info!("Transactions timeout receiver stopped.");
+        });
+    }
+}
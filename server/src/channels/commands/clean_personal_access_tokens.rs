@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use flume::Sender;
 use iggy::utils::duration::IggyDuration;
 use iggy::utils::timestamp::IggyTimestamp;
-use tokio::time;
+use tokio::time::{self, Instant};
 use tracing::{debug, error, info, instrument};
 
 pub struct PersonalAccessTokenCleaner {
@@ -32,27 +32,41 @@ impl PersonalAccessTokenCleaner {
         }
     }
 
-    pub fn start(&self) {
+    pub fn start(&self, system: SharedSystem) {
         if !self.enabled {
             info!("Personal access token cleaner is disabled.");
             return;
         }
 
-        let interval = self.interval;
+        let mut interval = self.interval;
         let sender = self.sender.clone();
         info!("Personal access token cleaner is enabled, expired tokens will be deleted every: {interval}.");
         tokio::spawn(async move {
+            let mut config_changes = system.read().await.subscribe_to_config_changes();
             let mut interval_timer = time::interval(interval.get_duration());
             loop {
-                interval_timer.tick().await;
-                sender
-                    .send(CleanPersonalAccessTokensCommand)
-                    .unwrap_or_else(|error| {
-                        error!(
-                            "Failed to send CleanPersonalAccessTokensCommand. Error: {}",
-                            error
-                        );
-                    });
+                tokio::select! {
+                    _ = interval_timer.tick() => {
+                        sender
+                            .send(CleanPersonalAccessTokensCommand)
+                            .unwrap_or_else(|error| {
+                                error!(
+                                    "Failed to send CleanPersonalAccessTokensCommand. Error: {}",
+                                    error
+                                );
+                            });
+                    }
+                    config = config_changes.changed() => {
+                        if config.personal_access_token_cleaner_interval != interval {
+                            interval = config.personal_access_token_cleaner_interval;
+                            interval_timer = time::interval_at(
+                                Instant::now() + interval.get_duration(),
+                                interval.get_duration(),
+                            );
+                            info!("Personal access token cleaner interval has been changed to: {interval}.");
+                        }
+                    }
+                }
             }
         });
     }
@@ -92,13 +106,13 @@ impl ServerCommand<CleanPersonalAccessTokensCommand> for CleanPersonalAccessToke
 
     fn start_command_sender(
         &mut self,
-        _system: SharedSystem,
+        system: SharedSystem,
         config: &crate::configs::server::ServerConfig,
         sender: Sender<CleanPersonalAccessTokensCommand>,
     ) {
         let personal_access_token_cleaner =
             PersonalAccessTokenCleaner::new(&config.personal_access_token.cleaner, sender);
-        personal_access_token_cleaner.start();
+        personal_access_token_cleaner.start(system);
     }
 
     fn start_command_consumer(
@@ -2,16 +2,20 @@ use crate::archiver::Archiver;
 use crate::channels::server_command::ServerCommand;
 use crate::configs::server::MessagesMaintenanceConfig;
 use crate::map_toggle_str;
+use crate::streaming::partitions::partition::Partition;
 use crate::streaming::systems::system::SharedSystem;
 use crate::streaming::topics::topic::Topic;
 use async_trait::async_trait;
 use flume::Sender;
 use iggy::error::IggyError;
 use iggy::locking::IggySharedMutFn;
+use iggy::models::cleanup_policy::CleanupPolicy;
 use iggy::utils::duration::IggyDuration;
 use iggy::utils::timestamp::IggyTimestamp;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tokio::time;
+use tokio::fs;
+use tokio::time::{self, Instant};
 use tracing::{debug, error, info, instrument};
 
 pub struct MessagesMaintainer {
@@ -43,13 +47,13 @@ impl MessagesMaintainer {
         }
     }
 
-    pub fn start(&self) {
+    pub fn start(&self, system: SharedSystem) {
         if !self.cleaner_enabled && !self.archiver_enabled {
             info!("Messages maintainer is disabled.");
             return;
         }
 
-        let interval = self.interval;
+        let mut interval = self.interval;
         let sender = self.sender.clone();
         info!(
             "Message maintainer, cleaner is {}, archiver is {}, interval: {interval}",
@@ -59,17 +63,31 @@ impl MessagesMaintainer {
         let clean_messages = self.cleaner_enabled;
         let archive_messages = self.archiver_enabled;
         tokio::spawn(async move {
+            let mut config_changes = system.read().await.subscribe_to_config_changes();
             let mut interval_timer = time::interval(interval.get_duration());
             loop {
-                interval_timer.tick().await;
-                sender
-                    .send(MaintainMessagesCommand {
-                        clean_messages,
-                        archive_messages,
-                    })
-                    .unwrap_or_else(|err| {
-                        error!("Failed to send MaintainMessagesCommand. Error: {}", err);
-                    });
+                tokio::select! {
+                    _ = interval_timer.tick() => {
+                        sender
+                            .send(MaintainMessagesCommand {
+                                clean_messages,
+                                archive_messages,
+                            })
+                            .unwrap_or_else(|err| {
+                                error!("Failed to send MaintainMessagesCommand. Error: {}", err);
+                            });
+                    }
+                    config = config_changes.changed() => {
+                        if config.messages_maintenance_interval != interval {
+                            interval = config.messages_maintenance_interval;
+                            interval_timer = time::interval_at(
+                                Instant::now() + interval.get_duration(),
+                                interval.get_duration(),
+                            );
+                            info!("Message maintainer interval has been changed to: {interval}.");
+                        }
+                    }
+                }
             }
         });
     }
@@ -89,13 +107,34 @@ impl ServerCommand<MaintainMessagesCommand> for MaintainMessagesExecutor {
                 } else {
                     None
                 };
-                let expired_segments = handle_expired_segments(
-                    topic,
-                    archiver.clone(),
-                    system.config.segment.archive_expired,
-                    command.clean_messages,
-                )
-                .await;
+                if let Err(error) = topic.close_aged_segments(IggyTimestamp::now()).await {
+                    error!(
+                        "Failed to close aged segments for stream ID: {}, topic ID: {}. Error: {}",
+                        topic.stream_id, topic.topic_id, error
+                    );
+                }
+
+                let expired_segments = match topic.cleanup_policy {
+                    CleanupPolicy::Delete => {
+                        handle_expired_segments(
+                            topic,
+                            archiver.clone(),
+                            system.config.segment.archive_expired,
+                            command.clean_messages,
+                        )
+                        .await
+                    }
+                    CleanupPolicy::Compact {
+                        tombstone_retention,
+                    } => {
+                        handle_compacted_segments(
+                            topic,
+                            tombstone_retention,
+                            command.clean_messages,
+                        )
+                        .await
+                    }
+                };
                 if expired_segments.is_err() {
                     error!(
                         "Failed to get expired segments for stream ID: {}, topic ID: {}",
@@ -118,13 +157,37 @@ impl ServerCommand<MaintainMessagesCommand> for MaintainMessagesExecutor {
                     continue;
                 }
 
+                let excess_segments = handle_excess_messages(topic, command.clean_messages).await;
+                if excess_segments.is_err() {
+                    error!(
+                        "Failed to get segments exceeding max messages for stream ID: {}, topic ID: {}",
+                        topic.stream_id, topic.topic_id
+                    );
+                    continue;
+                }
+
+                if let Some(archiver) = archiver
+                    .as_ref()
+                    .filter(|_| system.storage.tiered.is_some())
+                {
+                    if let Err(error) = offload_segments(topic, archiver.clone()).await {
+                        error!(
+                            "Failed to offload segments for stream ID: {}, topic ID: {}. Error: {}",
+                            topic.stream_id, topic.topic_id, error
+                        );
+                    }
+                }
+
                 let deleted_expired_segments = expired_segments.unwrap();
                 let deleted_oldest_segments = oldest_segments.unwrap();
+                let deleted_excess_segments = excess_segments.unwrap();
                 let deleted_segments = HandledSegments {
                     segments_count: deleted_expired_segments.segments_count
-                        + deleted_oldest_segments.segments_count,
+                        + deleted_oldest_segments.segments_count
+                        + deleted_excess_segments.segments_count,
                     messages_count: deleted_expired_segments.messages_count
-                        + deleted_oldest_segments.messages_count,
+                        + deleted_oldest_segments.messages_count
+                        + deleted_excess_segments.messages_count,
                 };
 
                 if deleted_segments.segments_count == 0 {
@@ -155,7 +218,7 @@ impl ServerCommand<MaintainMessagesCommand> for MaintainMessagesExecutor {
 
     fn start_command_sender(
         &mut self,
-        _system: SharedSystem,
+        system: SharedSystem,
         config: &crate::configs::server::ServerConfig,
         sender: Sender<MaintainMessagesCommand>,
     ) {
@@ -168,7 +231,7 @@ impl ServerCommand<MaintainMessagesCommand> for MaintainMessagesExecutor {
 
         let messages_maintainer =
             MessagesMaintainer::new(&config.data_maintenance.messages, sender);
-        messages_maintainer.start();
+        messages_maintainer.start(system);
     }
 
     fn start_command_consumer(
@@ -236,6 +299,30 @@ async fn handle_expired_segments(
     }
 }
 
+async fn handle_compacted_segments(
+    topic: &Topic,
+    tombstone_retention: IggyDuration,
+    clean: bool,
+) -> Result<HandledSegments, IggyError> {
+    if !clean {
+        info!(
+            "Compacting segments is disabled for stream ID: {}, topic ID: {}",
+            topic.stream_id, topic.topic_id
+        );
+        return Ok(HandledSegments::none());
+    }
+
+    // The segments of the compacted topic are never deleted by the expiry, only the obsolete messages are removed.
+    let removed_messages = topic
+        .compact_segments(tombstone_retention, IggyTimestamp::now())
+        .await?;
+    info!(
+        "Compacted segments for stream ID: {}, topic ID: {}, removed {} messages",
+        topic.stream_id, topic.topic_id, removed_messages
+    );
+    Ok(HandledSegments::none())
+}
+
 async fn get_expired_segments(topic: &Topic, now: IggyTimestamp) -> Vec<SegmentsToHandle> {
     let expired_segments = topic
         .get_expired_segments_start_offsets_per_partition(now)
@@ -386,6 +473,99 @@ async fn get_oldest_segments(topic: &Topic) -> Vec<SegmentsToHandle> {
     oldest_segments
 }
 
+async fn handle_excess_messages(topic: &Topic, clean: bool) -> Result<HandledSegments, IggyError> {
+    if topic.max_messages == 0 {
+        return Ok(HandledSegments::none());
+    }
+
+    if !clean {
+        info!(
+            "Deleting segments exceeding max messages is disabled for stream ID: {}, topic ID: {}",
+            topic.stream_id, topic.topic_id
+        );
+        return Ok(HandledSegments::none());
+    }
+
+    let excess_segments = get_excess_segments(topic).await;
+    if excess_segments.is_empty() {
+        return Ok(HandledSegments::none());
+    }
+
+    delete_segments(topic, &excess_segments).await
+}
+
+/// Finds the oldest closed segments which can be deleted while the topic still retains at least its max messages.
+/// The segments are taken from the partitions holding the most messages first, to keep the partitions balanced.
+async fn get_excess_segments(topic: &Topic) -> Vec<SegmentsToHandle> {
+    let mut excess_messages_count = topic.get_excess_messages_count();
+    if excess_messages_count == 0 {
+        return Vec::new();
+    }
+
+    let mut partitions = Vec::new();
+    for partition in topic.partitions.values() {
+        let partition = partition.read().await;
+        let closed_segments = partition
+            .get_segments()
+            .iter()
+            .take_while(|segment| segment.is_closed)
+            .map(|segment| (segment.start_offset, segment.get_messages_count()))
+            .collect::<VecDeque<_>>();
+        partitions.push((
+            partition.partition_id,
+            partition.get_messages_count(),
+            closed_segments,
+        ));
+    }
+
+    let mut excess_segments = HashMap::<u32, Vec<u64>>::new();
+    loop {
+        let candidate = partitions
+            .iter_mut()
+            .filter(|(_, _, closed_segments)| {
+                closed_segments
+                    .front()
+                    .is_some_and(|(_, messages_count)| *messages_count <= excess_messages_count)
+            })
+            .max_by_key(|(_, messages_count, _)| *messages_count);
+        let Some((partition_id, partition_messages_count, closed_segments)) = candidate else {
+            break;
+        };
+
+        let (start_offset, messages_count) = closed_segments.pop_front().unwrap();
+        *partition_messages_count = partition_messages_count.saturating_sub(messages_count);
+        excess_messages_count -= messages_count;
+        excess_segments
+            .entry(*partition_id)
+            .or_default()
+            .push(start_offset);
+    }
+
+    if excess_segments.is_empty() {
+        debug!(
+            "No segments exceeding max messages found for stream ID: {}, topic ID: {}",
+            topic.stream_id, topic.topic_id
+        );
+        return Vec::new();
+    }
+
+    info!(
+        "Found {} segments exceeding max messages: {} for stream ID: {}, topic ID: {}.",
+        excess_segments.values().map(Vec::len).sum::<usize>(),
+        topic.max_messages,
+        topic.stream_id,
+        topic.topic_id
+    );
+
+    excess_segments
+        .into_iter()
+        .map(|(partition_id, start_offsets)| SegmentsToHandle {
+            partition_id,
+            start_offsets,
+        })
+        .collect()
+}
+
 #[derive()]
 struct SegmentsToHandle {
     partition_id: u32,
@@ -457,6 +637,16 @@ async fn archive_segments(
                     );
                     archived_segments += 1;
                 }
+
+                // The offsets are archived along with the segments, so that the consumers can resume after the restore.
+                let files = get_consumer_offsets_files(&partition).await;
+                let files = files.iter().map(|file| file.as_str()).collect::<Vec<_>>();
+                if let Err(error) = archiver.archive(&files, None).await {
+                    error!(
+                        "Failed to archive consumer offsets for stream ID: {}, topic ID: {}, partition ID: {}. Error: {}",
+                        topic.stream_id, topic.topic_id, partition.partition_id, error
+                    );
+                }
             }
             Err(error) => {
                 error!(
@@ -471,6 +661,67 @@ async fn archive_segments(
     Ok(archived_segments)
 }
 
+async fn get_consumer_offsets_files(partition: &Partition) -> Vec<String> {
+    let mut files = Vec::new();
+    for path in [
+        &partition.consumer_offsets_path,
+        &partition.consumer_group_offsets_path,
+    ] {
+        let Ok(mut dir_entries) = fs::read_dir(path).await else {
+            continue;
+        };
+        while let Ok(Some(dir_entry)) = dir_entries.next_entry().await {
+            if let Some(file) = dir_entry.path().to_str() {
+                files.push(file.to_owned());
+            }
+        }
+    }
+    files
+}
+
+async fn offload_segments(topic: &Topic, archiver: Arc<dyn Archiver>) -> Result<u32, IggyError> {
+    let mut offloaded_segments = 0;
+    for partition in topic.partitions.values() {
+        let mut partition = partition.write().await;
+        let partition_id = partition.partition_id;
+        for segment in partition.get_segments_mut() {
+            if !segment.is_closed || segment.is_offloaded {
+                continue;
+            }
+
+            // Only the archived segments can be offloaded, otherwise their messages would be lost.
+            match archiver.is_archived(&segment.log_path, None).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    debug!(
+                        "Segment with start offset: {} is not archived yet, it will not be offloaded for stream ID: {}, topic ID: {}, partition ID: {}",
+                        segment.start_offset, topic.stream_id, topic.topic_id, partition_id
+                    );
+                    continue;
+                }
+                Err(error) => {
+                    error!(
+                        "Failed to check if segment with start offset: {} is archived for stream ID: {}, topic ID: {}, partition ID: {}. Error: {}",
+                        segment.start_offset, topic.stream_id, topic.topic_id, partition_id, error
+                    );
+                    continue;
+                }
+            }
+
+            segment.offload().await?;
+            offloaded_segments += 1;
+        }
+    }
+
+    if offloaded_segments > 0 {
+        info!(
+            "Offloaded {} segments for stream ID: {}, topic ID: {}",
+            offloaded_segments, topic.stream_id, topic.topic_id
+        );
+    }
+    Ok(offloaded_segments)
+}
+
 async fn delete_segments(
     topic: &Topic,
     segments_to_delete: &[SegmentsToHandle],
@@ -516,3 +767,88 @@ async fn delete_segments(
         messages_count,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::SystemConfig;
+    use crate::streaming::storage::tests::get_test_system_storage;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
+    use iggy::utils::expiry::IggyExpiry;
+    use iggy::utils::topic_size::MaxTopicSize;
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+    #[tokio::test]
+    async fn given_unlimited_max_messages_no_segments_should_exceed_it() {
+        let topic = create_topic().await;
+
+        let excess_segments = get_excess_segments(&topic).await;
+
+        assert!(excess_segments.is_empty());
+    }
+
+    #[tokio::test]
+    async fn given_exceeded_max_messages_oldest_closed_segments_should_exceed_it_while_retaining_max_messages(
+    ) {
+        let mut topic = create_topic().await;
+        topic.max_messages = 15;
+
+        let mut excess_segments = get_excess_segments(&topic).await;
+
+        excess_segments.sort_by_key(|segments| segments.partition_id);
+        let excess_segments = excess_segments
+            .into_iter()
+            .map(|segments| (segments.partition_id, segments.start_offsets))
+            .collect::<Vec<_>>();
+        // Deleting the second closed segment of the first partition would leave less than 15 messages.
+        assert_eq!(excess_segments, vec![(1, vec![0]), (2, vec![0])]);
+    }
+
+    async fn create_topic() -> Topic {
+        let topic = Topic::create(
+            1,
+            1,
+            "test",
+            2,
+            Arc::new(SystemConfig::default()),
+            Arc::new(get_test_system_storage()),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU32::new(0)),
+            IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
+            MaxTopicSize::ServerDefault,
+            1,
+        )
+        .unwrap();
+        set_segments(&topic, 1, &[10, 10, 5]).await;
+        set_segments(&topic, 2, &[30, 5]).await;
+        topic
+    }
+
+    /// Sets the messages counts of the partition segments, all of them are closed except the last one.
+    async fn set_segments(topic: &Topic, partition_id: u32, messages_counts: &[u64]) {
+        let partition = topic.get_partition(partition_id).unwrap();
+        let mut partition = partition.write().await;
+        let mut start_offset = 0;
+        for (index, messages_count) in messages_counts.iter().enumerate() {
+            if index > 0 {
+                partition.add_persisted_segment(start_offset).await.unwrap();
+            }
+
+            let segment = partition.get_segments_mut().last_mut().unwrap();
+            segment.size_bytes = 1;
+            segment.current_offset = start_offset + messages_count - 1;
+            segment.end_offset = segment.current_offset;
+            segment.is_closed = index < messages_counts.len() - 1;
+            start_offset += messages_count;
+        }
+
+        partition
+            .messages_count
+            .store(start_offset, Ordering::SeqCst);
+        topic
+            .messages_count
+            .fetch_add(start_offset, Ordering::SeqCst);
+    }
+}
@@ -1,6 +1,9 @@
+pub mod abort_expired_transactions;
 pub mod archive_state;
 pub mod clean_personal_access_tokens;
 pub mod maintain_messages;
 pub mod print_sysinfo;
+pub mod reencrypt_segments;
 pub mod save_messages;
+pub mod snapshot_state;
 pub mod verify_heartbeats;
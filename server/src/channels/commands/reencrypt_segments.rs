@@ -0,0 +1,108 @@
+use crate::channels::server_command::ServerCommand;
+use crate::configs::system::EncryptionConfig;
+use crate::streaming::systems::system::SharedSystem;
+use async_trait::async_trait;
+use flume::Sender;
+use iggy::utils::duration::IggyDuration;
+use tokio::time;
+use tracing::{error, info, instrument};
+
+pub struct SegmentsReencryptor {
+    enabled: bool,
+    interval: IggyDuration,
+    sender: Sender<ReencryptSegmentsCommand>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ReencryptSegmentsCommand;
+
+#[derive(Debug, Default, Clone)]
+pub struct ReencryptSegmentsExecutor;
+
+impl SegmentsReencryptor {
+    pub fn new(config: &EncryptionConfig, sender: Sender<ReencryptSegmentsCommand>) -> Self {
+        Self {
+            enabled: config.enabled && config.reencryption.enabled,
+            interval: config.reencryption.interval,
+            sender,
+        }
+    }
+
+    pub fn start(&self) {
+        if !self.enabled {
+            info!("Segments re-encryption is disabled.");
+            return;
+        }
+
+        let interval = self.interval;
+        let sender = self.sender.clone();
+        info!("Segments re-encryption is enabled, closed segments will be re-encrypted every: {interval}.");
+        tokio::spawn(async move {
+            let mut interval_timer = time::interval(interval.get_duration());
+            loop {
+                interval_timer.tick().await;
+                sender
+                    .send(ReencryptSegmentsCommand)
+                    .unwrap_or_else(|error| {
+                        error!("Failed to send ReencryptSegmentsCommand. Error: {}", error);
+                    });
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl ServerCommand<ReencryptSegmentsCommand> for ReencryptSegmentsExecutor {
+    #[instrument(skip_all)]
+    async fn execute(&mut self, system: &SharedSystem, _command: ReencryptSegmentsCommand) {
+        let system = system.read().await;
+        let Some(keyring) = system.keyring.clone() else {
+            return;
+        };
+
+        let mut reencrypted_messages = 0;
+        for stream in system.get_streams() {
+            for topic in stream.get_topics() {
+                match topic.reencrypt_segments(&keyring).await {
+                    Ok(count) => reencrypted_messages += count,
+                    Err(error) => {
+                        error!(
+                            "Failed to re-encrypt segments for stream ID: {}, topic ID: {}. Error: {}",
+                            topic.stream_id, topic.topic_id, error
+                        );
+                    }
+                }
+            }
+        }
+        info!("Re-encrypted {reencrypted_messages} messages of the closed segments.");
+    }
+
+    fn start_command_sender(
+        &mut self,
+        _system: SharedSystem,
+        config: &crate::configs::server::ServerConfig,
+        sender: Sender<ReencryptSegmentsCommand>,
+    ) {
+        let segments_reencryptor = SegmentsReencryptor::new(&config.system.encryption, sender);
+        segments_reencryptor.start();
+    }
+
+    fn start_command_consumer(
+        mut self,
+        system: SharedSystem,
+        config: &crate::configs::server::ServerConfig,
+        receiver: flume::Receiver<ReencryptSegmentsCommand>,
+    ) {
+        if !config.system.encryption.enabled || !config.system.encryption.reencryption.enabled {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let system = system.clone();
+            while let Ok(command) = receiver.recv_async().await {
+                self.execute(&system, command).await;
+            }
+            info!("Segments re-encryption receiver stopped.");
+        });
+    }
+}
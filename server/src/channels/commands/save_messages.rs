@@ -5,7 +5,7 @@ use crate::streaming::systems::system::SharedSystem;
 use async_trait::async_trait;
 use flume::{Receiver, Sender};
 use iggy::utils::duration::IggyDuration;
-use tokio::time;
+use tokio::time::{self, Instant};
 use tracing::{error, info, instrument, warn};
 
 pub struct MessagesSaver {
@@ -33,24 +33,38 @@ impl MessagesSaver {
         }
     }
 
-    pub fn start(&self) {
+    pub fn start(&self, system: SharedSystem) {
         if !self.enabled {
             info!("Message saver is disabled.");
             return;
         }
 
         let enforce_fsync = self.enforce_fsync;
-        let interval = self.interval;
+        let mut interval = self.interval;
         let sender = self.sender.clone();
         info!("Message saver is enabled, buffered messages will be automatically saved every: {interval}, enforce fsync: {enforce_fsync}.");
         tokio::spawn(async move {
+            let mut config_changes = system.read().await.subscribe_to_config_changes();
             let mut interval_timer = time::interval(interval.get_duration());
             loop {
-                interval_timer.tick().await;
-                let command = SaveMessagesCommand { enforce_fsync };
-                sender.send(command).unwrap_or_else(|e| {
-                    error!("Failed to send SaveMessagesCommand. Error: {e}",);
-                });
+                tokio::select! {
+                    _ = interval_timer.tick() => {
+                        let command = SaveMessagesCommand { enforce_fsync };
+                        sender.send(command).unwrap_or_else(|e| {
+                            error!("Failed to send SaveMessagesCommand. Error: {e}",);
+                        });
+                    }
+                    config = config_changes.changed() => {
+                        if config.message_saver_interval != interval {
+                            interval = config.message_saver_interval;
+                            interval_timer = time::interval_at(
+                                Instant::now() + interval.get_duration(),
+                                interval.get_duration(),
+                            );
+                            info!("Message saver interval has been changed to: {interval}.");
+                        }
+                    }
+                }
             }
         });
     }
@@ -75,12 +89,12 @@ impl ServerCommand<SaveMessagesCommand> for SaveMessagesExecutor {
 
     fn start_command_sender(
         &mut self,
-        _system: SharedSystem,
+        system: SharedSystem,
         config: &ServerConfig,
         sender: Sender<SaveMessagesCommand>,
     ) {
         let messages_saver = MessagesSaver::new(&config.message_saver, sender);
-        messages_saver.start();
+        messages_saver.start(system);
     }
 
     fn start_command_consumer(
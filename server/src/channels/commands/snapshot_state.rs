@@ -0,0 +1,104 @@
+use crate::channels::server_command::ServerCommand;
+use crate::configs::server::StateMaintenanceConfig;
+use crate::streaming::systems::system::SharedSystem;
+use async_trait::async_trait;
+use flume::Sender;
+use iggy::utils::duration::IggyDuration;
+use tokio::time;
+use tracing::{debug, error, info, instrument};
+
+pub struct StateSnapshotter {
+    enabled: bool,
+    interval: IggyDuration,
+    min_entries: u64,
+    sender: Sender<SnapshotStateCommand>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SnapshotStateCommand {
+    min_entries: u64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SnapshotStateExecutor;
+
+impl StateSnapshotter {
+    pub fn new(config: &StateMaintenanceConfig, sender: Sender<SnapshotStateCommand>) -> Self {
+        Self {
+            enabled: config.snapshot_enabled,
+            interval: config.snapshot_interval,
+            min_entries: config.snapshot_min_entries,
+            sender,
+        }
+    }
+
+    pub fn start(&self) {
+        if !self.enabled {
+            info!("State snapshotter is disabled.");
+            return;
+        }
+
+        let interval = self.interval;
+        let min_entries = self.min_entries;
+        let sender = self.sender.clone();
+        info!("State snapshotter is enabled, state will be snapshotted every: {interval}, if at least: {min_entries} entries were appended.");
+        tokio::spawn(async move {
+            let mut interval_timer = time::interval(interval.get_duration());
+            loop {
+                interval_timer.tick().await;
+                sender
+                    .send(SnapshotStateCommand { min_entries })
+                    .unwrap_or_else(|err| {
+                        error!("Failed to send SnapshotStateCommand. Error: {}", err);
+                    });
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl ServerCommand<SnapshotStateCommand> for SnapshotStateExecutor {
+    #[instrument(skip_all)]
+    async fn execute(&mut self, system: &SharedSystem, command: SnapshotStateCommand) {
+        // The write lock ensures that the state is not archived while the log is being compacted.
+        let system = system.write().await;
+        match system.state.snapshot(command.min_entries).await {
+            Ok(Some(index)) => info!("State snapshotted successfully, index: {index}."),
+            Ok(None) => debug!("State has not been snapshotted."),
+            Err(error) => error!("Failed to snapshot state. Error: {}", error),
+        }
+    }
+
+    fn start_command_sender(
+        &mut self,
+        _system: SharedSystem,
+        config: &crate::configs::server::ServerConfig,
+        sender: Sender<SnapshotStateCommand>,
+    ) {
+        if !config.data_maintenance.state.snapshot_enabled {
+            return;
+        }
+
+        let state_snapshotter = StateSnapshotter::new(&config.data_maintenance.state, sender);
+        state_snapshotter.start();
+    }
+
+    fn start_command_consumer(
+        mut self,
+        system: SharedSystem,
+        config: &crate::configs::server::ServerConfig,
+        receiver: flume::Receiver<SnapshotStateCommand>,
+    ) {
+        if !config.data_maintenance.state.snapshot_enabled {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let system = system.clone();
+            while let Ok(command) = receiver.recv_async().await {
+                self.execute(&system, command).await;
+            }
+            info!("State snapshotter receiver stopped.");
+        });
+    }
+}
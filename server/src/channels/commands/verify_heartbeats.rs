@@ -6,7 +6,7 @@ use flume::Sender;
 use iggy::locking::IggySharedMutFn;
 use iggy::utils::duration::IggyDuration;
 use iggy::utils::timestamp::IggyTimestamp;
-use tokio::time;
+use tokio::time::{self, Instant};
 use tracing::{debug, error, info, instrument, warn};
 
 const MAX_THRESHOLD: f64 = 1.2;
@@ -14,12 +14,14 @@ const MAX_THRESHOLD: f64 = 1.2;
 pub struct VerifyHeartbeats {
     enabled: bool,
     interval: IggyDuration,
+    consumer_group_session_timeout: IggyDuration,
     sender: Sender<VerifyHeartbeatsCommand>,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct VerifyHeartbeatsCommand {
     interval: IggyDuration,
+    consumer_group_session_timeout: IggyDuration,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -30,37 +32,76 @@ impl VerifyHeartbeats {
         Self {
             enabled: config.enabled,
             interval: config.interval,
+            consumer_group_session_timeout: config.consumer_group_session_timeout,
             sender,
         }
     }
 
-    pub fn start(&self) {
-        if !self.enabled {
+    /// The verification is scheduled even if it's disabled, so that it can be enabled by reloading the configuration.
+    pub fn start(&self, system: SharedSystem) {
+        let mut enabled = self.enabled;
+        let mut interval = self.interval;
+        let mut consumer_group_session_timeout = self.consumer_group_session_timeout;
+        let sender = self.sender.clone();
+        if enabled {
+            info!(
+                "Heartbeats will be verified every: {interval}. Max allowed interval: {}, consumer group session timeout: {consumer_group_session_timeout}.",
+                Self::get_max_interval(interval)
+            );
+        } else {
             info!("Heartbeats verification is disabled.");
-            return;
         }
 
-        let interval = self.interval;
-        let max_interval = IggyDuration::from((MAX_THRESHOLD * interval.as_micros() as f64) as u64);
-        let sender = self.sender.clone();
-        info!(
-            "Heartbeats will be verified every: {interval}. Max allowed interval: {max_interval}."
-        );
         tokio::spawn(async move {
+            let mut config_changes = system.read().await.subscribe_to_config_changes();
             let mut interval_timer = time::interval(interval.get_duration());
             loop {
-                interval_timer.tick().await;
-                debug!("Verifying heartbeats...");
-                sender
-                    .send(VerifyHeartbeatsCommand {
-                        interval: max_interval,
-                    })
-                    .unwrap_or_else(|error| {
-                        error!("Failed to send VerifyHeartbeats. Error: {}", error);
-                    });
+                tokio::select! {
+                    _ = interval_timer.tick() => {
+                        if !enabled {
+                            continue;
+                        }
+
+                        debug!("Verifying heartbeats...");
+                        sender
+                            .send(VerifyHeartbeatsCommand {
+                                interval: Self::get_max_interval(interval),
+                                consumer_group_session_timeout,
+                            })
+                            .unwrap_or_else(|error| {
+                                error!("Failed to send VerifyHeartbeats. Error: {}", error);
+                            });
+                    }
+                    config = config_changes.changed() => {
+                        let config = config.heartbeat;
+                        if config.enabled == enabled
+                            && config.interval == interval
+                            && config.consumer_group_session_timeout == consumer_group_session_timeout
+                        {
+                            continue;
+                        }
+
+                        if config.interval != interval {
+                            interval_timer = time::interval_at(
+                                Instant::now() + config.interval.get_duration(),
+                                config.interval.get_duration(),
+                            );
+                        }
+                        enabled = config.enabled;
+                        interval = config.interval;
+                        consumer_group_session_timeout = config.consumer_group_session_timeout;
+                        info!(
+                            "Heartbeats verification has been reconfigured, enabled: {enabled}, interval: {interval}, consumer group session timeout: {consumer_group_session_timeout}."
+                        );
+                    }
+                }
             }
         });
     }
+
+    fn get_max_interval(interval: IggyDuration) -> IggyDuration {
+        IggyDuration::from((MAX_THRESHOLD * interval.as_micros() as f64) as u64)
+    }
 }
 
 #[async_trait]
@@ -68,13 +109,26 @@ impl ServerCommand<VerifyHeartbeatsCommand> for VerifyHeartbeatsExecutor {
     #[instrument(skip_all)]
     async fn execute(&mut self, system: &SharedSystem, command: VerifyHeartbeatsCommand) {
         let system = system.read().await;
+        let now = IggyTimestamp::now();
+        let session_heartbeat_to = IggyTimestamp::from(
+            now.as_micros() - command.consumer_group_session_timeout.as_micros(),
+        );
+        debug!("Verifying consumer group members heartbeats at: {now}, max allowed timestamp: {session_heartbeat_to}");
+        match system
+            .evict_stale_consumer_group_members(session_heartbeat_to)
+            .await
+        {
+            Ok(0) => {}
+            Ok(count) => info!("Evicted {count} stale consumer group members."),
+            Err(error) => error!("Failed to evict stale consumer group members. Error: {error}"),
+        }
+
         let clients;
         {
             let client_manager = system.client_manager.read().await;
             clients = client_manager.get_clients();
         }
 
-        let now = IggyTimestamp::now();
         let heartbeat_to = IggyTimestamp::from(now.as_micros() - command.interval.as_micros());
         debug!("Verifying heartbeats at: {now}, max allowed timestamp: {heartbeat_to}");
         let mut stale_clients = Vec::new();
@@ -111,12 +165,12 @@ impl ServerCommand<VerifyHeartbeatsCommand> for VerifyHeartbeatsExecutor {
 
     fn start_command_sender(
         &mut self,
-        _system: SharedSystem,
+        system: SharedSystem,
         config: &crate::configs::server::ServerConfig,
         sender: Sender<VerifyHeartbeatsCommand>,
     ) {
         let verify_heartbeats = VerifyHeartbeats::new(&config.heartbeat, sender);
-        verify_heartbeats.start();
+        verify_heartbeats.start(system);
     }
 
     fn start_command_consumer(
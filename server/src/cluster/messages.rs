@@ -0,0 +1,553 @@
+use crate::state::entry::StateEntry;
+use bytes::{BufMut, Bytes, BytesMut};
+use iggy::bytes_serializable::BytesSerializable;
+use iggy::error::IggyError;
+use std::fmt::{Display, Formatter};
+use std::str::from_utf8;
+
+const VOTE_REQUEST_CODE: u8 = 1;
+const VOTE_RESPONSE_CODE: u8 = 2;
+const LOG_REQUEST_CODE: u8 = 3;
+const LOG_RESPONSE_CODE: u8 = 4;
+const FETCH_REQUEST_CODE: u8 = 5;
+const FETCH_RESPONSE_CODE: u8 = 6;
+const IN_SYNC_REPLICAS_REQUEST_CODE: u8 = 7;
+const STATE_ENTRY_HEADER_LENGTH: usize = 8 + 8 + 4 + 4 + 8 + 8 + 4 + 4 + 4;
+
+/// The messages exchanged by the cluster nodes, following the Raft consensus algorithm,
+/// where the log replication is expressed in terms of the log lengths rather than the indexes,
+/// along with the ones replicating the messages of the partitions from their leaders to the followers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClusterMessage {
+    VoteRequest(VoteRequest),
+    VoteResponse(VoteResponse),
+    LogRequest(LogRequest),
+    LogResponse(LogResponse),
+    FetchRequest(FetchRequest),
+    FetchResponse(FetchResponse),
+    InSyncReplicasRequest(InSyncReplicasRequest),
+}
+
+/// Sent by the candidate to all the other members to request their votes in the election.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteRequest {
+    pub candidate_id: u32,
+    pub term: u64,
+    pub log_length: u64,
+    pub last_term: u64,
+}
+
+/// Sent back to the candidate, `granted` is true if the node has voted for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteResponse {
+    pub voter_id: u32,
+    pub term: u64,
+    pub granted: bool,
+}
+
+/// Sent by the leader to the followers, both to replicate the entries and as the heartbeat (with no entries).
+/// - `prefix_length` - the number of the entries preceding the replicated ones.
+/// - `prefix_term` - the term of the last entry preceding the replicated ones.
+/// - `leader_commit` - the number of the entries committed by the leader.
+/// - `leader_tcp_address` and `leader_http_address` - the client addresses of the leader, used to redirect the clients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRequest {
+    pub leader_id: u32,
+    pub term: u64,
+    pub prefix_length: u64,
+    pub prefix_term: u64,
+    pub leader_commit: u64,
+    pub leader_tcp_address: String,
+    pub leader_http_address: String,
+    pub entries: Vec<StateEntry>,
+}
+
+/// Sent back to the leader, `ack` is the length of the follower log matching the leader one if `success` is true,
+/// otherwise it's the length of the follower log, so that the leader can resend the missing entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogResponse {
+    pub follower_id: u32,
+    pub term: u64,
+    pub ack: u64,
+    pub success: bool,
+}
+
+/// Sent by the follower to the partition leader to fetch the messages starting from the `offset`,
+/// which is the next offset of the follower partition, thus all the preceding messages are stored by the follower.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchRequest {
+    pub follower_id: u32,
+    pub stream_id: u32,
+    pub topic_id: u32,
+    pub partition_id: u32,
+    pub leader_epoch: u32,
+    pub offset: u64,
+}
+
+/// Sent back to the follower with the fetched messages serialized one after another.
+/// - `epoch_start_offset` - the next offset of the partition at the time the node became its leader,
+///   the messages of the follower starting from this offset might have not been replicated by the leader.
+/// - `log_start_offset` - the offset of the first message still stored by the leader, as the preceding ones
+///   might have been deleted by the retention policy, and the offsets of the following ones might have gaps.
+/// - `end_offset` - the next offset of the leader partition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchResponse {
+    pub leader_id: u32,
+    pub stream_id: u32,
+    pub topic_id: u32,
+    pub partition_id: u32,
+    pub leader_epoch: u32,
+    pub epoch_start_offset: u64,
+    pub log_start_offset: u64,
+    pub end_offset: u64,
+    pub messages_count: u32,
+    pub messages: Bytes,
+}
+
+/// Sent by the partition leader to the cluster leader to update the in-sync replicas of the partition,
+/// as only the cluster leader can append the entries to the state log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InSyncReplicasRequest {
+    pub leader_id: u32,
+    pub stream_id: u32,
+    pub topic_id: u32,
+    pub partition_id: u32,
+    pub leader_epoch: u32,
+    pub in_sync_replicas: Vec<u32>,
+}
+
+impl ClusterMessage {
+    pub fn sender_id(&self) -> u32 {
+        match self {
+            ClusterMessage::VoteRequest(request) => request.candidate_id,
+            ClusterMessage::VoteResponse(response) => response.voter_id,
+            ClusterMessage::LogRequest(request) => request.leader_id,
+            ClusterMessage::LogResponse(response) => response.follower_id,
+            ClusterMessage::FetchRequest(request) => request.follower_id,
+            ClusterMessage::FetchResponse(response) => response.leader_id,
+            ClusterMessage::InSyncReplicasRequest(request) => request.leader_id,
+        }
+    }
+}
+
+impl BytesSerializable for ClusterMessage {
+    fn to_bytes(&self) -> Bytes {
+        let mut bytes = BytesMut::new();
+        match self {
+            ClusterMessage::VoteRequest(request) => {
+                bytes.put_u8(VOTE_REQUEST_CODE);
+                bytes.put_u32_le(request.candidate_id);
+                bytes.put_u64_le(request.term);
+                bytes.put_u64_le(request.log_length);
+                bytes.put_u64_le(request.last_term);
+            }
+            ClusterMessage::VoteResponse(response) => {
+                bytes.put_u8(VOTE_RESPONSE_CODE);
+                bytes.put_u32_le(response.voter_id);
+                bytes.put_u64_le(response.term);
+                bytes.put_u8(response.granted as u8);
+            }
+            ClusterMessage::LogRequest(request) => {
+                bytes.put_u8(LOG_REQUEST_CODE);
+                bytes.put_u32_le(request.leader_id);
+                bytes.put_u64_le(request.term);
+                bytes.put_u64_le(request.prefix_length);
+                bytes.put_u64_le(request.prefix_term);
+                bytes.put_u64_le(request.leader_commit);
+                put_string(&mut bytes, &request.leader_tcp_address);
+                put_string(&mut bytes, &request.leader_http_address);
+                bytes.put_u32_le(request.entries.len() as u32);
+                for entry in &request.entries {
+                    let entry = entry.to_bytes();
+                    bytes.put_u32_le(entry.len() as u32);
+                    bytes.extend(entry);
+                }
+            }
+            ClusterMessage::LogResponse(response) => {
+                bytes.put_u8(LOG_RESPONSE_CODE);
+                bytes.put_u32_le(response.follower_id);
+                bytes.put_u64_le(response.term);
+                bytes.put_u64_le(response.ack);
+                bytes.put_u8(response.success as u8);
+            }
+            ClusterMessage::FetchRequest(request) => {
+                bytes.put_u8(FETCH_REQUEST_CODE);
+                bytes.put_u32_le(request.follower_id);
+                bytes.put_u32_le(request.stream_id);
+                bytes.put_u32_le(request.topic_id);
+                bytes.put_u32_le(request.partition_id);
+                bytes.put_u32_le(request.leader_epoch);
+                bytes.put_u64_le(request.offset);
+            }
+            ClusterMessage::FetchResponse(response) => {
+                bytes.put_u8(FETCH_RESPONSE_CODE);
+                bytes.put_u32_le(response.leader_id);
+                bytes.put_u32_le(response.stream_id);
+                bytes.put_u32_le(response.topic_id);
+                bytes.put_u32_le(response.partition_id);
+                bytes.put_u32_le(response.leader_epoch);
+                bytes.put_u64_le(response.epoch_start_offset);
+                bytes.put_u64_le(response.log_start_offset);
+                bytes.put_u64_le(response.end_offset);
+                bytes.put_u32_le(response.messages_count);
+                bytes.put_u32_le(response.messages.len() as u32);
+                bytes.put_slice(&response.messages);
+            }
+            ClusterMessage::InSyncReplicasRequest(request) => {
+                bytes.put_u8(IN_SYNC_REPLICAS_REQUEST_CODE);
+                bytes.put_u32_le(request.leader_id);
+                bytes.put_u32_le(request.stream_id);
+                bytes.put_u32_le(request.topic_id);
+                bytes.put_u32_le(request.partition_id);
+                bytes.put_u32_le(request.leader_epoch);
+                bytes.put_u32_le(request.in_sync_replicas.len() as u32);
+                for replica in &request.in_sync_replicas {
+                    bytes.put_u32_le(*replica);
+                }
+            }
+        }
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<Self, IggyError>
+    where
+        Self: Sized,
+    {
+        if bytes.is_empty() {
+            return Err(IggyError::InvalidClusterMessage);
+        }
+
+        let payload = bytes.slice(1..);
+        match bytes[0] {
+            VOTE_REQUEST_CODE => {
+                ensure_length(&payload, 28)?;
+                Ok(ClusterMessage::VoteRequest(VoteRequest {
+                    candidate_id: u32::from_le_bytes(payload[..4].try_into()?),
+                    term: u64::from_le_bytes(payload[4..12].try_into()?),
+                    log_length: u64::from_le_bytes(payload[12..20].try_into()?),
+                    last_term: u64::from_le_bytes(payload[20..28].try_into()?),
+                }))
+            }
+            VOTE_RESPONSE_CODE => {
+                ensure_length(&payload, 13)?;
+                Ok(ClusterMessage::VoteResponse(VoteResponse {
+                    voter_id: u32::from_le_bytes(payload[..4].try_into()?),
+                    term: u64::from_le_bytes(payload[4..12].try_into()?),
+                    granted: payload[12] == 1,
+                }))
+            }
+            LOG_REQUEST_CODE => {
+                ensure_length(&payload, 36)?;
+                let leader_id = u32::from_le_bytes(payload[..4].try_into()?);
+                let term = u64::from_le_bytes(payload[4..12].try_into()?);
+                let prefix_length = u64::from_le_bytes(payload[12..20].try_into()?);
+                let prefix_term = u64::from_le_bytes(payload[20..28].try_into()?);
+                let leader_commit = u64::from_le_bytes(payload[28..36].try_into()?);
+                let mut position = 36;
+                let leader_tcp_address = read_string(&payload, &mut position)?;
+                let leader_http_address = read_string(&payload, &mut position)?;
+                ensure_length(&payload, position + 4)?;
+                let entries_count = u32::from_le_bytes(payload[position..position + 4].try_into()?);
+                position += 4;
+                let mut entries = Vec::with_capacity(entries_count as usize);
+                for _ in 0..entries_count {
+                    ensure_length(&payload, position + 4)?;
+                    let entry_length =
+                        u32::from_le_bytes(payload[position..position + 4].try_into()?) as usize;
+                    position += 4;
+                    if entry_length < STATE_ENTRY_HEADER_LENGTH {
+                        return Err(IggyError::InvalidClusterMessage);
+                    }
+
+                    ensure_length(&payload, position + entry_length)?;
+                    let entry =
+                        StateEntry::from_bytes(payload.slice(position..position + entry_length))?;
+                    entries.push(entry);
+                    position += entry_length;
+                }
+
+                Ok(ClusterMessage::LogRequest(LogRequest {
+                    leader_id,
+                    term,
+                    prefix_length,
+                    prefix_term,
+                    leader_commit,
+                    leader_tcp_address,
+                    leader_http_address,
+                    entries,
+                }))
+            }
+            LOG_RESPONSE_CODE => {
+                ensure_length(&payload, 21)?;
+                Ok(ClusterMessage::LogResponse(LogResponse {
+                    follower_id: u32::from_le_bytes(payload[..4].try_into()?),
+                    term: u64::from_le_bytes(payload[4..12].try_into()?),
+                    ack: u64::from_le_bytes(payload[12..20].try_into()?),
+                    success: payload[20] == 1,
+                }))
+            }
+            FETCH_REQUEST_CODE => {
+                ensure_length(&payload, 28)?;
+                Ok(ClusterMessage::FetchRequest(FetchRequest {
+                    follower_id: u32::from_le_bytes(payload[..4].try_into()?),
+                    stream_id: u32::from_le_bytes(payload[4..8].try_into()?),
+                    topic_id: u32::from_le_bytes(payload[8..12].try_into()?),
+                    partition_id: u32::from_le_bytes(payload[12..16].try_into()?),
+                    leader_epoch: u32::from_le_bytes(payload[16..20].try_into()?),
+                    offset: u64::from_le_bytes(payload[20..28].try_into()?),
+                }))
+            }
+            FETCH_RESPONSE_CODE => {
+                ensure_length(&payload, 52)?;
+                let messages_length = u32::from_le_bytes(payload[48..52].try_into()?) as usize;
+                ensure_length(&payload, 52 + messages_length)?;
+                Ok(ClusterMessage::FetchResponse(FetchResponse {
+                    leader_id: u32::from_le_bytes(payload[..4].try_into()?),
+                    stream_id: u32::from_le_bytes(payload[4..8].try_into()?),
+                    topic_id: u32::from_le_bytes(payload[8..12].try_into()?),
+                    partition_id: u32::from_le_bytes(payload[12..16].try_into()?),
+                    leader_epoch: u32::from_le_bytes(payload[16..20].try_into()?),
+                    epoch_start_offset: u64::from_le_bytes(payload[20..28].try_into()?),
+                    log_start_offset: u64::from_le_bytes(payload[28..36].try_into()?),
+                    end_offset: u64::from_le_bytes(payload[36..44].try_into()?),
+                    messages_count: u32::from_le_bytes(payload[44..48].try_into()?),
+                    messages: payload.slice(52..52 + messages_length),
+                }))
+            }
+            IN_SYNC_REPLICAS_REQUEST_CODE => {
+                ensure_length(&payload, 24)?;
+                let replicas_count = u32::from_le_bytes(payload[20..24].try_into()?) as usize;
+                ensure_length(&payload, 24 + 4 * replicas_count)?;
+                let mut in_sync_replicas = Vec::with_capacity(replicas_count);
+                for index in 0..replicas_count {
+                    let position = 24 + 4 * index;
+                    in_sync_replicas.push(u32::from_le_bytes(
+                        payload[position..position + 4].try_into()?,
+                    ));
+                }
+
+                Ok(ClusterMessage::InSyncReplicasRequest(
+                    InSyncReplicasRequest {
+                        leader_id: u32::from_le_bytes(payload[..4].try_into()?),
+                        stream_id: u32::from_le_bytes(payload[4..8].try_into()?),
+                        topic_id: u32::from_le_bytes(payload[8..12].try_into()?),
+                        partition_id: u32::from_le_bytes(payload[12..16].try_into()?),
+                        leader_epoch: u32::from_le_bytes(payload[16..20].try_into()?),
+                        in_sync_replicas,
+                    },
+                ))
+            }
+            _ => Err(IggyError::InvalidClusterMessage),
+        }
+    }
+}
+
+fn put_string(bytes: &mut BytesMut, value: &str) {
+    bytes.put_u16_le(value.len() as u16);
+    bytes.put_slice(value.as_bytes());
+}
+
+fn read_string(payload: &Bytes, position: &mut usize) -> Result<String, IggyError> {
+    ensure_length(payload, *position + 2)?;
+    let length = u16::from_le_bytes(payload[*position..*position + 2].try_into()?) as usize;
+    *position += 2;
+    ensure_length(payload, *position + length)?;
+    let value = from_utf8(&payload[*position..*position + length])?.to_string();
+    *position += length;
+    Ok(value)
+}
+
+fn ensure_length(payload: &Bytes, length: usize) -> Result<(), IggyError> {
+    if payload.len() < length {
+        return Err(IggyError::InvalidClusterMessage);
+    }
+
+    Ok(())
+}
+
+impl Display for ClusterMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClusterMessage::VoteRequest(request) => write!(
+                f,
+                "VoteRequest {{ candidate ID: {}, term: {}, log length: {}, last term: {} }}",
+                request.candidate_id, request.term, request.log_length, request.last_term
+            ),
+            ClusterMessage::VoteResponse(response) => write!(
+                f,
+                "VoteResponse {{ voter ID: {}, term: {}, granted: {} }}",
+                response.voter_id, response.term, response.granted
+            ),
+            ClusterMessage::LogRequest(request) => write!(
+                f,
+                "LogRequest {{ leader ID: {}, term: {}, prefix length: {}, prefix term: {}, leader commit: {}, entries: {} }}",
+                request.leader_id,
+                request.term,
+                request.prefix_length,
+                request.prefix_term,
+                request.leader_commit,
+                request.entries.len()
+            ),
+            ClusterMessage::LogResponse(response) => write!(
+                f,
+                "LogResponse {{ follower ID: {}, term: {}, ack: {}, success: {} }}",
+                response.follower_id, response.term, response.ack, response.success
+            ),
+            ClusterMessage::FetchRequest(request) => write!(
+                f,
+                "FetchRequest {{ follower ID: {}, stream ID: {}, topic ID: {}, partition ID: {}, leader epoch: {}, offset: {} }}",
+                request.follower_id,
+                request.stream_id,
+                request.topic_id,
+                request.partition_id,
+                request.leader_epoch,
+                request.offset
+            ),
+            ClusterMessage::FetchResponse(response) => write!(
+                f,
+                "FetchResponse {{ leader ID: {}, stream ID: {}, topic ID: {}, partition ID: {}, leader epoch: {}, epoch start offset: {}, log start offset: {}, end offset: {}, messages: {} }}",
+                response.leader_id,
+                response.stream_id,
+                response.topic_id,
+                response.partition_id,
+                response.leader_epoch,
+                response.epoch_start_offset,
+                response.log_start_offset,
+                response.end_offset,
+                response.messages_count
+            ),
+            ClusterMessage::InSyncReplicasRequest(request) => write!(
+                f,
+                "InSyncReplicasRequest {{ leader ID: {}, stream ID: {}, topic ID: {}, partition ID: {}, leader epoch: {}, in-sync replicas: {:?} }}",
+                request.leader_id,
+                request.stream_id,
+                request.topic_id,
+                request.partition_id,
+                request.leader_epoch,
+                request.in_sync_replicas
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::command::EntryCommand;
+    use iggy::streams::create_stream::CreateStream;
+    use iggy::utils::timestamp::IggyTimestamp;
+
+    #[test]
+    fn vote_request_should_be_serialized_and_deserialized() {
+        let message = ClusterMessage::VoteRequest(VoteRequest {
+            candidate_id: 2,
+            term: 5,
+            log_length: 10,
+            last_term: 4,
+        });
+
+        let deserialized = ClusterMessage::from_bytes(message.to_bytes()).unwrap();
+
+        assert_eq!(deserialized, message);
+    }
+
+    #[test]
+    fn log_request_should_be_serialized_and_deserialized_with_entries() {
+        let command = EntryCommand::CreateStream(CreateStream {
+            stream_id: Some(1),
+            name: "test".to_string(),
+        })
+        .to_bytes();
+        let entry = StateEntry::new(
+            3,
+            2,
+            1,
+            1,
+            0,
+            // The timestamp is serialized with the microsecond precision.
+            IggyTimestamp::from(IggyTimestamp::now().as_micros()),
+            1,
+            123,
+            Bytes::new(),
+            command,
+        );
+        let message = ClusterMessage::LogRequest(LogRequest {
+            leader_id: 1,
+            term: 2,
+            prefix_length: 3,
+            prefix_term: 1,
+            leader_commit: 3,
+            leader_tcp_address: "127.0.0.1:8090".to_string(),
+            leader_http_address: "127.0.0.1:3000".to_string(),
+            entries: vec![entry.clone(), entry],
+        });
+
+        let deserialized = ClusterMessage::from_bytes(message.to_bytes()).unwrap();
+
+        assert_eq!(deserialized, message);
+    }
+
+    #[test]
+    fn log_response_should_be_serialized_and_deserialized() {
+        let message = ClusterMessage::LogResponse(LogResponse {
+            follower_id: 3,
+            term: 2,
+            ack: 7,
+            success: true,
+        });
+
+        let deserialized = ClusterMessage::from_bytes(message.to_bytes()).unwrap();
+
+        assert_eq!(deserialized, message);
+    }
+
+    #[test]
+    fn fetch_response_should_be_serialized_and_deserialized_with_messages() {
+        let message = ClusterMessage::FetchResponse(FetchResponse {
+            leader_id: 1,
+            stream_id: 2,
+            topic_id: 3,
+            partition_id: 4,
+            leader_epoch: 5,
+            epoch_start_offset: 10,
+            log_start_offset: 5,
+            end_offset: 20,
+            messages_count: 2,
+            messages: Bytes::from_static(b"messages"),
+        });
+
+        let deserialized = ClusterMessage::from_bytes(message.to_bytes()).unwrap();
+
+        assert_eq!(deserialized, message);
+    }
+
+    #[test]
+    fn in_sync_replicas_request_should_be_serialized_and_deserialized() {
+        let message = ClusterMessage::InSyncReplicasRequest(InSyncReplicasRequest {
+            leader_id: 2,
+            stream_id: 1,
+            topic_id: 1,
+            partition_id: 3,
+            leader_epoch: 4,
+            in_sync_replicas: vec![2, 3],
+        });
+
+        let deserialized = ClusterMessage::from_bytes(message.to_bytes()).unwrap();
+
+        assert_eq!(deserialized, message);
+    }
+
+    #[test]
+    fn truncated_message_should_not_be_deserialized() {
+        let message = ClusterMessage::VoteResponse(VoteResponse {
+            voter_id: 1,
+            term: 1,
+            granted: true,
+        });
+        let bytes = message.to_bytes();
+
+        let result = ClusterMessage::from_bytes(bytes.slice(..bytes.len() - 1));
+
+        assert!(result.is_err());
+    }
+}
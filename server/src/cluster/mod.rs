@@ -0,0 +1,5 @@
+pub mod messages;
+pub mod node;
+pub mod replication;
+pub mod state;
+pub mod transport;
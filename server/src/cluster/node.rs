@@ -0,0 +1,974 @@
+use crate::cluster::messages::{
+    ClusterMessage, LogRequest, LogResponse, VoteRequest, VoteResponse,
+};
+use crate::cluster::replication::ReplicaManager;
+use crate::cluster::transport::{self, ClusterTransport};
+use crate::configs::cluster::ClusterConfig;
+use crate::state::command::EntryCommand;
+use crate::state::entry::StateEntry;
+use crate::state::file::FileState;
+use crate::state::State;
+use crate::streaming::persistence::persister::Persister;
+use crate::streaming::systems::system::SharedSystem;
+use bytes::{Buf, BufMut, BytesMut};
+use iggy::command::{ADD_CLUSTER_NODE_CODE, REMOVE_CLUSTER_NODE_CODE};
+use iggy::error::IggyError;
+use iggy::models::cluster::{ClusterMetadata, ClusterNode as ClusterNodeInfo};
+use iggy::utils::duration::IggyDuration;
+use ring::rand::SecureRandom;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+use tokio::sync::{oneshot, watch, Mutex, MutexGuard};
+use tokio::time::Instant;
+use tracing::{debug, error, info, warn};
+
+/// The maximum number of the entries sent to the follower in a single log request.
+const MAX_ENTRIES_PER_REQUEST: usize = 1000;
+const TICK_INTERVAL: Duration = Duration::from_millis(10);
+/// Current term (u64), voted for (u32, 0 if none) and commit length (u64).
+const METADATA_LENGTH: usize = 8 + 4 + 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    Follower,
+    Candidate,
+    Leader,
+}
+
+/// The current leader of the cluster along with its client addresses, used to redirect the clients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterLeader {
+    pub id: u32,
+    pub tcp_address: String,
+    pub http_address: String,
+}
+
+/// The node of the cluster replicating the state log (streams, topics, users etc.) with the Raft consensus algorithm.
+/// Only the leader accepts the commands, which are committed once appended to the logs of the majority of the members.
+///
+/// The members are the initial nodes from the configuration, along with the nodes added and removed by the entries
+/// of the state log. The membership change takes effect as soon as its entry is appended to the log,
+/// and only a single change can be in progress (not committed yet) at the same time.
+#[derive(Debug)]
+pub struct ClusterNode {
+    id: u32,
+    address: String,
+    initial_nodes: Vec<(u32, String)>,
+    election_timeout: IggyDuration,
+    heartbeat_interval: IggyDuration,
+    commit_timeout: IggyDuration,
+    log: FileState,
+    metadata_path: String,
+    persister: Arc<dyn Persister>,
+    raft: Mutex<RaftState>,
+    leader: RwLock<Option<ClusterLeader>>,
+    client_addresses: OnceLock<(String, String)>,
+    commit_sender: watch::Sender<u64>,
+    proposal_lock: Mutex<()>,
+    transport: ClusterTransport,
+    replicas: ReplicaManager,
+    started: AtomicBool,
+}
+
+/// The state of the consensus, where the log replication is expressed in terms of the log lengths:
+/// `sent_length` and `acked_length` are tracked by the leader for each follower,
+/// `commit_length` is the number of the committed entries.
+/// The `proposals` are the entries proposed by this node (along with their terms), awaiting to be applied
+/// to the system once committed, the result of which is sent back to the proposer.
+/// The `last_response` is tracked by the leader for each follower to find out which members are alive.
+#[derive(Debug)]
+struct RaftState {
+    current_term: u64,
+    voted_for: Option<u32>,
+    role: Role,
+    current_leader: Option<u32>,
+    votes_received: HashSet<u32>,
+    sent_length: HashMap<u32, u64>,
+    acked_length: HashMap<u32, u64>,
+    entries: Vec<StateEntry>,
+    commit_length: u64,
+    members: BTreeMap<u32, String>,
+    addresses: HashMap<u32, String>,
+    proposals: HashMap<u64, Proposal>,
+    election_deadline: Instant,
+    last_heartbeat: Instant,
+    leader_since: Instant,
+    last_response: HashMap<u32, Instant>,
+}
+
+#[derive(Debug)]
+struct Proposal {
+    term: u64,
+    sender: oneshot::Sender<Result<(), IggyError>>,
+}
+
+impl ClusterNode {
+    pub fn new(
+        config: &ClusterConfig,
+        log: FileState,
+        metadata_path: &str,
+        persister: Arc<dyn Persister>,
+    ) -> Result<Self, IggyError> {
+        let initial_nodes = config.get_nodes().map_err(|error| {
+            error!("Invalid cluster nodes: {error}");
+            IggyError::InvalidClusterNodeAddress(config.nodes.clone())
+        })?;
+        let (commit_sender, _) = watch::channel(0);
+        Ok(Self {
+            id: config.node_id,
+            address: config.address.clone(),
+            initial_nodes: initial_nodes.clone(),
+            election_timeout: config.election_timeout,
+            heartbeat_interval: config.heartbeat_interval,
+            commit_timeout: config.commit_timeout,
+            log,
+            metadata_path: metadata_path.to_string(),
+            persister,
+            raft: Mutex::new(RaftState {
+                current_term: 0,
+                voted_for: None,
+                role: Role::Follower,
+                current_leader: None,
+                votes_received: HashSet::new(),
+                sent_length: HashMap::new(),
+                acked_length: HashMap::new(),
+                entries: Vec::new(),
+                commit_length: 0,
+                members: initial_nodes.iter().cloned().collect(),
+                addresses: initial_nodes.into_iter().collect(),
+                proposals: HashMap::new(),
+                election_deadline: Instant::now(),
+                last_heartbeat: Instant::now(),
+                leader_since: Instant::now(),
+                last_response: HashMap::new(),
+            }),
+            leader: RwLock::new(None),
+            client_addresses: OnceLock::new(),
+            commit_sender,
+            proposal_lock: Mutex::new(()),
+            transport: ClusterTransport::default(),
+            replicas: ReplicaManager::new(config.node_id, &config.replication),
+            started: AtomicBool::new(false),
+        })
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the current leader, if it's known, without waiting for the consensus state.
+    pub fn leader(&self) -> Option<ClusterLeader> {
+        self.leader.read().unwrap().clone()
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.leader().is_some_and(|leader| leader.id == self.id)
+    }
+
+    /// The bootstrapping node is the initial node with the lowest ID, the only one which creates the initial entries
+    /// (e.g. the root user) on the first startup, while the other nodes start with the empty log and receive them from the leader.
+    pub fn is_bootstrapping(&self) -> bool {
+        self.initial_nodes.iter().map(|(id, _)| *id).min() == Some(self.id)
+    }
+
+    pub(crate) fn replicas(&self) -> &ReplicaManager {
+        &self.replicas
+    }
+
+    /// Returns the error to be sent to the client which issued the command to the node which is not the leader.
+    pub fn not_leader_error(&self) -> IggyError {
+        match self.leader() {
+            Some(leader) => IggyError::NotLeader(leader.id, leader.tcp_address),
+            None => IggyError::LeaderNotElected,
+        }
+    }
+
+    pub async fn metadata(&self) -> ClusterMetadata {
+        let raft = self.raft.lock().await;
+        ClusterMetadata {
+            node_id: self.id,
+            term: raft.current_term,
+            leader_id: raft.current_leader,
+            nodes: raft
+                .members
+                .iter()
+                .map(|(id, address)| ClusterNodeInfo {
+                    id: *id,
+                    address: address.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Loads the state log and the consensus metadata, returns the committed entries only.
+    /// If the metadata doesn't exist yet, all the entries of the bootstrapping node are considered to be committed
+    /// (e.g. the existing standalone server becomes the first node of the cluster), while any other node must start
+    /// with the empty log, as its entries wouldn't match the ones replicated by the leader at the same indexes and terms.
+    /// The state snapshots are rejected by the config validation in the cluster mode, as there's no snapshot transfer
+    /// to the followers, so the log compacted earlier by the standalone server cannot be used either.
+    pub(crate) async fn init(&self) -> Result<Vec<StateEntry>, IggyError> {
+        let entries = self.log.init().await?;
+        if self.log.snapshot_entries_count() > 0 {
+            error!(
+                "State log has been compacted into the snapshot, the cluster requires all its entries to be replicated. \
+                The state snapshots are not supported in the cluster mode, the node must start with the uncompacted state log."
+            );
+            return Err(IggyError::StateSnapshotNotSupported);
+        }
+
+        let mut raft = self.raft.lock().await;
+        let entries_count = entries.len() as u64;
+        if Path::new(&self.metadata_path).exists() {
+            let metadata = tokio::fs::read(&self.metadata_path).await?;
+            if metadata.len() != METADATA_LENGTH {
+                error!(
+                    "Cluster metadata file: {} is corrupted.",
+                    self.metadata_path
+                );
+                return Err(IggyError::StateFileCorrupted);
+            }
+
+            let mut metadata = metadata.as_slice();
+            raft.current_term = metadata.get_u64_le();
+            raft.voted_for = Some(metadata.get_u32_le()).filter(|id| *id > 0);
+            raft.commit_length = metadata.get_u64_le().min(entries_count);
+        } else if entries_count > 0 && !self.is_bootstrapping() {
+            error!(
+                "Cluster node with ID: {} has no cluster metadata, but its state log contains {entries_count} entries, only the bootstrapping node can join the cluster with the existing state.",
+                self.id
+            );
+            return Err(IggyError::CannotJoinClusterWithExistingState(self.id));
+        } else {
+            raft.commit_length = entries_count;
+        }
+
+        raft.entries = entries;
+        self.reload_members(&mut raft);
+        self.persist_metadata(&raft).await?;
+        self.notify_committed(&raft, 0);
+        info!(
+            "Initialized cluster node with ID: {}, term: {}, entries: {}, committed: {}.",
+            self.id, raft.current_term, entries_count, raft.commit_length
+        );
+        Ok(raft.entries[..raft.commit_length as usize].to_vec())
+    }
+
+    pub(crate) async fn committed_entries(&self) -> Vec<StateEntry> {
+        let raft = self.raft.lock().await;
+        raft.entries[..raft.commit_length as usize].to_vec()
+    }
+
+    /// Applies the command to the state log. Until the bootstrapping node has started (e.g. the root user is being
+    /// created on the first startup), the entry is committed right away, otherwise it's replicated by the leader
+    /// and applied to the system once committed, thus the system must not be locked by the caller.
+    pub(crate) async fn apply(&self, user_id: u32, command: EntryCommand) -> Result<(), IggyError> {
+        if self.started.load(Ordering::SeqCst) {
+            return self.propose(user_id, command).await;
+        }
+
+        if !self.is_bootstrapping() {
+            error!(
+                "Cluster node with ID: {} is not the bootstrapping node, the initial entries are replicated by the leader.",
+                self.id
+            );
+            return Err(IggyError::CannotJoinClusterWithExistingState(self.id));
+        }
+
+        let mut raft = self.raft.lock().await;
+        let index = raft.entries.len() as u64;
+        let entry = self
+            .log
+            .create_entry(index, raft.current_term, 0, user_id, &command);
+        self.log.append(&entry).await?;
+        Self::apply_membership(&mut raft, &entry);
+        raft.entries.push(entry);
+        let previous_commit_length = raft.commit_length;
+        raft.commit_length = raft.entries.len() as u64;
+        self.persist_metadata(&raft).await?;
+        self.notify_committed(&raft, previous_commit_length);
+        Ok(())
+    }
+
+    /// Serializes the proposals of the commands validated against the system, so that each of them
+    /// is validated only once the preceding ones have been applied.
+    pub(crate) async fn lock_proposals(&self) -> MutexGuard<'_, ()> {
+        self.proposal_lock.lock().await
+    }
+
+    /// Appends the entry to the log of the leader and waits until it's committed and applied to the system,
+    /// returns the result of applying it. The system must not be locked by the caller.
+    /// Fails with `CannotCommitStateEntry` once the entry has been replaced by the new leader, thus never applied,
+    /// or with `StateEntryCommitTimedOut` if it hasn't been committed in time, in which case the outcome is unknown,
+    /// as the entry might still be committed and applied later on, so the client has to check the state before retrying.
+    pub(crate) async fn propose(
+        &self,
+        user_id: u32,
+        command: EntryCommand,
+    ) -> Result<(), IggyError> {
+        let (index, receiver) = {
+            let mut raft = self.raft.lock().await;
+            if raft.role != Role::Leader {
+                return Err(self.not_leader_error());
+            }
+
+            let is_membership_change = matches!(
+                command,
+                EntryCommand::AddClusterNode(_) | EntryCommand::RemoveClusterNode(_)
+            );
+            if is_membership_change
+                && raft.entries[raft.commit_length as usize..]
+                    .iter()
+                    .any(Self::is_membership_change)
+            {
+                return Err(IggyError::ClusterMembershipChangeInProgress);
+            }
+
+            let index = raft.entries.len() as u64;
+            let term = raft.current_term;
+            let entry = self
+                .log
+                .create_entry(index, term, self.id, user_id, &command);
+            self.log.append(&entry).await?;
+            Self::apply_membership(&mut raft, &entry);
+            raft.entries.push(entry);
+            let (sender, receiver) = oneshot::channel();
+            raft.proposals.insert(index, Proposal { term, sender });
+            self.replicate_to_followers(&mut raft);
+            raft.last_heartbeat = Instant::now();
+            self.commit_log_entries(&mut raft).await?;
+            (index, receiver)
+        };
+
+        match tokio::time::timeout(self.commit_timeout.get_duration(), receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => {
+                warn!("State entry with index: {index} has been replaced by the new leader.");
+                Err(IggyError::CannotCommitStateEntry(index))
+            }
+            Err(_) => {
+                // The entry might still be committed later on, then it's applied just like on the other nodes.
+                self.raft.lock().await.proposals.remove(&index);
+                warn!("State entry with index: {index} has not been committed in time.");
+                Err(IggyError::StateEntryCommitTimedOut(index))
+            }
+        }
+    }
+
+    /// Starts the cluster listener, the ticker (elections and heartbeats), the applier of the committed entries
+    /// and the replication of the partitions, returns the address of the cluster listener.
+    pub async fn start(
+        self: &Arc<Self>,
+        system: SharedSystem,
+        tcp_address: Option<SocketAddr>,
+        http_address: Option<SocketAddr>,
+    ) -> Result<SocketAddr, IggyError> {
+        let _ = self.client_addresses.set((
+            tcp_address
+                .map(|address| address.to_string())
+                .unwrap_or_default(),
+            http_address
+                .map(|address| address.to_string())
+                .unwrap_or_default(),
+        ));
+        let address = transport::listen(&self.address, self.clone()).await?;
+        {
+            let mut raft = self.raft.lock().await;
+            raft.election_deadline = self.next_election_deadline();
+            info!(
+                "Cluster node with ID: {} has started, members: {:?}.",
+                self.id,
+                raft.members.keys().collect::<Vec<_>>()
+            );
+        }
+        self.started.store(true, Ordering::SeqCst);
+        self.replicas.set_system(system.clone());
+
+        let node = self.clone();
+        tokio::spawn(async move {
+            node.apply_committed_entries(system).await;
+        });
+
+        let node = self.clone();
+        tokio::spawn(async move {
+            node.replicas.run(node.clone()).await;
+        });
+
+        let node = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(error) = node.tick().await {
+                    error!("Cluster node tick has failed. {error}");
+                }
+            }
+        });
+        Ok(address)
+    }
+
+    /// Applies the committed entries to the system, which is modified only by the committed entries
+    /// (including the ones proposed by this node), and sends the results to the awaiting proposers.
+    async fn apply_committed_entries(&self, system: SharedSystem) {
+        let mut receiver = self.commit_sender.subscribe();
+        let mut applied_length = *receiver.borrow_and_update();
+        while receiver.changed().await.is_ok() {
+            let commit_length = *receiver.borrow_and_update();
+            if commit_length <= applied_length {
+                continue;
+            }
+
+            let entries = {
+                let raft = self.raft.lock().await;
+                raft.entries[applied_length as usize..commit_length as usize].to_vec()
+            };
+            for (index, entry) in (applied_length..commit_length).zip(entries) {
+                debug!("Applying committed state entry: {entry}");
+                let result = system.write().await.apply_replicated_entry(&entry).await;
+                if let Err(error) = &result {
+                    error!("Cannot apply committed state entry with index: {index}. {error}");
+                }
+
+                let proposal = self.raft.lock().await.proposals.remove(&index);
+                if let Some(proposal) = proposal.filter(|proposal| proposal.term == entry.term) {
+                    let _ = proposal.sender.send(result);
+                }
+            }
+            applied_length = commit_length;
+        }
+    }
+
+    async fn tick(&self) -> Result<(), IggyError> {
+        let mut raft = self.raft.lock().await;
+        let now = Instant::now();
+        if raft.role == Role::Leader {
+            if now.duration_since(raft.last_heartbeat) >= self.heartbeat_interval.get_duration() {
+                self.replicate_to_followers(&mut raft);
+                raft.last_heartbeat = now;
+            }
+            return Ok(());
+        }
+
+        if now < raft.election_deadline {
+            return Ok(());
+        }
+
+        // The node which is not the member of the cluster (yet) only follows the leader, just like the one
+        // with the empty log, which can't be elected before receiving the initial entries of the bootstrapping node.
+        if !raft.members.contains_key(&self.id) || raft.entries.is_empty() {
+            raft.election_deadline = self.next_election_deadline();
+            return Ok(());
+        }
+
+        self.start_election(&mut raft).await
+    }
+
+    pub(crate) async fn handle(self: &Arc<Self>, message: ClusterMessage) -> Result<(), IggyError> {
+        // The replication of the partitions doesn't depend on the consensus state, and it might take a while
+        // to read or append the messages, thus these are handled in the background.
+        if matches!(
+            message,
+            ClusterMessage::FetchRequest(_)
+                | ClusterMessage::FetchResponse(_)
+                | ClusterMessage::InSyncReplicasRequest(_)
+        ) {
+            let node = self.clone();
+            tokio::spawn(async move {
+                if let Err(error) = node.replicas.handle(&node, message).await {
+                    error!("Cannot handle the replication message. {error}");
+                }
+            });
+            return Ok(());
+        }
+
+        let mut raft = self.raft.lock().await;
+        match message {
+            ClusterMessage::VoteRequest(request) => self.on_vote_request(&mut raft, request).await,
+            ClusterMessage::VoteResponse(response) => {
+                self.on_vote_response(&mut raft, response).await
+            }
+            ClusterMessage::LogRequest(request) => self.on_log_request(&mut raft, request).await,
+            ClusterMessage::LogResponse(response) => {
+                self.on_log_response(&mut raft, response).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the members of the cluster along with the ones which have responded to the leader within the timeout.
+    /// Until the node has been the leader for the timeout, all the members are considered to be alive.
+    /// Returns `None` if this node is not the leader.
+    pub(crate) async fn get_live_members(
+        &self,
+        timeout: Duration,
+    ) -> Option<(Vec<u32>, HashSet<u32>)> {
+        let raft = self.raft.lock().await;
+        if raft.role != Role::Leader {
+            return None;
+        }
+
+        let members = raft.members.keys().copied().collect::<Vec<_>>();
+        let now = Instant::now();
+        let alive_members = if now - raft.leader_since < timeout {
+            members.iter().copied().collect()
+        } else {
+            members
+                .iter()
+                .filter(|id| {
+                    **id == self.id
+                        || raft
+                            .last_response
+                            .get(id)
+                            .is_some_and(|responded_at| now - *responded_at <= timeout)
+                })
+                .copied()
+                .collect()
+        };
+        Some((members, alive_members))
+    }
+
+    pub(crate) async fn send_message(&self, node_id: u32, message: &ClusterMessage) {
+        let raft = self.raft.lock().await;
+        self.send(&raft, node_id, message);
+    }
+
+    async fn start_election(&self, raft: &mut RaftState) -> Result<(), IggyError> {
+        raft.current_term += 1;
+        raft.role = Role::Candidate;
+        raft.voted_for = Some(self.id);
+        raft.current_leader = None;
+        raft.votes_received = HashSet::from([self.id]);
+        raft.election_deadline = self.next_election_deadline();
+        self.set_leader(None);
+        self.persist_metadata(raft).await?;
+        info!(
+            "Cluster node with ID: {} has started the election in term: {}.",
+            self.id, raft.current_term
+        );
+
+        if Self::has_quorum(raft, |id| raft.votes_received.contains(&id)) {
+            self.become_leader(raft);
+            return Ok(());
+        }
+
+        let request = ClusterMessage::VoteRequest(VoteRequest {
+            candidate_id: self.id,
+            term: raft.current_term,
+            log_length: raft.entries.len() as u64,
+            last_term: raft.entries.last().map(|entry| entry.term).unwrap_or(0),
+        });
+        for (id, address) in raft.members.iter().filter(|(id, _)| **id != self.id) {
+            self.transport.send(*id, address, &request);
+        }
+        Ok(())
+    }
+
+    async fn on_vote_request(
+        &self,
+        raft: &mut RaftState,
+        request: VoteRequest,
+    ) -> Result<(), IggyError> {
+        if request.term > raft.current_term {
+            self.step_down(raft, request.term).await?;
+        }
+
+        let last_term = raft.entries.last().map(|entry| entry.term).unwrap_or(0);
+        let log_ok = request.last_term > last_term
+            || (request.last_term == last_term && request.log_length >= raft.entries.len() as u64);
+        let granted = request.term == raft.current_term
+            && log_ok
+            && raft
+                .voted_for
+                .is_none_or(|voted_for| voted_for == request.candidate_id);
+        if granted {
+            raft.voted_for = Some(request.candidate_id);
+            raft.election_deadline = self.next_election_deadline();
+            self.persist_metadata(raft).await?;
+        }
+
+        let response = ClusterMessage::VoteResponse(VoteResponse {
+            voter_id: self.id,
+            term: raft.current_term,
+            granted,
+        });
+        self.send(raft, request.candidate_id, &response);
+        Ok(())
+    }
+
+    async fn on_vote_response(
+        &self,
+        raft: &mut RaftState,
+        response: VoteResponse,
+    ) -> Result<(), IggyError> {
+        if response.term > raft.current_term {
+            return self.step_down(raft, response.term).await;
+        }
+
+        if raft.role != Role::Candidate || response.term != raft.current_term || !response.granted {
+            return Ok(());
+        }
+
+        raft.votes_received.insert(response.voter_id);
+        if Self::has_quorum(raft, |id| raft.votes_received.contains(&id)) {
+            self.become_leader(raft);
+        }
+        Ok(())
+    }
+
+    async fn on_log_request(
+        &self,
+        raft: &mut RaftState,
+        request: LogRequest,
+    ) -> Result<(), IggyError> {
+        if request.term > raft.current_term {
+            self.step_down(raft, request.term).await?;
+        }
+
+        if request.term == raft.current_term {
+            if raft.role != Role::Follower || raft.current_leader != Some(request.leader_id) {
+                info!(
+                    "Cluster node with ID: {} follows the leader with ID: {} in term: {}.",
+                    self.id, request.leader_id, raft.current_term
+                );
+            }
+            raft.role = Role::Follower;
+            raft.current_leader = Some(request.leader_id);
+            raft.election_deadline = self.next_election_deadline();
+            self.set_leader(Some(ClusterLeader {
+                id: request.leader_id,
+                tcp_address: request.leader_tcp_address.clone(),
+                http_address: request.leader_http_address.clone(),
+            }));
+        }
+
+        let prefix_length = request.prefix_length as usize;
+        let log_ok = raft.entries.len() >= prefix_length
+            && (prefix_length == 0 || raft.entries[prefix_length - 1].term == request.prefix_term);
+        let response = if request.term == raft.current_term && log_ok {
+            let ack = request.prefix_length + request.entries.len() as u64;
+            self.append_entries(raft, prefix_length, request.leader_commit, request.entries)
+                .await?;
+            LogResponse {
+                follower_id: self.id,
+                term: raft.current_term,
+                ack,
+                success: true,
+            }
+        } else {
+            LogResponse {
+                follower_id: self.id,
+                term: raft.current_term,
+                ack: raft.entries.len() as u64,
+                success: false,
+            }
+        };
+        self.send(
+            raft,
+            request.leader_id,
+            &ClusterMessage::LogResponse(response),
+        );
+        Ok(())
+    }
+
+    async fn append_entries(
+        &self,
+        raft: &mut RaftState,
+        prefix_length: usize,
+        leader_commit: u64,
+        entries: Vec<StateEntry>,
+    ) -> Result<(), IggyError> {
+        let matched_length = (prefix_length + entries.len()) as u64;
+        if !entries.is_empty() && raft.entries.len() > prefix_length {
+            let index = raft.entries.len().min(prefix_length + entries.len()) - 1;
+            if raft.entries[index].term != entries[index - prefix_length].term {
+                self.truncate(raft, prefix_length).await?;
+            }
+        }
+
+        if prefix_length + entries.len() > raft.entries.len() {
+            let skip = raft.entries.len() - prefix_length;
+            for entry in entries.into_iter().skip(skip) {
+                self.log.append(&entry).await?;
+                Self::apply_membership(raft, &entry);
+                raft.entries.push(entry);
+            }
+        }
+
+        // Only the entries matching the ones of the leader can be committed.
+        let commit_length = leader_commit.min(matched_length);
+        if commit_length > raft.commit_length {
+            let previous_commit_length = raft.commit_length;
+            raft.commit_length = commit_length;
+            self.persist_metadata(raft).await?;
+            self.notify_committed(raft, previous_commit_length);
+        }
+        Ok(())
+    }
+
+    /// Removes the uncommitted entries conflicting with the ones replicated by the leader.
+    async fn truncate(&self, raft: &mut RaftState, length: usize) -> Result<(), IggyError> {
+        warn!(
+            "Truncating the state log of cluster node with ID: {} from: {} to: {length} entries.",
+            self.id,
+            raft.entries.len()
+        );
+        self.log.truncate_entries(length as u64).await?;
+        raft.entries.truncate(length);
+        // The proposers of the removed entries are notified by dropping their senders.
+        raft.proposals.retain(|index, _| *index < length as u64);
+        self.reload_members(raft);
+        Ok(())
+    }
+
+    async fn on_log_response(
+        &self,
+        raft: &mut RaftState,
+        response: LogResponse,
+    ) -> Result<(), IggyError> {
+        if response.term > raft.current_term {
+            return self.step_down(raft, response.term).await;
+        }
+
+        if response.term != raft.current_term || raft.role != Role::Leader {
+            return Ok(());
+        }
+
+        let follower_id = response.follower_id;
+        raft.last_response.insert(follower_id, Instant::now());
+        if response.success {
+            let acked_length = raft.acked_length.get(&follower_id).copied().unwrap_or(0);
+            if response.ack < acked_length {
+                return Ok(());
+            }
+
+            raft.sent_length.insert(follower_id, response.ack);
+            raft.acked_length.insert(follower_id, response.ack);
+            self.commit_log_entries(raft).await?;
+            if response.ack < raft.entries.len() as u64 && raft.role == Role::Leader {
+                self.replicate_log(raft, follower_id);
+            }
+            return Ok(());
+        }
+
+        let sent_length = raft
+            .sent_length
+            .get(&follower_id)
+            .copied()
+            .unwrap_or(raft.entries.len() as u64);
+        if sent_length > 0 {
+            raft.sent_length
+                .insert(follower_id, (sent_length - 1).min(response.ack));
+            self.replicate_log(raft, follower_id);
+        }
+        Ok(())
+    }
+
+    /// Commits the entries of the current term acknowledged by the majority of the members,
+    /// along with all the preceding ones.
+    async fn commit_log_entries(&self, raft: &mut RaftState) -> Result<(), IggyError> {
+        let log_length = raft.entries.len() as u64;
+        let mut commit_length = raft.commit_length;
+        for length in (raft.commit_length + 1..=log_length).rev() {
+            if raft.entries[length as usize - 1].term != raft.current_term {
+                break;
+            }
+
+            let acked = |id: u32| {
+                if id == self.id {
+                    return true;
+                }
+                raft.acked_length.get(&id).copied().unwrap_or(0) >= length
+            };
+            if Self::has_quorum(raft, acked) {
+                commit_length = length;
+                break;
+            }
+        }
+
+        if commit_length == raft.commit_length {
+            return Ok(());
+        }
+
+        let previous_commit_length = raft.commit_length;
+        raft.commit_length = commit_length;
+        self.persist_metadata(raft).await?;
+        self.notify_committed(raft, previous_commit_length);
+
+        // The leader removed from the cluster steps down once the removal has been committed.
+        if !raft.members.contains_key(&self.id) && commit_length == log_length {
+            self.replicate_to_followers(raft);
+            info!(
+                "Cluster node with ID: {} has been removed from the cluster, stepping down.",
+                self.id
+            );
+            raft.role = Role::Follower;
+            raft.current_leader = None;
+            self.set_leader(None);
+        }
+        Ok(())
+    }
+
+    fn become_leader(&self, raft: &mut RaftState) {
+        info!(
+            "Cluster node with ID: {} has become the leader in term: {}.",
+            self.id, raft.current_term
+        );
+        raft.role = Role::Leader;
+        raft.current_leader = Some(self.id);
+        raft.sent_length.clear();
+        raft.acked_length.clear();
+        raft.leader_since = Instant::now();
+        raft.last_response.clear();
+        let (tcp_address, http_address) = self.client_addresses.get().cloned().unwrap_or_default();
+        self.set_leader(Some(ClusterLeader {
+            id: self.id,
+            tcp_address,
+            http_address,
+        }));
+        self.replicate_to_followers(raft);
+        raft.last_heartbeat = Instant::now();
+    }
+
+    async fn step_down(&self, raft: &mut RaftState, term: u64) -> Result<(), IggyError> {
+        if raft.role == Role::Leader {
+            info!(
+                "Cluster node with ID: {} is no longer the leader, term: {term}.",
+                self.id
+            );
+        }
+        raft.current_term = term;
+        raft.role = Role::Follower;
+        raft.voted_for = None;
+        raft.current_leader = None;
+        raft.votes_received.clear();
+        raft.election_deadline = self.next_election_deadline();
+        self.set_leader(None);
+        self.persist_metadata(raft).await
+    }
+
+    fn replicate_to_followers(&self, raft: &mut RaftState) {
+        let followers = raft
+            .members
+            .keys()
+            .filter(|id| **id != self.id)
+            .copied()
+            .collect::<Vec<_>>();
+        for follower_id in followers {
+            self.replicate_log(raft, follower_id);
+        }
+    }
+
+    fn replicate_log(&self, raft: &mut RaftState, follower_id: u32) {
+        let log_length = raft.entries.len();
+        let prefix_length = raft
+            .sent_length
+            .get(&follower_id)
+            .map(|length| (*length as usize).min(log_length))
+            .unwrap_or(log_length);
+        raft.sent_length.insert(follower_id, prefix_length as u64);
+        let end = log_length.min(prefix_length + MAX_ENTRIES_PER_REQUEST);
+        let (tcp_address, http_address) = self.client_addresses.get().cloned().unwrap_or_default();
+        let request = ClusterMessage::LogRequest(LogRequest {
+            leader_id: self.id,
+            term: raft.current_term,
+            prefix_length: prefix_length as u64,
+            prefix_term: match prefix_length {
+                0 => 0,
+                _ => raft.entries[prefix_length - 1].term,
+            },
+            leader_commit: raft.commit_length,
+            leader_tcp_address: tcp_address,
+            leader_http_address: http_address,
+            entries: raft.entries[prefix_length..end].to_vec(),
+        });
+        self.send(raft, follower_id, &request);
+    }
+
+    fn send(&self, raft: &RaftState, node_id: u32, message: &ClusterMessage) {
+        match raft.addresses.get(&node_id) {
+            Some(address) => self.transport.send(node_id, address, message),
+            None => warn!("Unknown address of cluster node with ID: {node_id}, message: {message} will not be sent."),
+        }
+    }
+
+    /// Notifies the applier about the committed entries. The assignments of the partitions replicas are applied
+    /// right away, as these are maintained by the cluster node rather than the system.
+    fn notify_committed(&self, raft: &RaftState, previous_commit_length: u64) {
+        for entry in &raft.entries[previous_commit_length as usize..raft.commit_length as usize] {
+            self.replicas.apply_entry(entry);
+        }
+        self.commit_sender.send_replace(raft.commit_length);
+    }
+
+    fn has_quorum(raft: &RaftState, acknowledged: impl Fn(u32) -> bool) -> bool {
+        let acknowledgements = raft.members.keys().filter(|id| acknowledged(**id)).count();
+        acknowledgements * 2 > raft.members.len()
+    }
+
+    fn set_leader(&self, leader: Option<ClusterLeader>) {
+        let mut current_leader = self.leader.write().unwrap();
+        if *current_leader != leader {
+            *current_leader = leader;
+        }
+    }
+
+    fn next_election_deadline(&self) -> Instant {
+        let timeout = self.election_timeout.get_duration();
+        let mut buffer = [0; 8];
+        let _ = ring::rand::SystemRandom::new().fill(&mut buffer);
+        let jitter = match timeout.as_micros() as u64 {
+            0 => 0,
+            micros => u64::from_le_bytes(buffer) % micros,
+        };
+        Instant::now() + timeout + Duration::from_micros(jitter)
+    }
+
+    fn is_membership_change(entry: &StateEntry) -> bool {
+        if entry.command.len() < 4 {
+            return false;
+        }
+
+        let code = entry.command.slice(0..4).get_u32_le();
+        code == ADD_CLUSTER_NODE_CODE || code == REMOVE_CLUSTER_NODE_CODE
+    }
+
+    fn apply_membership(raft: &mut RaftState, entry: &StateEntry) {
+        if !Self::is_membership_change(entry) {
+            return;
+        }
+
+        match entry.command() {
+            Ok(EntryCommand::AddClusterNode(command)) => {
+                raft.members
+                    .insert(command.node_id, command.address.clone());
+                raft.addresses.insert(command.node_id, command.address);
+            }
+            Ok(EntryCommand::RemoveClusterNode(command)) => {
+                raft.members.remove(&command.node_id);
+                raft.sent_length.remove(&command.node_id);
+                raft.acked_length.remove(&command.node_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn reload_members(&self, raft: &mut RaftState) {
+        raft.members = self.initial_nodes.iter().cloned().collect();
+        let entries = std::mem::take(&mut raft.entries);
+        for entry in &entries {
+            Self::apply_membership(raft, entry);
+        }
+        raft.entries = entries;
+    }
+
+    async fn persist_metadata(&self, raft: &RaftState) -> Result<(), IggyError> {
+        let mut bytes = BytesMut::with_capacity(METADATA_LENGTH);
+        bytes.put_u64_le(raft.current_term);
+        bytes.put_u32_le(raft.voted_for.unwrap_or(0));
+        bytes.put_u64_le(raft.commit_length);
+        self.persister.overwrite(&self.metadata_path, &bytes).await
+    }
+}
@@ -0,0 +1,872 @@
+use crate::cluster::messages::{
+    ClusterMessage, FetchRequest, FetchResponse, InSyncReplicasRequest,
+};
+use crate::cluster::node::ClusterNode;
+use crate::configs::cluster::ClusterReplicationConfig;
+use crate::state::command::EntryCommand;
+use crate::state::entry::StateEntry;
+use crate::state::models::{UpdatePartitionReplicas, UPDATE_PARTITION_REPLICAS_CODE};
+use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::partitions::partition::Partition;
+use crate::streaming::sizeable::Sizeable;
+use crate::streaming::systems::system::SharedSystem;
+use bytes::{Buf, Bytes, BytesMut};
+use iggy::error::IggyError;
+use iggy::identifier::Identifier;
+use iggy::locking::{IggySharedMut, IggySharedMutFn};
+use iggy::validatable::Validatable;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{debug, error, info, warn};
+
+/// The minimum interval between the requests updating the in-sync replicas of the same partition.
+const IN_SYNC_REPLICAS_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Stream ID, topic ID and partition ID.
+type PartitionKey = (u32, u32, u32);
+
+/// Replicates the messages of the partitions across the cluster nodes.
+///
+/// Each partition is assigned to the `replication_factor` nodes by the cluster leader (acting as the controller),
+/// one of these being the partition leader which accepts the messages, while the other ones (followers)
+/// keep fetching the messages from it. The assignments are the entries of the state log, thus all the nodes agree on them.
+///
+/// The in-sync replicas are the ones which have caught up with the partition leader within the `replica_lag_timeout`.
+/// Only these can become the partition leader once the current one is no longer available,
+/// and only these are awaited when the messages are sent with the acknowledgement of all the replicas.
+/// Each change of the partition leader starts the new leader epoch, and the follower having more messages
+/// than the new leader had at the beginning of its epoch truncates them, as these might have not been replicated.
+/// The followers skip the messages already deleted from the leader by the retention policy,
+/// and keep the gaps in the offsets left by the log compaction.
+#[derive(Debug)]
+pub struct ReplicaManager {
+    node_id: u32,
+    config: ClusterReplicationConfig,
+    system: OnceLock<SharedSystem>,
+    assignments: RwLock<HashMap<PartitionKey, UpdatePartitionReplicas>>,
+    leaders: Mutex<HashMap<PartitionKey, LeaderProgress>>,
+    followers: Mutex<HashMap<PartitionKey, FollowerProgress>>,
+    proposals: Mutex<HashSet<PartitionKey>>,
+    progress_sender: watch::Sender<u64>,
+}
+
+/// The progress of the followers tracked by the partition leader within its epoch.
+#[derive(Debug)]
+struct LeaderProgress {
+    epoch: u32,
+    epoch_start_offset: u64,
+    replicas: HashMap<u32, ReplicaProgress>,
+    in_sync_replicas_requested_at: Option<Instant>,
+}
+
+/// `next_offset` is the offset requested by the last fetch, thus all the preceding messages are stored by the follower.
+/// `last_fetch` is the time of the last fetch along with the next offset of the leader at that time.
+#[derive(Debug, Default)]
+struct ReplicaProgress {
+    next_offset: u64,
+    caught_up_at: Option<Instant>,
+    last_fetch: Option<(Instant, u64)>,
+}
+
+/// `verified_epoch` is the leader epoch for which the messages of the follower have been verified
+/// against the start offset of the epoch, 0 if none (the epochs start from 1).
+#[derive(Debug, Default)]
+struct FollowerProgress {
+    verified_epoch: u32,
+    requested_at: Option<Instant>,
+}
+
+impl ReplicaManager {
+    pub fn new(node_id: u32, config: &ClusterReplicationConfig) -> Self {
+        let (progress_sender, _) = watch::channel(0);
+        Self {
+            node_id,
+            config: config.clone(),
+            system: OnceLock::new(),
+            assignments: RwLock::new(HashMap::new()),
+            leaders: Mutex::new(HashMap::new()),
+            followers: Mutex::new(HashMap::new()),
+            proposals: Mutex::new(HashSet::new()),
+            progress_sender,
+        }
+    }
+
+    pub(crate) fn set_system(&self, system: SharedSystem) {
+        let _ = self.system.set(system);
+    }
+
+    pub(crate) fn get_acks_timeout(&self) -> Duration {
+        self.config.acks_timeout.get_duration()
+    }
+
+    /// Applies the committed entry assigning the replicas of the partition. It's invoked as soon as the entry
+    /// is committed, as the assignments are maintained by the cluster node rather than the system.
+    pub(crate) fn apply_entry(&self, entry: &StateEntry) {
+        if entry.command.len() < 4
+            || entry.command.slice(0..4).get_u32_le() != UPDATE_PARTITION_REPLICAS_CODE
+        {
+            return;
+        }
+
+        let command = match entry.command() {
+            Ok(EntryCommand::UpdatePartitionReplicas(command)) => command,
+            Ok(_) => return,
+            Err(error) => {
+                error!(
+                    "Cannot read the partition replicas from state entry with index: {}. {error}",
+                    entry.index
+                );
+                return;
+            }
+        };
+
+        debug!("Updated the partition replicas: {command}");
+        let key = (command.stream_id, command.topic_id, command.partition_id);
+        self.assignments.write().unwrap().insert(key, command);
+        self.notify_progress();
+    }
+
+    pub(crate) fn get_assignment(
+        &self,
+        stream_id: u32,
+        topic_id: u32,
+        partition_id: u32,
+    ) -> Option<UpdatePartitionReplicas> {
+        self.assignments
+            .read()
+            .unwrap()
+            .get(&(stream_id, topic_id, partition_id))
+            .cloned()
+    }
+
+    /// Waits until the replicas of the partition have been assigned by the cluster leader,
+    /// which happens shortly after the partition has been created.
+    pub(crate) async fn wait_for_assignment(
+        &self,
+        stream_id: u32,
+        topic_id: u32,
+        partition_id: u32,
+        deadline: Instant,
+    ) -> Option<UpdatePartitionReplicas> {
+        let mut receiver = self.progress_sender.subscribe();
+        loop {
+            let assignment = self.get_assignment(stream_id, topic_id, partition_id);
+            if assignment.is_some() {
+                return assignment;
+            }
+
+            if !matches!(
+                tokio::time::timeout_at(deadline, receiver.changed()).await,
+                Ok(Ok(_))
+            ) {
+                return self.get_assignment(stream_id, topic_id, partition_id);
+            }
+        }
+    }
+
+    /// Starts tracking the followers once this node has become the leader of the partition in the new epoch.
+    /// Must be invoked before any message is appended within the epoch, with the partition lock being held,
+    /// as the next offset of the partition is the start offset of the epoch.
+    pub(crate) fn begin_leader_epoch(
+        &self,
+        assignment: &UpdatePartitionReplicas,
+        partition: &Partition,
+    ) {
+        let key = (
+            assignment.stream_id,
+            assignment.topic_id,
+            assignment.partition_id,
+        );
+        let mut leaders = self.leaders.lock().unwrap();
+        if leaders
+            .get(&key)
+            .is_some_and(|progress| progress.epoch == assignment.leader_epoch)
+        {
+            return;
+        }
+
+        let now = Instant::now();
+        let epoch_start_offset = partition.get_next_append_offset();
+        let replicas = assignment
+            .replicas
+            .iter()
+            .filter(|id| **id != self.node_id)
+            .map(|id| {
+                let replica = ReplicaProgress {
+                    caught_up_at: assignment.in_sync_replicas.contains(id).then_some(now),
+                    ..Default::default()
+                };
+                (*id, replica)
+            })
+            .collect();
+        info!(
+            "Cluster node with ID: {} is the leader of partition with ID: {} for topic with ID: {} and stream with ID: {} in epoch: {}, start offset: {epoch_start_offset}.",
+            self.node_id,
+            assignment.partition_id,
+            assignment.topic_id,
+            assignment.stream_id,
+            assignment.leader_epoch
+        );
+        leaders.insert(
+            key,
+            LeaderProgress {
+                epoch: assignment.leader_epoch,
+                epoch_start_offset,
+                replicas,
+                in_sync_replicas_requested_at: None,
+            },
+        );
+    }
+
+    /// Waits until all the in-sync replicas of the partition have fetched the message with the given offset.
+    pub(crate) async fn wait_for_in_sync_replicas(
+        &self,
+        stream_id: u32,
+        topic_id: u32,
+        partition_id: u32,
+        offset: u64,
+    ) -> Result<(), IggyError> {
+        let key = (stream_id, topic_id, partition_id);
+        let deadline = Instant::now() + self.get_acks_timeout();
+        let mut receiver = self.progress_sender.subscribe();
+        loop {
+            match self.is_replicated(key, offset) {
+                Some(true) => return Ok(()),
+                Some(false) => {}
+                None => {
+                    warn!("Cluster node with ID: {} is no longer the leader of partition with ID: {partition_id} for topic with ID: {topic_id} and stream with ID: {stream_id}, message with offset: {offset} might have not been replicated.", self.node_id);
+                    return Err(IggyError::MessagesNotReplicated(offset));
+                }
+            }
+
+            if !matches!(
+                tokio::time::timeout_at(deadline, receiver.changed()).await,
+                Ok(Ok(_))
+            ) {
+                warn!("Message with offset: {offset} for partition with ID: {partition_id}, topic with ID: {topic_id} and stream with ID: {stream_id} has not been replicated in time.");
+                return Err(IggyError::MessagesNotReplicated(offset));
+            }
+        }
+    }
+
+    /// Returns `None` if this node is no longer the leader of the partition.
+    fn is_replicated(&self, key: PartitionKey, offset: u64) -> Option<bool> {
+        let assignment = self.get_assignment(key.0, key.1, key.2)?;
+        if assignment.leader_id != self.node_id {
+            return None;
+        }
+
+        let leaders = self.leaders.lock().unwrap();
+        let progress = leaders
+            .get(&key)
+            .filter(|progress| progress.epoch == assignment.leader_epoch)?;
+        Some(
+            assignment
+                .in_sync_replicas
+                .iter()
+                .filter(|id| **id != self.node_id)
+                .all(|id| {
+                    progress
+                        .replicas
+                        .get(id)
+                        .is_some_and(|replica| replica.next_offset > offset)
+                }),
+        )
+    }
+
+    /// Assigns the replicas of the new partitions (on the cluster leader), maintains the in-sync replicas
+    /// of the partitions led by this node and fetches the messages of the ones followed by this node.
+    pub(crate) async fn run(&self, node: Arc<ClusterNode>) {
+        let mut interval = tokio::time::interval(self.config.fetch_interval.get_duration());
+        loop {
+            interval.tick().await;
+            if node.is_leader() {
+                if let Err(error) = self.control(&node).await {
+                    error!("Cannot assign the partitions replicas. {error}");
+                }
+            }
+
+            let assignments = self
+                .assignments
+                .read()
+                .unwrap()
+                .values()
+                .filter(|assignment| assignment.replicas.contains(&self.node_id))
+                .cloned()
+                .collect::<Vec<_>>();
+            for assignment in assignments {
+                if assignment.leader_id == self.node_id {
+                    self.maintain_in_sync_replicas(&node, &assignment).await;
+                } else {
+                    self.fetch(&node, &assignment).await;
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn handle(
+        &self,
+        node: &Arc<ClusterNode>,
+        message: ClusterMessage,
+    ) -> Result<(), IggyError> {
+        match message {
+            ClusterMessage::FetchRequest(request) => self.on_fetch_request(node, request).await,
+            ClusterMessage::FetchResponse(response) => self.on_fetch_response(node, response).await,
+            ClusterMessage::InSyncReplicasRequest(request) => {
+                self.on_in_sync_replicas_request(node, request);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Assigns the replicas to the partitions which have none yet, and elects the new leader
+    /// from the in-sync replicas of the partitions whose leader is no longer available.
+    async fn control(&self, node: &Arc<ClusterNode>) -> Result<(), IggyError> {
+        let Some((members, alive_members)) = node
+            .get_live_members(self.config.replica_lag_timeout.get_duration())
+            .await
+        else {
+            return Ok(());
+        };
+
+        if members.is_empty() {
+            return Ok(());
+        }
+
+        let partitions = {
+            let Some(system) = self.system.get() else {
+                return Ok(());
+            };
+            let system = system.read().await;
+            let mut partitions = Vec::new();
+            for stream in system.get_streams() {
+                for topic in stream.get_topics() {
+                    for partition_id in topic.partitions.keys() {
+                        partitions.push((
+                            (topic.stream_id, topic.topic_id, *partition_id),
+                            topic.replication_factor,
+                        ));
+                    }
+                }
+            }
+            partitions
+        };
+
+        for (key, replication_factor) in partitions {
+            let command = match self.get_assignment(key.0, key.1, key.2) {
+                None => Some(Self::assign(key, replication_factor, &members)),
+                Some(assignment) if !alive_members.contains(&assignment.leader_id) => {
+                    Self::fail_over(&assignment, &alive_members)
+                }
+                Some(_) => None,
+            };
+            if let Some(command) = command {
+                self.propose(node, command);
+            }
+        }
+        Ok(())
+    }
+
+    /// Picks the subsequent members, starting from the one shifted by the IDs of the partition,
+    /// so that the leaders of the partitions are spread across the cluster.
+    fn assign(
+        key: PartitionKey,
+        replication_factor: u8,
+        members: &[u32],
+    ) -> UpdatePartitionReplicas {
+        let replicas_count = (replication_factor.max(1) as usize).min(members.len());
+        let shift = key.0.wrapping_add(key.1).wrapping_add(key.2) as usize % members.len();
+        let replicas = members
+            .iter()
+            .cycle()
+            .skip(shift)
+            .take(replicas_count)
+            .copied()
+            .collect::<Vec<_>>();
+        UpdatePartitionReplicas {
+            stream_id: key.0,
+            topic_id: key.1,
+            partition_id: key.2,
+            leader_id: replicas[0],
+            leader_epoch: 1,
+            in_sync_replicas: replicas.clone(),
+            replicas,
+        }
+    }
+
+    /// Returns `None` if none of the in-sync replicas is available, as the other replicas might miss the messages.
+    fn fail_over(
+        assignment: &UpdatePartitionReplicas,
+        alive_members: &HashSet<u32>,
+    ) -> Option<UpdatePartitionReplicas> {
+        let leader_id = assignment
+            .in_sync_replicas
+            .iter()
+            .find(|id| **id != assignment.leader_id && alive_members.contains(id))
+            .copied()?;
+        Some(UpdatePartitionReplicas {
+            leader_id,
+            leader_epoch: assignment.leader_epoch + 1,
+            in_sync_replicas: assignment
+                .in_sync_replicas
+                .iter()
+                .filter(|id| **id != assignment.leader_id)
+                .copied()
+                .collect(),
+            ..assignment.clone()
+        })
+    }
+
+    /// Proposes the assignment in the background, unless there's another one in progress for the same partition.
+    fn propose(&self, node: &Arc<ClusterNode>, command: UpdatePartitionReplicas) {
+        let key = (command.stream_id, command.topic_id, command.partition_id);
+        if !self.proposals.lock().unwrap().insert(key) {
+            return;
+        }
+
+        info!("Proposing the partition replicas: {command}");
+        let node = node.clone();
+        tokio::spawn(async move {
+            if let Err(error) = node
+                .apply(0, EntryCommand::UpdatePartitionReplicas(command))
+                .await
+            {
+                warn!("Cannot update the partition replicas. {error}");
+            }
+            node.replicas().proposals.lock().unwrap().remove(&key);
+        });
+    }
+
+    /// Requests the update of the in-sync replicas of the partition led by this node, once these differ
+    /// from the replicas which have caught up with this node within the lag timeout.
+    async fn maintain_in_sync_replicas(
+        &self,
+        node: &Arc<ClusterNode>,
+        assignment: &UpdatePartitionReplicas,
+    ) {
+        let key = (
+            assignment.stream_id,
+            assignment.topic_id,
+            assignment.partition_id,
+        );
+        let Some(partition) = self.get_partition(key).await else {
+            return;
+        };
+
+        self.begin_leader_epoch(assignment, &*partition.read().await);
+        let in_sync_replicas = {
+            let mut leaders = self.leaders.lock().unwrap();
+            let Some(progress) = leaders
+                .get_mut(&key)
+                .filter(|progress| progress.epoch == assignment.leader_epoch)
+            else {
+                return;
+            };
+
+            let now = Instant::now();
+            let lag_timeout = self.config.replica_lag_timeout.get_duration();
+            let in_sync_replicas = assignment
+                .replicas
+                .iter()
+                .filter(|id| {
+                    **id == self.node_id
+                        || progress
+                            .replicas
+                            .get(id)
+                            .and_then(|replica| replica.caught_up_at)
+                            .is_some_and(|caught_up_at| now - caught_up_at <= lag_timeout)
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            let mut current_in_sync_replicas = assignment.in_sync_replicas.clone();
+            current_in_sync_replicas.sort_unstable();
+            let mut expected_in_sync_replicas = in_sync_replicas.clone();
+            expected_in_sync_replicas.sort_unstable();
+            if current_in_sync_replicas == expected_in_sync_replicas
+                || progress
+                    .in_sync_replicas_requested_at
+                    .is_some_and(|requested_at| {
+                        now - requested_at < IN_SYNC_REPLICAS_RETRY_INTERVAL
+                    })
+            {
+                return;
+            }
+
+            progress.in_sync_replicas_requested_at = Some(now);
+            in_sync_replicas
+        };
+
+        info!(
+            "In-sync replicas of partition with ID: {} for topic with ID: {} and stream with ID: {} have changed from: {:?} to: {:?}.",
+            assignment.partition_id,
+            assignment.topic_id,
+            assignment.stream_id,
+            assignment.in_sync_replicas,
+            in_sync_replicas
+        );
+        let request = InSyncReplicasRequest {
+            leader_id: self.node_id,
+            stream_id: assignment.stream_id,
+            topic_id: assignment.topic_id,
+            partition_id: assignment.partition_id,
+            leader_epoch: assignment.leader_epoch,
+            in_sync_replicas,
+        };
+        if node.is_leader() {
+            self.on_in_sync_replicas_request(node, request);
+            return;
+        }
+
+        if let Some(leader) = node.leader() {
+            node.send_message(leader.id, &ClusterMessage::InSyncReplicasRequest(request))
+                .await;
+        }
+    }
+
+    fn on_in_sync_replicas_request(&self, node: &Arc<ClusterNode>, request: InSyncReplicasRequest) {
+        if !node.is_leader() {
+            return;
+        }
+
+        let Some(assignment) =
+            self.get_assignment(request.stream_id, request.topic_id, request.partition_id)
+        else {
+            return;
+        };
+
+        if assignment.leader_id != request.leader_id
+            || assignment.leader_epoch != request.leader_epoch
+        {
+            debug!("Ignoring the stale in-sync replicas request from cluster node with ID: {}, epoch: {}.", request.leader_id, request.leader_epoch);
+            return;
+        }
+
+        let command = UpdatePartitionReplicas {
+            in_sync_replicas: request.in_sync_replicas,
+            ..assignment.clone()
+        };
+        if command.validate().is_err() {
+            warn!(
+                "Received invalid in-sync replicas: {:?} from cluster node with ID: {}.",
+                command.in_sync_replicas, request.leader_id
+            );
+            return;
+        }
+
+        if command != assignment {
+            self.propose(node, command);
+        }
+    }
+
+    async fn on_fetch_request(
+        &self,
+        node: &Arc<ClusterNode>,
+        request: FetchRequest,
+    ) -> Result<(), IggyError> {
+        let key = (request.stream_id, request.topic_id, request.partition_id);
+        let Some(assignment) = self.get_assignment(key.0, key.1, key.2) else {
+            return Ok(());
+        };
+
+        if assignment.leader_id != self.node_id
+            || assignment.leader_epoch != request.leader_epoch
+            || !assignment.replicas.contains(&request.follower_id)
+        {
+            debug!(
+                "Ignoring the fetch request from cluster node with ID: {}, epoch: {}.",
+                request.follower_id, request.leader_epoch
+            );
+            return Ok(());
+        }
+
+        let Some(partition) = self.get_partition(key).await else {
+            return Ok(());
+        };
+
+        let (log_start_offset, next_offset, messages) = {
+            let partition = partition.read().await;
+            self.begin_leader_epoch(&assignment, &partition);
+            let log_start_offset = partition.get_log_start_offset();
+            let next_offset = partition.get_next_append_offset();
+            let mut offset = request.offset.max(log_start_offset);
+            let mut messages = Vec::new();
+            // The gaps left by the log compaction might be larger than the fetched range of offsets.
+            while messages.is_empty() && offset < next_offset {
+                let count =
+                    (next_offset - offset).min(self.config.fetch_max_messages as u64) as u32;
+                messages = partition.get_messages_by_offset(offset, count).await?;
+                offset += count as u64;
+            }
+            (log_start_offset, next_offset, messages)
+        };
+
+        let Some(epoch_start_offset) = self.track_fetch(key, &request, next_offset) else {
+            return Ok(());
+        };
+
+        let mut bytes = BytesMut::new();
+        for message in &messages {
+            message.extend(&mut bytes);
+        }
+        let response = ClusterMessage::FetchResponse(FetchResponse {
+            leader_id: self.node_id,
+            stream_id: request.stream_id,
+            topic_id: request.topic_id,
+            partition_id: request.partition_id,
+            leader_epoch: request.leader_epoch,
+            epoch_start_offset,
+            log_start_offset,
+            end_offset: next_offset,
+            messages_count: messages.len() as u32,
+            messages: bytes.freeze(),
+        });
+        node.send_message(request.follower_id, &response).await;
+        Ok(())
+    }
+
+    /// Records the offset fetched by the follower, which has caught up with the leader if it has fetched
+    /// all the messages, or at least the ones which had been available at the time of its previous fetch.
+    /// Returns the start offset of the leader epoch.
+    fn track_fetch(
+        &self,
+        key: PartitionKey,
+        request: &FetchRequest,
+        next_offset: u64,
+    ) -> Option<u64> {
+        let mut leaders = self.leaders.lock().unwrap();
+        let progress = leaders
+            .get_mut(&key)
+            .filter(|progress| progress.epoch == request.leader_epoch)?;
+        let now = Instant::now();
+        let replica = progress.replicas.entry(request.follower_id).or_default();
+        let caught_up_at = if request.offset >= next_offset {
+            Some(now)
+        } else {
+            replica
+                .last_fetch
+                .filter(|(_, fetched_next_offset)| request.offset >= *fetched_next_offset)
+                .map(|(fetched_at, _)| fetched_at)
+        };
+        if caught_up_at > replica.caught_up_at {
+            replica.caught_up_at = caught_up_at;
+        }
+        replica.last_fetch = Some((now, next_offset));
+        let advanced = replica.next_offset != request.offset;
+        replica.next_offset = request.offset;
+        let epoch_start_offset = progress.epoch_start_offset;
+        drop(leaders);
+        if advanced {
+            self.notify_progress();
+        }
+        Some(epoch_start_offset)
+    }
+
+    /// Requests the messages following the last one stored by this node, unless there's another request in progress,
+    /// which is retried after half of the lag timeout, as the request or the response might have been dropped.
+    async fn fetch(&self, node: &Arc<ClusterNode>, assignment: &UpdatePartitionReplicas) {
+        let key = (
+            assignment.stream_id,
+            assignment.topic_id,
+            assignment.partition_id,
+        );
+        let Some(partition) = self.get_partition(key).await else {
+            return;
+        };
+
+        {
+            let retry_interval = self.config.replica_lag_timeout.get_duration() / 2;
+            let mut followers = self.followers.lock().unwrap();
+            let follower = followers.entry(key).or_default();
+            if follower
+                .requested_at
+                .is_some_and(|requested_at| requested_at.elapsed() < retry_interval)
+            {
+                return;
+            }
+
+            follower.requested_at = Some(Instant::now());
+        }
+
+        let offset = partition.read().await.get_next_append_offset();
+        let request = ClusterMessage::FetchRequest(FetchRequest {
+            follower_id: self.node_id,
+            stream_id: assignment.stream_id,
+            topic_id: assignment.topic_id,
+            partition_id: assignment.partition_id,
+            leader_epoch: assignment.leader_epoch,
+            offset,
+        });
+        node.send_message(assignment.leader_id, &request).await;
+    }
+
+    async fn on_fetch_response(
+        &self,
+        node: &Arc<ClusterNode>,
+        response: FetchResponse,
+    ) -> Result<(), IggyError> {
+        let key = (response.stream_id, response.topic_id, response.partition_id);
+        let Some(assignment) = self.get_assignment(key.0, key.1, key.2) else {
+            return Ok(());
+        };
+
+        if assignment.leader_id != response.leader_id
+            || assignment.leader_epoch != response.leader_epoch
+            || assignment.leader_id == self.node_id
+            || !assignment.replicas.contains(&self.node_id)
+        {
+            return Ok(());
+        }
+
+        let Some(partition) = self.get_partition(key).await else {
+            return Ok(());
+        };
+
+        let verified = self
+            .followers
+            .lock()
+            .unwrap()
+            .get(&key)
+            .is_some_and(|follower| follower.verified_epoch == response.leader_epoch);
+        let messages = Self::read_messages(response.messages)?;
+        let next_offset = {
+            let mut partition = partition.write().await;
+            if !verified {
+                // The messages following the start of the epoch might have not been replicated by the new leader.
+                if partition.get_next_append_offset() > response.epoch_start_offset {
+                    warn!(
+                        "Partition with ID: {} for topic with ID: {} and stream with ID: {} has more messages than the leader with ID: {} in epoch: {}, truncating them from start offset: {}.",
+                        response.partition_id,
+                        response.topic_id,
+                        response.stream_id,
+                        response.leader_id,
+                        response.leader_epoch,
+                        response.epoch_start_offset
+                    );
+                    partition
+                        .truncate_messages(response.epoch_start_offset)
+                        .await?;
+                }
+
+                self.followers
+                    .lock()
+                    .unwrap()
+                    .entry(key)
+                    .or_default()
+                    .verified_epoch = response.leader_epoch;
+            }
+
+            // The preceding messages might have been already deleted from the leader by the retention policy,
+            // and the following ones might have the gaps left by the log compaction.
+            partition.advance_offset(response.log_start_offset);
+            let next_offset = partition.get_next_append_offset();
+            let messages = messages
+                .into_iter()
+                .filter(|message| message.offset >= next_offset)
+                .collect::<Vec<_>>();
+            if !messages.is_empty() {
+                let batch_size = messages
+                    .iter()
+                    .map(|message| message.get_size_bytes() as u64)
+                    .sum();
+                partition.add_segment_if_last_is_closed().await?;
+                partition
+                    .append_retained_messages(batch_size, messages)
+                    .await?;
+            }
+            partition.get_next_append_offset()
+        };
+
+        if let Some(follower) = self.followers.lock().unwrap().get_mut(&key) {
+            follower.requested_at = None;
+        }
+
+        if next_offset < response.end_offset {
+            self.fetch(node, &assignment).await;
+        }
+        Ok(())
+    }
+
+    fn read_messages(mut bytes: Bytes) -> Result<Vec<Arc<RetainedMessage>>, IggyError> {
+        let mut messages = Vec::new();
+        while bytes.remaining() >= 4 {
+            let length = bytes.get_u32_le() as usize;
+            if bytes.remaining() < length {
+                return Err(IggyError::InvalidClusterMessage);
+            }
+
+            messages.push(Arc::new(RetainedMessage::try_from_bytes(
+                bytes.split_to(length),
+            )?));
+        }
+        Ok(messages)
+    }
+
+    async fn get_partition(&self, key: PartitionKey) -> Option<IggySharedMut<Partition>> {
+        let system = self.system.get()?.read().await;
+        let stream = system.get_stream(&Identifier::numeric(key.0).ok()?).ok()?;
+        let topic = stream.get_topic(&Identifier::numeric(key.1).ok()?).ok()?;
+        topic.get_partition(key.2).ok()
+    }
+
+    fn notify_progress(&self) {
+        self.progress_sender
+            .send_modify(|version| *version = version.wrapping_add(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partitions_should_be_assigned_to_subsequent_members_shifted_by_partition() {
+        let members = [1, 2, 3];
+
+        let first = ReplicaManager::assign((1, 1, 1), 2, &members);
+        let second = ReplicaManager::assign((1, 1, 2), 2, &members);
+        let unreplicated = ReplicaManager::assign((1, 1, 3), 0, &members);
+        let exceeding = ReplicaManager::assign((1, 1, 1), 5, &members);
+
+        assert_eq!(first.replicas, vec![1, 2]);
+        assert_eq!(first.leader_id, 1);
+        assert_eq!(first.in_sync_replicas, first.replicas);
+        assert_eq!(second.replicas, vec![2, 3]);
+        assert_eq!(second.leader_id, 2);
+        assert_eq!(unreplicated.replicas, vec![3]);
+        assert_eq!(exceeding.replicas, vec![1, 2, 3]);
+        assert!(first.validate().is_ok());
+    }
+
+    #[test]
+    fn failed_leader_should_be_replaced_by_alive_in_sync_replica() {
+        let assignment = UpdatePartitionReplicas {
+            stream_id: 1,
+            topic_id: 1,
+            partition_id: 1,
+            leader_id: 1,
+            leader_epoch: 3,
+            replicas: vec![1, 2, 3],
+            in_sync_replicas: vec![1, 2, 3],
+        };
+
+        let command = ReplicaManager::fail_over(&assignment, &HashSet::from([3])).unwrap();
+        let unavailable = ReplicaManager::fail_over(
+            &UpdatePartitionReplicas {
+                in_sync_replicas: vec![1],
+                ..assignment.clone()
+            },
+            &HashSet::from([2, 3]),
+        );
+
+        assert_eq!(command.leader_id, 3);
+        assert_eq!(command.leader_epoch, 4);
+        assert_eq!(command.in_sync_replicas, vec![2, 3]);
+        assert_eq!(command.replicas, assignment.replicas);
+        assert!(command.validate().is_ok());
+        assert!(unavailable.is_none());
+    }
+}
@@ -0,0 +1,22 @@
+use crate::cluster::node::ClusterNode;
+use crate::state::command::EntryCommand;
+use crate::state::entry::StateEntry;
+use crate::state::State;
+use async_trait::async_trait;
+use iggy::error::IggyError;
+
+/// The state log replicated by the cluster, only the committed entries are loaded.
+#[async_trait]
+impl State for ClusterNode {
+    async fn init(&self) -> Result<Vec<StateEntry>, IggyError> {
+        ClusterNode::init(self).await
+    }
+
+    async fn load_entries(&self) -> Result<Vec<StateEntry>, IggyError> {
+        Ok(self.committed_entries().await)
+    }
+
+    async fn apply(&self, user_id: u32, command: EntryCommand) -> Result<(), IggyError> {
+        ClusterNode::apply(self, user_id, command).await
+    }
+}
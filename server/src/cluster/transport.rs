@@ -0,0 +1,126 @@
+use crate::cluster::messages::ClusterMessage;
+use crate::cluster::node::ClusterNode;
+use bytes::Bytes;
+use iggy::bytes_serializable::BytesSerializable;
+use iggy::error::IggyError;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::{debug, error, info, warn};
+
+const MAX_MESSAGE_LENGTH: usize = 64 * 1024 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The transport between the cluster nodes, each message is sent as the length (u32) followed by the payload.
+/// The outgoing messages are queued per peer and sent by the dedicated task, which (re)connects lazily,
+/// the message is dropped if it cannot be delivered, as the consensus algorithm retries on its own.
+#[derive(Debug, Default)]
+pub struct ClusterTransport {
+    peers: Mutex<HashMap<u32, UnboundedSender<Bytes>>>,
+}
+
+impl ClusterTransport {
+    pub fn send(&self, node_id: u32, address: &str, message: &ClusterMessage) {
+        let bytes = message.to_bytes();
+        let mut peers = self.peers.lock().unwrap();
+        if let Some(sender) = peers.get(&node_id) {
+            if sender.send(bytes.clone()).is_ok() {
+                return;
+            }
+        }
+
+        let (sender, receiver) = unbounded_channel();
+        let _ = sender.send(bytes);
+        peers.insert(node_id, sender);
+        tokio::spawn(send_messages(node_id, address.to_string(), receiver));
+    }
+}
+
+/// Binds the cluster listener and handles the incoming messages, returns the bound address.
+pub async fn listen(address: &str, node: Arc<ClusterNode>) -> Result<SocketAddr, IggyError> {
+    let listener = TcpListener::bind(address).await.map_err(|error| {
+        error!("Cannot bind the cluster listener to: {address}. {error}");
+        IggyError::InvalidClusterNodeAddress(address.to_string())
+    })?;
+    let address = listener.local_addr()?;
+    info!("Cluster node is listening on: {address}");
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer_address)) => {
+                    debug!("Accepted cluster connection from: {peer_address}");
+                    tokio::spawn(receive_messages(stream, node.clone()));
+                }
+                Err(error) => error!("Cannot accept the cluster connection. {error}"),
+            }
+        }
+    });
+    Ok(address)
+}
+
+async fn receive_messages(mut stream: TcpStream, node: Arc<ClusterNode>) {
+    loop {
+        let Ok(length) = stream.read_u32_le().await else {
+            return;
+        };
+
+        let length = length as usize;
+        if length > MAX_MESSAGE_LENGTH {
+            warn!("Received too large cluster message: {length} bytes, closing the connection.");
+            return;
+        }
+
+        let mut payload = vec![0; length];
+        if stream.read_exact(&mut payload).await.is_err() {
+            return;
+        }
+
+        match ClusterMessage::from_bytes(Bytes::from(payload)) {
+            Ok(message) => {
+                if let Err(error) = node.handle(message).await {
+                    error!("Cannot handle the cluster message. {error}");
+                }
+            }
+            Err(error) => {
+                warn!("Received invalid cluster message, closing the connection. {error}");
+                return;
+            }
+        }
+    }
+}
+
+async fn send_messages(node_id: u32, address: String, mut receiver: UnboundedReceiver<Bytes>) {
+    let mut stream: Option<TcpStream> = None;
+    while let Some(bytes) = receiver.recv().await {
+        if stream.is_none() {
+            match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&address)).await {
+                Ok(Ok(connection)) => {
+                    let _ = connection.set_nodelay(true);
+                    debug!("Connected to cluster node with ID: {node_id} at: {address}");
+                    stream = Some(connection);
+                }
+                _ => {
+                    debug!("Cannot connect to cluster node with ID: {node_id} at: {address}, dropping the queued messages.");
+                    while receiver.try_recv().is_ok() {}
+                    continue;
+                }
+            }
+        }
+
+        let Some(connection) = stream.as_mut() else {
+            continue;
+        };
+
+        let mut frame = Vec::with_capacity(4 + bytes.len());
+        frame.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&bytes);
+        if let Err(error) = connection.write_all(&frame).await {
+            debug!("Cannot send the message to cluster node with ID: {node_id}. {error}");
+            stream = None;
+        }
+    }
+}
@@ -1,14 +1,21 @@
 use bytes::{BufMut, Bytes, BytesMut};
+use iggy::cluster::add_cluster_node::AddClusterNode;
+use iggy::cluster::get_cluster_metadata::GetClusterMetadata;
+use iggy::cluster::remove_cluster_node::RemoveClusterNode;
 use iggy::command::*;
 use iggy::consumer_groups::create_consumer_group::CreateConsumerGroup;
 use iggy::consumer_groups::delete_consumer_group::DeleteConsumerGroup;
 use iggy::consumer_groups::get_consumer_group::GetConsumerGroup;
 use iggy::consumer_groups::get_consumer_groups::GetConsumerGroups;
+use iggy::consumer_groups::heartbeat_consumer_group::HeartbeatConsumerGroup;
 use iggy::consumer_groups::join_consumer_group::JoinConsumerGroup;
 use iggy::consumer_groups::leave_consumer_group::LeaveConsumerGroup;
 use iggy::consumer_offsets::get_consumer_offset::GetConsumerOffset;
 use iggy::consumer_offsets::store_consumer_offset::StoreConsumerOffset;
 use iggy::error::IggyError;
+use iggy::messages::ack_message::AckMessage;
+use iggy::messages::init_producer::InitProducer;
+use iggy::messages::nack_message::NackMessage;
 use iggy::messages::poll_messages::PollMessages;
 use iggy::messages::send_messages::SendMessages;
 use iggy::partitions::create_partitions::CreatePartitions;
@@ -28,12 +35,19 @@ use iggy::system::get_clients::GetClients;
 use iggy::system::get_me::GetMe;
 use iggy::system::get_stats::GetStats;
 use iggy::system::ping::Ping;
+use iggy::system::reload_config::ReloadConfig;
 use iggy::topics::create_topic::CreateTopic;
 use iggy::topics::delete_topic::DeleteTopic;
 use iggy::topics::get_topic::GetTopic;
 use iggy::topics::get_topics::GetTopics;
 use iggy::topics::purge_topic::PurgeTopic;
+use iggy::topics::set_cleanup_policy::SetCleanupPolicy;
+use iggy::topics::set_dead_letter_queue::SetDeadLetterQueue;
+use iggy::topics::set_max_topic_messages::SetMaxTopicMessages;
 use iggy::topics::update_topic::UpdateTopic;
+use iggy::transactions::abort_transaction::AbortTransaction;
+use iggy::transactions::begin_transaction::BeginTransaction;
+use iggy::transactions::commit_transaction::CommitTransaction;
 use iggy::users::change_password::ChangePassword;
 use iggy::users::create_user::CreateUser;
 use iggy::users::delete_user::DeleteUser;
@@ -57,6 +71,7 @@ pub enum ServerCommand {
     GetMe(GetMe),
     GetClient(GetClient),
     GetClients(GetClients),
+    ReloadConfig(ReloadConfig),
     GetUser(GetUser),
     GetUsers(GetUsers),
     CreateUser(CreateUser),
@@ -73,6 +88,12 @@ pub enum ServerCommand {
     SendMessages(SendMessages),
     PollMessages(PollMessages),
     FlushUnsavedBuffer(FlushUnsavedBuffer),
+    NackMessage(NackMessage),
+    AckMessage(AckMessage),
+    InitProducer(InitProducer),
+    BeginTransaction(BeginTransaction),
+    CommitTransaction(CommitTransaction),
+    AbortTransaction(AbortTransaction),
     GetConsumerOffset(GetConsumerOffset),
     StoreConsumerOffset(StoreConsumerOffset),
     GetStream(GetStream),
@@ -87,6 +108,9 @@ pub enum ServerCommand {
     DeleteTopic(DeleteTopic),
     UpdateTopic(UpdateTopic),
     PurgeTopic(PurgeTopic),
+    SetDeadLetterQueue(SetDeadLetterQueue),
+    SetCleanupPolicy(SetCleanupPolicy),
+    SetMaxTopicMessages(SetMaxTopicMessages),
     CreatePartitions(CreatePartitions),
     DeletePartitions(DeletePartitions),
     GetConsumerGroup(GetConsumerGroup),
@@ -95,6 +119,43 @@ pub enum ServerCommand {
     DeleteConsumerGroup(DeleteConsumerGroup),
     JoinConsumerGroup(JoinConsumerGroup),
     LeaveConsumerGroup(LeaveConsumerGroup),
+    HeartbeatConsumerGroup(HeartbeatConsumerGroup),
+    GetClusterMetadata(GetClusterMetadata),
+    AddClusterNode(AddClusterNode),
+    RemoveClusterNode(RemoveClusterNode),
+}
+
+impl ServerCommand {
+    /// Returns true if the command modifies the state replicated by the cluster, thus it must be handled by the leader.
+    pub fn is_replicated(&self) -> bool {
+        matches!(
+            self,
+            ServerCommand::CreateUser(_)
+                | ServerCommand::DeleteUser(_)
+                | ServerCommand::UpdateUser(_)
+                | ServerCommand::UpdatePermissions(_)
+                | ServerCommand::ChangePassword(_)
+                | ServerCommand::CreatePersonalAccessToken(_)
+                | ServerCommand::DeletePersonalAccessToken(_)
+                | ServerCommand::CreateStream(_)
+                | ServerCommand::DeleteStream(_)
+                | ServerCommand::UpdateStream(_)
+                | ServerCommand::PurgeStream(_)
+                | ServerCommand::CreateTopic(_)
+                | ServerCommand::DeleteTopic(_)
+                | ServerCommand::UpdateTopic(_)
+                | ServerCommand::PurgeTopic(_)
+                | ServerCommand::SetDeadLetterQueue(_)
+                | ServerCommand::SetCleanupPolicy(_)
+                | ServerCommand::SetMaxTopicMessages(_)
+                | ServerCommand::CreatePartitions(_)
+                | ServerCommand::DeletePartitions(_)
+                | ServerCommand::CreateConsumerGroup(_)
+                | ServerCommand::DeleteConsumerGroup(_)
+                | ServerCommand::AddClusterNode(_)
+                | ServerCommand::RemoveClusterNode(_)
+        )
+    }
 }
 
 impl BytesSerializable for ServerCommand {
@@ -105,6 +166,7 @@ impl BytesSerializable for ServerCommand {
             ServerCommand::GetMe(payload) => as_bytes(payload),
             ServerCommand::GetClient(payload) => as_bytes(payload),
             ServerCommand::GetClients(payload) => as_bytes(payload),
+            ServerCommand::ReloadConfig(payload) => as_bytes(payload),
             ServerCommand::GetUser(payload) => as_bytes(payload),
             ServerCommand::GetUsers(payload) => as_bytes(payload),
             ServerCommand::CreateUser(payload) => as_bytes(payload),
@@ -134,6 +196,9 @@ impl BytesSerializable for ServerCommand {
             ServerCommand::DeleteTopic(payload) => as_bytes(payload),
             ServerCommand::UpdateTopic(payload) => as_bytes(payload),
             ServerCommand::PurgeTopic(payload) => as_bytes(payload),
+            ServerCommand::SetDeadLetterQueue(payload) => as_bytes(payload),
+            ServerCommand::SetCleanupPolicy(payload) => as_bytes(payload),
+            ServerCommand::SetMaxTopicMessages(payload) => as_bytes(payload),
             ServerCommand::CreatePartitions(payload) => as_bytes(payload),
             ServerCommand::DeletePartitions(payload) => as_bytes(payload),
             ServerCommand::GetConsumerGroup(payload) => as_bytes(payload),
@@ -142,7 +207,17 @@ impl BytesSerializable for ServerCommand {
             ServerCommand::DeleteConsumerGroup(payload) => as_bytes(payload),
             ServerCommand::JoinConsumerGroup(payload) => as_bytes(payload),
             ServerCommand::LeaveConsumerGroup(payload) => as_bytes(payload),
+            ServerCommand::HeartbeatConsumerGroup(payload) => as_bytes(payload),
             ServerCommand::FlushUnsavedBuffer(payload) => as_bytes(payload),
+            ServerCommand::NackMessage(payload) => as_bytes(payload),
+            ServerCommand::AckMessage(payload) => as_bytes(payload),
+            ServerCommand::InitProducer(payload) => as_bytes(payload),
+            ServerCommand::BeginTransaction(payload) => as_bytes(payload),
+            ServerCommand::CommitTransaction(payload) => as_bytes(payload),
+            ServerCommand::AbortTransaction(payload) => as_bytes(payload),
+            ServerCommand::GetClusterMetadata(payload) => as_bytes(payload),
+            ServerCommand::AddClusterNode(payload) => as_bytes(payload),
+            ServerCommand::RemoveClusterNode(payload) => as_bytes(payload),
         }
     }
 
@@ -155,6 +230,9 @@ impl BytesSerializable for ServerCommand {
             GET_ME_CODE => Ok(ServerCommand::GetMe(GetMe::from_bytes(payload)?)),
             GET_CLIENT_CODE => Ok(ServerCommand::GetClient(GetClient::from_bytes(payload)?)),
             GET_CLIENTS_CODE => Ok(ServerCommand::GetClients(GetClients::from_bytes(payload)?)),
+            RELOAD_CONFIG_CODE => Ok(ServerCommand::ReloadConfig(ReloadConfig::from_bytes(
+                payload,
+            )?)),
             GET_USER_CODE => Ok(ServerCommand::GetUser(GetUser::from_bytes(payload)?)),
             GET_USERS_CODE => Ok(ServerCommand::GetUsers(GetUsers::from_bytes(payload)?)),
             CREATE_USER_CODE => Ok(ServerCommand::CreateUser(CreateUser::from_bytes(payload)?)),
@@ -191,6 +269,22 @@ impl BytesSerializable for ServerCommand {
             FLUSH_UNSAVED_BUFFER_CODE => Ok(ServerCommand::FlushUnsavedBuffer(
                 FlushUnsavedBuffer::from_bytes(payload)?,
             )),
+            NACK_MESSAGE_CODE => Ok(ServerCommand::NackMessage(NackMessage::from_bytes(
+                payload,
+            )?)),
+            ACK_MESSAGE_CODE => Ok(ServerCommand::AckMessage(AckMessage::from_bytes(payload)?)),
+            INIT_PRODUCER_CODE => Ok(ServerCommand::InitProducer(InitProducer::from_bytes(
+                payload,
+            )?)),
+            BEGIN_TRANSACTION_CODE => Ok(ServerCommand::BeginTransaction(
+                BeginTransaction::from_bytes(payload)?,
+            )),
+            COMMIT_TRANSACTION_CODE => Ok(ServerCommand::CommitTransaction(
+                CommitTransaction::from_bytes(payload)?,
+            )),
+            ABORT_TRANSACTION_CODE => Ok(ServerCommand::AbortTransaction(
+                AbortTransaction::from_bytes(payload)?,
+            )),
             STORE_CONSUMER_OFFSET_CODE => Ok(ServerCommand::StoreConsumerOffset(
                 StoreConsumerOffset::from_bytes(payload)?,
             )),
@@ -223,6 +317,15 @@ impl BytesSerializable for ServerCommand {
                 payload,
             )?)),
             PURGE_TOPIC_CODE => Ok(ServerCommand::PurgeTopic(PurgeTopic::from_bytes(payload)?)),
+            SET_DEAD_LETTER_QUEUE_CODE => Ok(ServerCommand::SetDeadLetterQueue(
+                SetDeadLetterQueue::from_bytes(payload)?,
+            )),
+            SET_CLEANUP_POLICY_CODE => Ok(ServerCommand::SetCleanupPolicy(
+                SetCleanupPolicy::from_bytes(payload)?,
+            )),
+            SET_MAX_TOPIC_MESSAGES_CODE => Ok(ServerCommand::SetMaxTopicMessages(
+                SetMaxTopicMessages::from_bytes(payload)?,
+            )),
             CREATE_PARTITIONS_CODE => Ok(ServerCommand::CreatePartitions(
                 CreatePartitions::from_bytes(payload)?,
             )),
@@ -247,6 +350,18 @@ impl BytesSerializable for ServerCommand {
             LEAVE_CONSUMER_GROUP_CODE => Ok(ServerCommand::LeaveConsumerGroup(
                 LeaveConsumerGroup::from_bytes(payload)?,
             )),
+            HEARTBEAT_CONSUMER_GROUP_CODE => Ok(ServerCommand::HeartbeatConsumerGroup(
+                HeartbeatConsumerGroup::from_bytes(payload)?,
+            )),
+            GET_CLUSTER_METADATA_CODE => Ok(ServerCommand::GetClusterMetadata(
+                GetClusterMetadata::from_bytes(payload)?,
+            )),
+            ADD_CLUSTER_NODE_CODE => Ok(ServerCommand::AddClusterNode(AddClusterNode::from_bytes(
+                payload,
+            )?)),
+            REMOVE_CLUSTER_NODE_CODE => Ok(ServerCommand::RemoveClusterNode(
+                RemoveClusterNode::from_bytes(payload)?,
+            )),
             _ => Err(IggyError::InvalidCommand),
         }
     }
@@ -268,6 +383,7 @@ impl Validatable<IggyError> for ServerCommand {
             ServerCommand::GetMe(command) => command.validate(),
             ServerCommand::GetClient(command) => command.validate(),
             ServerCommand::GetClients(command) => command.validate(),
+            ServerCommand::ReloadConfig(command) => command.validate(),
             ServerCommand::GetUser(command) => command.validate(),
             ServerCommand::GetUsers(command) => command.validate(),
             ServerCommand::CreateUser(command) => command.validate(),
@@ -297,6 +413,9 @@ impl Validatable<IggyError> for ServerCommand {
             ServerCommand::DeleteTopic(command) => command.validate(),
             ServerCommand::UpdateTopic(command) => command.validate(),
             ServerCommand::PurgeTopic(command) => command.validate(),
+            ServerCommand::SetDeadLetterQueue(command) => command.validate(),
+            ServerCommand::SetCleanupPolicy(command) => command.validate(),
+            ServerCommand::SetMaxTopicMessages(command) => command.validate(),
             ServerCommand::CreatePartitions(command) => command.validate(),
             ServerCommand::DeletePartitions(command) => command.validate(),
             ServerCommand::GetConsumerGroup(command) => command.validate(),
@@ -305,7 +424,17 @@ impl Validatable<IggyError> for ServerCommand {
             ServerCommand::DeleteConsumerGroup(command) => command.validate(),
             ServerCommand::JoinConsumerGroup(command) => command.validate(),
             ServerCommand::LeaveConsumerGroup(command) => command.validate(),
+            ServerCommand::HeartbeatConsumerGroup(command) => command.validate(),
             ServerCommand::FlushUnsavedBuffer(command) => command.validate(),
+            ServerCommand::NackMessage(command) => command.validate(),
+            ServerCommand::AckMessage(command) => command.validate(),
+            ServerCommand::InitProducer(command) => command.validate(),
+            ServerCommand::BeginTransaction(command) => command.validate(),
+            ServerCommand::CommitTransaction(command) => command.validate(),
+            ServerCommand::AbortTransaction(command) => command.validate(),
+            ServerCommand::GetClusterMetadata(command) => command.validate(),
+            ServerCommand::AddClusterNode(command) => command.validate(),
+            ServerCommand::RemoveClusterNode(command) => command.validate(),
         }
     }
 }
@@ -318,6 +447,7 @@ impl Display for ServerCommand {
             ServerCommand::GetMe(_) => write!(formatter, "{GET_ME}"),
             ServerCommand::GetClient(payload) => write!(formatter, "{GET_CLIENT}|{payload}"),
             ServerCommand::GetClients(_) => write!(formatter, "{GET_CLIENTS}"),
+            ServerCommand::ReloadConfig(_) => write!(formatter, "{RELOAD_CONFIG}"),
             ServerCommand::GetUser(payload) => write!(formatter, "{GET_USER}|{payload}"),
             ServerCommand::GetUsers(_) => write!(formatter, "{GET_USERS}"),
             ServerCommand::CreateUser(payload) => write!(formatter, "{CREATE_USER}|{payload}"),
@@ -355,6 +485,15 @@ impl Display for ServerCommand {
             ServerCommand::DeleteTopic(payload) => write!(formatter, "{DELETE_TOPIC}|{payload}"),
             ServerCommand::UpdateTopic(payload) => write!(formatter, "{UPDATE_TOPIC}|{payload}"),
             ServerCommand::PurgeTopic(payload) => write!(formatter, "{PURGE_TOPIC}|{payload}"),
+            ServerCommand::SetDeadLetterQueue(payload) => {
+                write!(formatter, "{SET_DEAD_LETTER_QUEUE}|{payload}")
+            }
+            ServerCommand::SetCleanupPolicy(payload) => {
+                write!(formatter, "{SET_CLEANUP_POLICY}|{payload}")
+            }
+            ServerCommand::SetMaxTopicMessages(payload) => {
+                write!(formatter, "{SET_MAX_TOPIC_MESSAGES}|{payload}")
+            }
             ServerCommand::CreatePartitions(payload) => {
                 write!(formatter, "{CREATE_PARTITIONS}|{payload}")
             }
@@ -387,9 +526,29 @@ impl Display for ServerCommand {
             ServerCommand::LeaveConsumerGroup(payload) => {
                 write!(formatter, "{LEAVE_CONSUMER_GROUP}|{payload}")
             }
+            ServerCommand::HeartbeatConsumerGroup(payload) => {
+                write!(formatter, "{HEARTBEAT_CONSUMER_GROUP}|{payload}")
+            }
             ServerCommand::FlushUnsavedBuffer(payload) => {
                 write!(formatter, "{FLUSH_UNSAVED_BUFFER}|{payload}")
             }
+            ServerCommand::NackMessage(payload) => write!(formatter, "{NACK_MESSAGE}|{payload}"),
+            ServerCommand::AckMessage(payload) => write!(formatter, "{ACK_MESSAGE}|{payload}"),
+            ServerCommand::InitProducer(_) => write!(formatter, "{INIT_PRODUCER}"),
+            ServerCommand::BeginTransaction(_) => write!(formatter, "{BEGIN_TRANSACTION}"),
+            ServerCommand::CommitTransaction(payload) => {
+                write!(formatter, "{COMMIT_TRANSACTION}|{payload}")
+            }
+            ServerCommand::AbortTransaction(payload) => {
+                write!(formatter, "{ABORT_TRANSACTION}|{payload}")
+            }
+            ServerCommand::GetClusterMetadata(_) => write!(formatter, "{GET_CLUSTER_METADATA}"),
+            ServerCommand::AddClusterNode(payload) => {
+                write!(formatter, "{ADD_CLUSTER_NODE}|{payload}")
+            }
+            ServerCommand::RemoveClusterNode(payload) => {
+                write!(formatter, "{REMOVE_CLUSTER_NODE}|{payload}")
+            }
         }
     }
 }
@@ -425,6 +584,11 @@ mod tests {
             GET_CLIENTS_CODE,
             &GetClients::default(),
         );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::ReloadConfig(ReloadConfig::default()),
+            RELOAD_CONFIG_CODE,
+            &ReloadConfig::default(),
+        );
         assert_serialized_as_bytes_and_deserialized_from_bytes(
             &ServerCommand::GetUser(GetUser::default()),
             GET_USER_CODE,
@@ -570,6 +734,21 @@ mod tests {
             PURGE_TOPIC_CODE,
             &PurgeTopic::default(),
         );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::SetDeadLetterQueue(SetDeadLetterQueue::default()),
+            SET_DEAD_LETTER_QUEUE_CODE,
+            &SetDeadLetterQueue::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::SetCleanupPolicy(SetCleanupPolicy::default()),
+            SET_CLEANUP_POLICY_CODE,
+            &SetCleanupPolicy::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::SetMaxTopicMessages(SetMaxTopicMessages::default()),
+            SET_MAX_TOPIC_MESSAGES_CODE,
+            &SetMaxTopicMessages::default(),
+        );
         assert_serialized_as_bytes_and_deserialized_from_bytes(
             &ServerCommand::CreatePartitions(CreatePartitions::default()),
             CREATE_PARTITIONS_CODE,
@@ -610,11 +789,61 @@ mod tests {
             LEAVE_CONSUMER_GROUP_CODE,
             &LeaveConsumerGroup::default(),
         );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::HeartbeatConsumerGroup(HeartbeatConsumerGroup::default()),
+            HEARTBEAT_CONSUMER_GROUP_CODE,
+            &HeartbeatConsumerGroup::default(),
+        );
         assert_serialized_as_bytes_and_deserialized_from_bytes(
             &ServerCommand::FlushUnsavedBuffer(FlushUnsavedBuffer::default()),
             FLUSH_UNSAVED_BUFFER_CODE,
             &FlushUnsavedBuffer::default(),
         );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::NackMessage(NackMessage::default()),
+            NACK_MESSAGE_CODE,
+            &NackMessage::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::AckMessage(AckMessage::default()),
+            ACK_MESSAGE_CODE,
+            &AckMessage::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::InitProducer(InitProducer::default()),
+            INIT_PRODUCER_CODE,
+            &InitProducer::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::BeginTransaction(BeginTransaction::default()),
+            BEGIN_TRANSACTION_CODE,
+            &BeginTransaction::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::CommitTransaction(CommitTransaction::default()),
+            COMMIT_TRANSACTION_CODE,
+            &CommitTransaction::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::AbortTransaction(AbortTransaction::default()),
+            ABORT_TRANSACTION_CODE,
+            &AbortTransaction::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::GetClusterMetadata(GetClusterMetadata::default()),
+            GET_CLUSTER_METADATA_CODE,
+            &GetClusterMetadata::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::AddClusterNode(AddClusterNode::default()),
+            ADD_CLUSTER_NODE_CODE,
+            &AddClusterNode::default(),
+        );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &ServerCommand::RemoveClusterNode(RemoveClusterNode::default()),
+            REMOVE_CLUSTER_NODE_CODE,
+            &RemoveClusterNode::default(),
+        );
     }
 
     fn assert_serialized_as_bytes_and_deserialized_from_bytes(
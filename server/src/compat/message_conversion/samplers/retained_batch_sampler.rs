@@ -41,7 +41,7 @@ impl BinarySchemaSampler for RetainedMessageBatchSampler {
             return Ok(BinarySchema::RetainedMessageBatchSchema);
         }
 
-        let _ = index_file.read_u32_le().await?;
+        let first_index_offset = index_file.read_u32_le().await?;
         let _ = index_file.read_u32_le().await?;
         let second_index_offset = index_file.read_u32_le().await;
         let second_end_position = index_file.read_u32_le().await;
@@ -55,7 +55,11 @@ impl BinarySchemaSampler for RetainedMessageBatchSampler {
             let _ = log_file.read_exact(&mut buffer).await?;
         }
         let batch = RetainedMessageBatchSnapshot::try_from(Bytes::from(buffer))?;
-        if batch.base_offset != self.segment_start_offset {
+        // The compacted segment might not start with the message at the segment start offset,
+        // yet the first batch still has to end with the offset of the first index.
+        if batch.base_offset < self.segment_start_offset
+            || batch.get_last_offset() != self.segment_start_offset + first_index_offset as u64
+        {
             return Err(ServerError::InvalidBatchBaseOffsetFormatConversion);
         }
         Ok(BinarySchema::RetainedMessageBatchSchema)
@@ -126,6 +126,7 @@ pub async fn convert(
                             topic_id: topic.topic_id.try_into()?,
                             group_id: Some(group.group_id),
                             name: group.name.to_owned(),
+                            mode: group.mode,
                         }),
                     )
                     .await?;
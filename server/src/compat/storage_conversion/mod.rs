@@ -6,20 +6,28 @@ use crate::configs::system::SystemConfig;
 use crate::state::system::{PartitionState, StreamState, TopicState};
 use crate::state::State;
 use crate::streaming::batching::message_batch::RetainedMessageBatch;
-use crate::streaming::partitions::partition::{ConsumerOffset, Partition};
+use crate::streaming::models::messages::FileRegion;
+use crate::streaming::partitions::partition::{
+    ConsumerGroupLeases, ConsumerOffset, DeadLetterChange, MessageLeaseChange, Partition,
+    ProducerSequence,
+};
 use crate::streaming::persistence::persister::Persister;
 use crate::streaming::segments::index::{Index, IndexRange};
 use crate::streaming::segments::segment::Segment;
 use crate::streaming::segments::time_index::TimeIndex;
+use crate::streaming::segments::verification::SegmentVerification;
 use crate::streaming::storage::{
-    PartitionStorage, SegmentStorage, StreamStorage, SystemInfoStorage, SystemStorage, TopicStorage,
+    PartitionStorage, SegmentStorage, StreamStorage, SystemInfoStorage, SystemStorage,
+    TopicStorage, TransactionLogStorage,
 };
 use crate::streaming::streams::stream::Stream;
 use crate::streaming::systems::info::SystemInfo;
+use crate::streaming::systems::transactions::TransactionLogEntry;
 use crate::streaming::topics::topic::Topic;
 use async_trait::async_trait;
 use iggy::consumer::ConsumerKind;
 use iggy::error::IggyError;
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::fs::{read_dir, rename};
@@ -68,11 +76,13 @@ pub async fn init(
 
     let noop_storage = SystemStorage {
         info: Arc::new(NoopSystemInfoStorage {}),
+        transactions: Arc::new(NoopTransactionLogStorage {}),
         stream: Arc::new(NoopStreamStorage {}),
         topic: Arc::new(NoopTopicStorage {}),
         partition: Arc::new(NoopPartitionStorage {}),
         segment: Arc::new(NoopSegmentStorage {}),
         persister: Arc::new(NoopPersister {}),
+        tiered: None,
     };
     let noop_storage = Arc::new(noop_storage);
     let mut dir_entries = dir_entries.unwrap();
@@ -101,6 +111,7 @@ pub async fn init(
 
 struct NoopPersister {}
 struct NoopSystemInfoStorage {}
+struct NoopTransactionLogStorage {}
 struct NoopStreamStorage {}
 struct NoopTopicStorage {}
 struct NoopPartitionStorage {}
@@ -132,6 +143,21 @@ impl SystemInfoStorage for NoopSystemInfoStorage {
     }
 }
 
+#[async_trait]
+impl TransactionLogStorage for NoopTransactionLogStorage {
+    async fn load(&self) -> Result<Vec<TransactionLogEntry>, IggyError> {
+        Ok(Vec::new())
+    }
+
+    async fn save(&self, _entries: &[TransactionLogEntry]) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn append(&self, _entry: &TransactionLogEntry) -> Result<(), IggyError> {
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl StreamStorage for NoopStreamStorage {
     async fn load(&self, _stream: &mut Stream, _state: StreamState) -> Result<(), IggyError> {
@@ -199,6 +225,92 @@ impl PartitionStorage for NoopPartitionStorage {
     async fn delete_consumer_offset(&self, _path: &str) -> Result<(), IggyError> {
         Ok(())
     }
+
+    async fn save_consumer_group_leases(
+        &self,
+        _leases: &ConsumerGroupLeases,
+    ) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn append_consumer_group_lease_changes(
+        &self,
+        _path: &str,
+        _changes: &[MessageLeaseChange],
+    ) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn load_consumer_group_leases(
+        &self,
+        _path: &str,
+    ) -> Result<Vec<ConsumerGroupLeases>, IggyError> {
+        Ok(vec![])
+    }
+
+    async fn save_dead_letters(&self, _partition: &Partition) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn append_dead_letter_changes(
+        &self,
+        _path: &str,
+        _changes: &[DeadLetterChange],
+    ) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn save_producer_sequences(
+        &self,
+        _path: &str,
+        _sequences: &HashMap<u64, u64>,
+        _persisted_offset: u64,
+    ) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn append_producer_sequences(
+        &self,
+        _path: &str,
+        _sequences: &HashMap<u64, u64>,
+        _persisted_offset: u64,
+    ) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn load_producer_sequences(
+        &self,
+        _path: &str,
+    ) -> Result<Option<Vec<ProducerSequence>>, IggyError> {
+        Ok(None)
+    }
+
+    async fn save_committed_transactions(
+        &self,
+        _path: &str,
+        _transactions: &HashMap<u64, u64>,
+    ) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn append_committed_transactions(
+        &self,
+        _path: &str,
+        _transactions: &HashMap<u64, u64>,
+    ) -> Result<(), IggyError> {
+        Ok(())
+    }
+
+    async fn load_committed_transactions(
+        &self,
+        _path: &str,
+    ) -> Result<Option<HashMap<u64, u64>>, IggyError> {
+        Ok(None)
+    }
+
+    async fn load_dead_letters(&self, _partition: &mut Partition) -> Result<(), IggyError> {
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -223,6 +335,14 @@ impl SegmentStorage for NoopSegmentStorage {
         Ok(vec![])
     }
 
+    async fn load_batches_region(
+        &self,
+        _segment: &Segment,
+        _index_range: &IndexRange,
+    ) -> Result<Option<FileRegion>, IggyError> {
+        Ok(None)
+    }
+
     async fn load_newest_batches_by_size(
         &self,
         _segment: &Segment,
@@ -239,14 +359,52 @@ impl SegmentStorage for NoopSegmentStorage {
         Ok(0)
     }
 
+    async fn rewrite_batches(
+        &self,
+        _segment: &Segment,
+        _batches: Vec<RetainedMessageBatch>,
+    ) -> Result<(Vec<Index>, Vec<TimeIndex>), IggyError> {
+        Ok((vec![], vec![]))
+    }
+
+    async fn offload(&self, _segment: &Segment) -> Result<(), IggyError> {
+        Ok(())
+    }
+
     async fn load_message_ids(&self, _segment: &Segment) -> Result<Vec<u128>, IggyError> {
         Ok(vec![])
     }
 
+    async fn load_committed_transactions(
+        &self,
+        _segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError> {
+        Ok(HashMap::new())
+    }
+
+    async fn load_producer_sequences(
+        &self,
+        _segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError> {
+        Ok(HashMap::new())
+    }
+
     async fn load_checksums(&self, _segment: &Segment) -> Result<(), IggyError> {
         Ok(())
     }
 
+    async fn verify(&self, _segment: &Segment) -> Result<SegmentVerification, IggyError> {
+        Ok(SegmentVerification::default())
+    }
+
+    async fn repair(
+        &self,
+        _segment: &Segment,
+        _verification: &SegmentVerification,
+    ) -> Result<(), IggyError> {
+        Ok(())
+    }
+
     async fn load_all_indexes(&self, _segment: &Segment) -> Result<Vec<Index>, IggyError> {
         Ok(vec![])
     }
@@ -181,6 +181,7 @@ pub async fn load(
             partition.config.clone(),
             partition.storage.clone(),
             partition.message_expiry,
+            partition.compression_algorithm,
             partition.size_of_parent_stream.clone(),
             partition.size_of_parent_topic.clone(),
             partition.size_bytes.clone(),
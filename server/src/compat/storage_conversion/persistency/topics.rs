@@ -5,6 +5,7 @@ use crate::streaming::topics::consumer_group::ConsumerGroup;
 use crate::streaming::topics::topic::Topic;
 use anyhow::Context;
 use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::error::IggyError;
 use iggy::locking::IggySharedMut;
 use iggy::locking::IggySharedMutFn;
@@ -96,6 +97,7 @@ pub async fn load(config: &SystemConfig, db: &Db, topic: &mut Topic) -> Result<(
             topic.config.clone(),
             topic.storage.clone(),
             topic.message_expiry,
+            topic.compression_algorithm,
             topic.messages_count_of_parent_stream.clone(),
             topic.messages_count.clone(),
             topic.size_of_parent_stream.clone(),
@@ -151,6 +153,7 @@ pub async fn load_consumer_groups(db: &Db, topic: &Topic) -> Result<Vec<Consumer
             consumer_group.id,
             &consumer_group.name,
             topic.get_partitions_count(),
+            ConsumerGroupMode::Offset,
         );
         consumer_groups.push(consumer_group);
     }
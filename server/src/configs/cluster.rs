@@ -0,0 +1,66 @@
+use iggy::utils::duration::IggyDuration;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use serde_with::DisplayFromStr;
+
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ClusterConfig {
+    pub enabled: bool,
+    pub node_id: u32,
+    pub address: String,
+    pub nodes: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub election_timeout: IggyDuration,
+    #[serde_as(as = "DisplayFromStr")]
+    pub heartbeat_interval: IggyDuration,
+    #[serde_as(as = "DisplayFromStr")]
+    pub commit_timeout: IggyDuration,
+    pub replication: ClusterReplicationConfig,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ClusterReplicationConfig {
+    #[serde_as(as = "DisplayFromStr")]
+    pub fetch_interval: IggyDuration,
+    pub fetch_max_messages: u32,
+    #[serde_as(as = "DisplayFromStr")]
+    pub replica_lag_timeout: IggyDuration,
+    #[serde_as(as = "DisplayFromStr")]
+    pub acks_timeout: IggyDuration,
+}
+
+impl ClusterConfig {
+    /// Parses the comma-separated `<node_id>=<address>` entries of the initial cluster members.
+    pub fn get_nodes(&self) -> Result<Vec<(u32, String)>, String> {
+        let mut nodes: Vec<(u32, String)> = Vec::new();
+        for entry in self.nodes.split(',').map(|entry| entry.trim()) {
+            if entry.is_empty() {
+                continue;
+            }
+
+            let Some((node_id, address)) = entry.split_once('=') else {
+                return Err(format!(
+                    "Invalid cluster node: {entry}, expected: <node_id>=<address>."
+                ));
+            };
+            let Ok(node_id) = node_id.trim().parse::<u32>() else {
+                return Err(format!("Invalid cluster node ID: {node_id}."));
+            };
+            if node_id == 0 {
+                return Err("Cluster node ID cannot be 0.".into());
+            }
+
+            if nodes.iter().any(|(id, _)| *id == node_id) {
+                return Err(format!(
+                    "Cluster node with ID: {node_id} is defined more than once."
+                ));
+            }
+
+            nodes.push((node_id, address.trim().to_string()));
+        }
+
+        Ok(nodes)
+    }
+}
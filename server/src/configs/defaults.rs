@@ -1,3 +1,4 @@
+use crate::configs::cluster::{ClusterConfig, ClusterReplicationConfig};
 use crate::configs::http::{
     HttpConfig, HttpCorsConfig, HttpJwtConfig, HttpMetricsConfig, HttpTlsConfig,
 };
@@ -6,12 +7,13 @@ use crate::configs::server::{
     ArchiverConfig, DataMaintenanceConfig, HeartbeatConfig, MessageSaverConfig,
     MessagesMaintenanceConfig, PersonalAccessTokenCleanerConfig, PersonalAccessTokenConfig,
     ServerConfig, StateMaintenanceConfig, TelemetryConfig, TelemetryLogsConfig,
-    TelemetryTracesConfig,
+    TelemetryTracesConfig, TieredStorageConfig,
 };
 use crate::configs::system::{
-    BackupConfig, CacheConfig, CompatibilityConfig, CompressionConfig, EncryptionConfig,
-    LoggingConfig, MessageDeduplicationConfig, PartitionConfig, RecoveryConfig, RuntimeConfig,
-    SegmentConfig, StateConfig, StreamConfig, SystemConfig, TopicConfig,
+    BackupConfig, CacheConfig, CompatibilityConfig, CompressionConfig, ConsumerGroupConfig,
+    EncryptionConfig, IdempotenceConfig, LoggingConfig, MessageDeduplicationConfig,
+    PartitionConfig, RecoveryConfig, ReencryptionConfig, RuntimeConfig, SegmentConfig, StateConfig,
+    StorageConfig, StreamConfig, SystemConfig, TopicConfig, TransactionConfig,
 };
 use crate::configs::tcp::{TcpConfig, TcpTlsConfig};
 use std::sync::Arc;
@@ -32,6 +34,7 @@ impl Default for ServerConfig {
             quic: QuicConfig::default(),
             tcp: TcpConfig::default(),
             http: HttpConfig::default(),
+            cluster: ClusterConfig::default(),
             telemetry: TelemetryConfig::default(),
         }
     }
@@ -68,6 +71,20 @@ impl Default for MessagesMaintenanceConfig {
     }
 }
 
+impl Default for TieredStorageConfig {
+    fn default() -> TieredStorageConfig {
+        TieredStorageConfig {
+            enabled: SERVER_CONFIG.data_maintenance.tiered_storage.enabled,
+            cache_size: SERVER_CONFIG
+                .data_maintenance
+                .tiered_storage
+                .cache_size
+                .parse()
+                .unwrap(),
+        }
+    }
+}
+
 impl Default for StateMaintenanceConfig {
     fn default() -> StateMaintenanceConfig {
         StateMaintenanceConfig {
@@ -79,6 +96,14 @@ impl Default for StateMaintenanceConfig {
                 .interval
                 .parse()
                 .unwrap(),
+            snapshot_enabled: SERVER_CONFIG.data_maintenance.state.snapshot_enabled,
+            snapshot_interval: SERVER_CONFIG
+                .data_maintenance
+                .state
+                .snapshot_interval
+                .parse()
+                .unwrap(),
+            snapshot_min_entries: SERVER_CONFIG.data_maintenance.state.snapshot_min_entries as u64,
         }
     }
 }
@@ -124,6 +149,47 @@ impl Default for TcpConfig {
     }
 }
 
+impl Default for ClusterConfig {
+    fn default() -> ClusterConfig {
+        ClusterConfig {
+            enabled: SERVER_CONFIG.cluster.enabled,
+            node_id: SERVER_CONFIG.cluster.node_id as u32,
+            address: SERVER_CONFIG.cluster.address.parse().unwrap(),
+            nodes: SERVER_CONFIG.cluster.nodes.parse().unwrap(),
+            election_timeout: SERVER_CONFIG.cluster.election_timeout.parse().unwrap(),
+            heartbeat_interval: SERVER_CONFIG.cluster.heartbeat_interval.parse().unwrap(),
+            commit_timeout: SERVER_CONFIG.cluster.commit_timeout.parse().unwrap(),
+            replication: ClusterReplicationConfig::default(),
+        }
+    }
+}
+
+impl Default for ClusterReplicationConfig {
+    fn default() -> ClusterReplicationConfig {
+        ClusterReplicationConfig {
+            fetch_interval: SERVER_CONFIG
+                .cluster
+                .replication
+                .fetch_interval
+                .parse()
+                .unwrap(),
+            fetch_max_messages: SERVER_CONFIG.cluster.replication.fetch_max_messages as u32,
+            replica_lag_timeout: SERVER_CONFIG
+                .cluster
+                .replication
+                .replica_lag_timeout
+                .parse()
+                .unwrap(),
+            acks_timeout: SERVER_CONFIG
+                .cluster
+                .replication
+                .acks_timeout
+                .parse()
+                .unwrap(),
+        }
+    }
+}
+
 impl Default for TcpTlsConfig {
     fn default() -> TcpTlsConfig {
         TcpTlsConfig {
@@ -283,8 +349,12 @@ impl Default for SystemConfig {
             partition: PartitionConfig::default(),
             segment: SegmentConfig::default(),
             state: StateConfig::default(),
+            storage: StorageConfig::default(),
             compression: CompressionConfig::default(),
             message_deduplication: MessageDeduplicationConfig::default(),
+            consumer_group: ConsumerGroupConfig::default(),
+            transaction: TransactionConfig::default(),
+            idempotence: IdempotenceConfig::default(),
             recovery: RecoveryConfig::default(),
         }
     }
@@ -318,6 +388,11 @@ impl Default for HeartbeatConfig {
         HeartbeatConfig {
             enabled: SERVER_CONFIG.heartbeat.enabled,
             interval: SERVER_CONFIG.heartbeat.interval.parse().unwrap(),
+            consumer_group_session_timeout: SERVER_CONFIG
+                .heartbeat
+                .consumer_group_session_timeout
+                .parse()
+                .unwrap(),
         }
     }
 }
@@ -375,6 +450,31 @@ impl Default for EncryptionConfig {
         EncryptionConfig {
             enabled: SERVER_CONFIG.system.encryption.enabled,
             key: SERVER_CONFIG.system.encryption.key.parse().unwrap(),
+            active_key_id: SERVER_CONFIG.system.encryption.active_key_id as u32,
+            keys: SERVER_CONFIG.system.encryption.keys.parse().unwrap(),
+            keyring_file: SERVER_CONFIG
+                .system
+                .encryption
+                .keyring_file
+                .parse()
+                .unwrap(),
+            topic_keys: SERVER_CONFIG.system.encryption.topic_keys.parse().unwrap(),
+            reencryption: ReencryptionConfig::default(),
+        }
+    }
+}
+
+impl Default for ReencryptionConfig {
+    fn default() -> ReencryptionConfig {
+        ReencryptionConfig {
+            enabled: SERVER_CONFIG.system.encryption.reencryption.enabled,
+            interval: SERVER_CONFIG
+                .system
+                .encryption
+                .reencryption
+                .interval
+                .parse()
+                .unwrap(),
         }
     }
 }
@@ -416,6 +516,7 @@ impl Default for SegmentConfig {
             cache_indexes: SERVER_CONFIG.system.segment.cache_indexes,
             cache_time_indexes: SERVER_CONFIG.system.segment.cache_time_indexes,
             message_expiry: SERVER_CONFIG.system.segment.message_expiry.parse().unwrap(),
+            max_age: SERVER_CONFIG.system.segment.max_age.parse().unwrap(),
             archive_expired: SERVER_CONFIG.system.segment.archive_expired,
         }
     }
@@ -429,6 +530,44 @@ impl Default for StateConfig {
     }
 }
 
+impl Default for StorageConfig {
+    fn default() -> StorageConfig {
+        StorageConfig {
+            backend: SERVER_CONFIG.system.storage.backend.parse().unwrap(),
+        }
+    }
+}
+
+impl Default for ConsumerGroupConfig {
+    fn default() -> ConsumerGroupConfig {
+        ConsumerGroupConfig {
+            static_member_grace_period: SERVER_CONFIG
+                .system
+                .consumer_group
+                .static_member_grace_period
+                .parse()
+                .unwrap(),
+        }
+    }
+}
+
+impl Default for TransactionConfig {
+    fn default() -> TransactionConfig {
+        TransactionConfig {
+            enabled: SERVER_CONFIG.system.transaction.enabled,
+            timeout: SERVER_CONFIG.system.transaction.timeout.parse().unwrap(),
+        }
+    }
+}
+
+impl Default for IdempotenceConfig {
+    fn default() -> IdempotenceConfig {
+        IdempotenceConfig {
+            enabled: SERVER_CONFIG.system.idempotence.enabled,
+        }
+    }
+}
+
 impl Default for MessageDeduplicationConfig {
     fn default() -> MessageDeduplicationConfig {
         MessageDeduplicationConfig {
@@ -448,6 +587,12 @@ impl Default for RecoveryConfig {
     fn default() -> RecoveryConfig {
         RecoveryConfig {
             recreate_missing_state: SERVER_CONFIG.system.recovery.recreate_missing_state,
+            inconsistent_indexes: SERVER_CONFIG
+                .system
+                .recovery
+                .inconsistent_indexes
+                .parse()
+                .unwrap(),
         }
     }
 }
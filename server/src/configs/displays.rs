@@ -1,17 +1,21 @@
+use crate::configs::cluster::{ClusterConfig, ClusterReplicationConfig};
 use crate::configs::quic::{QuicCertificateConfig, QuicConfig};
 use crate::configs::server::{
     ArchiverConfig, DataMaintenanceConfig, DiskArchiverConfig, HeartbeatConfig,
     MessagesMaintenanceConfig, S3ArchiverConfig, StateMaintenanceConfig, TelemetryConfig,
-    TelemetryLogsConfig, TelemetryTracesConfig,
+    TelemetryLogsConfig, TelemetryTracesConfig, TieredStorageConfig,
+};
+use crate::configs::system::{
+    ConsumerGroupConfig, IdempotenceConfig, MessageDeduplicationConfig, StorageConfig,
+    TransactionConfig,
 };
-use crate::configs::system::MessageDeduplicationConfig;
 use crate::configs::{
     http::{HttpConfig, HttpCorsConfig, HttpJwtConfig, HttpMetricsConfig, HttpTlsConfig},
     resource_quota::MemoryResourceQuota,
     server::{MessageSaverConfig, ServerConfig},
     system::{
         CacheConfig, CompressionConfig, EncryptionConfig, LoggingConfig, PartitionConfig,
-        SegmentConfig, StreamConfig, SystemConfig, TopicConfig,
+        ReencryptionConfig, SegmentConfig, StreamConfig, SystemConfig, TopicConfig,
     },
     tcp::{TcpConfig, TcpTlsConfig},
 };
@@ -119,8 +123,8 @@ impl Display for DataMaintenanceConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{ archiver: {}, messages: {}, state: {} }}",
-            self.archiver, self.messages, self.state
+            "{{ archiver: {}, messages: {}, state: {}, tiered_storage: {} }}",
+            self.archiver, self.messages, self.state, self.tiered_storage
         )
     }
 }
@@ -172,12 +176,27 @@ impl Display for MessagesMaintenanceConfig {
     }
 }
 
+impl Display for TieredStorageConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ enabled: {}, cache_size: {} }}",
+            self.enabled, self.cache_size
+        )
+    }
+}
+
 impl Display for StateMaintenanceConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{ archiver_enabled: {}, overwrite: {}, interval: {} }}",
-            self.archiver_enabled, self.overwrite, self.interval
+            "{{ archiver_enabled: {}, overwrite: {}, interval: {}, snapshot_enabled: {}, snapshot_interval: {}, snapshot_min_entries: {} }}",
+            self.archiver_enabled,
+            self.overwrite,
+            self.interval,
+            self.snapshot_enabled,
+            self.snapshot_interval,
+            self.snapshot_min_entries
         )
     }
 }
@@ -186,8 +205,8 @@ impl Display for ServerConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{ data_maintenance: {}, message_saver: {}, heartbeat: {}, system: {}, quic: {}, tcp: {}, http: {}, telemetry: {} }}",
-            self.data_maintenance, self.message_saver, self.heartbeat, self.system, self.quic, self.tcp, self.http, self.telemetry
+            "{{ data_maintenance: {}, message_saver: {}, heartbeat: {}, system: {}, quic: {}, tcp: {}, http: {}, cluster: {}, telemetry: {} }}",
+            self.data_maintenance, self.message_saver, self.heartbeat, self.system, self.quic, self.tcp, self.http, self.cluster, self.telemetry
         )
     }
 }
@@ -212,15 +231,29 @@ impl Display for HeartbeatConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{ enabled: {}, interval: {} }}",
-            self.enabled, self.interval
+            "{{ enabled: {}, interval: {}, consumer_group_session_timeout: {} }}",
+            self.enabled, self.interval, self.consumer_group_session_timeout
         )
     }
 }
 
 impl Display for EncryptionConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{{ enabled: {} }}", self.enabled)
+        write!(
+            f,
+            "{{ enabled: {}, active_key_id: {}, keyring_file: {}, topic_keys: {}, reencryption: {} }}",
+            self.enabled, self.active_key_id, self.keyring_file, self.topic_keys, self.reencryption
+        )
+    }
+}
+
+impl Display for ReencryptionConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ enabled: {}, interval: {} }}",
+            self.enabled, self.interval
+        )
     }
 }
 
@@ -263,16 +296,48 @@ impl Display for MessageDeduplicationConfig {
     }
 }
 
+impl Display for TransactionConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ enabled: {}, timeout: {} }}",
+            self.enabled, self.timeout
+        )
+    }
+}
+
+impl Display for IdempotenceConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{ enabled: {} }}", self.enabled)
+    }
+}
+
+impl Display for ConsumerGroupConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ static_member_grace_period: {} }}",
+            self.static_member_grace_period
+        )
+    }
+}
+
 impl Display for SegmentConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{ size_bytes: {}, cache_indexes: {}, cache_time_indexes: {}, message_expiry: {}, archive_expired: {} }}",
-            self.size, self.cache_indexes, self.cache_time_indexes, self.message_expiry, self.archive_expired
+            "{{ size_bytes: {}, cache_indexes: {}, cache_time_indexes: {}, message_expiry: {}, max_age: {}, archive_expired: {} }}",
+            self.size, self.cache_indexes, self.cache_time_indexes, self.message_expiry, self.max_age, self.archive_expired
         )
     }
 }
 
+impl Display for StorageConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{ backend: {} }}", self.backend)
+    }
+}
+
 impl Display for LoggingConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -306,6 +371,36 @@ impl Display for TcpTlsConfig {
     }
 }
 
+impl Display for ClusterConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ enabled: {}, node_id: {}, address: {}, nodes: {}, election_timeout: {}, heartbeat_interval: {}, commit_timeout: {}, replication: {} }}",
+            self.enabled,
+            self.node_id,
+            self.address,
+            self.nodes,
+            self.election_timeout,
+            self.heartbeat_interval,
+            self.commit_timeout,
+            self.replication
+        )
+    }
+}
+
+impl Display for ClusterReplicationConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ fetch_interval: {}, fetch_max_messages: {}, replica_lag_timeout: {}, acks_timeout: {} }}",
+            self.fetch_interval,
+            self.fetch_max_messages,
+            self.replica_lag_timeout,
+            self.acks_timeout
+        )
+    }
+}
+
 impl Display for TelemetryConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -340,15 +435,19 @@ impl Display for SystemConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
           f,
-          "{{ path: {}, logging: {}, cache: {}, stream: {}, topic: {}, partition: {}, segment: {}, encryption: {} }}",
+          "{{ path: {}, storage: {}, logging: {}, cache: {}, stream: {}, topic: {}, partition: {}, segment: {}, encryption: {}, consumer_group: {}, transaction: {}, idempotence: {} }}",
           self.path,
+          self.storage,
           self.logging,
           self.cache,
           self.stream,
           self.topic,
           self.partition,
           self.segment,
-          self.encryption
+          self.encryption,
+          self.consumer_group,
+          self.transaction,
+          self.idempotence
       )
     }
 }
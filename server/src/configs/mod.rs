@@ -1,6 +1,7 @@
 pub mod server;
 pub mod system;
 
+pub mod cluster;
 pub mod http;
 pub mod quic;
 pub mod tcp;
@@ -8,5 +9,6 @@ pub mod tcp;
 pub mod config_provider;
 pub mod defaults;
 pub mod displays;
+pub mod reloader;
 pub mod resource_quota;
 pub mod validators;
@@ -0,0 +1,335 @@
+use crate::configs::config_provider::ConfigProvider;
+use crate::configs::http::HttpCorsConfig;
+use crate::configs::server::{HeartbeatConfig, ServerConfig};
+use crate::server_error::ServerError;
+use iggy::utils::duration::IggyDuration;
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Display, Formatter};
+use tokio::sync::{watch, Mutex};
+use toml::Value as TomlValue;
+use tracing::{info, warn};
+
+/// The settings (or the whole sections) which can be changed without restarting the server.
+/// All the other settings are static, and their changes are only applied after the restart.
+const RELOADABLE_SETTINGS: [&str; 6] = [
+    "message_saver.interval",
+    "system.logging.level",
+    "http.cors",
+    "heartbeat",
+    "data_maintenance.messages.interval",
+    "personal_access_token.cleaner.interval",
+];
+
+/// The server default retention settings are static as well, and are reported separately on reload,
+/// as they're resolved into each topic when it's created, so even after the restart they only apply to the new topics.
+const RETENTION_SETTINGS: [&str; 2] = ["system.segment.message_expiry", "system.topic.max_size"];
+
+/// The reloadable part of the server configuration, see `RELOADABLE_SETTINGS`.
+#[derive(Debug, Clone)]
+pub struct ReloadableConfig {
+    pub message_saver_interval: IggyDuration,
+    pub logging_level: String,
+    pub http_cors: HttpCorsConfig,
+    pub heartbeat: HeartbeatConfig,
+    pub messages_maintenance_interval: IggyDuration,
+    pub personal_access_token_cleaner_interval: IggyDuration,
+}
+
+impl ReloadableConfig {
+    pub fn is_reloadable(setting: &str) -> bool {
+        RELOADABLE_SETTINGS.iter().any(|reloadable| {
+            setting == *reloadable
+                || setting
+                    .strip_prefix(reloadable)
+                    .is_some_and(|nested| nested.starts_with('.'))
+        })
+    }
+}
+
+impl ConfigChange {
+    pub fn is_retention(&self) -> bool {
+        RETENTION_SETTINGS.contains(&self.setting.as_str())
+    }
+}
+
+impl From<&ServerConfig> for ReloadableConfig {
+    fn from(config: &ServerConfig) -> Self {
+        Self {
+            message_saver_interval: config.message_saver.interval,
+            logging_level: config.system.logging.level.clone(),
+            http_cors: config.http.cors.clone(),
+            heartbeat: config.heartbeat.clone(),
+            messages_maintenance_interval: config.data_maintenance.messages.interval,
+            personal_access_token_cleaner_interval: config.personal_access_token.cleaner.interval,
+        }
+    }
+}
+
+/// The change of a single setting, identified by its path e.g. `message_saver.interval`.
+/// The missing value means that the optional setting has been added or removed.
+#[derive(Debug, PartialEq)]
+pub struct ConfigChange {
+    pub setting: String,
+    pub previous: Option<TomlValue>,
+    pub current: Option<TomlValue>,
+}
+
+impl ConfigChange {
+    /// Returns the changes of all the settings between the two configurations.
+    pub fn diff(
+        previous: &ServerConfig,
+        current: &ServerConfig,
+    ) -> Result<Vec<ConfigChange>, ServerError> {
+        let previous = Self::to_toml(previous)?;
+        let current = Self::to_toml(current)?;
+        let mut changes = Vec::new();
+        Self::diff_values("", &previous, &current, &mut changes);
+        Ok(changes)
+    }
+
+    fn to_toml(config: &ServerConfig) -> Result<TomlValue, ServerError> {
+        TomlValue::try_from(config).map_err(|error| {
+            ServerError::CannotLoadConfiguration(format!(
+                "Cannot serialize the configuration: {error}"
+            ))
+        })
+    }
+
+    fn diff_values(
+        setting: &str,
+        previous: &TomlValue,
+        current: &TomlValue,
+        changes: &mut Vec<ConfigChange>,
+    ) {
+        let (TomlValue::Table(previous), TomlValue::Table(current)) = (previous, current) else {
+            if previous != current {
+                changes.push(ConfigChange {
+                    setting: setting.to_string(),
+                    previous: Some(previous.clone()),
+                    current: Some(current.clone()),
+                });
+            }
+            return;
+        };
+
+        let keys = previous
+            .keys()
+            .chain(current.keys())
+            .collect::<BTreeSet<_>>();
+        for key in keys {
+            let nested_setting = if setting.is_empty() {
+                key.to_string()
+            } else {
+                format!("{setting}.{key}")
+            };
+            match (previous.get(key), current.get(key)) {
+                (Some(previous), Some(current)) => {
+                    Self::diff_values(&nested_setting, previous, current, changes)
+                }
+                (previous, current) => changes.push(ConfigChange {
+                    setting: nested_setting,
+                    previous: previous.cloned(),
+                    current: current.cloned(),
+                }),
+            }
+        }
+    }
+}
+
+impl Display for ConfigChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let format = |value: &Option<TomlValue>| match value {
+            Some(value) => value.to_string(),
+            None => "none".to_string(),
+        };
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.setting,
+            format(&self.previous),
+            format(&self.current)
+        )
+    }
+}
+
+/// Subscription to the changes of the reloadable configuration.
+/// If there's no reloader attached to the system, the changes are never received.
+pub struct ConfigSubscription {
+    receiver: Option<watch::Receiver<ReloadableConfig>>,
+}
+
+impl ConfigSubscription {
+    pub fn new(receiver: Option<watch::Receiver<ReloadableConfig>>) -> Self {
+        Self { receiver }
+    }
+
+    /// Waits for the next change of the reloadable configuration.
+    pub async fn changed(&mut self) -> ReloadableConfig {
+        if let Some(receiver) = self.receiver.as_mut() {
+            if receiver.changed().await.is_ok() {
+                return receiver.borrow_and_update().clone();
+            }
+        }
+
+        std::future::pending().await
+    }
+}
+
+/// Reloads the server configuration from its provider on demand (SIGHUP or the admin command).
+/// The new configuration is validated as a whole, then only the reloadable settings are applied
+/// and broadcast to the subscribers, while the changes of the static ones are reported as ignored.
+pub struct ConfigReloader {
+    provider: Box<dyn ConfigProvider>,
+    /// The configuration the server has been started with, the static settings are compared against it.
+    startup_config: ServerConfig,
+    /// The most recently reloaded configuration, the reloadable settings are compared against it.
+    config: Mutex<ServerConfig>,
+    sender: watch::Sender<ReloadableConfig>,
+}
+
+impl ConfigReloader {
+    pub fn new(provider: Box<dyn ConfigProvider>, config: ServerConfig) -> Self {
+        let (sender, _) = watch::channel(ReloadableConfig::from(&config));
+        Self {
+            provider,
+            startup_config: config.clone(),
+            config: Mutex::new(config),
+            sender,
+        }
+    }
+
+    pub fn subscribe(&self) -> ConfigSubscription {
+        ConfigSubscription::new(Some(self.sender.subscribe()))
+    }
+
+    /// Reloads the configuration and returns the changes of the reloadable settings that have been applied.
+    pub async fn reload(&self) -> Result<Vec<ConfigChange>, ServerError> {
+        let mut config = self.config.lock().await;
+        let reloaded_config = ServerConfig::load(self.provider.as_ref()).await?;
+        let applied_changes = ConfigChange::diff(&config, &reloaded_config)?
+            .into_iter()
+            .filter(|change| ReloadableConfig::is_reloadable(&change.setting))
+            .collect::<Vec<_>>();
+        let (retention_changes, ignored_changes): (Vec<_>, Vec<_>) =
+            ConfigChange::diff(&self.startup_config, &reloaded_config)?
+                .into_iter()
+                .filter(|change| !ReloadableConfig::is_reloadable(&change.setting))
+                .partition(|change| change.is_retention());
+
+        if !retention_changes.is_empty() {
+            warn!(
+                "Server configuration changes of the retention settings: {} require the restart and have not been applied. \
+                After the restart, they apply only to the topics created with the server default retention, \
+                the existing topics keep their own, which can be changed by updating the topic.",
+                retention_changes
+                    .iter()
+                    .map(|change| change.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        // The values of the static settings are not logged, as they might contain the secrets.
+        if !ignored_changes.is_empty() {
+            warn!(
+                "Server configuration changes of: {} require the restart and have not been applied.",
+                ignored_changes
+                    .iter()
+                    .map(|change| change.setting.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        if applied_changes.is_empty() {
+            info!("Server configuration has been reloaded, no reloadable settings have changed.");
+            return Ok(applied_changes);
+        }
+
+        self.sender
+            .send_replace(ReloadableConfig::from(&reloaded_config));
+        *config = reloaded_config;
+        info!(
+            "Server configuration has been reloaded, applied changes: {}.",
+            applied_changes
+                .iter()
+                .map(|change| change.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        Ok(applied_changes)
+    }
+}
+
+impl Debug for ConfigReloader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigReloader")
+            .field("config", &self.sender.borrow())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::SystemConfig;
+    use iggy::utils::byte_size::IggyByteSize;
+    use iggy::utils::expiry::IggyExpiry;
+    use iggy::utils::topic_size::MaxTopicSize;
+    use std::sync::Arc;
+
+    #[test]
+    fn should_detect_reloadable_settings() {
+        assert!(ReloadableConfig::is_reloadable("message_saver.interval"));
+        assert!(ReloadableConfig::is_reloadable("http.cors.allowed_origins"));
+        assert!(ReloadableConfig::is_reloadable("heartbeat.enabled"));
+        assert!(!ReloadableConfig::is_reloadable("message_saver.enabled"));
+        assert!(!ReloadableConfig::is_reloadable("http.cors_enabled"));
+        assert!(!ReloadableConfig::is_reloadable("system.logging.path"));
+        assert!(!ReloadableConfig::is_reloadable(
+            "system.segment.message_expiry"
+        ));
+        assert!(!ReloadableConfig::is_reloadable("system.topic.max_size"));
+    }
+
+    #[test]
+    fn should_detect_retention_changes() {
+        let previous = ServerConfig::default();
+        let mut system = SystemConfig::default();
+        system.segment.message_expiry = IggyExpiry::ExpireDuration(IggyDuration::from(60_000_000));
+        system.topic.max_size = MaxTopicSize::Custom(IggyByteSize::from(1_000_000_000));
+        system.topic.delete_oldest_segments = !system.topic.delete_oldest_segments;
+        let current = ServerConfig {
+            system: Arc::new(system),
+            ..ServerConfig::default()
+        };
+
+        let changes = ConfigChange::diff(&previous, &current).unwrap();
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].setting, "system.segment.message_expiry");
+        assert!(changes[0].is_retention());
+        assert_eq!(changes[1].setting, "system.topic.delete_oldest_segments");
+        assert!(!changes[1].is_retention());
+        assert_eq!(changes[2].setting, "system.topic.max_size");
+        assert!(changes[2].is_retention());
+    }
+
+    #[test]
+    fn should_diff_changed_settings() {
+        let previous = ServerConfig::default();
+        let mut current = ServerConfig::default();
+        current.message_saver.interval = IggyDuration::from(1_000_000);
+        current.tcp.address = "127.0.0.1:9090".to_string();
+
+        let changes = ConfigChange::diff(&previous, &current).unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].setting, "message_saver.interval");
+        assert_eq!(changes[1].setting, "tcp.address");
+        assert_eq!(
+            changes[1].current,
+            Some(TomlValue::String("127.0.0.1:9090".to_string()))
+        );
+    }
+}
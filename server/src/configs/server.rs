@@ -1,4 +1,5 @@
 use crate::archiver::ArchiverKind;
+use crate::configs::cluster::ClusterConfig;
 use crate::configs::config_provider::ConfigProvider;
 use crate::configs::http::HttpConfig;
 use crate::configs::quic::QuicConfig;
@@ -6,6 +7,7 @@ use crate::configs::system::SystemConfig;
 use crate::configs::tcp::TcpConfig;
 use crate::server_error::ServerError;
 use derive_more::Display;
+use iggy::utils::byte_size::IggyByteSize;
 use iggy::utils::duration::IggyDuration;
 use iggy::validatable::Validatable;
 use serde::{Deserialize, Serialize};
@@ -24,6 +26,7 @@ pub struct ServerConfig {
     pub quic: QuicConfig,
     pub tcp: TcpConfig,
     pub http: HttpConfig,
+    pub cluster: ClusterConfig,
     pub telemetry: TelemetryConfig,
 }
 
@@ -33,6 +36,7 @@ pub struct DataMaintenanceConfig {
     pub archiver: ArchiverConfig,
     pub messages: MessagesMaintenanceConfig,
     pub state: StateMaintenanceConfig,
+    pub tiered_storage: TieredStorageConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -52,6 +56,12 @@ pub struct MessagesMaintenanceConfig {
     pub interval: IggyDuration,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TieredStorageConfig {
+    pub enabled: bool,
+    pub cache_size: IggyByteSize,
+}
+
 #[serde_as]
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct StateMaintenanceConfig {
@@ -59,6 +69,10 @@ pub struct StateMaintenanceConfig {
     pub overwrite: bool,
     #[serde_as(as = "DisplayFromStr")]
     pub interval: IggyDuration,
+    pub snapshot_enabled: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub snapshot_interval: IggyDuration,
+    pub snapshot_min_entries: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -105,6 +119,8 @@ pub struct HeartbeatConfig {
     pub enabled: bool,
     #[serde_as(as = "DisplayFromStr")]
     pub interval: IggyDuration,
+    #[serde_as(as = "DisplayFromStr")]
+    pub consumer_group_session_timeout: IggyDuration,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
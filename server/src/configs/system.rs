@@ -1,4 +1,5 @@
 use crate::configs::resource_quota::MemoryResourceQuota;
+use derive_more::Display;
 use iggy::utils::byte_size::IggyByteSize;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::topic_size::MaxTopicSize;
@@ -8,6 +9,7 @@ use iggy::{
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
+use std::str::FromStr;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SystemConfig {
@@ -15,6 +17,7 @@ pub struct SystemConfig {
     pub backup: BackupConfig,
     pub database: Option<DatabaseConfig>,
     pub state: StateConfig,
+    pub storage: StorageConfig,
     pub runtime: RuntimeConfig,
     pub logging: LoggingConfig,
     pub cache: CacheConfig,
@@ -25,6 +28,9 @@ pub struct SystemConfig {
     pub encryption: EncryptionConfig,
     pub compression: CompressionConfig,
     pub message_deduplication: MessageDeduplicationConfig,
+    pub consumer_group: ConsumerGroupConfig,
+    pub transaction: TransactionConfig,
+    pub idempotence: IdempotenceConfig,
     pub recovery: RecoveryConfig,
 }
 
@@ -77,6 +83,19 @@ pub struct CacheConfig {
 pub struct EncryptionConfig {
     pub enabled: bool,
     pub key: String,
+    pub active_key_id: u32,
+    pub keys: String,
+    pub keyring_file: String,
+    pub topic_keys: String,
+    pub reencryption: ReencryptionConfig,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReencryptionConfig {
+    pub enabled: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub interval: IggyDuration,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -110,9 +129,53 @@ pub struct MessageDeduplicationConfig {
     pub expiry: IggyDuration,
 }
 
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConsumerGroupConfig {
+    #[serde_as(as = "DisplayFromStr")]
+    pub static_member_grace_period: IggyDuration,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TransactionConfig {
+    pub enabled: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub timeout: IggyDuration,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IdempotenceConfig {
+    pub enabled: bool,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RecoveryConfig {
     pub recreate_missing_state: bool,
+    pub inconsistent_indexes: IndexesRecovery,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Display, Copy, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexesRecovery {
+    #[display("repair")]
+    Repair,
+    #[display("refuse")]
+    Refuse,
+    #[display("truncate")]
+    Truncate,
+}
+
+impl FromStr for IndexesRecovery {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "repair" => Ok(IndexesRecovery::Repair),
+            "refuse" => Ok(IndexesRecovery::Refuse),
+            "truncate" => Ok(IndexesRecovery::Truncate),
+            _ => Err(format!("Invalid indexes recovery: {s}")),
+        }
+    }
 }
 
 #[serde_as]
@@ -123,6 +186,8 @@ pub struct SegmentConfig {
     pub cache_time_indexes: bool,
     #[serde_as(as = "DisplayFromStr")]
     pub message_expiry: IggyExpiry,
+    #[serde_as(as = "DisplayFromStr")]
+    pub max_age: IggyDuration,
     pub archive_expired: bool,
 }
 
@@ -131,6 +196,34 @@ pub struct StateConfig {
     pub enforce_fsync: bool,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Display, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    #[display("file")]
+    File,
+    #[display("io_uring")]
+    IoUring,
+    #[display("memory")]
+    Memory,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "file" => Ok(StorageBackend::File),
+            "io_uring" => Ok(StorageBackend::IoUring),
+            "memory" => Ok(StorageBackend::Memory),
+            _ => Err(format!("Invalid storage backend: {s}")),
+        }
+    }
+}
+
 impl SystemConfig {
     pub fn get_system_path(&self) -> String {
         self.path.to_string()
@@ -150,13 +243,26 @@ impl SystemConfig {
         format!("{}/log", self.get_state_path())
     }
 
+    pub fn get_state_snapshot_path(&self) -> String {
+        format!("{}/snapshot", self.get_state_path())
+    }
+
     pub fn get_state_info_path(&self) -> String {
         format!("{}/info", self.get_state_path())
     }
+
+    pub fn get_state_cluster_path(&self) -> String {
+        format!("{}/cluster", self.get_state_path())
+    }
+
     pub fn get_state_tokens_path(&self) -> String {
         format!("{}/tokens", self.get_state_path())
     }
 
+    pub fn get_state_transactions_path(&self) -> String {
+        format!("{}/transactions", self.get_state_path())
+    }
+
     pub fn get_backup_path(&self) -> String {
         format!("{}/{}", self.get_system_path(), self.backup.path)
     }
@@ -236,6 +342,49 @@ impl SystemConfig {
         )
     }
 
+    pub fn get_consumer_group_leases_path(
+        &self,
+        stream_id: u32,
+        topic_id: u32,
+        partition_id: u32,
+    ) -> String {
+        format!(
+            "{}/leases",
+            self.get_offsets_path(stream_id, topic_id, partition_id)
+        )
+    }
+
+    pub fn get_producers_path(&self, stream_id: u32, topic_id: u32, partition_id: u32) -> String {
+        format!(
+            "{}/producers",
+            self.get_offsets_path(stream_id, topic_id, partition_id)
+        )
+    }
+
+    pub fn get_transactions_path(
+        &self,
+        stream_id: u32,
+        topic_id: u32,
+        partition_id: u32,
+    ) -> String {
+        format!(
+            "{}/transactions",
+            self.get_offsets_path(stream_id, topic_id, partition_id)
+        )
+    }
+
+    pub fn get_dead_letters_path(
+        &self,
+        stream_id: u32,
+        topic_id: u32,
+        partition_id: u32,
+    ) -> String {
+        format!(
+            "{}/dead_letters",
+            self.get_offsets_path(stream_id, topic_id, partition_id)
+        )
+    }
+
     pub fn get_segment_path(
         &self,
         stream_id: u32,
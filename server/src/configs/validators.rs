@@ -1,31 +1,48 @@
 extern crate sysinfo;
 
 use super::server::{
-    ArchiverConfig, DataMaintenanceConfig, MessageSaverConfig, MessagesMaintenanceConfig,
-    StateMaintenanceConfig, TelemetryConfig,
+    ArchiverConfig, DataMaintenanceConfig, HeartbeatConfig, MessageSaverConfig,
+    MessagesMaintenanceConfig, StateMaintenanceConfig, TelemetryConfig,
 };
 use super::system::CompressionConfig;
 use crate::archiver::ArchiverKind;
+use crate::configs::cluster::ClusterConfig;
+use crate::configs::http::HttpCorsConfig;
 use crate::configs::server::{PersonalAccessTokenConfig, ServerConfig};
-use crate::configs::system::{CacheConfig, SegmentConfig};
+use crate::configs::system::{
+    CacheConfig, EncryptionConfig, SegmentConfig, StorageBackend, StorageConfig, TransactionConfig,
+};
 use crate::server_error::ServerError;
 use crate::streaming::segments::segment;
+use crate::streaming::utils::keyring::Keyring;
+use axum::http::{HeaderName, HeaderValue};
 use iggy::compression::compression_algorithm::CompressionAlgorithm;
 use iggy::utils::byte_size::IggyByteSize;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::topic_size::MaxTopicSize;
 use iggy::validatable::Validatable;
+use std::str::FromStr;
 use sysinfo::{Pid, ProcessesToUpdate, System};
 use tracing::{info, warn};
 
+const CORS_METHODS: [&str; 9] = [
+    "GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS", "CONNECT", "PATCH", "TRACE",
+];
+
 impl Validatable<ServerError> for ServerConfig {
     fn validate(&self) -> Result<(), ServerError> {
         self.data_maintenance.validate()?;
+        self.message_saver.validate()?;
         self.personal_access_token.validate()?;
+        self.heartbeat.validate()?;
         self.system.segment.validate()?;
+        self.system.storage.validate()?;
         self.system.cache.validate()?;
         self.system.compression.validate()?;
+        self.system.encryption.validate()?;
+        self.system.transaction.validate()?;
         self.telemetry.validate()?;
+        self.cluster.validate()?;
 
         let topic_size = match self.system.topic.max_size {
             MaxTopicSize::Custom(size) => Ok(size.as_bytes_u64()),
@@ -42,6 +59,7 @@ impl Validatable<ServerError> for ServerConfig {
         }
 
         if self.http.enabled {
+            self.http.cors.validate()?;
             if let IggyExpiry::ServerDefault = self.http.jwt.access_token_expiry {
                 return Err(ServerError::InvalidConfiguration(
                     "Access token expiry cannot be set to server default.".into(),
@@ -49,6 +67,18 @@ impl Validatable<ServerError> for ServerConfig {
             }
         }
 
+        if self.cluster.enabled && self.system.storage.backend == StorageBackend::Memory {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster mode requires the state log to be stored on disk, the memory storage backend cannot be used.".into(),
+            ));
+        }
+
+        if self.cluster.enabled && self.data_maintenance.state.snapshot_enabled {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster mode requires all the state log entries to be replicated, the state snapshots cannot be enabled.".into(),
+            ));
+        }
+
         if topic_size < self.system.segment.size.as_bytes_u64() {
             return Err(ServerError::InvalidConfiguration(format!(
                 "Max topic size cannot be lower than segment size. Max topic size: {}, segment size: {}.",
@@ -65,10 +95,9 @@ impl Validatable<ServerError> for CompressionConfig {
     fn validate(&self) -> Result<(), ServerError> {
         let compression_alg = &self.default_algorithm;
         if *compression_alg != CompressionAlgorithm::None {
-            // TODO(numinex): Change this message once server side compression is fully developed.
-            warn!(
-                "Server started with server-side compression enabled, using algorithm: {}, this feature is not implemented yet!",
-                compression_alg
+            info!(
+                "Server started with server-side compression enabled, using algorithm: {}, override per topic allowed: {}.",
+                compression_alg, self.allow_override
             );
         }
 
@@ -161,6 +190,51 @@ impl Validatable<ServerError> for SegmentConfig {
     }
 }
 
+impl Validatable<ServerError> for StorageConfig {
+    fn validate(&self) -> Result<(), ServerError> {
+        if self.backend == StorageBackend::IoUring
+            && !cfg!(all(feature = "io-uring", target_os = "linux"))
+        {
+            return Err(ServerError::InvalidConfiguration(
+                "The io_uring storage backend requires the server to be built with the `io-uring` feature on Linux.".into(),
+            ));
+        }
+
+        #[cfg(all(feature = "io-uring", target_os = "linux"))]
+        if self.backend == StorageBackend::IoUring {
+            if let Err(error) = crate::streaming::persistence::uring::Uring::get_instance() {
+                return Err(ServerError::InvalidConfiguration(format!(
+                    "Cannot initialize io_uring for the storage backend, make sure that the kernel supports it: {error}."
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Validatable<ServerError> for EncryptionConfig {
+    fn validate(&self) -> Result<(), ServerError> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        if let Err(error) = Keyring::from_config(self) {
+            return Err(ServerError::InvalidConfiguration(format!(
+                "Invalid encryption keyring: {error}."
+            )));
+        }
+
+        if self.reencryption.enabled && self.reencryption.interval.is_zero() {
+            return Err(ServerError::InvalidConfiguration(
+                "Re-encryption interval cannot be zero, it must be greater than 0.".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 impl Validatable<ServerError> for MessageSaverConfig {
     fn validate(&self) -> Result<(), ServerError> {
         if self.enabled && self.interval.is_zero() {
@@ -173,11 +247,178 @@ impl Validatable<ServerError> for MessageSaverConfig {
     }
 }
 
+impl Validatable<ServerError> for HeartbeatConfig {
+    fn validate(&self) -> Result<(), ServerError> {
+        // The heartbeats verifier is always running, as it can be enabled by reloading the configuration.
+        if self.interval.is_zero() {
+            return Err(ServerError::InvalidConfiguration(
+                "Heartbeat interval cannot be zero, it must be greater than 0.".into(),
+            ));
+        }
+
+        if self.enabled
+            && self.consumer_group_session_timeout.as_micros() < self.interval.as_micros()
+        {
+            return Err(ServerError::InvalidConfiguration(
+                "Consumer group session timeout cannot be lower than the heartbeat interval."
+                    .into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Validatable<ServerError> for HttpCorsConfig {
+    fn validate(&self) -> Result<(), ServerError> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        if let Some(method) = self.allowed_methods.iter().find(|method| {
+            !method.is_empty() && !CORS_METHODS.contains(&method.to_uppercase().as_str())
+        }) {
+            return Err(ServerError::InvalidConfiguration(format!(
+                "Invalid CORS allowed method: {method}."
+            )));
+        }
+
+        if let Some(origin) = self
+            .allowed_origins
+            .iter()
+            .find(|origin| origin.as_str() != "*" && HeaderValue::from_str(origin).is_err())
+        {
+            return Err(ServerError::InvalidConfiguration(format!(
+                "Invalid CORS allowed origin: {origin}."
+            )));
+        }
+
+        if let Some(header) = self
+            .allowed_headers
+            .iter()
+            .chain(self.exposed_headers.iter())
+            .find(|header| !header.is_empty() && HeaderName::from_str(header).is_err())
+        {
+            return Err(ServerError::InvalidConfiguration(format!(
+                "Invalid CORS header: {header}."
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Validatable<ServerError> for ClusterConfig {
+    fn validate(&self) -> Result<(), ServerError> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        if self.node_id == 0 {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster node ID cannot be 0.".into(),
+            ));
+        }
+
+        if self.heartbeat_interval.as_micros() == 0 {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster heartbeat interval cannot be zero.".into(),
+            ));
+        }
+
+        if self.election_timeout.as_micros() <= self.heartbeat_interval.as_micros() {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster election timeout must be greater than the heartbeat interval.".into(),
+            ));
+        }
+
+        if self.commit_timeout.as_micros() == 0 {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster commit timeout cannot be zero.".into(),
+            ));
+        }
+
+        if self.replication.fetch_interval.as_micros() == 0 {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster replication fetch interval cannot be zero.".into(),
+            ));
+        }
+
+        if self.replication.fetch_max_messages == 0 {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster replication fetch max messages cannot be zero.".into(),
+            ));
+        }
+
+        if self.replication.replica_lag_timeout.as_micros()
+            <= self.replication.fetch_interval.as_micros()
+        {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster replica lag timeout must be greater than the replication fetch interval."
+                    .into(),
+            ));
+        }
+
+        if self.replication.acks_timeout.as_micros() == 0 {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster replication acks timeout cannot be zero.".into(),
+            ));
+        }
+
+        let nodes = self
+            .get_nodes()
+            .map_err(ServerError::InvalidConfiguration)?;
+        if nodes.is_empty() {
+            return Err(ServerError::InvalidConfiguration(
+                "Cluster nodes cannot be empty.".into(),
+            ));
+        }
+
+        if !nodes.iter().any(|(node_id, _)| *node_id == self.node_id) {
+            warn!(
+                "Cluster node with ID: {} is not one of the configured nodes, it will wait to be added to the cluster.",
+                self.node_id
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Validatable<ServerError> for TransactionConfig {
+    fn validate(&self) -> Result<(), ServerError> {
+        if self.enabled && self.timeout.is_zero() {
+            return Err(ServerError::InvalidConfiguration(
+                "Transaction timeout cannot be zero.".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 impl Validatable<ServerError> for DataMaintenanceConfig {
     fn validate(&self) -> Result<(), ServerError> {
         self.archiver.validate()?;
         self.messages.validate()?;
         self.state.validate()?;
+        if !self.tiered_storage.enabled {
+            return Ok(());
+        }
+
+        if !self.archiver.enabled || !self.messages.archiver_enabled {
+            return Err(ServerError::InvalidConfiguration(
+                "Tiered storage requires the archiver and the messages archiver to be enabled."
+                    .into(),
+            ));
+        }
+
+        if self.tiered_storage.cache_size.as_bytes_u64() == 0 {
+            return Err(ServerError::InvalidConfiguration(
+                "Tiered storage cache size cannot be zero, it must be greater than 0.".into(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -251,7 +492,7 @@ impl Validatable<ServerError> for ArchiverConfig {
 
 impl Validatable<ServerError> for MessagesMaintenanceConfig {
     fn validate(&self) -> Result<(), ServerError> {
-        if self.archiver_enabled && self.interval.is_zero() {
+        if (self.archiver_enabled || self.cleaner_enabled) && self.interval.is_zero() {
             return Err(ServerError::InvalidConfiguration(
                 "Message maintenance interval size cannot be zero, it must be greater than 0."
                     .into(),
@@ -270,6 +511,12 @@ impl Validatable<ServerError> for StateMaintenanceConfig {
             ));
         }
 
+        if self.snapshot_enabled && self.snapshot_interval.is_zero() {
+            return Err(ServerError::InvalidConfiguration(
+                "State snapshot interval cannot be zero, it must be greater than 0.".into(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,141 @@
+use crate::http::error::CustomError;
+use crate::http::jwt::json_web_token::Identity;
+use crate::http::shared::AppState;
+use crate::state::command::EntryCommand;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::SharedSystem;
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{header, Method, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Extension, Json, Router};
+use iggy::cluster::add_cluster_node::AddClusterNode;
+use iggy::cluster::remove_cluster_node::RemoveClusterNode;
+use iggy::models::cluster::ClusterMetadata;
+use iggy::validatable::Validatable;
+use std::sync::Arc;
+use tracing::instrument;
+
+/// The paths of the requests which don't modify the replicated state, thus they can be handled by any node.
+const NOT_REPLICATED_PATHS: &[&str] = &["/messages", "/consumer-offsets", "/producers"];
+const NOT_REPLICATED_ENDPOINTS: &[&str] = &[
+    "/users/login",
+    "/users/logout",
+    "/users/refresh-token",
+    "/personal-access-tokens/login",
+    "/config/reload",
+];
+
+pub struct LeaderRedirect {
+    pub system: SharedSystem,
+    pub scheme: &'static str,
+}
+
+pub fn router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/cluster", get(get_cluster_metadata))
+        .route("/cluster/nodes", post(add_cluster_node))
+        .route("/cluster/nodes/:node_id", delete(remove_cluster_node))
+        .with_state(state)
+}
+
+/// Redirects the requests modifying the replicated state to the cluster leader.
+pub async fn redirect_to_leader(
+    State(state): State<Arc<LeaderRedirect>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if request.method() == Method::GET
+        || request.method() == Method::HEAD
+        || request.method() == Method::OPTIONS
+        || NOT_REPLICATED_ENDPOINTS.contains(&path)
+        || NOT_REPLICATED_PATHS
+            .iter()
+            .any(|not_replicated_path| path.contains(not_replicated_path))
+    {
+        return next.run(request).await;
+    }
+
+    let error = {
+        let system = state.system.read().await;
+        match system.ensure_leader() {
+            Ok(()) => None,
+            Err(error) => Some((
+                error,
+                system.cluster.as_ref().and_then(|cluster| cluster.leader()),
+            )),
+        }
+    };
+    let Some((error, leader)) = error else {
+        return next.run(request).await;
+    };
+
+    match leader.filter(|leader| !leader.http_address.is_empty()) {
+        Some(leader) => {
+            let path_and_query = request
+                .uri()
+                .path_and_query()
+                .map(|path_and_query| path_and_query.as_str())
+                .unwrap_or(request.uri().path());
+            let location = format!(
+                "{}://{}{}",
+                state.scheme, leader.http_address, path_and_query
+            );
+            (
+                StatusCode::TEMPORARY_REDIRECT,
+                [(header::LOCATION, location)],
+            )
+                .into_response()
+        }
+        None => CustomError::Error(error).into_response(),
+    }
+}
+
+async fn get_cluster_metadata(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<ClusterMetadata>, CustomError> {
+    let system = state.system.read().await;
+    let metadata = system
+        .get_cluster_metadata(&Session::stateless(identity.user_id, identity.ip_address))
+        .await?;
+    Ok(Json(metadata))
+}
+
+#[instrument(skip_all, fields(iggy_user_id = identity.user_id))]
+async fn add_cluster_node(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Json(command): Json<AddClusterNode>,
+) -> Result<StatusCode, CustomError> {
+    command.validate()?;
+    state
+        .system
+        .replicate(
+            &Session::stateless(identity.user_id, identity.ip_address),
+            EntryCommand::AddClusterNode(command),
+        )
+        .await?;
+    Ok(StatusCode::CREATED)
+}
+
+#[instrument(skip_all, fields(iggy_user_id = identity.user_id))]
+async fn remove_cluster_node(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Path(node_id): Path<u32>,
+) -> Result<StatusCode, CustomError> {
+    let command = RemoveClusterNode { node_id };
+    command.validate()?;
+    state
+        .system
+        .replicate(
+            &Session::stateless(identity.user_id, identity.ip_address),
+            EntryCommand::RemoveClusterNode(command),
+        )
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
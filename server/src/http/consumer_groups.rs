@@ -12,6 +12,7 @@ use iggy::consumer_groups::create_consumer_group::CreateConsumerGroup;
 use iggy::consumer_groups::delete_consumer_group::DeleteConsumerGroup;
 use iggy::identifier::Identifier;
 use iggy::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails};
+use iggy::utils::text;
 use iggy::validatable::Validatable;
 use std::sync::Arc;
 use tracing::instrument;
@@ -81,6 +82,30 @@ async fn create_consumer_group(
     command.stream_id = Identifier::from_str_value(&stream_id)?;
     command.topic_id = Identifier::from_str_value(&topic_id)?;
     command.validate()?;
+    if state.system.is_clustered().await {
+        let stream_id = command.stream_id.clone();
+        let topic_id = command.topic_id.clone();
+        let name = text::to_lowercase_non_whitespace(&command.name);
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::CreateConsumerGroup(command),
+            )
+            .await?;
+        let system = state.system.read().await;
+        let consumer_group = system
+            .get_stream(&stream_id)?
+            .get_topic(&topic_id)?
+            .get_consumer_group(&Identifier::named(&name)?)?
+            .read()
+            .await;
+        return Ok((
+            StatusCode::CREATED,
+            Json(mapper::map_consumer_group(&consumer_group).await),
+        ));
+    }
+
     let consumer_group_details;
     {
         let mut system = state.system.write().await;
@@ -91,6 +116,7 @@ async fn create_consumer_group(
                 &command.topic_id,
                 command.group_id,
                 &command.name,
+                command.mode,
             )
             .await?;
         let consumer_group = consumer_group.read().await;
@@ -115,6 +141,21 @@ async fn delete_consumer_group(
     let stream_id = Identifier::from_str_value(&stream_id)?;
     let topic_id = Identifier::from_str_value(&topic_id)?;
     let group_id = Identifier::from_str_value(&group_id)?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::DeleteConsumerGroup(DeleteConsumerGroup {
+                    stream_id,
+                    topic_id,
+                    group_id,
+                }),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     {
         let mut system = state.system.write().await;
         system
@@ -0,0 +1,98 @@
+use crate::configs::http::HttpCorsConfig;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{Method, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use std::convert::Infallible;
+use std::sync::{Arc, RwLock};
+use tower::{service_fn, Layer, ServiceExt};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// CORS layer, which is rebuilt whenever the CORS configuration is reloaded.
+/// It's applied by the `cors` middleware, as the router layers cannot be replaced once the server has started.
+#[derive(Debug, Default)]
+pub struct ReloadableCors {
+    layer: RwLock<Option<CorsLayer>>,
+}
+
+impl ReloadableCors {
+    pub fn new(config: &HttpCorsConfig) -> Self {
+        let cors = Self::default();
+        cors.reload(config);
+        cors
+    }
+
+    pub fn reload(&self, config: &HttpCorsConfig) {
+        let layer = config.enabled.then(|| configure_cors(config));
+        *self.layer.write().unwrap() = layer;
+    }
+}
+
+pub async fn cors(
+    State(cors): State<Arc<ReloadableCors>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let layer = cors.layer.read().unwrap().clone();
+    let Some(layer) = layer else {
+        return next.run(request).await;
+    };
+
+    let service = layer.layer(service_fn(move |request: Request<Body>| {
+        let next = next.clone();
+        async move { Ok::<_, Infallible>(next.run(request).await) }
+    }));
+    match service.oneshot(request).await {
+        Ok(response) => response,
+        Err(error) => match error {},
+    }
+}
+
+fn configure_cors(config: &HttpCorsConfig) -> CorsLayer {
+    let allowed_origins = match &config.allowed_origins {
+        origins if origins.is_empty() => AllowOrigin::default(),
+        origins if origins.first().unwrap() == "*" => AllowOrigin::any(),
+        origins => AllowOrigin::list(origins.iter().map(|s| s.parse().unwrap())),
+    };
+
+    let allowed_headers = config
+        .allowed_headers
+        .iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().unwrap())
+        .collect::<Vec<_>>();
+
+    let exposed_headers = config
+        .exposed_headers
+        .iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().unwrap())
+        .collect::<Vec<_>>();
+
+    let allowed_methods = config
+        .allowed_methods
+        .iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.to_uppercase().as_str() {
+            "GET" => Method::GET,
+            "POST" => Method::POST,
+            "PUT" => Method::PUT,
+            "DELETE" => Method::DELETE,
+            "HEAD" => Method::HEAD,
+            "OPTIONS" => Method::OPTIONS,
+            "CONNECT" => Method::CONNECT,
+            "PATCH" => Method::PATCH,
+            "TRACE" => Method::TRACE,
+            _ => panic!("Invalid HTTP method: {}", s),
+        })
+        .collect::<Vec<_>>();
+
+    CorsLayer::new()
+        .allow_methods(allowed_methods)
+        .allow_origin(allowed_origins)
+        .allow_headers(allowed_headers)
+        .expose_headers(exposed_headers)
+        .allow_credentials(config.allow_credentials)
+        .allow_private_network(config.allow_private_network)
+}
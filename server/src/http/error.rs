@@ -45,6 +45,15 @@ impl IntoResponse for CustomError {
                     IggyError::CannotParseUtf8(_) => StatusCode::INTERNAL_SERVER_ERROR,
                     IggyError::Unauthenticated => StatusCode::UNAUTHORIZED,
                     IggyError::Unauthorized => StatusCode::FORBIDDEN,
+                    IggyError::LeaderNotElected => StatusCode::SERVICE_UNAVAILABLE,
+                    IggyError::NotLeader(_, _) => StatusCode::MISDIRECTED_REQUEST,
+                    IggyError::CannotCommitStateEntry(_) => StatusCode::SERVICE_UNAVAILABLE,
+                    IggyError::StateEntryCommitTimedOut(_) => StatusCode::GATEWAY_TIMEOUT,
+                    IggyError::NotPartitionLeader(_, _, _, _) => StatusCode::MISDIRECTED_REQUEST,
+                    IggyError::PartitionLeaderNotAvailable(_, _, _) => {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    }
+                    IggyError::MessagesNotReplicated(_) => StatusCode::SERVICE_UNAVAILABLE,
                     _ => StatusCode::BAD_REQUEST,
                 };
                 (status_code, Json(ErrorResponse::from_error(error)))
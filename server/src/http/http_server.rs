@@ -1,4 +1,6 @@
-use crate::configs::http::{HttpConfig, HttpCorsConfig};
+use crate::configs::http::HttpConfig;
+use crate::http::cluster::{redirect_to_leader, LeaderRedirect};
+use crate::http::cors::ReloadableCors;
 use crate::http::diagnostics::request_diagnostics;
 use crate::http::jwt::cleaner::start_expired_tokens_cleaner;
 use crate::http::jwt::jwt_manager::JwtManager;
@@ -8,13 +10,11 @@ use crate::http::shared::AppState;
 use crate::http::*;
 use crate::streaming::systems::system::SharedSystem;
 use axum::extract::DefaultBodyLimit;
-use axum::http::Method;
 use axum::{middleware, Router};
 use axum_server::tls_rustls::RustlsConfig;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tower_http::cors::{AllowOrigin, CorsLayer};
 use tracing::{error, info};
 
 /// Starts the HTTP API server.
@@ -26,6 +26,10 @@ pub async fn start(config: HttpConfig, system: SharedSystem) -> SocketAddr {
         "HTTP API"
     };
 
+    let leader_redirect = Arc::new(LeaderRedirect {
+        system: system.clone(),
+        scheme: if config.tls.enabled { "https" } else { "http" },
+    });
     let app_state = build_app_state(&config, system).await;
     let mut app = Router::new()
         .merge(system::router(app_state.clone(), &config.metrics))
@@ -37,14 +41,20 @@ pub async fn start(config: HttpConfig, system: SharedSystem) -> SocketAddr {
         .merge(consumer_offsets::router(app_state.clone()))
         .merge(partitions::router(app_state.clone()))
         .merge(messages::router(app_state.clone()))
+        .merge(cluster::router(app_state.clone()))
         .layer(DefaultBodyLimit::max(
             config.max_request_size.as_bytes_u64() as usize,
         ))
-        .layer(middleware::from_fn_with_state(app_state.clone(), jwt_auth));
+        .layer(middleware::from_fn_with_state(app_state.clone(), jwt_auth))
+        .layer(middleware::from_fn_with_state(
+            leader_redirect,
+            redirect_to_leader,
+        ));
 
-    if config.cors.enabled {
-        app = app.layer(configure_cors(config.cors));
-    }
+    // CORS middleware is always installed, so that it can be enabled by reloading the configuration.
+    let reloadable_cors = Arc::new(ReloadableCors::new(&config.cors));
+    start_cors_reloader(reloadable_cors.clone(), &app_state.system).await;
+    app = app.layer(middleware::from_fn_with_state(reloadable_cors, cors::cors));
 
     if config.metrics.enabled {
         app = app.layer(middleware::from_fn_with_state(app_state.clone(), metrics));
@@ -125,50 +135,13 @@ async fn build_app_state(config: &HttpConfig, system: SharedSystem) -> Arc<AppSt
         system,
     })
 }
-fn configure_cors(config: HttpCorsConfig) -> CorsLayer {
-    let allowed_origins = match config.allowed_origins {
-        origins if origins.is_empty() => AllowOrigin::default(),
-        origins if origins.first().unwrap() == "*" => AllowOrigin::any(),
-        origins => AllowOrigin::list(origins.iter().map(|s| s.parse().unwrap())),
-    };
 
-    let allowed_headers = config
-        .allowed_headers
-        .iter()
-        .filter(|s| !s.is_empty())
-        .map(|s| s.parse().unwrap())
-        .collect::<Vec<_>>();
-
-    let exposed_headers = config
-        .exposed_headers
-        .iter()
-        .filter(|s| !s.is_empty())
-        .map(|s| s.parse().unwrap())
-        .collect::<Vec<_>>();
-
-    let allowed_methods = config
-        .allowed_methods
-        .iter()
-        .filter(|s| !s.is_empty())
-        .map(|s| match s.to_uppercase().as_str() {
-            "GET" => Method::GET,
-            "POST" => Method::POST,
-            "PUT" => Method::PUT,
-            "DELETE" => Method::DELETE,
-            "HEAD" => Method::HEAD,
-            "OPTIONS" => Method::OPTIONS,
-            "CONNECT" => Method::CONNECT,
-            "PATCH" => Method::PATCH,
-            "TRACE" => Method::TRACE,
-            _ => panic!("Invalid HTTP method: {}", s),
-        })
-        .collect::<Vec<_>>();
-
-    CorsLayer::new()
-        .allow_methods(allowed_methods)
-        .allow_origin(allowed_origins)
-        .allow_headers(allowed_headers)
-        .expose_headers(exposed_headers)
-        .allow_credentials(config.allow_credentials)
-        .allow_private_network(config.allow_private_network)
+async fn start_cors_reloader(cors: Arc<ReloadableCors>, system: &SharedSystem) {
+    let mut config_changes = system.read().await.subscribe_to_config_changes();
+    tokio::spawn(async move {
+        loop {
+            let config = config_changes.changed().await;
+            cors.reload(&config.http_cors);
+        }
+    });
 }
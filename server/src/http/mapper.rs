@@ -192,6 +192,8 @@ pub async fn map_consumer_groups(
             name: consumer_group.name.clone(),
             partitions_count: consumer_group.partitions_count,
             members_count: consumer_group.get_members().len() as u32,
+            generation: consumer_group.generation,
+            evicted_members_count: consumer_group.evicted_members_count,
         };
         groups.push(consumer_group);
     }
@@ -205,6 +207,8 @@ pub async fn map_consumer_group(consumer_group: &ConsumerGroup) -> ConsumerGroup
         name: consumer_group.name.clone(),
         partitions_count: consumer_group.partitions_count,
         members_count: consumer_group.get_members().len() as u32,
+        generation: consumer_group.generation,
+        evicted_members_count: consumer_group.evicted_members_count,
         members: Vec::new(),
     };
     let members = consumer_group.get_members();
@@ -6,13 +6,17 @@ use crate::streaming::systems::messages::PollingArgs;
 use crate::streaming::utils::random_id;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
-use axum::routing::get;
+use axum::routing::{get, post};
 use axum::{Extension, Json, Router};
 use iggy::consumer::Consumer;
 use iggy::identifier::Identifier;
+use iggy::messages::ack_message::AckMessage;
+use iggy::messages::init_producer::InitProducer;
+use iggy::messages::nack_message::NackMessage;
 use iggy::messages::poll_messages::PollMessages;
-use iggy::messages::send_messages::SendMessages;
+use iggy::messages::send_messages::{Acks, SendMessages};
 use iggy::models::messages::PolledMessages;
+use iggy::models::producer::Producer;
 use iggy::validatable::Validatable;
 use std::sync::Arc;
 use tracing::instrument;
@@ -27,6 +31,15 @@ pub fn router(state: Arc<AppState>) -> Router {
             "/streams/:stream_id/topics/:topic_id/messages/flush/:partition_id/:fsync",
             get(flush_unsaved_buffer),
         )
+        .route(
+            "/streams/:stream_id/topics/:topic_id/messages/nack",
+            post(nack_message),
+        )
+        .route(
+            "/streams/:stream_id/topics/:topic_id/messages/ack",
+            post(ack_message),
+        )
+        .route("/producers", post(init_producer))
         .with_state(state)
 }
 
@@ -49,7 +62,13 @@ async fn poll_messages(
             &query.0.stream_id,
             &query.0.topic_id,
             query.0.partition_id,
-            PollingArgs::new(query.0.strategy, query.0.count, query.0.auto_commit),
+            PollingArgs::new(
+                query.0.strategy,
+                query.0.count,
+                query.0.auto_commit,
+                query.0.filter.clone(),
+                query.0.isolation,
+            ),
         )
         .await?;
     Ok(Json(polled_messages))
@@ -75,8 +94,10 @@ async fn send_messages(
     let stream_id = command.stream_id;
     let topic_id = command.topic_id;
     let partitioning = command.partitioning;
-    let system = state.system.read().await;
-    system
+    let replicated_messages = state
+        .system
+        .read()
+        .await
         .append_messages(
             &Session::stateless(identity.user_id, identity.ip_address),
             stream_id,
@@ -85,6 +106,12 @@ async fn send_messages(
             messages,
         )
         .await?;
+    // The replicas fetch the messages through the system, thus its lock must not be held while waiting for them.
+    if command.acks == Acks::All {
+        if let Some(replicated_messages) = replicated_messages {
+            replicated_messages.wait_for_in_sync_replicas().await?;
+        }
+    }
     Ok(StatusCode::CREATED)
 }
 
@@ -108,3 +135,66 @@ async fn flush_unsaved_buffer(
         .await?;
     Ok(StatusCode::OK)
 }
+
+#[instrument(skip_all, fields(iggy_user_id = identity.user_id, iggy_stream_id = stream_id, iggy_topic_id = topic_id))]
+async fn nack_message(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Path((stream_id, topic_id)): Path<(String, String)>,
+    Json(mut command): Json<NackMessage>,
+) -> Result<StatusCode, CustomError> {
+    command.stream_id = Identifier::from_str_value(&stream_id)?;
+    command.topic_id = Identifier::from_str_value(&topic_id)?;
+    command.validate()?;
+    let system = state.system.read().await;
+    system
+        .nack_message(
+            &Session::stateless(identity.user_id, identity.ip_address),
+            &command.consumer,
+            &command.stream_id,
+            &command.topic_id,
+            command.partition_id,
+            command.offset,
+            &command.reason,
+        )
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(skip_all, fields(iggy_user_id = identity.user_id, iggy_stream_id = stream_id, iggy_topic_id = topic_id))]
+async fn ack_message(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Path((stream_id, topic_id)): Path<(String, String)>,
+    Json(mut command): Json<AckMessage>,
+) -> Result<StatusCode, CustomError> {
+    command.stream_id = Identifier::from_str_value(&stream_id)?;
+    command.topic_id = Identifier::from_str_value(&topic_id)?;
+    command.validate()?;
+    let system = state.system.read().await;
+    system
+        .ack_message(
+            &Session::stateless(identity.user_id, identity.ip_address),
+            &command.consumer,
+            &command.stream_id,
+            &command.topic_id,
+            command.partition_id,
+            command.offset,
+        )
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(skip_all, fields(iggy_user_id = identity.user_id))]
+async fn init_producer(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Json(command): Json<InitProducer>,
+) -> Result<Json<Producer>, CustomError> {
+    command.validate()?;
+    let system = state.system.read().await;
+    let producer_id = system
+        .init_producer(&Session::stateless(identity.user_id, identity.ip_address))
+        .await?;
+    Ok(Json(Producer { id: producer_id }))
+}
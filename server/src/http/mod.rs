@@ -1,5 +1,7 @@
+pub mod cluster;
 pub mod consumer_groups;
 pub mod consumer_offsets;
+pub mod cors;
 pub mod diagnostics;
 pub mod error;
 pub mod http_server;
@@ -33,6 +33,17 @@ async fn create_partitions(
     command.stream_id = Identifier::from_str_value(&stream_id)?;
     command.topic_id = Identifier::from_str_value(&topic_id)?;
     command.validate()?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::CreatePartitions(command),
+            )
+            .await?;
+        return Ok(StatusCode::CREATED);
+    }
+
     {
         let mut system = state.system.write().await;
         system
@@ -63,6 +74,21 @@ async fn delete_partitions(
     query.stream_id = Identifier::from_str_value(&stream_id)?;
     query.topic_id = Identifier::from_str_value(&topic_id)?;
     query.validate()?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::DeletePartitions(DeletePartitions {
+                    stream_id: query.stream_id.clone(),
+                    topic_id: query.topic_id.clone(),
+                    partitions_count: query.partitions_count,
+                }),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     {
         let mut system = state.system.write().await;
         system
@@ -16,6 +16,7 @@ use iggy::models::personal_access_token::{PersonalAccessTokenInfo, RawPersonalAc
 use iggy::personal_access_tokens::create_personal_access_token::CreatePersonalAccessToken;
 use iggy::personal_access_tokens::delete_personal_access_token::DeletePersonalAccessToken;
 use iggy::personal_access_tokens::login_with_personal_access_token::LoginWithPersonalAccessToken;
+use iggy::utils::timestamp::IggyTimestamp;
 use iggy::validatable::Validatable;
 use std::sync::Arc;
 use tracing::instrument;
@@ -56,6 +57,28 @@ async fn create_personal_access_token(
     Json(command): Json<CreatePersonalAccessToken>,
 ) -> Result<Json<RawPersonalAccessToken>, CustomError> {
     command.validate()?;
+    if state.system.is_clustered().await {
+        // Only the hash of the token is replicated, while the raw one is returned to the user.
+        let (_, token) = PersonalAccessToken::new(
+            identity.user_id,
+            &command.name,
+            IggyTimestamp::now(),
+            command.expiry,
+        );
+        let hash = PersonalAccessToken::hash_token(&token);
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::CreatePersonalAccessToken(CreatePersonalAccessTokenWithHash {
+                    command,
+                    hash,
+                }),
+            )
+            .await?;
+        return Ok(Json(RawPersonalAccessToken { token }));
+    }
+
     let token;
     {
         let mut system = state.system.write().await;
@@ -89,6 +112,17 @@ async fn delete_personal_access_token(
     Extension(identity): Extension<Identity>,
     Path(name): Path<String>,
 ) -> Result<StatusCode, CustomError> {
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::DeletePersonalAccessToken(DeletePersonalAccessToken { name }),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     {
         let mut system = state.system.write().await;
         system
@@ -13,6 +13,7 @@ use iggy::streams::create_stream::CreateStream;
 use iggy::streams::delete_stream::DeleteStream;
 use iggy::streams::purge_stream::PurgeStream;
 use iggy::streams::update_stream::UpdateStream;
+use iggy::utils::text;
 use iggy::validatable::Validatable;
 
 use crate::state::command::EntryCommand;
@@ -67,6 +68,20 @@ async fn create_stream(
     Json(command): Json<CreateStream>,
 ) -> Result<Json<StreamDetails>, CustomError> {
     command.validate()?;
+    if state.system.is_clustered().await {
+        let name = text::to_lowercase_non_whitespace(&command.name);
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::CreateStream(command),
+            )
+            .await?;
+        let system = state.system.read().await;
+        let stream = system.get_stream(&Identifier::named(&name)?)?;
+        return Ok(Json(mapper::map_stream(stream)));
+    }
+
     let response;
     {
         let mut system = state.system.write().await;
@@ -97,6 +112,17 @@ async fn update_stream(
 ) -> Result<StatusCode, CustomError> {
     command.stream_id = Identifier::from_str_value(&stream_id)?;
     command.validate()?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::UpdateStream(command),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     {
         let mut system = state.system.write().await;
         system
@@ -123,6 +149,17 @@ async fn delete_stream(
     Path(stream_id): Path<String>,
 ) -> Result<StatusCode, CustomError> {
     let stream_id = Identifier::from_str_value(&stream_id)?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::DeleteStream(DeleteStream { stream_id }),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     {
         let mut system = state.system.write().await;
         system
@@ -151,6 +188,17 @@ async fn purge_stream(
     Path(stream_id): Path<String>,
 ) -> Result<StatusCode, CustomError> {
     let stream_id = Identifier::from_str_value(&stream_id)?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::PurgeStream(PurgeStream { stream_id }),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     let system = state.system.read().await;
     system
         .purge_stream(
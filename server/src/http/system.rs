@@ -5,7 +5,8 @@ use crate::http::mapper;
 use crate::http::shared::AppState;
 use crate::streaming::session::Session;
 use axum::extract::{Path, State};
-use axum::routing::get;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
 use axum::{Extension, Json, Router};
 use iggy::locking::IggySharedMutFn;
 use iggy::models::client_info::{ClientInfo, ClientInfoDetails};
@@ -21,7 +22,8 @@ pub fn router(state: Arc<AppState>, metrics_config: &HttpMetricsConfig) -> Route
         .route("/ping", get(|| async { PONG }))
         .route("/stats", get(get_stats))
         .route("/clients", get(get_clients))
-        .route("/clients/:client_id", get(get_client));
+        .route("/clients/:client_id", get(get_client))
+        .route("/config/reload", post(reload_config));
     if metrics_config.enabled {
         router = router.route(&metrics_config.endpoint, get(get_metrics));
     }
@@ -78,3 +80,14 @@ async fn get_clients(
     let clients = mapper::map_clients(&clients).await;
     Ok(Json(clients))
 }
+
+async fn reload_config(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+) -> Result<StatusCode, CustomError> {
+    let system = state.system.read().await;
+    system
+        .reload_config(&Session::stateless(identity.user_id, identity.ip_address))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -6,14 +6,18 @@ use crate::state::command::EntryCommand;
 use crate::streaming::session::Session;
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
-use axum::routing::{delete, get};
+use axum::routing::{delete, get, put};
 use axum::{Extension, Json, Router};
 use iggy::identifier::Identifier;
 use iggy::models::topic::{Topic, TopicDetails};
 use iggy::topics::create_topic::CreateTopic;
 use iggy::topics::delete_topic::DeleteTopic;
 use iggy::topics::purge_topic::PurgeTopic;
+use iggy::topics::set_cleanup_policy::SetCleanupPolicy;
+use iggy::topics::set_dead_letter_queue::SetDeadLetterQueue;
+use iggy::topics::set_max_topic_messages::SetMaxTopicMessages;
 use iggy::topics::update_topic::UpdateTopic;
+use iggy::utils::text;
 use iggy::validatable::Validatable;
 use std::sync::Arc;
 use tracing::instrument;
@@ -32,6 +36,18 @@ pub fn router(state: Arc<AppState>) -> Router {
             "/streams/:stream_id/topics/:topic_id/purge",
             delete(purge_topic),
         )
+        .route(
+            "/streams/:stream_id/topics/:topic_id/dead-letter-queue",
+            put(set_dead_letter_queue),
+        )
+        .route(
+            "/streams/:stream_id/topics/:topic_id/cleanup-policy",
+            put(set_cleanup_policy),
+        )
+        .route(
+            "/streams/:stream_id/topics/:topic_id/max-messages",
+            put(set_max_topic_messages),
+        )
         .with_state(state)
 }
 
@@ -80,6 +96,23 @@ async fn create_topic(
 ) -> Result<Json<TopicDetails>, CustomError> {
     command.stream_id = Identifier::from_str_value(&stream_id)?;
     command.validate()?;
+    if state.system.is_clustered().await {
+        let stream_id = command.stream_id.clone();
+        let name = text::to_lowercase_non_whitespace(&command.name);
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::CreateTopic(command),
+            )
+            .await?;
+        let system = state.system.read().await;
+        let topic = system
+            .get_stream(&stream_id)?
+            .get_topic(&Identifier::named(&name)?)?;
+        return Ok(Json(mapper::map_topic(topic).await));
+    }
+
     let response;
     {
         let mut system = state.system.write().await;
@@ -119,6 +152,17 @@ async fn update_topic(
     command.stream_id = Identifier::from_str_value(&stream_id)?;
     command.topic_id = Identifier::from_str_value(&topic_id)?;
     command.validate()?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::UpdateTopic(command),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     {
         let mut system = state.system.write().await;
         let topic = system
@@ -153,6 +197,20 @@ async fn delete_topic(
 ) -> Result<StatusCode, CustomError> {
     let stream_id = Identifier::from_str_value(&stream_id)?;
     let topic_id = Identifier::from_str_value(&topic_id)?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::DeleteTopic(DeleteTopic {
+                    stream_id,
+                    topic_id,
+                }),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     {
         let mut system = state.system.write().await;
         system
@@ -186,6 +244,20 @@ async fn purge_topic(
 ) -> Result<StatusCode, CustomError> {
     let stream_id = Identifier::from_str_value(&stream_id)?;
     let topic_id = Identifier::from_str_value(&topic_id)?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::PurgeTopic(PurgeTopic {
+                    stream_id,
+                    topic_id,
+                }),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
     let system = state.system.read().await;
     system
         .purge_topic(
@@ -206,3 +278,126 @@ async fn purge_topic(
         .await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[instrument(skip_all, fields(iggy_user_id = identity.user_id, iggy_stream_id = stream_id, iggy_topic_id = topic_id))]
+async fn set_dead_letter_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Path((stream_id, topic_id)): Path<(String, String)>,
+    Json(mut command): Json<SetDeadLetterQueue>,
+) -> Result<StatusCode, CustomError> {
+    command.stream_id = Identifier::from_str_value(&stream_id)?;
+    command.topic_id = Identifier::from_str_value(&topic_id)?;
+    command.validate()?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::SetDeadLetterQueue(command),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    {
+        let mut system = state.system.write().await;
+        command.dead_letter_queue = system
+            .set_dead_letter_queue(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                &command.stream_id,
+                &command.topic_id,
+                command.dead_letter_queue.take(),
+            )
+            .await?;
+    }
+
+    let system = state.system.read().await;
+    system
+        .state
+        .apply(identity.user_id, EntryCommand::SetDeadLetterQueue(command))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(skip_all, fields(iggy_user_id = identity.user_id, iggy_stream_id = stream_id, iggy_topic_id = topic_id))]
+async fn set_cleanup_policy(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Path((stream_id, topic_id)): Path<(String, String)>,
+    Json(mut command): Json<SetCleanupPolicy>,
+) -> Result<StatusCode, CustomError> {
+    command.stream_id = Identifier::from_str_value(&stream_id)?;
+    command.topic_id = Identifier::from_str_value(&topic_id)?;
+    command.validate()?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::SetCleanupPolicy(command),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    {
+        let mut system = state.system.write().await;
+        system
+            .set_cleanup_policy(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                &command.stream_id,
+                &command.topic_id,
+                command.cleanup_policy,
+            )
+            .await?;
+    }
+
+    let system = state.system.read().await;
+    system
+        .state
+        .apply(identity.user_id, EntryCommand::SetCleanupPolicy(command))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(skip_all, fields(iggy_user_id = identity.user_id, iggy_stream_id = stream_id, iggy_topic_id = topic_id))]
+async fn set_max_topic_messages(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Path((stream_id, topic_id)): Path<(String, String)>,
+    Json(mut command): Json<SetMaxTopicMessages>,
+) -> Result<StatusCode, CustomError> {
+    command.stream_id = Identifier::from_str_value(&stream_id)?;
+    command.topic_id = Identifier::from_str_value(&topic_id)?;
+    command.validate()?;
+    if state.system.is_clustered().await {
+        state
+            .system
+            .replicate(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                EntryCommand::SetMaxTopicMessages(command),
+            )
+            .await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    {
+        let mut system = state.system.write().await;
+        system
+            .set_max_topic_messages(
+                &Session::stateless(identity.user_id, identity.ip_address),
+                &command.stream_id,
+                &command.topic_id,
+                command.max_messages,
+            )
+            .await?;
+    }
+
+    let system = state.system.read().await;
+    system
+        .state
+        .apply(identity.user_id, EntryCommand::SetMaxTopicMessages(command))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -12,6 +12,7 @@ pub mod archiver;
 pub mod args;
 pub mod binary;
 pub mod channels;
+pub mod cluster;
 mod command;
 pub(crate) mod compat;
 pub mod configs;
@@ -262,7 +262,7 @@ impl Logging {
 
         trace!("Logging config: {}", config);
 
-        let filtering_level = Self::get_filtering_level(Some(config));
+        let filtering_level = Self::get_filtering_level(Some(&config.level));
 
         self.filtering_stdout_reload_handle
             .as_ref()
@@ -334,8 +334,25 @@ impl Logging {
         Ok(())
     }
 
+    /// Changes the level of the stdout and file logs, e.g. when the server configuration is reloaded.
+    pub fn reload_level(&self, level: &str) -> Result<(), ServerError> {
+        let filtering_level = Self::get_filtering_level(Some(level));
+        self.filtering_stdout_reload_handle
+            .as_ref()
+            .ok_or(ServerError::FilterReloadFailure)?
+            .modify(|layer| *layer = filtering_level.boxed())
+            .map_err(|_| ServerError::FilterReloadFailure)?;
+        self.filtering_file_reload_handle
+            .as_ref()
+            .ok_or(ServerError::FilterReloadFailure)?
+            .modify(|layer| *layer = filtering_level.boxed())
+            .map_err(|_| ServerError::FilterReloadFailure)?;
+        info!("Log level has been changed to: {filtering_level}.");
+        Ok(())
+    }
+
     // RUST_LOG always takes precedence over config
-    fn get_filtering_level(config: Option<&LoggingConfig>) -> LevelFilter {
+    fn get_filtering_level(level: Option<&str>) -> LevelFilter {
         if let Ok(rust_log) = std::env::var("RUST_LOG") {
             // Parse log level from RUST_LOG env variable
             if let Ok(level) = LevelFilter::from_str(&rust_log.to_uppercase()) {
@@ -346,13 +363,13 @@ impl Logging {
             }
         } else {
             // Parse log level from config
-            if let Some(config) = config {
-                if let Ok(level) = LevelFilter::from_str(&config.level.to_uppercase()) {
-                    level
+            if let Some(level) = level {
+                if let Ok(filtering_level) = LevelFilter::from_str(&level.to_uppercase()) {
+                    filtering_level
                 } else {
                     println!(
                         "Invalid log level in config: {}, falling back to info",
-                        config.level
+                        level
                     );
                     LevelFilter::INFO
                 }
@@ -26,6 +26,10 @@ impl Logging {
     ) -> Result<(), ServerError> {
         Ok(())
     }
+
+    pub fn reload_level(&self, _level: &str) -> Result<(), ServerError> {
+        Ok(())
+    }
 }
 
 impl Default for Logging {
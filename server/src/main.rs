@@ -1,15 +1,22 @@
 use anyhow::Result;
 use clap::Parser;
 use figlet_rs::FIGfont;
+use iggy::utils::timestamp::IggyTimestamp;
+use server::archiver;
+use server::archiver::restore::{self, RestoreOptions};
 use server::args::Args;
+use server::channels::commands::abort_expired_transactions::AbortExpiredTransactionsExecutor;
 use server::channels::commands::archive_state::ArchiveStateExecutor;
 use server::channels::commands::clean_personal_access_tokens::CleanPersonalAccessTokensExecutor;
 use server::channels::commands::maintain_messages::MaintainMessagesExecutor;
 use server::channels::commands::print_sysinfo::SysInfoPrintExecutor;
+use server::channels::commands::reencrypt_segments::ReencryptSegmentsExecutor;
 use server::channels::commands::save_messages::SaveMessagesExecutor;
+use server::channels::commands::snapshot_state::SnapshotStateExecutor;
 use server::channels::commands::verify_heartbeats::VerifyHeartbeatsExecutor;
 use server::channels::handler::ServerCommandHandler;
 use server::configs::config_provider;
+use server::configs::reloader::{ConfigReloader, ReloadableConfig};
 use server::configs::server::ServerConfig;
 use server::http::http_server;
 #[cfg(not(feature = "tokio-console"))]
@@ -20,8 +27,9 @@ use server::quic::quic_server;
 use server::server_error::ServerError;
 use server::streaming::systems::system::{SharedSystem, System};
 use server::tcp::tcp_server;
+use std::sync::Arc;
 use tokio::time::Instant;
-use tracing::{info, instrument};
+use tracing::{error, info, instrument};
 
 #[tokio::main]
 #[instrument(skip_all)]
@@ -42,11 +50,29 @@ async fn main() -> Result<(), ServerError> {
 
     logging.late_init(config.system.get_system_path(), &config.system.logging)?;
 
-    let system = SharedSystem::new(System::new(
+    if args.restore {
+        let archiver = archiver::create(&config.data_maintenance.archiver)?;
+        let options = RestoreOptions {
+            state_directory: args.restore_state_directory,
+            streams: args.restore_streams,
+            topics: args.restore_topics,
+            timestamp: args.restore_timestamp.map(IggyTimestamp::from),
+        };
+        restore::restore(config.system.clone(), archiver, &options).await?;
+    }
+
+    let mut system = System::new(
         config.system.clone(),
         config.data_maintenance.clone(),
         config.personal_access_token.clone(),
-    ));
+    );
+    let config_reloader = Arc::new(ConfigReloader::new(config_provider, config.clone()));
+    system.attach_config_reloader(config_reloader.clone());
+    let cluster_node = match config.cluster.enabled {
+        true => Some(system.attach_cluster(&config.cluster)?),
+        false => None,
+    };
+    let system = SharedSystem::new(system);
 
     // Workaround to ensure that the statistics are initialized before the server
     // loads streams and starts accepting connections. This is necessary to
@@ -58,24 +84,31 @@ async fn main() -> Result<(), ServerError> {
         .install_handler(SaveMessagesExecutor)
         .install_handler(MaintainMessagesExecutor)
         .install_handler(ArchiveStateExecutor)
+        .install_handler(SnapshotStateExecutor)
         .install_handler(CleanPersonalAccessTokensExecutor)
         .install_handler(SysInfoPrintExecutor)
-        .install_handler(VerifyHeartbeatsExecutor);
+        .install_handler(VerifyHeartbeatsExecutor)
+        .install_handler(AbortExpiredTransactionsExecutor)
+        .install_handler(ReencryptSegmentsExecutor);
 
     #[cfg(unix)]
-    let (mut ctrl_c, mut sigterm) = {
+    let (mut ctrl_c, mut sigterm, mut sighup) = {
         use tokio::signal::unix::{signal, SignalKind};
         (
             signal(SignalKind::interrupt())?,
             signal(SignalKind::terminate())?,
+            signal(SignalKind::hangup())?,
         )
     };
 
     let mut current_config = config.clone();
+    let mut http_addr = None;
+    let mut tcp_addr = None;
 
     if config.http.enabled {
-        let http_addr = http_server::start(config.http, system.clone()).await;
-        current_config.http.address = http_addr.to_string();
+        let addr = http_server::start(config.http, system.clone()).await;
+        current_config.http.address = addr.to_string();
+        http_addr = Some(addr);
     }
 
     if config.quic.enabled {
@@ -84,8 +117,16 @@ async fn main() -> Result<(), ServerError> {
     }
 
     if config.tcp.enabled {
-        let tcp_addr = tcp_server::start(config.tcp, system.clone()).await;
-        current_config.tcp.address = tcp_addr.to_string();
+        let addr = tcp_server::start(config.tcp, system.clone()).await;
+        current_config.tcp.address = addr.to_string();
+        tcp_addr = Some(addr);
+    }
+
+    if let Some(cluster_node) = cluster_node {
+        let cluster_addr = cluster_node
+            .start(system.clone(), tcp_addr, http_addr)
+            .await?;
+        current_config.cluster.address = cluster_addr.to_string();
     }
 
     let runtime_path = current_config.system.get_runtime_path();
@@ -100,23 +141,49 @@ async fn main() -> Result<(), ServerError> {
         elapsed_time.as_millis()
     );
 
+    let mut config_changes = config_reloader.subscribe();
+    let mut logging_level = config.system.logging.level.clone();
+
     #[cfg(unix)]
-    tokio::select! {
-        _ = ctrl_c.recv() => {
-            info!("Received SIGINT. Shutting down Iggy server...");
-        },
-        _ = sigterm.recv() => {
-            info!("Received SIGTERM. Shutting down Iggy server...");
+    loop {
+        tokio::select! {
+            _ = ctrl_c.recv() => {
+                info!("Received SIGINT. Shutting down Iggy server...");
+                break;
+            },
+            _ = sigterm.recv() => {
+                info!("Received SIGTERM. Shutting down Iggy server...");
+                break;
+            },
+            _ = sighup.recv() => {
+                info!("Received SIGHUP. Reloading the server configuration...");
+                if let Err(error) = config_reloader.reload().await {
+                    error!("Failed to reload the server configuration. Error: {error}");
+                }
+            },
+            config = config_changes.changed() => {
+                reload_logging_level(&logging, &mut logging_level, &config);
+            }
         }
     }
 
     #[cfg(windows)]
-    match tokio::signal::ctrl_c().await {
-        Ok(()) => {
-            info!("Received CTRL-C. Shutting down Iggy server...");
-        }
-        Err(err) => {
-            eprintln!("Unable to listen for shutdown signal: {}", err);
+    loop {
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                match result {
+                    Ok(()) => {
+                        info!("Received CTRL-C. Shutting down Iggy server...");
+                    }
+                    Err(err) => {
+                        eprintln!("Unable to listen for shutdown signal: {}", err);
+                    }
+                }
+                break;
+            },
+            config = config_changes.changed() => {
+                reload_logging_level(&logging, &mut logging_level, &config);
+            }
         }
     }
 
@@ -131,3 +198,14 @@ async fn main() -> Result<(), ServerError> {
     );
     Ok(())
 }
+
+fn reload_logging_level(logging: &Logging, logging_level: &mut String, config: &ReloadableConfig) {
+    if *logging_level == config.logging_level {
+        return;
+    }
+
+    match logging.reload_level(&config.logging_level) {
+        Ok(()) => *logging_level = config.logging_level.clone(),
+        Err(error) => error!("Failed to reload the log level. Error: {error}"),
+    }
+}
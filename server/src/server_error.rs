@@ -53,4 +53,12 @@ pub enum ServerError {
     InvalidS3Credentials,
     #[error("File to archive not found: {0}")]
     FileToArchiveNotFound(String),
+    #[error("Cannot restore file: {0}")]
+    CannotRestoreFile(String),
+    #[error("File to restore not found: {0}")]
+    FileToRestoreNotFound(String),
+    #[error("Cannot list archived files in directory: {0}")]
+    CannotListArchivedFiles(String),
+    #[error("Cannot restore archive: {0}")]
+    CannotRestoreArchive(String),
 }
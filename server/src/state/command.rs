@@ -1,12 +1,18 @@
-use crate::state::models::CreatePersonalAccessTokenWithHash;
+use crate::state::models::{
+    CreatePersonalAccessTokenWithHash, UpdatePartitionReplicas, UPDATE_PARTITION_REPLICAS_CODE,
+};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use iggy::bytes_serializable::BytesSerializable;
+use iggy::cluster::add_cluster_node::AddClusterNode;
+use iggy::cluster::remove_cluster_node::RemoveClusterNode;
 use iggy::command::{
-    Command, CHANGE_PASSWORD_CODE, CREATE_CONSUMER_GROUP_CODE, CREATE_PARTITIONS_CODE,
-    CREATE_PERSONAL_ACCESS_TOKEN_CODE, CREATE_STREAM_CODE, CREATE_TOPIC_CODE, CREATE_USER_CODE,
-    DELETE_CONSUMER_GROUP_CODE, DELETE_PARTITIONS_CODE, DELETE_PERSONAL_ACCESS_TOKEN_CODE,
-    DELETE_STREAM_CODE, DELETE_TOPIC_CODE, DELETE_USER_CODE, PURGE_STREAM_CODE, PURGE_TOPIC_CODE,
-    UPDATE_PERMISSIONS_CODE, UPDATE_STREAM_CODE, UPDATE_TOPIC_CODE, UPDATE_USER_CODE,
+    Command, ADD_CLUSTER_NODE_CODE, CHANGE_PASSWORD_CODE, CREATE_CONSUMER_GROUP_CODE,
+    CREATE_PARTITIONS_CODE, CREATE_PERSONAL_ACCESS_TOKEN_CODE, CREATE_STREAM_CODE,
+    CREATE_TOPIC_CODE, CREATE_USER_CODE, DELETE_CONSUMER_GROUP_CODE, DELETE_PARTITIONS_CODE,
+    DELETE_PERSONAL_ACCESS_TOKEN_CODE, DELETE_STREAM_CODE, DELETE_TOPIC_CODE, DELETE_USER_CODE,
+    PURGE_STREAM_CODE, PURGE_TOPIC_CODE, REMOVE_CLUSTER_NODE_CODE, SET_CLEANUP_POLICY_CODE,
+    SET_DEAD_LETTER_QUEUE_CODE, SET_MAX_TOPIC_MESSAGES_CODE, UPDATE_PERMISSIONS_CODE,
+    UPDATE_STREAM_CODE, UPDATE_TOPIC_CODE, UPDATE_USER_CODE,
 };
 use iggy::consumer_groups::create_consumer_group::CreateConsumerGroup;
 use iggy::consumer_groups::delete_consumer_group::DeleteConsumerGroup;
@@ -21,6 +27,9 @@ use iggy::streams::update_stream::UpdateStream;
 use iggy::topics::create_topic::CreateTopic;
 use iggy::topics::delete_topic::DeleteTopic;
 use iggy::topics::purge_topic::PurgeTopic;
+use iggy::topics::set_cleanup_policy::SetCleanupPolicy;
+use iggy::topics::set_dead_letter_queue::SetDeadLetterQueue;
+use iggy::topics::set_max_topic_messages::SetMaxTopicMessages;
 use iggy::topics::update_topic::UpdateTopic;
 use iggy::users::change_password::ChangePassword;
 use iggy::users::create_user::CreateUser;
@@ -39,6 +48,9 @@ pub enum EntryCommand {
     UpdateTopic(UpdateTopic),
     DeleteTopic(DeleteTopic),
     PurgeTopic(PurgeTopic),
+    SetDeadLetterQueue(SetDeadLetterQueue),
+    SetCleanupPolicy(SetCleanupPolicy),
+    SetMaxTopicMessages(SetMaxTopicMessages),
     CreatePartitions(CreatePartitions),
     DeletePartitions(DeletePartitions),
     CreateConsumerGroup(CreateConsumerGroup),
@@ -50,6 +62,9 @@ pub enum EntryCommand {
     UpdatePermissions(UpdatePermissions),
     CreatePersonalAccessToken(CreatePersonalAccessTokenWithHash),
     DeletePersonalAccessToken(DeletePersonalAccessToken),
+    AddClusterNode(AddClusterNode),
+    RemoveClusterNode(RemoveClusterNode),
+    UpdatePartitionReplicas(UpdatePartitionReplicas),
 }
 
 impl BytesSerializable for EntryCommand {
@@ -63,6 +78,9 @@ impl BytesSerializable for EntryCommand {
             EntryCommand::UpdateTopic(command) => (command.code(), command.to_bytes()),
             EntryCommand::DeleteTopic(command) => (command.code(), command.to_bytes()),
             EntryCommand::PurgeTopic(command) => (command.code(), command.to_bytes()),
+            EntryCommand::SetDeadLetterQueue(command) => (command.code(), command.to_bytes()),
+            EntryCommand::SetCleanupPolicy(command) => (command.code(), command.to_bytes()),
+            EntryCommand::SetMaxTopicMessages(command) => (command.code(), command.to_bytes()),
             EntryCommand::CreatePartitions(command) => (command.code(), command.to_bytes()),
             EntryCommand::DeletePartitions(command) => (command.code(), command.to_bytes()),
             EntryCommand::CreateConsumerGroup(command) => (command.code(), command.to_bytes()),
@@ -78,6 +96,9 @@ impl BytesSerializable for EntryCommand {
             EntryCommand::DeletePersonalAccessToken(command) => {
                 (command.code(), command.to_bytes())
             }
+            EntryCommand::AddClusterNode(command) => (command.code(), command.to_bytes()),
+            EntryCommand::RemoveClusterNode(command) => (command.code(), command.to_bytes()),
+            EntryCommand::UpdatePartitionReplicas(command) => (command.code(), command.to_bytes()),
         };
 
         let mut bytes = BytesMut::with_capacity(4 + 4 + command.len());
@@ -109,6 +130,15 @@ impl BytesSerializable for EntryCommand {
             UPDATE_TOPIC_CODE => Ok(EntryCommand::UpdateTopic(UpdateTopic::from_bytes(payload)?)),
             DELETE_TOPIC_CODE => Ok(EntryCommand::DeleteTopic(DeleteTopic::from_bytes(payload)?)),
             PURGE_TOPIC_CODE => Ok(EntryCommand::PurgeTopic(PurgeTopic::from_bytes(payload)?)),
+            SET_DEAD_LETTER_QUEUE_CODE => Ok(EntryCommand::SetDeadLetterQueue(
+                SetDeadLetterQueue::from_bytes(payload)?,
+            )),
+            SET_CLEANUP_POLICY_CODE => Ok(EntryCommand::SetCleanupPolicy(
+                SetCleanupPolicy::from_bytes(payload)?,
+            )),
+            SET_MAX_TOPIC_MESSAGES_CODE => Ok(EntryCommand::SetMaxTopicMessages(
+                SetMaxTopicMessages::from_bytes(payload)?,
+            )),
             CREATE_PARTITIONS_CODE => Ok(EntryCommand::CreatePartitions(
                 CreatePartitions::from_bytes(payload)?,
             )),
@@ -136,6 +166,15 @@ impl BytesSerializable for EntryCommand {
             DELETE_PERSONAL_ACCESS_TOKEN_CODE => Ok(EntryCommand::DeletePersonalAccessToken(
                 DeletePersonalAccessToken::from_bytes(payload)?,
             )),
+            ADD_CLUSTER_NODE_CODE => Ok(EntryCommand::AddClusterNode(AddClusterNode::from_bytes(
+                payload,
+            )?)),
+            REMOVE_CLUSTER_NODE_CODE => Ok(EntryCommand::RemoveClusterNode(
+                RemoveClusterNode::from_bytes(payload)?,
+            )),
+            UPDATE_PARTITION_REPLICAS_CODE => Ok(EntryCommand::UpdatePartitionReplicas(
+                UpdatePartitionReplicas::from_bytes(payload)?,
+            )),
             _ => Err(IggyError::InvalidCommand),
         }
     }
@@ -152,6 +191,13 @@ impl Display for EntryCommand {
             EntryCommand::UpdateTopic(command) => write!(f, "UpdateTopic({})", command),
             EntryCommand::DeleteTopic(command) => write!(f, "DeleteTopic({})", command),
             EntryCommand::PurgeTopic(command) => write!(f, "PurgeTopic({})", command),
+            EntryCommand::SetDeadLetterQueue(command) => {
+                write!(f, "SetDeadLetterQueue({})", command)
+            }
+            EntryCommand::SetCleanupPolicy(command) => write!(f, "SetCleanupPolicy({})", command),
+            EntryCommand::SetMaxTopicMessages(command) => {
+                write!(f, "SetMaxTopicMessages({})", command)
+            }
             EntryCommand::CreatePartitions(command) => write!(f, "CreatePartitions({})", command),
             EntryCommand::DeletePartitions(command) => write!(f, "DeletePartitions({})", command),
             EntryCommand::CreateConsumerGroup(command) => {
@@ -171,6 +217,11 @@ impl Display for EntryCommand {
             EntryCommand::DeletePersonalAccessToken(command) => {
                 write!(f, "DeletePersonalAccessToken({})", command)
             }
+            EntryCommand::AddClusterNode(command) => write!(f, "AddClusterNode({})", command),
+            EntryCommand::RemoveClusterNode(command) => write!(f, "RemoveClusterNode({})", command),
+            EntryCommand::UpdatePartitionReplicas(command) => {
+                write!(f, "UpdatePartitionReplicas({})", command)
+            }
         }
     }
 }
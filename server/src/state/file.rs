@@ -1,4 +1,6 @@
 use crate::state::command::EntryCommand;
+use crate::state::snapshot::StateSnapshot;
+use crate::state::system::SystemState;
 use crate::state::{State, StateEntry};
 use crate::streaming::persistence::persister::Persister;
 use crate::streaming::utils::file;
@@ -16,25 +18,32 @@ use std::path::Path;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::io::{AsyncReadExt, BufReader};
+use tokio::sync::Mutex;
 use tracing::{error, info};
 
 const BUF_READER_CAPACITY_BYTES: usize = 512 * 1000;
 
+/// The state log stored in the file, optionally compacted into the snapshot of the system state,
+/// in which case the log contains only the entries following the snapshot.
 #[derive(Debug)]
 pub struct FileState {
     current_index: AtomicU64,
     entries_count: AtomicU64,
+    snapshot_entries_count: AtomicU64,
     current_leader: AtomicU32,
     term: AtomicU64,
     version: u32,
     path: String,
+    snapshot_path: String,
     persister: Arc<dyn Persister>,
     encryptor: Option<Arc<dyn Encryptor>>,
+    append_lock: Mutex<()>,
 }
 
 impl FileState {
     pub fn new(
         path: &str,
+        snapshot_path: &str,
         version: &SemanticVersion,
         persister: Arc<dyn Persister>,
         encryptor: Option<Arc<dyn Encryptor>>,
@@ -42,9 +51,12 @@ impl FileState {
         Self {
             current_index: AtomicU64::new(0),
             entries_count: AtomicU64::new(0),
+            snapshot_entries_count: AtomicU64::new(0),
             current_leader: AtomicU32::new(0),
             term: AtomicU64::new(0),
             path: path.into(),
+            snapshot_path: snapshot_path.into(),
+            append_lock: Mutex::new(()),
             persister,
             encryptor,
             version: version.get_numeric_version().expect("Invalid version"),
@@ -55,13 +67,174 @@ impl FileState {
         self.current_index.load(Ordering::SeqCst)
     }
 
+    /// Returns the number of all the entries, including the ones compacted into the snapshot.
     pub fn entries_count(&self) -> u64 {
         self.entries_count.load(Ordering::SeqCst)
     }
 
+    /// Returns the number of the entries compacted into the snapshot.
+    pub fn snapshot_entries_count(&self) -> u64 {
+        self.snapshot_entries_count.load(Ordering::SeqCst)
+    }
+
     pub fn term(&self) -> u64 {
         self.term.load(Ordering::SeqCst)
     }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Truncates the state log, removing the entries issued after the given timestamp,
+    /// e.g. to restore the state from the archive to the point in time. Returns the number of the retained entries.
+    pub async fn truncate(&self, timestamp: IggyTimestamp) -> Result<u64, IggyError> {
+        let (position, entries_count, file_size) = self
+            .find_position(|_, entry_timestamp| entry_timestamp > timestamp.as_micros())
+            .await?;
+        if position < file_size {
+            info!(
+                "Truncating state log to: {entries_count} entries issued until: {timestamp}, removing: {} bytes.",
+                file_size - position
+            );
+            file::overwrite(&self.path).await?.set_len(position).await?;
+        }
+        Ok(entries_count)
+    }
+
+    /// Truncates the state log to the given number of entries,
+    /// e.g. to remove the uncommitted entries conflicting with the ones replicated by the cluster leader.
+    pub async fn truncate_entries(&self, entries_count: u64) -> Result<(), IggyError> {
+        let (position, retained_entries_count, file_size) = self
+            .find_position(|index, _| index >= entries_count)
+            .await?;
+        if position < file_size {
+            info!(
+                "Truncating state log to: {retained_entries_count} entries, removing: {} bytes.",
+                file_size - position
+            );
+            file::overwrite(&self.path).await?.set_len(position).await?;
+        }
+
+        self.entries_count
+            .store(retained_entries_count, Ordering::SeqCst);
+        self.current_index
+            .store(retained_entries_count.saturating_sub(1), Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Returns the position of the first entry matching the predicate (index and timestamp),
+    /// the number of the entries preceding it and the size of the state file.
+    async fn find_position(
+        &self,
+        predicate: impl Fn(u64, u64) -> bool,
+    ) -> Result<(u64, u64, u64), IggyError> {
+        if !Path::new(&self.path).exists() {
+            return Err(IggyError::StateFileNotFound);
+        }
+
+        let file = file::open(&self.path).await?;
+        let file_size = file.metadata().await?.len();
+        let mut reader = BufReader::with_capacity(BUF_READER_CAPACITY_BYTES, file);
+        let mut position = 0;
+        let mut entries_count = 0;
+        while position < file_size {
+            let entry_index = reader.read_u64_le().await?;
+            // Term, leader ID, version and flags precede the timestamp of the entry.
+            let mut header = [0; 8 + 4 + 4 + 8];
+            reader.read_exact(&mut header).await?;
+            let entry_timestamp = reader.read_u64_le().await?;
+            if predicate(entry_index, entry_timestamp) {
+                break;
+            }
+
+            let _user_id = reader.read_u32_le().await?;
+            let _checksum = reader.read_u32_le().await?;
+            let context_length = reader.read_u32_le().await?;
+            let mut context = vec![0; context_length as usize];
+            reader.read_exact(&mut context).await?;
+            let _code = reader.read_u32_le().await?;
+            let command_length = reader.read_u32_le().await?;
+            let mut command = vec![0; command_length as usize];
+            reader.read_exact(&mut command).await?;
+            position += 8
+                + header.len() as u64
+                + 8
+                + 4
+                + 4
+                + 4
+                + context_length as u64
+                + 4
+                + 4
+                + command_length as u64;
+            entries_count += 1;
+        }
+
+        Ok((position, entries_count, file_size))
+    }
+
+    /// Creates the entry with the checksum calculated over its plain (not encrypted) command.
+    pub fn create_entry(
+        &self,
+        index: u64,
+        term: u64,
+        leader_id: u32,
+        user_id: u32,
+        command: &EntryCommand,
+    ) -> StateEntry {
+        let timestamp = IggyTimestamp::now();
+        let flags = 0;
+        let context = Bytes::new();
+        let command = command.to_bytes();
+        let checksum = StateEntry::calculate_checksum(
+            index,
+            term,
+            leader_id,
+            self.version,
+            flags,
+            timestamp,
+            user_id,
+            &context,
+            &command,
+        );
+        StateEntry::new(
+            index,
+            term,
+            leader_id,
+            self.version,
+            flags,
+            timestamp,
+            user_id,
+            checksum,
+            context,
+            command,
+        )
+    }
+
+    /// Appends the entry to the state log, the command is encrypted if the encryption is enabled.
+    pub async fn append(&self, entry: &StateEntry) -> Result<(), IggyError> {
+        let mut entry = entry.clone();
+        if let Some(encryptor) = &self.encryptor {
+            debug!("Encrypting state entry command with index: {}", entry.index);
+            let command_code = entry.command.slice(0..4).get_u32_le();
+            let mut command_length = entry.command.slice(4..8).get_u32_le() as usize;
+            let command_payload = entry.command.slice(8..8 + command_length);
+            let encrypted_command_payload = encryptor.encrypt(&command_payload)?;
+            command_length = encrypted_command_payload.len();
+            let mut command_bytes = BytesMut::with_capacity(4 + 4 + command_length);
+            command_bytes.put_u32_le(command_code);
+            command_bytes.put_u32_le(command_length as u32);
+            command_bytes.extend(encrypted_command_payload);
+            entry.command = command_bytes.freeze();
+        }
+
+        let bytes = entry.to_bytes();
+        let _append_lock = self.append_lock.lock().await;
+        self.entries_count.fetch_add(1, Ordering::SeqCst);
+        self.current_index.fetch_max(entry.index, Ordering::SeqCst);
+        self.persister.append(&self.path, &bytes).await?;
+        debug!("Appended state entry: {entry}");
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -72,16 +245,23 @@ impl State for FileState {
             self.persister.overwrite(&self.path, &[]).await?;
         }
 
+        let snapshot_entries_count = match self.load_snapshot().await? {
+            Some(snapshot) => {
+                info!("Loaded state snapshot: {snapshot}");
+                snapshot.entries_count()
+            }
+            None => 0,
+        };
+        self.snapshot_entries_count
+            .store(snapshot_entries_count, Ordering::SeqCst);
         let entries = self.load_entries().await?;
-        let entries_count = entries.len() as u64;
+        let entries_count = snapshot_entries_count + entries.len() as u64;
         self.entries_count.store(entries_count, Ordering::SeqCst);
-        if entries_count == 0 {
-            self.current_index.store(0, Ordering::SeqCst);
-        } else {
-            let last_index = entries[entries_count as usize - 1].index;
-            self.current_index.store(last_index, Ordering::SeqCst);
-        }
-
+        let current_index = match entries.last() {
+            Some(entry) => entry.index,
+            None => snapshot_entries_count.saturating_sub(1),
+        };
+        self.current_index.store(current_index, Ordering::SeqCst);
         return Ok(entries);
     }
 
@@ -101,6 +281,7 @@ impl State for FileState {
             "Loading state, file size: {}",
             IggyByteSize::from(file_size).as_human_string()
         );
+        let snapshot_entries_count = self.snapshot_entries_count.load(Ordering::SeqCst);
         let mut entries = Vec::new();
         let mut total_size: u64 = 0;
         let mut reader = BufReader::with_capacity(BUF_READER_CAPACITY_BYTES, file);
@@ -188,19 +369,37 @@ impl State for FileState {
                 ));
             }
 
-            entries.push(entry);
+            // The entries already included in the snapshot remain in the log, if it couldn't be truncated after taking the snapshot.
+            if index < snapshot_entries_count {
+                debug!("Skipping state entry with index: {index} included in the snapshot.");
+            } else {
+                entries.push(entry);
+            }
+
             if total_size == file_size {
                 break;
             }
         }
 
-        info!("Loaded {entries_count} state entries, current index: {current_index}");
+        if let Some(entry) = entries.first() {
+            if snapshot_entries_count > 0 && entry.index != snapshot_entries_count {
+                error!(
+                    "State file is corrupted, expected index: {snapshot_entries_count} following the snapshot, got: {}",
+                    entry.index
+                );
+                return Err(IggyError::StateFileCorrupted);
+            }
+        }
+
+        info!(
+            "Loaded {} state entries, current index: {current_index}",
+            entries.len()
+        );
         Ok(entries)
     }
 
     async fn apply(&self, user_id: u32, command: EntryCommand) -> Result<(), IggyError> {
         debug!("Applying state entry with command: {command}, user ID: {user_id}");
-        let timestamp = IggyTimestamp::now();
         let index = if self.entries_count.load(Ordering::SeqCst) == 0 {
             0
         } else {
@@ -208,52 +407,57 @@ impl State for FileState {
         };
         let term = self.term.load(Ordering::SeqCst);
         let current_leader = self.current_leader.load(Ordering::SeqCst);
-        let version = self.version;
-        let flags = 0;
-        let context = Bytes::new();
-        let mut command = command.to_bytes();
-        let checksum = StateEntry::calculate_checksum(
-            index,
-            term,
-            current_leader,
-            version,
-            flags,
-            timestamp,
-            user_id,
-            &context,
-            &command,
-        );
+        let entry = self.create_entry(index, term, current_leader, user_id, &command);
+        self.append(&entry).await?;
+        debug!("Applied state entry: {entry}");
+        Ok(())
+    }
 
-        if let Some(encryptor) = &self.encryptor {
-            debug!("Encrypting state entry command with index: {index}");
-            let command_code = command.slice(0..4).get_u32_le();
-            let mut command_length = command.slice(4..8).get_u32_le() as usize;
-            let command_payload = command.slice(8..8 + command_length);
-            let encrypted_command_payload = encryptor.encrypt(&command_payload)?;
-            command_length = encrypted_command_payload.len();
-            let mut command_bytes = BytesMut::with_capacity(4 + 4 + command_length);
-            command_bytes.put_u32_le(command_code);
-            command_bytes.put_u32_le(command_length as u32);
-            command_bytes.extend(encrypted_command_payload);
-            command = command_bytes.freeze();
+    async fn load_snapshot(&self) -> Result<Option<StateSnapshot>, IggyError> {
+        if !Path::new(&self.snapshot_path).exists() {
+            return Ok(None);
         }
 
-        let entry = StateEntry::new(
-            index,
-            term,
-            current_leader,
-            version,
-            flags,
-            timestamp,
-            user_id,
-            checksum,
-            context,
-            command,
+        let bytes = tokio::fs::read(&self.snapshot_path).await?;
+        let snapshot = StateSnapshot::from_bytes(Bytes::from(bytes), self.encryptor.as_deref())?;
+        debug!("Read state snapshot: {snapshot}");
+        Ok(Some(snapshot))
+    }
+
+    async fn snapshot(&self, min_entries: u64) -> Result<Option<u64>, IggyError> {
+        // No entries can be appended in the meantime, as all of them are removed from the log once the snapshot is taken.
+        let _append_lock = self.append_lock.lock().await;
+        let snapshot_entries_count = self.snapshot_entries_count.load(Ordering::SeqCst);
+        let appended_entries_count =
+            self.entries_count.load(Ordering::SeqCst) - snapshot_entries_count;
+        if appended_entries_count == 0 || appended_entries_count < min_entries {
+            debug!("Skipping state snapshot, {appended_entries_count} entries appended since the last one.");
+            return Ok(None);
+        }
+
+        let entries = self.load_entries().await?;
+        let Some(last_entry) = entries.last() else {
+            return Ok(None);
+        };
+
+        let (index, term, timestamp) = (last_entry.index, last_entry.term, last_entry.timestamp);
+        let state = match self.load_snapshot().await? {
+            Some(snapshot) => snapshot.state.replay(entries).await?,
+            None => SystemState::init(entries).await?,
+        };
+        let snapshot = StateSnapshot::new(index, term, self.version, timestamp, state);
+        let bytes = snapshot.to_bytes(self.encryptor.as_deref())?;
+        // The snapshot is written to the temporary file first and then renamed, so that it's replaced atomically.
+        let temporary_path = format!("{}.tmp", self.snapshot_path);
+        self.persister.overwrite(&temporary_path, &bytes).await?;
+        file::rename(&temporary_path, &self.snapshot_path).await?;
+        self.snapshot_entries_count
+            .store(snapshot.entries_count(), Ordering::SeqCst);
+        self.persister.overwrite(&self.path, &[]).await?;
+        info!(
+            "Created state snapshot: {snapshot}, size: {}, removed {appended_entries_count} entries from the state log.",
+            IggyByteSize::from(bytes.len() as u64).as_human_string()
         );
-        let bytes = entry.to_bytes();
-        self.entries_count.fetch_add(1, Ordering::SeqCst);
-        self.persister.append(&self.path, &bytes).await?;
-        debug!("Applied state entry: {entry}");
-        Ok(())
+        Ok(Some(index))
     }
 }
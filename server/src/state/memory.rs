@@ -0,0 +1,75 @@
+use crate::state::command::EntryCommand;
+use crate::state::{State, StateEntry};
+use crate::versioning::SemanticVersion;
+use async_trait::async_trait;
+use bytes::Bytes;
+use iggy::bytes_serializable::BytesSerializable;
+use iggy::error::IggyError;
+use iggy::utils::timestamp::IggyTimestamp;
+use log::debug;
+use std::sync::Mutex;
+
+/// The state kept in memory by the in-memory storage backend, it starts empty and is lost on shutdown.
+#[derive(Debug)]
+pub struct MemoryState {
+    version: u32,
+    entries: Mutex<Vec<StateEntry>>,
+}
+
+impl MemoryState {
+    pub fn new(version: &SemanticVersion) -> Self {
+        Self {
+            version: version.get_numeric_version().expect("Invalid version"),
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl State for MemoryState {
+    async fn init(&self) -> Result<Vec<StateEntry>, IggyError> {
+        self.load_entries().await
+    }
+
+    async fn load_entries(&self) -> Result<Vec<StateEntry>, IggyError> {
+        Ok(self.entries.lock().unwrap().clone())
+    }
+
+    async fn apply(&self, user_id: u32, command: EntryCommand) -> Result<(), IggyError> {
+        debug!("Applying state entry with command: {command}, user ID: {user_id}");
+        let timestamp = IggyTimestamp::now();
+        let term = 0;
+        let leader_id = 0;
+        let flags = 0;
+        let context = Bytes::new();
+        let command = command.to_bytes();
+        let mut entries = self.entries.lock().unwrap();
+        let index = entries.len() as u64;
+        let checksum = StateEntry::calculate_checksum(
+            index,
+            term,
+            leader_id,
+            self.version,
+            flags,
+            timestamp,
+            user_id,
+            &context,
+            &command,
+        );
+        let entry = StateEntry::new(
+            index,
+            term,
+            leader_id,
+            self.version,
+            flags,
+            timestamp,
+            user_id,
+            checksum,
+            context,
+            command,
+        );
+        debug!("Applied state entry: {entry}");
+        entries.push(entry);
+        Ok(())
+    }
+}
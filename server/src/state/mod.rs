@@ -1,5 +1,6 @@
 use crate::state::command::EntryCommand;
 use crate::state::entry::StateEntry;
+use crate::state::snapshot::StateSnapshot;
 use async_trait::async_trait;
 use iggy::error::IggyError;
 use std::fmt::Debug;
@@ -7,7 +8,9 @@ use std::fmt::Debug;
 pub mod command;
 pub mod entry;
 pub mod file;
+pub mod memory;
 pub mod models;
+pub mod snapshot;
 pub mod system;
 
 #[async_trait]
@@ -15,4 +18,15 @@ pub trait State: Send + Sync + Debug {
     async fn init(&self) -> Result<Vec<StateEntry>, IggyError>;
     async fn load_entries(&self) -> Result<Vec<StateEntry>, IggyError>;
     async fn apply(&self, user_id: u32, command: EntryCommand) -> Result<(), IggyError>;
+
+    /// Loads the latest snapshot of the state, the entries returned by `init` and `load_entries` follow it.
+    async fn load_snapshot(&self) -> Result<Option<StateSnapshot>, IggyError> {
+        Ok(None)
+    }
+
+    /// Takes the snapshot of the state if at least the given number of entries has been appended since the last one,
+    /// then removes the entries included in it from the log. Returns the snapshot index, if it was taken.
+    async fn snapshot(&self, _min_entries: u64) -> Result<Option<u64>, IggyError> {
+        Ok(None)
+    }
 }
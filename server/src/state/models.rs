@@ -1,4 +1,4 @@
-use bytes::{BufMut, Bytes, BytesMut};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use iggy::bytes_serializable::BytesSerializable;
 use iggy::command::Command;
 use iggy::error::IggyError;
@@ -64,3 +64,143 @@ impl Display for CreatePersonalAccessTokenWithHash {
         )
     }
 }
+
+/// The code of the state entry command which is never sent by the clients, but proposed by the cluster leader.
+pub const UPDATE_PARTITION_REPLICAS_CODE: u32 = 810;
+
+/// Assigns the replicas of the partition (the cluster nodes storing its messages), along with the leader
+/// accepting the messages and the in-sync replicas which have fetched all the messages acknowledged by the leader.
+/// The leader epoch is incremented each time the leader changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdatePartitionReplicas {
+    pub stream_id: u32,
+    pub topic_id: u32,
+    pub partition_id: u32,
+    pub leader_id: u32,
+    pub leader_epoch: u32,
+    pub replicas: Vec<u32>,
+    pub in_sync_replicas: Vec<u32>,
+}
+
+impl Validatable<IggyError> for UpdatePartitionReplicas {
+    fn validate(&self) -> Result<(), IggyError> {
+        if self.replicas.is_empty()
+            || !self.replicas.contains(&self.leader_id)
+            || !self.in_sync_replicas.contains(&self.leader_id)
+            || self
+                .in_sync_replicas
+                .iter()
+                .any(|replica| !self.replicas.contains(replica))
+        {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        Ok(())
+    }
+}
+
+impl Command for UpdatePartitionReplicas {
+    fn code(&self) -> u32 {
+        UPDATE_PARTITION_REPLICAS_CODE
+    }
+}
+
+impl BytesSerializable for UpdatePartitionReplicas {
+    fn to_bytes(&self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(
+            4 * 5 + 4 * 2 + 4 * (self.replicas.len() + self.in_sync_replicas.len()),
+        );
+        bytes.put_u32_le(self.stream_id);
+        bytes.put_u32_le(self.topic_id);
+        bytes.put_u32_le(self.partition_id);
+        bytes.put_u32_le(self.leader_id);
+        bytes.put_u32_le(self.leader_epoch);
+        for replicas in [&self.replicas, &self.in_sync_replicas] {
+            bytes.put_u32_le(replicas.len() as u32);
+            for replica in replicas {
+                bytes.put_u32_le(*replica);
+            }
+        }
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<Self, IggyError>
+    where
+        Self: Sized,
+    {
+        let mut bytes = bytes;
+        if bytes.remaining() < 4 * 5 + 4 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let stream_id = bytes.get_u32_le();
+        let topic_id = bytes.get_u32_le();
+        let partition_id = bytes.get_u32_le();
+        let leader_id = bytes.get_u32_le();
+        let leader_epoch = bytes.get_u32_le();
+        let mut replicas_lists = Vec::with_capacity(2);
+        for _ in 0..2 {
+            if bytes.remaining() < 4 {
+                return Err(IggyError::InvalidCommand);
+            }
+
+            let count = bytes.get_u32_le() as usize;
+            if bytes.remaining() < 4 * count {
+                return Err(IggyError::InvalidCommand);
+            }
+
+            replicas_lists.push((0..count).map(|_| bytes.get_u32_le()).collect::<Vec<_>>());
+        }
+
+        let in_sync_replicas = replicas_lists.pop().unwrap_or_default();
+        let replicas = replicas_lists.pop().unwrap_or_default();
+        Ok(Self {
+            stream_id,
+            topic_id,
+            partition_id,
+            leader_id,
+            leader_epoch,
+            replicas,
+            in_sync_replicas,
+        })
+    }
+}
+
+impl Display for UpdatePartitionReplicas {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UpdatePartitionReplicas {{ stream_id: {}, topic_id: {}, partition_id: {}, leader_id: {}, leader_epoch: {}, replicas: {:?}, in_sync_replicas: {:?} }}",
+            self.stream_id,
+            self.topic_id,
+            self.partition_id,
+            self.leader_id,
+            self.leader_epoch,
+            self.replicas,
+            self.in_sync_replicas
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_partition_replicas_should_be_serialized_and_deserialized() {
+        let command = UpdatePartitionReplicas {
+            stream_id: 1,
+            topic_id: 2,
+            partition_id: 3,
+            leader_id: 2,
+            leader_epoch: 5,
+            replicas: vec![1, 2, 3],
+            in_sync_replicas: vec![2, 3],
+        };
+
+        let deserialized_command = UpdatePartitionReplicas::from_bytes(command.to_bytes()).unwrap();
+
+        assert!(command.validate().is_ok());
+        assert_eq!(deserialized_command, command);
+    }
+}
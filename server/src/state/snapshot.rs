@@ -0,0 +1,132 @@
+use crate::state::system::SystemState;
+use anyhow::Context;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use iggy::error::IggyError;
+use iggy::utils::checksum;
+use iggy::utils::crypto::Encryptor;
+use iggy::utils::timestamp::IggyTimestamp;
+use std::fmt::{Display, Formatter};
+
+const HEADER_LENGTH: usize = 8 + 8 + 4 + 8 + 4 + 4;
+
+/// Snapshot of the system state materialized from the state log entries up to (including) the index.
+/// - `index` - Index of the last entry included in the snapshot
+/// - `term` - Election term of the last entry included in the snapshot
+/// - `version` - Server version based on semver as number e.g. 1.234.567 -> 1234567
+/// - `timestamp` - Timestamp of the last entry included in the snapshot
+/// - `state` - The materialized system state
+#[derive(Debug)]
+pub struct StateSnapshot {
+    pub index: u64,
+    pub term: u64,
+    pub version: u32,
+    pub timestamp: IggyTimestamp,
+    pub state: SystemState,
+}
+
+impl StateSnapshot {
+    pub fn new(
+        index: u64,
+        term: u64,
+        version: u32,
+        timestamp: IggyTimestamp,
+        state: SystemState,
+    ) -> Self {
+        Self {
+            index,
+            term,
+            version,
+            timestamp,
+            state,
+        }
+    }
+
+    /// The number of the state log entries included in the snapshot.
+    pub fn entries_count(&self) -> u64 {
+        self.index + 1
+    }
+
+    pub fn calculate_checksum(
+        index: u64,
+        term: u64,
+        version: u32,
+        timestamp: IggyTimestamp,
+        state: &[u8],
+    ) -> u32 {
+        let mut bytes = BytesMut::with_capacity(8 + 8 + 4 + 8 + state.len());
+        bytes.put_u64_le(index);
+        bytes.put_u64_le(term);
+        bytes.put_u32_le(version);
+        bytes.put_u64_le(timestamp.into());
+        bytes.put_slice(state);
+        checksum::calculate(&bytes.freeze())
+    }
+
+    /// Serializes the snapshot, the checksum is calculated over the plain (not encrypted) state.
+    pub fn to_bytes(&self, encryptor: Option<&dyn Encryptor>) -> Result<Bytes, IggyError> {
+        let state = rmp_serde::to_vec_named(&self.state)
+            .with_context(|| "Failed to serialize state snapshot")
+            .map_err(IggyError::CannotSerializeResource)?;
+        let checksum =
+            Self::calculate_checksum(self.index, self.term, self.version, self.timestamp, &state);
+        let state = match encryptor {
+            Some(encryptor) => encryptor.encrypt(&state)?,
+            None => state,
+        };
+        let mut bytes = BytesMut::with_capacity(HEADER_LENGTH + state.len());
+        bytes.put_u64_le(self.index);
+        bytes.put_u64_le(self.term);
+        bytes.put_u32_le(self.version);
+        bytes.put_u64_le(self.timestamp.into());
+        bytes.put_u32_le(checksum);
+        bytes.put_u32_le(state.len() as u32);
+        bytes.put_slice(&state);
+        Ok(bytes.freeze())
+    }
+
+    pub fn from_bytes(bytes: Bytes, encryptor: Option<&dyn Encryptor>) -> Result<Self, IggyError> {
+        if bytes.len() < HEADER_LENGTH {
+            return Err(IggyError::StateFileCorrupted);
+        }
+
+        let mut header = bytes.slice(..HEADER_LENGTH);
+        let index = header.get_u64_le();
+        let term = header.get_u64_le();
+        let version = header.get_u32_le();
+        let timestamp = IggyTimestamp::from(header.get_u64_le());
+        let checksum = header.get_u32_le();
+        let length = header.get_u32_le() as usize;
+        if bytes.len() != HEADER_LENGTH + length {
+            return Err(IggyError::StateFileCorrupted);
+        }
+
+        let state = bytes.slice(HEADER_LENGTH..);
+        let state = match encryptor {
+            Some(encryptor) => Bytes::from(encryptor.decrypt(&state)?),
+            None => state,
+        };
+        let calculated_checksum = Self::calculate_checksum(index, term, version, timestamp, &state);
+        if calculated_checksum != checksum {
+            return Err(IggyError::InvalidStateSnapshotChecksum(
+                calculated_checksum,
+                checksum,
+                index,
+            ));
+        }
+
+        let state = rmp_serde::from_slice(&state)
+            .with_context(|| "Failed to deserialize state snapshot")
+            .map_err(IggyError::CannotDeserializeResource)?;
+        Ok(Self::new(index, term, version, timestamp, state))
+    }
+}
+
+impl Display for StateSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "StateSnapshot {{ index: {}, term: {}, version: {}, timestamp: {} }}",
+            self.index, self.term, self.version, self.timestamp,
+        )
+    }
+}
@@ -1,24 +1,31 @@
-use crate::state::{EntryCommand, StateEntry};
+use crate::state::{EntryCommand, State, StateEntry};
 use crate::streaming::personal_access_tokens::personal_access_token::PersonalAccessToken;
 use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::error::IggyError;
 use iggy::identifier::{IdKind, Identifier};
+use iggy::models::cleanup_policy::CleanupPolicy;
+use iggy::models::dead_letter_queue::DeadLetterQueue;
 use iggy::models::permissions::Permissions;
 use iggy::models::user_status::UserStatus;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
 use iggy::utils::topic_size::MaxTopicSize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Display;
 use tracing::debug;
 
-#[derive(Debug)]
+/// The system state materialized from the state log entries, it's also stored as the snapshot of the state.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SystemState {
     pub streams: HashMap<u32, StreamState>,
     pub users: HashMap<u32, UserState>,
+    current_stream_id: u32,
+    current_user_id: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct StreamState {
     pub id: u32,
     pub name: String,
@@ -27,7 +34,7 @@ pub struct StreamState {
     pub current_topic_id: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TopicState {
     pub id: u32,
     pub name: String,
@@ -37,24 +44,27 @@ pub struct TopicState {
     pub message_expiry: IggyExpiry,
     pub max_topic_size: MaxTopicSize,
     pub replication_factor: Option<u8>,
+    pub dead_letter_queue: Option<DeadLetterQueue>,
+    pub cleanup_policy: CleanupPolicy,
+    pub max_messages: u64,
     pub created_at: IggyTimestamp,
     pub current_consumer_group_id: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PartitionState {
     pub id: u32,
     pub created_at: IggyTimestamp,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PersonalAccessTokenState {
     pub name: String,
     pub token_hash: String,
     pub expiry_at: Option<IggyTimestamp>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UserState {
     pub id: u32,
     pub username: String,
@@ -64,18 +74,35 @@ pub struct UserState {
     pub personal_access_tokens: HashMap<String, PersonalAccessTokenState>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ConsumerGroupState {
     pub id: u32,
     pub name: String,
+    pub mode: ConsumerGroupMode,
 }
 
 impl SystemState {
+    /// Loads the latest snapshot of the state (if any) and replays the following entries on top of it.
+    pub async fn load(state: &dyn State) -> Result<Self, IggyError> {
+        let entries = state.init().await?;
+        match state.load_snapshot().await? {
+            Some(snapshot) => snapshot.state.replay(entries).await,
+            None => Self::init(entries).await,
+        }
+    }
+
     pub async fn init(entries: Vec<StateEntry>) -> Result<Self, IggyError> {
-        let mut streams = HashMap::new();
-        let mut users = HashMap::new();
-        let mut current_stream_id = 0;
-        let mut current_user_id = 0;
+        Self::default().replay(entries).await
+    }
+
+    /// Applies the entries to the state, e.g. the ones following its snapshot.
+    pub async fn replay(self, entries: Vec<StateEntry>) -> Result<Self, IggyError> {
+        let SystemState {
+            mut streams,
+            mut users,
+            mut current_stream_id,
+            mut current_user_id,
+        } = self;
         for entry in entries {
             debug!("Processing state entry: {entry}",);
             match entry.command()? {
@@ -129,6 +156,9 @@ impl SystemState {
                         message_expiry: command.message_expiry,
                         max_topic_size: command.max_topic_size,
                         replication_factor: command.replication_factor,
+                        dead_letter_queue: None,
+                        cleanup_policy: CleanupPolicy::default(),
+                        max_messages: 0,
                         created_at: entry.timestamp,
                         partitions: if command.partitions_count > 0 {
                             let mut partitions = HashMap::new();
@@ -184,6 +214,42 @@ impl SystemState {
                         .unwrap_or_else(|| panic!("{}", format!("Topic: {topic_id} not found")));
                     // It only affects the segments which are not part of the state
                 }
+                EntryCommand::SetDeadLetterQueue(command) => {
+                    let stream_id = find_stream_id(&streams, &command.stream_id);
+                    let stream = streams
+                        .get_mut(&stream_id)
+                        .unwrap_or_else(|| panic!("{}", format!("Stream: {stream_id} not found")));
+                    let topic_id = find_topic_id(&stream.topics, &command.topic_id);
+                    let topic = stream
+                        .topics
+                        .get_mut(&topic_id)
+                        .unwrap_or_else(|| panic!("{}", format!("Topic: {topic_id} not found")));
+                    topic.dead_letter_queue = command.dead_letter_queue;
+                }
+                EntryCommand::SetCleanupPolicy(command) => {
+                    let stream_id = find_stream_id(&streams, &command.stream_id);
+                    let stream = streams
+                        .get_mut(&stream_id)
+                        .unwrap_or_else(|| panic!("{}", format!("Stream: {stream_id} not found")));
+                    let topic_id = find_topic_id(&stream.topics, &command.topic_id);
+                    let topic = stream
+                        .topics
+                        .get_mut(&topic_id)
+                        .unwrap_or_else(|| panic!("{}", format!("Topic: {topic_id} not found")));
+                    topic.cleanup_policy = command.cleanup_policy;
+                }
+                EntryCommand::SetMaxTopicMessages(command) => {
+                    let stream_id = find_stream_id(&streams, &command.stream_id);
+                    let stream = streams
+                        .get_mut(&stream_id)
+                        .unwrap_or_else(|| panic!("{}", format!("Stream: {stream_id} not found")));
+                    let topic_id = find_topic_id(&stream.topics, &command.topic_id);
+                    let topic = stream
+                        .topics
+                        .get_mut(&topic_id)
+                        .unwrap_or_else(|| panic!("{}", format!("Topic: {topic_id} not found")));
+                    topic.max_messages = command.max_messages;
+                }
                 EntryCommand::CreatePartitions(command) => {
                     let stream_id = find_stream_id(&streams, &command.stream_id);
                     let stream = streams
@@ -255,6 +321,7 @@ impl SystemState {
                     let consumer_group = ConsumerGroupState {
                         id: consumer_group_id,
                         name: command.name,
+                        mode: command.mode,
                     };
                     topic
                         .consumer_groups
@@ -349,10 +416,19 @@ impl SystemState {
                         .unwrap_or_else(|| panic!("{}", format!("User: {user_id} not found")));
                     user.personal_access_tokens.remove(&command.name);
                 }
+                // The cluster membership and the partitions replicas are restored from the state log by the cluster node.
+                EntryCommand::AddClusterNode(_)
+                | EntryCommand::RemoveClusterNode(_)
+                | EntryCommand::UpdatePartitionReplicas(_) => {}
             }
         }
 
-        let state = SystemState { streams, users };
+        let state = SystemState {
+            streams,
+            users,
+            current_stream_id,
+            current_user_id,
+        };
         debug!("+++ State +++");
         debug!("{state}");
         debug!("+++ State +++");
@@ -450,7 +526,11 @@ impl Display for SystemState {
 
 impl Display for ConsumerGroupState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "ConsumerGroup -> ID: {}, Name: {}", self.id, self.name)
+        write!(
+            f,
+            "ConsumerGroup -> ID: {}, Name: {}, Mode: {}",
+            self.id, self.name, self.mode
+        )
     }
 }
 
@@ -2,6 +2,13 @@ use crate::streaming::batching::batch_filter::BatchItemizer;
 use crate::streaming::batching::iterator::IntoMessagesIterator;
 use crate::streaming::models::messages::RetainedMessage;
 use bytes::{BufMut, Bytes, BytesMut};
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::error::IggyError;
+use iggy::messages::send_messages::CompressedMessages;
+use iggy::models::batch_attributes::{
+    split_producer_compressed_payload, BatchAttributes, BATCH_ATTRIBUTES_MARKER,
+    BATCH_ATTRIBUTES_PREFIX_SIZE, PRODUCER_COMPRESSED_TIMESTAMP_SIZE,
+};
 
 pub const RETAINED_BATCH_OVERHEAD: u32 = 8 + 8 + 4 + 4;
 
@@ -32,6 +39,37 @@ impl RetainedMessageBatch {
         }
     }
 
+    /// Creates the batch from the messages compressed by the producer, which are stored as they are,
+    /// preceded by the timestamps assigned to each of them. The offsets are assigned once the batch is decompressed.
+    pub fn from_compressed_messages(
+        base_offset: u64,
+        timestamps: &[u64],
+        compressed_messages: &CompressedMessages,
+    ) -> Self {
+        let attributes = BatchAttributes {
+            compression_algorithm: compressed_messages.compression_algorithm,
+            producer_compressed: true,
+        };
+        let mut bytes = BytesMut::with_capacity(
+            BATCH_ATTRIBUTES_PREFIX_SIZE
+                + timestamps.len() * PRODUCER_COMPRESSED_TIMESTAMP_SIZE
+                + compressed_messages.payload.len(),
+        );
+        bytes.put_u32_le(BATCH_ATTRIBUTES_MARKER);
+        bytes.put_u8(attributes.as_code());
+        for timestamp in timestamps {
+            bytes.put_u64_le(*timestamp);
+        }
+        bytes.put_slice(&compressed_messages.payload);
+        RetainedMessageBatch::new(
+            base_offset,
+            compressed_messages.messages_count - 1,
+            timestamps.iter().copied().max().unwrap_or_default(),
+            bytes.len() as u32,
+            bytes.freeze(),
+        )
+    }
+
     pub fn is_contained_or_overlapping_within_offset_range(
         &self,
         start_offset: u64,
@@ -46,6 +84,93 @@ impl RetainedMessageBatch {
         self.base_offset + self.last_offset_delta as u64
     }
 
+    pub fn attributes(&self) -> Result<BatchAttributes, IggyError> {
+        BatchAttributes::from_payload(&self.bytes)
+    }
+
+    /// Compresses the batch payload using the provided algorithm. The plain batch is returned
+    /// if the compression is disabled or it wouldn't reduce the size of the payload.
+    pub fn compress(self, compression_algorithm: CompressionAlgorithm) -> Result<Self, IggyError> {
+        if compression_algorithm.is_none()
+            || self.bytes.is_empty()
+            || !self.attributes()?.is_empty()
+        {
+            return Ok(self);
+        }
+
+        let compressed = compression_algorithm.compress(&self.bytes)?;
+        if compressed.len() + BATCH_ATTRIBUTES_PREFIX_SIZE >= self.bytes.len() {
+            return Ok(self);
+        }
+
+        let attributes = BatchAttributes {
+            compression_algorithm,
+            producer_compressed: false,
+        };
+        let mut bytes = BytesMut::with_capacity(BATCH_ATTRIBUTES_PREFIX_SIZE + compressed.len());
+        bytes.put_u32_le(BATCH_ATTRIBUTES_MARKER);
+        bytes.put_u8(attributes.as_code());
+        bytes.put_slice(&compressed);
+        Ok(RetainedMessageBatch::new(
+            self.base_offset,
+            self.last_offset_delta,
+            self.max_timestamp,
+            bytes.len() as u32,
+            bytes.freeze(),
+        ))
+    }
+
+    /// Returns the batch with a plain payload, decompressing it if it was stored compressed.
+    pub fn decompress(self) -> Result<Self, IggyError> {
+        let attributes = self.attributes()?;
+        if attributes.is_empty() {
+            return Ok(self);
+        }
+
+        if attributes.producer_compressed {
+            return self.decompress_producer_messages(attributes.compression_algorithm);
+        }
+
+        let payload = attributes
+            .compression_algorithm
+            .decompress(&self.bytes[BATCH_ATTRIBUTES_PREFIX_SIZE..])?;
+        Ok(RetainedMessageBatch::new(
+            self.base_offset,
+            self.last_offset_delta,
+            self.max_timestamp,
+            payload.len() as u32,
+            Bytes::from(payload),
+        ))
+    }
+
+    fn decompress_producer_messages(
+        self,
+        compression_algorithm: CompressionAlgorithm,
+    ) -> Result<Self, IggyError> {
+        let messages_count = self.last_offset_delta + 1;
+        let (timestamps, payload) = split_producer_compressed_payload(
+            &self.bytes.slice(BATCH_ATTRIBUTES_PREFIX_SIZE..),
+            messages_count,
+        )?;
+        let compressed_messages =
+            CompressedMessages::new(compression_algorithm, messages_count, payload);
+        let mut bytes = BytesMut::with_capacity(self.bytes.len());
+        for ((offset, timestamp), message) in (self.base_offset..)
+            .zip(timestamps)
+            .zip(compressed_messages.decompress()?)
+        {
+            RetainedMessage::new(offset, timestamp, message).extend(&mut bytes);
+        }
+
+        Ok(RetainedMessageBatch::new(
+            self.base_offset,
+            self.last_offset_delta,
+            self.max_timestamp,
+            bytes.len() as u32,
+            bytes.freeze(),
+        ))
+    }
+
     pub fn extend(&self, bytes: &mut BytesMut) {
         bytes.put_u64_le(self.base_offset);
         bytes.put_u32_le(self.length);
@@ -81,3 +206,98 @@ impl Sizeable for RetainedMessageBatch {
         RETAINED_BATCH_OVERHEAD + self.length
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::models::messages::RetainedMessage;
+    use iggy::messages::send_messages::Message;
+
+    #[test]
+    fn given_compression_algorithm_batch_should_be_compressed_and_decompressed_correctly() {
+        for algorithm in [
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Lz4,
+            CompressionAlgorithm::Zstd,
+            CompressionAlgorithm::Snappy,
+        ] {
+            let batch = create_batch();
+            let plain_bytes = batch.bytes.clone();
+            let compressed_batch = batch.compress(algorithm).unwrap();
+            assert!(compressed_batch.length < plain_bytes.len() as u32);
+            assert_eq!(
+                compressed_batch.attributes().unwrap().compression_algorithm,
+                algorithm
+            );
+
+            let decompressed_batch = compressed_batch.decompress().unwrap();
+            assert!(decompressed_batch.attributes().unwrap().is_empty());
+            assert_eq!(decompressed_batch.length, plain_bytes.len() as u32);
+            assert_eq!(decompressed_batch.bytes, plain_bytes);
+            assert_eq!(decompressed_batch.base_offset, 10);
+            assert_eq!(decompressed_batch.last_offset_delta, 9);
+        }
+    }
+
+    #[test]
+    fn given_no_compression_batch_should_remain_plain() {
+        let batch = create_batch();
+        let plain_bytes = batch.bytes.clone();
+        let batch = batch.compress(CompressionAlgorithm::None).unwrap();
+        assert!(batch.attributes().unwrap().is_empty());
+        assert_eq!(batch.bytes, plain_bytes);
+        let batch = batch.decompress().unwrap();
+        assert_eq!(batch.bytes, plain_bytes);
+    }
+
+    #[test]
+    fn given_producer_compressed_messages_batch_should_be_decompressed_into_retained_messages() {
+        let messages = (0..10)
+            .map(|id| Message::new(Some(id + 1), Bytes::from(format!("message {id}")), None))
+            .collect::<Vec<_>>();
+        let compressed_messages =
+            CompressedMessages::compress(CompressionAlgorithm::Zstd, &messages).unwrap();
+        let timestamps = (0..10).map(|index| 5000 + index * 10).collect::<Vec<_>>();
+        let batch =
+            RetainedMessageBatch::from_compressed_messages(100, &timestamps, &compressed_messages);
+        let attributes = batch.attributes().unwrap();
+        assert!(attributes.producer_compressed);
+        assert_eq!(attributes.compression_algorithm, CompressionAlgorithm::Zstd);
+        assert_eq!(batch.get_last_offset(), 109);
+        assert_eq!(batch.max_timestamp, 5090);
+
+        let batch = batch.compress(CompressionAlgorithm::Gzip).unwrap();
+        assert_eq!(
+            batch.attributes().unwrap().compression_algorithm,
+            CompressionAlgorithm::Zstd
+        );
+
+        let batch = batch.decompress().unwrap();
+        assert!(batch.attributes().unwrap().is_empty());
+        let retained_messages = batch.into_messages_iter().collect::<Vec<_>>();
+        assert_eq!(retained_messages.len(), messages.len());
+        for (index, message) in retained_messages.iter().enumerate() {
+            assert_eq!(message.offset, 100 + index as u64);
+            assert_eq!(message.timestamp, timestamps[index]);
+            assert_eq!(message.id, messages[index].id);
+            assert_eq!(message.payload, messages[index].payload);
+        }
+    }
+
+    fn create_batch() -> RetainedMessageBatch {
+        let mut bytes = BytesMut::new();
+        for offset in 10..20 {
+            let message = RetainedMessage::new(
+                offset,
+                1000 + offset,
+                Message::new(
+                    Some(offset as u128),
+                    Bytes::from(r#"{"tenant":"acme","priority":3,"status":"pending"}"#),
+                    None,
+                ),
+            );
+            message.extend(&mut bytes);
+        }
+        RetainedMessageBatch::new(10, 9, 1019, bytes.len() as u32, bytes.freeze())
+    }
+}
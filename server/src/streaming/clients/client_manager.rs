@@ -5,7 +5,7 @@ use iggy::locking::IggySharedMut;
 use iggy::locking::IggySharedMutFn;
 use iggy::models::user_info::UserId;
 use iggy::utils::timestamp::IggyTimestamp;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::{Display, Formatter};
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -13,6 +13,7 @@ use std::sync::Arc;
 #[derive(Debug, Default)]
 pub struct ClientManager {
     clients: HashMap<u32, IggySharedMut<Client>>,
+    last_transaction_id: u64,
 }
 
 #[derive(Debug)]
@@ -22,6 +23,7 @@ pub struct Client {
     pub transport: Transport,
     pub consumer_groups: Vec<ConsumerGroup>,
     pub last_heartbeat: IggyTimestamp,
+    pub transaction: Option<Transaction>,
 }
 
 #[derive(Debug)]
@@ -31,6 +33,13 @@ pub struct ConsumerGroup {
     pub group_id: u32,
 }
 
+#[derive(Debug)]
+pub struct Transaction {
+    pub id: u64,
+    pub started_at: IggyTimestamp,
+    pub partitions: BTreeSet<(u32, u32, u32)>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Transport {
     Tcp,
@@ -56,6 +65,7 @@ impl ClientManager {
             transport,
             consumer_groups: Vec::new(),
             last_heartbeat: IggyTimestamp::now(),
+            transaction: None,
         };
         self.clients.insert(client_id, IggySharedMut::new(client));
         session
@@ -218,4 +228,87 @@ impl ClientManager {
             }
         }
     }
+
+    /// Begins the transaction for the client, the transaction IDs are based on the current timestamp,
+    /// so that they remain unique across the server restarts.
+    pub async fn begin_transaction(&mut self, client_id: u32) -> Result<u64, IggyError> {
+        let Some(client) = self.clients.get(&client_id) else {
+            return Err(IggyError::ClientNotFound(client_id));
+        };
+
+        let mut client = client.write().await;
+        if let Some(transaction) = &client.transaction {
+            return Err(IggyError::TransactionAlreadyOpen(transaction.id));
+        }
+
+        let now = IggyTimestamp::now();
+        let transaction_id = now.as_micros().max(self.last_transaction_id + 1);
+        self.last_transaction_id = transaction_id;
+        client.transaction = Some(Transaction {
+            id: transaction_id,
+            started_at: now,
+            partitions: BTreeSet::new(),
+        });
+        client.session.set_transaction_id(transaction_id);
+        Ok(transaction_id)
+    }
+
+    pub async fn add_transaction_partition(
+        &self,
+        client_id: u32,
+        transaction_id: u64,
+        stream_id: u32,
+        topic_id: u32,
+        partition_id: u32,
+    ) -> Result<(), IggyError> {
+        let Some(client) = self.clients.get(&client_id) else {
+            return Err(IggyError::ClientNotFound(client_id));
+        };
+
+        let mut client = client.write().await;
+        match client.transaction.as_mut() {
+            Some(transaction) if transaction.id == transaction_id => {
+                transaction
+                    .partitions
+                    .insert((stream_id, topic_id, partition_id));
+                Ok(())
+            }
+            _ => Err(IggyError::TransactionNotFound(transaction_id)),
+        }
+    }
+
+    pub async fn end_transaction(
+        &self,
+        client_id: u32,
+        transaction_id: u64,
+    ) -> Result<Transaction, IggyError> {
+        let Some(client) = self.clients.get(&client_id) else {
+            return Err(IggyError::ClientNotFound(client_id));
+        };
+
+        let mut client = client.write().await;
+        match client.transaction.take() {
+            Some(transaction) if transaction.id == transaction_id => {
+                client.session.clear_transaction_id();
+                Ok(transaction)
+            }
+            transaction => {
+                client.transaction = transaction;
+                Err(IggyError::TransactionNotFound(transaction_id))
+            }
+        }
+    }
+
+    pub async fn get_expired_transactions(&self, started_before: IggyTimestamp) -> Vec<(u32, u64)> {
+        let mut expired_transactions = Vec::new();
+        for client in self.clients.values() {
+            let client = client.read().await;
+            if let Some(transaction) = &client.transaction {
+                if transaction.started_at.as_micros() <= started_before.as_micros() {
+                    expired_transactions.push((client.session.client_id, transaction.id));
+                }
+            }
+        }
+        expired_transactions
+    }
 }
@@ -2,6 +2,7 @@ use crate::streaming::sizeable::Sizeable;
 use bytes::{BufMut, Bytes, BytesMut};
 use iggy::bytes_serializable::BytesSerializable;
 use iggy::error::IggyError;
+use iggy::messages::message_filter::MessageFilter;
 use iggy::models::messages::PolledMessage;
 use iggy::utils::checksum;
 use iggy::{messages::send_messages::Message, models::messages::MessageState};
@@ -18,6 +19,28 @@ pub struct PolledMessages {
     pub messages: Vec<Arc<PolledMessage>>,
 }
 
+/// The batches polled from the partition, which are sent as they are stored in the segment log files.
+/// The regions might contain the messages outside the offset range, which are skipped by the consumer.
+#[derive(Debug)]
+pub struct PolledBatches {
+    pub partition_id: u32,
+    pub current_offset: u64,
+    pub start_offset: u64,
+    pub end_offset: u64,
+    pub regions: Vec<FileRegion>,
+}
+
+/// The region of the file containing the consecutive message batches.
+/// The file is opened while the partition is locked, so the region can be sent once the lock is released,
+/// even if the segment is deleted or rewritten by the compaction in the meantime.
+#[derive(Debug, Clone)]
+pub struct FileRegion {
+    pub path: String,
+    pub position: u64,
+    pub length: u64,
+    pub file: Arc<std::fs::File>,
+}
+
 #[derive(Debug)]
 pub struct RetainedMessage {
     pub id: u128,
@@ -44,6 +67,11 @@ impl RetainedMessage {
         };
         Ok(message)
     }
+
+    pub fn matches(&self, filter: &MessageFilter) -> Result<bool, IggyError> {
+        let headers = self.headers.clone().map(HashMap::from_bytes).transpose()?;
+        Ok(filter.matches(headers.as_ref()))
+    }
 }
 
 impl RetainedMessage {
@@ -0,0 +1,314 @@
+use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::partitions::partition::Partition;
+use crate::streaming::segments::compaction::CompactedSegment;
+use crate::streaming::segments::segment::Segment;
+use bytes::Bytes;
+use iggy::bytes_serializable::BytesSerializable;
+use iggy::error::IggyError;
+use iggy::models::cleanup_policy::MESSAGE_KEY_HEADER;
+use iggy::models::header::{HeaderKey, HeaderKind};
+use iggy::utils::duration::IggyDuration;
+use iggy::utils::timestamp::IggyTimestamp;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tracing::{info, trace};
+
+type MessageKey = (HeaderKind, Bytes);
+
+/// The index of the message keys used by the log compaction. It's updated only with the messages appended
+/// since the previous pass, and tracks the segments containing the obsolete messages, so that only these
+/// have to be read and rewritten, instead of all the segments of the partition on every pass.
+#[derive(Debug, Default)]
+pub struct CompactionIndex {
+    indexed_offset: Option<u64>,
+    newest_offsets: HashMap<MessageKey, u64>,
+    tombstones: BTreeMap<u64, (MessageKey, u64)>,
+    dirty_segments: BTreeSet<u64>,
+}
+
+/// The keyed messages which haven't been indexed yet, read without modifying the partition.
+#[derive(Debug, Default)]
+pub struct UnindexedMessageKeys {
+    last_offset: Option<u64>,
+    messages: Vec<KeyedMessage>,
+}
+
+#[derive(Debug)]
+struct KeyedMessage {
+    offset: u64,
+    timestamp: u64,
+    key: MessageKey,
+    is_tombstone: bool,
+}
+
+impl Partition {
+    /// Compacts the closed segments of the partition, keeping only the newest message for each key.
+    /// The messages without the key are always retained, while the tombstones (keyed messages with the empty payload)
+    /// are removed along with the older messages for their key, once they are older than the tombstone retention.
+    /// Only the closed segments containing the messages made obsolete since the previous pass are rewritten.
+    /// Returns the number of the removed messages.
+    pub async fn compact_segments(
+        &mut self,
+        tombstone_retention: IggyDuration,
+        now: IggyTimestamp,
+    ) -> Result<u64, IggyError> {
+        let tombstone_expiry = get_tombstone_expiry(tombstone_retention, now);
+        let message_keys = self.read_unindexed_message_keys().await?;
+        self.index_message_keys(message_keys, tombstone_expiry);
+        let mut removed_messages = 0;
+        for start_offset in self.get_segments_to_compact() {
+            let compacted_segment = self
+                .prepare_segment_compaction(start_offset, tombstone_expiry)
+                .await?;
+            removed_messages += self
+                .complete_segment_compaction(start_offset, compacted_segment, tombstone_expiry)
+                .await?;
+        }
+
+        Ok(removed_messages)
+    }
+
+    /// Reads the keys of the messages appended since the previous pass of the log compaction.
+    pub async fn read_unindexed_message_keys(&self) -> Result<UnindexedMessageKeys, IggyError> {
+        let mut message_keys = UnindexedMessageKeys::default();
+        if !self.should_increment_offset {
+            return Ok(message_keys);
+        }
+
+        for segment in &self.segments {
+            // The empty open segment has no messages yet, even though its current offset is set to the start one.
+            if segment.is_offloaded || segment.start_offset > self.current_offset {
+                continue;
+            }
+
+            let start_offset = match self.compaction_index.indexed_offset {
+                Some(indexed_offset) if indexed_offset >= segment.current_offset => continue,
+                Some(indexed_offset) => indexed_offset + 1,
+                None => segment.start_offset,
+            };
+            let messages = segment
+                .get_filtered_messages(start_offset, segment.current_offset, &|message| {
+                    message.headers.is_some()
+                })
+                .await?;
+            for message in messages {
+                if let Some(key) = get_message_key(&message)? {
+                    message_keys.messages.push(KeyedMessage {
+                        offset: message.offset,
+                        timestamp: message.timestamp,
+                        key,
+                        is_tombstone: message.payload.is_empty(),
+                    });
+                }
+            }
+            message_keys.last_offset = Some(segment.current_offset);
+        }
+
+        Ok(message_keys)
+    }
+
+    /// Updates the compaction index with the newly read message keys, and marks the segments containing
+    /// the messages made obsolete by them, or the tombstones older than the retention, to be compacted.
+    pub fn index_message_keys(
+        &mut self,
+        message_keys: UnindexedMessageKeys,
+        tombstone_expiry: u64,
+    ) {
+        let segments = &self.segments;
+        let index = &mut self.compaction_index;
+        for message in message_keys.messages {
+            if index
+                .indexed_offset
+                .is_some_and(|indexed_offset| message.offset <= indexed_offset)
+            {
+                continue;
+            }
+
+            if message.is_tombstone {
+                index
+                    .tombstones
+                    .insert(message.offset, (message.key.clone(), message.timestamp));
+            }
+            if let Some(previous_offset) = index.newest_offsets.insert(message.key, message.offset)
+            {
+                index.tombstones.remove(&previous_offset);
+                if let Some(start_offset) = get_segment_start_offset(segments, previous_offset) {
+                    index.dirty_segments.insert(start_offset);
+                }
+            }
+        }
+
+        if let Some(last_offset) = message_keys.last_offset {
+            if index
+                .indexed_offset
+                .is_none_or(|indexed_offset| last_offset > indexed_offset)
+            {
+                index.indexed_offset = Some(last_offset);
+            }
+        }
+
+        for (offset, (_, timestamp)) in &index.tombstones {
+            if *timestamp > tombstone_expiry {
+                continue;
+            }
+
+            if let Some(start_offset) = get_segment_start_offset(segments, *offset) {
+                index.dirty_segments.insert(start_offset);
+            }
+        }
+
+        // The keys of the deleted segments no longer have to be tracked.
+        let first_offset = segments
+            .first()
+            .map(|segment| segment.start_offset)
+            .unwrap_or_default();
+        index
+            .newest_offsets
+            .retain(|_, offset| *offset >= first_offset);
+        index.tombstones.retain(|offset, _| *offset >= first_offset);
+        index
+            .dirty_segments
+            .retain(|start_offset| *start_offset >= first_offset);
+        trace!(
+            "Indexed {} unique message keys for partition with ID: {}, {} segments to compact.",
+            index.newest_offsets.len(),
+            self.partition_id,
+            index.dirty_segments.len()
+        );
+    }
+
+    /// Returns the start offsets of the closed segments containing the obsolete messages.
+    /// The open segments stay marked until they're closed.
+    pub fn get_segments_to_compact(&self) -> Vec<u64> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.is_closed && !segment.is_offloaded)
+            .filter(|segment| {
+                self.compaction_index
+                    .dirty_segments
+                    .contains(&segment.start_offset)
+            })
+            .map(|segment| segment.start_offset)
+            .collect()
+    }
+
+    /// Prepares the compacted batches of the segment without modifying it, so it only requires the read lock.
+    pub async fn prepare_segment_compaction(
+        &self,
+        start_offset: u64,
+        tombstone_expiry: u64,
+    ) -> Result<Option<CompactedSegment>, IggyError> {
+        let Some(segment) = self
+            .segments
+            .iter()
+            .find(|segment| segment.start_offset == start_offset)
+        else {
+            return Ok(None);
+        };
+
+        let newest_offsets = &self.compaction_index.newest_offsets;
+        segment
+            .prepare_compaction(|message| {
+                let Some(key) = get_message_key(message)? else {
+                    return Ok(true);
+                };
+
+                // The keys appended after the index was updated are missing, they're compacted on the next pass.
+                if newest_offsets
+                    .get(&key)
+                    .is_some_and(|offset| *offset != message.offset)
+                {
+                    return Ok(false);
+                }
+
+                Ok(!message.payload.is_empty() || message.timestamp > tombstone_expiry)
+            })
+            .await
+    }
+
+    /// Replaces the segment with its compacted batches, unless it has been rewritten in the meantime,
+    /// in which case it stays marked to be compacted on the next pass.
+    /// Returns the number of the removed messages.
+    pub async fn complete_segment_compaction(
+        &mut self,
+        start_offset: u64,
+        compacted_segment: Option<CompactedSegment>,
+        tombstone_expiry: u64,
+    ) -> Result<u64, IggyError> {
+        let Some(segment) = self
+            .segments
+            .iter_mut()
+            .find(|segment| segment.start_offset == start_offset)
+        else {
+            self.compaction_index.dirty_segments.remove(&start_offset);
+            return Ok(0);
+        };
+
+        let mut removed_messages = 0;
+        if let Some(compacted_segment) = compacted_segment {
+            let rewrites_count = compacted_segment.rewrites_count;
+            if segment.rewrites_count != rewrites_count {
+                return Ok(0);
+            }
+
+            removed_messages = segment.replace_with_compacted(compacted_segment).await?;
+        }
+
+        // The expired tombstones are gone, apart from the one being the last message of the segment,
+        // which is kept to preserve its current offset, so none of them has to be checked again.
+        let end_offset = segment.current_offset;
+        let index = &mut self.compaction_index;
+        index.dirty_segments.remove(&start_offset);
+        let expired_tombstones = index
+            .tombstones
+            .range(start_offset..=end_offset)
+            .filter(|(_, (_, timestamp))| *timestamp <= tombstone_expiry)
+            .map(|(offset, (key, _))| (*offset, key.clone()))
+            .collect::<Vec<_>>();
+        for (offset, key) in expired_tombstones {
+            index.tombstones.remove(&offset);
+            if offset != end_offset {
+                index.newest_offsets.remove(&key);
+            }
+        }
+
+        if removed_messages > 0 {
+            // The cache has to contain the consecutive messages, so it can't be used once some of them are removed.
+            if let Some(cache) = &mut self.cache {
+                cache.purge();
+            }
+
+            info!(
+                "Compacted segment with start offset: {} for partition with ID: {}, stream with ID: {}, topic with ID: {}, removed {} messages.",
+                start_offset, self.partition_id, self.stream_id, self.topic_id, removed_messages
+            );
+        }
+        Ok(removed_messages)
+    }
+}
+
+/// Returns the timestamp before which the tombstones are removed by the log compaction.
+pub fn get_tombstone_expiry(tombstone_retention: IggyDuration, now: IggyTimestamp) -> u64 {
+    now.as_micros()
+        .saturating_sub(tombstone_retention.as_micros())
+}
+
+fn get_segment_start_offset(segments: &[Segment], offset: u64) -> Option<u64> {
+    let position = segments.partition_point(|segment| segment.start_offset <= offset);
+    position
+        .checked_sub(1)
+        .map(|position| segments[position].start_offset)
+}
+
+/// Returns the key of the message used by the log compaction, if any.
+pub fn get_message_key(
+    message: &RetainedMessage,
+) -> Result<Option<(HeaderKind, Bytes)>, IggyError> {
+    let Some(headers) = &message.headers else {
+        return Ok(None);
+    };
+
+    let mut headers = HashMap::from_bytes(headers.clone())?;
+    Ok(headers
+        .remove(&HeaderKey::new(MESSAGE_KEY_HEADER)?)
+        .map(|value| (value.kind, value.value)))
+}
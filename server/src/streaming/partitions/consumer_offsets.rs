@@ -1,10 +1,19 @@
-use crate::streaming::partitions::partition::{ConsumerOffset, Partition};
+use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::partitions::partition::{
+    ConsumerGroupLeases, ConsumerOffset, MessageLease, MessageLeaseChange, Partition,
+};
 use crate::streaming::polling_consumer::PollingConsumer;
 use dashmap::DashMap;
 use iggy::consumer::ConsumerKind;
 use iggy::error::IggyError;
+use iggy::messages::poll_messages::IsolationLevel;
+use iggy::utils::duration::IggyDuration;
+use iggy::utils::timestamp::IggyTimestamp;
+use std::sync::Arc;
 use tracing::trace;
 
+const MIN_LEASES_JOURNAL_LENGTH: u64 = 1000;
+
 impl Partition {
     pub async fn get_consumer_offset(&self, consumer: PollingConsumer) -> Result<u64, IggyError> {
         trace!(
@@ -22,7 +31,7 @@ impl Partition {
                 }
             }
             PollingConsumer::ConsumerGroup(consumer_group_id, _) => {
-                let consumer_offset = self.consumer_offsets.get(&consumer_group_id);
+                let consumer_offset = self.consumer_group_offsets.get(&consumer_group_id);
                 if let Some(consumer_offset) = consumer_offset {
                     return Ok(consumer_offset.offset);
                 }
@@ -101,9 +110,309 @@ impl Partition {
         self.load_consumer_offsets_from_storage(ConsumerKind::Consumer)
             .await?;
         self.load_consumer_offsets_from_storage(ConsumerKind::ConsumerGroup)
+            .await?;
+        self.load_consumer_group_leases_from_storage().await?;
+        self.load_dead_letters().await
+    }
+
+    async fn load_consumer_group_leases_from_storage(&self) -> Result<(), IggyError> {
+        let loaded_consumer_group_leases = self
+            .storage
+            .partition
+            .load_consumer_group_leases(&self.consumer_group_leases_path)
+            .await?;
+        for leases in loaded_consumer_group_leases {
+            trace!("Loaded {} message leases with next offset: {} for consumer group with ID: {} for partition with ID: {} for topic with ID: {} and stream with ID: {}.",
+                leases.leases.len(),
+                leases.next_offset,
+                leases.consumer_group_id,
+                self.partition_id,
+                self.topic_id,
+                self.stream_id
+            );
+            self.consumer_group_leases
+                .insert(leases.consumer_group_id, leases);
+        }
+        Ok(())
+    }
+
+    /// Leases up to `count` messages to the member of the consumer group in queue mode.
+    /// The messages whose leases have expired are redelivered first, then the ones which haven't been delivered yet.
+    /// The leased messages are not delivered to any other member until the `visibility_timeout` passes.
+    /// The messages which are not visible with the given isolation level are acknowledged right away, so they are skipped.
+    pub async fn lease_messages(
+        &self,
+        consumer_group_id: u32,
+        member_id: u32,
+        count: u32,
+        visibility_timeout: IggyDuration,
+        isolation: IsolationLevel,
+    ) -> Result<Vec<Arc<RetainedMessage>>, IggyError> {
+        trace!(
+            "Leasing {} messages for member with ID: {} in consumer group with ID: {}, partition: {}, current: {}...",
+            count,
+            member_id,
+            consumer_group_id,
+            self.partition_id,
+            self.current_offset
+        );
+        if !self.should_increment_offset {
+            return Ok(Vec::new());
+        }
+
+        let now = IggyTimestamp::now();
+        let expires_at = IggyTimestamp::from(now.as_micros() + visibility_timeout.as_micros());
+        let first_available_offset = self.get_first_unconsumed_offset(consumer_group_id);
+        let end_offset = match isolation {
+            IsolationLevel::ReadCommitted => self.get_last_stable_offset(),
+            IsolationLevel::ReadUncommitted => Some(self.current_offset),
+        }
+        .map_or(0, |last_offset| last_offset + 1);
+        let leases_lock = self.consumer_group_leases_lock.lock().await;
+        let (expired_offsets, new_offsets, changes) = {
+            let mut leases = self
+                .consumer_group_leases
+                .entry(consumer_group_id)
+                .or_insert_with(|| {
+                    ConsumerGroupLeases::new(
+                        consumer_group_id,
+                        first_available_offset,
+                        &self.consumer_group_leases_path,
+                    )
+                });
+            let expired_offsets = leases
+                .leases
+                .iter()
+                .filter(|(_, lease)| lease.expires_at.as_micros() <= now.as_micros())
+                .map(|(offset, _)| *offset)
+                .take(count as usize)
+                .collect::<Vec<_>>();
+            let start_offset = leases.next_offset.max(first_available_offset);
+            let end_offset =
+                (start_offset + (count as usize - expired_offsets.len()) as u64).min(end_offset);
+            let new_offsets = start_offset..end_offset.max(start_offset);
+            if expired_offsets.is_empty() && new_offsets.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let mut changes =
+                Vec::with_capacity(expired_offsets.len() + new_offsets.clone().count() + 1);
+            for offset in expired_offsets.iter().copied().chain(new_offsets.clone()) {
+                let lease = leases.leases.entry(offset).or_insert(MessageLease {
+                    member_id,
+                    expires_at,
+                    deliveries: 0,
+                });
+                lease.member_id = member_id;
+                lease.expires_at = expires_at;
+                lease.deliveries += 1;
+                changes.push(MessageLeaseChange::Leased(offset, *lease));
+            }
+            if leases.next_offset != new_offsets.end {
+                leases.next_offset = new_offsets.end;
+                changes.push(MessageLeaseChange::NextOffset(new_offsets.end));
+            }
+            (expired_offsets, new_offsets, changes)
+        };
+
+        self.save_consumer_group_lease_changes(consumer_group_id, &changes)
+            .await?;
+        drop(leases_lock);
+
+        let mut messages = Vec::with_capacity(expired_offsets.len() + new_offsets.clone().count());
+        for offset in expired_offsets.iter() {
+            messages.extend(
+                self.get_messages_by_offset(*offset, 1)
+                    .await?
+                    .into_iter()
+                    .filter(|message| message.offset == *offset),
+            );
+        }
+        if !new_offsets.is_empty() {
+            messages.extend(
+                self.get_messages_by_offset(
+                    new_offsets.start,
+                    (new_offsets.end - new_offsets.start) as u32,
+                )
+                .await?
+                .into_iter()
+                .filter(|message| new_offsets.contains(&message.offset)),
+            );
+        }
+        messages.sort_by_key(|message| message.offset);
+
+        let mut visible_messages = Vec::with_capacity(messages.len());
+        for message in messages {
+            if self.is_visible(&message, isolation)? {
+                visible_messages.push(message);
+            } else {
+                self.ack_message(consumer_group_id, member_id, message.offset)
+                    .await?;
+            }
+        }
+        Ok(visible_messages)
+    }
+
+    /// Acknowledges the message leased to the member of the consumer group in queue mode.
+    /// The offset of the consumer group is moved forward to the last message before the first unacknowledged one.
+    pub async fn ack_message(
+        &self,
+        consumer_group_id: u32,
+        member_id: u32,
+        offset: u64,
+    ) -> Result<(), IggyError> {
+        trace!(
+            "Acking message with offset: {} for member with ID: {} in consumer group with ID: {}, partition: {}...",
+            offset,
+            member_id,
+            consumer_group_id,
+            self.partition_id
+        );
+        let _leases_lock = self.consumer_group_leases_lock.lock().await;
+        let acked_offset = {
+            let Some(mut leases) = self.consumer_group_leases.get_mut(&consumer_group_id) else {
+                return Err(IggyError::MessageLeaseNotFound(offset, consumer_group_id));
+            };
+
+            // The lease which has expired can still be acknowledged, unless it was taken over by another member.
+            let now = IggyTimestamp::now();
+            match leases.leases.get(&offset) {
+                Some(lease)
+                    if lease.member_id == member_id
+                        || lease.expires_at.as_micros() <= now.as_micros() => {}
+                _ => return Err(IggyError::MessageLeaseNotFound(offset, consumer_group_id)),
+            }
+
+            leases.leases.remove(&offset);
+            let first_unacked_offset = leases
+                .leases
+                .keys()
+                .next()
+                .copied()
+                .unwrap_or(leases.next_offset);
+            first_unacked_offset.checked_sub(1)
+        };
+
+        self.save_consumer_group_lease_changes(
+            consumer_group_id,
+            &[MessageLeaseChange::Acked(offset)],
+        )
+        .await?;
+
+        let Some(acked_offset) = acked_offset else {
+            return Ok(());
+        };
+
+        let stored_offset = self
+            .consumer_group_offsets
+            .get(&consumer_group_id)
+            .map(|consumer_offset| consumer_offset.offset);
+        if stored_offset.is_none_or(|stored_offset| stored_offset < acked_offset) {
+            self.store_consumer_offset(
+                PollingConsumer::consumer_group(consumer_group_id, member_id),
+                acked_offset,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Releases the leases of the given messages, or all the messages leased to the member, if no offset is provided,
+    /// so that they can be redelivered immediately, e.g. when the member has left the consumer group.
+    pub async fn release_message_leases(
+        &self,
+        consumer_group_id: u32,
+        member_id: u32,
+        offset: Option<u64>,
+    ) -> Result<(), IggyError> {
+        let _leases_lock = self.consumer_group_leases_lock.lock().await;
+        let changes = {
+            let Some(mut leases) = self.consumer_group_leases.get_mut(&consumer_group_id) else {
+                return Ok(());
+            };
+
+            let mut changes = Vec::new();
+            for (lease_offset, lease) in leases.leases.iter_mut() {
+                if lease.member_id != member_id
+                    || offset.is_some_and(|offset| offset != *lease_offset)
+                {
+                    continue;
+                }
+
+                lease.expires_at = IggyTimestamp::zero();
+                changes.push(MessageLeaseChange::Leased(*lease_offset, *lease));
+            }
+
+            if changes.is_empty() {
+                return Ok(());
+            }
+
+            changes
+        };
+
+        trace!(
+            "Released message leases for member with ID: {} in consumer group with ID: {}, partition: {}.",
+            member_id,
+            consumer_group_id,
+            self.partition_id
+        );
+        self.save_consumer_group_lease_changes(consumer_group_id, &changes)
             .await
     }
 
+    /// Appends the changes to the journal of the consumer group leases, or starts the journal over
+    /// with the current leases once it has become much longer than the number of the leases.
+    /// The caller must hold `consumer_group_leases_lock`, so that the changes are stored in the order they were made.
+    async fn save_consumer_group_lease_changes(
+        &self,
+        consumer_group_id: u32,
+        changes: &[MessageLeaseChange],
+    ) -> Result<(), IggyError> {
+        let (path, snapshot) = {
+            let Some(mut leases) = self.consumer_group_leases.get_mut(&consumer_group_id) else {
+                return Ok(());
+            };
+
+            // The journal doesn't exist yet, until the leases are saved for the first time.
+            let is_new = leases.journal_length == 0;
+            leases.journal_length += changes.len() as u64;
+            let max_journal_length = MIN_LEASES_JOURNAL_LENGTH.max(2 * leases.leases.len() as u64);
+            if !is_new && leases.journal_length <= max_journal_length {
+                (leases.path.clone(), None)
+            } else {
+                leases.journal_length = leases.leases.len() as u64 + 1;
+                (leases.path.clone(), Some(leases.clone()))
+            }
+        };
+
+        match snapshot {
+            Some(leases) => {
+                self.storage
+                    .partition
+                    .save_consumer_group_leases(&leases)
+                    .await
+            }
+            None => {
+                self.storage
+                    .partition
+                    .append_consumer_group_lease_changes(&path, changes)
+                    .await
+            }
+        }
+    }
+
+    fn get_first_unconsumed_offset(&self, consumer_group_id: u32) -> u64 {
+        let first_offset = self
+            .segments
+            .first()
+            .map(|segment| segment.start_offset)
+            .unwrap_or(0);
+        match self.consumer_group_offsets.get(&consumer_group_id) {
+            Some(consumer_offset) => (consumer_offset.offset + 1).max(first_offset),
+            None => first_offset,
+        }
+    }
+
     async fn load_consumer_offsets_from_storage(
         &self,
         kind: ConsumerKind,
@@ -143,3 +452,274 @@ impl Partition {
             );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::SystemConfig;
+    use crate::streaming::batching::appendable_batch_info::AppendableBatchInfo;
+    use crate::streaming::partitions::create_messages;
+    use crate::streaming::storage::tests::get_test_system_storage;
+    use crate::streaming::storage::SystemStorage;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
+    use iggy::utils::expiry::IggyExpiry;
+    use std::sync::atomic::{AtomicU32, AtomicU64};
+    use std::time::Duration;
+
+    const GROUP_ID: u32 = 1;
+
+    #[tokio::test]
+    async fn leased_messages_should_be_delivered_to_single_member_and_acked_by_it() {
+        let partition = create_partition().await;
+        let visibility_timeout = IggyDuration::new(Duration::from_secs(60));
+
+        let messages = partition
+            .lease_messages(
+                GROUP_ID,
+                1,
+                2,
+                visibility_timeout,
+                IsolationLevel::ReadUncommitted,
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_offsets(&messages), vec![0, 1]);
+        let messages = partition
+            .lease_messages(
+                GROUP_ID,
+                2,
+                10,
+                visibility_timeout,
+                IsolationLevel::ReadUncommitted,
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_offsets(&messages), vec![2, 3, 4, 5]);
+        assert!(partition
+            .lease_messages(
+                GROUP_ID,
+                1,
+                10,
+                visibility_timeout,
+                IsolationLevel::ReadUncommitted,
+            )
+            .await
+            .unwrap()
+            .is_empty());
+
+        let result = partition.ack_message(GROUP_ID, 2, 0).await;
+        assert!(matches!(
+            result,
+            Err(IggyError::MessageLeaseNotFound(0, GROUP_ID))
+        ));
+        partition.ack_message(GROUP_ID, 2, 2).await.unwrap();
+        assert!(!partition.consumer_group_offsets.contains_key(&GROUP_ID));
+        partition.ack_message(GROUP_ID, 1, 0).await.unwrap();
+        partition.ack_message(GROUP_ID, 1, 1).await.unwrap();
+        assert_eq!(get_stored_offset(&partition), 2);
+    }
+
+    #[tokio::test]
+    async fn expired_and_released_leases_should_be_redelivered() {
+        let partition = create_partition().await;
+
+        let messages = partition
+            .lease_messages(
+                GROUP_ID,
+                1,
+                2,
+                IggyDuration::new(Duration::from_millis(1)),
+                IsolationLevel::ReadUncommitted,
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_offsets(&messages), vec![0, 1]);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let visibility_timeout = IggyDuration::new(Duration::from_secs(60));
+        let messages = partition
+            .lease_messages(
+                GROUP_ID,
+                2,
+                3,
+                visibility_timeout,
+                IsolationLevel::ReadUncommitted,
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_offsets(&messages), vec![0, 1, 2]);
+
+        partition
+            .release_message_leases(GROUP_ID, 2, Some(1))
+            .await
+            .unwrap();
+        let messages = partition
+            .lease_messages(
+                GROUP_ID,
+                1,
+                1,
+                visibility_timeout,
+                IsolationLevel::ReadUncommitted,
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_offsets(&messages), vec![1]);
+        let leases = partition.consumer_group_leases.get(&GROUP_ID).unwrap();
+        assert_eq!(leases.leases.get(&1).unwrap().deliveries, 3);
+        assert_eq!(leases.leases.get(&1).unwrap().member_id, 1);
+    }
+
+    #[tokio::test]
+    async fn consumer_group_offset_should_be_stored_apart_from_consumer_offset_with_same_id() {
+        let partition = create_partition().await;
+        let consumer_group = PollingConsumer::consumer_group(GROUP_ID, 1);
+        let consumer = PollingConsumer::Consumer(GROUP_ID, partition.partition_id);
+
+        partition
+            .store_consumer_offset(consumer_group, 3)
+            .await
+            .unwrap();
+        assert_eq!(
+            partition.get_consumer_offset(consumer_group).await.unwrap(),
+            3
+        );
+        assert_eq!(partition.get_consumer_offset(consumer).await.unwrap(), 0);
+
+        partition.store_consumer_offset(consumer, 1).await.unwrap();
+        assert_eq!(
+            partition.get_consumer_offset(consumer_group).await.unwrap(),
+            3
+        );
+        assert_eq!(partition.get_consumer_offset(consumer).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn leases_should_be_loaded_from_journal() {
+        let storage = Arc::new(SystemStorage::in_memory(Arc::new(SystemConfig::default())));
+        let partition = create_partition_with_storage(storage.clone()).await;
+        let visibility_timeout = IggyDuration::new(Duration::from_secs(60));
+        partition
+            .lease_messages(
+                GROUP_ID,
+                1,
+                3,
+                visibility_timeout,
+                IsolationLevel::ReadUncommitted,
+            )
+            .await
+            .unwrap();
+        partition.ack_message(GROUP_ID, 1, 1).await.unwrap();
+        partition
+            .release_message_leases(GROUP_ID, 1, Some(2))
+            .await
+            .unwrap();
+
+        let loaded_partition = create_partition_with_storage(storage).await;
+        loaded_partition
+            .load_consumer_group_leases_from_storage()
+            .await
+            .unwrap();
+
+        let leases = partition.consumer_group_leases.get(&GROUP_ID).unwrap();
+        let loaded_leases = loaded_partition
+            .consumer_group_leases
+            .get(&GROUP_ID)
+            .unwrap();
+        assert_eq!(loaded_leases.next_offset, 3);
+        assert_eq!(loaded_leases.leases, leases.leases);
+        assert_eq!(
+            loaded_leases.leases.keys().copied().collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+        assert_eq!(loaded_leases.journal_length, leases.journal_length);
+    }
+
+    #[tokio::test]
+    async fn leases_journal_should_start_over_once_too_long() {
+        let storage = Arc::new(SystemStorage::in_memory(Arc::new(SystemConfig::default())));
+        let partition = create_partition_with_storage(storage.clone()).await;
+        let visibility_timeout = IggyDuration::new(Duration::from_secs(60));
+        partition
+            .lease_messages(
+                GROUP_ID,
+                1,
+                1,
+                visibility_timeout,
+                IsolationLevel::ReadUncommitted,
+            )
+            .await
+            .unwrap();
+        for _ in 0..MIN_LEASES_JOURNAL_LENGTH {
+            partition
+                .release_message_leases(GROUP_ID, 1, Some(0))
+                .await
+                .unwrap();
+        }
+
+        let journal_length = partition
+            .consumer_group_leases
+            .get(&GROUP_ID)
+            .unwrap()
+            .journal_length;
+        assert!(journal_length < MIN_LEASES_JOURNAL_LENGTH);
+        let loaded_partition = create_partition_with_storage(storage).await;
+        loaded_partition
+            .load_consumer_group_leases_from_storage()
+            .await
+            .unwrap();
+        let loaded_leases = loaded_partition
+            .consumer_group_leases
+            .get(&GROUP_ID)
+            .unwrap();
+        assert_eq!(loaded_leases.journal_length, journal_length);
+        assert_eq!(
+            loaded_leases.leases.get(&0).unwrap().expires_at,
+            IggyTimestamp::zero()
+        );
+    }
+
+    fn get_offsets(messages: &[Arc<RetainedMessage>]) -> Vec<u64> {
+        messages.iter().map(|message| message.offset).collect()
+    }
+
+    fn get_stored_offset(partition: &Partition) -> u64 {
+        partition
+            .consumer_group_offsets
+            .get(&GROUP_ID)
+            .unwrap()
+            .offset
+    }
+
+    async fn create_partition() -> Partition {
+        create_partition_with_storage(Arc::new(get_test_system_storage())).await
+    }
+
+    async fn create_partition_with_storage(storage: Arc<SystemStorage>) -> Partition {
+        let mut partition = Partition::create(
+            1,
+            2,
+            3,
+            true,
+            Arc::new(SystemConfig::default()),
+            storage,
+            IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU32::new(0)),
+            IggyTimestamp::now(),
+        );
+        let messages = create_messages();
+        let appendable_batch_info = AppendableBatchInfo {
+            batch_size: messages.iter().map(|m| m.get_size_bytes() as u64).sum(),
+            partition_id: partition.partition_id,
+        };
+        partition
+            .append_messages(appendable_batch_info, messages)
+            .await
+            .unwrap();
+        partition
+    }
+}
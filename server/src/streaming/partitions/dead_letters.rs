@@ -0,0 +1,318 @@
+use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::partitions::partition::{DeadLetterChange, Partition};
+use iggy::error::IggyError;
+use std::sync::Arc;
+use tracing::trace;
+
+const MAX_DELIVERY_ATTEMPTS_ENTRIES: usize = 10_000;
+const MIN_DEAD_LETTERS_JOURNAL_LENGTH: u64 = 1000;
+
+impl Partition {
+    pub fn is_poisoned(&self, offset: u64) -> bool {
+        self.poisoned_offsets.contains(&offset)
+    }
+
+    /// Registers the failed delivery of the message with the given offset.
+    /// Once the number of the failed deliveries reaches `max_delivery_attempts`, the message is returned
+    /// along with the number of the delivery attempts, so that it can be moved to the dead letter queue.
+    /// Until the move is completed with `mark_as_poisoned` or cancelled with `cancel_dead_letter`,
+    /// the next failed deliveries of the message are ignored.
+    /// The delivery attempts are tracked for up to `MAX_DELIVERY_ATTEMPTS_ENTRIES` messages at once.
+    pub async fn register_failed_delivery(
+        &mut self,
+        offset: u64,
+        max_delivery_attempts: u32,
+    ) -> Result<Option<(Arc<RetainedMessage>, u32)>, IggyError> {
+        trace!(
+            "Registering failed delivery of message with offset: {} for partition: {}, current: {}...",
+            offset,
+            self.partition_id,
+            self.current_offset
+        );
+        if !self.should_increment_offset || offset > self.current_offset {
+            return Err(IggyError::InvalidOffset(offset));
+        }
+
+        if self.is_poisoned(offset) || self.pending_dead_letters.contains(&offset) {
+            return Ok(None);
+        }
+
+        let mut changes = Vec::with_capacity(2);
+        // The delivery attempts of the oldest messages are evicted first, e.g. the ones never acknowledged in queue mode.
+        if self.delivery_attempts.len() >= MAX_DELIVERY_ATTEMPTS_ENTRIES
+            && !self.delivery_attempts.contains_key(&offset)
+        {
+            if let Some((evicted_offset, _)) = self.delivery_attempts.pop_first() {
+                changes.push(DeadLetterChange::Evicted(evicted_offset));
+            }
+        }
+        let delivery_attempts = self.delivery_attempts.entry(offset).or_insert(0);
+        *delivery_attempts += 1;
+        let delivery_attempts = *delivery_attempts;
+        changes.push(DeadLetterChange::DeliveryAttempts(
+            offset,
+            delivery_attempts,
+        ));
+        self.save_dead_letter_changes(&changes).await?;
+        if delivery_attempts < max_delivery_attempts {
+            return Ok(None);
+        }
+
+        let message = self
+            .get_messages_by_offset(offset, 1)
+            .await?
+            .into_iter()
+            .find(|message| message.offset == offset);
+        let Some(message) = message else {
+            return Err(IggyError::InvalidOffset(offset));
+        };
+
+        self.pending_dead_letters.insert(offset);
+        Ok(Some((message, delivery_attempts)))
+    }
+
+    /// Marks the message as poisoned once it has been moved to the dead letter queue,
+    /// so that it's skipped when polling the partition.
+    pub async fn mark_as_poisoned(&mut self, offset: u64) -> Result<(), IggyError> {
+        self.pending_dead_letters.remove(&offset);
+        self.delivery_attempts.remove(&offset);
+        self.poisoned_offsets.insert(offset);
+        self.save_dead_letter_changes(&[DeadLetterChange::Poisoned(offset)])
+            .await
+    }
+
+    /// Cancels moving the message to the dead letter queue, e.g. when it couldn't be appended to it,
+    /// so that the next failed delivery will try to move it again.
+    pub fn cancel_dead_letter(&mut self, offset: u64) {
+        self.pending_dead_letters.remove(&offset);
+    }
+
+    pub async fn load_dead_letters(&mut self) -> Result<(), IggyError> {
+        let storage = self.storage.clone();
+        storage.partition.load_dead_letters(self).await
+    }
+
+    /// Evicts the delivery attempts of the message acknowledged by the consumer group in queue mode.
+    pub async fn evict_delivery_attempts(&mut self, offset: u64) -> Result<(), IggyError> {
+        if self.delivery_attempts.remove(&offset).is_none() {
+            return Ok(());
+        }
+
+        self.save_dead_letter_changes(&[DeadLetterChange::Evicted(offset)])
+            .await
+    }
+
+    /// Starts the journal of the dead letters over with the current state,
+    /// e.g. once the poisoned offsets and the delivery attempts have been removed in bulk.
+    pub async fn save_dead_letters(&mut self) -> Result<(), IggyError> {
+        self.dead_letters_journal_length =
+            (self.poisoned_offsets.len() + self.delivery_attempts.len()) as u64;
+        self.storage.partition.save_dead_letters(self).await
+    }
+
+    /// Appends the changes to the journal of the dead letters, or starts the journal over
+    /// with the current state once it has become much longer than the number of the dead letters.
+    async fn save_dead_letter_changes(
+        &mut self,
+        changes: &[DeadLetterChange],
+    ) -> Result<(), IggyError> {
+        // The journal doesn't exist yet, until the dead letters are saved for the first time.
+        let is_new = self.dead_letters_journal_length == 0;
+        self.dead_letters_journal_length += changes.len() as u64;
+        let max_journal_length = MIN_DEAD_LETTERS_JOURNAL_LENGTH
+            .max(2 * (self.poisoned_offsets.len() + self.delivery_attempts.len()) as u64);
+        if is_new || self.dead_letters_journal_length > max_journal_length {
+            return self.save_dead_letters().await;
+        }
+
+        self.storage
+            .partition
+            .append_dead_letter_changes(&self.dead_letters_path, changes)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::SystemConfig;
+    use crate::streaming::batching::appendable_batch_info::AppendableBatchInfo;
+    use crate::streaming::partitions::create_messages;
+    use crate::streaming::storage::tests::get_test_system_storage;
+    use crate::streaming::storage::SystemStorage;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
+    use iggy::messages::poll_messages::IsolationLevel;
+    use iggy::utils::expiry::IggyExpiry;
+    use iggy::utils::timestamp::IggyTimestamp;
+    use std::sync::atomic::{AtomicU32, AtomicU64};
+
+    #[tokio::test]
+    async fn message_should_be_poisoned_once_moved_after_max_delivery_attempts() {
+        let mut partition = create_partition(Arc::new(get_test_system_storage())).await;
+        let offset = 2;
+        let max_delivery_attempts = 3;
+
+        for _ in 1..max_delivery_attempts {
+            let dead_letter = partition
+                .register_failed_delivery(offset, max_delivery_attempts)
+                .await
+                .unwrap();
+            assert!(dead_letter.is_none());
+            assert!(!partition.is_poisoned(offset));
+        }
+
+        let (message, delivery_attempts) = partition
+            .register_failed_delivery(offset, max_delivery_attempts)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.offset, offset);
+        assert_eq!(delivery_attempts, max_delivery_attempts);
+        assert!(!partition.is_poisoned(offset));
+        assert!(partition
+            .register_failed_delivery(offset, max_delivery_attempts)
+            .await
+            .unwrap()
+            .is_none());
+
+        partition.mark_as_poisoned(offset).await.unwrap();
+        assert!(partition.is_poisoned(offset));
+        assert!(!partition.delivery_attempts.contains_key(&offset));
+        assert!(partition
+            .register_failed_delivery(offset, max_delivery_attempts)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn poisoned_message_should_not_be_visible() {
+        let mut partition = create_partition(Arc::new(get_test_system_storage())).await;
+        let offset = 2;
+        partition.mark_as_poisoned(offset).await.unwrap();
+
+        let messages = partition
+            .get_messages_by_offset(0, partition.current_offset as u32 + 1)
+            .await
+            .unwrap();
+        let (visible_messages, last_scanned_offset) = partition
+            .filter_visible_messages(messages, IsolationLevel::ReadUncommitted)
+            .unwrap();
+
+        assert_eq!(visible_messages.len() as u64, partition.current_offset);
+        assert!(visible_messages
+            .iter()
+            .all(|message| message.offset != offset));
+        assert_eq!(last_scanned_offset, Some(partition.current_offset));
+    }
+
+    #[tokio::test]
+    async fn cancelled_dead_letter_should_be_moved_again_on_next_failed_delivery() {
+        let mut partition = create_partition(Arc::new(get_test_system_storage())).await;
+        let offset = 2;
+
+        assert!(partition
+            .register_failed_delivery(offset, 1)
+            .await
+            .unwrap()
+            .is_some());
+        partition.cancel_dead_letter(offset);
+
+        assert!(!partition.is_poisoned(offset));
+        let (_, delivery_attempts) = partition
+            .register_failed_delivery(offset, 1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivery_attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn dead_letters_should_be_loaded_from_storage() {
+        let config = Arc::new(SystemConfig::default());
+        let storage = Arc::new(SystemStorage::in_memory(config));
+        let mut partition = create_partition(storage.clone()).await;
+        partition.register_failed_delivery(1, 3).await.unwrap();
+        partition.register_failed_delivery(2, 1).await.unwrap();
+        partition.mark_as_poisoned(2).await.unwrap();
+
+        let mut loaded_partition = create_partition(storage).await;
+        loaded_partition.load_dead_letters().await.unwrap();
+
+        assert_eq!(loaded_partition.delivery_attempts.get(&1), Some(&1));
+        assert!(!loaded_partition.delivery_attempts.contains_key(&2));
+        assert!(loaded_partition.is_poisoned(2));
+    }
+
+    #[tokio::test]
+    async fn dead_letter_changes_should_be_appended_to_journal_and_loaded_from_storage() {
+        let config = Arc::new(SystemConfig::default());
+        let storage = Arc::new(SystemStorage::in_memory(config));
+        let mut partition = create_partition(storage.clone()).await;
+        partition.register_failed_delivery(1, 3).await.unwrap();
+        partition.register_failed_delivery(1, 3).await.unwrap();
+        partition.register_failed_delivery(2, 3).await.unwrap();
+        partition.register_failed_delivery(3, 3).await.unwrap();
+        partition.evict_delivery_attempts(2).await.unwrap();
+        partition.mark_as_poisoned(3).await.unwrap();
+        assert_eq!(partition.dead_letters_journal_length, 6);
+
+        let mut loaded_partition = create_partition(storage).await;
+        loaded_partition.load_dead_letters().await.unwrap();
+
+        assert_eq!(loaded_partition.delivery_attempts.get(&1), Some(&2));
+        assert!(!loaded_partition.delivery_attempts.contains_key(&2));
+        assert!(!loaded_partition.delivery_attempts.contains_key(&3));
+        assert!(loaded_partition.is_poisoned(3));
+        assert_eq!(loaded_partition.dead_letters_journal_length, 6);
+    }
+
+    #[tokio::test]
+    async fn delivery_attempts_should_be_evicted_once_acknowledged() {
+        let mut partition = create_partition(Arc::new(get_test_system_storage())).await;
+        partition.register_failed_delivery(1, 3).await.unwrap();
+
+        partition.evict_delivery_attempts(1).await.unwrap();
+
+        assert!(partition.delivery_attempts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_delivery_should_not_be_registered_for_non_existing_offset() {
+        let mut partition = create_partition(Arc::new(get_test_system_storage())).await;
+        let offset = partition.current_offset + 1;
+
+        let result = partition.register_failed_delivery(offset, 1).await;
+
+        assert!(matches!(result, Err(IggyError::InvalidOffset(_))));
+    }
+
+    async fn create_partition(storage: Arc<SystemStorage>) -> Partition {
+        let mut partition = Partition::create(
+            1,
+            2,
+            3,
+            true,
+            Arc::new(SystemConfig::default()),
+            storage,
+            IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU32::new(0)),
+            IggyTimestamp::now(),
+        );
+        let messages = create_messages();
+        let appendable_batch_info = AppendableBatchInfo {
+            batch_size: messages.iter().map(|m| m.get_size_bytes() as u64).sum(),
+            partition_id: partition.partition_id,
+        };
+        partition
+            .append_messages(appendable_batch_info, messages)
+            .await
+            .unwrap();
+        partition
+    }
+}
@@ -0,0 +1,283 @@
+use crate::state::system::PartitionState;
+use crate::streaming::partitions::partition::{
+    ConsumerGroupLeases, ConsumerOffset, DeadLetterChange, MessageLeaseChange, Partition,
+    ProducerSequence,
+};
+use crate::streaming::partitions::storage::{
+    decode_committed_transactions, decode_consumer_group_leases, decode_dead_letters,
+    decode_producer_sequences, encode_committed_transactions, encode_consumer_group_leases,
+    encode_dead_letter_changes, encode_dead_letters, encode_lease_changes,
+    encode_producer_sequences,
+};
+use crate::streaming::persistence::memory::MemoryPersister;
+use crate::streaming::persistence::persister::Persister;
+use crate::streaming::storage::PartitionStorage;
+use async_trait::async_trait;
+use iggy::consumer::ConsumerKind;
+use iggy::error::IggyError;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{error, info, trace};
+
+/// The partition storage keeping the consumer offsets and the consumer group leases in memory.
+#[derive(Debug)]
+pub struct MemoryPartitionStorage {
+    files: Arc<MemoryPersister>,
+}
+
+impl MemoryPartitionStorage {
+    pub fn new(files: Arc<MemoryPersister>) -> Self {
+        Self { files }
+    }
+}
+
+#[async_trait]
+impl PartitionStorage for MemoryPartitionStorage {
+    async fn load(
+        &self,
+        partition: &mut Partition,
+        _state: PartitionState,
+    ) -> Result<(), IggyError> {
+        // The in-memory storage doesn't outlive the server, so there's never a partition to load.
+        Err(IggyError::PartitionNotFound(
+            partition.partition_id,
+            partition.topic_id,
+            partition.stream_id,
+        ))
+    }
+
+    async fn save(&self, partition: &Partition) -> Result<(), IggyError> {
+        for segment in partition.get_segments() {
+            segment.persist().await?;
+        }
+
+        info!(
+            "Saved partition with ID: {} for stream with ID: {} and topic with ID: {} in memory.",
+            partition.partition_id, partition.stream_id, partition.topic_id
+        );
+        Ok(())
+    }
+
+    async fn delete(&self, partition: &Partition) -> Result<(), IggyError> {
+        let files_count = self.files.delete_all(&partition.partition_path);
+        info!(
+            "Deleted partition with ID: {} for stream with ID: {} and topic with ID: {} along with {files_count} files from memory.",
+            partition.partition_id, partition.stream_id, partition.topic_id,
+        );
+        Ok(())
+    }
+
+    async fn save_consumer_offset(&self, offset: &ConsumerOffset) -> Result<(), IggyError> {
+        self.files
+            .overwrite(&offset.path, &offset.offset.to_le_bytes())
+            .await?;
+        trace!(
+            "Stored consumer offset value: {} for {} with ID: {}, path: {}",
+            offset.offset,
+            offset.kind,
+            offset.consumer_id,
+            offset.path
+        );
+        Ok(())
+    }
+
+    async fn load_consumer_offsets(
+        &self,
+        kind: ConsumerKind,
+        path: &str,
+    ) -> Result<Vec<ConsumerOffset>, IggyError> {
+        let mut consumer_offsets = Vec::new();
+        for offset_path in self.files.list(path) {
+            let name = get_file_name(&offset_path);
+            let Ok(consumer_id) = name.parse::<u32>() else {
+                error!("Invalid consumer ID file with name: '{name}'.");
+                continue;
+            };
+
+            let Some(Ok(offset)) = self.files.read(&offset_path, |bytes| {
+                <[u8; 8]>::try_from(bytes).map(u64::from_le_bytes)
+            }) else {
+                error!("Invalid consumer offset file with name: '{name}'.");
+                continue;
+            };
+
+            consumer_offsets.push(ConsumerOffset {
+                kind,
+                consumer_id,
+                offset,
+                path: offset_path,
+            });
+        }
+
+        consumer_offsets.sort_by_key(|consumer_offset| consumer_offset.consumer_id);
+        Ok(consumer_offsets)
+    }
+
+    async fn delete_consumer_offsets(&self, path: &str) -> Result<(), IggyError> {
+        self.files.delete_all(path);
+        Ok(())
+    }
+
+    async fn delete_consumer_offset(&self, path: &str) -> Result<(), IggyError> {
+        if !self.files.exists(path) {
+            trace!("Consumer offset file does not exist: {path}.");
+            return Ok(());
+        }
+
+        self.files.delete(path).await
+    }
+
+    async fn save_consumer_group_leases(
+        &self,
+        leases: &ConsumerGroupLeases,
+    ) -> Result<(), IggyError> {
+        self.files
+            .overwrite(&leases.path, &encode_consumer_group_leases(leases))
+            .await
+    }
+
+    async fn append_consumer_group_lease_changes(
+        &self,
+        path: &str,
+        changes: &[MessageLeaseChange],
+    ) -> Result<(), IggyError> {
+        self.files
+            .append(path, &encode_lease_changes(changes))
+            .await
+    }
+
+    async fn load_consumer_group_leases(
+        &self,
+        path: &str,
+    ) -> Result<Vec<ConsumerGroupLeases>, IggyError> {
+        let mut consumer_group_leases = Vec::new();
+        for leases_path in self.files.list(path) {
+            let name = get_file_name(&leases_path);
+            let Ok(consumer_group_id) = name.parse::<u32>() else {
+                error!("Invalid consumer group ID leases file with name: '{name}'.");
+                continue;
+            };
+
+            let leases = self
+                .files
+                .read(&leases_path, |bytes| {
+                    decode_consumer_group_leases(consumer_group_id, path, bytes)
+                })
+                .flatten();
+            let Some(leases) = leases else {
+                error!("Invalid consumer group leases file with name: '{name}'.");
+                continue;
+            };
+            consumer_group_leases.push(leases);
+        }
+
+        consumer_group_leases.sort_by_key(|leases| leases.consumer_group_id);
+        Ok(consumer_group_leases)
+    }
+
+    async fn save_dead_letters(&self, partition: &Partition) -> Result<(), IggyError> {
+        self.files
+            .overwrite(
+                &partition.dead_letters_path,
+                &encode_dead_letters(partition),
+            )
+            .await
+    }
+
+    async fn append_dead_letter_changes(
+        &self,
+        path: &str,
+        changes: &[DeadLetterChange],
+    ) -> Result<(), IggyError> {
+        self.files
+            .append(path, &encode_dead_letter_changes(changes))
+            .await
+    }
+
+    async fn save_producer_sequences(
+        &self,
+        path: &str,
+        sequences: &HashMap<u64, u64>,
+        persisted_offset: u64,
+    ) -> Result<(), IggyError> {
+        self.files
+            .overwrite(
+                path,
+                &encode_producer_sequences(sequences, persisted_offset),
+            )
+            .await
+    }
+
+    async fn append_producer_sequences(
+        &self,
+        path: &str,
+        sequences: &HashMap<u64, u64>,
+        persisted_offset: u64,
+    ) -> Result<(), IggyError> {
+        self.files
+            .append(
+                path,
+                &encode_producer_sequences(sequences, persisted_offset),
+            )
+            .await
+    }
+
+    async fn load_producer_sequences(
+        &self,
+        path: &str,
+    ) -> Result<Option<Vec<ProducerSequence>>, IggyError> {
+        Ok(self.files.read(path, decode_producer_sequences))
+    }
+
+    async fn save_committed_transactions(
+        &self,
+        path: &str,
+        transactions: &HashMap<u64, u64>,
+    ) -> Result<(), IggyError> {
+        self.files
+            .overwrite(path, &encode_committed_transactions(transactions))
+            .await
+    }
+
+    async fn append_committed_transactions(
+        &self,
+        path: &str,
+        transactions: &HashMap<u64, u64>,
+    ) -> Result<(), IggyError> {
+        self.files
+            .append(path, &encode_committed_transactions(transactions))
+            .await
+    }
+
+    async fn load_committed_transactions(
+        &self,
+        path: &str,
+    ) -> Result<Option<HashMap<u64, u64>>, IggyError> {
+        Ok(self.files.read(path, decode_committed_transactions))
+    }
+
+    async fn load_dead_letters(&self, partition: &mut Partition) -> Result<(), IggyError> {
+        let Some(bytes) = self
+            .files
+            .read(&partition.dead_letters_path, |bytes| bytes.to_vec())
+        else {
+            return Ok(());
+        };
+
+        if !decode_dead_letters(partition, &bytes) {
+            error!(
+                "Invalid dead letters file: '{}'.",
+                partition.dead_letters_path
+            );
+        }
+        Ok(())
+    }
+}
+
+fn get_file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+}
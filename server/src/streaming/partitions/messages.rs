@@ -1,18 +1,23 @@
 use crate::streaming::batching::appendable_batch_info::AppendableBatchInfo;
 use crate::streaming::batching::iterator::IntoMessagesIterator;
-use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::batching::message_batch::RetainedMessageBatch;
+use crate::streaming::models::messages::{PolledBatches, RetainedMessage};
 use crate::streaming::partitions::partition::Partition;
 use crate::streaming::polling_consumer::PollingConsumer;
 use crate::streaming::segments::segment::Segment;
-use iggy::messages::send_messages::Message;
+use iggy::messages::message_filter::MessageFilter;
+use iggy::messages::poll_messages::{IsolationLevel, PollingKind, PollingStrategy};
+use iggy::messages::send_messages::{CompressedMessages, Message};
 use iggy::models::messages::POLLED_MESSAGE_METADATA;
 use iggy::utils::timestamp::IggyTimestamp;
 use iggy::{error::IggyError, utils::duration::IggyDuration};
-use std::sync::{atomic::Ordering, Arc};
+use std::sync::{atomic::Ordering, Arc, OnceLock};
 use std::time::Duration;
 use tracing::{trace, warn};
 
 const EMPTY_MESSAGES: Vec<RetainedMessage> = vec![];
+/// The maximum number of messages scanned by a single poll with the filter, unless more messages are polled.
+const MAX_FILTER_SCANNED_MESSAGES: u64 = 10_000;
 
 impl Partition {
     pub fn get_messages_count(&self) -> u64 {
@@ -217,6 +222,213 @@ impl Partition {
         self.get_messages_by_offset(offset, count).await
     }
 
+    /// Returns the regions of the segment log files containing the batches with the polled messages,
+    /// so that they can be sent as they are stored. Returns `None` if the messages have to be loaded instead,
+    /// i.e. polled by the timestamp, cached, poisoned, offloaded or not persisted yet.
+    pub async fn get_batches(
+        &self,
+        consumer: PollingConsumer,
+        strategy: PollingStrategy,
+        count: u32,
+    ) -> Result<Option<PolledBatches>, IggyError> {
+        if self.segments.is_empty() {
+            return Ok(None);
+        }
+
+        let start_offset = match strategy.kind {
+            PollingKind::Offset => strategy.value,
+            PollingKind::First => 0,
+            PollingKind::Last => {
+                let count = (count as u64).min(self.current_offset + 1);
+                1 + self.current_offset - count
+            }
+            PollingKind::Next => match self.get_next_offset(consumer) {
+                Some(offset) => offset,
+                None => return Ok(None),
+            },
+            PollingKind::Timestamp => return Ok(None),
+        };
+        if start_offset > self.current_offset {
+            return Ok(None);
+        }
+
+        if let Some(cache) = &self.cache {
+            if !cache.is_empty() && start_offset >= cache[0].offset {
+                return Ok(None);
+            }
+        }
+
+        let end_offset = self.get_end_offset(start_offset, count);
+        if self
+            .poisoned_offsets
+            .iter()
+            .any(|offset| (start_offset..=end_offset).contains(offset))
+        {
+            return Ok(None);
+        }
+
+        let mut regions = Vec::new();
+        for segment in self.filter_segments_by_offsets(start_offset, end_offset) {
+            if segment.get_messages_count() == 0 {
+                continue;
+            }
+
+            let segment_end_offset = end_offset.min(segment.current_offset);
+            let Some(region) = segment
+                .get_batches_region(start_offset, segment_end_offset)
+                .await?
+            else {
+                return Ok(None);
+            };
+            regions.push(region);
+        }
+
+        if regions.is_empty() {
+            return Ok(None);
+        }
+
+        trace!(
+            "Found {} regions of batches for offsets: {start_offset}...{end_offset} for partition: {}",
+            regions.len(),
+            self.partition_id
+        );
+        Ok(Some(PolledBatches {
+            partition_id: self.partition_id,
+            current_offset: self.current_offset,
+            start_offset,
+            end_offset,
+            regions,
+        }))
+    }
+
+    pub async fn get_filtered_messages(
+        &self,
+        consumer: PollingConsumer,
+        strategy: PollingStrategy,
+        count: u32,
+        filter: &MessageFilter,
+        isolation: IsolationLevel,
+    ) -> Result<(Vec<Arc<RetainedMessage>>, Option<u64>), IggyError> {
+        if self.segments.is_empty() || self.get_messages_count() == 0 {
+            return Ok((Vec::new(), None));
+        }
+
+        let start_offset = match strategy.kind {
+            PollingKind::Offset => Some(strategy.value),
+            PollingKind::First => Some(0),
+            PollingKind::Last => {
+                let count = (count as u64).min(self.current_offset + 1);
+                Some(1 + self.current_offset - count)
+            }
+            PollingKind::Next => self.get_next_offset(consumer),
+            PollingKind::Timestamp => self
+                .get_messages_by_timestamp(strategy.value.into(), 1)
+                .await?
+                .first()
+                .map(|message| message.offset),
+        };
+
+        let Some(start_offset) = start_offset else {
+            return Ok((Vec::new(), None));
+        };
+
+        // The last messages are not scanned any further, so only the ones matching the filter
+        // among the last N messages are returned, while for the other strategies the partition
+        // is scanned until N matching messages are found, or the end of the partition or the scan limit is reached.
+        let last_offset = match isolation {
+            IsolationLevel::ReadCommitted => self.get_last_stable_offset(),
+            IsolationLevel::ReadUncommitted => Some(self.current_offset),
+        };
+        let Some(last_offset) = last_offset else {
+            return Ok((Vec::new(), None));
+        };
+
+        let max_offset = if strategy.kind == PollingKind::Last {
+            last_offset.min(start_offset + count as u64 - 1)
+        } else {
+            last_offset
+        };
+
+        if start_offset > max_offset {
+            return Ok((Vec::new(), None));
+        }
+
+        // The scan is bounded, so that a selective filter doesn't turn a single poll into reading the whole
+        // partition while holding its lock. The following messages are scanned by the next poll.
+        let scan_end_offset =
+            max_offset.min(start_offset + MAX_FILTER_SCANNED_MESSAGES.max(count as u64) - 1);
+        trace!(
+            "Getting filtered messages for offsets: {}...{} for partition: {}, filter: {}...",
+            start_offset,
+            scan_end_offset,
+            self.partition_id,
+            filter
+        );
+        let error = OnceLock::new();
+        let matches = |message: &RetainedMessage| {
+            if message.offset < start_offset || message.offset > scan_end_offset {
+                return false;
+            }
+
+            let matches = self
+                .is_visible(message, isolation)
+                .and_then(|visible| Ok(visible && message.matches(filter)?));
+            matches.unwrap_or_else(|matching_error| {
+                let _ = error.set(matching_error);
+                false
+            })
+        };
+
+        let mut messages = Vec::new();
+        if let Some(cached_messages) =
+            self.try_get_messages_from_cache(start_offset, scan_end_offset)
+        {
+            messages.extend(
+                cached_messages
+                    .into_iter()
+                    .filter(|message| matches(message)),
+            );
+        } else {
+            for segment in self.filter_segments_by_offsets(start_offset, scan_end_offset) {
+                messages.extend(
+                    segment
+                        .get_filtered_messages(start_offset, scan_end_offset, &matches)
+                        .await?,
+                );
+                if messages.len() >= count as usize {
+                    break;
+                }
+            }
+        }
+
+        if let Some(error) = error.into_inner() {
+            return Err(error);
+        }
+
+        // Unless enough messages have matched, the whole range has been scanned, including the messages
+        // which have been filtered out, so the next poll continues right after it.
+        if messages.len() >= count as usize {
+            messages.truncate(count as usize);
+            let last_scanned_offset = messages.last().map(|message| message.offset);
+            return Ok((messages, last_scanned_offset));
+        }
+
+        Ok((messages, Some(scan_end_offset)))
+    }
+
+    fn get_next_offset(&self, consumer: PollingConsumer) -> Option<u64> {
+        let (consumer_offsets, consumer_id) = match consumer {
+            PollingConsumer::Consumer(consumer_id, _) => (&self.consumer_offsets, consumer_id),
+            PollingConsumer::ConsumerGroup(group_id, _) => (&self.consumer_group_offsets, group_id),
+        };
+
+        match consumer_offsets.get(&consumer_id) {
+            Some(consumer_offset) if consumer_offset.offset == self.current_offset => None,
+            Some(consumer_offset) => Some(consumer_offset.offset + 1),
+            None => Some(0),
+        }
+    }
+
     fn get_end_offset(&self, offset: u64, count: u32) -> u64 {
         let mut end_offset = offset + (count - 1) as u64;
         let segment = self.segments.last().unwrap();
@@ -382,18 +594,9 @@ impl Partition {
         appendable_batch_info: AppendableBatchInfo,
         messages: Vec<Message>,
     ) -> Result<(), IggyError> {
-        {
-            let last_segment = self.segments.last_mut().ok_or(IggyError::SegmentNotFound)?;
-            if last_segment.is_closed {
-                let start_offset = last_segment.end_offset + 1;
-                trace!(
-                    "Current segment is closed, creating new segment with start offset: {} for partition with ID: {}...",
-                    start_offset, self.partition_id
-                );
-                self.add_persisted_segment(start_offset).await?;
-            }
-        }
+        self.add_segment_if_last_is_closed().await?;
 
+        let (messages, producer_sequences) = self.check_producer_sequences(messages)?;
         let batch_size = appendable_batch_info.batch_size
             + (POLLED_MESSAGE_METADATA * messages.len() as u32) as u64;
         let base_offset = if !self.should_increment_offset {
@@ -443,7 +646,8 @@ impl Partition {
             }
         }
         if messages_count == 0 {
-            return Ok(());
+            self.apply_producer_sequences(producer_sequences);
+            return self.save_producer_sequences_if_persisted().await;
         }
 
         let avg_timestamp_delta =
@@ -454,7 +658,38 @@ impl Partition {
         let dynamic_range = 10.00;
         self.update_avg_timestamp_delta(avg_timestamp_delta, min_alpha, max_alpha, dynamic_range);
 
-        let last_offset = base_offset + (messages_count - 1) as u64;
+        self.append_retained_messages(batch_size, retained_messages)
+            .await?;
+        self.apply_producer_sequences(producer_sequences);
+        self.save_producer_sequences_if_persisted().await
+    }
+
+    /// Saves the producer sequences once all the messages appended so far have been persisted.
+    async fn save_producer_sequences_if_persisted(&mut self) -> Result<(), IggyError> {
+        if self.unsaved_messages_count > 0 {
+            return Ok(());
+        }
+
+        self.save_producer_sequences().await
+    }
+
+    /// Appends the messages which already have the ascending offsets assigned, starting from the next offset of the partition,
+    /// or following it with the gaps in case of the messages replicated from the compacted partition.
+    pub(crate) async fn append_retained_messages(
+        &mut self,
+        batch_size: u64,
+        retained_messages: Vec<Arc<RetainedMessage>>,
+    ) -> Result<(), IggyError> {
+        let Some(last_offset) = retained_messages.last().map(|message| message.offset) else {
+            return Ok(());
+        };
+
+        let messages_count = retained_messages.len() as u32;
+        let is_consecutive = retained_messages.first().map(|message| message.offset)
+            == Some(self.get_next_append_offset())
+            && retained_messages
+                .windows(2)
+                .all(|messages| messages[1].offset == messages[0].offset + 1);
         if self.should_increment_offset {
             self.current_offset = last_offset;
         } else {
@@ -470,7 +705,12 @@ impl Partition {
         }
 
         if let Some(cache) = &mut self.cache {
-            cache.extend(retained_messages);
+            // The cache has to contain the consecutive messages, so it can't be used once some of them are missing.
+            if is_consecutive {
+                cache.extend(retained_messages);
+            } else {
+                cache.purge();
+            }
         }
 
         self.unsaved_messages_count += messages_count;
@@ -493,6 +733,99 @@ impl Partition {
         Ok(())
     }
 
+    /// Appends the batch compressed by the producer, which is validated by its header and checksum and stored as-is,
+    /// unless the deduplication or the idempotence is enabled, which require the message IDs and the sequence numbers.
+    /// The batch is buffered like the plain messages, and decompressed only to extend the cache, if it's enabled.
+    pub async fn append_compressed_messages(
+        &mut self,
+        compressed_messages: CompressedMessages,
+    ) -> Result<(), IggyError> {
+        compressed_messages.validate()?;
+        if self.message_deduplicator.is_some() || self.config.idempotence.enabled {
+            let messages = compressed_messages.decompress()?;
+            let batch_size = messages
+                .iter()
+                .map(|message| message.get_size_bytes() as u64)
+                .sum();
+            let appendable_batch_info = AppendableBatchInfo::new(batch_size, self.partition_id);
+            return self.append_messages(appendable_batch_info, messages).await;
+        }
+
+        // The unsaved plain messages have to be persisted first to preserve the order of batches in the segment.
+        let has_unsaved_plain_messages = self.segments.last().is_some_and(|segment| {
+            segment
+                .unsaved_messages
+                .as_ref()
+                .is_some_and(|batch_accumulator| !batch_accumulator.is_empty())
+        });
+        if has_unsaved_plain_messages {
+            self.flush_unsaved_buffer(false).await?;
+        }
+        self.add_segment_if_last_is_closed().await?;
+
+        let base_offset = self.get_next_append_offset();
+        // Each message gets its own timestamp, just like the ones appended uncompressed.
+        let timestamps = (0..compressed_messages.messages_count)
+            .map(|_| IggyTimestamp::now().as_micros())
+            .collect::<Vec<_>>();
+        let batch = RetainedMessageBatch::from_compressed_messages(
+            base_offset,
+            &timestamps,
+            &compressed_messages,
+        );
+        let last_offset = batch.get_last_offset();
+        let messages_count = compressed_messages.messages_count;
+        if let Some(cache) = &mut self.cache {
+            // The cache has to contain the consecutive messages, so it can't be used if the batch can't be read.
+            match batch.clone().decompress() {
+                Ok(batch) => cache.extend(batch.into_messages_iter().map(Arc::new)),
+                Err(error) => {
+                    warn!(
+                        "Cannot decompress the batch of {} messages for partition with ID: {} to cache it. {}",
+                        messages_count, self.partition_id, error
+                    );
+                    cache.purge();
+                }
+            }
+        }
+        {
+            let last_segment = self.segments.last_mut().ok_or(IggyError::SegmentNotFound)?;
+            last_segment.append_compressed_batch(batch).await?;
+        }
+
+        self.current_offset = last_offset;
+        self.should_increment_offset = true;
+        self.unsaved_messages_count += messages_count;
+        let last_segment = self.segments.last_mut().ok_or(IggyError::SegmentNotFound)?;
+        if self.unsaved_messages_count >= self.config.partition.messages_required_to_save
+            || last_segment.is_full().await
+        {
+            trace!(
+                "Segment with start offset: {} for partition with ID: {} will be persisted on disk...",
+                last_segment.start_offset,
+                self.partition_id
+            );
+
+            last_segment.persist_messages().await?;
+            self.unsaved_messages_count = 0;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn add_segment_if_last_is_closed(&mut self) -> Result<(), IggyError> {
+        let last_segment = self.segments.last().ok_or(IggyError::SegmentNotFound)?;
+        if last_segment.is_closed {
+            let start_offset = last_segment.end_offset + 1;
+            trace!(
+                "Current segment is closed, creating new segment with start offset: {} for partition with ID: {}...",
+                start_offset, self.partition_id
+            );
+            self.add_persisted_segment(start_offset).await?;
+        }
+        Ok(())
+    }
+
     pub async fn flush_unsaved_buffer(&mut self, fsync: bool) -> Result<(), IggyError> {
         let _fsync = fsync;
         if self.unsaved_messages_count == 0 {
@@ -508,11 +841,11 @@ impl Partition {
 
         // Make sure all of the messages from the accumulator are persisted
         // no leftover from one round trip.
-        while last_segment.unsaved_messages.is_some() {
+        while last_segment.has_unsaved_messages() {
             last_segment.persist_messages().await.unwrap();
         }
         self.unsaved_messages_count = 0;
-        Ok(())
+        self.save_producer_sequences().await
     }
 
     fn update_avg_timestamp_delta(
@@ -537,6 +870,7 @@ impl Partition {
 
 #[cfg(test)]
 mod tests {
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
     use iggy::utils::expiry::IggyExpiry;
     use std::sync::atomic::{AtomicU32, AtomicU64};
 
@@ -609,6 +943,7 @@ mod tests {
             config,
             storage,
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
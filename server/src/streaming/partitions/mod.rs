@@ -1,12 +1,19 @@
 use bytes::Bytes;
 use iggy::messages::send_messages;
 
+pub mod compaction;
 pub mod consumer_offsets;
+pub mod dead_letters;
+pub mod memory_storage;
 pub mod messages;
 pub mod partition;
 pub mod persistence;
+pub mod producers;
+pub mod reencryption;
 pub mod segments;
 pub mod storage;
+pub mod transactions;
+pub mod truncation;
 
 #[allow(dead_code)]
 fn create_messages() -> Vec<send_messages::Message> {
@@ -3,15 +3,19 @@ use crate::streaming::cache::buffer::SmartCache;
 use crate::streaming::cache::memory_tracker::CacheMemoryTracker;
 use crate::streaming::deduplication::message_deduplicator::MessageDeduplicator;
 use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::partitions::compaction::CompactionIndex;
 use crate::streaming::segments::segment::Segment;
 use crate::streaming::storage::SystemStorage;
 use dashmap::DashMap;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
 use iggy::consumer::ConsumerKind;
 use iggy::utils::duration::IggyDuration;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
+use tokio::sync::Mutex;
 
 #[derive(Debug)]
 pub struct Partition {
@@ -22,6 +26,10 @@ pub struct Partition {
     pub offsets_path: String,
     pub consumer_offsets_path: String,
     pub consumer_group_offsets_path: String,
+    pub consumer_group_leases_path: String,
+    pub dead_letters_path: String,
+    pub producers_path: String,
+    pub transactions_path: String,
     pub current_offset: u64,
     pub cache: Option<SmartCache<Arc<RetainedMessage>>>,
     pub cached_memory_tracker: Option<Arc<CacheMemoryTracker>>,
@@ -38,8 +46,23 @@ pub struct Partition {
     pub size_bytes: Arc<AtomicU64>,
     pub segments_count_of_parent_stream: Arc<AtomicU32>,
     pub(crate) message_expiry: IggyExpiry,
+    pub(crate) compression_algorithm: CompressionAlgorithm,
     pub(crate) consumer_offsets: DashMap<u32, ConsumerOffset>,
     pub(crate) consumer_group_offsets: DashMap<u32, ConsumerOffset>,
+    pub(crate) consumer_group_leases: DashMap<u32, ConsumerGroupLeases>,
+    pub(crate) consumer_group_leases_lock: Mutex<()>,
+    pub(crate) delivery_attempts: BTreeMap<u64, u32>,
+    pub(crate) poisoned_offsets: HashSet<u64>,
+    pub(crate) pending_dead_letters: HashSet<u64>,
+    pub(crate) dead_letters_journal_length: u64,
+    pub(crate) open_transactions: BTreeMap<u64, u64>,
+    pub(crate) committed_transactions: HashMap<u64, u64>,
+    pub(crate) unsaved_committed_transactions: HashMap<u64, u64>,
+    pub(crate) transactions_journal_length: u64,
+    pub(crate) producer_sequences: HashMap<u64, u64>,
+    pub(crate) unsaved_producer_sequences: HashMap<u64, u64>,
+    pub(crate) producers_journal_length: u64,
+    pub(crate) compaction_index: CompactionIndex,
     pub(crate) segments: Vec<Segment>,
     pub(crate) config: Arc<SystemConfig>,
     pub(crate) storage: Arc<SystemStorage>,
@@ -64,6 +87,59 @@ impl ConsumerOffset {
     }
 }
 
+/// The last sequence number of the idempotent producer saved to the producers journal, along with the offset
+/// up to which the messages of the partition had been persisted when it was saved.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ProducerSequence {
+    pub producer_id: u64,
+    pub sequence: u64,
+    pub persisted_offset: u64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConsumerGroupLeases {
+    pub consumer_group_id: u32,
+    pub next_offset: u64,
+    pub leases: BTreeMap<u64, MessageLease>,
+    pub path: String,
+    pub journal_length: u64,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MessageLease {
+    pub member_id: u32,
+    pub expires_at: IggyTimestamp,
+    pub deliveries: u32,
+}
+
+/// The change of the dead letters state appended to the dead letters journal.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DeadLetterChange {
+    DeliveryAttempts(u64, u32),
+    Poisoned(u64),
+    Evicted(u64),
+}
+
+/// The change of the consumer group leases appended to the leases journal.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MessageLeaseChange {
+    Leased(u64, MessageLease),
+    Acked(u64),
+    NextOffset(u64),
+}
+
+impl ConsumerGroupLeases {
+    pub fn new(consumer_group_id: u32, next_offset: u64, path: &str) -> ConsumerGroupLeases {
+        ConsumerGroupLeases {
+            consumer_group_id,
+            next_offset,
+            leases: BTreeMap::new(),
+            path: format!("{path}/{consumer_group_id}"),
+            journal_length: 0,
+        }
+    }
+}
+
 impl Partition {
     #[allow(clippy::too_many_arguments)]
     pub fn create(
@@ -74,6 +150,7 @@ impl Partition {
         config: Arc<SystemConfig>,
         storage: Arc<SystemStorage>,
         message_expiry: IggyExpiry,
+        compression_algorithm: CompressionAlgorithm,
         messages_count_of_parent_stream: Arc<AtomicU64>,
         messages_count_of_parent_topic: Arc<AtomicU64>,
         size_of_parent_stream: Arc<AtomicU64>,
@@ -87,6 +164,11 @@ impl Partition {
             config.get_consumer_offsets_path(stream_id, topic_id, partition_id);
         let consumer_group_offsets_path =
             config.get_consumer_group_offsets_path(stream_id, topic_id, partition_id);
+        let consumer_group_leases_path =
+            config.get_consumer_group_leases_path(stream_id, topic_id, partition_id);
+        let dead_letters_path = config.get_dead_letters_path(stream_id, topic_id, partition_id);
+        let producers_path = config.get_producers_path(stream_id, topic_id, partition_id);
+        let transactions_path = config.get_transactions_path(stream_id, topic_id, partition_id);
         let (cached_memory_tracker, messages) = match config.cache.enabled {
             false => (None, None),
             true => (
@@ -103,7 +185,12 @@ impl Partition {
             offsets_path,
             consumer_offsets_path,
             consumer_group_offsets_path,
+            consumer_group_leases_path,
+            dead_letters_path,
+            producers_path,
+            transactions_path,
             message_expiry,
+            compression_algorithm,
             cache: messages,
             cached_memory_tracker,
             message_deduplicator: match config.message_deduplication.enabled {
@@ -129,6 +216,20 @@ impl Partition {
             should_increment_offset: false,
             consumer_offsets: DashMap::new(),
             consumer_group_offsets: DashMap::new(),
+            consumer_group_leases: DashMap::new(),
+            consumer_group_leases_lock: Mutex::new(()),
+            delivery_attempts: BTreeMap::new(),
+            poisoned_offsets: HashSet::new(),
+            pending_dead_letters: HashSet::new(),
+            dead_letters_journal_length: 0,
+            open_transactions: BTreeMap::new(),
+            committed_transactions: HashMap::new(),
+            unsaved_committed_transactions: HashMap::new(),
+            transactions_journal_length: 0,
+            producer_sequences: HashMap::new(),
+            unsaved_producer_sequences: HashMap::new(),
+            producers_journal_length: 0,
+            compaction_index: CompactionIndex::default(),
             config,
             storage,
             created_at,
@@ -151,6 +252,7 @@ impl Partition {
                 partition.config.clone(),
                 partition.storage.clone(),
                 partition.message_expiry,
+                partition.compression_algorithm,
                 partition.size_of_parent_stream.clone(),
                 partition.size_of_parent_topic.clone(),
                 partition.size_bytes.clone(),
@@ -177,6 +279,7 @@ mod tests {
     use crate::configs::system::{CacheConfig, SystemConfig};
     use crate::streaming::partitions::partition::Partition;
     use crate::streaming::storage::tests::get_test_system_storage;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
     use iggy::utils::duration::IggyDuration;
     use iggy::utils::expiry::IggyExpiry;
     use iggy::utils::timestamp::IggyTimestamp;
@@ -201,6 +304,7 @@ mod tests {
             config,
             storage,
             message_expiry,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -241,6 +345,7 @@ mod tests {
             }),
             storage,
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
@@ -263,6 +368,7 @@ mod tests {
             Arc::new(SystemConfig::default()),
             storage,
             IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
             Arc::new(AtomicU64::new(0)),
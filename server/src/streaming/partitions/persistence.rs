@@ -1,10 +1,8 @@
 use crate::state::system::PartitionState;
+use crate::streaming::partitions::compaction::CompactionIndex;
 use crate::streaming::partitions::partition::Partition;
 use iggy::error::IggyError;
-use std::path::Path;
 use std::sync::atomic::Ordering;
-use tokio::fs::create_dir;
-use tracing::error;
 
 impl Partition {
     pub async fn load(&mut self, state: PartitionState) -> Result<(), IggyError> {
@@ -26,20 +24,12 @@ impl Partition {
     }
 
     pub async fn purge(&mut self) -> Result<(), IggyError> {
-        self.current_offset = 0;
-        self.unsaved_messages_count = 0;
-        self.should_increment_offset = false;
         self.consumer_offsets.clear();
         self.consumer_group_offsets.clear();
-        if let Some(cache) = self.cache.as_mut() {
-            cache.purge();
-        }
-        for segment in &self.segments {
-            self.storage.segment.delete(segment).await?;
-            self.segments_count_of_parent_stream
-                .fetch_sub(1, Ordering::SeqCst);
-        }
-        self.segments.clear();
+        self.consumer_group_leases.clear();
+        self.delivery_attempts.clear();
+        self.poisoned_offsets.clear();
+        self.purge_messages().await?;
         self.storage
             .partition
             .delete_consumer_offsets(&self.consumer_offsets_path)
@@ -48,32 +38,32 @@ impl Partition {
             .partition
             .delete_consumer_offsets(&self.consumer_group_offsets_path)
             .await?;
-        self.add_persisted_segment(0).await?;
+        self.storage
+            .partition
+            .delete_consumer_offsets(&self.consumer_group_leases_path)
+            .await?;
+        self.save_dead_letters().await?;
 
-        if !Path::new(&self.consumer_offsets_path).exists()
-            && create_dir(&self.consumer_offsets_path).await.is_err()
-        {
-            error!(
-                "Failed to create consumer offsets directory for partition with ID: {} for stream with ID: {} and topic with ID: {}.",
-                self.partition_id, self.stream_id, self.topic_id
-            );
-            return Err(IggyError::CannotCreateConsumerOffsetsDirectory(
-                self.consumer_offsets_path.to_owned(),
-            ));
-        }
+        // Recreates the consumer offsets and leases directories removed above.
+        self.persist().await
+    }
 
-        if !Path::new(&self.consumer_group_offsets_path).exists()
-            && create_dir(&self.consumer_group_offsets_path).await.is_err()
-        {
-            error!(
-                "Failed to create consumer group offsets directory for partition with ID: {} for stream with ID: {} and topic with ID: {}.",
-                self.partition_id, self.stream_id, self.topic_id
-            );
-            return Err(IggyError::CannotCreateConsumerOffsetsDirectory(
-                self.consumer_group_offsets_path.to_owned(),
-            ));
+    /// Deletes all the messages of the partition and starts over from the offset 0, keeping the consumer offsets,
+    /// e.g. when the replica truncates all of its messages diverged from the partition leader.
+    pub async fn purge_messages(&mut self) -> Result<(), IggyError> {
+        self.current_offset = 0;
+        self.unsaved_messages_count = 0;
+        self.should_increment_offset = false;
+        self.compaction_index = CompactionIndex::default();
+        if let Some(cache) = self.cache.as_mut() {
+            cache.purge();
         }
-
-        Ok(())
+        for segment in &self.segments {
+            self.storage.segment.delete(segment).await?;
+            self.segments_count_of_parent_stream
+                .fetch_sub(1, Ordering::SeqCst);
+        }
+        self.segments.clear();
+        self.add_persisted_segment(0).await
     }
 }
@@ -0,0 +1,365 @@
+use crate::streaming::partitions::partition::Partition;
+use iggy::bytes_serializable::BytesSerializable;
+use iggy::error::IggyError;
+use iggy::messages::send_messages::Message;
+use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::models::producer::{PRODUCER_ID_HEADER, PRODUCER_SEQUENCE_HEADER};
+use std::collections::HashMap;
+use tracing::{info, trace, warn};
+
+const MIN_PRODUCERS_JOURNAL_LENGTH: u64 = 1000;
+
+impl Partition {
+    /// Checks the sequence numbers of the messages sent by the idempotent producers against the last ones
+    /// appended to the partition. The already appended messages are skipped, so that the retried batches
+    /// are not duplicated, while a gap in the sequence numbers rejects the whole batch.
+    /// Returns the messages to append along with the last sequence number of each producer within the batch,
+    /// which should be applied only once the messages have been appended.
+    pub fn check_producer_sequences(
+        &self,
+        messages: Vec<Message>,
+    ) -> Result<(Vec<Message>, HashMap<u64, u64>), IggyError> {
+        if !self.config.idempotence.enabled {
+            return Ok((messages, HashMap::new()));
+        }
+
+        let mut last_sequences = HashMap::new();
+        let mut accepted_messages = Vec::with_capacity(messages.len());
+        for message in messages {
+            let Some((producer_id, sequence)) = message
+                .headers
+                .as_ref()
+                .map(get_producer_sequence)
+                .transpose()?
+                .flatten()
+            else {
+                accepted_messages.push(message);
+                continue;
+            };
+
+            let last_sequence = last_sequences
+                .get(&producer_id)
+                .or_else(|| self.producer_sequences.get(&producer_id))
+                .copied();
+            match last_sequence {
+                Some(last_sequence) if sequence <= last_sequence => {
+                    warn!(
+                        "Ignored the duplicated sequence number: {} of producer with ID: {} for partition with ID: {}, last sequence number: {}.",
+                        sequence, producer_id, self.partition_id, last_sequence
+                    );
+                    continue;
+                }
+                Some(last_sequence) if sequence > last_sequence + 1 => {
+                    return Err(IggyError::InvalidProducerSequence(
+                        producer_id,
+                        last_sequence + 1,
+                        sequence,
+                    ));
+                }
+                _ => {}
+            }
+
+            last_sequences.insert(producer_id, sequence);
+            accepted_messages.push(message);
+        }
+        Ok((accepted_messages, last_sequences))
+    }
+
+    /// Stores the last sequence numbers of the producers whose messages have been appended to the partition.
+    /// They are saved along with the messages, see `save_producer_sequences`.
+    pub fn apply_producer_sequences(&mut self, sequences: HashMap<u64, u64>) {
+        for (producer_id, sequence) in sequences {
+            let last_sequence = self.producer_sequences.entry(producer_id).or_default();
+            *last_sequence = (*last_sequence).max(sequence);
+            self.unsaved_producer_sequences
+                .insert(producer_id, *last_sequence);
+            trace!(
+                "Stored the last sequence number: {} of producer with ID: {} for partition with ID: {}.",
+                last_sequence,
+                producer_id,
+                self.partition_id
+            );
+        }
+    }
+
+    /// Appends the sequence numbers applied since the last save to the producers journal, which is kept apart
+    /// from the segments, so that the producers outlive the deleted or compacted segments.
+    /// It should be called once the messages have been persisted, so that the sequence numbers saved
+    /// never get ahead of the messages, which would make the producer's retry after the crash be ignored.
+    /// Each record includes the offset up to which the messages have been persisted, as the crash before the journal
+    /// is appended leaves it behind the messages, whose sequence numbers are then loaded from their headers.
+    /// The journal starts over with the current sequence numbers once it has become much longer than the number of producers.
+    pub async fn save_producer_sequences(&mut self) -> Result<(), IggyError> {
+        if self.unsaved_producer_sequences.is_empty() {
+            return Ok(());
+        }
+
+        let journal_length =
+            self.producers_journal_length + self.unsaved_producer_sequences.len() as u64;
+        let max_journal_length =
+            MIN_PRODUCERS_JOURNAL_LENGTH.max(2 * self.producer_sequences.len() as u64);
+        // The journal doesn't exist yet, until the sequence numbers are saved for the first time.
+        if self.producers_journal_length == 0 || journal_length > max_journal_length {
+            self.storage
+                .partition
+                .save_producer_sequences(
+                    &self.producers_path,
+                    &self.producer_sequences,
+                    self.current_offset,
+                )
+                .await?;
+            self.producers_journal_length = self.producer_sequences.len() as u64;
+        } else {
+            self.storage
+                .partition
+                .append_producer_sequences(
+                    &self.producers_path,
+                    &self.unsaved_producer_sequences,
+                    self.current_offset,
+                )
+                .await?;
+            self.producers_journal_length = journal_length;
+        }
+        self.unsaved_producer_sequences.clear();
+        Ok(())
+    }
+
+    /// Loads the last sequence numbers of the producers from the producers journal.
+    /// Returns the offset up to which the messages had been persisted when the journal was last saved,
+    /// or none if there's no journal yet, e.g. for the partitions created before it was introduced.
+    pub async fn load_producer_sequences(&mut self) -> Result<Option<u64>, IggyError> {
+        let Some(records) = self
+            .storage
+            .partition
+            .load_producer_sequences(&self.producers_path)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        self.producers_journal_length = records.len() as u64;
+        let mut persisted_offset = 0;
+        for record in records {
+            let last_sequence = self
+                .producer_sequences
+                .entry(record.producer_id)
+                .or_default();
+            *last_sequence = (*last_sequence).max(record.sequence);
+            persisted_offset = persisted_offset.max(record.persisted_offset);
+        }
+        info!(
+            "Loaded the sequence numbers of: {} producers for partition with ID: {}, persisted offset: {}.",
+            self.producer_sequences.len(),
+            self.partition_id,
+            persisted_offset
+        );
+        Ok(Some(persisted_offset))
+    }
+
+    /// Loads the last sequence numbers of the producers from the headers of the messages persisted after the offset
+    /// saved in the producers journal, which is appended only once the messages have been persisted,
+    /// so it's left behind them if the server crashes in between. It should be called once the segments are loaded.
+    pub async fn load_unjournaled_producer_sequences(
+        &mut self,
+        persisted_offset: u64,
+    ) -> Result<(), IggyError> {
+        let mut sequences = HashMap::new();
+        for segment in self
+            .segments
+            .iter()
+            .filter(|segment| segment.current_offset > persisted_offset)
+        {
+            let start_offset = segment.start_offset.max(persisted_offset + 1);
+            let count = (segment.current_offset - start_offset + 1) as u32;
+            for message in segment.get_messages(start_offset, count).await? {
+                let Some(headers) = message.headers.clone() else {
+                    continue;
+                };
+
+                let headers = HashMap::from_bytes(headers)?;
+                if let Some((producer_id, sequence)) = get_producer_sequence(&headers)? {
+                    let last_sequence: &mut u64 = sequences.entry(producer_id).or_default();
+                    *last_sequence = (*last_sequence).max(sequence);
+                }
+            }
+        }
+
+        if sequences.is_empty() {
+            return Ok(());
+        }
+
+        warn!(
+            "Loaded the sequence numbers of: {} producers missing in the journal from the messages after offset: {} for partition with ID: {}.",
+            sequences.len(),
+            persisted_offset,
+            self.partition_id
+        );
+        self.apply_producer_sequences(sequences);
+        Ok(())
+    }
+}
+
+/// Returns the ID of the idempotent producer which has sent the message along with its sequence number, if any.
+pub fn get_producer_sequence(
+    headers: &HashMap<HeaderKey, HeaderValue>,
+) -> Result<Option<(u64, u64)>, IggyError> {
+    let producer_id = headers.get(&HeaderKey::new(PRODUCER_ID_HEADER)?);
+    let sequence = headers.get(&HeaderKey::new(PRODUCER_SEQUENCE_HEADER)?);
+    match (producer_id, sequence) {
+        (Some(producer_id), Some(sequence)) => {
+            Ok(Some((producer_id.as_uint64()?, sequence.as_uint64()?)))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::{IdempotenceConfig, SystemConfig};
+    use crate::streaming::batching::appendable_batch_info::AppendableBatchInfo;
+    use crate::streaming::storage::tests::get_test_system_storage;
+    use crate::streaming::storage::SystemStorage;
+    use bytes::Bytes;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
+    use iggy::utils::expiry::IggyExpiry;
+    use iggy::utils::timestamp::IggyTimestamp;
+    use std::sync::atomic::{AtomicU32, AtomicU64};
+    use std::sync::Arc;
+
+    const PRODUCER_ID: u64 = 1;
+
+    #[tokio::test]
+    async fn resent_messages_should_not_be_appended_again() {
+        let mut partition = create_partition(true);
+        append_messages(&mut partition, &[1, 2, 3]).await.unwrap();
+        append_messages(&mut partition, &[1, 2, 3]).await.unwrap();
+        append_messages(&mut partition, &[3, 4]).await.unwrap();
+
+        assert_eq!(partition.current_offset, 3);
+        assert_eq!(partition.producer_sequences.get(&PRODUCER_ID), Some(&4));
+    }
+
+    #[tokio::test]
+    async fn sequence_gap_should_reject_the_batch() {
+        let mut partition = create_partition(true);
+        append_messages(&mut partition, &[1, 2]).await.unwrap();
+
+        let result = append_messages(&mut partition, &[3, 5]).await;
+
+        assert!(matches!(
+            result,
+            Err(IggyError::InvalidProducerSequence(PRODUCER_ID, 4, 5))
+        ));
+        assert_eq!(partition.current_offset, 1);
+        assert_eq!(partition.producer_sequences.get(&PRODUCER_ID), Some(&2));
+    }
+
+    #[tokio::test]
+    async fn sequences_should_be_ignored_when_idempotence_is_disabled() {
+        let mut partition = create_partition(false);
+        append_messages(&mut partition, &[1, 2]).await.unwrap();
+        append_messages(&mut partition, &[1, 2]).await.unwrap();
+
+        assert_eq!(partition.current_offset, 3);
+        assert!(partition.producer_sequences.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sequences_should_be_saved_once_messages_are_persisted() {
+        let config = create_config(true);
+        let storage = Arc::new(SystemStorage::in_memory(config.clone()));
+        let mut partition = create_partition_with_storage(config.clone(), storage.clone());
+        partition.persist().await.unwrap();
+        append_messages(&mut partition, &[1, 2, 3]).await.unwrap();
+
+        let mut loaded_partition = create_partition_with_storage(config.clone(), storage.clone());
+        assert!(loaded_partition
+            .load_producer_sequences()
+            .await
+            .unwrap()
+            .is_none());
+
+        partition.flush_unsaved_buffer(false).await.unwrap();
+        append_messages(&mut partition, &[4]).await.unwrap();
+        partition.flush_unsaved_buffer(false).await.unwrap();
+
+        let mut loaded_partition = create_partition_with_storage(config, storage);
+        assert_eq!(
+            loaded_partition.load_producer_sequences().await.unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            loaded_partition.producer_sequences.get(&PRODUCER_ID),
+            Some(&4)
+        );
+        assert_eq!(loaded_partition.producers_journal_length, 2);
+    }
+
+    async fn append_messages(
+        partition: &mut Partition,
+        sequences: &[u64],
+    ) -> Result<(), IggyError> {
+        let messages = sequences
+            .iter()
+            .map(|sequence| {
+                let headers = HashMap::from([
+                    (
+                        HeaderKey::new(PRODUCER_ID_HEADER).unwrap(),
+                        HeaderValue::from_uint64(PRODUCER_ID).unwrap(),
+                    ),
+                    (
+                        HeaderKey::new(PRODUCER_SEQUENCE_HEADER).unwrap(),
+                        HeaderValue::from_uint64(*sequence).unwrap(),
+                    ),
+                ]);
+                Message::new(None, Bytes::from("message"), Some(headers))
+            })
+            .collect::<Vec<_>>();
+        let appendable_batch_info = AppendableBatchInfo {
+            batch_size: messages.iter().map(|m| m.get_size_bytes() as u64).sum(),
+            partition_id: partition.partition_id,
+        };
+        partition
+            .append_messages(appendable_batch_info, messages)
+            .await
+    }
+
+    fn create_partition(idempotence_enabled: bool) -> Partition {
+        create_partition_with_storage(
+            create_config(idempotence_enabled),
+            Arc::new(get_test_system_storage()),
+        )
+    }
+
+    fn create_config(idempotence_enabled: bool) -> Arc<SystemConfig> {
+        Arc::new(SystemConfig {
+            idempotence: IdempotenceConfig {
+                enabled: idempotence_enabled,
+            },
+            ..Default::default()
+        })
+    }
+
+    fn create_partition_with_storage(
+        config: Arc<SystemConfig>,
+        storage: Arc<SystemStorage>,
+    ) -> Partition {
+        Partition::create(
+            1,
+            2,
+            3,
+            true,
+            config,
+            storage,
+            IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU32::new(0)),
+            IggyTimestamp::now(),
+        )
+    }
+}
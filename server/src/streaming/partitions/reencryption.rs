@@ -0,0 +1,32 @@
+use crate::streaming::partitions::partition::Partition;
+use crate::streaming::utils::keyring::Keyring;
+use iggy::error::IggyError;
+use tracing::info;
+
+impl Partition {
+    /// Re-encrypts the messages of the closed segments of the partition with the given key.
+    /// Returns the number of the re-encrypted messages.
+    pub async fn reencrypt_segments(
+        &mut self,
+        keyring: &Keyring,
+        key_id: u32,
+    ) -> Result<u64, IggyError> {
+        let mut reencrypted_messages = 0;
+        for segment in self.segments.iter_mut().filter(|segment| segment.is_closed) {
+            reencrypted_messages += segment.reencrypt(keyring, key_id).await?;
+        }
+
+        if reencrypted_messages > 0 {
+            // The cached copies of the re-encrypted messages still hold the payloads encrypted with the previous keys.
+            if let Some(cache) = &mut self.cache {
+                cache.purge();
+            }
+
+            info!(
+                "Re-encrypted segments for partition with ID: {}, stream with ID: {}, topic with ID: {}, re-encrypted {} messages.",
+                self.partition_id, self.stream_id, self.topic_id, reencrypted_messages
+            );
+        }
+        Ok(reencrypted_messages)
+    }
+}
@@ -30,6 +30,16 @@ impl Partition {
         &mut self.segments
     }
 
+    /// Returns the start offset of the first segment containing any messages, as the preceding ones might have been
+    /// deleted by the retention policy, or the next offset of the partition if there are no messages at all.
+    pub fn get_log_start_offset(&self) -> u64 {
+        self.segments
+            .iter()
+            .find(|segment| segment.size_bytes > 0)
+            .map(|segment| segment.start_offset)
+            .unwrap_or_else(|| self.get_next_append_offset())
+    }
+
     pub async fn get_expired_segments_start_offsets(&self, now: IggyTimestamp) -> Vec<u64> {
         let mut expired_segments = Vec::new();
         for segment in &self.segments {
@@ -42,6 +52,22 @@ impl Partition {
         expired_segments
     }
 
+    /// Closes the open segment once it's older than the configured `segment.max_age`,
+    /// the new segment is created when the next messages are appended. Returns true if the segment was closed.
+    pub async fn close_aged_segment(&mut self, now: IggyTimestamp) -> Result<bool, IggyError> {
+        let Some(last_segment) = self.segments.last_mut() else {
+            return Ok(false);
+        };
+
+        if !last_segment.close_if_aged(now).await? {
+            return Ok(false);
+        }
+
+        self.unsaved_messages_count = 0;
+        self.save_producer_sequences().await?;
+        Ok(true)
+    }
+
     pub async fn add_persisted_segment(&mut self, start_offset: u64) -> Result<(), IggyError> {
         info!(
             "Creating the new segment for partition with ID: {}, stream with ID: {}, topic with ID: {}...",
@@ -55,6 +81,7 @@ impl Partition {
             self.config.clone(),
             self.storage.clone(),
             self.message_expiry,
+            self.compression_algorithm,
             self.size_of_parent_stream.clone(),
             self.size_of_parent_topic.clone(),
             self.size_bytes.clone(),
@@ -91,8 +118,17 @@ impl Partition {
         }
 
         self.segments.retain(|s| s.start_offset != start_offset);
+        let dead_letters_count = self.delivery_attempts.len() + self.poisoned_offsets.len();
+        self.delivery_attempts
+            .retain(|offset, _| *offset < start_offset || *offset > deleted_segment.end_offset);
+        self.poisoned_offsets
+            .retain(|offset| *offset < start_offset || *offset > deleted_segment.end_offset);
+        if dead_letters_count != self.delivery_attempts.len() + self.poisoned_offsets.len() {
+            self.save_dead_letters().await?;
+        }
         self.segments
             .sort_by(|a, b| a.start_offset.cmp(&b.start_offset));
+        self.prune_committed_transactions().await?;
         info!(
             "Segment with start offset: {} has been deleted from partition with ID: {}, stream with ID: {}, topic with ID: {}",
             start_offset, self.partition_id, self.stream_id, self.topic_id
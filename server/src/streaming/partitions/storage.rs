@@ -1,15 +1,20 @@
 use crate::compat::message_conversion::message_converter::MessageFormatConverter;
 use crate::state::system::PartitionState;
 use crate::streaming::batching::batch_accumulator::BatchAccumulator;
-use crate::streaming::partitions::partition::{ConsumerOffset, Partition};
+use crate::streaming::partitions::partition::{
+    ConsumerGroupLeases, ConsumerOffset, DeadLetterChange, MessageLease, MessageLeaseChange,
+    Partition, ProducerSequence,
+};
 use crate::streaming::persistence::persister::Persister;
-use crate::streaming::segments::segment::{Segment, LOG_EXTENSION};
+use crate::streaming::segments::segment::{Segment, LOG_EXTENSION, OFFLOADED_EXTENSION};
 use crate::streaming::storage::PartitionStorage;
 use crate::streaming::utils::file;
 use anyhow::Context;
 use async_trait::async_trait;
+use bytes::{Buf, BufMut, BytesMut};
 use iggy::consumer::ConsumerKind;
 use iggy::error::IggyError;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -51,6 +56,21 @@ impl PartitionStorage for FilePartitionStorage {
                 return Err(IggyError::CannotReadPartitions(err));
             }
 
+        // Load the last sequence numbers of the idempotent producers, so that the batches retried after the restart
+        // are not appended again.
+        let journaled_offset = if partition.config.idempotence.enabled {
+            partition.load_producer_sequences().await?
+        } else {
+            None
+        };
+        let has_producers_journal = journaled_offset.is_some();
+        // Load the committed transactions, so that their messages remain visible for the read committed consumers,
+        // while the messages of the aborted or interrupted ones are hidden.
+        let has_transactions_journal = if partition.config.transaction.enabled {
+            partition.load_committed_transactions().await?
+        } else {
+            false
+        };
         let mut dir_entries = dir_entries.unwrap();
         while let Some(dir_entry) = dir_entries.next_entry().await.unwrap_or(None) {
             let metadata = dir_entry.metadata().await.unwrap();
@@ -59,18 +79,22 @@ impl PartitionStorage for FilePartitionStorage {
             }
 
             let path = dir_entry.path();
-            let extension = path.extension();
-            if extension.is_none() || extension.unwrap() != LOG_EXTENSION {
+            let Some(extension) = path.extension() else {
+                continue;
+            };
+
+            // The offloaded segment has no local log file, only the marker file, see `Segment::offload`.
+            let is_offloaded = extension == OFFLOADED_EXTENSION;
+            if extension != LOG_EXTENSION && !is_offloaded {
                 continue;
             }
 
-            let log_file_name = dir_entry
-                .file_name()
-                .into_string()
+            let start_offset = path
+                .file_stem()
+                .and_then(|file_stem| file_stem.to_str())
                 .unwrap()
-                .replace(&format!(".{}", LOG_EXTENSION), "");
-
-            let start_offset = log_file_name.parse::<u64>().unwrap();
+                .parse::<u64>()
+                .unwrap();
             let mut segment = Segment::create(
                 partition.stream_id,
                 partition.topic_id,
@@ -79,6 +103,7 @@ impl PartitionStorage for FilePartitionStorage {
                 partition.config.clone(),
                 partition.storage.clone(),
                 partition.message_expiry,
+                partition.compression_algorithm,
                 partition.size_of_parent_stream.clone(),
                 partition.size_of_parent_topic.clone(),
                 partition.size_bytes.clone(),
@@ -87,6 +112,7 @@ impl PartitionStorage for FilePartitionStorage {
                 partition.messages_count.clone(),
             );
 
+            segment.is_offloaded = is_offloaded;
             let log_path = segment.log_path.to_owned();
             let index_path = segment.index_path.to_owned();
             let message_format_converter =
@@ -94,8 +120,13 @@ impl PartitionStorage for FilePartitionStorage {
 
             info!("Attempting to detect changes in binary schema for partition with ID: {} and segment with start offset: {}", partition.partition_id, start_offset);
             let samplers_count = message_format_converter.samplers.len();
-            // Check if partition has any segments
-            for (idx, sampler) in message_format_converter.samplers.iter().enumerate() {
+            // Check if partition has any segments, the offloaded ones could only be archived in the newest format.
+            for (idx, sampler) in message_format_converter
+                .samplers
+                .iter()
+                .enumerate()
+                .filter(|_| !is_offloaded)
+            {
                 trace!("Trying to sample the message format for partition with ID: {} and segment with start offset: {}", partition.partition_id, start_offset);
                 match sampler.try_sample().await {
                     Ok(schema) if idx == 0 => {
@@ -156,6 +187,30 @@ impl PartitionStorage for FilePartitionStorage {
                 info!("Loaded: {} unique message IDs for partition with ID: {} and segment with start offset: {}...", unique_message_ids_count, partition.partition_id, segment.start_offset);
             }
 
+            // The partitions created before the transactions journal was introduced have the committed transactions
+            // loaded from the commit markers, and saved to the journal afterwards.
+            if partition.config.transaction.enabled && !has_transactions_journal {
+                let transactions = segment
+                    .storage
+                    .segment
+                    .load_committed_transactions(&segment)
+                    .await?;
+                info!("Loaded: {} committed transactions for partition with ID: {} and segment with start offset: {}.", transactions.len(), partition.partition_id, segment.start_offset);
+                partition.committed_transactions.extend(transactions);
+            }
+
+            // The partitions created before the producers journal was introduced have the last sequence numbers
+            // of the idempotent producers loaded from the messages headers, and saved to the journal afterwards.
+            if partition.config.idempotence.enabled && !has_producers_journal {
+                let sequences = segment
+                    .storage
+                    .segment
+                    .load_producer_sequences(&segment)
+                    .await?;
+                info!("Loaded the sequence numbers of: {} producers for partition with ID: {} and segment with start offset: {}.", sequences.len(), partition.partition_id, segment.start_offset);
+                partition.apply_producer_sequences(sequences);
+            }
+
             partition
                 .segments_count_of_parent_stream
                 .fetch_add(1, Ordering::SeqCst);
@@ -180,6 +235,9 @@ impl PartitionStorage for FilePartitionStorage {
             }
 
             segment.end_offset = end_offsets[end_offset_index];
+            // Only the last segment can be appended to, e.g. the compacted one could be no longer full.
+            segment.is_closed = true;
+            segment.unsaved_messages = None;
         }
 
         if !partition.segments.is_empty() {
@@ -191,7 +249,29 @@ impl PartitionStorage for FilePartitionStorage {
             partition.current_offset = last_segment.current_offset;
         }
 
+        if let Some(journaled_offset) = journaled_offset {
+            partition
+                .load_unjournaled_producer_sequences(journaled_offset)
+                .await?;
+        }
+
         partition.load_consumer_offsets().await?;
+        partition.save_producer_sequences().await?;
+        if partition.config.transaction.enabled {
+            if !has_transactions_journal {
+                partition
+                    .storage
+                    .partition
+                    .save_committed_transactions(
+                        &partition.transactions_path,
+                        &partition.committed_transactions,
+                    )
+                    .await?;
+                partition.transactions_journal_length =
+                    partition.committed_transactions.len() as u64;
+            }
+            partition.prune_committed_transactions().await?;
+        }
         info!(
             "Loaded partition with ID: {} for stream with ID: {} and topic with ID: {}, current offset: {}.",
             partition.partition_id, partition.stream_id, partition.topic_id, partition.current_offset
@@ -259,6 +339,22 @@ impl PartitionStorage for FilePartitionStorage {
             ));
         }
 
+        if !Path::new(&partition.consumer_group_leases_path).exists()
+            && create_dir(&partition.consumer_group_leases_path)
+                .await
+                .is_err()
+        {
+            error!(
+                "Failed to create consumer group leases directory for partition with ID: {} for stream with ID: {} and topic with ID: {}.",
+                partition.partition_id, partition.stream_id, partition.topic_id
+            );
+            return Err(IggyError::CannotCreatePartition(
+                partition.partition_id,
+                partition.stream_id,
+                partition.topic_id,
+            ));
+        }
+
         for segment in partition.get_segments() {
             segment.persist().await?;
         }
@@ -408,4 +504,410 @@ impl PartitionStorage for FilePartitionStorage {
         }
         Ok(())
     }
+
+    async fn save_consumer_group_leases(
+        &self,
+        leases: &ConsumerGroupLeases,
+    ) -> Result<(), IggyError> {
+        self.persister
+            .overwrite(&leases.path, &encode_consumer_group_leases(leases))
+            .await?;
+        trace!(
+            "Stored {} message leases with next offset: {} for consumer group with ID: {}, path: {}",
+            leases.leases.len(),
+            leases.next_offset,
+            leases.consumer_group_id,
+            leases.path
+        );
+        Ok(())
+    }
+
+    async fn append_consumer_group_lease_changes(
+        &self,
+        path: &str,
+        changes: &[MessageLeaseChange],
+    ) -> Result<(), IggyError> {
+        self.persister
+            .append(path, &encode_lease_changes(changes))
+            .await?;
+        trace!(
+            "Appended {} message lease changes, path: {path}",
+            changes.len()
+        );
+        Ok(())
+    }
+
+    async fn load_consumer_group_leases(
+        &self,
+        path: &str,
+    ) -> Result<Vec<ConsumerGroupLeases>, IggyError> {
+        trace!("Loading consumer group leases from path: {path}...");
+        // The partitions created before the leases were introduced don't have the directory yet.
+        if !Path::new(path).exists() {
+            if create_dir(path).await.is_err() {
+                return Err(IggyError::CannotCreateConsumerOffsetsDirectory(
+                    path.to_owned(),
+                ));
+            }
+            return Ok(Vec::new());
+        }
+
+        let dir_entries = fs::read_dir(&path).await;
+        if dir_entries.is_err() {
+            return Err(IggyError::CannotReadConsumerOffsets(path.to_owned()));
+        }
+
+        let mut consumer_group_leases = Vec::new();
+        let mut dir_entries = dir_entries.unwrap();
+        while let Some(dir_entry) = dir_entries.next_entry().await.unwrap_or(None) {
+            let name = dir_entry.file_name().into_string().unwrap();
+            let consumer_group_id = name.parse::<u32>();
+            if consumer_group_id.is_err() {
+                error!(
+                    "Invalid consumer group ID leases file with name: '{}'.",
+                    name
+                );
+                continue;
+            }
+
+            let bytes = fs::read(dir_entry.path()).await?;
+            let Some(leases) =
+                decode_consumer_group_leases(consumer_group_id.unwrap(), path, &bytes)
+            else {
+                error!("Invalid consumer group leases file with name: '{}'.", name);
+                continue;
+            };
+            consumer_group_leases.push(leases);
+        }
+
+        consumer_group_leases.sort_by_key(|leases| leases.consumer_group_id);
+        Ok(consumer_group_leases)
+    }
+
+    async fn save_dead_letters(&self, partition: &Partition) -> Result<(), IggyError> {
+        let bytes = encode_dead_letters(partition);
+        self.persister
+            .overwrite(&partition.dead_letters_path, &bytes)
+            .await?;
+        trace!(
+            "Stored {} poisoned offsets and {} delivery attempts for partition with ID: {}, path: {}",
+            partition.poisoned_offsets.len(),
+            partition.delivery_attempts.len(),
+            partition.partition_id,
+            partition.dead_letters_path
+        );
+        Ok(())
+    }
+
+    async fn append_dead_letter_changes(
+        &self,
+        path: &str,
+        changes: &[DeadLetterChange],
+    ) -> Result<(), IggyError> {
+        self.persister
+            .append(path, &encode_dead_letter_changes(changes))
+            .await?;
+        trace!(
+            "Appended {} dead letter changes, path: {path}",
+            changes.len()
+        );
+        Ok(())
+    }
+
+    async fn save_producer_sequences(
+        &self,
+        path: &str,
+        sequences: &HashMap<u64, u64>,
+        persisted_offset: u64,
+    ) -> Result<(), IggyError> {
+        self.persister
+            .overwrite(
+                path,
+                &encode_producer_sequences(sequences, persisted_offset),
+            )
+            .await?;
+        trace!(
+            "Stored the sequence numbers of {} producers, path: {path}",
+            sequences.len()
+        );
+        Ok(())
+    }
+
+    async fn append_producer_sequences(
+        &self,
+        path: &str,
+        sequences: &HashMap<u64, u64>,
+        persisted_offset: u64,
+    ) -> Result<(), IggyError> {
+        self.persister
+            .append(
+                path,
+                &encode_producer_sequences(sequences, persisted_offset),
+            )
+            .await?;
+        trace!(
+            "Appended the sequence numbers of {} producers, path: {path}",
+            sequences.len()
+        );
+        Ok(())
+    }
+
+    async fn load_producer_sequences(
+        &self,
+        path: &str,
+    ) -> Result<Option<Vec<ProducerSequence>>, IggyError> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(path).await?;
+        Ok(Some(decode_producer_sequences(&bytes)))
+    }
+
+    async fn save_committed_transactions(
+        &self,
+        path: &str,
+        transactions: &HashMap<u64, u64>,
+    ) -> Result<(), IggyError> {
+        self.persister
+            .overwrite(path, &encode_committed_transactions(transactions))
+            .await?;
+        trace!(
+            "Stored {} committed transactions, path: {path}",
+            transactions.len()
+        );
+        Ok(())
+    }
+
+    async fn append_committed_transactions(
+        &self,
+        path: &str,
+        transactions: &HashMap<u64, u64>,
+    ) -> Result<(), IggyError> {
+        self.persister
+            .append(path, &encode_committed_transactions(transactions))
+            .await?;
+        trace!(
+            "Appended {} committed transactions, path: {path}",
+            transactions.len()
+        );
+        Ok(())
+    }
+
+    async fn load_committed_transactions(
+        &self,
+        path: &str,
+    ) -> Result<Option<HashMap<u64, u64>>, IggyError> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(path).await?;
+        Ok(Some(decode_committed_transactions(&bytes)))
+    }
+
+    async fn load_dead_letters(&self, partition: &mut Partition) -> Result<(), IggyError> {
+        // The partitions created before the dead letters were persisted don't have the file yet.
+        if !Path::new(&partition.dead_letters_path).exists() {
+            return Ok(());
+        }
+
+        let bytes = fs::read(&partition.dead_letters_path).await?;
+        if !decode_dead_letters(partition, &bytes) {
+            error!(
+                "Invalid dead letters file: '{}'.",
+                partition.dead_letters_path
+            );
+        }
+        Ok(())
+    }
+}
+
+const LEASE_CHANGE_SIZE: usize = 25;
+const PRODUCER_SEQUENCE_RECORD_LENGTH: usize = 24;
+const COMMITTED_TRANSACTION_RECORD_LENGTH: usize = 16;
+const LEASED_CHANGE_KIND: u8 = 1;
+const ACKED_CHANGE_KIND: u8 = 2;
+const NEXT_OFFSET_CHANGE_KIND: u8 = 3;
+
+/// Encodes the changes of the consumer group leases as the journal records of the same size:
+/// the kind (u8), the offset (u64), the member ID (u32), the expiry (u64) and the deliveries (u32).
+pub(crate) fn encode_lease_changes(changes: &[MessageLeaseChange]) -> BytesMut {
+    let mut bytes = BytesMut::with_capacity(LEASE_CHANGE_SIZE * changes.len());
+    for change in changes.iter().copied() {
+        let (kind, offset, lease) = match change {
+            MessageLeaseChange::Leased(offset, lease) => (LEASED_CHANGE_KIND, offset, Some(lease)),
+            MessageLeaseChange::Acked(offset) => (ACKED_CHANGE_KIND, offset, None),
+            MessageLeaseChange::NextOffset(offset) => (NEXT_OFFSET_CHANGE_KIND, offset, None),
+        };
+        bytes.put_u8(kind);
+        bytes.put_u64_le(offset);
+        bytes.put_u32_le(lease.map_or(0, |lease| lease.member_id));
+        bytes.put_u64_le(lease.map_or(0, |lease| lease.expires_at.into()));
+        bytes.put_u32_le(lease.map_or(0, |lease| lease.deliveries));
+    }
+    bytes
+}
+
+/// Encodes the consumer group leases as the journal starting over with the current state.
+pub(crate) fn encode_consumer_group_leases(leases: &ConsumerGroupLeases) -> BytesMut {
+    let mut changes = Vec::with_capacity(leases.leases.len() + 1);
+    changes.push(MessageLeaseChange::NextOffset(leases.next_offset));
+    changes.extend(
+        leases
+            .leases
+            .iter()
+            .map(|(offset, lease)| MessageLeaseChange::Leased(*offset, *lease)),
+    );
+    encode_lease_changes(&changes)
+}
+
+/// Replays the journal of the consumer group leases, returns None if it contains an invalid record.
+/// The incomplete record at the end, e.g. torn by the crash while appending, is skipped.
+pub(crate) fn decode_consumer_group_leases(
+    consumer_group_id: u32,
+    path: &str,
+    bytes: &[u8],
+) -> Option<ConsumerGroupLeases> {
+    let mut leases = ConsumerGroupLeases::new(consumer_group_id, 0, path);
+    for mut record in bytes.chunks_exact(LEASE_CHANGE_SIZE) {
+        let kind = record.get_u8();
+        let offset = record.get_u64_le();
+        match kind {
+            LEASED_CHANGE_KIND => {
+                let lease = MessageLease {
+                    member_id: record.get_u32_le(),
+                    expires_at: record.get_u64_le().into(),
+                    deliveries: record.get_u32_le(),
+                };
+                leases.leases.insert(offset, lease);
+            }
+            ACKED_CHANGE_KIND => {
+                leases.leases.remove(&offset);
+            }
+            NEXT_OFFSET_CHANGE_KIND => leases.next_offset = offset,
+            _ => return None,
+        }
+        leases.journal_length += 1;
+    }
+    Some(leases)
+}
+
+/// Encodes the last sequence numbers of the producers as the journal records: the producer ID (u64), the sequence (u64)
+/// and the offset (u64) up to which the messages of the partition had been persisted when they were saved.
+pub(crate) fn encode_producer_sequences(
+    sequences: &HashMap<u64, u64>,
+    persisted_offset: u64,
+) -> BytesMut {
+    let mut bytes = BytesMut::with_capacity(PRODUCER_SEQUENCE_RECORD_LENGTH * sequences.len());
+    for (producer_id, sequence) in sequences {
+        bytes.put_u64_le(*producer_id);
+        bytes.put_u64_le(*sequence);
+        bytes.put_u64_le(persisted_offset);
+    }
+    bytes
+}
+
+/// Decodes the journal records of the producer sequences, the incomplete record at the end is skipped.
+pub(crate) fn decode_producer_sequences(bytes: &[u8]) -> Vec<ProducerSequence> {
+    bytes
+        .chunks_exact(PRODUCER_SEQUENCE_RECORD_LENGTH)
+        .map(|mut record| ProducerSequence {
+            producer_id: record.get_u64_le(),
+            sequence: record.get_u64_le(),
+            persisted_offset: record.get_u64_le(),
+        })
+        .collect()
+}
+
+/// Encodes the committed transactions as the journal records: the transaction ID (u64) and the offset of its commit marker (u64).
+pub(crate) fn encode_committed_transactions(transactions: &HashMap<u64, u64>) -> BytesMut {
+    let mut bytes =
+        BytesMut::with_capacity(COMMITTED_TRANSACTION_RECORD_LENGTH * transactions.len());
+    for (transaction_id, marker_offset) in transactions {
+        bytes.put_u64_le(*transaction_id);
+        bytes.put_u64_le(*marker_offset);
+    }
+    bytes
+}
+
+/// Decodes the journal records of the committed transactions, the incomplete record at the end is skipped.
+pub(crate) fn decode_committed_transactions(bytes: &[u8]) -> HashMap<u64, u64> {
+    bytes
+        .chunks_exact(COMMITTED_TRANSACTION_RECORD_LENGTH)
+        .map(|mut record| (record.get_u64_le(), record.get_u64_le()))
+        .collect()
+}
+
+const DEAD_LETTER_CHANGE_SIZE: usize = 13;
+const DELIVERY_ATTEMPTS_CHANGE_KIND: u8 = 1;
+const POISONED_CHANGE_KIND: u8 = 2;
+const EVICTED_CHANGE_KIND: u8 = 3;
+
+/// Encodes the changes of the dead letters as the journal records of the same size:
+/// the kind (u8), the offset (u64) and the delivery attempts (u32).
+pub(crate) fn encode_dead_letter_changes(changes: &[DeadLetterChange]) -> BytesMut {
+    let mut bytes = BytesMut::with_capacity(DEAD_LETTER_CHANGE_SIZE * changes.len());
+    for change in changes.iter().copied() {
+        let (kind, offset, delivery_attempts) = match change {
+            DeadLetterChange::DeliveryAttempts(offset, delivery_attempts) => {
+                (DELIVERY_ATTEMPTS_CHANGE_KIND, offset, delivery_attempts)
+            }
+            DeadLetterChange::Poisoned(offset) => (POISONED_CHANGE_KIND, offset, 0),
+            DeadLetterChange::Evicted(offset) => (EVICTED_CHANGE_KIND, offset, 0),
+        };
+        bytes.put_u8(kind);
+        bytes.put_u64_le(offset);
+        bytes.put_u32_le(delivery_attempts);
+    }
+    bytes
+}
+
+/// Encodes the dead letters of the partition as the journal starting over with the current state.
+pub(crate) fn encode_dead_letters(partition: &Partition) -> BytesMut {
+    let changes = partition
+        .poisoned_offsets
+        .iter()
+        .map(|offset| DeadLetterChange::Poisoned(*offset))
+        .chain(
+            partition
+                .delivery_attempts
+                .iter()
+                .map(|(offset, attempts)| DeadLetterChange::DeliveryAttempts(*offset, *attempts)),
+        )
+        .collect::<Vec<_>>();
+    encode_dead_letter_changes(&changes)
+}
+
+/// Replays the journal of the dead letters into the partition,
+/// returns false and leaves the partition intact if it contains an invalid record.
+/// The incomplete record at the end, e.g. torn by the crash while appending, is skipped.
+pub(crate) fn decode_dead_letters(partition: &mut Partition, bytes: &[u8]) -> bool {
+    let mut poisoned_offsets = HashSet::new();
+    let mut delivery_attempts = BTreeMap::new();
+    let mut journal_length = 0;
+    for mut record in bytes.chunks_exact(DEAD_LETTER_CHANGE_SIZE) {
+        let kind = record.get_u8();
+        let offset = record.get_u64_le();
+        let attempts = record.get_u32_le();
+        match kind {
+            DELIVERY_ATTEMPTS_CHANGE_KIND => {
+                delivery_attempts.insert(offset, attempts);
+            }
+            POISONED_CHANGE_KIND => {
+                delivery_attempts.remove(&offset);
+                poisoned_offsets.insert(offset);
+            }
+            EVICTED_CHANGE_KIND => {
+                delivery_attempts.remove(&offset);
+            }
+            _ => return false,
+        }
+        journal_length += 1;
+    }
+
+    partition.poisoned_offsets = poisoned_offsets;
+    partition.delivery_attempts = delivery_attempts;
+    partition.dead_letters_journal_length = journal_length;
+    true
 }
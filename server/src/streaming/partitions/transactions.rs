@@ -0,0 +1,445 @@
+use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::partitions::partition::Partition;
+use crate::streaming::sizeable::Sizeable;
+use bytes::Bytes;
+use iggy::bytes_serializable::BytesSerializable;
+use iggy::error::IggyError;
+use iggy::messages::poll_messages::IsolationLevel;
+use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::models::messages::MessageState;
+use iggy::models::transaction::TRANSACTION_ID_HEADER;
+use iggy::utils::checksum;
+use iggy::utils::timestamp::IggyTimestamp;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, trace};
+
+const MIN_TRANSACTIONS_JOURNAL_LENGTH: u64 = 1000;
+
+impl Partition {
+    /// Registers the transaction which is about to append the messages to the partition.
+    /// The offset of its first message is kept, as none of the messages from this offset onwards
+    /// can be read with the read committed isolation level until the transaction is completed.
+    pub fn register_transaction(&mut self, transaction_id: u64) {
+        if self.open_transactions.contains_key(&transaction_id) {
+            return;
+        }
+
+        let first_offset = self.get_next_append_offset();
+        trace!(
+            "Registered transaction with ID: {} and first offset: {} for partition with ID: {}.",
+            transaction_id,
+            first_offset,
+            self.partition_id
+        );
+        self.open_transactions.insert(transaction_id, first_offset);
+    }
+
+    /// Completes the transaction by appending the commit or abort marker to the partition.
+    /// Does nothing if the transaction hasn't appended any messages to the partition.
+    /// The commit marker is persisted right away, and the transaction stays open until the marker is appended,
+    /// so that the failed completion can be retried.
+    pub async fn complete_transaction(
+        &mut self,
+        transaction_id: u64,
+        committed: bool,
+    ) -> Result<(), IggyError> {
+        if !self.open_transactions.contains_key(&transaction_id) {
+            return Ok(());
+        }
+
+        // The commit marker might have been appended already, by the attempt which failed to persist it or before the restart.
+        if !self.committed_transactions.contains_key(&transaction_id) {
+            self.add_segment_if_last_is_closed().await?;
+            let marker = Arc::new(Self::create_transaction_marker(
+                self.get_next_append_offset(),
+                transaction_id,
+                committed,
+            )?);
+            trace!(
+                "Appending transaction marker: {} with offset: {} for partition with ID: {}...",
+                marker.message_state,
+                marker.offset,
+                self.partition_id
+            );
+            let marker_offset = marker.offset;
+            self.append_retained_messages(marker.get_size_bytes() as u64, vec![marker])
+                .await?;
+            if committed {
+                self.committed_transactions
+                    .insert(transaction_id, marker_offset);
+                self.unsaved_committed_transactions
+                    .insert(transaction_id, marker_offset);
+            }
+        }
+
+        if committed {
+            self.flush_unsaved_buffer(true).await?;
+            self.save_committed_transactions().await?;
+        }
+        self.open_transactions.remove(&transaction_id);
+        Ok(())
+    }
+
+    /// Appends the transactions committed since the last save to the transactions journal, along with the offsets
+    /// of their commit markers, so that they don't have to be loaded from the segments on startup.
+    /// The journal starts over with the current transactions once it has become much longer than their number.
+    pub async fn save_committed_transactions(&mut self) -> Result<(), IggyError> {
+        if self.unsaved_committed_transactions.is_empty() {
+            return Ok(());
+        }
+
+        let journal_length =
+            self.transactions_journal_length + self.unsaved_committed_transactions.len() as u64;
+        let max_journal_length =
+            MIN_TRANSACTIONS_JOURNAL_LENGTH.max(2 * self.committed_transactions.len() as u64);
+        // The journal doesn't exist yet, until the committed transactions are saved for the first time.
+        if self.transactions_journal_length == 0 || journal_length > max_journal_length {
+            self.storage
+                .partition
+                .save_committed_transactions(&self.transactions_path, &self.committed_transactions)
+                .await?;
+            self.transactions_journal_length = self.committed_transactions.len() as u64;
+        } else {
+            self.storage
+                .partition
+                .append_committed_transactions(
+                    &self.transactions_path,
+                    &self.unsaved_committed_transactions,
+                )
+                .await?;
+            self.transactions_journal_length = journal_length;
+        }
+        self.unsaved_committed_transactions.clear();
+        Ok(())
+    }
+
+    /// Loads the committed transactions from the transactions journal.
+    /// Returns false if there's no journal yet, e.g. for the partitions created before it was introduced,
+    /// whose committed transactions have to be loaded from the commit markers in the segments.
+    pub async fn load_committed_transactions(&mut self) -> Result<bool, IggyError> {
+        let Some(transactions) = self
+            .storage
+            .partition
+            .load_committed_transactions(&self.transactions_path)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        self.transactions_journal_length = transactions.len() as u64;
+        info!(
+            "Loaded: {} committed transactions for partition with ID: {} from the journal.",
+            transactions.len(),
+            self.partition_id
+        );
+        self.committed_transactions.extend(transactions);
+        Ok(true)
+    }
+
+    /// Removes the committed transactions whose messages are no longer retained, i.e. the ones with the commit marker
+    /// before the first segment, as all the messages of the transaction precede its marker.
+    pub async fn prune_committed_transactions(&mut self) -> Result<(), IggyError> {
+        let Some(first_offset) = self.segments.first().map(|segment| segment.start_offset) else {
+            return Ok(());
+        };
+
+        let transactions_count = self.committed_transactions.len();
+        self.committed_transactions
+            .retain(|_, marker_offset| *marker_offset >= first_offset);
+        self.unsaved_committed_transactions
+            .retain(|_, marker_offset| *marker_offset >= first_offset);
+        let pruned_transactions = transactions_count - self.committed_transactions.len();
+        if pruned_transactions == 0 {
+            return Ok(());
+        }
+
+        self.storage
+            .partition
+            .save_committed_transactions(&self.transactions_path, &self.committed_transactions)
+            .await?;
+        self.transactions_journal_length = self.committed_transactions.len() as u64;
+        trace!(
+            "Pruned {} committed transactions before offset: {} for partition with ID: {}.",
+            pruned_transactions,
+            first_offset,
+            self.partition_id
+        );
+        Ok(())
+    }
+
+    /// Returns the offset of the last message which can be read with the read committed isolation level,
+    /// which is the one right before the first message of the oldest open transaction.
+    pub fn get_last_stable_offset(&self) -> Option<u64> {
+        if !self.should_increment_offset {
+            return None;
+        }
+
+        match self.open_transactions.values().min() {
+            Some(0) => None,
+            Some(first_offset) => Some(first_offset - 1),
+            None => Some(self.current_offset),
+        }
+    }
+
+    /// Checks whether the message can be returned to the consumer with the given isolation level.
+    /// The transaction markers and the poisoned messages moved to the dead letter queue are never returned,
+    /// while the read committed isolation level additionally hides the messages of the open, aborted or interrupted transactions.
+    pub fn is_visible(
+        &self,
+        message: &RetainedMessage,
+        isolation: IsolationLevel,
+    ) -> Result<bool, IggyError> {
+        if message.message_state.is_transaction_marker() || self.is_poisoned(message.offset) {
+            return Ok(false);
+        }
+
+        if isolation == IsolationLevel::ReadUncommitted {
+            return Ok(true);
+        }
+
+        match get_transaction_id(message)? {
+            Some(transaction_id) => Ok(self.committed_transactions.contains_key(&transaction_id)),
+            None => Ok(true),
+        }
+    }
+
+    /// Returns the messages visible with the given isolation level, skipping the ones past the last stable offset,
+    /// along with the offset of the last scanned message, so that the hidden messages are not scanned again.
+    pub fn filter_visible_messages(
+        &self,
+        messages: Vec<Arc<RetainedMessage>>,
+        isolation: IsolationLevel,
+    ) -> Result<(Vec<Arc<RetainedMessage>>, Option<u64>), IggyError> {
+        let max_offset = match isolation {
+            IsolationLevel::ReadCommitted => self.get_last_stable_offset(),
+            IsolationLevel::ReadUncommitted => Some(self.current_offset),
+        };
+        let Some(max_offset) = max_offset else {
+            return Ok((Vec::new(), None));
+        };
+
+        let mut last_scanned_offset = None;
+        let mut visible_messages = Vec::with_capacity(messages.len());
+        for message in messages {
+            if message.offset > max_offset {
+                break;
+            }
+
+            last_scanned_offset = Some(message.offset);
+            if self.is_visible(&message, isolation)? {
+                visible_messages.push(message);
+            }
+        }
+        Ok((visible_messages, last_scanned_offset))
+    }
+
+    pub(crate) fn get_next_append_offset(&self) -> u64 {
+        if self.should_increment_offset {
+            self.current_offset + 1
+        } else {
+            0
+        }
+    }
+
+    fn create_transaction_marker(
+        offset: u64,
+        transaction_id: u64,
+        committed: bool,
+    ) -> Result<RetainedMessage, IggyError> {
+        let headers = HashMap::from([(
+            HeaderKey::new(TRANSACTION_ID_HEADER)?,
+            HeaderValue::from_uint64(transaction_id)?,
+        )]);
+        let payload = Bytes::copy_from_slice(&transaction_id.to_le_bytes());
+        Ok(RetainedMessage {
+            id: 0,
+            offset,
+            timestamp: IggyTimestamp::now().as_micros(),
+            checksum: checksum::calculate(&payload),
+            message_state: match committed {
+                true => MessageState::TransactionCommitted,
+                false => MessageState::TransactionAborted,
+            },
+            headers: Some(headers.to_bytes()),
+            payload,
+        })
+    }
+}
+
+/// Returns the ID of the transaction within which the message was sent, if any.
+pub fn get_transaction_id(message: &RetainedMessage) -> Result<Option<u64>, IggyError> {
+    let Some(headers) = &message.headers else {
+        return Ok(None);
+    };
+
+    let headers = HashMap::from_bytes(headers.clone())?;
+    headers
+        .get(&HeaderKey::new(TRANSACTION_ID_HEADER)?)
+        .map(|value| value.as_uint64())
+        .transpose()
+}
+
+/// Returns the ID of the transaction completed by the marker, which is stored in its payload.
+pub(crate) fn get_marker_transaction_id(marker: &RetainedMessage) -> Result<u64, IggyError> {
+    let Some(transaction_id) = marker.payload.get(..8) else {
+        error!(
+            "Invalid payload length: {} of the transaction marker with offset: {}.",
+            marker.payload.len(),
+            marker.offset
+        );
+        return Err(IggyError::InvalidMessagePayloadLength);
+    };
+
+    Ok(u64::from_le_bytes(transaction_id.try_into()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::SystemConfig;
+    use crate::streaming::batching::appendable_batch_info::AppendableBatchInfo;
+    use crate::streaming::storage::tests::get_test_system_storage;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
+    use iggy::messages::send_messages::Message;
+    use iggy::utils::expiry::IggyExpiry;
+    use std::sync::atomic::{AtomicU32, AtomicU64};
+
+    const TRANSACTION_ID: u64 = 1;
+
+    #[tokio::test]
+    async fn last_stable_offset_should_stop_before_open_transaction() {
+        let mut partition = create_partition();
+        append_messages(&mut partition, None, 2).await;
+        assert_eq!(partition.get_last_stable_offset(), Some(1));
+
+        partition.register_transaction(TRANSACTION_ID);
+        append_messages(&mut partition, Some(TRANSACTION_ID), 2).await;
+        append_messages(&mut partition, None, 1).await;
+
+        assert_eq!(partition.current_offset, 4);
+        assert_eq!(partition.get_last_stable_offset(), Some(1));
+
+        partition
+            .complete_transaction(TRANSACTION_ID, true)
+            .await
+            .unwrap();
+        assert_eq!(partition.current_offset, 5);
+        assert_eq!(partition.get_last_stable_offset(), Some(5));
+    }
+
+    #[tokio::test]
+    async fn committed_transaction_messages_should_be_visible() {
+        let mut partition = create_partition();
+        partition.register_transaction(TRANSACTION_ID);
+        append_messages(&mut partition, Some(TRANSACTION_ID), 2).await;
+        assert_eq!(partition.get_last_stable_offset(), None);
+
+        partition
+            .complete_transaction(TRANSACTION_ID, true)
+            .await
+            .unwrap();
+
+        let messages = partition.get_messages_by_offset(0, 10).await.unwrap();
+        assert_eq!(messages.len(), 3);
+        assert!(messages[2].message_state.is_transaction_marker());
+        for message in &messages[..2] {
+            assert!(partition
+                .is_visible(message, IsolationLevel::ReadCommitted)
+                .unwrap());
+        }
+        assert!(!partition
+            .is_visible(&messages[2], IsolationLevel::ReadUncommitted)
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn aborted_transaction_messages_should_be_visible_only_when_reading_uncommitted() {
+        let mut partition = create_partition();
+        partition.register_transaction(TRANSACTION_ID);
+        append_messages(&mut partition, Some(TRANSACTION_ID), 2).await;
+        partition
+            .complete_transaction(TRANSACTION_ID, false)
+            .await
+            .unwrap();
+
+        let messages = partition.get_messages_by_offset(0, 10).await.unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[2].message_state, MessageState::TransactionAborted);
+        for message in &messages[..2] {
+            assert!(!partition
+                .is_visible(message, IsolationLevel::ReadCommitted)
+                .unwrap());
+            assert!(partition
+                .is_visible(message, IsolationLevel::ReadUncommitted)
+                .unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn completing_unknown_transaction_should_not_append_marker() {
+        let mut partition = create_partition();
+        append_messages(&mut partition, None, 1).await;
+
+        partition
+            .complete_transaction(TRANSACTION_ID, true)
+            .await
+            .unwrap();
+
+        assert_eq!(partition.current_offset, 0);
+        assert!(partition.committed_transactions.is_empty());
+    }
+
+    #[test]
+    fn marker_with_short_payload_should_be_rejected() {
+        let mut marker = Partition::create_transaction_marker(0, TRANSACTION_ID, true).unwrap();
+        assert_eq!(get_marker_transaction_id(&marker).unwrap(), TRANSACTION_ID);
+
+        marker.payload = Bytes::from_static(&[1, 2, 3]);
+        assert!(matches!(
+            get_marker_transaction_id(&marker),
+            Err(IggyError::InvalidMessagePayloadLength)
+        ));
+    }
+
+    async fn append_messages(partition: &mut Partition, transaction_id: Option<u64>, count: u32) {
+        let messages = (0..count)
+            .map(|_| {
+                let headers = transaction_id.map(|transaction_id| {
+                    HashMap::from([(
+                        HeaderKey::new(TRANSACTION_ID_HEADER).unwrap(),
+                        HeaderValue::from_uint64(transaction_id).unwrap(),
+                    )])
+                });
+                Message::new(None, Bytes::from("message"), headers)
+            })
+            .collect::<Vec<_>>();
+        let appendable_batch_info = AppendableBatchInfo {
+            batch_size: messages.iter().map(|m| m.get_size_bytes() as u64).sum(),
+            partition_id: partition.partition_id,
+        };
+        partition
+            .append_messages(appendable_batch_info, messages)
+            .await
+            .unwrap();
+    }
+
+    fn create_partition() -> Partition {
+        Partition::create(
+            1,
+            2,
+            3,
+            true,
+            Arc::new(SystemConfig::default()),
+            Arc::new(get_test_system_storage()),
+            IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU32::new(0)),
+            IggyTimestamp::now(),
+        )
+    }
+}
@@ -0,0 +1,82 @@
+use crate::streaming::partitions::compaction::CompactionIndex;
+use crate::streaming::partitions::partition::Partition;
+use iggy::error::IggyError;
+use tracing::info;
+
+impl Partition {
+    /// Removes the messages starting from the given offset, keeping the preceding ones and the consumer offsets,
+    /// e.g. when the replica has more messages than the new partition leader had at the beginning of its epoch.
+    /// The segments following the offset are deleted, the one containing it is rewritten, and the next messages
+    /// are appended from this offset. If none of the messages precede the offset, the partition starts over from 0.
+    /// Returns the number of the removed messages.
+    pub async fn truncate_messages(&mut self, offset: u64) -> Result<u64, IggyError> {
+        if !self.should_increment_offset || offset > self.current_offset {
+            return Ok(0);
+        }
+
+        self.flush_unsaved_buffer(false).await?;
+        let mut removed_messages = 0;
+        while let Some(segment) = self.segments.last_mut() {
+            if segment.size_bytes > 0 && segment.current_offset < offset {
+                break;
+            }
+
+            // The offloaded segment can't be rewritten, so it's deleted altogether and its messages are fetched again.
+            if segment.start_offset < offset && !segment.is_offloaded {
+                removed_messages += segment.truncate(offset).await?;
+                if segment.size_bytes > 0 {
+                    break;
+                }
+            }
+
+            let start_offset = segment.start_offset;
+            removed_messages += self.delete_segment(start_offset).await?.messages_count;
+        }
+
+        if self.segments.is_empty() {
+            self.purge_messages().await?;
+            return Ok(removed_messages);
+        }
+
+        self.current_offset = self.segments.last().unwrap().current_offset;
+        self.compaction_index = CompactionIndex::default();
+        if let Some(cache) = self.cache.as_mut() {
+            cache.purge();
+        }
+
+        let dead_letters_count = self.delivery_attempts.len() + self.poisoned_offsets.len();
+        self.delivery_attempts
+            .retain(|message_offset, _| *message_offset < offset);
+        self.poisoned_offsets
+            .retain(|message_offset| *message_offset < offset);
+        if dead_letters_count != self.delivery_attempts.len() + self.poisoned_offsets.len() {
+            self.save_dead_letters().await?;
+        }
+
+        info!(
+            "Truncated partition with ID: {}, stream with ID: {}, topic with ID: {} at offset: {}, removed {} messages, current offset: {}.",
+            self.partition_id, self.stream_id, self.topic_id, offset, removed_messages, self.current_offset
+        );
+        Ok(removed_messages)
+    }
+
+    /// Moves the next offset of the partition forward to the given one, without appending any messages,
+    /// e.g. when the preceding messages have already been deleted from the partition leader by the retention policy.
+    /// The offset isn't persisted, as it's derived from the stored messages when loading the partition.
+    pub fn advance_offset(&mut self, offset: u64) {
+        if offset == 0 || offset <= self.get_next_append_offset() {
+            return;
+        }
+
+        self.current_offset = offset - 1;
+        self.should_increment_offset = true;
+        // The cache has to contain the consecutive messages, so it can't be used once some of them are skipped.
+        if let Some(cache) = self.cache.as_mut() {
+            cache.purge();
+        }
+        info!(
+            "Advanced partition with ID: {}, stream with ID: {}, topic with ID: {} to offset: {}.",
+            self.partition_id, self.stream_id, self.topic_id, offset
+        );
+    }
+}
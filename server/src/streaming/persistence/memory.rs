@@ -0,0 +1,154 @@
+use crate::streaming::persistence::persister::Persister;
+use async_trait::async_trait;
+use bytes::BytesMut;
+use iggy::error::IggyError;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::sync::RwLock;
+
+/// The persister keeping the files in memory, keyed by their paths, used by the in-memory storage backend.
+/// There are no directories, the files of the directory are the ones with the paths starting with its path.
+#[derive(Debug, Default)]
+pub struct MemoryPersister {
+    files: RwLock<HashMap<String, BytesMut>>,
+}
+
+impl MemoryPersister {
+    pub fn exists(&self, path: &str) -> bool {
+        self.files.read().unwrap().contains_key(path)
+    }
+
+    /// Returns the size of the file, or `None` if it doesn't exist.
+    pub fn size(&self, path: &str) -> Option<u64> {
+        self.files
+            .read()
+            .unwrap()
+            .get(path)
+            .map(|bytes| bytes.len() as u64)
+    }
+
+    /// Calls the function with the content of the file, or returns `None` if it doesn't exist.
+    pub fn read<R>(&self, path: &str, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        self.files.read().unwrap().get(path).map(|bytes| f(bytes))
+    }
+
+    /// Returns the sorted paths of the files directly within the directory.
+    pub fn list(&self, directory: &str) -> Vec<String> {
+        let prefix = format!("{directory}/");
+        let mut paths = self
+            .files
+            .read()
+            .unwrap()
+            .keys()
+            .filter(|path| {
+                path.strip_prefix(&prefix)
+                    .is_some_and(|name| !name.contains('/'))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    /// Removes the directory along with all the files within it, returns the number of the removed files.
+    pub fn delete_all(&self, directory: &str) -> usize {
+        let prefix = format!("{directory}/");
+        let mut files = self.files.write().unwrap();
+        let count = files.len();
+        files.retain(|path, _| path != directory && !path.starts_with(&prefix));
+        count - files.len()
+    }
+
+    pub fn truncate(&self, path: &str, size: u64) -> Result<(), IggyError> {
+        let mut files = self.files.write().unwrap();
+        let Some(bytes) = files.get_mut(path) else {
+            return Err(std::io::Error::from(ErrorKind::NotFound).into());
+        };
+        bytes.truncate(size as usize);
+        Ok(())
+    }
+
+    pub fn rename(&self, old_path: &str, new_path: &str) -> Result<(), IggyError> {
+        let mut files = self.files.write().unwrap();
+        let Some(bytes) = files.remove(old_path) else {
+            return Err(std::io::Error::from(ErrorKind::NotFound).into());
+        };
+        files.insert(new_path.to_owned(), bytes);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Persister for MemoryPersister {
+    async fn append(&self, path: &str, bytes: &[u8]) -> Result<(), IggyError> {
+        let mut files = self.files.write().unwrap();
+        let Some(file) = files.get_mut(path) else {
+            return Err(std::io::Error::from(ErrorKind::NotFound).into());
+        };
+        file.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    async fn overwrite(&self, path: &str, bytes: &[u8]) -> Result<(), IggyError> {
+        self.files
+            .write()
+            .unwrap()
+            .insert(path.to_owned(), BytesMut::from(bytes));
+        Ok(())
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), IggyError> {
+        if self.files.write().unwrap().remove(path).is_none() {
+            return Err(std::io::Error::from(ErrorKind::NotFound).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn files_should_be_appended_overwritten_and_deleted() {
+        let persister = MemoryPersister::default();
+        assert!(persister.append("data/file", b"bytes").await.is_err());
+
+        persister.overwrite("data/file", b"first").await.unwrap();
+        persister.append("data/file", b" second").await.unwrap();
+        assert_eq!(persister.size("data/file"), Some(12));
+        assert_eq!(
+            persister.read("data/file", |bytes| bytes.to_vec()),
+            Some(b"first second".to_vec())
+        );
+
+        persister.truncate("data/file", 5).unwrap();
+        assert_eq!(
+            persister.read("data/file", |bytes| bytes.to_vec()),
+            Some(b"first".to_vec())
+        );
+
+        persister.rename("data/file", "data/renamed").unwrap();
+        assert!(!persister.exists("data/file"));
+        assert!(persister.exists("data/renamed"));
+
+        persister.delete("data/renamed").await.unwrap();
+        assert!(persister.delete("data/renamed").await.is_err());
+        assert!(persister.truncate("data/renamed", 0).is_err());
+        assert!(persister.rename("data/renamed", "data/file").is_err());
+    }
+
+    #[tokio::test]
+    async fn directory_should_list_and_delete_only_its_files() {
+        let persister = MemoryPersister::default();
+        for path in ["data/b", "data/a", "data/nested/c", "data_other/d"] {
+            persister.overwrite(path, &[]).await.unwrap();
+        }
+
+        assert_eq!(persister.list("data"), vec!["data/a", "data/b"]);
+        assert_eq!(persister.list("data/nested"), vec!["data/nested/c"]);
+        assert_eq!(persister.delete_all("data"), 3);
+        assert!(persister.list("data").is_empty());
+        assert!(persister.exists("data_other/d"));
+    }
+}
@@ -1 +1,4 @@
+pub mod memory;
 pub mod persister;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub mod uring;
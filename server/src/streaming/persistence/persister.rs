@@ -43,6 +43,7 @@ impl Persister for FilePersister {
     async fn overwrite(&self, path: &str, bytes: &[u8]) -> Result<(), IggyError> {
         let mut file = file::overwrite(path).await?;
         file.write_all(bytes).await?;
+        file.set_len(bytes.len() as u64).await?;
         Ok(())
     }
 
@@ -64,6 +65,7 @@ impl Persister for FileWithSyncPersister {
     async fn overwrite(&self, path: &str, bytes: &[u8]) -> Result<(), IggyError> {
         let mut file = file::overwrite(path).await?;
         file.write_all(bytes).await?;
+        file.set_len(bytes.len() as u64).await?;
         file.sync_all().await?;
         Ok(())
     }
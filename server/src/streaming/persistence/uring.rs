@@ -0,0 +1,756 @@
+use crate::streaming::persistence::persister::Persister;
+use async_trait::async_trait;
+use iggy::error::IggyError;
+use io_uring::{opcode, squeue, types, IoUring};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::CString;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tracing::{error, info, warn};
+
+const RING_ENTRIES: u32 = 256;
+const MAX_SUBMIT_RETRIES: u32 = 10;
+const SUBMIT_RETRY_BACKOFF: Duration = Duration::from_millis(10);
+const READ_CHUNK_SIZE: usize = 512 * 1000;
+// The offset which makes the write start at the current position of the file, i.e. its end for the appended files.
+const CURRENT_POSITION: u64 = u64::MAX;
+// The user data of the eventfd read waking the ring up, never assigned to the requests.
+const WAKEUP_ID: u64 = u64::MAX;
+
+static INSTANCE: OnceLock<Result<Arc<Uring>, String>> = OnceLock::new();
+
+/// The io_uring instance shared by the persisters and the segment storage.
+/// The operations are submitted to the ring and completed by the dedicated thread,
+/// so they never occupy the blocking thread pool used by the standard asynchronous file API.
+/// If the ring fails, the pending operations are failed with its error and the following ones are rejected.
+/// Every queued request signals the eventfd read by the ring, so that the thread waiting for the completions
+/// picks it up right away, instead of once the operations already in flight are completed.
+#[derive(Debug)]
+pub struct Uring {
+    sender: flume::Sender<Request>,
+    wakeup: Arc<OwnedFd>,
+}
+
+pub type UringFile = Arc<OwnedFd>;
+
+struct Request {
+    operation: Operation,
+    sender: oneshot::Sender<io::Result<Completion>>,
+}
+
+enum Operation {
+    Open {
+        path: CString,
+        flags: i32,
+    },
+    Write {
+        file: UringFile,
+        offset: u64,
+        bytes: Vec<u8>,
+        written: usize,
+    },
+    Read {
+        file: UringFile,
+        offset: u64,
+        bytes: Vec<u8>,
+        read: usize,
+    },
+    Fsync {
+        file: UringFile,
+    },
+    Unlink {
+        path: CString,
+    },
+}
+
+enum Completion {
+    File(OwnedFd),
+    Bytes(Vec<u8>),
+    Done,
+}
+
+impl Uring {
+    /// Returns the shared instance, starting the ring on the first use.
+    /// Fails if the kernel doesn't support io_uring or doesn't permit it, e.g. due to the seccomp profile of the container,
+    /// the failure is kept, so the ring is never started again.
+    pub fn get_instance() -> Result<Arc<Uring>, io::Error> {
+        INSTANCE
+            .get_or_init(|| {
+                Uring::new(RING_ENTRIES)
+                    .map(Arc::new)
+                    .map_err(|error| error.to_string())
+            })
+            .clone()
+            .map_err(io::Error::other)
+    }
+
+    fn new(entries: u32) -> Result<Self, io::Error> {
+        let ring = IoUring::new(entries)?;
+        // SAFETY: the eventfd is created with the valid flags and owned from now on, if succeeded.
+        let wakeup = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC) };
+        if wakeup < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let wakeup = Arc::new(unsafe { OwnedFd::from_raw_fd(wakeup) });
+        let (sender, receiver) = flume::unbounded();
+        let ring_wakeup = wakeup.clone();
+        std::thread::Builder::new()
+            .name("iggy-io-uring".to_string())
+            .spawn(move || run(ring, receiver, ring_wakeup))?;
+        info!("Started io_uring instance with {entries} entries.");
+        Ok(Self { sender, wakeup })
+    }
+
+    pub async fn open(&self, path: &str, flags: i32) -> Result<UringFile, io::Error> {
+        let path = CString::new(path).map_err(|error| io::Error::other(error.to_string()))?;
+        match self
+            .submit(Operation::Open {
+                path,
+                flags: flags | libc::O_CLOEXEC,
+            })
+            .await?
+        {
+            Completion::File(file) => Ok(Arc::new(file)),
+            _ => Err(io::Error::other("Unexpected io_uring completion.")),
+        }
+    }
+
+    /// Writes the bytes at the given offset, or at the end of the file opened with `O_APPEND`, if `None`.
+    /// Nothing is submitted for no bytes, as the write completing with zero bytes is treated as a failure.
+    pub async fn write(
+        &self,
+        file: &UringFile,
+        offset: Option<u64>,
+        bytes: Vec<u8>,
+    ) -> Result<(), io::Error> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        self.submit(Operation::Write {
+            file: file.clone(),
+            offset: offset.unwrap_or(CURRENT_POSITION),
+            bytes,
+            written: 0,
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Reads up to the given length of bytes starting at the offset, less bytes are returned at the end of the file.
+    pub async fn read(
+        &self,
+        file: &UringFile,
+        offset: u64,
+        length: usize,
+    ) -> Result<Vec<u8>, io::Error> {
+        match self
+            .submit(Operation::Read {
+                file: file.clone(),
+                offset,
+                bytes: vec![0; length],
+                read: 0,
+            })
+            .await?
+        {
+            Completion::Bytes(bytes) => Ok(bytes),
+            _ => Err(io::Error::other("Unexpected io_uring completion.")),
+        }
+    }
+
+    /// Reads the bytes starting at the offset until the end of the file.
+    pub async fn read_to_end(&self, file: &UringFile, offset: u64) -> Result<Vec<u8>, io::Error> {
+        let mut bytes = Vec::new();
+        loop {
+            let chunk = self
+                .read(file, offset + bytes.len() as u64, READ_CHUNK_SIZE)
+                .await?;
+            let is_last = chunk.len() < READ_CHUNK_SIZE;
+            bytes.extend_from_slice(&chunk);
+            if is_last {
+                return Ok(bytes);
+            }
+        }
+    }
+
+    pub async fn fsync(&self, file: &UringFile) -> Result<(), io::Error> {
+        self.submit(Operation::Fsync { file: file.clone() }).await?;
+        Ok(())
+    }
+
+    pub async fn unlink(&self, path: &str) -> Result<(), io::Error> {
+        let path = CString::new(path).map_err(|error| io::Error::other(error.to_string()))?;
+        self.submit(Operation::Unlink { path }).await?;
+        Ok(())
+    }
+
+    async fn submit(&self, operation: Operation) -> Result<Completion, io::Error> {
+        let (sender, receiver) = oneshot::channel();
+        self.sender
+            .send(Request { operation, sender })
+            .map_err(|_| io::Error::other("The io_uring instance has been stopped."))?;
+        self.wake_up();
+        receiver
+            .await
+            .map_err(|_| io::Error::other("The io_uring instance has been stopped."))?
+    }
+}
+
+impl Uring {
+    /// Increments the eventfd counter, completing the read pending in the ring.
+    /// The failure is ignored, as the counter can't overflow in practice and the ring is woken up anyway
+    /// by the next completion or request.
+    fn wake_up(&self) {
+        let value = 1u64;
+        // SAFETY: the eventfd is owned by the instance and the value is the required 8 bytes long.
+        unsafe {
+            libc::write(
+                self.wakeup.as_raw_fd(),
+                &value as *const u64 as *const libc::c_void,
+                size_of::<u64>(),
+            );
+        }
+    }
+}
+
+impl Operation {
+    // The buffers and the paths are owned by the operation, which is kept until it's completed,
+    // thus the pointers passed to the ring remain valid, even if the awaiting future is dropped.
+    fn build_entry(&mut self) -> squeue::Entry {
+        match self {
+            Operation::Open { path, flags } => {
+                opcode::OpenAt::new(types::Fd(libc::AT_FDCWD), path.as_ptr())
+                    .flags(*flags)
+                    .mode(0o644)
+                    .build()
+            }
+            Operation::Write {
+                file,
+                offset,
+                bytes,
+                written,
+            } => {
+                let offset = match *offset {
+                    CURRENT_POSITION => CURRENT_POSITION,
+                    offset => offset + *written as u64,
+                };
+                let remaining = &bytes[*written..];
+                opcode::Write::new(
+                    types::Fd(file.as_raw_fd()),
+                    remaining.as_ptr(),
+                    remaining.len() as u32,
+                )
+                .offset(offset)
+                .build()
+            }
+            Operation::Read {
+                file,
+                offset,
+                bytes,
+                read,
+            } => {
+                let remaining = &mut bytes[*read..];
+                opcode::Read::new(
+                    types::Fd(file.as_raw_fd()),
+                    remaining.as_mut_ptr(),
+                    remaining.len() as u32,
+                )
+                .offset(*offset + *read as u64)
+                .build()
+            }
+            Operation::Fsync { file } => opcode::Fsync::new(types::Fd(file.as_raw_fd())).build(),
+            Operation::Unlink { path } => {
+                opcode::UnlinkAt::new(types::Fd(libc::AT_FDCWD), path.as_ptr()).build()
+            }
+        }
+    }
+
+    /// Returns the completion, or `None` if only a part of the bytes has been written or read,
+    /// in which case the operation has to be submitted again for the remaining ones.
+    fn complete(&mut self, result: i32) -> Option<io::Result<Completion>> {
+        if result < 0 {
+            return Some(Err(io::Error::from_raw_os_error(-result)));
+        }
+
+        let result = result as usize;
+        match self {
+            // SAFETY: the successful open returns the new file descriptor, which is owned from now on.
+            Operation::Open { .. } => Some(Ok(Completion::File(unsafe {
+                OwnedFd::from_raw_fd(result as i32)
+            }))),
+            Operation::Write { bytes, written, .. } => {
+                if result == 0 {
+                    return Some(Err(io::Error::from(io::ErrorKind::WriteZero)));
+                }
+
+                *written += result;
+                if *written < bytes.len() {
+                    return None;
+                }
+                Some(Ok(Completion::Done))
+            }
+            Operation::Read { bytes, read, .. } => {
+                *read += result;
+                if result > 0 && *read < bytes.len() {
+                    return None;
+                }
+                bytes.truncate(*read);
+                Some(Ok(Completion::Bytes(std::mem::take(bytes))))
+            }
+            Operation::Fsync { .. } | Operation::Unlink { .. } => Some(Ok(Completion::Done)),
+        }
+    }
+}
+
+/// The action taken when the operations can't be submitted to the ring.
+#[derive(Debug, PartialEq)]
+enum SubmitFailure {
+    /// The wait has been interrupted by a signal, the submission is repeated right away.
+    Retry,
+    /// The kernel is temporarily out of resources, e.g. the completion queue is full,
+    /// so the available completions are reaped after the backoff, before the submission is repeated.
+    Backoff(Duration),
+    /// The ring is unusable, all the requests are failed and the ring is shut down.
+    Shutdown,
+}
+
+impl SubmitFailure {
+    fn from_error(error: &io::Error, retries: u32) -> Self {
+        if error.kind() == io::ErrorKind::Interrupted {
+            return SubmitFailure::Retry;
+        }
+
+        if matches!(error.raw_os_error(), Some(libc::EAGAIN | libc::EBUSY))
+            && retries < MAX_SUBMIT_RETRIES
+        {
+            return SubmitFailure::Backoff(SUBMIT_RETRY_BACKOFF * (retries + 1));
+        }
+
+        SubmitFailure::Shutdown
+    }
+}
+
+fn run(mut ring: IoUring, receiver: flume::Receiver<Request>, wakeup: Arc<OwnedFd>) {
+    let mut pending = VecDeque::new();
+    let mut in_flight: HashMap<u64, Request> = HashMap::new();
+    let mut next_id = 0u64;
+    let mut retries = 0;
+    // The counter of the eventfd read by the ring, boxed to keep its address while the read is in flight.
+    let mut wakeup_counter = Box::new(0u64);
+    let mut is_wakeup_armed = false;
+    loop {
+        if !is_wakeup_armed {
+            let entry = opcode::Read::new(
+                types::Fd(wakeup.as_raw_fd()),
+                wakeup_counter.as_mut() as *mut u64 as *mut u8,
+                size_of::<u64>() as u32,
+            )
+            .build()
+            .user_data(WAKEUP_ID);
+            // SAFETY: the counter is kept until the read is completed, or leaked when the ring is shut down.
+            is_wakeup_armed = unsafe { ring.submission().push(&entry) }.is_ok();
+        }
+
+        pending.extend(receiver.try_iter());
+        if pending.is_empty() && in_flight.is_empty() && receiver.is_disconnected() {
+            std::mem::forget(wakeup_counter);
+            return;
+        }
+
+        while let Some(mut request) = pending.pop_front() {
+            let entry = request.operation.build_entry().user_data(next_id);
+            // SAFETY: the entry points to the data owned by the request, which is kept in flight until it's completed.
+            if unsafe { ring.submission().push(&entry) }.is_err() {
+                pending.push_front(request);
+                break;
+            }
+            in_flight.insert(next_id, request);
+            next_id = next_id.wrapping_add(1) % WAKEUP_ID;
+        }
+
+        match ring.submit_and_wait(1) {
+            Ok(_) => retries = 0,
+            Err(error) => match SubmitFailure::from_error(&error, retries) {
+                SubmitFailure::Retry => continue,
+                SubmitFailure::Backoff(backoff) => {
+                    retries += 1;
+                    warn!("Cannot submit the operations to io_uring, retrying in {backoff:?}. {error}");
+                    std::thread::sleep(backoff);
+                }
+                SubmitFailure::Shutdown => {
+                    error!("Cannot submit the operations to io_uring, shutting it down. {error}");
+                    fail_requests(&error, in_flight, pending, &receiver);
+                    std::mem::forget(wakeup_counter);
+                    return;
+                }
+            },
+        }
+
+        let completions = ring
+            .completion()
+            .map(|entry| (entry.user_data(), entry.result()))
+            .collect::<Vec<_>>();
+        for (id, result) in completions {
+            // The queued requests are picked up in the next iteration, which also arms the wakeup again.
+            if id == WAKEUP_ID {
+                is_wakeup_armed = false;
+                continue;
+            }
+
+            let Some(mut request) = in_flight.remove(&id) else {
+                continue;
+            };
+
+            match request.operation.complete(result) {
+                Some(completion) => {
+                    let _ = request.sender.send(completion);
+                }
+                None => pending.push_back(request),
+            }
+        }
+    }
+}
+
+/// Fails all the requests with the submission error, including the ones still waiting in the channel.
+/// The operations pushed to the ring are leaked rather than dropped, as the kernel might still access their buffers.
+fn fail_requests(
+    error: &io::Error,
+    in_flight: HashMap<u64, Request>,
+    pending: VecDeque<Request>,
+    receiver: &flume::Receiver<Request>,
+) {
+    for Request { operation, sender } in in_flight.into_values() {
+        std::mem::forget(operation);
+        let _ = sender.send(Err(io::Error::new(error.kind(), error.to_string())));
+    }
+
+    for Request { sender, .. } in pending.into_iter().chain(receiver.drain()) {
+        let _ = sender.send(Err(io::Error::new(error.kind(), error.to_string())));
+    }
+}
+
+/// The persister submitting the file operations to the shared io_uring instance.
+#[derive(Debug)]
+pub struct UringPersister {
+    uring: Arc<Uring>,
+    enforce_fsync: bool,
+}
+
+impl UringPersister {
+    pub fn new(uring: Arc<Uring>, enforce_fsync: bool) -> Self {
+        Self {
+            uring,
+            enforce_fsync,
+        }
+    }
+}
+
+#[async_trait]
+impl Persister for UringPersister {
+    async fn append(&self, path: &str, bytes: &[u8]) -> Result<(), IggyError> {
+        let file = self
+            .uring
+            .open(path, libc::O_WRONLY | libc::O_APPEND)
+            .await?;
+        self.uring.write(&file, None, bytes.to_vec()).await?;
+        if self.enforce_fsync {
+            self.uring.fsync(&file).await?;
+        }
+        Ok(())
+    }
+
+    async fn overwrite(&self, path: &str, bytes: &[u8]) -> Result<(), IggyError> {
+        let file = self
+            .uring
+            .open(path, libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC)
+            .await?;
+        self.uring.write(&file, Some(0), bytes.to_vec()).await?;
+        if self.enforce_fsync {
+            self.uring.fsync(&file).await?;
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), IggyError> {
+        self.uring.unlink(path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn persister_should_append_and_overwrite_the_file() {
+        let Some(uring) = get_uring() else {
+            return;
+        };
+        let persister = UringPersister::new(uring, true);
+        let directory = TestDirectory::new();
+        let path = directory.file_path("append");
+
+        persister.overwrite(&path, &[]).await.unwrap();
+        assert!(std::fs::read(&path).unwrap().is_empty());
+        persister.overwrite(&path, b"first").await.unwrap();
+        persister.append(&path, b" second").await.unwrap();
+        persister.append(&path, b" third").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"first second third");
+
+        persister.overwrite(&path, b"overwritten").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"overwritten");
+
+        persister.delete(&path).await.unwrap();
+        assert!(!PathBuf::from(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn uring_should_read_the_written_bytes_at_the_given_offsets() {
+        let Some(uring) = get_uring() else {
+            return;
+        };
+        let directory = TestDirectory::new();
+        let path = directory.file_path("read");
+        let file = uring
+            .open(&path, libc::O_RDWR | libc::O_CREAT | libc::O_TRUNC)
+            .await
+            .unwrap();
+        let bytes = (0..READ_CHUNK_SIZE * 2 + 100)
+            .map(|index| (index % 251) as u8)
+            .collect::<Vec<_>>();
+        uring.write(&file, Some(0), bytes.clone()).await.unwrap();
+        uring.write(&file, Some(10), vec![0; 5]).await.unwrap();
+        let mut expected_bytes = bytes;
+        expected_bytes[10..15].fill(0);
+
+        let read_bytes = uring.read(&file, 5, 20).await.unwrap();
+        assert_eq!(read_bytes, expected_bytes[5..25]);
+        let read_bytes = uring
+            .read(&file, expected_bytes.len() as u64 - 10, 100)
+            .await
+            .unwrap();
+        assert_eq!(read_bytes, expected_bytes[expected_bytes.len() - 10..]);
+        let read_bytes = uring.read_to_end(&file, 0).await.unwrap();
+        assert_eq!(read_bytes, expected_bytes);
+        let read_bytes = uring
+            .read_to_end(&file, expected_bytes.len() as u64 + 10)
+            .await
+            .unwrap();
+        assert!(read_bytes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn request_should_not_wait_for_the_operations_in_flight() {
+        let Some(uring) = get_uring() else {
+            return;
+        };
+        let directory = TestDirectory::new();
+        let fifo_path = directory.file_path("fifo");
+        let fifo_path_c = CString::new(fifo_path.clone()).unwrap();
+        // SAFETY: the path is a valid C string.
+        assert_eq!(unsafe { libc::mkfifo(fifo_path_c.as_ptr(), 0o644) }, 0);
+        // Opening the FIFO for reading stays in flight until it's opened for writing.
+        let blocked_uring = uring.clone();
+        let blocked_path = fifo_path.clone();
+        let blocked_open =
+            tokio::spawn(async move { blocked_uring.open(&blocked_path, libc::O_RDONLY).await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let persister = UringPersister::new(uring, false);
+        let path = directory.file_path("file");
+        tokio::time::timeout(Duration::from_secs(5), persister.overwrite(&path, b"bytes"))
+            .await
+            .expect("The request has been blocked by the operation in flight.")
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"bytes");
+
+        let _writer = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&fifo_path)
+            .unwrap();
+        assert!(blocked_open.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn persister_should_fail_for_the_missing_file() {
+        let Some(uring) = get_uring() else {
+            return;
+        };
+        let persister = UringPersister::new(uring.clone(), false);
+        let directory = TestDirectory::new();
+        let path = directory.file_path("missing");
+
+        assert!(persister.append(&path, b"bytes").await.is_err());
+        assert!(persister.delete(&path).await.is_err());
+        let error = uring.open(&path, libc::O_RDONLY).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn partial_write_should_be_resubmitted_until_all_bytes_are_written() {
+        let directory = TestDirectory::new();
+        let mut operation = Operation::Write {
+            file: directory.open_file("write"),
+            offset: 100,
+            bytes: vec![1; 10],
+            written: 0,
+        };
+
+        assert!(operation.complete(4).is_none());
+        assert!(operation.complete(5).is_none());
+        let Operation::Write { written, .. } = &operation else {
+            panic!("Unexpected operation.");
+        };
+        assert_eq!(*written, 9);
+        assert!(matches!(operation.complete(1), Some(Ok(Completion::Done))));
+    }
+
+    #[test]
+    fn write_of_no_bytes_should_fail_instead_of_being_resubmitted() {
+        let directory = TestDirectory::new();
+        let mut operation = Operation::Write {
+            file: directory.open_file("write"),
+            offset: CURRENT_POSITION,
+            bytes: vec![1; 10],
+            written: 0,
+        };
+
+        let error = operation.complete(0).unwrap().err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn partial_read_should_be_resubmitted_until_the_end_of_file() {
+        let directory = TestDirectory::new();
+        let mut operation = Operation::Read {
+            file: directory.open_file("read"),
+            offset: 0,
+            bytes: vec![0; 10],
+            read: 0,
+        };
+
+        assert!(operation.complete(4).is_none());
+        assert!(operation.complete(2).is_none());
+        let Some(Ok(Completion::Bytes(bytes))) = operation.complete(0) else {
+            panic!("Unexpected completion.");
+        };
+        assert_eq!(bytes.len(), 6);
+    }
+
+    #[test]
+    fn failed_operation_should_return_the_os_error() {
+        let directory = TestDirectory::new();
+        let mut operation = Operation::Fsync {
+            file: directory.open_file("fsync"),
+        };
+
+        let error = operation.complete(-libc::EIO).unwrap().err().unwrap();
+        assert_eq!(error.raw_os_error(), Some(libc::EIO));
+    }
+
+    #[test]
+    fn submit_error_should_be_retried_backed_off_or_shut_the_ring_down() {
+        let interrupted = io::Error::from(io::ErrorKind::Interrupted);
+        assert_eq!(
+            SubmitFailure::from_error(&interrupted, MAX_SUBMIT_RETRIES),
+            SubmitFailure::Retry
+        );
+
+        let busy = io::Error::from_raw_os_error(libc::EBUSY);
+        assert_eq!(
+            SubmitFailure::from_error(&busy, 0),
+            SubmitFailure::Backoff(SUBMIT_RETRY_BACKOFF)
+        );
+        assert_eq!(
+            SubmitFailure::from_error(&busy, 2),
+            SubmitFailure::Backoff(SUBMIT_RETRY_BACKOFF * 3)
+        );
+        assert_eq!(
+            SubmitFailure::from_error(&busy, MAX_SUBMIT_RETRIES),
+            SubmitFailure::Shutdown
+        );
+
+        let bad_file = io::Error::from_raw_os_error(libc::EBADF);
+        assert_eq!(
+            SubmitFailure::from_error(&bad_file, 0),
+            SubmitFailure::Shutdown
+        );
+    }
+
+    #[tokio::test]
+    async fn all_requests_should_be_failed_when_the_ring_is_shut_down() {
+        let directory = TestDirectory::new();
+        let (request_sender, request_receiver) = flume::unbounded();
+        let (in_flight_request, in_flight_receiver) =
+            create_request(directory.open_file("in_flight"));
+        let (pending_request, pending_receiver) = create_request(directory.open_file("pending"));
+        let (queued_request, queued_receiver) = create_request(directory.open_file("queued"));
+        request_sender.send(queued_request).unwrap();
+
+        let error = io::Error::from_raw_os_error(libc::EBADF);
+        fail_requests(
+            &error,
+            HashMap::from([(0, in_flight_request)]),
+            VecDeque::from([pending_request]),
+            &request_receiver,
+        );
+
+        for receiver in [in_flight_receiver, pending_receiver, queued_receiver] {
+            let request_error = receiver.await.unwrap().err().unwrap();
+            assert_eq!(request_error.kind(), error.kind());
+            assert_eq!(request_error.to_string(), error.to_string());
+        }
+    }
+
+    fn get_uring() -> Option<Arc<Uring>> {
+        match Uring::get_instance() {
+            Ok(uring) => Some(uring),
+            Err(error) => {
+                eprintln!("Skipping the test, io_uring is not available: {error}");
+                None
+            }
+        }
+    }
+
+    fn create_request(file: UringFile) -> (Request, oneshot::Receiver<io::Result<Completion>>) {
+        let (sender, receiver) = oneshot::channel();
+        let request = Request {
+            operation: Operation::Fsync { file },
+            sender,
+        };
+        (request, receiver)
+    }
+
+    struct TestDirectory {
+        path: PathBuf,
+    }
+
+    impl TestDirectory {
+        fn new() -> Self {
+            let path =
+                std::env::temp_dir().join(format!("iggy_io_uring_{}", Uuid::now_v7().to_u128_le()));
+            std::fs::create_dir_all(&path).unwrap();
+            Self { path }
+        }
+
+        fn file_path(&self, name: &str) -> String {
+            self.path.join(name).to_string_lossy().to_string()
+        }
+
+        fn open_file(&self, name: &str) -> UringFile {
+            Arc::new(OwnedFd::from(File::create(self.path.join(name)).unwrap()))
+        }
+    }
+
+    impl Drop for TestDirectory {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+}
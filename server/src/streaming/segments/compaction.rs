@@ -0,0 +1,155 @@
+use crate::streaming::batching::iterator::IntoMessagesIterator;
+use crate::streaming::batching::message_batch::RetainedMessageBatch;
+use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::segments::segment::Segment;
+use crate::streaming::sizeable::Sizeable;
+use bytes::BytesMut;
+use iggy::error::IggyError;
+use std::sync::atomic::Ordering;
+use tracing::{info, trace};
+
+/// The batches of the closed segment with the obsolete messages removed, to replace the segment files with.
+#[derive(Debug)]
+pub struct CompactedSegment {
+    pub rewrites_count: u64,
+    pub batches: Vec<RetainedMessageBatch>,
+    pub removed_messages: u64,
+}
+
+impl Segment {
+    /// Prepares the batches of the closed segment keeping only the messages matching the predicate, along with the last one,
+    /// so that the current offset of the segment is preserved after loading it from disk.
+    /// The segment isn't modified, so it's enough to hold the read lock of the partition.
+    /// Returns None if there are no messages to remove.
+    pub async fn prepare_compaction(
+        &self,
+        mut retain: impl FnMut(&RetainedMessage) -> Result<bool, IggyError>,
+    ) -> Result<Option<CompactedSegment>, IggyError> {
+        // The offloaded segment is no longer stored locally, so it can't be rewritten.
+        if !self.is_closed || self.is_offloaded {
+            return Ok(None);
+        }
+
+        let mut removed_messages = 0;
+        let mut compacted_batches = Vec::new();
+        for batch in self.get_all_batches().await? {
+            let mut retained_messages = Vec::new();
+            for message in batch.into_messages_iter() {
+                if message.offset == self.current_offset || retain(&message)? {
+                    retained_messages.push(message);
+                } else {
+                    removed_messages += 1;
+                }
+            }
+
+            if let Some(batch) = Self::create_batch(&retained_messages) {
+                compacted_batches.push(batch.compress(self.compression_algorithm)?);
+            }
+        }
+
+        if removed_messages == 0 {
+            trace!(
+                "No messages to remove from segment with start offset: {} for partition with ID: {}.",
+                self.start_offset,
+                self.partition_id
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(CompactedSegment {
+            rewrites_count: self.rewrites_count,
+            batches: compacted_batches,
+            removed_messages,
+        }))
+    }
+
+    /// Replaces the segment files with the compacted batches, unless the segment has been rewritten,
+    /// offloaded or replaced with the new open one since they were prepared, in which case they are discarded.
+    /// The offsets of the retained messages don't change. Returns the number of the removed messages.
+    pub async fn replace_with_compacted(
+        &mut self,
+        compacted_segment: CompactedSegment,
+    ) -> Result<u64, IggyError> {
+        if !self.is_closed
+            || self.is_offloaded
+            || self.rewrites_count != compacted_segment.rewrites_count
+        {
+            trace!(
+                "Discarding the outdated compaction of segment with start offset: {} for partition with ID: {}.",
+                self.start_offset,
+                self.partition_id
+            );
+            return Ok(0);
+        }
+
+        let previous_size_bytes = self.size_bytes;
+        self.rewrite(compacted_segment.batches).await?;
+        let removed_bytes = previous_size_bytes.saturating_sub(self.size_bytes) as u64;
+
+        info!(
+            "Compacted segment with start offset: {} for partition with ID: {}, removed {} messages and {} bytes.",
+            self.start_offset, self.partition_id, compacted_segment.removed_messages, removed_bytes
+        );
+        Ok(compacted_segment.removed_messages)
+    }
+
+    /// Replaces the log, index and time index files of the closed segment with the given batches,
+    /// and updates the size of the segment along with its parents.
+    pub(crate) async fn rewrite(
+        &mut self,
+        batches: Vec<RetainedMessageBatch>,
+    ) -> Result<(), IggyError> {
+        let size_bytes = batches
+            .iter()
+            .map(|batch| batch.get_size_bytes())
+            .sum::<u32>();
+        let storage = self.storage.segment.clone();
+        let (indexes, time_indexes) = storage.rewrite_batches(self, batches).await?;
+        if size_bytes < self.size_bytes {
+            let removed_bytes = (self.size_bytes - size_bytes) as u64;
+            self.size_of_parent_stream
+                .fetch_sub(removed_bytes, Ordering::AcqRel);
+            self.size_of_parent_topic
+                .fetch_sub(removed_bytes, Ordering::AcqRel);
+            self.size_of_parent_partition
+                .fetch_sub(removed_bytes, Ordering::AcqRel);
+        } else {
+            let added_bytes = (size_bytes - self.size_bytes) as u64;
+            self.size_of_parent_stream
+                .fetch_add(added_bytes, Ordering::AcqRel);
+            self.size_of_parent_topic
+                .fetch_add(added_bytes, Ordering::AcqRel);
+            self.size_of_parent_partition
+                .fetch_add(added_bytes, Ordering::AcqRel);
+        }
+        self.size_bytes = size_bytes;
+        self.last_index_position = size_bytes;
+        self.rewrites_count += 1;
+        if self.indexes.is_some() {
+            self.indexes = Some(indexes);
+        }
+        if self.time_indexes.is_some() {
+            self.time_indexes = Some(time_indexes);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn create_batch(messages: &[RetainedMessage]) -> Option<RetainedMessageBatch> {
+        let first_message = messages.first()?;
+        let last_message = messages.last()?;
+        let mut bytes = BytesMut::new();
+        let mut max_timestamp = 0;
+        for message in messages {
+            message.extend(&mut bytes);
+            max_timestamp = max_timestamp.max(message.timestamp);
+        }
+
+        Some(RetainedMessageBatch::new(
+            first_message.offset,
+            (last_message.offset - first_message.offset) as u32,
+            max_timestamp,
+            bytes.len() as u32,
+            bytes.freeze(),
+        ))
+    }
+}
@@ -0,0 +1,745 @@
+use crate::streaming::batching::iterator::IntoMessagesIterator;
+use crate::streaming::batching::message_batch::{RetainedMessageBatch, RETAINED_BATCH_OVERHEAD};
+use crate::streaming::models::messages::{FileRegion, RetainedMessage};
+use crate::streaming::partitions::producers::get_producer_sequence;
+use crate::streaming::partitions::transactions::get_marker_transaction_id;
+use crate::streaming::persistence::memory::MemoryPersister;
+use crate::streaming::persistence::persister::Persister;
+use crate::streaming::segments::index::{Index, IndexRange};
+use crate::streaming::segments::segment::Segment;
+use crate::streaming::segments::storage::{parse_batches, INDEX_SIZE, TIME_INDEX_SIZE};
+use crate::streaming::segments::time_index::TimeIndex;
+use crate::streaming::segments::verification::{
+    verify_batch_messages, SegmentIssue, SegmentVerification,
+};
+use crate::streaming::sizeable::Sizeable;
+use crate::streaming::storage::SegmentStorage;
+use crate::streaming::utils::head_tail_buf::HeadTailBuffer;
+use async_trait::async_trait;
+use bytes::{BufMut, Bytes, BytesMut};
+use iggy::bytes_serializable::BytesSerializable;
+use iggy::error::IggyError;
+use iggy::models::messages::MessageState;
+use iggy::utils::checksum;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tracing::{info, trace};
+
+/// The segment storage keeping the log, index and time index files in memory, in the same format as on disk.
+#[derive(Debug)]
+pub struct MemorySegmentStorage {
+    files: Arc<MemoryPersister>,
+}
+
+impl MemorySegmentStorage {
+    pub fn new(files: Arc<MemoryPersister>) -> Self {
+        Self { files }
+    }
+
+    /// Returns the bytes of the log starting at the position, the offloaded segments are kept in memory as well.
+    fn read_log(&self, segment: &Segment, position: u64) -> Result<Bytes, IggyError> {
+        self.files
+            .read(&segment.log_path, |bytes| {
+                Bytes::copy_from_slice(bytes.get(position as usize..).unwrap_or_default())
+            })
+            .ok_or(IggyError::SegmentNotFound)
+    }
+
+    fn load_all_batches(&self, segment: &Segment) -> Result<Vec<RetainedMessageBatch>, IggyError> {
+        parse_batches(self.read_log(segment, 0)?, u64::MAX)
+    }
+
+    fn read_indexes(&self, path: &str) -> Vec<Index> {
+        self.files
+            .read(path, |bytes| {
+                bytes
+                    .chunks_exact(INDEX_SIZE as usize)
+                    .map(|index| Index {
+                        relative_offset: u32::from_le_bytes(index[..4].try_into().unwrap()),
+                        position: u32::from_le_bytes(index[4..].try_into().unwrap()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn read_time_indexes(&self, path: &str) -> Vec<TimeIndex> {
+        self.files
+            .read(path, |bytes| {
+                bytes
+                    .chunks_exact(TIME_INDEX_SIZE as usize)
+                    .map(|index| TimeIndex {
+                        relative_offset: u32::from_le_bytes(index[..4].try_into().unwrap()),
+                        timestamp: u64::from_le_bytes(index[4..].try_into().unwrap()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl SegmentStorage for MemorySegmentStorage {
+    async fn load(&self, segment: &mut Segment) -> Result<(), IggyError> {
+        let size_bytes = self
+            .files
+            .size(&segment.log_path)
+            .ok_or(IggyError::SegmentNotFound)?;
+        segment.size_bytes = size_bytes as _;
+        segment.last_index_position = size_bytes as _;
+        if segment.config.segment.cache_indexes {
+            segment.indexes = Some(self.read_indexes(&segment.index_path));
+        }
+
+        let time_indexes = self.read_time_indexes(&segment.time_index_path);
+        if let Some(last_index) = time_indexes.last() {
+            segment.current_offset = segment.start_offset + last_index.relative_offset as u64;
+        }
+        if segment.config.segment.cache_time_indexes {
+            segment.time_indexes = Some(time_indexes);
+        }
+
+        segment.load_first_message_timestamp().await;
+        if segment.is_offloaded || segment.is_full().await {
+            segment.is_closed = true;
+        }
+
+        let messages_count = segment.get_messages_count();
+        segment
+            .size_of_parent_stream
+            .fetch_add(size_bytes, Ordering::SeqCst);
+        segment
+            .size_of_parent_topic
+            .fetch_add(size_bytes, Ordering::SeqCst);
+        segment
+            .size_of_parent_partition
+            .fetch_add(size_bytes, Ordering::SeqCst);
+        segment
+            .messages_count_of_parent_stream
+            .fetch_add(messages_count, Ordering::SeqCst);
+        segment
+            .messages_count_of_parent_topic
+            .fetch_add(messages_count, Ordering::SeqCst);
+        segment
+            .messages_count_of_parent_partition
+            .fetch_add(messages_count, Ordering::SeqCst);
+        info!(
+            "Loaded segment of size {size_bytes} bytes from memory for start offset {}, current offset: {}, and partition with ID: {} for topic with ID: {} and stream with ID: {}.",
+            segment.start_offset, segment.current_offset, segment.partition_id, segment.topic_id, segment.stream_id
+        );
+        Ok(())
+    }
+
+    async fn save(&self, segment: &Segment) -> Result<(), IggyError> {
+        for path in [
+            &segment.log_path,
+            &segment.index_path,
+            &segment.time_index_path,
+        ] {
+            if !self.files.exists(path) {
+                self.files.overwrite(path, &[]).await?;
+            }
+        }
+        trace!(
+            "Saved segment with start offset: {} for partition with ID: {} for topic with ID: {} and stream with ID: {} in memory.",
+            segment.start_offset, segment.partition_id, segment.topic_id, segment.stream_id
+        );
+        Ok(())
+    }
+
+    async fn delete(&self, segment: &Segment) -> Result<(), IggyError> {
+        let segment_count_of_messages = segment.get_messages_count();
+        for path in [
+            &segment.log_path,
+            &segment.index_path,
+            &segment.time_index_path,
+        ] {
+            self.files.delete(path).await?;
+        }
+        segment
+            .size_of_parent_stream
+            .fetch_sub(segment.size_bytes as u64, Ordering::SeqCst);
+        segment
+            .size_of_parent_topic
+            .fetch_sub(segment.size_bytes as u64, Ordering::SeqCst);
+        segment
+            .size_of_parent_partition
+            .fetch_sub(segment.size_bytes as u64, Ordering::SeqCst);
+        segment
+            .messages_count_of_parent_stream
+            .fetch_sub(segment_count_of_messages, Ordering::SeqCst);
+        segment
+            .messages_count_of_parent_topic
+            .fetch_sub(segment_count_of_messages, Ordering::SeqCst);
+        segment
+            .messages_count_of_parent_partition
+            .fetch_sub(segment_count_of_messages, Ordering::SeqCst);
+        info!(
+            "Deleted segment of size {} with start offset: {} for partition with ID: {} for stream with ID: {} and topic with ID: {} from memory.",
+            segment.size_bytes, segment.start_offset, segment.partition_id, segment.stream_id, segment.topic_id,
+        );
+        Ok(())
+    }
+
+    async fn load_message_batches(
+        &self,
+        segment: &Segment,
+        index_range: &IndexRange,
+    ) -> Result<Vec<RetainedMessageBatch>, IggyError> {
+        let bytes = self.read_log(segment, index_range.start.position as u64)?;
+        let index_last_offset = index_range.end.relative_offset as u64 + segment.start_offset;
+        let batches = parse_batches(bytes, index_last_offset)?;
+        trace!("Loaded {} message batches from memory.", batches.len());
+        Ok(batches)
+    }
+
+    async fn load_batches_region(
+        &self,
+        _segment: &Segment,
+        _index_range: &IndexRange,
+    ) -> Result<Option<FileRegion>, IggyError> {
+        // There are no files to send the batches from, they're read from memory instead.
+        Ok(None)
+    }
+
+    async fn load_newest_batches_by_size(
+        &self,
+        segment: &Segment,
+        size_bytes: u64,
+    ) -> Result<Vec<RetainedMessageBatch>, IggyError> {
+        let batches = self.load_all_batches(segment)?;
+        let mut total_size_bytes = 0;
+        let newest_batches = batches
+            .into_iter()
+            .rev()
+            .take_while(|batch| {
+                let is_within_size = total_size_bytes < size_bytes;
+                total_size_bytes += batch.get_size_bytes() as u64;
+                is_within_size
+            })
+            .collect::<Vec<_>>();
+        Ok(newest_batches.into_iter().rev().collect())
+    }
+
+    async fn save_batches(
+        &self,
+        segment: &Segment,
+        batch: RetainedMessageBatch,
+    ) -> Result<u32, IggyError> {
+        let batch_size = batch.get_size_bytes();
+        let mut bytes = BytesMut::with_capacity(batch_size as usize);
+        batch.extend(&mut bytes);
+        self.files.append(&segment.log_path, &bytes).await?;
+        Ok(batch_size)
+    }
+
+    async fn rewrite_batches(
+        &self,
+        segment: &Segment,
+        batches: Vec<RetainedMessageBatch>,
+    ) -> Result<(Vec<Index>, Vec<TimeIndex>), IggyError> {
+        let mut log_bytes = BytesMut::new();
+        let mut index_bytes = BytesMut::with_capacity(batches.len() * INDEX_SIZE as usize);
+        let mut time_index_bytes =
+            BytesMut::with_capacity(batches.len() * TIME_INDEX_SIZE as usize);
+        let mut indexes = Vec::with_capacity(batches.len());
+        let mut time_indexes = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let relative_offset = (batch.get_last_offset() - segment.start_offset) as u32;
+            let index = Index {
+                relative_offset,
+                position: log_bytes.len() as u32,
+            };
+            let time_index = TimeIndex {
+                relative_offset,
+                timestamp: batch.max_timestamp,
+            };
+            index_bytes.put_u32_le(index.relative_offset);
+            index_bytes.put_u32_le(index.position);
+            time_index_bytes.put_u32_le(time_index.relative_offset);
+            time_index_bytes.put_u64_le(time_index.timestamp);
+            batch.extend(&mut log_bytes);
+            indexes.push(index);
+            time_indexes.push(time_index);
+        }
+
+        self.files
+            .overwrite(&segment.index_path, &index_bytes)
+            .await?;
+        self.files
+            .overwrite(&segment.time_index_path, &time_index_bytes)
+            .await?;
+        self.files.overwrite(&segment.log_path, &log_bytes).await?;
+        Ok((indexes, time_indexes))
+    }
+
+    async fn offload(&self, segment: &Segment) -> Result<(), IggyError> {
+        // The log remains in memory, there's no local disk space to reclaim by offloading it.
+        info!(
+            "Segment with start offset: {} for partition with ID: {} is kept in memory instead of being offloaded.",
+            segment.start_offset, segment.partition_id
+        );
+        Ok(())
+    }
+
+    async fn load_message_ids(&self, segment: &Segment) -> Result<Vec<u128>, IggyError> {
+        let message_ids = self
+            .load_all_batches(segment)?
+            .into_iter()
+            .flat_map(|batch| {
+                batch
+                    .into_messages_iter()
+                    .map(|msg: RetainedMessage| msg.id)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Ok(message_ids)
+    }
+
+    async fn load_committed_transactions(
+        &self,
+        segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError> {
+        let mut transactions = HashMap::new();
+        for batch in self.load_all_batches(segment)? {
+            for message in batch.into_messages_iter() {
+                if message.message_state == MessageState::TransactionCommitted {
+                    transactions.insert(get_marker_transaction_id(&message)?, message.offset);
+                }
+            }
+        }
+        Ok(transactions)
+    }
+
+    async fn load_producer_sequences(
+        &self,
+        segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError> {
+        let mut sequences = HashMap::new();
+        for batch in self.load_all_batches(segment)? {
+            for message in batch.into_messages_iter() {
+                let Some(headers) = message.headers else {
+                    continue;
+                };
+
+                let headers = HashMap::from_bytes(headers)?;
+                if let Some((producer_id, sequence)) = get_producer_sequence(&headers)? {
+                    let last_sequence: &mut u64 = sequences.entry(producer_id).or_default();
+                    *last_sequence = (*last_sequence).max(sequence);
+                }
+            }
+        }
+        Ok(sequences)
+    }
+
+    async fn load_checksums(&self, segment: &Segment) -> Result<(), IggyError> {
+        for batch in self.load_all_batches(segment)? {
+            for message in batch.into_messages_iter() {
+                let calculated_checksum = checksum::calculate(&message.payload);
+                if calculated_checksum != message.checksum {
+                    return Err(IggyError::InvalidMessageChecksum(
+                        calculated_checksum,
+                        message.checksum,
+                        message.offset,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn verify(&self, segment: &Segment) -> Result<SegmentVerification, IggyError> {
+        let bytes = self.read_log(segment, 0)?;
+        let log_size_bytes = bytes.len() as u64;
+        let mut verification = SegmentVerification {
+            start_offset: segment.start_offset,
+            log_path: segment.log_path.clone(),
+            log_size_bytes,
+            ..Default::default()
+        };
+        let mut position = 0;
+        while position < log_size_bytes {
+            let start = position as usize;
+            if log_size_bytes - position < RETAINED_BATCH_OVERHEAD as u64 {
+                verification
+                    .issues
+                    .push(SegmentIssue::TruncatedBatch { position });
+                break;
+            }
+
+            let base_offset = u64::from_le_bytes(bytes[start..start + 8].try_into()?);
+            let batch_length = u32::from_le_bytes(bytes[start + 8..start + 12].try_into()?);
+            let last_offset_delta = u32::from_le_bytes(bytes[start + 12..start + 16].try_into()?);
+            let max_timestamp = u64::from_le_bytes(bytes[start + 16..start + 24].try_into()?);
+            let batch_size = RETAINED_BATCH_OVERHEAD as u64 + batch_length as u64;
+            if position + batch_size > log_size_bytes {
+                verification
+                    .issues
+                    .push(SegmentIssue::TruncatedBatch { position });
+                break;
+            }
+
+            let payload_start = start + RETAINED_BATCH_OVERHEAD as usize;
+            let batch = RetainedMessageBatch::new(
+                base_offset,
+                last_offset_delta,
+                max_timestamp,
+                batch_length,
+                bytes.slice(payload_start..payload_start + batch_length as usize),
+            );
+            let result = if base_offset < segment.start_offset {
+                Err(IggyError::InvalidOffset(base_offset))
+            } else {
+                batch
+                    .clone()
+                    .decompress()
+                    .and_then(|batch| verify_batch_messages(&batch))
+            };
+            match result {
+                Ok((messages_count, issues)) => {
+                    verification.add_batch(position, &batch);
+                    verification.messages_count += messages_count;
+                    verification.issues.extend(issues);
+                }
+                Err(error) => {
+                    verification.issues.push(SegmentIssue::InvalidBatch {
+                        position,
+                        base_offset,
+                        error: error.to_string(),
+                    });
+                    break;
+                }
+            }
+            position += batch_size;
+        }
+
+        let indexes = self.read_indexes(&segment.index_path);
+        let index_size = self.files.size(&segment.index_path).unwrap_or_default();
+        verification.verify_indexes(&indexes, index_size);
+        let time_indexes = self.read_time_indexes(&segment.time_index_path);
+        let time_index_size = self
+            .files
+            .size(&segment.time_index_path)
+            .unwrap_or_default();
+        verification.verify_time_indexes(&time_indexes, time_index_size);
+        Ok(verification)
+    }
+
+    async fn repair(
+        &self,
+        segment: &Segment,
+        verification: &SegmentVerification,
+    ) -> Result<(), IggyError> {
+        if verification.has_corrupt_tail() {
+            self.files
+                .truncate(&segment.log_path, verification.valid_size_bytes)?;
+        }
+
+        if !verification.has_corrupt_tail() && !verification.has_index_mismatch() {
+            return Ok(());
+        }
+
+        let mut index_bytes =
+            BytesMut::with_capacity(verification.indexes.len() * INDEX_SIZE as usize);
+        for index in &verification.indexes {
+            index_bytes.put_u32_le(index.relative_offset);
+            index_bytes.put_u32_le(index.position);
+        }
+        let mut time_index_bytes =
+            BytesMut::with_capacity(verification.time_indexes.len() * TIME_INDEX_SIZE as usize);
+        for time_index in &verification.time_indexes {
+            time_index_bytes.put_u32_le(time_index.relative_offset);
+            time_index_bytes.put_u64_le(time_index.timestamp);
+        }
+        self.files
+            .overwrite(&segment.index_path, &index_bytes)
+            .await?;
+        self.files
+            .overwrite(&segment.time_index_path, &time_index_bytes)
+            .await?;
+        Ok(())
+    }
+
+    async fn load_all_indexes(&self, segment: &Segment) -> Result<Vec<Index>, IggyError> {
+        Ok(self.read_indexes(&segment.index_path))
+    }
+
+    async fn load_index_range(
+        &self,
+        segment: &Segment,
+        index_start_offset: u64,
+        index_end_offset: u64,
+    ) -> Result<Option<IndexRange>, IggyError> {
+        if index_start_offset > index_end_offset {
+            return Ok(None);
+        }
+
+        let indexes = self.read_indexes(&segment.index_path);
+        if indexes.is_empty() {
+            return Ok(None);
+        }
+
+        let relative_start_offset = (index_start_offset - segment.start_offset) as u32;
+        let relative_end_offset = (index_end_offset - segment.start_offset) as u32;
+        let mut idx_pred = HeadTailBuffer::new();
+        let mut index_range = IndexRange::default();
+        for idx in indexes {
+            idx_pred.push(idx);
+            if idx.relative_offset >= relative_start_offset {
+                index_range.start = idx_pred.tail().unwrap_or_default();
+            }
+            if idx.relative_offset >= relative_end_offset {
+                index_range.end = idx;
+                break;
+            }
+        }
+        Ok(Some(index_range))
+    }
+
+    async fn save_index(&self, index_path: &str, index: Index) -> Result<(), IggyError> {
+        let mut bytes = BytesMut::with_capacity(INDEX_SIZE as usize);
+        bytes.put_u32_le(index.relative_offset);
+        bytes.put_u32_le(index.position);
+        self.files.append(index_path, &bytes).await
+    }
+
+    async fn try_load_time_index_for_timestamp(
+        &self,
+        segment: &Segment,
+        timestamp: u64,
+    ) -> Result<Option<TimeIndex>, IggyError> {
+        let time_indexes = self.read_time_indexes(&segment.time_index_path);
+        if time_indexes.is_empty() {
+            return Ok(Some(TimeIndex::default()));
+        }
+
+        let mut idx_pred = HeadTailBuffer::new();
+        for idx in time_indexes {
+            idx_pred.push(idx);
+            if idx.timestamp >= timestamp {
+                return Ok(idx_pred.tail());
+            }
+        }
+        Ok(None)
+    }
+
+    async fn load_all_time_indexes(&self, segment: &Segment) -> Result<Vec<TimeIndex>, IggyError> {
+        Ok(self.read_time_indexes(&segment.time_index_path))
+    }
+
+    async fn load_last_time_index(
+        &self,
+        segment: &Segment,
+    ) -> Result<Option<TimeIndex>, IggyError> {
+        Ok(self.read_time_indexes(&segment.time_index_path).pop())
+    }
+
+    async fn save_time_index(&self, index_path: &str, index: TimeIndex) -> Result<(), IggyError> {
+        let mut bytes = BytesMut::with_capacity(TIME_INDEX_SIZE as usize);
+        bytes.put_u32_le(index.relative_offset);
+        bytes.put_u64_le(index.timestamp);
+        self.files.append(index_path, &bytes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::SystemConfig;
+    use crate::streaming::storage::SystemStorage;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
+    use iggy::messages::send_messages::Message;
+    use iggy::utils::expiry::IggyExpiry;
+    use std::sync::atomic::AtomicU64;
+
+    #[tokio::test]
+    async fn rewritten_batches_should_be_loaded_along_with_their_indexes() {
+        let (files, segment) = create_segment().await;
+        let storage = MemorySegmentStorage::new(files);
+        let batches = vec![create_batch(0, 3), create_batch(3, 4), create_batch(7, 3)];
+        let (indexes, time_indexes) = storage
+            .rewrite_batches(&segment, batches.clone())
+            .await
+            .unwrap();
+
+        let loaded_indexes = storage.load_all_indexes(&segment).await.unwrap();
+        assert_eq!(
+            loaded_indexes
+                .iter()
+                .map(|index| (index.relative_offset, index.position))
+                .collect::<Vec<_>>(),
+            indexes
+                .iter()
+                .map(|index| (index.relative_offset, index.position))
+                .collect::<Vec<_>>()
+        );
+        let loaded_time_indexes = storage.load_all_time_indexes(&segment).await.unwrap();
+        assert_eq!(
+            loaded_time_indexes
+                .iter()
+                .map(|index| (index.relative_offset, index.timestamp))
+                .collect::<Vec<_>>(),
+            time_indexes
+                .iter()
+                .map(|index| (index.relative_offset, index.timestamp))
+                .collect::<Vec<_>>()
+        );
+
+        let index_range = storage
+            .load_index_range(&segment, 4, 8)
+            .await
+            .unwrap()
+            .unwrap();
+        let loaded_batches = storage
+            .load_message_batches(&segment, &index_range)
+            .await
+            .unwrap();
+        assert_eq!(
+            loaded_batches
+                .iter()
+                .map(|batch| batch.base_offset)
+                .collect::<Vec<_>>(),
+            vec![3, 7]
+        );
+        assert_eq!(loaded_batches[0].bytes, batches[1].bytes);
+
+        let time_index = storage
+            .try_load_time_index_for_timestamp(&segment, 1005)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(time_index.relative_offset, 2);
+        let last_time_index = storage.load_last_time_index(&segment).await.unwrap();
+        assert_eq!(last_time_index.unwrap().timestamp, 1009);
+
+        let newest_batches = storage
+            .load_newest_batches_by_size(&segment, 1)
+            .await
+            .unwrap();
+        assert_eq!(newest_batches.len(), 1);
+        assert_eq!(newest_batches[0].base_offset, 7);
+        assert_eq!(
+            storage.load_message_ids(&segment).await.unwrap(),
+            (1..=10).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn segment_with_corrupt_tail_should_be_verified_and_repaired() {
+        let (files, segment) = create_segment().await;
+        let storage = MemorySegmentStorage::new(files.clone());
+        let mut size_bytes = 0;
+        for batch in [create_batch(0, 3), create_batch(3, 3)] {
+            let relative_offset = batch.get_last_offset() as u32;
+            let max_timestamp = batch.max_timestamp;
+            let position = size_bytes;
+            size_bytes += storage.save_batches(&segment, batch).await.unwrap();
+            storage
+                .save_index(
+                    &segment.index_path,
+                    Index {
+                        relative_offset,
+                        position,
+                    },
+                )
+                .await
+                .unwrap();
+            storage
+                .save_time_index(
+                    &segment.time_index_path,
+                    TimeIndex {
+                        relative_offset,
+                        timestamp: max_timestamp,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        files
+            .append(&segment.log_path, &[1, 2, 3, 4, 5])
+            .await
+            .unwrap();
+
+        let verification = storage.verify(&segment).await.unwrap();
+        assert!(verification.has_corrupt_tail());
+        assert_eq!(verification.messages_count, 6);
+        assert_eq!(verification.valid_size_bytes, size_bytes as u64);
+
+        storage.repair(&segment, &verification).await.unwrap();
+        assert_eq!(files.size(&segment.log_path), Some(size_bytes as u64));
+        let verification = storage.verify(&segment).await.unwrap();
+        assert!(verification.issues.is_empty());
+        assert_eq!(storage.load_all_indexes(&segment).await.unwrap().len(), 2);
+        storage.load_checksums(&segment).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn deleted_segment_should_not_be_loaded() {
+        let (files, mut segment) = create_segment().await;
+        let storage = MemorySegmentStorage::new(files.clone());
+        storage
+            .save_batches(&segment, create_batch(0, 3))
+            .await
+            .unwrap();
+        storage.delete(&segment).await.unwrap();
+
+        assert!(!files.exists(&segment.log_path));
+        assert!(!files.exists(&segment.index_path));
+        assert!(!files.exists(&segment.time_index_path));
+        assert!(matches!(
+            storage.load(&mut segment).await,
+            Err(IggyError::SegmentNotFound)
+        ));
+    }
+
+    async fn create_segment() -> (Arc<MemoryPersister>, Segment) {
+        let config = Arc::new(SystemConfig::default());
+        let files = Arc::new(MemoryPersister::default());
+        let storage = Arc::new(SystemStorage::in_memory(config.clone()));
+        let segment = Segment::create(
+            1,
+            1,
+            1,
+            0,
+            config,
+            storage,
+            IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+        );
+        MemorySegmentStorage::new(files.clone())
+            .save(&segment)
+            .await
+            .unwrap();
+        (files, segment)
+    }
+
+    fn create_batch(base_offset: u64, messages_count: u32) -> RetainedMessageBatch {
+        let mut bytes = BytesMut::new();
+        for offset in base_offset..base_offset + messages_count as u64 {
+            RetainedMessage::new(
+                offset,
+                1000 + offset,
+                Message::new(
+                    Some(offset as u128 + 1),
+                    Bytes::from(format!("message {offset}")),
+                    None,
+                ),
+            )
+            .extend(&mut bytes);
+        }
+        RetainedMessageBatch::new(
+            base_offset,
+            messages_count - 1,
+            1000 + base_offset + messages_count as u64 - 1,
+            bytes.len() as u32,
+            bytes.freeze(),
+        )
+    }
+}
@@ -1,7 +1,8 @@
 use crate::streaming::batching::batch_accumulator::BatchAccumulator;
 use crate::streaming::batching::batch_filter::BatchItemizer;
+use crate::streaming::batching::iterator::IntoMessagesIterator;
 use crate::streaming::batching::message_batch::{RetainedMessageBatch, RETAINED_BATCH_OVERHEAD};
-use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::models::messages::{FileRegion, RetainedMessage};
 use crate::streaming::segments::index::{Index, IndexRange};
 use crate::streaming::segments::segment::Segment;
 use crate::streaming::segments::time_index::TimeIndex;
@@ -9,7 +10,7 @@ use crate::streaming::sizeable::Sizeable;
 use iggy::error::IggyError;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tracing::{info, trace, warn};
+use tracing::{trace, warn};
 
 const EMPTY_MESSAGES: Vec<RetainedMessage> = vec![];
 
@@ -24,44 +25,125 @@ impl Segment {
 
     pub async fn get_messages(
         &self,
-        mut offset: u64,
+        offset: u64,
         count: u32,
     ) -> Result<Vec<Arc<RetainedMessage>>, IggyError> {
         if count == 0 {
             return Ok(EMPTY_MESSAGES.into_iter().map(Arc::new).collect());
         }
 
+        let offset = offset.max(self.start_offset);
+        let end_offset = offset + (count - 1) as u64;
+        self.get_filtered_messages(offset, end_offset, &|_| true)
+            .await
+    }
+
+    /// Returns the messages within the offset range which match the filter.
+    /// The messages loaded from disk are filtered while their batches are itemized,
+    /// so the ones not matching the filter are never collected.
+    pub async fn get_filtered_messages<F>(
+        &self,
+        mut offset: u64,
+        end_offset: u64,
+        filter: &F,
+    ) -> Result<Vec<Arc<RetainedMessage>>, IggyError>
+    where
+        F: Fn(&RetainedMessage) -> bool,
+    {
         if offset < self.start_offset {
             offset = self.start_offset;
         }
 
-        let end_offset = offset + (count - 1) as u64;
         // In case that the partition messages buffer is disabled, we need to check the unsaved messages buffer
-        if self.unsaved_messages.is_none() {
-            return self.load_messages_from_disk(offset, end_offset).await;
+        let Some(first_offset) = self.get_first_unsaved_offset() else {
+            return self
+                .load_messages_from_disk(offset, end_offset, filter)
+                .await;
+        };
+
+        if end_offset < first_offset {
+            return self
+                .load_messages_from_disk(offset, end_offset, filter)
+                .await;
         }
 
-        let batch_accumulator = self.unsaved_messages.as_ref().unwrap();
-        if batch_accumulator.is_empty() {
-            return self.load_messages_from_disk(offset, end_offset).await;
+        // Can this be somehow improved? maybe with chain iterators
+        let mut messages = if offset < first_offset {
+            self.load_messages_from_disk(offset, end_offset, filter)
+                .await?
+        } else {
+            Vec::new()
+        };
+        let mut compressed_messages =
+            self.load_messages_from_unsaved_compressed_batches(offset, end_offset, filter)?;
+        messages.append(&mut compressed_messages);
+        let mut buffered_messages =
+            self.load_messages_from_unsaved_buffer(offset, end_offset, filter);
+        messages.append(&mut buffered_messages);
+
+        Ok(messages)
+    }
+
+    /// Returns the offset of the first message which hasn't been persisted yet, if any.
+    fn get_first_unsaved_offset(&self) -> Option<u64> {
+        if let Some(batch) = self.unsaved_compressed_batches.first() {
+            return Some(batch.base_offset);
         }
 
-        let first_offset = batch_accumulator.batch_base_offset();
-        if end_offset < first_offset {
-            return self.load_messages_from_disk(offset, end_offset).await;
+        self.unsaved_messages
+            .as_ref()
+            .filter(|batch_accumulator| !batch_accumulator.is_empty())
+            .map(|batch_accumulator| batch_accumulator.batch_base_offset())
+    }
+
+    pub(crate) fn has_unsaved_messages(&self) -> bool {
+        self.unsaved_messages.is_some() || !self.unsaved_compressed_batches.is_empty()
+    }
+
+    /// Returns the region of the log file containing the batches within the offset range,
+    /// or `None` if some of the messages haven't been persisted yet or the segment is offloaded.
+    pub async fn get_batches_region(
+        &self,
+        start_offset: u64,
+        end_offset: u64,
+    ) -> Result<Option<FileRegion>, IggyError> {
+        if self.is_offloaded || start_offset > end_offset || end_offset > self.current_offset {
+            return Ok(None);
         }
 
-        let last_offset = batch_accumulator.batch_max_offset();
-        if offset >= first_offset && end_offset <= last_offset {
-            return Ok(self.load_messages_from_unsaved_buffer(offset, end_offset));
+        if self
+            .get_first_unsaved_offset()
+            .is_some_and(|first_offset| end_offset >= first_offset)
+        {
+            return Ok(None);
         }
 
-        // Can this be somehow improved? maybe with chain iterators
-        let mut messages = self.load_messages_from_disk(offset, end_offset).await?;
-        let mut buffered_messages = self.load_messages_from_unsaved_buffer(offset, last_offset);
-        messages.append(&mut buffered_messages);
+        let start_offset = start_offset.max(self.start_offset);
+        let relative_start_offset = (start_offset - self.start_offset) as u32;
+        let relative_end_offset = (end_offset - self.start_offset) as u32;
+        let index_range = match &self.indexes {
+            Some(indexes) => self.load_highest_lower_bound_index(
+                indexes,
+                relative_start_offset,
+                relative_end_offset,
+            ),
+            None => {
+                let indexes = self.storage.segment.load_all_indexes(self).await?;
+                self.load_highest_lower_bound_index(
+                    &indexes,
+                    relative_start_offset,
+                    relative_end_offset,
+                )
+            }
+        };
+        let Ok(index_range) = index_range else {
+            return Ok(None);
+        };
 
-        Ok(messages)
+        self.storage
+            .segment
+            .load_batches_region(self, &index_range)
+            .await
     }
 
     pub async fn get_all_messages(&self) -> Result<Vec<Arc<RetainedMessage>>, IggyError> {
@@ -89,20 +171,63 @@ impl Segment {
         Ok(messages)
     }
 
-    fn load_messages_from_unsaved_buffer(
+    fn load_messages_from_unsaved_buffer<F>(
         &self,
         start_offset: u64,
         end_offset: u64,
-    ) -> Vec<Arc<RetainedMessage>> {
-        let batch_accumulator = self.unsaved_messages.as_ref().unwrap();
-        batch_accumulator.get_messages_by_offset(start_offset, end_offset)
+        filter: &F,
+    ) -> Vec<Arc<RetainedMessage>>
+    where
+        F: Fn(&RetainedMessage) -> bool,
+    {
+        let Some(batch_accumulator) = self.unsaved_messages.as_ref() else {
+            return Vec::new();
+        };
+        let mut messages = batch_accumulator.get_messages_by_offset(start_offset, end_offset);
+        messages.retain(|message| filter(message));
+        messages
     }
 
-    async fn load_messages_from_disk(
+    /// The compressed batches are decompressed when read, just like the ones loaded from disk.
+    fn load_messages_from_unsaved_compressed_batches<F>(
         &self,
         start_offset: u64,
         end_offset: u64,
-    ) -> Result<Vec<Arc<RetainedMessage>>, IggyError> {
+        filter: &F,
+    ) -> Result<Vec<Arc<RetainedMessage>>, IggyError>
+    where
+        F: Fn(&RetainedMessage) -> bool,
+    {
+        let mut messages = Vec::new();
+        for batch in self.unsaved_compressed_batches.iter() {
+            if batch.get_last_offset() < start_offset || batch.base_offset > end_offset {
+                continue;
+            }
+
+            let batch = batch.clone().decompress()?;
+            messages.extend(
+                batch
+                    .into_messages_iter()
+                    .filter(|message| {
+                        message.offset >= start_offset
+                            && message.offset <= end_offset
+                            && filter(message)
+                    })
+                    .map(Arc::new),
+            );
+        }
+        Ok(messages)
+    }
+
+    async fn load_messages_from_disk<F>(
+        &self,
+        start_offset: u64,
+        end_offset: u64,
+        filter: &F,
+    ) -> Result<Vec<Arc<RetainedMessage>>, IggyError>
+    where
+        F: Fn(&RetainedMessage) -> bool,
+    {
         trace!(
             "Loading messages from disk, segment start offset: {}, end offset: {}, current offset: {}...",
             start_offset,
@@ -138,7 +263,7 @@ impl Segment {
             };
 
             return self
-                .load_messages_from_segment_file(&index_range, start_offset, end_offset)
+                .load_messages_from_segment_file(&index_range, start_offset, end_offset, filter)
                 .await;
         }
 
@@ -149,19 +274,23 @@ impl Segment {
             .await?
         {
             Some(index_range) => {
-                self.load_messages_from_segment_file(&index_range, start_offset, end_offset)
+                self.load_messages_from_segment_file(&index_range, start_offset, end_offset, filter)
                     .await
             }
             None => Ok(EMPTY_MESSAGES.into_iter().map(Arc::new).collect()),
         }
     }
 
-    async fn load_messages_from_segment_file(
+    async fn load_messages_from_segment_file<F>(
         &self,
         index_range: &IndexRange,
         start_offset: u64,
         end_offset: u64,
-    ) -> Result<Vec<Arc<RetainedMessage>>, IggyError> {
+        filter: &F,
+    ) -> Result<Vec<Arc<RetainedMessage>>, IggyError>
+    where
+        F: Fn(&RetainedMessage) -> bool,
+    {
         let messages_count = (start_offset + end_offset) as usize;
         let messages = self
             .storage
@@ -170,7 +299,7 @@ impl Segment {
             .await?
             .iter()
             .to_messages_with_filter(messages_count, &|msg| {
-                msg.offset >= start_offset && msg.offset <= end_offset
+                msg.offset >= start_offset && msg.offset <= end_offset && filter(msg)
             });
 
         trace!(
@@ -195,6 +324,11 @@ impl Segment {
                 self.partition_id,
             ));
         }
+        if self.size_bytes == 0 {
+            self.first_message_timestamp = batch.first().map(|message| message.timestamp);
+        } else {
+            self.load_first_message_timestamp().await;
+        }
         let messages_cap = self.config.partition.messages_required_to_save as usize;
         let batch_base_offset = batch.first().unwrap().offset;
         let batch_accumulator = self
@@ -221,6 +355,72 @@ impl Segment {
         Ok(())
     }
 
+    /// Buffers the batch compressed by the producer, until it's persisted along with the unsaved messages.
+    /// The batch must not follow any unsaved messages, so that the order of the batches is preserved on disk.
+    pub async fn append_compressed_batch(
+        &mut self,
+        batch: RetainedMessageBatch,
+    ) -> Result<(), IggyError> {
+        if self.is_closed {
+            return Err(IggyError::SegmentClosed(
+                self.start_offset,
+                self.partition_id,
+            ));
+        }
+
+        if self.size_bytes == 0 {
+            self.first_message_timestamp = Some(batch.max_timestamp);
+        } else {
+            self.load_first_message_timestamp().await;
+        }
+        let batch_size = batch.get_size_bytes();
+        let messages_count = batch.last_offset_delta as u64 + 1;
+        self.current_offset = batch.get_last_offset();
+        self.size_bytes += batch_size;
+        self.size_of_parent_stream
+            .fetch_add(batch_size as u64, Ordering::AcqRel);
+        self.size_of_parent_topic
+            .fetch_add(batch_size as u64, Ordering::AcqRel);
+        self.size_of_parent_partition
+            .fetch_add(batch_size as u64, Ordering::AcqRel);
+        self.messages_count_of_parent_stream
+            .fetch_add(messages_count, Ordering::SeqCst);
+        self.messages_count_of_parent_topic
+            .fetch_add(messages_count, Ordering::SeqCst);
+        self.messages_count_of_parent_partition
+            .fetch_add(messages_count, Ordering::SeqCst);
+        self.unsaved_compressed_batches.push(batch);
+        Ok(())
+    }
+
+    /// Persists the buffered batches compressed by the producers, returns the number of their messages.
+    async fn persist_compressed_batches(&mut self) -> Result<usize, IggyError> {
+        let storage = self.storage.segment.clone();
+        let mut persisted_messages = 0;
+        for batch in std::mem::take(&mut self.unsaved_compressed_batches) {
+            let messages_count = batch.last_offset_delta as usize + 1;
+            let (index, time_index) = self.store_offset_and_timestamp_index_for_batch(
+                batch.get_last_offset(),
+                batch.max_timestamp,
+            );
+            let batch_size = storage.save_batches(self, batch).await?;
+            storage.save_index(&self.index_path, index).await?;
+            storage
+                .save_time_index(&self.time_index_path, time_index)
+                .await?;
+            self.last_index_position += batch_size;
+            persisted_messages += messages_count;
+            trace!(
+                "Saved compressed batch of {} messages on disk in segment with start offset: {} for partition with ID: {}, total bytes written: {}.",
+                messages_count,
+                self.start_offset,
+                self.partition_id,
+                batch_size
+            );
+        }
+        Ok(persisted_messages)
+    }
+
     fn store_offset_and_timestamp_index_for_batch(
         &mut self,
         batch_last_offset: u64,
@@ -254,14 +454,17 @@ impl Segment {
 
     pub async fn persist_messages(&mut self) -> Result<usize, IggyError> {
         let storage = self.storage.segment.clone();
-        if self.unsaved_messages.is_none() {
-            return Ok(0);
-        }
-
-        let mut batch_accumulator = self.unsaved_messages.take().unwrap();
-        if batch_accumulator.is_empty() {
-            return Ok(0);
-        }
+        let compressed_messages_number = self.persist_compressed_batches().await?;
+        let batch_accumulator = self
+            .unsaved_messages
+            .take()
+            .filter(|batch_accumulator| !batch_accumulator.is_empty());
+        let Some(mut batch_accumulator) = batch_accumulator else {
+            if compressed_messages_number > 0 && self.is_full().await {
+                self.close();
+            }
+            return Ok(compressed_messages_number);
+        };
         let batch_max_offset = batch_accumulator.batch_max_offset();
         let batch_max_timestamp = batch_accumulator.batch_max_timestamp();
         let (index, time_index) =
@@ -276,6 +479,8 @@ impl Segment {
         );
 
         let (has_remainder, batch) = batch_accumulator.materialize_batch_and_maybe_update_state();
+        let plain_batch_size = batch.get_size_bytes();
+        let batch = batch.compress(self.compression_algorithm)?;
         let batch_size = batch.get_size_bytes();
         if has_remainder {
             self.unsaved_messages = Some(batch_accumulator);
@@ -293,6 +498,18 @@ impl Segment {
             .fetch_add(RETAINED_BATCH_OVERHEAD as u64, Ordering::AcqRel);
         self.size_of_parent_partition
             .fetch_add(RETAINED_BATCH_OVERHEAD as u64, Ordering::AcqRel);
+        if batch_size < plain_batch_size {
+            // The size was accounted for the plain messages when appending them, so the space
+            // saved by the compression has to be subtracted to reflect the actual size on disk.
+            let compression_savings = (plain_batch_size - batch_size).min(self.size_bytes);
+            self.size_bytes -= compression_savings;
+            self.size_of_parent_stream
+                .fetch_sub(compression_savings as u64, Ordering::AcqRel);
+            self.size_of_parent_topic
+                .fetch_sub(compression_savings as u64, Ordering::AcqRel);
+            self.size_of_parent_partition
+                .fetch_sub(compression_savings as u64, Ordering::AcqRel);
+        }
 
         trace!(
             "Saved {} messages on disk in segment with start offset: {} for partition with ID: {}, total bytes written: {}.",
@@ -302,15 +519,10 @@ impl Segment {
             saved_bytes
         );
 
-        if self.is_full().await {
-            self.end_offset = self.current_offset;
-            self.is_closed = true;
-            self.unsaved_messages = None;
-            info!(
-                "Closed segment with start offset: {} for partition with ID: {}.",
-                self.start_offset, self.partition_id
-            );
+        // The segment is closed only once the remaining messages are persisted, otherwise they would be lost.
+        if self.unsaved_messages.is_none() && self.is_full().await {
+            self.close();
         }
-        Ok(unsaved_messages_number)
+        Ok(compressed_messages_number + unsaved_messages_number)
     }
 }
@@ -1,6 +1,13 @@
+pub mod compaction;
 pub mod index;
+pub mod memory_storage;
 pub mod messages;
 pub mod persistence;
+pub mod reencryption;
 pub mod segment;
 pub mod storage;
 pub mod time_index;
+pub mod truncation;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub mod uring_storage;
+pub mod verification;
@@ -0,0 +1,57 @@
+use crate::streaming::batching::iterator::IntoMessagesIterator;
+use crate::streaming::segments::segment::Segment;
+use crate::streaming::utils::keyring::Keyring;
+use bytes::Bytes;
+use iggy::error::IggyError;
+use iggy::utils::checksum;
+use iggy::utils::crypto::Encryptor;
+use tracing::{info, trace};
+
+impl Segment {
+    /// Re-encrypts the messages of the closed segment encrypted with a different key than the given one,
+    /// so that the keys retired after the rotation are no longer needed to decrypt them.
+    /// The log, index and time index files are replaced altogether, the offsets of the messages don't change.
+    /// Returns the number of the re-encrypted messages.
+    pub async fn reencrypt(&mut self, keyring: &Keyring, key_id: u32) -> Result<u64, IggyError> {
+        // The offloaded segment is no longer stored locally, so it can't be rewritten.
+        if !self.is_closed || self.is_offloaded || self.encryption_key_id == Some(key_id) {
+            return Ok(0);
+        }
+
+        let mut reencrypted_messages = 0;
+        let mut reencrypted_batches = Vec::new();
+        for batch in self.get_all_batches().await? {
+            let mut messages = Vec::new();
+            for mut message in batch.into_messages_iter() {
+                if keyring.get_data_key_id(&message.payload) != Some(key_id) {
+                    let payload = keyring.decrypt(&message.payload)?;
+                    message.payload = Bytes::from(keyring.encrypt_with(key_id, &payload)?);
+                    message.checksum = checksum::calculate(&message.payload);
+                    reencrypted_messages += 1;
+                }
+                messages.push(message);
+            }
+
+            if let Some(batch) = Self::create_batch(&messages) {
+                reencrypted_batches.push(batch.compress(self.compression_algorithm)?);
+            }
+        }
+
+        if reencrypted_messages > 0 {
+            self.rewrite(reencrypted_batches).await?;
+            info!(
+                "Re-encrypted {} messages of segment with start offset: {} for partition with ID: {} using key with ID: {}.",
+                reencrypted_messages, self.start_offset, self.partition_id, key_id
+            );
+        } else {
+            trace!(
+                "No messages to re-encrypt in segment with start offset: {} for partition with ID: {}.",
+                self.start_offset,
+                self.partition_id
+            );
+        }
+
+        self.encryption_key_id = Some(key_id);
+        Ok(reencrypted_messages)
+    }
+}
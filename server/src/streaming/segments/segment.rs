@@ -6,6 +6,7 @@ use crate::compat::message_conversion::snapshots::retained_batch_snapshot::Retai
 use crate::compat::message_conversion::streams::retained_batch::RetainedBatchWriter;
 use crate::compat::message_conversion::streams::retained_message::RetainedMessageStream;
 use crate::configs::system::SystemConfig;
+use crate::streaming::batching::message_batch::RetainedMessageBatch;
 use crate::streaming::segments::index::Index;
 use crate::streaming::segments::time_index::TimeIndex;
 use crate::streaming::sizeable::Sizeable;
@@ -16,6 +17,7 @@ use crate::{
     streaming::batching::batch_accumulator::BatchAccumulator,
 };
 use futures::{pin_mut, TryStreamExt};
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
 use iggy::error::IggyError;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
@@ -27,6 +29,7 @@ use tracing::{info, trace};
 pub const LOG_EXTENSION: &str = "log";
 pub const INDEX_EXTENSION: &str = "index";
 pub const TIME_INDEX_EXTENSION: &str = "timeindex";
+pub const OFFLOADED_EXTENSION: &str = "offloaded";
 pub const MAX_SIZE_BYTES: u32 = 1000 * 1000 * 1000;
 
 #[derive(Debug)]
@@ -40,6 +43,7 @@ pub struct Segment {
     pub index_path: String,
     pub log_path: String,
     pub time_index_path: String,
+    pub offloaded_path: String,
     pub size_bytes: u32,
     pub last_index_position: u32,
     pub max_size_bytes: u32,
@@ -50,8 +54,18 @@ pub struct Segment {
     pub messages_count_of_parent_topic: Arc<AtomicU64>,
     pub messages_count_of_parent_partition: Arc<AtomicU64>,
     pub is_closed: bool,
+    pub is_offloaded: bool,
+    /// The ID of the key all the messages of the closed segment are known to be encrypted with, once re-encrypted.
+    pub(crate) encryption_key_id: Option<u32>,
+    /// The timestamp of the first message of the segment, used for the time-based segment rolling.
+    pub(crate) first_message_timestamp: Option<u64>,
+    /// The number of times the closed segment has been rewritten, e.g. by the log compaction or the re-encryption.
+    pub rewrites_count: u64,
     pub(crate) message_expiry: IggyExpiry,
+    pub(crate) compression_algorithm: CompressionAlgorithm,
     pub(crate) unsaved_messages: Option<BatchAccumulator>,
+    /// The batches compressed by the producers, stored as-is once persisted, always preceding the unsaved messages.
+    pub(crate) unsaved_compressed_batches: Vec<RetainedMessageBatch>,
     pub(crate) config: Arc<SystemConfig>,
     pub(crate) indexes: Option<Vec<Index>>,
     pub(crate) time_indexes: Option<Vec<TimeIndex>>,
@@ -68,6 +82,7 @@ impl Segment {
         config: Arc<SystemConfig>,
         storage: Arc<SystemStorage>,
         message_expiry: IggyExpiry,
+        compression_algorithm: CompressionAlgorithm,
         size_of_parent_stream: Arc<AtomicU64>,
         size_of_parent_topic: Arc<AtomicU64>,
         size_of_parent_partition: Arc<AtomicU64>,
@@ -87,6 +102,7 @@ impl Segment {
             log_path: Self::get_log_path(&path),
             index_path: Self::get_index_path(&path),
             time_index_path: Self::get_time_index_path(&path),
+            offloaded_path: Self::get_offloaded_path(&path),
             size_bytes: 0,
             last_index_position: 0,
             max_size_bytes: config.segment.size.as_bytes_u64() as u32,
@@ -94,6 +110,7 @@ impl Segment {
                 IggyExpiry::ServerDefault => config.segment.message_expiry,
                 _ => message_expiry,
             },
+            compression_algorithm,
             indexes: match config.segment.cache_indexes {
                 true => Some(Vec::new()),
                 false => None,
@@ -103,7 +120,12 @@ impl Segment {
                 false => None,
             },
             unsaved_messages: None,
+            unsaved_compressed_batches: Vec::new(),
             is_closed: false,
+            is_offloaded: false,
+            encryption_key_id: None,
+            first_message_timestamp: None,
+            rewrites_count: 0,
             size_of_parent_stream,
             size_of_parent_partition,
             size_of_parent_topic,
@@ -120,7 +142,76 @@ impl Segment {
             return true;
         }
 
-        self.is_expired(IggyTimestamp::now()).await
+        let now = IggyTimestamp::now();
+        if self.is_aged(now) {
+            return true;
+        }
+
+        self.is_expired(now).await
+    }
+
+    /// Checks whether the open segment accepts the messages for longer than the configured `segment.max_age`,
+    /// counting from its first message, and thus should be closed to roll over to the next segment.
+    pub fn is_aged(&self, now: IggyTimestamp) -> bool {
+        // The `IggyDuration::is_zero` is true for any sub-second duration, so the microseconds are compared instead.
+        let max_age = self.config.segment.max_age;
+        if self.is_closed || max_age.as_micros() == 0 {
+            return false;
+        }
+
+        let Some(first_message_timestamp) = self.first_message_timestamp else {
+            return false;
+        };
+
+        first_message_timestamp + max_age.as_micros() <= now.as_micros()
+    }
+
+    /// Closes the open segment if it's aged, even when no more messages are appended to it,
+    /// so that it can be expired and deleted. Returns true if the segment was closed.
+    pub async fn close_if_aged(&mut self, now: IggyTimestamp) -> Result<bool, IggyError> {
+        if self.is_closed || self.config.segment.max_age.as_micros() == 0 {
+            return Ok(false);
+        }
+
+        self.load_first_message_timestamp().await;
+        if !self.is_aged(now) {
+            return Ok(false);
+        }
+
+        // Persisting all the unsaved messages closes the segment, as it's already aged.
+        while self.has_unsaved_messages() {
+            self.persist_messages().await?;
+        }
+        if !self.is_closed {
+            self.close();
+        }
+        Ok(true)
+    }
+
+    /// Loads the timestamp of the first message of the segment, which is not known yet when the segment was loaded from disk.
+    pub(crate) async fn load_first_message_timestamp(&mut self) {
+        if self.first_message_timestamp.is_some()
+            || self.is_closed
+            || self.is_offloaded
+            || self.size_bytes == 0
+            || self.config.segment.max_age.as_micros() == 0
+        {
+            return;
+        }
+
+        if let Ok(messages) = self.get_messages(self.start_offset, 1).await {
+            self.first_message_timestamp = messages.first().map(|message| message.timestamp);
+        }
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.end_offset = self.current_offset;
+        self.is_closed = true;
+        self.unsaved_messages = None;
+        info!(
+            "Closed segment with start offset: {} for partition with ID: {}.",
+            self.start_offset, self.partition_id
+        );
     }
 
     pub async fn is_expired(&self, now: IggyTimestamp) -> bool {
@@ -132,6 +223,16 @@ impl Segment {
             IggyExpiry::NeverExpire => false,
             IggyExpiry::ServerDefault => false,
             IggyExpiry::ExpireDuration(expiry) => {
+                // The last time index of the offloaded segment is stored locally, so it doesn't have to be fetched.
+                if self.is_offloaded {
+                    let Ok(Some(last_time_index)) =
+                        self.storage.segment.load_last_time_index(self).await
+                    else {
+                        return false;
+                    };
+                    return last_time_index.timestamp + expiry.as_micros() <= now.as_micros();
+                }
+
                 let last_messages = self.get_messages(self.current_offset, 1).await;
                 if last_messages.is_err() {
                     return false;
@@ -149,6 +250,18 @@ impl Segment {
         }
     }
 
+    /// Offloads the closed segment, which must be already archived, by removing its log file from the local disk.
+    /// The messages of the offloaded segment are fetched back from the archive by the tiered storage on demand.
+    pub async fn offload(&mut self) -> Result<(), IggyError> {
+        if !self.is_closed || self.is_offloaded {
+            return Ok(());
+        }
+
+        self.storage.segment.offload(self).await?;
+        self.is_offloaded = true;
+        Ok(())
+    }
+
     fn get_log_path(path: &str) -> String {
         format!("{}.{}", path, LOG_EXTENSION)
     }
@@ -161,6 +274,10 @@ impl Segment {
         format!("{}.{}", path, TIME_INDEX_EXTENSION)
     }
 
+    fn get_offloaded_path(path: &str) -> String {
+        format!("{}.{}", path, OFFLOADED_EXTENSION)
+    }
+
     pub async fn convert_segment_from_schema(&self, schema: BinarySchema) -> Result<(), IggyError> {
         let log_path = self.log_path.as_str();
         let index_path = self.index_path.as_str();
@@ -253,6 +370,7 @@ mod tests {
         let log_path = Segment::get_log_path(&path);
         let index_path = Segment::get_index_path(&path);
         let time_index_path = Segment::get_time_index_path(&path);
+        let offloaded_path = Segment::get_offloaded_path(&path);
         let message_expiry = IggyExpiry::ExpireDuration(IggyDuration::from(10));
         let size_of_parent_stream = Arc::new(AtomicU64::new(0));
         let size_of_parent_topic = Arc::new(AtomicU64::new(0));
@@ -269,6 +387,7 @@ mod tests {
             config,
             storage,
             message_expiry,
+            CompressionAlgorithm::None,
             size_of_parent_stream,
             size_of_parent_topic,
             size_of_parent_partition,
@@ -287,11 +406,14 @@ mod tests {
         assert_eq!(segment.log_path, log_path);
         assert_eq!(segment.index_path, index_path);
         assert_eq!(segment.time_index_path, time_index_path);
+        assert_eq!(segment.offloaded_path, offloaded_path);
         assert_eq!(segment.message_expiry, message_expiry);
+        assert_eq!(segment.compression_algorithm, CompressionAlgorithm::None);
         assert!(segment.unsaved_messages.is_none());
         assert!(segment.indexes.is_some());
         assert!(segment.time_indexes.is_some());
         assert!(!segment.is_closed);
+        assert!(!segment.is_offloaded);
         assert!(!segment.is_full().await);
     }
 
@@ -325,6 +447,7 @@ mod tests {
             config,
             storage,
             message_expiry,
+            CompressionAlgorithm::None,
             size_of_parent_stream,
             size_of_parent_topic,
             size_of_parent_partition,
@@ -366,6 +489,7 @@ mod tests {
             config,
             storage,
             message_expiry,
+            CompressionAlgorithm::None,
             size_of_parent_stream,
             size_of_parent_topic,
             size_of_parent_partition,
@@ -1,20 +1,29 @@
+use crate::configs::system::IndexesRecovery;
 use crate::streaming::batching::iterator::IntoMessagesIterator;
-use crate::streaming::batching::message_batch::RetainedMessageBatch;
-use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::batching::message_batch::{RetainedMessageBatch, RETAINED_BATCH_OVERHEAD};
+use crate::streaming::models::messages::{FileRegion, RetainedMessage};
+use crate::streaming::partitions::producers::get_producer_sequence;
+use crate::streaming::partitions::transactions::get_marker_transaction_id;
 use crate::streaming::persistence::persister::Persister;
 use crate::streaming::segments::index::{Index, IndexRange};
 use crate::streaming::segments::segment::Segment;
 use crate::streaming::segments::time_index::TimeIndex;
+use crate::streaming::segments::verification::{
+    verify_batch_messages, SegmentIssue, SegmentVerification,
+};
 use crate::streaming::sizeable::Sizeable;
 use crate::streaming::storage::SegmentStorage;
 use crate::streaming::utils::file;
 use crate::streaming::utils::head_tail_buf::HeadTailBuffer;
 use anyhow::Context;
 use async_trait::async_trait;
-use bytes::{BufMut, BytesMut};
+use bytes::{BufMut, Bytes, BytesMut};
+use iggy::bytes_serializable::BytesSerializable;
 use iggy::error::IggyError;
+use iggy::models::messages::MessageState;
 use iggy::utils::byte_size::IggyByteSize;
 use iggy::utils::checksum;
+use std::collections::HashMap;
 use std::io::SeekFrom;
 use std::path::Path;
 use std::sync::atomic::Ordering;
@@ -37,6 +46,36 @@ impl FileSegmentStorage {
     pub fn new(persister: Arc<dyn Persister>) -> Self {
         Self { persister }
     }
+
+    /// Detects the index and time index files inconsistent with the log, e.g. after a crash between appending
+    /// the batch and its indexes, and recovers them as configured by `system.recovery.inconsistent_indexes`.
+    async fn recover_indexes(&self, segment: &Segment) -> Result<(), IggyError> {
+        if are_indexes_consistent(segment).await? {
+            return Ok(());
+        }
+
+        let recovery = segment.config.recovery.inconsistent_indexes;
+        warn!(
+            "Indexes of segment with start offset: {} for partition with ID: {} for topic with ID: {} and stream with ID: {} are inconsistent with the log, recovery: {recovery}.",
+            segment.start_offset, segment.partition_id, segment.topic_id, segment.stream_id
+        );
+        if recovery == IndexesRecovery::Refuse {
+            return Err(IggyError::InconsistentSegmentIndexes(
+                segment.log_path.clone(),
+            ));
+        }
+
+        let mut verification = self.verify(segment).await?;
+        if recovery == IndexesRecovery::Truncate {
+            verification.truncate(verification.consistent_batches_count());
+        }
+        self.repair(segment, &verification).await?;
+        info!(
+            "Recovered indexes of segment with start offset: {} for partition with ID: {} for topic with ID: {} and stream with ID: {}, retained {} batches.",
+            segment.start_offset, segment.partition_id, segment.topic_id, segment.stream_id, verification.batches_count
+        );
+        Ok(())
+    }
 }
 
 unsafe impl Send for FileSegmentStorage {}
@@ -49,8 +88,14 @@ impl SegmentStorage for FileSegmentStorage {
             "Loading segment from disk for start offset: {} and partition with ID: {} for topic with ID: {} and stream with ID: {} ...",
             segment.start_offset, segment.partition_id, segment.topic_id, segment.stream_id
         );
-        let log_file = file::open(&segment.log_path).await?;
-        let file_size = log_file.metadata().await.unwrap().len() as u64;
+        let file_size = if segment.is_offloaded {
+            load_offloaded_size(segment).await?
+        } else {
+            complete_rewrite(segment).await?;
+            self.recover_indexes(segment).await?;
+            let log_file = file::open(&segment.log_path).await?;
+            log_file.metadata().await.unwrap().len() as u64
+        };
         segment.size_bytes = file_size as _;
         segment.last_index_position = file_size as _;
 
@@ -96,7 +141,8 @@ impl SegmentStorage for FileSegmentStorage {
             }
         }
 
-        if segment.is_full().await {
+        segment.load_first_message_timestamp().await;
+        if segment.is_offloaded || segment.is_full().await {
             segment.is_closed = true;
         }
 
@@ -181,7 +227,14 @@ impl SegmentStorage for FileSegmentStorage {
             "Deleting segment of size {segment_size} with start offset: {} for partition with ID: {} for stream with ID: {} and topic with ID: {}...",
             segment.start_offset, segment.partition_id, segment.stream_id, segment.topic_id,
         );
-        self.persister.delete(&segment.log_path).await?;
+        if segment.is_offloaded {
+            self.persister.delete(&segment.offloaded_path).await?;
+            if let Some(tiered) = &segment.storage.tiered {
+                tiered.evict(&segment.log_path).await?;
+            }
+        } else {
+            self.persister.delete(&segment.log_path).await?;
+        }
         self.persister.delete(&segment.index_path).await?;
         self.persister.delete(&segment.time_index_path).await?;
         segment
@@ -224,6 +277,44 @@ impl SegmentStorage for FileSegmentStorage {
         Ok(batches)
     }
 
+    async fn load_batches_region(
+        &self,
+        segment: &Segment,
+        index_range: &IndexRange,
+    ) -> Result<Option<FileRegion>, IggyError> {
+        let mut file = file::open(&segment.log_path).await?;
+        let file_size = file.metadata().await?.len();
+        let start_position = index_range.start.position as u64;
+        let end_position = index_range.end.position as u64;
+        if start_position > end_position
+            || end_position + RETAINED_BATCH_OVERHEAD as u64 > file_size
+        {
+            return Ok(None);
+        }
+
+        // The index points to the start of the last batch, so its length is read to find where the region ends.
+        file.seek(SeekFrom::Start(end_position + 8)).await?;
+        let batch_length = file
+            .read_u32_le()
+            .await
+            .map_err(|_| IggyError::CannotReadBatchLength)?;
+        let end_position = end_position + RETAINED_BATCH_OVERHEAD as u64 + batch_length as u64;
+        if end_position > file_size {
+            return Ok(None);
+        }
+
+        trace!(
+            "Loaded region: {start_position}...{end_position} of segment log file: {}",
+            segment.log_path
+        );
+        Ok(Some(FileRegion {
+            path: segment.log_path.clone(),
+            position: start_position,
+            length: end_position - start_position,
+            file: Arc::new(file.into_std().await),
+        }))
+    }
+
     async fn load_newest_batches_by_size(
         &self,
         segment: &Segment,
@@ -267,6 +358,106 @@ impl SegmentStorage for FileSegmentStorage {
         Ok(batch_size)
     }
 
+    async fn rewrite_batches(
+        &self,
+        segment: &Segment,
+        batches: Vec<RetainedMessageBatch>,
+    ) -> Result<(Vec<Index>, Vec<TimeIndex>), IggyError> {
+        let mut log_bytes = BytesMut::new();
+        let mut index_bytes = BytesMut::with_capacity(batches.len() * INDEX_SIZE as usize);
+        let mut time_index_bytes =
+            BytesMut::with_capacity(batches.len() * TIME_INDEX_SIZE as usize);
+        let mut indexes = Vec::with_capacity(batches.len());
+        let mut time_indexes = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let relative_offset = (batch.get_last_offset() - segment.start_offset) as u32;
+            let index = Index {
+                relative_offset,
+                position: log_bytes.len() as u32,
+            };
+            let time_index = TimeIndex {
+                relative_offset,
+                timestamp: batch.max_timestamp,
+            };
+            index_bytes.put_u32_le(index.relative_offset);
+            index_bytes.put_u32_le(index.position);
+            time_index_bytes.put_u32_le(time_index.relative_offset);
+            time_index_bytes.put_u64_le(time_index.timestamp);
+            batch.extend(&mut log_bytes);
+            indexes.push(index);
+            time_indexes.push(time_index);
+        }
+
+        // All the files are written aside first, the log file being the last one, so that the interrupted
+        // rewrite can be either discarded or completed when loading the segment, see `complete_rewrite`.
+        let index_path = get_rewritten_path(&segment.index_path);
+        if let Err(err) = self
+            .persister
+            .overwrite(&index_path, &index_bytes)
+            .await
+            .with_context(|| format!("Failed to rewrite index of segment: {}", index_path))
+        {
+            return Err(IggyError::CannotSaveIndexToSegment(err));
+        }
+
+        let time_index_path = get_rewritten_path(&segment.time_index_path);
+        if let Err(err) = self
+            .persister
+            .overwrite(&time_index_path, &time_index_bytes)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to rewrite TimeIndex of segment: {}",
+                    time_index_path
+                )
+            })
+        {
+            return Err(IggyError::CannotSaveTimeIndexToSegment(err));
+        }
+
+        let log_path = get_rewritten_path(&segment.log_path);
+        if let Err(err) = self
+            .persister
+            .overwrite(&log_path, &log_bytes)
+            .await
+            .with_context(|| format!("Failed to rewrite messages of segment: {}", log_path))
+        {
+            return Err(IggyError::CannotSaveMessagesToSegment(err));
+        }
+
+        file::rename(&index_path, &segment.index_path).await?;
+        file::rename(&time_index_path, &segment.time_index_path).await?;
+        file::rename(&log_path, &segment.log_path).await?;
+        trace!(
+            "Rewritten {} message batches of total size {} bytes in segment: {}.",
+            indexes.len(),
+            log_bytes.len(),
+            segment.log_path
+        );
+        Ok((indexes, time_indexes))
+    }
+
+    async fn offload(&self, segment: &Segment) -> Result<(), IggyError> {
+        // The size of the log is kept in the marker file, as it's no longer available locally,
+        // while the index and time index files remain on disk to find the messages without fetching the log.
+        let mut bytes = BytesMut::with_capacity(4);
+        bytes.put_u32_le(segment.size_bytes);
+        self.persister
+            .overwrite(&segment.offloaded_path, &bytes)
+            .await
+            .with_context(|| format!("Failed to offload segment: {}", segment.log_path))
+            .map_err(IggyError::CannotSaveMessagesToSegment)?;
+        self.persister.delete(&segment.log_path).await?;
+        if let Some(tiered) = &segment.storage.tiered {
+            tiered.evict(&segment.log_path).await?;
+        }
+        info!(
+            "Offloaded segment with start offset: {} for partition with ID: {} for topic with ID: {} and stream with ID: {}.",
+            segment.start_offset, segment.partition_id, segment.topic_id, segment.stream_id
+        );
+        Ok(())
+    }
+
     async fn load_message_ids(&self, segment: &Segment) -> Result<Vec<u128>, IggyError> {
         let mut message_ids = Vec::new();
         load_batches_by_range(segment, &IndexRange::max_range(), |batch| {
@@ -282,6 +473,54 @@ impl SegmentStorage for FileSegmentStorage {
         Ok(message_ids)
     }
 
+    async fn load_committed_transactions(
+        &self,
+        segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError> {
+        let mut transactions = HashMap::new();
+        load_batches_by_range(segment, &IndexRange::max_range(), |batch| {
+            for message in batch.into_messages_iter() {
+                if message.message_state == MessageState::TransactionCommitted {
+                    transactions.insert(get_marker_transaction_id(&message)?, message.offset);
+                }
+            }
+            Ok(())
+        })
+        .await?;
+        trace!(
+            "Loaded {} committed transaction IDs from disk.",
+            transactions.len()
+        );
+        Ok(transactions)
+    }
+
+    async fn load_producer_sequences(
+        &self,
+        segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError> {
+        let mut sequences = HashMap::new();
+        load_batches_by_range(segment, &IndexRange::max_range(), |batch| {
+            for message in batch.into_messages_iter() {
+                let Some(headers) = message.headers else {
+                    continue;
+                };
+
+                let headers = HashMap::from_bytes(headers)?;
+                if let Some((producer_id, sequence)) = get_producer_sequence(&headers)? {
+                    let last_sequence: &mut u64 = sequences.entry(producer_id).or_default();
+                    *last_sequence = (*last_sequence).max(sequence);
+                }
+            }
+            Ok(())
+        })
+        .await?;
+        trace!(
+            "Loaded the sequence numbers of {} producers from disk.",
+            sequences.len()
+        );
+        Ok(sequences)
+    }
+
     async fn load_checksums(&self, segment: &Segment) -> Result<(), IggyError> {
         load_batches_by_range(segment, &IndexRange::max_range(), |batch| {
             for message in batch.into_messages_iter() {
@@ -306,6 +545,164 @@ impl SegmentStorage for FileSegmentStorage {
         Ok(())
     }
 
+    async fn verify(&self, segment: &Segment) -> Result<SegmentVerification, IggyError> {
+        let file = file::open(&segment.log_path).await?;
+        let log_size_bytes = file.metadata().await?.len();
+        let mut verification = SegmentVerification {
+            start_offset: segment.start_offset,
+            log_path: segment.log_path.clone(),
+            log_size_bytes,
+            ..Default::default()
+        };
+        let mut reader = BufReader::with_capacity(BUF_READER_CAPACITY_BYTES, file);
+        let mut position = 0;
+        while position < log_size_bytes {
+            if log_size_bytes - position < RETAINED_BATCH_OVERHEAD as u64 {
+                verification
+                    .issues
+                    .push(SegmentIssue::TruncatedBatch { position });
+                break;
+            }
+
+            let base_offset = reader.read_u64_le().await?;
+            let batch_length = reader.read_u32_le().await?;
+            let last_offset_delta = reader.read_u32_le().await?;
+            let max_timestamp = reader.read_u64_le().await?;
+            let batch_size = RETAINED_BATCH_OVERHEAD as u64 + batch_length as u64;
+            if position + batch_size > log_size_bytes {
+                verification
+                    .issues
+                    .push(SegmentIssue::TruncatedBatch { position });
+                break;
+            }
+
+            let mut payload = BytesMut::with_capacity(batch_length as usize);
+            payload.put_bytes(0, batch_length as usize);
+            reader.read_exact(&mut payload).await?;
+            let batch = RetainedMessageBatch::new(
+                base_offset,
+                last_offset_delta,
+                max_timestamp,
+                batch_length,
+                payload.freeze(),
+            );
+            let result = if base_offset < segment.start_offset {
+                Err(IggyError::InvalidOffset(base_offset))
+            } else {
+                batch
+                    .clone()
+                    .decompress()
+                    .and_then(|batch| verify_batch_messages(&batch))
+            };
+            match result {
+                Ok((messages_count, issues)) => {
+                    verification.add_batch(position, &batch);
+                    verification.messages_count += messages_count;
+                    verification.issues.extend(issues);
+                }
+                Err(error) => {
+                    verification.issues.push(SegmentIssue::InvalidBatch {
+                        position,
+                        base_offset,
+                        error: error.to_string(),
+                    });
+                    break;
+                }
+            }
+            position += batch_size;
+        }
+
+        let index_bytes = load_index_file(&segment.index_path).await?;
+        let indexes = index_bytes
+            .chunks_exact(INDEX_SIZE as usize)
+            .map(|index| Index {
+                relative_offset: u32::from_le_bytes(index[..4].try_into().unwrap()),
+                position: u32::from_le_bytes(index[4..].try_into().unwrap()),
+            })
+            .collect::<Vec<_>>();
+        verification.verify_indexes(&indexes, index_bytes.len() as u64);
+        let time_index_bytes = load_index_file(&segment.time_index_path).await?;
+        let time_indexes = time_index_bytes
+            .chunks_exact(TIME_INDEX_SIZE as usize)
+            .map(|index| TimeIndex {
+                relative_offset: u32::from_le_bytes(index[..4].try_into().unwrap()),
+                timestamp: u64::from_le_bytes(index[4..].try_into().unwrap()),
+            })
+            .collect::<Vec<_>>();
+        verification.verify_time_indexes(&time_indexes, time_index_bytes.len() as u64);
+        trace!(
+            "Verified segment: {}, found {} issues.",
+            segment.log_path,
+            verification.issues.len()
+        );
+        Ok(verification)
+    }
+
+    async fn repair(
+        &self,
+        segment: &Segment,
+        verification: &SegmentVerification,
+    ) -> Result<(), IggyError> {
+        if verification.has_corrupt_tail() {
+            warn!(
+                "Truncating corrupt tail of {} bytes of segment: {}.",
+                verification.log_size_bytes - verification.valid_size_bytes,
+                segment.log_path
+            );
+            file::overwrite(&segment.log_path)
+                .await?
+                .set_len(verification.valid_size_bytes)
+                .await?;
+        }
+
+        if !verification.has_corrupt_tail() && !verification.has_index_mismatch() {
+            return Ok(());
+        }
+
+        let mut index_bytes =
+            BytesMut::with_capacity(verification.indexes.len() * INDEX_SIZE as usize);
+        for index in &verification.indexes {
+            index_bytes.put_u32_le(index.relative_offset);
+            index_bytes.put_u32_le(index.position);
+        }
+        let mut time_index_bytes =
+            BytesMut::with_capacity(verification.time_indexes.len() * TIME_INDEX_SIZE as usize);
+        for time_index in &verification.time_indexes {
+            time_index_bytes.put_u32_le(time_index.relative_offset);
+            time_index_bytes.put_u64_le(time_index.timestamp);
+        }
+
+        if let Err(err) = self
+            .persister
+            .overwrite(&segment.index_path, &index_bytes)
+            .await
+            .with_context(|| format!("Failed to rebuild index of segment: {}", segment.log_path))
+        {
+            return Err(IggyError::CannotSaveIndexToSegment(err));
+        }
+
+        if let Err(err) = self
+            .persister
+            .overwrite(&segment.time_index_path, &time_index_bytes)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to rebuild TimeIndex of segment: {}",
+                    segment.log_path
+                )
+            })
+        {
+            return Err(IggyError::CannotSaveTimeIndexToSegment(err));
+        }
+
+        info!(
+            "Rebuilt {} indexes of segment: {}.",
+            verification.indexes.len(),
+            segment.log_path
+        );
+        Ok(())
+    }
+
     async fn load_all_indexes(&self, segment: &Segment) -> Result<Vec<Index>, IggyError> {
         trace!("Loading indexes from file...");
         let file = file::open(&segment.index_path).await?;
@@ -560,12 +957,132 @@ impl SegmentStorage for FileSegmentStorage {
     }
 }
 
+/// Checks whether the index and time index files cover exactly all the batches of the log,
+/// by comparing their sizes and the last index with the last batch, without reading the whole log.
+async fn are_indexes_consistent(segment: &Segment) -> Result<bool, IggyError> {
+    if !Path::new(&segment.index_path).exists() || !Path::new(&segment.time_index_path).exists() {
+        return Ok(false);
+    }
+
+    let mut log_file = file::open(&segment.log_path).await?;
+    let log_size = log_file.metadata().await?.len();
+    let mut index_file = file::open(&segment.index_path).await?;
+    let index_size = index_file.metadata().await?.len();
+    let time_index_size = file::open(&segment.time_index_path)
+        .await?
+        .metadata()
+        .await?
+        .len();
+    if index_size % INDEX_SIZE as u64 != 0
+        || time_index_size % TIME_INDEX_SIZE as u64 != 0
+        || index_size / INDEX_SIZE as u64 != time_index_size / TIME_INDEX_SIZE as u64
+    {
+        return Ok(false);
+    }
+
+    if index_size == 0 {
+        return Ok(log_size == 0);
+    }
+
+    index_file
+        .seek(SeekFrom::Start(index_size - INDEX_SIZE as u64))
+        .await?;
+    let relative_offset = index_file.read_u32_le().await?;
+    let position = index_file.read_u32_le().await? as u64;
+    if position + RETAINED_BATCH_OVERHEAD as u64 > log_size {
+        return Ok(false);
+    }
+
+    log_file.seek(SeekFrom::Start(position)).await?;
+    let base_offset = log_file.read_u64_le().await?;
+    let batch_length = log_file.read_u32_le().await?;
+    let last_offset_delta = log_file.read_u32_le().await?;
+    Ok(
+        position + RETAINED_BATCH_OVERHEAD as u64 + batch_length as u64 == log_size
+            && base_offset + last_offset_delta as u64
+                == segment.start_offset + relative_offset as u64,
+    )
+}
+
+/// Loads the raw content of the index or time index file, which is empty if the file doesn't exist.
+async fn load_index_file(path: &str) -> Result<Vec<u8>, IggyError> {
+    if !Path::new(path).exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(tokio::fs::read(path).await?)
+}
+
+fn get_rewritten_path(path: &str) -> String {
+    format!("{path}.rewritten")
+}
+
+/// Completes or discards the rewrite of the segment files (e.g. by the log compaction) interrupted by the server shutdown.
+/// The rewritten log file exists until all the files are replaced, and the index file is replaced first,
+/// so its absence means that the rewritten files were complete and the remaining ones have to be replaced.
+async fn complete_rewrite(segment: &Segment) -> Result<(), IggyError> {
+    let log_path = get_rewritten_path(&segment.log_path);
+    if !Path::new(&log_path).exists() {
+        return Ok(());
+    }
+
+    let index_path = get_rewritten_path(&segment.index_path);
+    let time_index_path = get_rewritten_path(&segment.time_index_path);
+    if Path::new(&index_path).exists() {
+        warn!(
+            "Discarding the interrupted rewrite of segment with start offset: {} for partition with ID: {}.",
+            segment.start_offset, segment.partition_id
+        );
+        for path in [&index_path, &time_index_path, &log_path] {
+            if Path::new(path).exists() {
+                tokio::fs::remove_file(path).await?;
+            }
+        }
+        return Ok(());
+    }
+
+    warn!(
+        "Completing the interrupted rewrite of segment with start offset: {} for partition with ID: {}.",
+        segment.start_offset, segment.partition_id
+    );
+    if Path::new(&time_index_path).exists() {
+        file::rename(&time_index_path, &segment.time_index_path).await?;
+    }
+    file::rename(&log_path, &segment.log_path).await?;
+    Ok(())
+}
+
+async fn load_offloaded_size(segment: &Segment) -> Result<u64, IggyError> {
+    let mut file = file::open(&segment.offloaded_path).await?;
+    Ok(file.read_u32_le().await? as u64)
+}
+
+/// Opens the log file of the segment, which is fetched from the archive by the tiered storage if it was offloaded.
+async fn open_log(segment: &Segment) -> Result<tokio::fs::File, IggyError> {
+    if !segment.is_offloaded {
+        return Ok(file::open(&segment.log_path).await?);
+    }
+
+    let Some(tiered) = &segment.storage.tiered else {
+        error!(
+            "Segment with start offset: {} for partition with ID: {} is offloaded, but the tiered storage is disabled.",
+            segment.start_offset, segment.partition_id
+        );
+        return Err(IggyError::CannotFetchArchivedFile(
+            segment.log_path.to_owned(),
+        ));
+    };
+
+    let cached_path = tiered.fetch(&segment.log_path).await?;
+    Ok(file::open(&cached_path).await?)
+}
+
 async fn load_batches_by_range(
     segment: &Segment,
     index_range: &IndexRange,
     mut on_batch: impl FnMut(RetainedMessageBatch) -> Result<(), IggyError>,
 ) -> Result<(), IggyError> {
-    let file = file::open(&segment.log_path).await?;
+    let file = open_log(segment).await?;
     let file_size = file.metadata().await?.len();
     if file_size == 0 {
         return Ok(());
@@ -625,7 +1142,8 @@ async fn load_batches_by_range(
             max_timestamp,
             batch_length,
             payload.freeze(),
-        );
+        )
+        .decompress()?;
         on_batch(batch)?;
     }
     Ok(())
@@ -636,7 +1154,7 @@ async fn load_messages_by_size(
     size_bytes: u64,
     mut on_batch: impl FnMut(RetainedMessageBatch) -> Result<(), IggyError>,
 ) -> Result<(), IggyError> {
-    let file = file::open(&segment.log_path).await?;
+    let file = open_log(segment).await?;
     let file_size = file.metadata().await?.len();
     if file_size == 0 {
         return Ok(());
@@ -681,7 +1199,7 @@ async fn load_messages_by_size(
         );
         let message_size = batch.get_size_bytes() as u64;
         if accumulated_size >= threshold {
-            on_batch(batch)?;
+            on_batch(batch.decompress()?)?;
         }
 
         accumulated_size += message_size;
@@ -692,3 +1210,43 @@ async fn load_messages_by_size(
 
     Ok(())
 }
+
+/// Parses the batches of the log read from the batch boundary until the batch with the given last offset,
+/// or until the end of the bytes, if there's no such batch, e.g. when reading by the max range.
+pub(crate) fn parse_batches(
+    bytes: Bytes,
+    last_offset: u64,
+) -> Result<Vec<RetainedMessageBatch>, IggyError> {
+    let mut batches = Vec::new();
+    let mut position = 0;
+    while position + RETAINED_BATCH_OVERHEAD as usize <= bytes.len() {
+        let base_offset = u64::from_le_bytes(bytes[position..position + 8].try_into()?);
+        let batch_length = u32::from_le_bytes(bytes[position + 8..position + 12].try_into()?);
+        let last_offset_delta = u32::from_le_bytes(bytes[position + 12..position + 16].try_into()?);
+        let max_timestamp = u64::from_le_bytes(bytes[position + 16..position + 24].try_into()?);
+        let payload_start = position + RETAINED_BATCH_OVERHEAD as usize;
+        let payload_end = payload_start + batch_length as usize;
+        if payload_end > bytes.len() {
+            warn!(
+                "Cannot read batch payload for batch with base offset: {base_offset}, last offset delta: {last_offset_delta}, max timestamp: {max_timestamp} and batch length: {batch_length}.\nProbably OS hasn't flushed the data yet, try setting `enforce_fsync = true` for partition configuration if this issue occurs again.",
+            );
+            break;
+        }
+
+        let batch = RetainedMessageBatch::new(
+            base_offset,
+            last_offset_delta,
+            max_timestamp,
+            batch_length,
+            bytes.slice(payload_start..payload_end),
+        )
+        .decompress()?;
+        let batch_last_offset = batch.get_last_offset();
+        batches.push(batch);
+        position = payload_end;
+        if batch_last_offset == last_offset {
+            break;
+        }
+    }
+    Ok(batches)
+}
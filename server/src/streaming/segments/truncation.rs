@@ -0,0 +1,64 @@
+use crate::streaming::batching::iterator::IntoMessagesIterator;
+use crate::streaming::segments::segment::Segment;
+use iggy::error::IggyError;
+use std::sync::atomic::Ordering;
+use tracing::info;
+
+impl Segment {
+    /// Removes the messages starting from the given offset, keeping the preceding ones.
+    /// The unsaved messages are persisted first, then the log, index and time index files are replaced altogether,
+    /// and the current offset of the segment is moved back to the last retained message.
+    /// Returns the number of the removed messages.
+    pub async fn truncate(&mut self, offset: u64) -> Result<u64, IggyError> {
+        if self.is_offloaded || offset > self.current_offset {
+            return Ok(0);
+        }
+
+        while self.has_unsaved_messages() {
+            self.persist_messages().await?;
+        }
+
+        let mut removed_messages = 0;
+        let mut last_offset = None;
+        let mut retained_batches = Vec::new();
+        for batch in self.get_all_batches().await? {
+            let mut retained_messages = Vec::new();
+            for message in batch.into_messages_iter() {
+                if message.offset < offset {
+                    last_offset = Some(message.offset);
+                    retained_messages.push(message);
+                } else {
+                    removed_messages += 1;
+                }
+            }
+
+            if let Some(batch) = Self::create_batch(&retained_messages) {
+                retained_batches.push(batch.compress(self.compression_algorithm)?);
+            }
+        }
+
+        let previous_messages_count = self.get_messages_count();
+        self.rewrite(retained_batches).await?;
+        self.current_offset = last_offset.unwrap_or(self.start_offset);
+        if self.is_closed {
+            self.end_offset = self.current_offset;
+        }
+        if self.size_bytes == 0 {
+            self.first_message_timestamp = None;
+        }
+
+        // The messages count is derived from the offsets, so it's updated by the difference rather than the removed messages.
+        let removed_count = previous_messages_count.saturating_sub(self.get_messages_count());
+        self.messages_count_of_parent_stream
+            .fetch_sub(removed_count, Ordering::SeqCst);
+        self.messages_count_of_parent_topic
+            .fetch_sub(removed_count, Ordering::SeqCst);
+        self.messages_count_of_parent_partition
+            .fetch_sub(removed_count, Ordering::SeqCst);
+        info!(
+            "Truncated segment with start offset: {} for partition with ID: {} at offset: {}, removed {} messages.",
+            self.start_offset, self.partition_id, offset, removed_messages
+        );
+        Ok(removed_messages)
+    }
+}
@@ -0,0 +1,423 @@
+use crate::streaming::batching::message_batch::{RetainedMessageBatch, RETAINED_BATCH_OVERHEAD};
+use crate::streaming::models::messages::FileRegion;
+use crate::streaming::persistence::persister::Persister;
+use crate::streaming::persistence::uring::Uring;
+use crate::streaming::segments::index::{Index, IndexRange};
+use crate::streaming::segments::segment::Segment;
+use crate::streaming::segments::storage::{parse_batches, FileSegmentStorage};
+use crate::streaming::segments::time_index::TimeIndex;
+use crate::streaming::segments::verification::SegmentVerification;
+use crate::streaming::storage::SegmentStorage;
+use async_trait::async_trait;
+use bytes::Bytes;
+use iggy::error::IggyError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::trace;
+
+/// The segment storage reading the message batches and appending them (via the given persister) through io_uring.
+/// The remaining, less frequent operations such as loading, verifying or offloading the segment are delegated to the file storage.
+#[derive(Debug)]
+pub struct UringSegmentStorage {
+    uring: Arc<Uring>,
+    file: FileSegmentStorage,
+}
+
+impl UringSegmentStorage {
+    pub fn new(uring: Arc<Uring>, persister: Arc<dyn Persister>) -> Self {
+        Self {
+            uring,
+            file: FileSegmentStorage::new(persister),
+        }
+    }
+
+    /// Reads the bytes of the log from the start of the range until the end of the last batch in range,
+    /// or until the end of the file, if the last batch can't be determined, e.g. for the max range.
+    async fn read_range(
+        &self,
+        segment: &Segment,
+        index_range: &IndexRange,
+    ) -> Result<Bytes, IggyError> {
+        let file = self.uring.open(&segment.log_path, libc::O_RDONLY).await?;
+        let start_position = index_range.start.position as u64;
+        let end_position = index_range.end.position as u64;
+        if end_position >= start_position {
+            let header = self
+                .uring
+                .read(&file, end_position, RETAINED_BATCH_OVERHEAD as usize)
+                .await?;
+            if header.len() == RETAINED_BATCH_OVERHEAD as usize {
+                let batch_length = u32::from_le_bytes(header[8..12].try_into()?) as u64;
+                let length =
+                    end_position - start_position + RETAINED_BATCH_OVERHEAD as u64 + batch_length;
+                let bytes = self
+                    .uring
+                    .read(&file, start_position, length as usize)
+                    .await?;
+                return Ok(Bytes::from(bytes));
+            }
+        }
+
+        let bytes = self.uring.read_to_end(&file, start_position).await?;
+        Ok(Bytes::from(bytes))
+    }
+}
+
+#[async_trait]
+impl SegmentStorage for UringSegmentStorage {
+    async fn load(&self, segment: &mut Segment) -> Result<(), IggyError> {
+        self.file.load(segment).await
+    }
+
+    async fn save(&self, segment: &Segment) -> Result<(), IggyError> {
+        self.file.save(segment).await
+    }
+
+    async fn delete(&self, segment: &Segment) -> Result<(), IggyError> {
+        self.file.delete(segment).await
+    }
+
+    async fn load_message_batches(
+        &self,
+        segment: &Segment,
+        index_range: &IndexRange,
+    ) -> Result<Vec<RetainedMessageBatch>, IggyError> {
+        if segment.is_offloaded {
+            return self.file.load_message_batches(segment, index_range).await;
+        }
+
+        let bytes = self.read_range(segment, index_range).await?;
+        let index_last_offset = index_range.end.relative_offset as u64 + segment.start_offset;
+        let batches = parse_batches(bytes, index_last_offset)?;
+        trace!(
+            "Loaded {} message batches from disk using io_uring.",
+            batches.len()
+        );
+        Ok(batches)
+    }
+
+    async fn load_batches_region(
+        &self,
+        segment: &Segment,
+        index_range: &IndexRange,
+    ) -> Result<Option<FileRegion>, IggyError> {
+        self.file.load_batches_region(segment, index_range).await
+    }
+
+    async fn load_newest_batches_by_size(
+        &self,
+        segment: &Segment,
+        size_bytes: u64,
+    ) -> Result<Vec<RetainedMessageBatch>, IggyError> {
+        self.file
+            .load_newest_batches_by_size(segment, size_bytes)
+            .await
+    }
+
+    async fn save_batches(
+        &self,
+        segment: &Segment,
+        batch: RetainedMessageBatch,
+    ) -> Result<u32, IggyError> {
+        self.file.save_batches(segment, batch).await
+    }
+
+    async fn rewrite_batches(
+        &self,
+        segment: &Segment,
+        batches: Vec<RetainedMessageBatch>,
+    ) -> Result<(Vec<Index>, Vec<TimeIndex>), IggyError> {
+        self.file.rewrite_batches(segment, batches).await
+    }
+
+    async fn offload(&self, segment: &Segment) -> Result<(), IggyError> {
+        self.file.offload(segment).await
+    }
+
+    async fn load_message_ids(&self, segment: &Segment) -> Result<Vec<u128>, IggyError> {
+        self.file.load_message_ids(segment).await
+    }
+
+    async fn load_committed_transactions(
+        &self,
+        segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError> {
+        self.file.load_committed_transactions(segment).await
+    }
+
+    async fn load_producer_sequences(
+        &self,
+        segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError> {
+        self.file.load_producer_sequences(segment).await
+    }
+
+    async fn load_checksums(&self, segment: &Segment) -> Result<(), IggyError> {
+        self.file.load_checksums(segment).await
+    }
+
+    async fn verify(&self, segment: &Segment) -> Result<SegmentVerification, IggyError> {
+        self.file.verify(segment).await
+    }
+
+    async fn repair(
+        &self,
+        segment: &Segment,
+        verification: &SegmentVerification,
+    ) -> Result<(), IggyError> {
+        self.file.repair(segment, verification).await
+    }
+
+    async fn load_all_indexes(&self, segment: &Segment) -> Result<Vec<Index>, IggyError> {
+        self.file.load_all_indexes(segment).await
+    }
+
+    async fn load_index_range(
+        &self,
+        segment: &Segment,
+        index_start_offset: u64,
+        index_end_offset: u64,
+    ) -> Result<Option<IndexRange>, IggyError> {
+        self.file
+            .load_index_range(segment, index_start_offset, index_end_offset)
+            .await
+    }
+
+    async fn save_index(&self, index_path: &str, index: Index) -> Result<(), IggyError> {
+        self.file.save_index(index_path, index).await
+    }
+
+    async fn try_load_time_index_for_timestamp(
+        &self,
+        segment: &Segment,
+        timestamp: u64,
+    ) -> Result<Option<TimeIndex>, IggyError> {
+        self.file
+            .try_load_time_index_for_timestamp(segment, timestamp)
+            .await
+    }
+
+    async fn load_all_time_indexes(&self, segment: &Segment) -> Result<Vec<TimeIndex>, IggyError> {
+        self.file.load_all_time_indexes(segment).await
+    }
+
+    async fn load_last_time_index(
+        &self,
+        segment: &Segment,
+    ) -> Result<Option<TimeIndex>, IggyError> {
+        self.file.load_last_time_index(segment).await
+    }
+
+    async fn save_time_index(&self, index_path: &str, index: TimeIndex) -> Result<(), IggyError> {
+        self.file.save_time_index(index_path, index).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::{StorageBackend, SystemConfig};
+    use crate::streaming::models::messages::RetainedMessage;
+    use crate::streaming::persistence::uring::UringPersister;
+    use crate::streaming::segments::index::Index;
+    use crate::streaming::storage::SystemStorage;
+    use bytes::BytesMut;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
+    use iggy::messages::send_messages::Message;
+    use iggy::utils::expiry::IggyExpiry;
+    use std::sync::atomic::AtomicU64;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn saved_batches_should_be_loaded_by_the_index_range() {
+        let Some(test_segment) = create_segment().await else {
+            return;
+        };
+        let segment = &test_segment.segment;
+        let storage = segment.storage.segment.clone();
+        let mut positions = Vec::new();
+        let mut position = 0;
+        for base_offset in [0, 3, 6] {
+            positions.push(position);
+            position += storage
+                .save_batches(segment, create_batch(base_offset, 3))
+                .await
+                .unwrap();
+        }
+
+        let batches = storage
+            .load_message_batches(segment, &IndexRange::max_range())
+            .await
+            .unwrap();
+        assert_batches(&batches, &[0, 3, 6]);
+
+        let index_range = IndexRange {
+            start: Index {
+                relative_offset: 2,
+                position: positions[0],
+            },
+            end: Index {
+                relative_offset: 5,
+                position: positions[1],
+            },
+        };
+        let batches = storage
+            .load_message_batches(segment, &index_range)
+            .await
+            .unwrap();
+        assert_batches(&batches, &[0, 3]);
+
+        let index_range = IndexRange {
+            start: Index {
+                relative_offset: 8,
+                position: positions[2],
+            },
+            end: Index {
+                relative_offset: 8,
+                position: positions[2],
+            },
+        };
+        let batches = storage
+            .load_message_batches(segment, &index_range)
+            .await
+            .unwrap();
+        assert_batches(&batches, &[6]);
+    }
+
+    #[tokio::test]
+    async fn batches_appended_after_the_rewrite_should_be_loaded() {
+        let Some(test_segment) = create_segment().await else {
+            return;
+        };
+        let segment = &test_segment.segment;
+        let storage = segment.storage.segment.clone();
+        storage
+            .save_batches(segment, create_batch(0, 5))
+            .await
+            .unwrap();
+        storage
+            .rewrite_batches(segment, vec![create_batch(0, 2), create_batch(2, 2)])
+            .await
+            .unwrap();
+        storage
+            .save_batches(segment, create_batch(4, 1))
+            .await
+            .unwrap();
+
+        let batches = storage
+            .load_message_batches(segment, &IndexRange::max_range())
+            .await
+            .unwrap();
+        assert_batches(&batches, &[0, 2, 4]);
+    }
+
+    #[tokio::test]
+    async fn loading_batches_of_the_missing_log_should_fail() {
+        let Some(test_segment) = create_segment().await else {
+            return;
+        };
+        let segment = &test_segment.segment;
+        let storage = segment.storage.segment.clone();
+        std::fs::remove_file(&segment.log_path).unwrap();
+
+        assert!(storage
+            .load_message_batches(segment, &IndexRange::max_range())
+            .await
+            .is_err());
+        assert!(storage
+            .save_batches(segment, create_batch(0, 1))
+            .await
+            .is_err());
+    }
+
+    async fn create_segment() -> Option<TestSegment> {
+        let uring = match Uring::get_instance() {
+            Ok(uring) => uring,
+            Err(error) => {
+                eprintln!("Skipping the test, io_uring is not available: {error}");
+                return None;
+            }
+        };
+        let mut config = SystemConfig {
+            path: std::env::temp_dir()
+                .join(format!("iggy_io_uring_{}", Uuid::now_v7().to_u128_le()))
+                .to_string_lossy()
+                .to_string(),
+            ..Default::default()
+        };
+        config.storage.backend = StorageBackend::IoUring;
+        let config = Arc::new(config);
+        std::fs::create_dir_all(config.get_partition_path(1, 1, 1)).unwrap();
+        let storage = Arc::new(SystemStorage::new(
+            config.clone(),
+            Arc::new(UringPersister::new(uring, false)),
+        ));
+        let segment = Segment::create(
+            1,
+            1,
+            1,
+            0,
+            config,
+            storage.clone(),
+            IggyExpiry::NeverExpire,
+            CompressionAlgorithm::None,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+        );
+        storage.segment.save(&segment).await.unwrap();
+        Some(TestSegment { segment })
+    }
+
+    fn create_batch(base_offset: u64, messages_count: u32) -> RetainedMessageBatch {
+        let mut bytes = BytesMut::new();
+        for offset in base_offset..base_offset + messages_count as u64 {
+            RetainedMessage::new(
+                offset,
+                1000 + offset,
+                Message::new(
+                    Some(offset as u128 + 1),
+                    Bytes::from(format!("message {offset}")),
+                    None,
+                ),
+            )
+            .extend(&mut bytes);
+        }
+        RetainedMessageBatch::new(
+            base_offset,
+            messages_count - 1,
+            1000 + base_offset + messages_count as u64 - 1,
+            bytes.len() as u32,
+            bytes.freeze(),
+        )
+    }
+
+    fn assert_batches(batches: &[RetainedMessageBatch], base_offsets: &[u64]) {
+        assert_eq!(
+            batches
+                .iter()
+                .map(|batch| batch.base_offset)
+                .collect::<Vec<_>>(),
+            base_offsets
+        );
+        for batch in batches {
+            assert_eq!(
+                batch.bytes,
+                create_batch(batch.base_offset, batch.last_offset_delta + 1).bytes
+            );
+        }
+    }
+
+    struct TestSegment {
+        segment: Segment,
+    }
+
+    impl Drop for TestSegment {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.segment.config.path);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::streaming::batching::message_batch::RetainedMessageBatch;
+use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::segments::index::Index;
+use crate::streaming::segments::storage::{INDEX_SIZE, TIME_INDEX_SIZE};
+use crate::streaming::segments::time_index::TimeIndex;
+use crate::streaming::sizeable::Sizeable;
+use iggy::error::IggyError;
+use iggy::utils::checksum;
+use serde::Serialize;
+
+/// The result of verifying the segment files by `SegmentStorage::verify`.
+/// The indexes and time indexes are rebuilt from the valid part of the log, so that they can be used to repair the segment.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SegmentVerification {
+    pub start_offset: u64,
+    pub log_path: String,
+    pub log_size_bytes: u64,
+    /// The size of the log until the end of the last valid batch, the remaining bytes are the corrupt tail.
+    pub valid_size_bytes: u64,
+    pub batches_count: u32,
+    pub messages_count: u64,
+    pub issues: Vec<SegmentIssue>,
+    #[serde(skip)]
+    pub(crate) indexes: Vec<Index>,
+    #[serde(skip)]
+    pub(crate) time_indexes: Vec<TimeIndex>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SegmentIssue {
+    /// The batch starting at the position is incomplete, e.g. due to a torn write.
+    TruncatedBatch { position: u64 },
+    /// The batch starting at the position can't be read, e.g. its messages can't be parsed or decompressed.
+    InvalidBatch {
+        position: u64,
+        base_offset: u64,
+        error: String,
+    },
+    /// The message is readable, but its payload doesn't match the checksum, it can't be repaired.
+    InvalidMessageChecksum {
+        offset: u64,
+        expected: u32,
+        calculated: u32,
+    },
+    /// The index file doesn't match the batches of the log, starting from the index number.
+    IndexMismatch {
+        expected_count: usize,
+        actual_count: usize,
+        first_mismatch: usize,
+    },
+    /// The time index file doesn't match the batches of the log, starting from the index number.
+    TimeIndexMismatch {
+        expected_count: usize,
+        actual_count: usize,
+        first_mismatch: usize,
+    },
+}
+
+impl SegmentVerification {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn has_corrupt_tail(&self) -> bool {
+        self.valid_size_bytes < self.log_size_bytes
+    }
+
+    /// Returns true if the segment can be repaired, by truncating the corrupt tail and rebuilding the indexes.
+    pub fn is_repairable(&self) -> bool {
+        self.issues
+            .iter()
+            .all(|issue| !matches!(issue, SegmentIssue::InvalidMessageChecksum { .. }))
+    }
+
+    pub fn has_index_mismatch(&self) -> bool {
+        self.issues.iter().any(|issue| {
+            matches!(
+                issue,
+                SegmentIssue::IndexMismatch { .. } | SegmentIssue::TimeIndexMismatch { .. }
+            )
+        })
+    }
+
+    /// Returns the number of the leading batches, which are indexed consistently in both the index and time index files.
+    pub fn consistent_batches_count(&self) -> usize {
+        self.issues
+            .iter()
+            .filter_map(|issue| match issue {
+                SegmentIssue::IndexMismatch { first_mismatch, .. }
+                | SegmentIssue::TimeIndexMismatch { first_mismatch, .. } => Some(*first_mismatch),
+                _ => None,
+            })
+            .min()
+            .unwrap_or(self.indexes.len())
+    }
+
+    /// Limits the valid part of the log to the given number of batches,
+    /// so that repairing the segment truncates the log and the indexes after them.
+    pub(crate) fn truncate(&mut self, batches_count: usize) {
+        if batches_count >= self.indexes.len() {
+            return;
+        }
+
+        self.valid_size_bytes = self.indexes[batches_count].position as u64;
+        self.indexes.truncate(batches_count);
+        self.time_indexes.truncate(batches_count);
+        self.batches_count = batches_count as u32;
+    }
+
+    /// Adds the valid batch read from the given position of the log, along with its indexes.
+    pub(crate) fn add_batch(&mut self, position: u64, batch: &RetainedMessageBatch) {
+        let relative_offset = (batch.get_last_offset() - self.start_offset) as u32;
+        self.indexes.push(Index {
+            relative_offset,
+            position: position as u32,
+        });
+        self.time_indexes.push(TimeIndex {
+            relative_offset,
+            timestamp: batch.max_timestamp,
+        });
+        self.batches_count += 1;
+        self.valid_size_bytes = position + batch.get_size_bytes() as u64;
+    }
+
+    /// Compares the indexes loaded from the index file with the ones rebuilt from the log.
+    pub(crate) fn verify_indexes(&mut self, indexes: &[Index], size_bytes: u64) {
+        let first_mismatch = self
+            .indexes
+            .iter()
+            .zip(indexes)
+            .position(|(expected, actual)| {
+                expected.relative_offset != actual.relative_offset
+                    || expected.position != actual.position
+            });
+        if let Some(issue) = Self::get_mismatch(
+            self.indexes.len(),
+            indexes.len(),
+            first_mismatch,
+            !size_bytes.is_multiple_of(INDEX_SIZE as u64),
+        ) {
+            let (expected_count, actual_count, first_mismatch) = issue;
+            self.issues.push(SegmentIssue::IndexMismatch {
+                expected_count,
+                actual_count,
+                first_mismatch,
+            });
+        }
+    }
+
+    /// Compares the time indexes loaded from the time index file with the ones rebuilt from the log.
+    pub(crate) fn verify_time_indexes(&mut self, time_indexes: &[TimeIndex], size_bytes: u64) {
+        let first_mismatch = self
+            .time_indexes
+            .iter()
+            .zip(time_indexes)
+            .position(|(expected, actual)| expected != actual);
+        if let Some(issue) = Self::get_mismatch(
+            self.time_indexes.len(),
+            time_indexes.len(),
+            first_mismatch,
+            !size_bytes.is_multiple_of(TIME_INDEX_SIZE as u64),
+        ) {
+            let (expected_count, actual_count, first_mismatch) = issue;
+            self.issues.push(SegmentIssue::TimeIndexMismatch {
+                expected_count,
+                actual_count,
+                first_mismatch,
+            });
+        }
+    }
+
+    fn get_mismatch(
+        expected_count: usize,
+        actual_count: usize,
+        first_mismatch: Option<usize>,
+        has_partial_entry: bool,
+    ) -> Option<(usize, usize, usize)> {
+        let first_mismatch = match first_mismatch {
+            Some(first_mismatch) => first_mismatch,
+            None if expected_count != actual_count || has_partial_entry => {
+                expected_count.min(actual_count)
+            }
+            None => return None,
+        };
+        Some((expected_count, actual_count, first_mismatch))
+    }
+}
+
+/// Parses the messages of the decompressed batch, returning the ones with the invalid checksums.
+/// Unlike the messages iterator, it fails instead of panicking or stopping when the message exceeds the batch.
+pub(crate) fn verify_batch_messages(
+    batch: &RetainedMessageBatch,
+) -> Result<(u64, Vec<SegmentIssue>), IggyError> {
+    let mut messages_count = 0;
+    let mut issues = Vec::new();
+    let mut position = 0;
+    let bytes = &batch.bytes;
+    while position < bytes.len() {
+        let length = bytes
+            .get(position..position + 4)
+            .ok_or(IggyError::CannotReadBatchPayload)?;
+        let length = u32::from_le_bytes(length.try_into()?) as usize;
+        // The message starts with its offset, state, timestamp, ID, checksum and headers length.
+        if length < 8 + 1 + 8 + 16 + 4 + 4 || position + 4 + length > bytes.len() {
+            return Err(IggyError::CannotReadBatchPayload);
+        }
+
+        let message = bytes.slice(position + 4..position + 4 + length);
+        let headers_length = u32::from_le_bytes(message[37..41].try_into()?) as usize;
+        if 41 + headers_length > message.len() {
+            return Err(IggyError::CannotReadBatchPayload);
+        }
+
+        let message = RetainedMessage::try_from_bytes(message)?;
+        let calculated = checksum::calculate(&message.payload);
+        if calculated != message.checksum {
+            issues.push(SegmentIssue::InvalidMessageChecksum {
+                offset: message.offset,
+                expected: message.checksum,
+                calculated,
+            });
+        }
+        messages_count += 1;
+        position += 4 + length;
+    }
+    Ok((messages_count, issues))
+}
@@ -1,13 +1,14 @@
 use iggy::models::user_info::{AtomicUserId, UserId};
 use std::fmt::Display;
 use std::net::SocketAddr;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 // This might be extended with more fields in the future e.g. custom name, permissions etc.
 #[derive(Debug)]
 pub struct Session {
     user_id: AtomicUserId,
     active: AtomicBool,
+    transaction_id: AtomicU64,
     pub client_id: u32,
     pub ip_address: SocketAddr,
 }
@@ -17,6 +18,7 @@ impl Session {
         Self {
             client_id,
             active: AtomicBool::new(true),
+            transaction_id: AtomicU64::new(0),
             user_id: AtomicUserId::new(user_id),
             ip_address,
         }
@@ -46,6 +48,21 @@ impl Session {
         self.set_user_id(0)
     }
 
+    pub fn get_transaction_id(&self) -> Option<u64> {
+        match self.transaction_id.load(Ordering::Acquire) {
+            0 => None,
+            transaction_id => Some(transaction_id),
+        }
+    }
+
+    pub fn set_transaction_id(&self, transaction_id: u64) {
+        self.transaction_id.store(transaction_id, Ordering::Release)
+    }
+
+    pub fn clear_transaction_id(&self) {
+        self.set_transaction_id(0)
+    }
+
     pub fn is_active(&self) -> bool {
         self.active.load(Ordering::Acquire)
     }
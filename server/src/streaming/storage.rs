@@ -1,22 +1,37 @@
 use super::batching::message_batch::RetainedMessageBatch;
+use crate::archiver::tiered::TieredStorage;
 use crate::configs::system::SystemConfig;
 use crate::state::system::{PartitionState, StreamState, TopicState};
-use crate::streaming::partitions::partition::{ConsumerOffset, Partition};
+use crate::streaming::models::messages::FileRegion;
+use crate::streaming::partitions::memory_storage::MemoryPartitionStorage;
+use crate::streaming::partitions::partition::{
+    ConsumerGroupLeases, ConsumerOffset, DeadLetterChange, MessageLeaseChange, Partition,
+    ProducerSequence,
+};
 use crate::streaming::partitions::storage::FilePartitionStorage;
+use crate::streaming::persistence::memory::MemoryPersister;
 use crate::streaming::persistence::persister::Persister;
 use crate::streaming::segments::index::{Index, IndexRange};
+use crate::streaming::segments::memory_storage::MemorySegmentStorage;
 use crate::streaming::segments::segment::Segment;
 use crate::streaming::segments::storage::FileSegmentStorage;
 use crate::streaming::segments::time_index::TimeIndex;
+use crate::streaming::segments::verification::SegmentVerification;
+use crate::streaming::streams::memory_storage::MemoryStreamStorage;
 use crate::streaming::streams::storage::FileStreamStorage;
 use crate::streaming::streams::stream::Stream;
 use crate::streaming::systems::info::SystemInfo;
-use crate::streaming::systems::storage::FileSystemInfoStorage;
+use crate::streaming::systems::memory_storage::MemorySystemInfoStorage;
+use crate::streaming::systems::memory_storage::MemoryTransactionLogStorage;
+use crate::streaming::systems::storage::{FileSystemInfoStorage, FileTransactionLogStorage};
+use crate::streaming::systems::transactions::TransactionLogEntry;
+use crate::streaming::topics::memory_storage::MemoryTopicStorage;
 use crate::streaming::topics::storage::FileTopicStorage;
 use crate::streaming::topics::topic::Topic;
 use async_trait::async_trait;
 use iggy::consumer::ConsumerKind;
 use iggy::error::IggyError;
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
@@ -26,6 +41,13 @@ pub trait SystemInfoStorage: Sync + Send {
     async fn save(&self, system_info: &SystemInfo) -> Result<(), IggyError>;
 }
 
+#[async_trait]
+pub trait TransactionLogStorage: Sync + Send {
+    async fn load(&self) -> Result<Vec<TransactionLogEntry>, IggyError>;
+    async fn save(&self, entries: &[TransactionLogEntry]) -> Result<(), IggyError>;
+    async fn append(&self, entry: &TransactionLogEntry) -> Result<(), IggyError>;
+}
+
 #[async_trait]
 pub trait StreamStorage: Send + Sync {
     async fn load(&self, stream: &mut Stream, state: StreamState) -> Result<(), IggyError>;
@@ -54,6 +76,56 @@ pub trait PartitionStorage: Send + Sync {
     ) -> Result<Vec<ConsumerOffset>, IggyError>;
     async fn delete_consumer_offsets(&self, path: &str) -> Result<(), IggyError>;
     async fn delete_consumer_offset(&self, path: &str) -> Result<(), IggyError>;
+    async fn save_consumer_group_leases(
+        &self,
+        leases: &ConsumerGroupLeases,
+    ) -> Result<(), IggyError>;
+    async fn append_consumer_group_lease_changes(
+        &self,
+        path: &str,
+        changes: &[MessageLeaseChange],
+    ) -> Result<(), IggyError>;
+    async fn load_consumer_group_leases(
+        &self,
+        path: &str,
+    ) -> Result<Vec<ConsumerGroupLeases>, IggyError>;
+    async fn save_dead_letters(&self, partition: &Partition) -> Result<(), IggyError>;
+    async fn append_dead_letter_changes(
+        &self,
+        path: &str,
+        changes: &[DeadLetterChange],
+    ) -> Result<(), IggyError>;
+    async fn save_producer_sequences(
+        &self,
+        path: &str,
+        sequences: &HashMap<u64, u64>,
+        persisted_offset: u64,
+    ) -> Result<(), IggyError>;
+    async fn append_producer_sequences(
+        &self,
+        path: &str,
+        sequences: &HashMap<u64, u64>,
+        persisted_offset: u64,
+    ) -> Result<(), IggyError>;
+    async fn load_producer_sequences(
+        &self,
+        path: &str,
+    ) -> Result<Option<Vec<ProducerSequence>>, IggyError>;
+    async fn save_committed_transactions(
+        &self,
+        path: &str,
+        transactions: &HashMap<u64, u64>,
+    ) -> Result<(), IggyError>;
+    async fn append_committed_transactions(
+        &self,
+        path: &str,
+        transactions: &HashMap<u64, u64>,
+    ) -> Result<(), IggyError>;
+    async fn load_committed_transactions(
+        &self,
+        path: &str,
+    ) -> Result<Option<HashMap<u64, u64>>, IggyError>;
+    async fn load_dead_letters(&self, partition: &mut Partition) -> Result<(), IggyError>;
 }
 
 #[async_trait]
@@ -66,6 +138,11 @@ pub trait SegmentStorage: Send + Sync {
         segment: &Segment,
         index_range: &IndexRange,
     ) -> Result<Vec<RetainedMessageBatch>, IggyError>;
+    async fn load_batches_region(
+        &self,
+        segment: &Segment,
+        index_range: &IndexRange,
+    ) -> Result<Option<FileRegion>, IggyError>;
     async fn load_newest_batches_by_size(
         &self,
         segment: &Segment,
@@ -76,8 +153,28 @@ pub trait SegmentStorage: Send + Sync {
         segment: &Segment,
         batch: RetainedMessageBatch,
     ) -> Result<u32, IggyError>;
+    async fn rewrite_batches(
+        &self,
+        segment: &Segment,
+        batches: Vec<RetainedMessageBatch>,
+    ) -> Result<(Vec<Index>, Vec<TimeIndex>), IggyError>;
+    async fn offload(&self, segment: &Segment) -> Result<(), IggyError>;
     async fn load_message_ids(&self, segment: &Segment) -> Result<Vec<u128>, IggyError>;
+    async fn load_committed_transactions(
+        &self,
+        segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError>;
+    async fn load_producer_sequences(
+        &self,
+        segment: &Segment,
+    ) -> Result<HashMap<u64, u64>, IggyError>;
     async fn load_checksums(&self, segment: &Segment) -> Result<(), IggyError>;
+    async fn verify(&self, segment: &Segment) -> Result<SegmentVerification, IggyError>;
+    async fn repair(
+        &self,
+        segment: &Segment,
+        verification: &SegmentVerification,
+    ) -> Result<(), IggyError>;
     async fn load_all_indexes(&self, segment: &Segment) -> Result<Vec<Index>, IggyError>;
     async fn load_index_range(
         &self,
@@ -100,11 +197,13 @@ pub trait SegmentStorage: Send + Sync {
 #[derive(Debug)]
 pub struct SystemStorage {
     pub info: Arc<dyn SystemInfoStorage>,
+    pub transactions: Arc<dyn TransactionLogStorage>,
     pub stream: Arc<dyn StreamStorage>,
     pub topic: Arc<dyn TopicStorage>,
     pub partition: Arc<dyn PartitionStorage>,
     pub segment: Arc<dyn SegmentStorage>,
     pub persister: Arc<dyn Persister>,
+    pub tiered: Option<Arc<TieredStorage>>,
 }
 
 impl SystemStorage {
@@ -114,11 +213,60 @@ impl SystemStorage {
                 config.get_state_info_path(),
                 persister.clone(),
             )),
+            transactions: Arc::new(FileTransactionLogStorage::new(
+                config.get_state_transactions_path(),
+                persister.clone(),
+            )),
             stream: Arc::new(FileStreamStorage),
             topic: Arc::new(FileTopicStorage),
             partition: Arc::new(FilePartitionStorage::new(persister.clone())),
-            segment: Arc::new(FileSegmentStorage::new(persister.clone())),
+            segment: Self::resolve_segment_storage(&config, persister.clone()),
             persister,
+            tiered: None,
+        }
+    }
+
+    /// Creates the storage keeping all the data in memory, nothing is stored on disk.
+    pub fn in_memory(config: Arc<SystemConfig>) -> Self {
+        let files = Arc::new(MemoryPersister::default());
+        Self {
+            info: Arc::new(MemorySystemInfoStorage::new(
+                config.get_state_info_path(),
+                files.clone(),
+            )),
+            transactions: Arc::new(MemoryTransactionLogStorage::new(
+                config.get_state_transactions_path(),
+                files.clone(),
+            )),
+            stream: Arc::new(MemoryStreamStorage::new(files.clone())),
+            topic: Arc::new(MemoryTopicStorage::new(files.clone())),
+            partition: Arc::new(MemoryPartitionStorage::new(files.clone())),
+            segment: Arc::new(MemorySegmentStorage::new(files.clone())),
+            persister: files,
+            tiered: None,
+        }
+    }
+
+    fn resolve_segment_storage(
+        config: &SystemConfig,
+        persister: Arc<dyn Persister>,
+    ) -> Arc<dyn SegmentStorage> {
+        match config.storage.backend {
+            #[cfg(all(feature = "io-uring", target_os = "linux"))]
+            crate::configs::system::StorageBackend::IoUring => {
+                match crate::streaming::persistence::uring::Uring::get_instance() {
+                    Ok(uring) => Arc::new(
+                        crate::streaming::segments::uring_storage::UringSegmentStorage::new(
+                            uring, persister,
+                        ),
+                    ),
+                    Err(error) => {
+                        tracing::warn!("Cannot use io_uring for the segment storage, falling back to the file one. {error}");
+                        Arc::new(FileSegmentStorage::new(persister))
+                    }
+                }
+            }
+            _ => Arc::new(FileSegmentStorage::new(persister)),
         }
     }
 }
@@ -129,6 +277,12 @@ impl Debug for dyn SystemInfoStorage {
     }
 }
 
+impl Debug for dyn TransactionLogStorage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TransactionLogStorage")
+    }
+}
+
 impl Debug for dyn StreamStorage {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "StreamStorage")
@@ -167,6 +321,7 @@ pub(crate) mod tests {
 
     struct TestPersister {}
     struct TestSystemInfoStorage {}
+    struct TestTransactionLogStorage {}
     struct TestStreamStorage {}
     struct TestTopicStorage {}
     struct TestPartitionStorage {}
@@ -198,6 +353,21 @@ pub(crate) mod tests {
         }
     }
 
+    #[async_trait]
+    impl TransactionLogStorage for TestTransactionLogStorage {
+        async fn load(&self) -> Result<Vec<TransactionLogEntry>, IggyError> {
+            Ok(Vec::new())
+        }
+
+        async fn save(&self, _entries: &[TransactionLogEntry]) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn append(&self, _entry: &TransactionLogEntry) -> Result<(), IggyError> {
+            Ok(())
+        }
+    }
+
     #[async_trait]
     impl StreamStorage for TestStreamStorage {
         async fn load(&self, _stream: &mut Stream, _state: StreamState) -> Result<(), IggyError> {
@@ -265,6 +435,92 @@ pub(crate) mod tests {
         async fn delete_consumer_offset(&self, _path: &str) -> Result<(), IggyError> {
             Ok(())
         }
+
+        async fn save_consumer_group_leases(
+            &self,
+            _leases: &ConsumerGroupLeases,
+        ) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn append_consumer_group_lease_changes(
+            &self,
+            _path: &str,
+            _changes: &[MessageLeaseChange],
+        ) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn load_consumer_group_leases(
+            &self,
+            _path: &str,
+        ) -> Result<Vec<ConsumerGroupLeases>, IggyError> {
+            Ok(vec![])
+        }
+
+        async fn save_dead_letters(&self, _partition: &Partition) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn append_dead_letter_changes(
+            &self,
+            _path: &str,
+            _changes: &[DeadLetterChange],
+        ) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn save_producer_sequences(
+            &self,
+            _path: &str,
+            _sequences: &HashMap<u64, u64>,
+            _persisted_offset: u64,
+        ) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn append_producer_sequences(
+            &self,
+            _path: &str,
+            _sequences: &HashMap<u64, u64>,
+            _persisted_offset: u64,
+        ) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn load_producer_sequences(
+            &self,
+            _path: &str,
+        ) -> Result<Option<Vec<ProducerSequence>>, IggyError> {
+            Ok(None)
+        }
+
+        async fn save_committed_transactions(
+            &self,
+            _path: &str,
+            _transactions: &HashMap<u64, u64>,
+        ) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn append_committed_transactions(
+            &self,
+            _path: &str,
+            _transactions: &HashMap<u64, u64>,
+        ) -> Result<(), IggyError> {
+            Ok(())
+        }
+
+        async fn load_committed_transactions(
+            &self,
+            _path: &str,
+        ) -> Result<Option<HashMap<u64, u64>>, IggyError> {
+            Ok(None)
+        }
+
+        async fn load_dead_letters(&self, _partition: &mut Partition) -> Result<(), IggyError> {
+            Ok(())
+        }
     }
 
     #[async_trait]
@@ -289,6 +545,14 @@ pub(crate) mod tests {
             Ok(vec![])
         }
 
+        async fn load_batches_region(
+            &self,
+            _segment: &Segment,
+            _index_range: &IndexRange,
+        ) -> Result<Option<FileRegion>, IggyError> {
+            Ok(None)
+        }
+
         async fn load_newest_batches_by_size(
             &self,
             _segment: &Segment,
@@ -305,14 +569,52 @@ pub(crate) mod tests {
             Ok(0)
         }
 
+        async fn rewrite_batches(
+            &self,
+            _segment: &Segment,
+            _batches: Vec<RetainedMessageBatch>,
+        ) -> Result<(Vec<Index>, Vec<TimeIndex>), IggyError> {
+            Ok((vec![], vec![]))
+        }
+
+        async fn offload(&self, _segment: &Segment) -> Result<(), IggyError> {
+            Ok(())
+        }
+
         async fn load_message_ids(&self, _segment: &Segment) -> Result<Vec<u128>, IggyError> {
             Ok(vec![])
         }
 
+        async fn load_committed_transactions(
+            &self,
+            _segment: &Segment,
+        ) -> Result<HashMap<u64, u64>, IggyError> {
+            Ok(HashMap::new())
+        }
+
+        async fn load_producer_sequences(
+            &self,
+            _segment: &Segment,
+        ) -> Result<HashMap<u64, u64>, IggyError> {
+            Ok(HashMap::new())
+        }
+
         async fn load_checksums(&self, _segment: &Segment) -> Result<(), IggyError> {
             Ok(())
         }
 
+        async fn verify(&self, _segment: &Segment) -> Result<SegmentVerification, IggyError> {
+            Ok(SegmentVerification::default())
+        }
+
+        async fn repair(
+            &self,
+            _segment: &Segment,
+            _verification: &SegmentVerification,
+        ) -> Result<(), IggyError> {
+            Ok(())
+        }
+
         async fn load_all_indexes(&self, _segment: &Segment) -> Result<Vec<Index>, IggyError> {
             Ok(vec![])
         }
@@ -364,11 +666,13 @@ pub(crate) mod tests {
     pub fn get_test_system_storage() -> SystemStorage {
         SystemStorage {
             info: Arc::new(TestSystemInfoStorage {}),
+            transactions: Arc::new(TestTransactionLogStorage {}),
             stream: Arc::new(TestStreamStorage {}),
             topic: Arc::new(TestTopicStorage {}),
             partition: Arc::new(TestPartitionStorage {}),
             segment: Arc::new(TestSegmentStorage {}),
             persister: Arc::new(TestPersister {}),
+            tiered: None,
         }
     }
 }
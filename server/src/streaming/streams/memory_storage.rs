@@ -0,0 +1,42 @@
+use crate::state::system::StreamState;
+use crate::streaming::persistence::memory::MemoryPersister;
+use crate::streaming::storage::StreamStorage;
+use crate::streaming::streams::stream::Stream;
+use async_trait::async_trait;
+use iggy::error::IggyError;
+use std::sync::Arc;
+use tracing::info;
+
+/// The stream storage for the in-memory backend, the streams have no files of their own.
+#[derive(Debug)]
+pub struct MemoryStreamStorage {
+    files: Arc<MemoryPersister>,
+}
+
+impl MemoryStreamStorage {
+    pub fn new(files: Arc<MemoryPersister>) -> Self {
+        Self { files }
+    }
+}
+
+#[async_trait]
+impl StreamStorage for MemoryStreamStorage {
+    async fn load(&self, stream: &mut Stream, _state: StreamState) -> Result<(), IggyError> {
+        // The in-memory storage doesn't outlive the server, so there's never a stream to load.
+        Err(IggyError::StreamIdNotFound(stream.stream_id))
+    }
+
+    async fn save(&self, stream: &Stream) -> Result<(), IggyError> {
+        info!("Saved stream with ID: {} in memory.", stream.stream_id);
+        Ok(())
+    }
+
+    async fn delete(&self, stream: &Stream) -> Result<(), IggyError> {
+        let files_count = self.files.delete_all(&stream.path);
+        info!(
+            "Deleted stream with ID: {} along with {files_count} files from memory.",
+            stream.stream_id
+        );
+        Ok(())
+    }
+}
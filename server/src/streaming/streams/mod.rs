@@ -1,3 +1,4 @@
+pub mod memory_storage;
 pub mod messages;
 pub mod partitions;
 pub mod persistence;
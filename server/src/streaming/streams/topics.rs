@@ -85,6 +85,8 @@ impl Stream {
         replication_factor: u8,
     ) -> Result<(), IggyError> {
         let message_expiry = Topic::get_message_expiry(message_expiry, &self.config);
+        let effective_compression_algorithm =
+            Topic::get_compression_algorithm(compression_algorithm, &self.config);
         let max_topic_size = Topic::get_max_topic_size(max_topic_size, &self.config)?;
         let topic_id;
         {
@@ -121,8 +123,10 @@ impl Stream {
             for partition in topic.partitions.values_mut() {
                 let mut partition = partition.write().await;
                 partition.message_expiry = message_expiry;
+                partition.compression_algorithm = effective_compression_algorithm;
                 for segment in partition.segments.iter_mut() {
                     segment.message_expiry = message_expiry;
+                    segment.compression_algorithm = effective_compression_algorithm;
                 }
             }
             topic.max_topic_size = max_topic_size;
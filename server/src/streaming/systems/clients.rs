@@ -20,6 +20,7 @@ impl System {
 
     pub async fn delete_client(&self, client_id: u32) {
         let consumer_groups: Vec<(u32, u32, u32)>;
+        let transaction;
 
         {
             let mut client_manager = self.client_manager.write().await;
@@ -37,6 +38,10 @@ impl System {
                 .iter()
                 .map(|c| (c.stream_id, c.topic_id, c.group_id))
                 .collect();
+            transaction = client
+                .transaction
+                .as_ref()
+                .map(|transaction| (transaction.id, transaction.partitions.clone()));
 
             info!(
                 "Deleted {} client with ID: {} for IP address: {}",
@@ -46,7 +51,7 @@ impl System {
 
         for (stream_id, topic_id, consumer_group_id) in consumer_groups.into_iter() {
             _ = self
-                .leave_consumer_group_by_client(
+                .disconnect_consumer_group_member_by_client(
                     &Identifier::numeric(stream_id).unwrap(),
                     &Identifier::numeric(topic_id).unwrap(),
                     &Identifier::numeric(consumer_group_id).unwrap(),
@@ -54,6 +59,16 @@ impl System {
                 )
                 .await
         }
+
+        if let Some((transaction_id, partitions)) = transaction {
+            info!("Aborting transaction with ID: {transaction_id} of deleted client with ID: {client_id}...");
+            if let Err(error) = self
+                .complete_transaction(Some(client_id), transaction_id, partitions, false)
+                .await
+            {
+                error!("Failed to abort transaction with ID: {transaction_id} of deleted client with ID: {client_id}. Error: {error}");
+            }
+        }
     }
 
     pub async fn get_client(
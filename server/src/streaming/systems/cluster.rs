@@ -0,0 +1,707 @@
+use crate::cluster::node::ClusterNode;
+use crate::configs::cluster::ClusterConfig;
+use crate::state::command::EntryCommand;
+use crate::state::entry::StateEntry;
+use crate::state::file::FileState;
+use crate::streaming::personal_access_tokens::personal_access_token::PersonalAccessToken;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::{SharedSystem, System};
+use crate::streaming::topics::partitions::MAX_PARTITIONS_COUNT;
+use crate::streaming::topics::topic::Topic;
+use crate::streaming::utils::crypto;
+use crate::versioning::SemanticVersion;
+use iggy::error::IggyError;
+use iggy::identifier::Identifier;
+use iggy::locking::IggySharedMutFn;
+use iggy::messages::send_messages::{Partitioning, PartitioningKind};
+use iggy::models::cluster::ClusterMetadata;
+use iggy::utils::crypto::Encryptor;
+use iggy::utils::text;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::time::Instant;
+use tracing::{error, info};
+
+/// The messages appended to the replicated partition led by this node, which can be awaited
+/// to be fetched by all the in-sync replicas, once the lock of the system has been released.
+#[derive(Debug)]
+pub struct ReplicatedMessages {
+    cluster: Arc<ClusterNode>,
+    stream_id: u32,
+    topic_id: u32,
+    partition_id: u32,
+    offset: u64,
+}
+
+impl ReplicatedMessages {
+    pub async fn wait_for_in_sync_replicas(&self) -> Result<(), IggyError> {
+        self.cluster
+            .replicas()
+            .wait_for_in_sync_replicas(
+                self.stream_id,
+                self.topic_id,
+                self.partition_id,
+                self.offset,
+            )
+            .await
+    }
+}
+
+impl SharedSystem {
+    /// Returns true if the commands modifying the replicated state have to be replicated by the cluster,
+    /// instead of being applied to the system by the handlers.
+    pub async fn is_clustered(&self) -> bool {
+        self.read().await.cluster.is_some()
+    }
+
+    /// Validates the command modifying the replicated state and proposes it to the cluster, then waits until
+    /// it's committed and applied to the system, which is never modified by the uncommitted entries.
+    pub async fn replicate(
+        &self,
+        session: &Session,
+        mut command: EntryCommand,
+    ) -> Result<(), IggyError> {
+        let Some(cluster) = self.read().await.cluster.clone() else {
+            return Err(IggyError::ClusterDisabled);
+        };
+
+        let _proposal = cluster.lock_proposals().await;
+        self.read()
+            .await
+            .prepare_replicated_command(session, &mut command)
+            .await?;
+        cluster.propose(session.get_user_id(), command).await
+    }
+}
+
+impl System {
+    /// Replaces the state log with the one replicated by the cluster, must be invoked before the system is initialized.
+    pub fn attach_cluster(
+        &mut self,
+        config: &ClusterConfig,
+    ) -> Result<Arc<ClusterNode>, IggyError> {
+        let version = SemanticVersion::current()?;
+        let persister =
+            Self::resolve_persister(self.config.storage.backend, self.config.state.enforce_fsync);
+        let log = FileState::new(
+            &self.config.get_state_log_path(),
+            &self.config.get_state_snapshot_path(),
+            &version,
+            persister.clone(),
+            self.keyring
+                .clone()
+                .map(|keyring| keyring as Arc<dyn Encryptor>),
+        );
+        let node = Arc::new(ClusterNode::new(
+            config,
+            log,
+            &self.config.get_state_cluster_path(),
+            persister,
+        )?);
+        info!(
+            "Cluster is enabled, node ID: {}, address: {}.",
+            config.node_id, config.address
+        );
+        self.state = node.clone();
+        self.cluster = Some(node.clone());
+        Ok(node)
+    }
+
+    /// Ensures that the command modifying the replicated state can be handled by this node.
+    pub fn ensure_leader(&self) -> Result<(), IggyError> {
+        match &self.cluster {
+            Some(cluster) if !cluster.is_leader() => Err(cluster.not_leader_error()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Validates the command against the current state of the system, so that only the commands which can be applied
+    /// by all the nodes are committed. The server defaults are resolved and the passwords are hashed, just like
+    /// in the commands applied to the state log in the standalone mode.
+    async fn prepare_replicated_command(
+        &self,
+        session: &Session,
+        command: &mut EntryCommand,
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        let user_id = session.get_user_id();
+        match command {
+            EntryCommand::CreateStream(command) => {
+                self.permissioner.create_stream(user_id)?;
+                let name = text::to_lowercase_non_whitespace(&command.name);
+                if self.streams_ids.contains_key(&name) {
+                    return Err(IggyError::StreamNameAlreadyExists(name));
+                }
+
+                if let Some(stream_id) = command.stream_id {
+                    if self.streams.contains_key(&stream_id) {
+                        return Err(IggyError::StreamIdAlreadyExists(stream_id));
+                    }
+                }
+            }
+            EntryCommand::UpdateStream(command) => {
+                let stream = self.get_stream(&command.stream_id)?;
+                self.permissioner.update_stream(user_id, stream.stream_id)?;
+                let name = text::to_lowercase_non_whitespace(&command.name);
+                if self
+                    .streams_ids
+                    .get(&name)
+                    .is_some_and(|stream_id| *stream_id != stream.stream_id)
+                {
+                    return Err(IggyError::StreamNameAlreadyExists(name));
+                }
+            }
+            EntryCommand::DeleteStream(command) => {
+                let stream = self.get_stream(&command.stream_id)?;
+                self.permissioner.delete_stream(user_id, stream.stream_id)?;
+            }
+            EntryCommand::PurgeStream(command) => {
+                let stream = self.get_stream(&command.stream_id)?;
+                self.permissioner.purge_stream(user_id, stream.stream_id)?;
+            }
+            EntryCommand::CreateTopic(command) => {
+                let stream = self.get_stream(&command.stream_id)?;
+                self.permissioner.create_topic(user_id, stream.stream_id)?;
+                let name = text::to_lowercase_non_whitespace(&command.name);
+                if stream.topics_ids.contains_key(&name) {
+                    return Err(IggyError::TopicNameAlreadyExists(name, stream.stream_id));
+                }
+
+                if let Some(topic_id) = command.topic_id {
+                    if stream.topics.contains_key(&topic_id) {
+                        return Err(IggyError::TopicIdAlreadyExists(topic_id, stream.stream_id));
+                    }
+                }
+
+                if command.partitions_count > MAX_PARTITIONS_COUNT {
+                    return Err(IggyError::TooManyPartitions);
+                }
+
+                command.message_expiry =
+                    Topic::get_message_expiry(command.message_expiry, &self.config);
+                command.max_topic_size =
+                    Topic::get_max_topic_size(command.max_topic_size, &self.config)?;
+            }
+            EntryCommand::UpdateTopic(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner
+                    .update_topic(user_id, topic.stream_id, topic.topic_id)?;
+                let name = text::to_lowercase_non_whitespace(&command.name);
+                let stream = self.get_stream(&command.stream_id)?;
+                if stream
+                    .topics_ids
+                    .get(&name)
+                    .is_some_and(|topic_id| *topic_id != topic.topic_id)
+                {
+                    return Err(IggyError::TopicNameAlreadyExists(name, stream.stream_id));
+                }
+
+                command.message_expiry =
+                    Topic::get_message_expiry(command.message_expiry, &self.config);
+                command.max_topic_size =
+                    Topic::get_max_topic_size(command.max_topic_size, &self.config)?;
+            }
+            EntryCommand::DeleteTopic(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner
+                    .delete_topic(user_id, topic.stream_id, topic.topic_id)?;
+            }
+            EntryCommand::PurgeTopic(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner
+                    .purge_topic(user_id, topic.stream_id, topic.topic_id)?;
+            }
+            EntryCommand::SetDeadLetterQueue(command) => {
+                command.dead_letter_queue = self.validate_dead_letter_queue(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    command.dead_letter_queue.take(),
+                )?;
+            }
+            EntryCommand::SetCleanupPolicy(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner
+                    .set_cleanup_policy(user_id, topic.stream_id, topic.topic_id)?;
+            }
+            EntryCommand::SetMaxTopicMessages(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner.set_max_topic_messages(
+                    user_id,
+                    topic.stream_id,
+                    topic.topic_id,
+                )?;
+            }
+            EntryCommand::CreatePartitions(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner
+                    .create_partitions(user_id, topic.stream_id, topic.topic_id)?;
+                if topic.get_partitions_count() + command.partitions_count > MAX_PARTITIONS_COUNT {
+                    return Err(IggyError::TooManyPartitions);
+                }
+            }
+            EntryCommand::DeletePartitions(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner
+                    .delete_partitions(user_id, topic.stream_id, topic.topic_id)?;
+            }
+            EntryCommand::CreateConsumerGroup(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner.create_consumer_group(
+                    user_id,
+                    topic.stream_id,
+                    topic.topic_id,
+                )?;
+                let name = text::to_lowercase_non_whitespace(&command.name);
+                if topic.consumer_groups_ids.contains_key(&name) {
+                    return Err(IggyError::ConsumerGroupNameAlreadyExists(
+                        name,
+                        topic.topic_id,
+                    ));
+                }
+
+                if let Some(group_id) = command.group_id {
+                    if topic.consumer_groups.contains_key(&group_id) {
+                        return Err(IggyError::ConsumerGroupIdAlreadyExists(
+                            group_id,
+                            topic.topic_id,
+                        ));
+                    }
+                }
+            }
+            EntryCommand::DeleteConsumerGroup(command) => {
+                let topic = self.find_topic(session, &command.stream_id, &command.topic_id)?;
+                self.permissioner.delete_consumer_group(
+                    user_id,
+                    topic.stream_id,
+                    topic.topic_id,
+                )?;
+                topic.get_consumer_group(&command.group_id)?;
+            }
+            EntryCommand::CreateUser(command) => {
+                self.permissioner.create_user(user_id)?;
+                let username = text::to_lowercase_non_whitespace(&command.username);
+                if self.users.values().any(|user| user.username == username) {
+                    error!("User: {username} already exists.");
+                    return Err(IggyError::UserAlreadyExists);
+                }
+
+                command.password = crypto::hash_password(&command.password);
+            }
+            EntryCommand::UpdateUser(command) => {
+                self.permissioner.update_user(user_id)?;
+                let user = self.get_user(&command.user_id)?;
+                if let Some(username) = &command.username {
+                    let username = text::to_lowercase_non_whitespace(username);
+                    if self.users.values().any(|existing_user| {
+                        existing_user.username == username && existing_user.id != user.id
+                    }) {
+                        error!("User: {username} already exists.");
+                        return Err(IggyError::UserAlreadyExists);
+                    }
+                }
+            }
+            EntryCommand::DeleteUser(command) => {
+                self.permissioner.delete_user(user_id)?;
+                let user = self.get_user(&command.user_id)?;
+                if user.is_root() {
+                    error!("Cannot delete the root user.");
+                    return Err(IggyError::CannotDeleteUser(user.id));
+                }
+            }
+            EntryCommand::ChangePassword(command) => {
+                let user = self.get_user(&command.user_id)?;
+                if user.id != user_id {
+                    self.permissioner.change_password(user_id)?;
+                }
+
+                if !crypto::verify_password(&command.current_password, &user.password) {
+                    error!(
+                        "Invalid current password for user: {} with ID: {}.",
+                        user.username, user.id
+                    );
+                    return Err(IggyError::InvalidCredentials);
+                }
+
+                command.current_password = "".into();
+                command.new_password = crypto::hash_password(&command.new_password);
+            }
+            EntryCommand::UpdatePermissions(command) => {
+                self.permissioner.update_permissions(user_id)?;
+                let user = self.get_user(&command.user_id)?;
+                if user.is_root() {
+                    error!("Cannot change the root user permissions.");
+                    return Err(IggyError::CannotChangePermissions(user.id));
+                }
+            }
+            EntryCommand::CreatePersonalAccessToken(command) => {
+                let user = self.get_user(&user_id.try_into()?)?;
+                let max_tokens_per_user = self.personal_access_token.max_tokens_per_user;
+                if user.personal_access_tokens.len() as u32 >= max_tokens_per_user {
+                    return Err(IggyError::PersonalAccessTokensLimitReached(
+                        user_id,
+                        max_tokens_per_user,
+                    ));
+                }
+
+                let name = text::to_lowercase_non_whitespace(&command.command.name);
+                if user
+                    .personal_access_tokens
+                    .values()
+                    .any(|pat| pat.name == name)
+                {
+                    return Err(IggyError::PersonalAccessTokenAlreadyExists(name, user_id));
+                }
+            }
+            EntryCommand::DeletePersonalAccessToken(command) => {
+                let user = self.get_user(&user_id.try_into()?)?;
+                let name = text::to_lowercase_non_whitespace(&command.name);
+                if !user
+                    .personal_access_tokens
+                    .values()
+                    .any(|pat| pat.name == name)
+                {
+                    return Err(IggyError::ResourceNotFound(name));
+                }
+            }
+            EntryCommand::AddClusterNode(command) => {
+                self.add_cluster_node(session, command.node_id, &command.address)
+                    .await?;
+            }
+            EntryCommand::RemoveClusterNode(command) => {
+                self.remove_cluster_node(session, command.node_id).await?;
+            }
+            // The partitions replicas are proposed by the cluster node itself.
+            EntryCommand::UpdatePartitionReplicas(_) => {}
+        }
+        Ok(())
+    }
+
+    /// In the cluster mode, the messages can be appended only to the partitions led by this node,
+    /// thus the balanced partitioning picks the next partition led by this node.
+    /// Returns the partitioning by the ID of the resolved partition.
+    pub(crate) async fn resolve_partitioning(
+        &self,
+        topic: &Topic,
+        partitioning: Partitioning,
+    ) -> Result<Partitioning, IggyError> {
+        let Some(cluster) = &self.cluster else {
+            return Ok(partitioning);
+        };
+
+        let attempts = match partitioning.kind {
+            PartitioningKind::Balanced => topic.get_partitions_count().max(1),
+            _ => 1,
+        };
+        let replicas = cluster.replicas();
+        let deadline = Instant::now() + replicas.get_acks_timeout();
+        let mut error = IggyError::PartitionLeaderNotAvailable(0, topic.topic_id, topic.stream_id);
+        for _ in 0..attempts {
+            let partition_id = topic.get_partition_id(&partitioning)?;
+            let partition = topic.get_partition(partition_id)?;
+            let Some(assignment) = replicas
+                .wait_for_assignment(topic.stream_id, topic.topic_id, partition_id, deadline)
+                .await
+            else {
+                error = IggyError::PartitionLeaderNotAvailable(
+                    partition_id,
+                    topic.topic_id,
+                    topic.stream_id,
+                );
+                continue;
+            };
+
+            if assignment.leader_id != cluster.id() {
+                error = IggyError::NotPartitionLeader(
+                    partition_id,
+                    topic.topic_id,
+                    topic.stream_id,
+                    assignment.leader_id,
+                );
+                continue;
+            }
+
+            replicas.begin_leader_epoch(&assignment, &*partition.read().await);
+            return Ok(Partitioning::partition_id(partition_id));
+        }
+
+        error!(
+            "Cannot append the messages to topic with ID: {} for stream with ID: {}. {error}",
+            topic.topic_id, topic.stream_id
+        );
+        Err(error)
+    }
+
+    /// Returns the messages appended to the partition resolved for the cluster, if it's enabled.
+    pub(crate) async fn get_replicated_messages(
+        &self,
+        topic: &Topic,
+        partitioning: &Partitioning,
+    ) -> Result<Option<ReplicatedMessages>, IggyError> {
+        let Some(cluster) = &self.cluster else {
+            return Ok(None);
+        };
+
+        let partition_id = topic.get_partition_id(partitioning)?;
+        let offset = topic
+            .get_partition(partition_id)?
+            .read()
+            .await
+            .current_offset;
+        Ok(Some(ReplicatedMessages {
+            cluster: cluster.clone(),
+            stream_id: topic.stream_id,
+            topic_id: topic.topic_id,
+            partition_id,
+            offset,
+        }))
+    }
+
+    pub async fn get_cluster_metadata(
+        &self,
+        session: &Session,
+    ) -> Result<ClusterMetadata, IggyError> {
+        self.ensure_authenticated(session)?;
+        self.permissioner
+            .get_cluster_metadata(session.get_user_id())?;
+        let Some(cluster) = &self.cluster else {
+            return Err(IggyError::ClusterDisabled);
+        };
+
+        Ok(cluster.metadata().await)
+    }
+
+    pub async fn add_cluster_node(
+        &self,
+        session: &Session,
+        node_id: u32,
+        address: &str,
+    ) -> Result<(), IggyError> {
+        let metadata = self.get_cluster_metadata_to_manage(session).await?;
+        if metadata.nodes.iter().any(|node| node.id == node_id) {
+            error!("Cluster node with ID: {node_id} already exists.");
+            return Err(IggyError::ClusterNodeAlreadyExists(node_id));
+        }
+
+        if address.parse::<SocketAddr>().is_err() {
+            error!("Invalid cluster node address: {address}.");
+            return Err(IggyError::InvalidClusterNodeAddress(address.to_string()));
+        }
+
+        info!("Adding cluster node with ID: {node_id}, address: {address}...");
+        Ok(())
+    }
+
+    pub async fn remove_cluster_node(
+        &self,
+        session: &Session,
+        node_id: u32,
+    ) -> Result<(), IggyError> {
+        let metadata = self.get_cluster_metadata_to_manage(session).await?;
+        if !metadata.nodes.iter().any(|node| node.id == node_id) {
+            error!("Cluster node with ID: {node_id} does not exist.");
+            return Err(IggyError::ClusterNodeNotFound(node_id));
+        }
+
+        if metadata.nodes.len() == 1 {
+            error!("Cannot remove the last cluster node with ID: {node_id}.");
+            return Err(IggyError::CannotRemoveLastClusterNode(node_id));
+        }
+
+        info!("Removing cluster node with ID: {node_id}...");
+        Ok(())
+    }
+
+    async fn get_cluster_metadata_to_manage(
+        &self,
+        session: &Session,
+    ) -> Result<ClusterMetadata, IggyError> {
+        self.ensure_authenticated(session)?;
+        self.permissioner.manage_cluster(session.get_user_id())?;
+        let Some(cluster) = &self.cluster else {
+            return Err(IggyError::ClusterDisabled);
+        };
+
+        Ok(cluster.metadata().await)
+    }
+
+    /// Applies the committed entry replicated from the cluster leader, on behalf of the user who issued the command.
+    /// The passwords and tokens are replicated as hashes, thus they're stored as they are.
+    pub(crate) async fn apply_replicated_entry(
+        &mut self,
+        entry: &StateEntry,
+    ) -> Result<(), IggyError> {
+        let session = Session::stateless(
+            entry.user_id,
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0),
+        );
+        let session = &session;
+        match entry.command()? {
+            EntryCommand::CreateStream(command) => {
+                self.create_stream(session, command.stream_id, &command.name)
+                    .await?;
+            }
+            EntryCommand::UpdateStream(command) => {
+                self.update_stream(session, &command.stream_id, &command.name)
+                    .await?;
+            }
+            EntryCommand::DeleteStream(command) => {
+                self.delete_stream(session, &command.stream_id).await?;
+            }
+            EntryCommand::PurgeStream(command) => {
+                self.purge_stream(session, &command.stream_id).await?;
+            }
+            EntryCommand::CreateTopic(command) => {
+                self.create_topic(
+                    session,
+                    &command.stream_id,
+                    command.topic_id,
+                    &command.name,
+                    command.partitions_count,
+                    command.message_expiry,
+                    command.compression_algorithm,
+                    command.max_topic_size,
+                    command.replication_factor,
+                )
+                .await?;
+            }
+            EntryCommand::UpdateTopic(command) => {
+                self.update_topic(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    &command.name,
+                    command.message_expiry,
+                    command.compression_algorithm,
+                    command.max_topic_size,
+                    command.replication_factor,
+                )
+                .await?;
+            }
+            EntryCommand::DeleteTopic(command) => {
+                self.delete_topic(session, &command.stream_id, &command.topic_id)
+                    .await?;
+            }
+            EntryCommand::PurgeTopic(command) => {
+                self.purge_topic(session, &command.stream_id, &command.topic_id)
+                    .await?;
+            }
+            EntryCommand::SetDeadLetterQueue(command) => {
+                self.set_dead_letter_queue(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    command.dead_letter_queue,
+                )
+                .await?;
+            }
+            EntryCommand::SetCleanupPolicy(command) => {
+                self.set_cleanup_policy(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    command.cleanup_policy,
+                )
+                .await?;
+            }
+            EntryCommand::SetMaxTopicMessages(command) => {
+                self.set_max_topic_messages(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    command.max_messages,
+                )
+                .await?;
+            }
+            EntryCommand::CreatePartitions(command) => {
+                self.create_partitions(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    command.partitions_count,
+                )
+                .await?;
+            }
+            EntryCommand::DeletePartitions(command) => {
+                self.delete_partitions(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    command.partitions_count,
+                )
+                .await?;
+            }
+            EntryCommand::CreateConsumerGroup(command) => {
+                self.create_consumer_group(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    command.group_id,
+                    &command.name,
+                    command.mode,
+                )
+                .await?;
+            }
+            EntryCommand::DeleteConsumerGroup(command) => {
+                self.delete_consumer_group(
+                    session,
+                    &command.stream_id,
+                    &command.topic_id,
+                    &command.group_id,
+                )
+                .await?;
+            }
+            EntryCommand::CreateUser(command) if entry.user_id == 0 => {
+                self.create_initial_user(command)?;
+            }
+            EntryCommand::CreateUser(command) => {
+                let user_id = self
+                    .create_user(
+                        session,
+                        &command.username,
+                        &command.password,
+                        command.status,
+                        command.permissions,
+                    )
+                    .await?
+                    .id;
+                self.get_user_mut(&Identifier::numeric(user_id)?)?.password = command.password;
+            }
+            EntryCommand::UpdateUser(command) => {
+                self.update_user(session, &command.user_id, command.username, command.status)
+                    .await?;
+            }
+            EntryCommand::DeleteUser(command) => {
+                self.delete_user(session, &command.user_id).await?;
+            }
+            EntryCommand::ChangePassword(command) => {
+                self.get_user_mut(&command.user_id)?.password = command.new_password;
+            }
+            EntryCommand::UpdatePermissions(command) => {
+                self.update_permissions(session, &command.user_id, command.permissions)
+                    .await?;
+            }
+            EntryCommand::CreatePersonalAccessToken(command) => {
+                let name = text::to_lowercase_non_whitespace(&command.command.name);
+                let expiry_at = PersonalAccessToken::calculate_expiry_at(
+                    entry.timestamp,
+                    command.command.expiry,
+                );
+                let user = self.get_user_mut(&Identifier::numeric(entry.user_id)?)?;
+                user.personal_access_tokens.insert(
+                    command.hash.clone(),
+                    PersonalAccessToken::raw(entry.user_id, &name, &command.hash, expiry_at),
+                );
+            }
+            EntryCommand::DeletePersonalAccessToken(command) => {
+                self.delete_personal_access_token(session, &command.name)
+                    .await?;
+            }
+            // The membership and the partitions replicas are maintained by the cluster node itself.
+            EntryCommand::AddClusterNode(_)
+            | EntryCommand::RemoveClusterNode(_)
+            | EntryCommand::UpdatePartitionReplicas(_) => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::configs::reloader::{ConfigReloader, ConfigSubscription};
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::System;
+use iggy::error::IggyError;
+use std::sync::Arc;
+use tracing::error;
+
+impl System {
+    pub fn attach_config_reloader(&mut self, config_reloader: Arc<ConfigReloader>) {
+        self.config_reloader = Some(config_reloader);
+    }
+
+    /// Subscribes to the changes of the reloadable configuration, which are never received if the reloader is not attached.
+    pub fn subscribe_to_config_changes(&self) -> ConfigSubscription {
+        match &self.config_reloader {
+            Some(config_reloader) => config_reloader.subscribe(),
+            None => ConfigSubscription::new(None),
+        }
+    }
+
+    pub async fn reload_config(&self, session: &Session) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        self.permissioner.reload_config(session.get_user_id())?;
+        let Some(config_reloader) = &self.config_reloader else {
+            return Err(IggyError::FeatureUnavailable);
+        };
+
+        config_reloader.reload().await.map_err(|error| {
+            error!("Failed to reload the server configuration requested by user with ID: {}. Error: {error}", session.get_user_id());
+            IggyError::InvalidConfiguration
+        })?;
+        Ok(())
+    }
+}
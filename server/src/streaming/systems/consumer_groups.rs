@@ -1,9 +1,12 @@
 use crate::streaming::session::Session;
 use crate::streaming::systems::system::System;
 use crate::streaming::topics::consumer_group::ConsumerGroup;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::error::IggyError;
 use iggy::identifier::Identifier;
 use iggy::locking::IggySharedMutFn;
+use iggy::models::consumer_group::ConsumerGroupMembership;
+use iggy::utils::timestamp::IggyTimestamp;
 use tokio::sync::RwLock;
 
 impl System {
@@ -49,6 +52,7 @@ impl System {
         topic_id: &Identifier,
         group_id: Option<u32>,
         name: &str,
+        mode: ConsumerGroupMode,
     ) -> Result<&RwLock<ConsumerGroup>, IggyError> {
         self.ensure_authenticated(session)?;
         {
@@ -61,7 +65,7 @@ impl System {
         }
 
         let topic = self.get_stream_mut(stream_id)?.get_topic_mut(topic_id)?;
-        topic.create_consumer_group(group_id, name).await
+        topic.create_consumer_group(group_id, name, mode).await
     }
 
     pub async fn delete_consumer_group(
@@ -115,7 +119,8 @@ impl System {
         stream_id: &Identifier,
         topic_id: &Identifier,
         consumer_group_id: &Identifier,
-    ) -> Result<(), IggyError> {
+        member_name: Option<&str>,
+    ) -> Result<ConsumerGroupMembership, IggyError> {
         self.ensure_authenticated(session)?;
         let stream_id_value;
         let topic_id_value;
@@ -131,6 +136,7 @@ impl System {
         }
 
         let group_id;
+        let membership;
         {
             let topic = self.find_topic(session, stream_id, topic_id)?;
 
@@ -140,8 +146,8 @@ impl System {
                 group_id = consumer_group.group_id;
             }
 
-            topic
-                .join_consumer_group(consumer_group_id, session.client_id)
+            membership = topic
+                .join_consumer_group(consumer_group_id, session.client_id, member_name)
                 .await?;
         }
 
@@ -149,7 +155,7 @@ impl System {
         client_manager
             .join_consumer_group(session.client_id, stream_id_value, topic_id_value, group_id)
             .await?;
-        Ok(())
+        Ok(membership)
     }
 
     pub async fn leave_consumer_group(
@@ -210,4 +216,65 @@ impl System {
             .leave_consumer_group(client_id, stream_id_value, topic_id_value, group_id)
             .await
     }
+
+    pub async fn disconnect_consumer_group_member_by_client(
+        &self,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        consumer_group_id: &Identifier,
+        client_id: u32,
+    ) -> Result<(), IggyError> {
+        let stream = self.get_stream(stream_id)?;
+        let topic = stream.get_topic(topic_id)?;
+        topic
+            .disconnect_consumer_group_member(consumer_group_id, client_id)
+            .await
+    }
+
+    pub async fn heartbeat_consumer_group(
+        &self,
+        session: &Session,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        consumer_group_id: &Identifier,
+    ) -> Result<ConsumerGroupMembership, IggyError> {
+        self.ensure_authenticated(session)?;
+        let topic = self.find_topic(session, stream_id, topic_id)?;
+        self.permissioner.heartbeat_consumer_group(
+            session.get_user_id(),
+            topic.stream_id,
+            topic.topic_id,
+        )?;
+        topic
+            .heartbeat_consumer_group(consumer_group_id, session.client_id)
+            .await
+    }
+
+    pub async fn evict_stale_consumer_group_members(
+        &self,
+        heartbeat_to: IggyTimestamp,
+    ) -> Result<u32, IggyError> {
+        let mut evicted_members_count = 0;
+        for stream in self.streams.values() {
+            for topic in stream.topics.values() {
+                let evicted_members = topic
+                    .evict_stale_consumer_group_members(heartbeat_to)
+                    .await?;
+                if evicted_members.is_empty() {
+                    continue;
+                }
+
+                let client_manager = self.client_manager.read().await;
+                for (group_id, member_id) in evicted_members {
+                    evicted_members_count += 1;
+                    // The client might have already disconnected, thus there's nothing more to clean up.
+                    let _ = client_manager
+                        .leave_consumer_group(member_id, stream.stream_id, topic.topic_id, group_id)
+                        .await;
+                }
+            }
+        }
+
+        Ok(evicted_members_count)
+    }
 }
@@ -0,0 +1,87 @@
+use crate::streaming::persistence::memory::MemoryPersister;
+use crate::streaming::persistence::persister::Persister;
+use crate::streaming::storage::{SystemInfoStorage, TransactionLogStorage};
+use crate::streaming::systems::info::SystemInfo;
+use crate::streaming::systems::storage::{decode_transaction_log, encode_transaction_log};
+use crate::streaming::systems::transactions::TransactionLogEntry;
+use anyhow::Context;
+use async_trait::async_trait;
+use iggy::error::IggyError;
+use std::sync::Arc;
+use tracing::info;
+
+/// The system info storage for the in-memory backend, keeping the info in the same format as on disk.
+#[derive(Debug)]
+pub struct MemorySystemInfoStorage {
+    files: Arc<MemoryPersister>,
+    path: String,
+}
+
+impl MemorySystemInfoStorage {
+    pub fn new(path: String, files: Arc<MemoryPersister>) -> Self {
+        Self { path, files }
+    }
+}
+
+#[async_trait]
+impl SystemInfoStorage for MemorySystemInfoStorage {
+    async fn load(&self) -> Result<SystemInfo, IggyError> {
+        let Some(system_info) = self.files.read(&self.path, |bytes| {
+            bincode::deserialize(bytes)
+                .with_context(|| "Failed to deserialize system info")
+                .map_err(IggyError::CannotDeserializeResource)
+        }) else {
+            return Err(IggyError::ResourceNotFound(self.path.to_owned()));
+        };
+        system_info
+    }
+
+    async fn save(&self, system_info: &SystemInfo) -> Result<(), IggyError> {
+        let data = bincode::serialize(&system_info)
+            .with_context(|| "Failed to serialize system info")
+            .map_err(IggyError::CannotSerializeResource)?;
+        self.files.overwrite(&self.path, &data).await?;
+        info!("Saved system info in memory, {}", system_info);
+        Ok(())
+    }
+}
+
+/// The transaction log storage for the in-memory backend, keeping the log in the same format as on disk.
+#[derive(Debug)]
+pub struct MemoryTransactionLogStorage {
+    files: Arc<MemoryPersister>,
+    path: String,
+}
+
+impl MemoryTransactionLogStorage {
+    pub fn new(path: String, files: Arc<MemoryPersister>) -> Self {
+        Self { path, files }
+    }
+}
+
+#[async_trait]
+impl TransactionLogStorage for MemoryTransactionLogStorage {
+    async fn load(&self) -> Result<Vec<TransactionLogEntry>, IggyError> {
+        Ok(self
+            .files
+            .read(&self.path, |bytes| {
+                decode_transaction_log(&self.path, bytes)
+            })
+            .unwrap_or_default())
+    }
+
+    async fn save(&self, entries: &[TransactionLogEntry]) -> Result<(), IggyError> {
+        self.files
+            .overwrite(&self.path, &encode_transaction_log(entries))
+            .await
+    }
+
+    async fn append(&self, entry: &TransactionLogEntry) -> Result<(), IggyError> {
+        self.files
+            .append(
+                &self.path,
+                &encode_transaction_log(std::slice::from_ref(entry)),
+            )
+            .await
+    }
+}
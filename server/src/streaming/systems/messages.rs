@@ -1,14 +1,30 @@
 use crate::streaming::cache::memory_tracker::CacheMemoryTracker;
+use crate::streaming::models::messages::{PolledBatches, RetainedMessage};
+use crate::streaming::polling_consumer::PollingConsumer;
 use crate::streaming::session::Session;
+use crate::streaming::systems::cluster::ReplicatedMessages;
 use crate::streaming::systems::system::System;
+use crate::streaming::topics::topic::Topic;
 use bytes::Bytes;
+use iggy::bytes_serializable::BytesSerializable;
 use iggy::consumer::Consumer;
-use iggy::messages::poll_messages::PollingStrategy;
-use iggy::messages::send_messages::Message;
+use iggy::locking::IggySharedMutFn;
+use iggy::messages::message_filter::MessageFilter;
+use iggy::messages::poll_messages::{IsolationLevel, PollingStrategy};
 use iggy::messages::send_messages::Partitioning;
+use iggy::messages::send_messages::{CompressedMessages, Message};
+use iggy::models::dead_letter_queue::{
+    DELIVERY_ATTEMPTS_HEADER, ORIGIN_OFFSET_HEADER, ORIGIN_PARTITION_ID_HEADER,
+    ORIGIN_STREAM_ID_HEADER, ORIGIN_TOPIC_ID_HEADER, REASON_HEADER,
+};
+use iggy::models::header::{HeaderKey, HeaderValue};
 use iggy::models::messages::{PolledMessage, PolledMessages};
+use iggy::models::transaction::TRANSACTION_ID_HEADER;
+use iggy::utils::crypto::Encryptor;
 use iggy::{error::IggyError, identifier::Identifier};
-use tracing::{error, trace};
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::{error, info, trace};
 
 impl System {
     pub async fn poll_messages(
@@ -20,6 +36,66 @@ impl System {
         partition_id: Option<u32>,
         args: PollingArgs,
     ) -> Result<PolledMessages, IggyError> {
+        let (topic, polling_consumer, partition_id) = self
+            .resolve_polling_partition(session, consumer, stream_id, topic_id, partition_id, &args)
+            .await?;
+        self.poll_partition_messages(topic, polling_consumer, partition_id, args)
+            .await
+    }
+
+    /// Polls the messages as the batches stored in the segment log files, so that they can be sent without loading them into memory.
+    /// The messages are polled as usual when they need to be processed first, e.g. filtered, decrypted or leased,
+    /// or when they're not fully persisted or cached.
+    pub async fn poll_batches(
+        &self,
+        session: &Session,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        args: PollingArgs,
+    ) -> Result<PollingResult, IggyError> {
+        let (topic, polling_consumer, partition_id) = self
+            .resolve_polling_partition(session, consumer, stream_id, topic_id, partition_id, &args)
+            .await?;
+        let can_poll_batches = self.keyring.is_none()
+            && args.filter.is_none()
+            && args.isolation == IsolationLevel::ReadUncommitted
+            && topic
+                .get_queue_visibility_timeout(polling_consumer)
+                .await?
+                .is_none();
+        if can_poll_batches {
+            if let Some(polled_batches) = topic
+                .get_batches(polling_consumer, partition_id, args.strategy, args.count)
+                .await?
+            {
+                if args.auto_commit {
+                    let offset = polled_batches.end_offset;
+                    trace!("Last offset: {} will be automatically stored for {}, stream: {}, topic: {}, partition: {}", offset, consumer, stream_id, topic_id, partition_id);
+                    topic
+                        .store_consumer_offset_internal(polling_consumer, offset, partition_id)
+                        .await?;
+                }
+                return Ok(PollingResult::Batches(polled_batches));
+            }
+        }
+
+        let polled_messages = self
+            .poll_partition_messages(topic, polling_consumer, partition_id, args)
+            .await?;
+        Ok(PollingResult::Messages(polled_messages))
+    }
+
+    async fn resolve_polling_partition(
+        &self,
+        session: &Session,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        args: &PollingArgs,
+    ) -> Result<(&Topic, PollingConsumer, u32), IggyError> {
         self.ensure_authenticated(session)?;
         if args.count == 0 {
             return Err(IggyError::InvalidMessagesCount);
@@ -36,31 +112,93 @@ impl System {
         let (polling_consumer, partition_id) = topic
             .resolve_consumer_with_partition_id(consumer, session.client_id, partition_id, true)
             .await?;
+        Ok((topic, polling_consumer, partition_id))
+    }
 
-        let mut polled_messages = topic
-            .get_messages(polling_consumer, partition_id, args.strategy, args.count)
-            .await?;
+    async fn poll_partition_messages(
+        &self,
+        topic: &Topic,
+        polling_consumer: PollingConsumer,
+        partition_id: u32,
+        args: PollingArgs,
+    ) -> Result<PolledMessages, IggyError> {
+        // In queue mode, the messages are leased to the member and acknowledged one by one,
+        // thus the polling strategy is ignored and the offset is never committed on poll.
+        if let Some(visibility_timeout) =
+            topic.get_queue_visibility_timeout(polling_consumer).await?
+        {
+            if args.filter.is_some() {
+                return Err(IggyError::InvalidMessageFilter(
+                    "filter is not supported by consumer group in queue mode".to_string(),
+                ));
+            }
 
-        if polled_messages.messages.is_empty() {
-            return Ok(polled_messages);
+            let polled_messages = topic
+                .lease_messages(
+                    polling_consumer,
+                    partition_id,
+                    args.count,
+                    visibility_timeout,
+                    args.isolation,
+                )
+                .await?;
+            return self.decrypt_messages(polled_messages);
         }
 
-        let offset = polled_messages.messages.last().unwrap().offset;
+        let (polled_messages, last_offset) = match &args.filter {
+            Some(filter) => {
+                topic
+                    .get_filtered_messages(
+                        polling_consumer,
+                        partition_id,
+                        args.strategy,
+                        args.count,
+                        filter,
+                        args.isolation,
+                    )
+                    .await?
+            }
+            None => {
+                topic
+                    .get_messages(
+                        polling_consumer,
+                        partition_id,
+                        args.strategy,
+                        args.count,
+                        args.isolation,
+                    )
+                    .await?
+            }
+        };
+
+        // The offset of the last scanned message is committed, so that the messages which didn't match the filter
+        // or are not visible with the given isolation level (e.g. the transaction markers) are not scanned again.
+        let Some(offset) = last_offset else {
+            return Ok(polled_messages);
+        };
+
         if args.auto_commit {
-            trace!("Last offset: {} will be automatically stored for {}, stream: {}, topic: {}, partition: {}", offset, consumer, stream_id, topic_id, partition_id);
+            trace!("Last offset: {} will be automatically stored for {}, stream: {}, topic: {}, partition: {}", offset, polling_consumer, topic.stream_id, topic.topic_id, partition_id);
             topic
                 .store_consumer_offset_internal(polling_consumer, offset, partition_id)
                 .await?;
         }
 
-        if self.encryptor.is_none() {
+        self.decrypt_messages(polled_messages)
+    }
+
+    fn decrypt_messages(
+        &self,
+        mut polled_messages: PolledMessages,
+    ) -> Result<PolledMessages, IggyError> {
+        if self.keyring.is_none() || polled_messages.messages.is_empty() {
             return Ok(polled_messages);
         }
 
-        let encryptor = self.encryptor.as_ref().unwrap();
+        let keyring = self.keyring.as_ref().unwrap();
         let mut decrypted_messages = Vec::with_capacity(polled_messages.messages.len());
         for message in polled_messages.messages.iter() {
-            let payload = encryptor.decrypt(&message.payload);
+            let payload = keyring.decrypt(&message.payload);
             match payload {
                 Ok(payload) => {
                     decrypted_messages.push(PolledMessage {
@@ -91,7 +229,7 @@ impl System {
         topic_id: Identifier,
         partitioning: Partitioning,
         messages: Vec<Message>,
-    ) -> Result<(), IggyError> {
+    ) -> Result<Option<ReplicatedMessages>, IggyError> {
         self.ensure_authenticated(session)?;
         let topic = self.find_topic(session, &stream_id, &topic_id)?;
         self.permissioner.append_messages(
@@ -99,12 +237,26 @@ impl System {
             topic.stream_id,
             topic.topic_id,
         )?;
+        let partitioning = self.resolve_partitioning(topic, partitioning).await?;
 
         let mut batch_size_bytes = 0;
         let mut messages = messages;
-        if let Some(encryptor) = &self.encryptor {
+        let transaction_id = session.get_transaction_id();
+        if let Some(transaction_id) = transaction_id {
+            let header_key = HeaderKey::new(TRANSACTION_ID_HEADER)?;
+            let header_value = HeaderValue::from_uint64(transaction_id)?;
+            for message in messages.iter_mut() {
+                message
+                    .headers
+                    .get_or_insert_with(HashMap::new)
+                    .insert(header_key.clone(), header_value.clone());
+            }
+        }
+
+        if let Some(keyring) = &self.keyring {
+            let key_id = keyring.get_key_id(topic.stream_id, topic.topic_id);
             for message in messages.iter_mut() {
-                let payload = encryptor.encrypt(&message.payload);
+                let payload = keyring.encrypt_with(key_id, &message.payload);
                 match payload {
                     Ok(payload) => {
                         message.payload = Bytes::from(payload);
@@ -127,11 +279,78 @@ impl System {
             }
         }
         let messages_count = messages.len() as u64;
+        let Some(transaction_id) = transaction_id else {
+            topic
+                .append_messages(batch_size_bytes, partitioning.clone(), messages)
+                .await?;
+            self.metrics.increment_messages(messages_count);
+            return self.get_replicated_messages(topic, &partitioning).await;
+        };
+
+        let partition_id = topic
+            .append_transactional_messages(
+                batch_size_bytes,
+                partitioning.clone(),
+                messages,
+                transaction_id,
+            )
+            .await?;
+        self.metrics.increment_messages(messages_count);
+        // The transaction might have been aborted in the meantime (e.g. once expired),
+        // thus it has to be completed in the partition, so that it doesn't stay open forever.
+        if let Err(error) = self
+            .client_manager
+            .read()
+            .await
+            .add_transaction_partition(
+                session.client_id,
+                transaction_id,
+                topic.stream_id,
+                topic.topic_id,
+                partition_id,
+            )
+            .await
+        {
+            topic
+                .complete_transaction(partition_id, transaction_id, false)
+                .await?;
+            return Err(error);
+        }
+        self.get_replicated_messages(topic, &partitioning).await
+    }
+
+    pub async fn append_compressed_messages(
+        &self,
+        session: &Session,
+        stream_id: Identifier,
+        topic_id: Identifier,
+        partitioning: Partitioning,
+        compressed_messages: CompressedMessages,
+    ) -> Result<Option<ReplicatedMessages>, IggyError> {
+        self.ensure_authenticated(session)?;
+        let topic = self.find_topic(session, &stream_id, &topic_id)?;
+        self.permissioner.append_messages(
+            session.get_user_id(),
+            topic.stream_id,
+            topic.topic_id,
+        )?;
+
+        if self.keyring.is_some() || session.get_transaction_id().is_some() {
+            // The payloads are encrypted one by one and the messages sent within the transaction
+            // have the transaction header added, so the batch cannot be stored as-is.
+            let messages = compressed_messages.decompress()?;
+            return self
+                .append_messages(session, stream_id, topic_id, partitioning, messages)
+                .await;
+        }
+
+        let partitioning = self.resolve_partitioning(topic, partitioning).await?;
+        let messages_count = compressed_messages.messages_count as u64;
         topic
-            .append_messages(batch_size_bytes, partitioning, messages)
+            .append_compressed_messages(partitioning.clone(), compressed_messages)
             .await?;
         self.metrics.increment_messages(messages_count);
-        Ok(())
+        self.get_replicated_messages(topic, &partitioning).await
     }
 
     pub async fn flush_unsaved_buffer(
@@ -153,6 +372,178 @@ impl System {
         topic.flush_unsaved_buffer(partition_id, fsync).await?;
         Ok(())
     }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn nack_message(
+        &self,
+        session: &Session,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+        reason: &str,
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        let topic = self.find_topic(session, stream_id, topic_id)?;
+        self.permissioner
+            .nack_message(session.get_user_id(), topic.stream_id, topic.topic_id)?;
+
+        let (polling_consumer, partition_id) = topic
+            .resolve_consumer_with_partition_id(consumer, session.client_id, partition_id, false)
+            .await?;
+        // In queue mode, the lease of the nacked message is released, so that it's redelivered immediately.
+        let is_queue = topic
+            .get_queue_visibility_timeout(polling_consumer)
+            .await?
+            .is_some();
+        let Some(dead_letter_queue) = &topic.dead_letter_queue else {
+            if is_queue {
+                return topic
+                    .release_message_lease(polling_consumer, offset, partition_id)
+                    .await;
+            }
+
+            return Err(IggyError::DeadLetterQueueNotConfigured(
+                topic.topic_id,
+                topic.stream_id,
+            ));
+        };
+
+        let dead_letter_queue_topic = self
+            .get_stream(&dead_letter_queue.stream_id)?
+            .get_topic(&dead_letter_queue.topic_id)?;
+        // The invalid reason must not count as the failed delivery.
+        let reason = HeaderValue::from_str(reason)?;
+        let Some((message, delivery_attempts)) = topic
+            .register_failed_delivery(
+                partition_id,
+                offset,
+                dead_letter_queue.max_delivery_attempts,
+            )
+            .await?
+        else {
+            if is_queue {
+                return topic
+                    .release_message_lease(polling_consumer, offset, partition_id)
+                    .await;
+            }
+
+            return Ok(());
+        };
+
+        if let Err(error) = self
+            .append_dead_letter(
+                topic,
+                dead_letter_queue_topic,
+                partition_id,
+                &message,
+                delivery_attempts,
+                reason,
+            )
+            .await
+        {
+            error!("Cannot move the message with offset: {offset} from partition with ID: {partition_id}, topic with ID: {}, stream with ID: {} to the dead letter queue. Error: {error}", topic.topic_id, topic.stream_id);
+            topic.cancel_dead_letter(partition_id, offset).await?;
+            return Err(error);
+        }
+
+        topic.mark_as_poisoned(partition_id, offset).await?;
+        self.metrics.increment_messages(1);
+        info!("Moved the message with offset: {offset} from partition with ID: {partition_id}, topic with ID: {}, stream with ID: {} to the dead letter queue: {dead_letter_queue} after {delivery_attempts} delivery attempts.", topic.topic_id, topic.stream_id);
+        if is_queue {
+            topic
+                .ack_message(consumer, offset, Some(partition_id), session.client_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Appends the message to the dead letter queue along with the headers describing where it comes from.
+    /// The payload is moved as-is, so the encrypted messages remain encrypted.
+    async fn append_dead_letter(
+        &self,
+        topic: &Topic,
+        dead_letter_queue_topic: &Topic,
+        partition_id: u32,
+        message: &RetainedMessage,
+        delivery_attempts: u32,
+        reason: HeaderValue,
+    ) -> Result<(), IggyError> {
+        let mut headers = message
+            .headers
+            .clone()
+            .map(HashMap::from_bytes)
+            .transpose()?
+            .unwrap_or_default();
+        headers.insert(
+            HeaderKey::new(ORIGIN_STREAM_ID_HEADER)?,
+            HeaderValue::from_uint32(topic.stream_id)?,
+        );
+        headers.insert(
+            HeaderKey::new(ORIGIN_TOPIC_ID_HEADER)?,
+            HeaderValue::from_uint32(topic.topic_id)?,
+        );
+        headers.insert(
+            HeaderKey::new(ORIGIN_PARTITION_ID_HEADER)?,
+            HeaderValue::from_uint32(partition_id)?,
+        );
+        headers.insert(
+            HeaderKey::new(ORIGIN_OFFSET_HEADER)?,
+            HeaderValue::from_uint64(message.offset)?,
+        );
+        headers.insert(HeaderKey::new(REASON_HEADER)?, reason);
+        headers.insert(
+            HeaderKey::new(DELIVERY_ATTEMPTS_HEADER)?,
+            HeaderValue::from_uint32(delivery_attempts)?,
+        );
+        let dead_letter = Message {
+            id: message.id,
+            length: message.payload.len() as u32,
+            payload: message.payload.clone(),
+            headers: Some(headers),
+        };
+
+        let batch_size_bytes = dead_letter.get_size_bytes() as u64;
+        if let Some(memory_tracker) = CacheMemoryTracker::get_instance() {
+            if !memory_tracker.will_fit_into_cache(batch_size_bytes) {
+                self.clean_cache(batch_size_bytes).await;
+            }
+        }
+        dead_letter_queue_topic
+            .append_messages(
+                batch_size_bytes,
+                Partitioning::balanced(),
+                vec![dead_letter],
+            )
+            .await
+    }
+
+    pub async fn ack_message(
+        &self,
+        session: &Session,
+        consumer: &Consumer,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partition_id: Option<u32>,
+        offset: u64,
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        let topic = self.find_topic(session, stream_id, topic_id)?;
+        self.permissioner
+            .ack_message(session.get_user_id(), topic.stream_id, topic.topic_id)?;
+        topic
+            .ack_message(consumer, offset, partition_id, session.client_id)
+            .await
+    }
+}
+
+/// The result of polling the messages, either as the batches stored in the segment log files,
+/// or as the messages loaded and processed by the server.
+#[derive(Debug)]
+pub enum PollingResult {
+    Batches(PolledBatches),
+    Messages(PolledMessages),
 }
 
 #[derive(Debug)]
@@ -160,14 +551,24 @@ pub struct PollingArgs {
     pub strategy: PollingStrategy,
     pub count: u32,
     pub auto_commit: bool,
+    pub filter: Option<MessageFilter>,
+    pub isolation: IsolationLevel,
 }
 
 impl PollingArgs {
-    pub fn new(strategy: PollingStrategy, count: u32, auto_commit: bool) -> Self {
+    pub fn new(
+        strategy: PollingStrategy,
+        count: u32,
+        auto_commit: bool,
+        filter: Option<MessageFilter>,
+        isolation: IsolationLevel,
+    ) -> Self {
         Self {
             strategy,
             count,
             auto_commit,
+            filter,
+            isolation,
         }
     }
 }
@@ -0,0 +1,32 @@
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::System;
+use iggy::error::IggyError;
+use iggy::utils::timestamp::IggyTimestamp;
+use std::sync::atomic::Ordering;
+use tracing::info;
+
+impl System {
+    /// Assigns the unique ID to the idempotent producer. The IDs are based on the current timestamp,
+    /// so that they are not reused after the server restart, when the sequence numbers of the previous producers
+    /// are loaded from the partitions.
+    pub async fn init_producer(&self, session: &Session) -> Result<u64, IggyError> {
+        self.ensure_authenticated(session)?;
+        if !self.config.idempotence.enabled {
+            return Err(IggyError::FeatureUnavailable);
+        }
+
+        let now = IggyTimestamp::now().as_micros();
+        let previous_producer_id = self
+            .last_producer_id
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last_producer_id| {
+                Some(now.max(last_producer_id + 1))
+            })
+            .unwrap_or_default();
+        let producer_id = now.max(previous_producer_id + 1);
+        info!(
+            "Initialized producer with ID: {} for client with ID: {}.",
+            producer_id, session.client_id
+        );
+        Ok(producer_id)
+    }
+}
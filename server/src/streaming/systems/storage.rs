@@ -1,14 +1,18 @@
 use crate::streaming::persistence::persister::Persister;
-use crate::streaming::storage::SystemInfoStorage;
+use crate::streaming::storage::{SystemInfoStorage, TransactionLogStorage};
 use crate::streaming::systems::info::SystemInfo;
+use crate::streaming::systems::transactions::TransactionLogEntry;
 use crate::streaming::utils::file;
 use anyhow::Context;
 use async_trait::async_trait;
-use bytes::{BufMut, BytesMut};
+use bytes::{Buf, BufMut, BytesMut};
 use iggy::error::IggyError;
+use std::collections::BTreeSet;
+use std::path::Path;
 use std::sync::Arc;
+use tokio::fs;
 use tokio::io::AsyncReadExt;
-use tracing::info;
+use tracing::{error, info, trace};
 
 #[derive(Debug)]
 pub struct FileSystemInfoStorage {
@@ -51,3 +55,113 @@ impl SystemInfoStorage for FileSystemInfoStorage {
         Ok(())
     }
 }
+
+#[derive(Debug)]
+pub struct FileTransactionLogStorage {
+    persister: Arc<dyn Persister>,
+    path: String,
+}
+
+impl FileTransactionLogStorage {
+    pub fn new(path: String, persister: Arc<dyn Persister>) -> Self {
+        Self { path, persister }
+    }
+}
+
+#[async_trait]
+impl TransactionLogStorage for FileTransactionLogStorage {
+    async fn load(&self) -> Result<Vec<TransactionLogEntry>, IggyError> {
+        if !Path::new(&self.path).exists() {
+            return Ok(Vec::new());
+        }
+
+        let bytes = fs::read(&self.path).await?;
+        Ok(decode_transaction_log(&self.path, &bytes))
+    }
+
+    async fn save(&self, entries: &[TransactionLogEntry]) -> Result<(), IggyError> {
+        self.persister
+            .overwrite(&self.path, &encode_transaction_log(entries))
+            .await?;
+        trace!(
+            "Stored {} entries of the transaction log, path: {}",
+            entries.len(),
+            self.path
+        );
+        Ok(())
+    }
+
+    async fn append(&self, entry: &TransactionLogEntry) -> Result<(), IggyError> {
+        self.persister
+            .append(
+                &self.path,
+                &encode_transaction_log(std::slice::from_ref(entry)),
+            )
+            .await
+    }
+}
+
+const TRANSACTION_LOG_HEADER_LENGTH: usize = 13;
+const TRANSACTION_LOG_PARTITION_LENGTH: usize = 12;
+const COMMITTED_ENTRY_KIND: u8 = 1;
+const COMPLETED_ENTRY_KIND: u8 = 2;
+
+/// Encodes the entries of the transaction log: the kind (u8), the transaction ID (u64), the number of the partitions (u32)
+/// and the stream, topic and partition IDs (u32 each) of every partition the committed transaction has touched.
+pub(crate) fn encode_transaction_log(entries: &[TransactionLogEntry]) -> BytesMut {
+    let mut bytes = BytesMut::new();
+    for entry in entries {
+        match entry {
+            TransactionLogEntry::Committed {
+                transaction_id,
+                partitions,
+            } => {
+                bytes.put_u8(COMMITTED_ENTRY_KIND);
+                bytes.put_u64_le(*transaction_id);
+                bytes.put_u32_le(partitions.len() as u32);
+                for (stream_id, topic_id, partition_id) in partitions {
+                    bytes.put_u32_le(*stream_id);
+                    bytes.put_u32_le(*topic_id);
+                    bytes.put_u32_le(*partition_id);
+                }
+            }
+            TransactionLogEntry::Completed { transaction_id } => {
+                bytes.put_u8(COMPLETED_ENTRY_KIND);
+                bytes.put_u64_le(*transaction_id);
+                bytes.put_u32_le(0);
+            }
+        }
+    }
+    bytes
+}
+
+/// Decodes the entries of the transaction log, the incomplete entry at the end, e.g. torn by the crash while appending, is skipped.
+pub(crate) fn decode_transaction_log(path: &str, mut bytes: &[u8]) -> Vec<TransactionLogEntry> {
+    let mut entries = Vec::new();
+    while bytes.len() >= TRANSACTION_LOG_HEADER_LENGTH {
+        let kind = bytes.get_u8();
+        let transaction_id = bytes.get_u64_le();
+        let partitions_count = bytes.get_u32_le() as usize;
+        match kind {
+            COMMITTED_ENTRY_KIND => {
+                if bytes.len() < partitions_count * TRANSACTION_LOG_PARTITION_LENGTH {
+                    break;
+                }
+
+                let partitions = (0..partitions_count)
+                    .map(|_| (bytes.get_u32_le(), bytes.get_u32_le(), bytes.get_u32_le()))
+                    .collect::<BTreeSet<_>>();
+                entries.push(TransactionLogEntry::Committed {
+                    transaction_id,
+                    partitions,
+                });
+            }
+            COMPLETED_ENTRY_KIND => entries.push(TransactionLogEntry::Completed { transaction_id }),
+            _ => {
+                error!("Invalid entry kind: {kind} in the transaction log: '{path}'.");
+                break;
+            }
+        }
+    }
+    entries
+}
@@ -1,3 +1,4 @@
+use crate::configs::system::StorageBackend;
 use crate::state::system::StreamState;
 use crate::streaming::session::Session;
 use crate::streaming::streams::stream::Stream;
@@ -21,6 +22,11 @@ impl System {
         &mut self,
         streams: Vec<StreamState>,
     ) -> Result<(), IggyError> {
+        if self.config.storage.backend == StorageBackend::Memory {
+            // The in-memory storage starts empty along with the state, so there's nothing to load.
+            return Ok(());
+        }
+
         info!("Loading streams from disk...");
         let mut unloaded_streams = Vec::new();
         let dir_entries = read_dir(&self.config.get_streams_path()).await;
@@ -1,5 +1,6 @@
+use crate::configs::reloader::ConfigReloader;
 use crate::configs::server::{DataMaintenanceConfig, PersonalAccessTokenConfig};
-use crate::configs::system::SystemConfig;
+use crate::configs::system::{StorageBackend, SystemConfig};
 use crate::streaming::cache::memory_tracker::CacheMemoryTracker;
 use crate::streaming::clients::client_manager::ClientManager;
 use crate::streaming::diagnostics::metrics::Metrics;
@@ -7,20 +8,25 @@ use crate::streaming::persistence::persister::*;
 use crate::streaming::session::Session;
 use crate::streaming::storage::SystemStorage;
 use crate::streaming::streams::stream::Stream;
+use crate::streaming::systems::transactions::CompletingTransactions;
 use crate::streaming::users::permissioner::Permissioner;
+use crate::streaming::utils::keyring::Keyring;
 use iggy::error::IggyError;
-use iggy::utils::crypto::{Aes256GcmEncryptor, Encryptor};
+use iggy::utils::crypto::Encryptor;
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
-use tokio::fs::{create_dir, remove_dir_all};
+use tokio::fs::{create_dir, create_dir_all, remove_dir_all};
 use tokio::time::Instant;
-use tracing::{info, instrument, trace};
+use tracing::{info, instrument, trace, warn};
 
-use crate::archiver::disk::DiskArchiver;
-use crate::archiver::s3::S3Archiver;
-use crate::archiver::{Archiver, ArchiverKind};
+use crate::archiver;
+use crate::archiver::tiered::TieredStorage;
+use crate::archiver::Archiver;
+use crate::cluster::node::ClusterNode;
 use crate::state::file::FileState;
+use crate::state::memory::MemoryState;
 use crate::state::system::SystemState;
 use crate::state::State;
 use crate::streaming::users::user::User;
@@ -69,10 +75,14 @@ pub struct System {
     pub(crate) users: HashMap<UserId, User>,
     pub(crate) config: Arc<SystemConfig>,
     pub(crate) client_manager: IggySharedMut<ClientManager>,
-    pub(crate) encryptor: Option<Arc<dyn Encryptor>>,
+    pub(crate) keyring: Option<Arc<Keyring>>,
     pub(crate) metrics: Metrics,
     pub(crate) state: Arc<dyn State>,
     pub(crate) archiver: Option<Arc<dyn Archiver>>,
+    pub(crate) cluster: Option<Arc<ClusterNode>>,
+    pub(crate) config_reloader: Option<Arc<ConfigReloader>>,
+    pub(crate) last_producer_id: AtomicU64,
+    pub(crate) completing_transactions: IggySharedMut<CompletingTransactions>,
     pub personal_access_token: PersonalAccessTokenConfig,
 }
 
@@ -80,6 +90,9 @@ pub struct System {
 /// This is done on purpose to avoid evicting messages on every write.
 const CACHE_OVER_EVICTION_FACTOR: u64 = 5;
 
+/// The directory (relative to the runtime one) for the log files fetched from the archive by the tiered storage.
+const TIERED_STORAGE_CACHE_DIRECTORY: &str = "tiered_cache";
+
 impl System {
     pub fn new(
         config: Arc<SystemConfig>,
@@ -92,36 +105,73 @@ impl System {
             map_toggle_str(config.encryption.enabled)
         );
 
-        let encryptor: Option<Arc<dyn Encryptor>> = match config.encryption.enabled {
+        let keyring = match config.encryption.enabled {
             true => Some(Arc::new(
-                Aes256GcmEncryptor::from_base64_key(&config.encryption.key).unwrap(),
+                Keyring::from_config(&config.encryption).expect("Invalid encryption keyring"),
             )),
             false => None,
         };
+        if let Some(keyring) = &keyring {
+            info!(
+                "Encryption key with ID: {} is active.",
+                keyring.active_key_id()
+            );
+        }
 
-        let state_persister = Self::resolve_persister(config.state.enforce_fsync);
-        let partition_persister = Self::resolve_persister(config.partition.enforce_fsync);
+        info!("Storage backend: {}.", config.storage.backend);
+        if config.storage.backend == StorageBackend::Memory {
+            warn!("All the data is stored in memory only and will be lost on shutdown.");
+            return Self::create(
+                config.clone(),
+                SystemStorage::in_memory(config),
+                Arc::new(MemoryState::new(&version)),
+                keyring,
+                data_maintenance_config,
+                pat_config,
+            );
+        }
+
+        let state_persister =
+            Self::resolve_persister(config.storage.backend, config.state.enforce_fsync);
+        let partition_persister =
+            Self::resolve_persister(config.storage.backend, config.partition.enforce_fsync);
 
         let state = Arc::new(FileState::new(
             &config.get_state_log_path(),
+            &config.get_state_snapshot_path(),
             &version,
             state_persister,
-            encryptor.clone(),
+            keyring.clone().map(|keyring| keyring as Arc<dyn Encryptor>),
         ));
         Self::create(
             config.clone(),
             SystemStorage::new(config, partition_persister),
             state,
-            encryptor,
+            keyring,
             data_maintenance_config,
             pat_config,
         )
     }
 
-    fn resolve_persister(enforce_fsync: bool) -> Arc<dyn Persister> {
-        match enforce_fsync {
-            true => Arc::new(FileWithSyncPersister),
-            false => Arc::new(FilePersister),
+    pub fn resolve_persister(backend: StorageBackend, enforce_fsync: bool) -> Arc<dyn Persister> {
+        match (backend, enforce_fsync) {
+            #[cfg(all(feature = "io-uring", target_os = "linux"))]
+            (StorageBackend::IoUring, enforce_fsync) => {
+                match crate::streaming::persistence::uring::Uring::get_instance() {
+                    Ok(uring) => {
+                        Arc::new(crate::streaming::persistence::uring::UringPersister::new(
+                            uring,
+                            enforce_fsync,
+                        ))
+                    }
+                    Err(error) => {
+                        warn!("Cannot use io_uring for the persister, falling back to the file one. {error}");
+                        Self::resolve_persister(StorageBackend::File, enforce_fsync)
+                    }
+                }
+            }
+            (_, true) => Arc::new(FileWithSyncPersister),
+            (_, false) => Arc::new(FilePersister),
         }
     }
 
@@ -129,41 +179,43 @@ impl System {
         system_config: Arc<SystemConfig>,
         storage: SystemStorage,
         state: Arc<dyn State>,
-        encryptor: Option<Arc<dyn Encryptor>>,
+        keyring: Option<Arc<Keyring>>,
         data_maintenance_config: DataMaintenanceConfig,
         pat_config: PersonalAccessTokenConfig,
     ) -> System {
         let archiver_config = data_maintenance_config.archiver;
         let archiver: Option<Arc<dyn Archiver>> = if archiver_config.enabled {
             info!("Archiving is enabled, kind: {}", archiver_config.kind);
-            match archiver_config.kind {
-                ArchiverKind::Disk => Some(Arc::new(DiskArchiver::new(
-                    archiver_config
-                        .disk
-                        .clone()
-                        .expect("Disk archiver config is missing"),
-                ))),
-                ArchiverKind::S3 => Some(Arc::new(
-                    S3Archiver::new(
-                        archiver_config
-                            .s3
-                            .clone()
-                            .expect("S3 archiver config is missing"),
-                    )
-                    .expect("Failed to create S3 archiver"),
-                )),
-            }
+            Some(archiver::create(&archiver_config).expect("Failed to create archiver"))
         } else {
             info!("Archiving is disabled.");
             None
         };
 
+        let mut storage = storage;
+        let tiered_storage_config = data_maintenance_config.tiered_storage;
+        if let Some(archiver) = archiver.as_ref().filter(|_| tiered_storage_config.enabled) {
+            info!(
+                "Tiered storage is enabled, cache size: {}",
+                tiered_storage_config.cache_size
+            );
+            storage.tiered = Some(Arc::new(TieredStorage::new(
+                archiver.clone(),
+                &format!(
+                    "{}/{}",
+                    system_config.get_runtime_path(),
+                    TIERED_STORAGE_CACHE_DIRECTORY
+                ),
+                tiered_storage_config.cache_size,
+            )));
+        }
+
         System {
             config: system_config,
             streams: HashMap::new(),
             streams_ids: HashMap::new(),
             storage: Arc::new(storage),
-            encryptor,
+            keyring,
             client_manager: IggySharedMut::new(ClientManager::default()),
             permissioner: Permissioner::default(),
             metrics: Metrics::init(),
@@ -171,40 +223,21 @@ impl System {
             state,
             personal_access_token: pat_config,
             archiver,
+            cluster: None,
+            config_reloader: None,
+            last_producer_id: AtomicU64::new(0),
+            completing_transactions: IggySharedMut::new(CompletingTransactions::default()),
         }
     }
 
     #[instrument(skip_all)]
     pub async fn init(&mut self) -> Result<(), IggyError> {
-        let system_path = self.config.get_system_path();
-        if !Path::new(&system_path).exists() && create_dir(&system_path).await.is_err() {
-            return Err(IggyError::CannotCreateBaseDirectory(system_path));
-        }
-
-        let state_path = self.config.get_state_path();
-        if !Path::new(&state_path).exists() && create_dir(&state_path).await.is_err() {
-            return Err(IggyError::CannotCreateStateDirectory(state_path));
-        }
-
-        let streams_path = self.config.get_streams_path();
-        if !Path::new(&streams_path).exists() && create_dir(&streams_path).await.is_err() {
-            return Err(IggyError::CannotCreateStreamsDirectory(streams_path));
-        }
-
-        let runtime_path = self.config.get_runtime_path();
-        if Path::new(&runtime_path).exists() && remove_dir_all(&runtime_path).await.is_err() {
-            return Err(IggyError::CannotRemoveRuntimeDirectory(runtime_path));
-        }
-
-        if create_dir(&runtime_path).await.is_err() {
-            return Err(IggyError::CannotCreateRuntimeDirectory(runtime_path));
+        if self.config.storage.backend == StorageBackend::Memory {
+            self.init_runtime_directory().await?;
+        } else {
+            self.init_directories().await?;
         }
 
-        info!(
-            "Initializing system, data will be stored at: {}",
-            self.config.get_system_path()
-        );
-
         if self.config.database.is_some() {
             compat::storage_conversion::init(
                 self.config.clone(),
@@ -214,14 +247,14 @@ impl System {
             .await?;
         }
 
-        let state_entries = self.state.init().await?;
-        let system_state = SystemState::init(state_entries).await?;
+        let system_state = SystemState::load(self.state.as_ref()).await?;
         let now = Instant::now();
         self.load_version().await?;
         self.load_users(system_state.users.into_values().collect())
             .await?;
         self.load_streams(system_state.streams.into_values().collect())
             .await?;
+        self.load_transactions().await?;
         if let Some(archiver) = self.archiver.as_ref() {
             archiver
                 .init()
@@ -232,6 +265,45 @@ impl System {
         Ok(())
     }
 
+    async fn init_directories(&self) -> Result<(), IggyError> {
+        let system_path = self.config.get_system_path();
+        if !Path::new(&system_path).exists() && create_dir(&system_path).await.is_err() {
+            return Err(IggyError::CannotCreateBaseDirectory(system_path));
+        }
+
+        let state_path = self.config.get_state_path();
+        if !Path::new(&state_path).exists() && create_dir(&state_path).await.is_err() {
+            return Err(IggyError::CannotCreateStateDirectory(state_path));
+        }
+
+        let streams_path = self.config.get_streams_path();
+        if !Path::new(&streams_path).exists() && create_dir(&streams_path).await.is_err() {
+            return Err(IggyError::CannotCreateStreamsDirectory(streams_path));
+        }
+
+        self.init_runtime_directory().await?;
+        info!(
+            "Initializing system, data will be stored at: {}",
+            self.config.get_system_path()
+        );
+        Ok(())
+    }
+
+    /// The runtime directory is created for the in-memory storage as well,
+    /// so that the current config (e.g. with the bound addresses) remains available.
+    async fn init_runtime_directory(&self) -> Result<(), IggyError> {
+        let runtime_path = self.config.get_runtime_path();
+        if Path::new(&runtime_path).exists() && remove_dir_all(&runtime_path).await.is_err() {
+            return Err(IggyError::CannotRemoveRuntimeDirectory(runtime_path));
+        }
+
+        if create_dir_all(&runtime_path).await.is_err() {
+            return Err(IggyError::CannotCreateRuntimeDirectory(runtime_path));
+        }
+
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     pub async fn shutdown(&mut self) -> Result<(), IggyError> {
         self.persist_messages().await?;
@@ -5,6 +5,8 @@ use iggy::compression::compression_algorithm::CompressionAlgorithm;
 use iggy::error::IggyError;
 use iggy::identifier::Identifier;
 use iggy::locking::IggySharedMutFn;
+use iggy::models::cleanup_policy::CleanupPolicy;
+use iggy::models::dead_letter_queue::DeadLetterQueue;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::topic_size::MaxTopicSize;
 
@@ -155,6 +157,114 @@ impl System {
         Ok(())
     }
 
+    pub async fn set_dead_letter_queue(
+        &mut self,
+        session: &Session,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        dead_letter_queue: Option<DeadLetterQueue>,
+    ) -> Result<Option<DeadLetterQueue>, IggyError> {
+        let dead_letter_queue =
+            self.validate_dead_letter_queue(session, stream_id, topic_id, dead_letter_queue)?;
+        self.get_stream_mut(stream_id)?
+            .get_topic_mut(topic_id)?
+            .dead_letter_queue = dead_letter_queue.clone();
+        Ok(dead_letter_queue)
+    }
+
+    /// Validates the dead letter queue to be set for the topic, returns it with the numeric identifiers.
+    pub(crate) fn validate_dead_letter_queue(
+        &self,
+        session: &Session,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        dead_letter_queue: Option<DeadLetterQueue>,
+    ) -> Result<Option<DeadLetterQueue>, IggyError> {
+        self.ensure_authenticated(session)?;
+        let topic = self.find_topic(session, stream_id, topic_id)?;
+        self.permissioner.set_dead_letter_queue(
+            session.get_user_id(),
+            topic.stream_id,
+            topic.topic_id,
+        )?;
+
+        let dead_letter_queue = match dead_letter_queue {
+            Some(dead_letter_queue) => {
+                let dead_letter_queue_topic = self.find_topic(
+                    session,
+                    &dead_letter_queue.stream_id,
+                    &dead_letter_queue.topic_id,
+                )?;
+                if dead_letter_queue_topic.stream_id == topic.stream_id
+                    && dead_letter_queue_topic.topic_id == topic.topic_id
+                {
+                    return Err(IggyError::InvalidDeadLetterQueue);
+                }
+
+                self.permissioner.append_messages(
+                    session.get_user_id(),
+                    dead_letter_queue_topic.stream_id,
+                    dead_letter_queue_topic.topic_id,
+                )?;
+
+                // Store the numeric identifiers, so that renaming the topic doesn't break the dead letter queue.
+                Some(DeadLetterQueue {
+                    stream_id: Identifier::numeric(dead_letter_queue_topic.stream_id)?,
+                    topic_id: Identifier::numeric(dead_letter_queue_topic.topic_id)?,
+                    max_delivery_attempts: dead_letter_queue.max_delivery_attempts,
+                })
+            }
+            None => None,
+        };
+        Ok(dead_letter_queue)
+    }
+
+    pub async fn set_cleanup_policy(
+        &mut self,
+        session: &Session,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        cleanup_policy: CleanupPolicy,
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        {
+            let topic = self.find_topic(session, stream_id, topic_id)?;
+            self.permissioner.set_cleanup_policy(
+                session.get_user_id(),
+                topic.stream_id,
+                topic.topic_id,
+            )?;
+        }
+
+        self.get_stream_mut(stream_id)?
+            .get_topic_mut(topic_id)?
+            .cleanup_policy = cleanup_policy;
+        Ok(())
+    }
+
+    pub async fn set_max_topic_messages(
+        &mut self,
+        session: &Session,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        max_messages: u64,
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        {
+            let topic = self.find_topic(session, stream_id, topic_id)?;
+            self.permissioner.set_max_topic_messages(
+                session.get_user_id(),
+                topic.stream_id,
+                topic.topic_id,
+            )?;
+        }
+
+        self.get_stream_mut(stream_id)?
+            .get_topic_mut(topic_id)?
+            .max_messages = max_messages;
+        Ok(())
+    }
+
     pub async fn purge_topic(
         &self,
         session: &Session,
@@ -0,0 +1,614 @@
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::System;
+use iggy::error::IggyError;
+use iggy::identifier::Identifier;
+use iggy::locking::IggySharedMutFn;
+use iggy::utils::timestamp::IggyTimestamp;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tracing::{error, info, warn};
+
+/// The number of the entries in the transaction log, above which it's rewritten with the unfinished commits only.
+const TRANSACTION_LOG_COMPACTION_THRESHOLD: usize = 1000;
+
+/// The entry of the transaction log, the commit is recorded before any of its markers is appended,
+/// and it's marked as completed once all the partitions have the marker.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionLogEntry {
+    Committed {
+        transaction_id: u64,
+        partitions: BTreeSet<(u32, u32, u32)>,
+    },
+    Completed {
+        transaction_id: u64,
+    },
+}
+
+/// The transaction whose markers are being appended to the partitions, the client ID is missing for the commits
+/// finished on restart.
+#[derive(Debug, Clone)]
+pub struct CompletingTransaction {
+    pub client_id: Option<u32>,
+    pub partitions: BTreeSet<(u32, u32, u32)>,
+    pub committed: bool,
+}
+
+/// The transactions which haven't been completed in all the partitions yet,
+/// along with the number of the entries in the transaction log.
+#[derive(Debug, Default)]
+pub struct CompletingTransactions {
+    pub transactions: HashMap<u64, CompletingTransaction>,
+    pub log_length: usize,
+}
+
+impl System {
+    pub async fn begin_transaction(&self, session: &Session) -> Result<u64, IggyError> {
+        self.ensure_authenticated(session)?;
+        self.ensure_transactions_enabled()?;
+        let transaction_id = self
+            .client_manager
+            .write()
+            .await
+            .begin_transaction(session.client_id)
+            .await?;
+        info!(
+            "Began transaction with ID: {} for client with ID: {}.",
+            transaction_id, session.client_id
+        );
+        Ok(transaction_id)
+    }
+
+    pub async fn commit_transaction(
+        &self,
+        session: &Session,
+        transaction_id: u64,
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        self.ensure_transactions_enabled()?;
+        self.end_transaction(session.client_id, transaction_id, true)
+            .await
+    }
+
+    pub async fn abort_transaction(
+        &self,
+        session: &Session,
+        transaction_id: u64,
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        self.ensure_transactions_enabled()?;
+        self.end_transaction(session.client_id, transaction_id, false)
+            .await
+    }
+
+    /// Aborts the transactions which have been open for longer than the configured timeout,
+    /// and retries completing the ones whose markers couldn't be appended to all the partitions.
+    pub async fn abort_expired_transactions(&self) -> Result<(), IggyError> {
+        let started_before = IggyTimestamp::from(
+            IggyTimestamp::now()
+                .as_micros()
+                .saturating_sub(self.config.transaction.timeout.as_micros()),
+        );
+        let expired_transactions = self
+            .client_manager
+            .read()
+            .await
+            .get_expired_transactions(started_before)
+            .await;
+        for (client_id, transaction_id) in expired_transactions {
+            warn!(
+                "Transaction with ID: {} for client with ID: {} has expired and will be aborted.",
+                transaction_id, client_id
+            );
+            if let Err(error) = self.end_transaction(client_id, transaction_id, false).await {
+                error!(
+                    "Failed to abort expired transaction with ID: {} for client with ID: {}. Error: {}",
+                    transaction_id, client_id, error
+                );
+            }
+        }
+
+        let completing_transactions = self
+            .completing_transactions
+            .read()
+            .await
+            .transactions
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        for transaction_id in completing_transactions {
+            if let Err(error) = self.finish_transaction(transaction_id).await {
+                error!(
+                    "Failed to complete transaction with ID: {}, it will be retried. Error: {}",
+                    transaction_id, error
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn end_transaction(
+        &self,
+        client_id: u32,
+        transaction_id: u64,
+        committed: bool,
+    ) -> Result<(), IggyError> {
+        let transaction = match self
+            .client_manager
+            .read()
+            .await
+            .end_transaction(client_id, transaction_id)
+            .await
+        {
+            Ok(transaction) => transaction,
+            Err(error) => {
+                // The client retries the completion which has failed before, its outcome cannot be changed.
+                match self
+                    .completing_transactions
+                    .read()
+                    .await
+                    .transactions
+                    .get(&transaction_id)
+                {
+                    Some(transaction) if transaction.client_id == Some(client_id) => {
+                        if transaction.committed != committed {
+                            return Err(IggyError::TransactionAlreadyCompleting(transaction_id));
+                        }
+                    }
+                    _ => return Err(error),
+                }
+                return self.finish_transaction(transaction_id).await;
+            }
+        };
+        self.complete_transaction(
+            Some(client_id),
+            transaction_id,
+            transaction.partitions,
+            committed,
+        )
+        .await
+    }
+
+    /// Completes the transaction by appending the commit or abort marker to all the partitions it has touched.
+    /// The commit is recorded in the transaction log before any marker is appended, so that it's finished on restart,
+    /// and if it cannot be recorded, the transaction is aborted instead. The transaction is kept
+    /// until all the partitions have the marker, and the failed ones are retried by the expired transactions job.
+    pub(crate) async fn complete_transaction(
+        &self,
+        client_id: Option<u32>,
+        transaction_id: u64,
+        partitions: BTreeSet<(u32, u32, u32)>,
+        committed: bool,
+    ) -> Result<(), IggyError> {
+        let mut result = Ok(());
+        {
+            let mut completing_transactions = self.completing_transactions.write().await;
+            completing_transactions.transactions.insert(
+                transaction_id,
+                CompletingTransaction {
+                    client_id,
+                    partitions: partitions.clone(),
+                    committed,
+                },
+            );
+            if committed {
+                let entry = TransactionLogEntry::Committed {
+                    transaction_id,
+                    partitions,
+                };
+                if let Err(error) = self
+                    .append_transaction_log_entry(&mut completing_transactions, entry)
+                    .await
+                {
+                    error!(
+                        "Cannot record the commit of transaction with ID: {}, it will be aborted. Error: {}",
+                        transaction_id, error
+                    );
+                    if let Some(transaction) = completing_transactions
+                        .transactions
+                        .get_mut(&transaction_id)
+                    {
+                        transaction.committed = false;
+                    }
+                    result = Err(error);
+                }
+            }
+        }
+
+        let completed = self.finish_transaction(transaction_id).await;
+        result.and(completed)
+    }
+
+    /// Appends the marker of the completing transaction to all the partitions it has touched,
+    /// and once all of them have it, the transaction is removed and its commit is marked as completed in the log.
+    async fn finish_transaction(&self, transaction_id: u64) -> Result<(), IggyError> {
+        let Some(transaction) = self
+            .completing_transactions
+            .read()
+            .await
+            .transactions
+            .get(&transaction_id)
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        for (stream_id, topic_id, partition_id) in transaction.partitions.iter().copied() {
+            let topic = match self
+                .get_stream(&Identifier::numeric(stream_id)?)
+                .and_then(|stream| stream.get_topic(&Identifier::numeric(topic_id)?))
+            {
+                Ok(topic) => topic,
+                Err(error) => {
+                    warn!(
+                        "Cannot complete transaction with ID: {} for partition with ID: {}, topic with ID: {} and stream with ID: {}. Error: {}",
+                        transaction_id, partition_id, topic_id, stream_id, error
+                    );
+                    continue;
+                }
+            };
+
+            if let Err(error) = topic
+                .complete_transaction(partition_id, transaction_id, transaction.committed)
+                .await
+            {
+                error!(
+                    "Failed to append the marker of transaction with ID: {} to partition with ID: {}, topic with ID: {} and stream with ID: {}. Error: {}",
+                    transaction_id, partition_id, topic_id, stream_id, error
+                );
+                result = Err(error);
+            }
+        }
+        result?;
+
+        let mut completing_transactions = self.completing_transactions.write().await;
+        if completing_transactions
+            .transactions
+            .remove(&transaction_id)
+            .is_none()
+        {
+            return Ok(());
+        }
+
+        if transaction.committed {
+            let entry = TransactionLogEntry::Completed { transaction_id };
+            // The commit markers which are already appended are skipped, when it's finished again on restart.
+            if let Err(error) = self
+                .append_transaction_log_entry(&mut completing_transactions, entry)
+                .await
+            {
+                warn!(
+                    "Cannot record the completion of transaction with ID: {}. Error: {}",
+                    transaction_id, error
+                );
+            }
+        }
+
+        info!(
+            "{} transaction with ID: {} in {} partition(s).",
+            if transaction.committed {
+                "Committed"
+            } else {
+                "Aborted"
+            },
+            transaction_id,
+            transaction.partitions.len()
+        );
+        Ok(())
+    }
+
+    /// Appends the entry to the transaction log, which is rewritten with the unfinished commits only
+    /// once it grows beyond the threshold, so that it doesn't grow forever.
+    async fn append_transaction_log_entry(
+        &self,
+        completing_transactions: &mut CompletingTransactions,
+        entry: TransactionLogEntry,
+    ) -> Result<(), IggyError> {
+        let unfinished_commits = completing_transactions
+            .transactions
+            .values()
+            .filter(|transaction| transaction.committed)
+            .count();
+        if completing_transactions.log_length
+            < TRANSACTION_LOG_COMPACTION_THRESHOLD.max(2 * unfinished_commits)
+        {
+            self.storage.transactions.append(&entry).await?;
+            completing_transactions.log_length += 1;
+            return Ok(());
+        }
+
+        let entries = completing_transactions
+            .transactions
+            .iter()
+            .filter(|(_, transaction)| transaction.committed)
+            .map(
+                |(transaction_id, transaction)| TransactionLogEntry::Committed {
+                    transaction_id: *transaction_id,
+                    partitions: transaction.partitions.clone(),
+                },
+            )
+            .collect::<Vec<_>>();
+        self.storage.transactions.save(&entries).await?;
+        completing_transactions.log_length = entries.len();
+        Ok(())
+    }
+
+    /// Loads the commits recorded in the transaction log which haven't been completed before the shutdown,
+    /// and appends the commit markers to the partitions which don't have them yet.
+    /// The log is rewritten with the unfinished commits only.
+    pub(crate) async fn load_transactions(&self) -> Result<(), IggyError> {
+        let entries = self.storage.transactions.load().await?;
+        let mut unfinished_commits = BTreeMap::new();
+        for entry in entries {
+            match entry {
+                TransactionLogEntry::Committed {
+                    transaction_id,
+                    partitions,
+                } => {
+                    unfinished_commits.insert(transaction_id, partitions);
+                }
+                TransactionLogEntry::Completed { transaction_id } => {
+                    unfinished_commits.remove(&transaction_id);
+                }
+            }
+        }
+
+        let entries = unfinished_commits
+            .iter()
+            .map(
+                |(transaction_id, partitions)| TransactionLogEntry::Committed {
+                    transaction_id: *transaction_id,
+                    partitions: partitions.clone(),
+                },
+            )
+            .collect::<Vec<_>>();
+        self.storage.transactions.save(&entries).await?;
+        {
+            let mut completing_transactions = self.completing_transactions.write().await;
+            completing_transactions.log_length = entries.len();
+            for (transaction_id, partitions) in unfinished_commits.iter() {
+                for (stream_id, topic_id, partition_id) in partitions.iter().copied() {
+                    if let Ok(partition) = self
+                        .get_stream(&Identifier::numeric(stream_id)?)
+                        .and_then(|stream| stream.get_topic(&Identifier::numeric(topic_id)?))
+                        .and_then(|topic| topic.get_partition(partition_id))
+                    {
+                        partition
+                            .write()
+                            .await
+                            .register_transaction(*transaction_id);
+                    }
+                }
+                completing_transactions.transactions.insert(
+                    *transaction_id,
+                    CompletingTransaction {
+                        client_id: None,
+                        partitions: partitions.clone(),
+                        committed: true,
+                    },
+                );
+            }
+        }
+
+        for transaction_id in unfinished_commits.into_keys() {
+            info!("Finishing the commit of transaction with ID: {transaction_id}...");
+            if let Err(error) = self.finish_transaction(transaction_id).await {
+                error!(
+                    "Failed to finish the commit of transaction with ID: {}, it will be retried. Error: {}",
+                    transaction_id, error
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_transactions_enabled(&self) -> Result<(), IggyError> {
+        if self.config.transaction.enabled {
+            Ok(())
+        } else {
+            Err(IggyError::FeatureUnavailable)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::server::{DataMaintenanceConfig, PersonalAccessTokenConfig};
+    use crate::configs::system::{StorageBackend, SystemConfig};
+    use crate::state::memory::MemoryState;
+    use crate::streaming::clients::client_manager::Transport;
+    use crate::streaming::partitions::partition::Partition;
+    use crate::streaming::storage::SystemStorage;
+    use crate::streaming::users::user::User;
+    use crate::versioning::SemanticVersion;
+    use bytes::Bytes;
+    use iggy::compression::compression_algorithm::CompressionAlgorithm;
+    use iggy::locking::IggySharedMut;
+    use iggy::messages::send_messages::{Message, Partitioning};
+    use iggy::users::defaults::{DEFAULT_ROOT_PASSWORD, DEFAULT_ROOT_USERNAME};
+    use iggy::utils::expiry::IggyExpiry;
+    use iggy::utils::topic_size::MaxTopicSize;
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::sync::Arc;
+
+    const STREAM_ID: u32 = 1;
+    const TOPIC_ID: u32 = 1;
+    const PARTITION_ID: u32 = 1;
+
+    #[tokio::test]
+    async fn commit_should_be_recorded_before_markers_and_completed_after_them() {
+        let system = create_system().await;
+        let (client_id, transaction_id) = begin_transaction(&system).await;
+
+        system
+            .end_transaction(client_id, transaction_id, true)
+            .await
+            .unwrap();
+
+        let entries = system.storage.transactions.load().await.unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                TransactionLogEntry::Committed {
+                    transaction_id,
+                    partitions: BTreeSet::from([(STREAM_ID, TOPIC_ID, PARTITION_ID)]),
+                },
+                TransactionLogEntry::Completed { transaction_id },
+            ]
+        );
+        assert_committed(&system, transaction_id).await;
+    }
+
+    #[tokio::test]
+    async fn commit_recorded_before_restart_should_be_finished_on_load() {
+        let system = create_system().await;
+        let (_, transaction_id) = begin_transaction(&system).await;
+        system
+            .storage
+            .transactions
+            .append(&TransactionLogEntry::Committed {
+                transaction_id,
+                partitions: BTreeSet::from([(STREAM_ID, TOPIC_ID, PARTITION_ID)]),
+            })
+            .await
+            .unwrap();
+        get_partition(&system)
+            .write()
+            .await
+            .open_transactions
+            .clear();
+
+        system.load_transactions().await.unwrap();
+
+        let entries = system.storage.transactions.load().await.unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                TransactionLogEntry::Committed {
+                    transaction_id,
+                    partitions: BTreeSet::from([(STREAM_ID, TOPIC_ID, PARTITION_ID)]),
+                },
+                TransactionLogEntry::Completed { transaction_id },
+            ]
+        );
+        assert_committed(&system, transaction_id).await;
+        let partition = get_partition(&system);
+        let partition = partition.read().await;
+        assert_eq!(partition.current_offset, 1);
+    }
+
+    #[tokio::test]
+    async fn completed_commit_should_not_be_finished_again_on_load() {
+        let system = create_system().await;
+        let (client_id, transaction_id) = begin_transaction(&system).await;
+        system
+            .end_transaction(client_id, transaction_id, true)
+            .await
+            .unwrap();
+
+        system.load_transactions().await.unwrap();
+
+        assert_committed(&system, transaction_id).await;
+        let partition = get_partition(&system);
+        let partition = partition.read().await;
+        assert_eq!(partition.current_offset, 1);
+    }
+
+    async fn create_system() -> System {
+        let mut config = SystemConfig::default();
+        config.storage.backend = StorageBackend::Memory;
+        config.transaction.enabled = true;
+        let config = Arc::new(config);
+        let version = SemanticVersion::current().unwrap();
+        let mut system = System::create(
+            config.clone(),
+            SystemStorage::in_memory(config),
+            Arc::new(MemoryState::new(&version)),
+            None,
+            DataMaintenanceConfig::default(),
+            PersonalAccessTokenConfig::default(),
+        );
+        let root = User::root(DEFAULT_ROOT_USERNAME, DEFAULT_ROOT_PASSWORD);
+        let session = Session::new(
+            1,
+            root.id,
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234),
+        );
+        system
+            .permissioner
+            .init_permissions_for_user(root.id, root.permissions.clone());
+        system
+            .create_stream(&session, Some(STREAM_ID), "stream")
+            .await
+            .unwrap();
+        system
+            .create_topic(
+                &session,
+                &Identifier::numeric(STREAM_ID).unwrap(),
+                Some(TOPIC_ID),
+                "topic",
+                1,
+                IggyExpiry::NeverExpire,
+                CompressionAlgorithm::None,
+                MaxTopicSize::ServerDefault,
+                None,
+            )
+            .await
+            .unwrap();
+        system.load_transactions().await.unwrap();
+        system
+    }
+
+    async fn begin_transaction(system: &System) -> (u32, u64) {
+        let mut client_manager = system.client_manager.write().await;
+        let address = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+        let session = client_manager.add_client(&address, Transport::Tcp);
+        let client_id = session.client_id;
+        let transaction_id = client_manager.begin_transaction(client_id).await.unwrap();
+        let topic = system
+            .get_stream(&Identifier::numeric(STREAM_ID).unwrap())
+            .unwrap()
+            .get_topic(&Identifier::numeric(TOPIC_ID).unwrap())
+            .unwrap();
+        let message = Message::new(None, Bytes::from("message"), None);
+        topic
+            .append_transactional_messages(
+                message.get_size_bytes() as u64,
+                Partitioning::partition_id(PARTITION_ID),
+                vec![message],
+                transaction_id,
+            )
+            .await
+            .unwrap();
+        client_manager
+            .add_transaction_partition(client_id, transaction_id, STREAM_ID, TOPIC_ID, PARTITION_ID)
+            .await
+            .unwrap();
+        (client_id, transaction_id)
+    }
+
+    fn get_partition(system: &System) -> IggySharedMut<Partition> {
+        system
+            .get_stream(&Identifier::numeric(STREAM_ID).unwrap())
+            .unwrap()
+            .get_topic(&Identifier::numeric(TOPIC_ID).unwrap())
+            .unwrap()
+            .get_partition(PARTITION_ID)
+            .unwrap()
+    }
+
+    async fn assert_committed(system: &System, transaction_id: u64) {
+        assert!(system
+            .completing_transactions
+            .read()
+            .await
+            .transactions
+            .is_empty());
+        let partition = get_partition(system);
+        let partition = partition.read().await;
+        assert!(partition
+            .committed_transactions
+            .contains_key(&transaction_id));
+        assert!(partition.open_transactions.is_empty());
+    }
+}
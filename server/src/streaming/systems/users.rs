@@ -24,7 +24,14 @@ const MAX_USERS: usize = u32::MAX as usize;
 impl System {
     pub(crate) async fn load_users(&mut self, users: Vec<UserState>) -> Result<(), IggyError> {
         info!("Loading users...");
-        if users.is_empty() {
+        if users.is_empty()
+            && self
+                .cluster
+                .as_ref()
+                .is_some_and(|cluster| !cluster.is_bootstrapping())
+        {
+            info!("No users found, the root user will be replicated by the cluster leader.");
+        } else if users.is_empty() {
             info!("No users found, creating the root user...");
             let root = Self::create_root_user();
             let command = CreateUser {
@@ -69,7 +76,8 @@ impl System {
         }
 
         let users_count = self.users.len();
-        let current_user_id = self.users.keys().max().unwrap_or(&1);
+        // Until the root user is replicated by the cluster leader, there are no users, so it gets the ID 1 as well.
+        let current_user_id = self.users.keys().max().unwrap_or(&0);
         USER_ID.store(current_user_id + 1, Ordering::SeqCst);
         self.permissioner
             .init(&self.users.values().collect::<Vec<&User>>());
@@ -78,6 +86,31 @@ impl System {
         Ok(())
     }
 
+    /// Creates the initial user (i.e. the root user) replicated from the entry of the bootstrapping cluster node,
+    /// which isn't issued by any user. The password is replicated as the hash, so it's stored as it is.
+    pub(crate) fn create_initial_user(&mut self, command: CreateUser) -> Result<(), IggyError> {
+        let username = command.username;
+        if self.users.values().any(|user| user.username == username) {
+            error!("User: {username} already exists.");
+            return Err(IggyError::UserAlreadyExists);
+        }
+
+        let user_id = USER_ID.fetch_add(1, Ordering::SeqCst);
+        let user = User::with_password(
+            user_id,
+            &username,
+            command.password,
+            command.status,
+            command.permissions.clone(),
+        );
+        self.permissioner
+            .init_permissions_for_user(user_id, command.permissions);
+        self.users.insert(user.id, user);
+        self.metrics.increment_users(1);
+        info!("Created initial user: {username} with ID: {user_id}.");
+        Ok(())
+    }
+
     fn create_root_user() -> User {
         let username = env::var(IGGY_ROOT_USERNAME_ENV);
         let password = env::var(IGGY_ROOT_PASSWORD_ENV);
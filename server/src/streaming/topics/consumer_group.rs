@@ -1,5 +1,7 @@
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::error::IggyError;
-use std::collections::HashMap;
+use iggy::utils::timestamp::IggyTimestamp;
+use std::collections::{BTreeSet, HashMap};
 use tokio::sync::RwLock;
 use tracing::trace;
 
@@ -9,25 +11,43 @@ pub struct ConsumerGroup {
     pub group_id: u32,
     pub name: String,
     pub partitions_count: u32,
+    pub mode: ConsumerGroupMode,
+    pub generation: u32,
+    pub evicted_members_count: u32,
     members: HashMap<u32, RwLock<ConsumerGroupMember>>,
+    static_members: HashMap<String, u32>,
+    departed_members: HashMap<u32, IggyTimestamp>,
 }
 
 #[derive(Debug)]
 pub struct ConsumerGroupMember {
     pub id: u32,
+    pub name: Option<String>,
     partitions: HashMap<u32, u32>,
     current_partition_index: u32,
     current_partition_id: u32,
+    last_heartbeat: IggyTimestamp,
 }
 
 impl ConsumerGroup {
-    pub fn new(topic_id: u32, group_id: u32, name: &str, partitions_count: u32) -> ConsumerGroup {
+    pub fn new(
+        topic_id: u32,
+        group_id: u32,
+        name: &str,
+        partitions_count: u32,
+        mode: ConsumerGroupMode,
+    ) -> ConsumerGroup {
         ConsumerGroup {
             topic_id,
             group_id,
             name: name.to_string(),
             partitions_count,
+            mode,
+            generation: 0,
+            evicted_members_count: 0,
             members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
         }
     }
 
@@ -64,14 +84,64 @@ impl ConsumerGroup {
         ))
     }
 
-    pub async fn add_member(&mut self, member_id: u32) {
+    pub async fn get_member_partitions(&self, member_id: u32) -> Result<Vec<u32>, IggyError> {
+        let member = self.members.get(&member_id);
+        if let Some(member) = member {
+            let mut partitions = member.read().await.get_partitions();
+            partitions.sort();
+            return Ok(partitions);
+        }
+        Err(IggyError::ConsumerGroupMemberNotFound(
+            member_id,
+            self.group_id,
+            self.topic_id,
+        ))
+    }
+
+    /// Adds the member to the consumer group and rebalances it. The member joining with the name of the static member
+    /// takes over its partitions without a rebalance, and the ID of the replaced member is returned.
+    pub async fn add_member(&mut self, member_id: u32, member_name: Option<&str>) -> Option<u32> {
+        // The member which has already joined keeps its partitions, thus there's no need to rebalance.
+        if let Some(member) = self.members.get(&member_id) {
+            member.write().await.last_heartbeat = IggyTimestamp::now();
+            return None;
+        }
+
+        if let Some(member_name) = member_name {
+            if let Some(previous_member_id) = self.static_members.get(member_name).copied() {
+                if let Some(member) = self.members.remove(&previous_member_id) {
+                    let mut member = member.into_inner();
+                    member.id = member_id;
+                    member.last_heartbeat = IggyTimestamp::now();
+                    self.members.insert(member_id, RwLock::new(member));
+                    self.departed_members.remove(&previous_member_id);
+                    self.static_members
+                        .insert(member_name.to_string(), member_id);
+                    trace!(
+                        "Member with ID: {} has taken over static member: {} with ID: {} in consumer group: {} for topic with ID: {}",
+                        member_id,
+                        member_name,
+                        previous_member_id,
+                        self.group_id,
+                        self.topic_id
+                    );
+                    return Some(previous_member_id);
+                }
+            }
+
+            self.static_members
+                .insert(member_name.to_string(), member_id);
+        }
+
         self.members.insert(
             member_id,
             RwLock::new(ConsumerGroupMember {
                 id: member_id,
+                name: member_name.map(|name| name.to_string()),
                 partitions: HashMap::new(),
                 current_partition_index: 0,
                 current_partition_id: 0,
+                last_heartbeat: IggyTimestamp::now(),
             }),
         );
         trace!(
@@ -81,10 +151,11 @@ impl ConsumerGroup {
             self.topic_id
         );
         self.assign_partitions().await;
+        None
     }
 
     pub async fn delete_member(&mut self, member_id: u32) {
-        if self.members.remove(&member_id).is_some() {
+        if self.remove_member(member_id) {
             trace!(
                 "Deleted member with ID: {} in consumer group: {} for topic with ID: {}",
                 member_id,
@@ -95,31 +166,199 @@ impl ConsumerGroup {
         }
     }
 
+    /// Handles the disconnection of the member's client. The static member keeps its partitions until it's reclaimed
+    /// or the grace period expires, while any other member is deleted. Returns `true` if the member has been kept.
+    pub async fn depart_member(&mut self, member_id: u32, departed_at: IggyTimestamp) -> bool {
+        let Some(member) = self.members.get(&member_id) else {
+            return false;
+        };
+
+        if member.read().await.name.is_none() {
+            self.delete_member(member_id).await;
+            return false;
+        }
+
+        self.departed_members.insert(member_id, departed_at);
+        trace!(
+            "Static member with ID: {} has departed from consumer group: {} for topic with ID: {}",
+            member_id,
+            self.group_id,
+            self.topic_id
+        );
+        true
+    }
+
+    pub fn has_expired_departed_members(&self, departed_before: IggyTimestamp) -> bool {
+        self.departed_members
+            .values()
+            .any(|departed_at| departed_at.as_micros() < departed_before.as_micros())
+    }
+
+    /// Removes the static members which have departed before the given timestamp and haven't been reclaimed,
+    /// then rebalances the group once, returning the IDs of the removed members.
+    pub async fn expire_departed_members(&mut self, departed_before: IggyTimestamp) -> Vec<u32> {
+        let mut expired_members = self
+            .departed_members
+            .iter()
+            .filter(|(_, departed_at)| departed_at.as_micros() < departed_before.as_micros())
+            .map(|(member_id, _)| *member_id)
+            .collect::<Vec<_>>();
+        if expired_members.is_empty() {
+            return expired_members;
+        }
+
+        expired_members.sort();
+        for member_id in &expired_members {
+            self.remove_member(*member_id);
+            trace!(
+                "Expired departed static member with ID: {} in consumer group: {} for topic with ID: {}",
+                member_id,
+                self.group_id,
+                self.topic_id
+            );
+        }
+        self.evicted_members_count += expired_members.len() as u32;
+        self.assign_partitions().await;
+        expired_members
+    }
+
+    fn remove_member(&mut self, member_id: u32) -> bool {
+        let Some(member) = self.members.remove(&member_id) else {
+            return false;
+        };
+
+        self.departed_members.remove(&member_id);
+        if let Some(name) = member.into_inner().name {
+            if self.static_members.get(&name) == Some(&member_id) {
+                self.static_members.remove(&name);
+            }
+        }
+        true
+    }
+
+    pub async fn heartbeat(&self, member_id: u32) -> Result<(), IggyError> {
+        let member = self.members.get(&member_id);
+        if let Some(member) = member {
+            member.write().await.last_heartbeat = IggyTimestamp::now();
+            return Ok(());
+        }
+        Err(IggyError::ConsumerGroupMemberNotFound(
+            member_id,
+            self.group_id,
+            self.topic_id,
+        ))
+    }
+
+    /// Removes the members whose last heartbeat is older than the given timestamp and rebalances the group once,
+    /// returning the IDs of the evicted members.
+    pub async fn evict_stale_members(&mut self, heartbeat_to: IggyTimestamp) -> Vec<u32> {
+        let mut stale_members = Vec::new();
+        for member in self.members.values() {
+            let member = member.read().await;
+            // The departed static members are kept until their grace period expires.
+            if self.departed_members.contains_key(&member.id) {
+                continue;
+            }
+
+            if member.last_heartbeat.as_micros() < heartbeat_to.as_micros() {
+                stale_members.push(member.id);
+            }
+        }
+
+        if stale_members.is_empty() {
+            return stale_members;
+        }
+
+        stale_members.sort();
+        for member_id in &stale_members {
+            self.remove_member(*member_id);
+            trace!(
+                "Evicted stale member with ID: {} from consumer group: {} for topic with ID: {}",
+                member_id,
+                self.group_id,
+                self.topic_id
+            );
+        }
+        self.evicted_members_count += stale_members.len() as u32;
+        self.assign_partitions().await;
+        stale_members
+    }
+
+    /// Assigns the partitions to the members using the sticky strategy, which moves only the partitions it must:
+    /// each member keeps as many of its current partitions as its fair share allows,
+    /// and only the partitions left unassigned are distributed between the members below their share.
+    /// Each rebalance starts the next generation of the consumer group.
     async fn assign_partitions(&mut self) {
-        let mut members = self.members.values_mut().collect::<Vec<_>>();
+        self.generation += 1;
+        let members = self.members.values().collect::<Vec<_>>();
         if members.is_empty() {
             return;
         }
 
+        // In queue mode, the messages are leased one by one, thus each member can poll any partition.
+        if self.mode.is_queue() {
+            for member in members {
+                let mut member = member.write().await;
+                member.assign((1..=self.partitions_count).collect());
+                trace!("Assigned all partitions to member with ID: {} for topic with ID: {} in consumer group: {}, generation: {}",
+                    member.id, self.topic_id, self.group_id, self.generation)
+            }
+            return;
+        }
+
+        let mut current_assignments = Vec::with_capacity(members.len());
+        for member in &members {
+            let member = member.read().await;
+            current_assignments.push((member.id, member.get_partitions()));
+        }
+
+        // The members already owning the most partitions get the remainder of the division, so that fewer partitions are moved.
         let members_count = members.len() as u32;
-        for member in members.iter_mut() {
-            let mut member = member.write().await;
-            member.current_partition_index = 0;
-            member.current_partition_id = 0;
-            member.partitions.clear();
+        let share = self.partitions_count / members_count;
+        let mut remainder = self.partitions_count % members_count;
+        let mut order = (0..members.len()).collect::<Vec<_>>();
+        order.sort_by_key(|index| {
+            let (member_id, partitions) = &current_assignments[*index];
+            (std::cmp::Reverse(partitions.len()), *member_id)
+        });
+
+        let mut unassigned_partitions = (1..=self.partitions_count).collect::<BTreeSet<_>>();
+        let mut assignments = vec![Vec::new(); members.len()];
+        let mut quotas = vec![share; members.len()];
+        for index in order.iter().copied() {
+            if remainder > 0 {
+                quotas[index] += 1;
+                remainder -= 1;
+            }
+
+            let (_, partitions) = &mut current_assignments[index];
+            partitions.sort();
+            for partition_id in partitions.iter() {
+                if assignments[index].len() as u32 == quotas[index] {
+                    break;
+                }
+
+                if unassigned_partitions.remove(partition_id) {
+                    assignments[index].push(*partition_id);
+                }
+            }
+        }
+
+        order.sort_by_key(|index| current_assignments[*index].0);
+        for index in order {
+            while (assignments[index].len() as u32) < quotas[index] {
+                let Some(partition_id) = unassigned_partitions.pop_first() else {
+                    break;
+                };
+                assignments[index].push(partition_id);
+            }
         }
 
-        for partition_index in 0..self.partitions_count {
-            let partition_id = partition_index + 1;
-            let member_index = partition_index % members_count;
-            let member = members.get(member_index as usize).unwrap();
+        for (member, partitions) in members.iter().zip(assignments) {
             let mut member = member.write().await;
-            let member_partition_index = member.partitions.len() as u32;
-            member
-                .partitions
-                .insert(member_partition_index, partition_id);
-            trace!("Assigned partition ID: {} to member with ID: {} for topic with ID: {} in consumer group: {}",
-                partition_id, member.id, self.topic_id, self.group_id)
+            trace!("Assigned partitions: {:?} to member with ID: {} for topic with ID: {} in consumer group: {}, generation: {}",
+                partitions, member.id, self.topic_id, self.group_id, self.generation);
+            member.assign(partitions);
         }
     }
 }
@@ -129,6 +368,23 @@ impl ConsumerGroupMember {
         self.partitions.values().copied().collect()
     }
 
+    fn assign(&mut self, mut partitions: Vec<u32>) {
+        partitions.sort();
+        let mut current_partitions = self.get_partitions();
+        current_partitions.sort();
+        if current_partitions == partitions {
+            return;
+        }
+
+        self.current_partition_index = 0;
+        self.current_partition_id = 0;
+        self.partitions = partitions
+            .into_iter()
+            .enumerate()
+            .map(|(index, partition_id)| (index as u32, partition_id))
+            .collect();
+    }
+
     pub fn calculate_partition_id(&mut self) -> u32 {
         let partition_index = self.current_partition_index;
         let partition_id = if let Some(partition_id) = self.partitions.get(&partition_index) {
@@ -159,6 +415,7 @@ impl ConsumerGroupMember {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use iggy::utils::duration::IggyDuration;
 
     #[tokio::test]
     async fn should_calculate_partition_id_using_round_robin() {
@@ -168,10 +425,15 @@ mod tests {
             group_id: 1,
             name: "test".to_string(),
             partitions_count: 3,
+            mode: ConsumerGroupMode::Offset,
+            generation: 0,
+            evicted_members_count: 0,
             members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
         };
 
-        consumer_group.add_member(member_id).await;
+        consumer_group.add_member(member_id, None).await;
         for i in 0..1000 {
             let partition_id = consumer_group
                 .calculate_partition_id(member_id)
@@ -189,10 +451,15 @@ mod tests {
             group_id: 1,
             name: "test".to_string(),
             partitions_count: 3,
+            mode: ConsumerGroupMode::Offset,
+            generation: 0,
+            evicted_members_count: 0,
             members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
         };
 
-        consumer_group.add_member(member_id).await;
+        consumer_group.add_member(member_id, None).await;
         let member = consumer_group.members.get(&member_id).unwrap();
         let member = member.read().await;
         assert_eq!(
@@ -214,11 +481,16 @@ mod tests {
             group_id: 1,
             name: "test".to_string(),
             partitions_count: 3,
+            mode: ConsumerGroupMode::Offset,
+            generation: 0,
+            evicted_members_count: 0,
             members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
         };
 
-        consumer_group.add_member(member1_id).await;
-        consumer_group.add_member(member2_id).await;
+        consumer_group.add_member(member1_id, None).await;
+        consumer_group.add_member(member2_id, None).await;
         let member1 = consumer_group.members.get(&member1_id).unwrap();
         let member2 = consumer_group.members.get(&member2_id).unwrap();
         let member1 = member1.read().await;
@@ -251,11 +523,16 @@ mod tests {
             group_id: 1,
             name: "test".to_string(),
             partitions_count: 1,
+            mode: ConsumerGroupMode::Offset,
+            generation: 0,
+            evicted_members_count: 0,
             members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
         };
 
-        consumer_group.add_member(member1_id).await;
-        consumer_group.add_member(member2_id).await;
+        consumer_group.add_member(member1_id, None).await;
+        consumer_group.add_member(member2_id, None).await;
         let member1 = consumer_group.members.get(&member1_id).unwrap();
         let member2 = consumer_group.members.get(&member2_id).unwrap();
         let member1 = member1.read().await;
@@ -267,4 +544,210 @@ mod tests {
             assert_eq!(member2.partitions.len(), 1);
         }
     }
+
+    #[tokio::test]
+    async fn should_assign_all_partitions_to_each_member_in_queue_mode() {
+        let member1_id = 123;
+        let member2_id = 456;
+        let mut consumer_group = ConsumerGroup {
+            topic_id: 1,
+            group_id: 1,
+            name: "test".to_string(),
+            partitions_count: 2,
+            mode: ConsumerGroupMode::queue(IggyDuration::ONE_SECOND),
+            generation: 0,
+            evicted_members_count: 0,
+            members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
+        };
+
+        consumer_group.add_member(member1_id, None).await;
+        consumer_group.add_member(member2_id, None).await;
+        for member_id in [member1_id, member2_id] {
+            let member = consumer_group.members.get(&member_id).unwrap();
+            let mut member_partitions = member.read().await.get_partitions();
+            member_partitions.sort();
+            assert_eq!(member_partitions, vec![1, 2]);
+        }
+    }
+
+    #[tokio::test]
+    async fn should_move_only_required_partitions_when_members_join_and_leave() {
+        let mut consumer_group = ConsumerGroup {
+            topic_id: 1,
+            group_id: 1,
+            name: "test".to_string(),
+            partitions_count: 6,
+            mode: ConsumerGroupMode::Offset,
+            generation: 0,
+            evicted_members_count: 0,
+            members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
+        };
+
+        consumer_group.add_member(1, None).await;
+        consumer_group.add_member(2, None).await;
+        consumer_group.add_member(3, None).await;
+        assert_eq!(consumer_group.generation, 3);
+        let assignment = get_assignment(&consumer_group, &[1, 2, 3]).await;
+        for partitions in &assignment {
+            assert_eq!(partitions.len(), 2);
+        }
+
+        consumer_group.add_member(4, None).await;
+        assert_eq!(consumer_group.generation, 4);
+        let new_assignment = get_assignment(&consumer_group, &[1, 2, 3, 4]).await;
+        assert_eq!(get_moved_partitions_count(&assignment, &new_assignment), 1);
+        for (partitions, new_partitions) in assignment.iter().zip(&new_assignment) {
+            assert!(new_partitions.iter().all(|id| partitions.contains(id)));
+        }
+
+        consumer_group.delete_member(2).await;
+        assert_eq!(consumer_group.generation, 5);
+        let assignment = new_assignment;
+        let new_assignment = get_assignment(&consumer_group, &[1, 3, 4]).await;
+        for (member_index, partitions) in [0, 2, 3].into_iter().zip(&new_assignment) {
+            assert!(assignment[member_index]
+                .iter()
+                .all(|id| partitions.contains(id)));
+            assert_eq!(partitions.len(), 2);
+        }
+
+        consumer_group.add_member(1, None).await;
+        assert_eq!(consumer_group.generation, 5);
+    }
+
+    async fn get_assignment(consumer_group: &ConsumerGroup, member_ids: &[u32]) -> Vec<Vec<u32>> {
+        let mut assignment = Vec::new();
+        for member_id in member_ids {
+            assignment.push(
+                consumer_group
+                    .get_member_partitions(*member_id)
+                    .await
+                    .unwrap(),
+            );
+        }
+        let mut all_partitions = assignment.iter().flatten().copied().collect::<Vec<_>>();
+        all_partitions.sort();
+        assert_eq!(
+            all_partitions,
+            (1..=consumer_group.partitions_count).collect::<Vec<_>>()
+        );
+        assignment
+    }
+
+    fn get_moved_partitions_count(assignment: &[Vec<u32>], new_assignment: &[Vec<u32>]) -> usize {
+        new_assignment
+            .iter()
+            .enumerate()
+            .map(|(index, partitions)| {
+                partitions
+                    .iter()
+                    .filter(|id| assignment.get(index).is_none_or(|old| !old.contains(id)))
+                    .count()
+            })
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn should_evict_only_stale_members_and_rebalance_once() {
+        let mut consumer_group = ConsumerGroup {
+            topic_id: 1,
+            group_id: 1,
+            name: "test".to_string(),
+            partitions_count: 3,
+            mode: ConsumerGroupMode::Offset,
+            generation: 0,
+            evicted_members_count: 0,
+            members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
+        };
+
+        consumer_group.add_member(1, None).await;
+        consumer_group.add_member(2, None).await;
+        consumer_group.add_member(3, None).await;
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        let heartbeat_to = IggyTimestamp::now();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        consumer_group.heartbeat(2).await.unwrap();
+        assert!(consumer_group.heartbeat(4).await.is_err());
+
+        let evicted_members = consumer_group.evict_stale_members(heartbeat_to).await;
+        assert_eq!(evicted_members, vec![1, 3]);
+        assert_eq!(consumer_group.evicted_members_count, 2);
+        assert_eq!(consumer_group.generation, 4);
+        assert_eq!(
+            consumer_group.get_member_partitions(2).await.unwrap(),
+            vec![1, 2, 3]
+        );
+        assert!(consumer_group.get_member_partitions(1).await.is_err());
+
+        let evicted_members = consumer_group.evict_stale_members(heartbeat_to).await;
+        assert!(evicted_members.is_empty());
+        assert_eq!(consumer_group.generation, 4);
+    }
+
+    #[tokio::test]
+    async fn should_keep_partitions_of_departed_static_member_until_grace_period_expires() {
+        let mut consumer_group = ConsumerGroup {
+            topic_id: 1,
+            group_id: 1,
+            name: "test".to_string(),
+            partitions_count: 4,
+            mode: ConsumerGroupMode::Offset,
+            generation: 0,
+            evicted_members_count: 0,
+            members: HashMap::new(),
+            static_members: HashMap::new(),
+            departed_members: HashMap::new(),
+        };
+
+        consumer_group.add_member(1, Some("static")).await;
+        consumer_group.add_member(2, None).await;
+        let partitions = consumer_group.get_member_partitions(1).await.unwrap();
+        assert_eq!(consumer_group.generation, 2);
+
+        let departed_at = IggyTimestamp::now();
+        assert!(consumer_group.depart_member(1, departed_at).await);
+        assert_eq!(consumer_group.generation, 2);
+        assert_eq!(consumer_group.add_member(3, Some("static")).await, Some(1));
+        assert_eq!(consumer_group.generation, 2);
+        assert_eq!(
+            consumer_group.get_member_partitions(3).await.unwrap(),
+            partitions
+        );
+        assert!(consumer_group.get_member_partitions(1).await.is_err());
+
+        assert!(!consumer_group.depart_member(2, departed_at).await);
+        assert_eq!(consumer_group.generation, 3);
+        assert_eq!(
+            consumer_group.get_member_partitions(3).await.unwrap(),
+            vec![1, 2, 3, 4]
+        );
+
+        let departed_at = IggyTimestamp::now();
+        assert!(consumer_group.depart_member(3, departed_at).await);
+        assert!(!consumer_group.has_expired_departed_members(departed_at));
+        assert!(consumer_group
+            .expire_departed_members(departed_at)
+            .await
+            .is_empty());
+
+        let departed_before = IggyTimestamp::from(departed_at.as_micros() + 1);
+        assert!(consumer_group.has_expired_departed_members(departed_before));
+        assert_eq!(
+            consumer_group
+                .expire_departed_members(departed_before)
+                .await,
+            vec![3]
+        );
+        assert_eq!(consumer_group.generation, 4);
+        assert_eq!(consumer_group.evicted_members_count, 1);
+        assert!(consumer_group.get_members().is_empty());
+        assert_eq!(consumer_group.add_member(4, Some("static")).await, None);
+        assert_eq!(consumer_group.generation, 5);
+    }
 }
@@ -1,12 +1,15 @@
 use crate::streaming::topics::consumer_group::ConsumerGroup;
 use crate::streaming::topics::topic::Topic;
+use iggy::consumer_groups::consumer_group_mode::ConsumerGroupMode;
 use iggy::error::IggyError;
 use iggy::identifier::{IdKind, Identifier};
 use iggy::locking::IggySharedMutFn;
+use iggy::models::consumer_group::ConsumerGroupMembership;
 use iggy::utils::text;
+use iggy::utils::timestamp::IggyTimestamp;
 use std::sync::atomic::Ordering;
 use tokio::sync::RwLock;
-use tracing::info;
+use tracing::{info, warn};
 
 impl Topic {
     pub async fn reassign_consumer_groups(&mut self) {
@@ -67,6 +70,7 @@ impl Topic {
         &mut self,
         group_id: Option<u32>,
         name: &str,
+        mode: ConsumerGroupMode,
     ) -> Result<&RwLock<ConsumerGroup>, IggyError> {
         let name = text::to_lowercase_non_whitespace(name);
         if self.consumer_groups_ids.contains_key(&name) {
@@ -102,12 +106,12 @@ impl Topic {
         }
 
         let consumer_group =
-            ConsumerGroup::new(self.topic_id, id, &name, self.partitions.len() as u32);
+            ConsumerGroup::new(self.topic_id, id, &name, self.partitions.len() as u32, mode);
         self.consumer_groups.insert(id, RwLock::new(consumer_group));
         self.consumer_groups_ids.insert(name, id);
         info!(
-            "Created consumer group with ID: {} for topic with ID: {} and stream with ID: {}.",
-            id, self.topic_id, self.stream_id
+            "Created consumer group with ID: {}, mode: {} for topic with ID: {} and stream with ID: {}.",
+            id, mode, self.topic_id, self.stream_id
         );
         self.get_consumer_group_by_id(id)
     }
@@ -146,6 +150,13 @@ impl Topic {
                         .delete_consumer_offset(&offset.path)
                         .await?;
                 }
+                let _leases_lock = partition.consumer_group_leases_lock.lock().await;
+                if let Some((_, leases)) = partition.consumer_group_leases.remove(&group_id) {
+                    self.storage
+                        .partition
+                        .delete_consumer_offset(&leases.path)
+                        .await?;
+                }
             }
 
             info!(
@@ -161,15 +172,29 @@ impl Topic {
         &self,
         group_id: &Identifier,
         member_id: u32,
-    ) -> Result<(), IggyError> {
+        member_name: Option<&str>,
+    ) -> Result<ConsumerGroupMembership, IggyError> {
         let consumer_group = self.get_consumer_group(group_id)?;
         let mut consumer_group = consumer_group.write().await;
-        consumer_group.add_member(member_id).await;
+        self.expire_departed_members(&mut consumer_group).await?;
+        if let Some(previous_member_id) = consumer_group.add_member(member_id, member_name).await {
+            self.release_member_message_leases(&consumer_group, previous_member_id)
+                .await?;
+            info!(
+                "Member with ID: {} has reclaimed the partitions of static member: {} with ID: {} in consumer group with ID: {} for topic with ID: {} and stream with ID: {}.",
+                member_id, member_name.unwrap_or_default(), previous_member_id, group_id, self.topic_id, self.stream_id
+            );
+        }
+        let partitions = consumer_group.get_member_partitions(member_id).await?;
         info!(
-            "Member with ID: {} has joined consumer group with ID: {} for topic with ID: {} and stream with ID: {}.",
-            member_id, group_id, self.topic_id, self.stream_id
+            "Member with ID: {} has joined consumer group with ID: {} for topic with ID: {} and stream with ID: {}, generation: {}, partitions: {:?}.",
+            member_id, group_id, self.topic_id, self.stream_id, consumer_group.generation, partitions
         );
-        Ok(())
+        Ok(ConsumerGroupMembership {
+            member_id,
+            generation: consumer_group.generation,
+            partitions,
+        })
     }
 
     pub async fn leave_consumer_group(
@@ -180,12 +205,146 @@ impl Topic {
         let consumer_group = self.get_consumer_group(group_id)?;
         let mut consumer_group = consumer_group.write().await;
         consumer_group.delete_member(member_id).await;
+        self.release_member_message_leases(&consumer_group, member_id)
+            .await?;
         info!(
             "Member with ID: {} has left consumer group with ID: {} for topic with ID: {} and stream with ID: {}.",
             member_id, group_id, self.topic_id, self.stream_id
         );
         Ok(())
     }
+
+    /// Handles the disconnection of the member's client. Unlike leaving the consumer group, the static member
+    /// keeps its partitions for the grace period, so that the reconnecting client can reclaim them.
+    pub async fn disconnect_consumer_group_member(
+        &self,
+        group_id: &Identifier,
+        member_id: u32,
+    ) -> Result<(), IggyError> {
+        let consumer_group = self.get_consumer_group(group_id)?;
+        let mut consumer_group = consumer_group.write().await;
+        let departed = consumer_group
+            .depart_member(member_id, IggyTimestamp::now())
+            .await;
+        self.release_member_message_leases(&consumer_group, member_id)
+            .await?;
+        if departed {
+            info!(
+                "Static member with ID: {} has disconnected from consumer group with ID: {} for topic with ID: {} and stream with ID: {}, its partitions will be kept for: {}.",
+                member_id, group_id, self.topic_id, self.stream_id, self.config.consumer_group.static_member_grace_period
+            );
+        } else {
+            info!(
+                "Member with ID: {} has disconnected from consumer group with ID: {} for topic with ID: {} and stream with ID: {}.",
+                member_id, group_id, self.topic_id, self.stream_id
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn heartbeat_consumer_group(
+        &self,
+        group_id: &Identifier,
+        member_id: u32,
+    ) -> Result<ConsumerGroupMembership, IggyError> {
+        let consumer_group = self.get_consumer_group(group_id)?;
+        self.expire_departed_consumer_group_members(consumer_group)
+            .await?;
+        let consumer_group = consumer_group.read().await;
+        consumer_group.heartbeat(member_id).await?;
+        let partitions = consumer_group.get_member_partitions(member_id).await?;
+        Ok(ConsumerGroupMembership {
+            member_id,
+            generation: consumer_group.generation,
+            partitions,
+        })
+    }
+
+    /// Evicts the members which haven't sent a heartbeat since the given timestamp from all the consumer groups,
+    /// returning the IDs of the consumer groups and their evicted members.
+    pub async fn evict_stale_consumer_group_members(
+        &self,
+        heartbeat_to: IggyTimestamp,
+    ) -> Result<Vec<(u32, u32)>, IggyError> {
+        let mut evicted_members = Vec::new();
+        for consumer_group in self.consumer_groups.values() {
+            let mut consumer_group = consumer_group.write().await;
+            self.expire_departed_members(&mut consumer_group).await?;
+            for member_id in consumer_group.evict_stale_members(heartbeat_to).await {
+                self.release_member_message_leases(&consumer_group, member_id)
+                    .await?;
+                warn!(
+                    "Member with ID: {} has been evicted from consumer group with ID: {} for topic with ID: {} and stream with ID: {}, last heartbeat before: {}.",
+                    member_id, consumer_group.group_id, self.topic_id, self.stream_id, heartbeat_to
+                );
+                evicted_members.push((consumer_group.group_id, member_id));
+            }
+        }
+        Ok(evicted_members)
+    }
+
+    /// Removes the departed static members whose grace period has expired, which is checked lazily
+    /// whenever the consumer group is used, as the partitions kept for them matter only to the active members.
+    pub async fn expire_departed_consumer_group_members(
+        &self,
+        consumer_group: &RwLock<ConsumerGroup>,
+    ) -> Result<(), IggyError> {
+        if !consumer_group
+            .read()
+            .await
+            .has_expired_departed_members(self.get_departed_before())
+        {
+            return Ok(());
+        }
+
+        let mut consumer_group = consumer_group.write().await;
+        self.expire_departed_members(&mut consumer_group).await
+    }
+
+    async fn expire_departed_members(
+        &self,
+        consumer_group: &mut ConsumerGroup,
+    ) -> Result<(), IggyError> {
+        let departed_before = self.get_departed_before();
+        for member_id in consumer_group
+            .expire_departed_members(departed_before)
+            .await
+        {
+            warn!(
+                "Static member with ID: {} has not reconnected to consumer group with ID: {} for topic with ID: {} and stream with ID: {} within the grace period, its partitions have been reassigned.",
+                member_id, consumer_group.group_id, self.topic_id, self.stream_id
+            );
+        }
+        Ok(())
+    }
+
+    fn get_departed_before(&self) -> IggyTimestamp {
+        let grace_period = self.config.consumer_group.static_member_grace_period;
+        IggyTimestamp::from(
+            IggyTimestamp::now()
+                .as_micros()
+                .saturating_sub(grace_period.as_micros()),
+        )
+    }
+
+    async fn release_member_message_leases(
+        &self,
+        consumer_group: &ConsumerGroup,
+        member_id: u32,
+    ) -> Result<(), IggyError> {
+        if !consumer_group.mode.is_queue() {
+            return Ok(());
+        }
+
+        for partition in self.partitions.values() {
+            partition
+                .read()
+                .await
+                .release_message_leases(consumer_group.group_id, member_id, None)
+                .await?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -205,7 +364,9 @@ mod tests {
         let name = "test";
         let mut topic = get_topic();
         let topic_id = topic.topic_id;
-        let result = topic.create_consumer_group(Some(group_id), name).await;
+        let result = topic
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
+            .await;
         assert!(result.is_ok());
         {
             let created_consumer_group = result.unwrap().read().await;
@@ -233,10 +394,14 @@ mod tests {
         let group_id = 1;
         let name = "test";
         let mut topic = get_topic();
-        let result = topic.create_consumer_group(Some(group_id), name).await;
+        let result = topic
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
+            .await;
         assert!(result.is_ok());
         assert_eq!(topic.consumer_groups.len(), 1);
-        let result = topic.create_consumer_group(Some(group_id), "test2").await;
+        let result = topic
+            .create_consumer_group(Some(group_id), "test2", ConsumerGroupMode::Offset)
+            .await;
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(matches!(err, IggyError::ConsumerGroupIdAlreadyExists(_, _)));
@@ -248,11 +413,15 @@ mod tests {
         let group_id = 1;
         let name = "test";
         let mut topic = get_topic();
-        let result = topic.create_consumer_group(Some(group_id), name).await;
+        let result = topic
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
+            .await;
         assert!(result.is_ok());
         assert_eq!(topic.consumer_groups.len(), 1);
         let group_id = group_id + 1;
-        let result = topic.create_consumer_group(Some(group_id), name).await;
+        let result = topic
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
+            .await;
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(matches!(
@@ -267,7 +436,9 @@ mod tests {
         let group_id = 1;
         let name = "test";
         let mut topic = get_topic();
-        let result = topic.create_consumer_group(Some(group_id), name).await;
+        let result = topic
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
+            .await;
         assert!(result.is_ok());
         assert_eq!(topic.consumer_groups.len(), 1);
         let result = topic
@@ -282,7 +453,9 @@ mod tests {
         let group_id = 1;
         let name = "test";
         let mut topic = get_topic();
-        let result = topic.create_consumer_group(Some(group_id), name).await;
+        let result = topic
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
+            .await;
         assert!(result.is_ok());
         assert_eq!(topic.consumer_groups.len(), 1);
         let group_id = group_id + 1;
@@ -300,11 +473,11 @@ mod tests {
         let member_id = 1;
         let mut topic = get_topic();
         topic
-            .create_consumer_group(Some(group_id), name)
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
             .await
             .unwrap();
         let result = topic
-            .join_consumer_group(&Identifier::numeric(group_id).unwrap(), member_id)
+            .join_consumer_group(&Identifier::numeric(group_id).unwrap(), member_id, None)
             .await;
         assert!(result.is_ok());
         let consumer_group = topic
@@ -323,11 +496,11 @@ mod tests {
         let member_id = 1;
         let mut topic = get_topic();
         topic
-            .create_consumer_group(Some(group_id), name)
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
             .await
             .unwrap();
         topic
-            .join_consumer_group(&Identifier::numeric(group_id).unwrap(), member_id)
+            .join_consumer_group(&Identifier::numeric(group_id).unwrap(), member_id, None)
             .await
             .unwrap();
         let result = topic
@@ -343,6 +516,47 @@ mod tests {
         assert!(members.is_empty())
     }
 
+    #[tokio::test]
+    async fn should_evict_member_which_stopped_sending_heartbeats() {
+        let group_id = 1;
+        let name = "test";
+        let mut topic = get_topic();
+        topic
+            .create_consumer_group(Some(group_id), name, ConsumerGroupMode::Offset)
+            .await
+            .unwrap();
+        for member_id in [1, 2] {
+            topic
+                .join_consumer_group(&Identifier::numeric(group_id).unwrap(), member_id, None)
+                .await
+                .unwrap();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        let heartbeat_to = IggyTimestamp::now();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        let membership = topic
+            .heartbeat_consumer_group(&Identifier::numeric(group_id).unwrap(), 2)
+            .await
+            .unwrap();
+        assert_eq!(membership.generation, 2);
+
+        let evicted_members = topic
+            .evict_stale_consumer_group_members(heartbeat_to)
+            .await
+            .unwrap();
+        assert_eq!(evicted_members, vec![(group_id, 1)]);
+        let membership = topic
+            .heartbeat_consumer_group(&Identifier::numeric(group_id).unwrap(), 2)
+            .await
+            .unwrap();
+        assert_eq!(membership.generation, 3);
+        assert_eq!(membership.partitions, vec![1, 2, 3]);
+        assert!(topic
+            .heartbeat_consumer_group(&Identifier::numeric(group_id).unwrap(), 1)
+            .await
+            .is_err());
+    }
+
     fn get_topic() -> Topic {
         let storage = Arc::new(get_test_system_storage());
         let stream_id = 1;
@@ -4,6 +4,7 @@ use iggy::consumer::Consumer;
 use iggy::error::IggyError;
 use iggy::locking::IggySharedMutFn;
 use iggy::models::consumer_offset_info::ConsumerOffsetInfo;
+use iggy::utils::duration::IggyDuration;
 
 impl Topic {
     pub async fn store_consumer_offset(
@@ -34,6 +35,72 @@ impl Topic {
         partition.store_consumer_offset(consumer, offset).await
     }
 
+    pub async fn ack_message(
+        &self,
+        consumer: &Consumer,
+        offset: u64,
+        partition_id: Option<u32>,
+        client_id: u32,
+    ) -> Result<(), IggyError> {
+        let (polling_consumer, partition_id) = self
+            .resolve_consumer_with_partition_id(consumer, client_id, partition_id, false)
+            .await?;
+        let PollingConsumer::ConsumerGroup(consumer_group_id, member_id) = polling_consumer else {
+            return Err(IggyError::InvalidConsumerGroupId);
+        };
+
+        if self
+            .get_queue_visibility_timeout(polling_consumer)
+            .await?
+            .is_none()
+        {
+            return Err(IggyError::ConsumerGroupNotInQueueMode(
+                consumer_group_id,
+                self.topic_id,
+            ));
+        }
+
+        let partition = self.get_partition(partition_id)?;
+        partition
+            .read()
+            .await
+            .ack_message(consumer_group_id, member_id, offset)
+            .await?;
+        let mut partition = partition.write().await;
+        partition.evict_delivery_attempts(offset).await
+    }
+
+    pub async fn release_message_lease(
+        &self,
+        consumer: PollingConsumer,
+        offset: u64,
+        partition_id: u32,
+    ) -> Result<(), IggyError> {
+        let PollingConsumer::ConsumerGroup(consumer_group_id, member_id) = consumer else {
+            return Ok(());
+        };
+
+        let partition = self.get_partition(partition_id)?;
+        let partition = partition.read().await;
+        partition
+            .release_message_leases(consumer_group_id, member_id, Some(offset))
+            .await
+    }
+
+    /// Returns the visibility timeout if the consumer is a member of the consumer group in queue mode.
+    pub async fn get_queue_visibility_timeout(
+        &self,
+        consumer: PollingConsumer,
+    ) -> Result<Option<IggyDuration>, IggyError> {
+        let PollingConsumer::ConsumerGroup(consumer_group_id, _) = consumer else {
+            return Ok(None);
+        };
+
+        let consumer_group = self.get_consumer_group_by_id(consumer_group_id)?;
+        let visibility_timeout = consumer_group.read().await.mode.visibility_timeout();
+        Ok(visibility_timeout)
+    }
+
     pub async fn get_consumer_offset(
         &self,
         consumer: &Consumer,
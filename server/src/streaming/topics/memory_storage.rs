@@ -0,0 +1,48 @@
+use crate::state::system::TopicState;
+use crate::streaming::persistence::memory::MemoryPersister;
+use crate::streaming::storage::TopicStorage;
+use crate::streaming::topics::topic::Topic;
+use async_trait::async_trait;
+use iggy::error::IggyError;
+use iggy::locking::IggySharedMutFn;
+use std::sync::Arc;
+use tracing::info;
+
+/// The topic storage for the in-memory backend, the topics have no files of their own.
+#[derive(Debug)]
+pub struct MemoryTopicStorage {
+    files: Arc<MemoryPersister>,
+}
+
+impl MemoryTopicStorage {
+    pub fn new(files: Arc<MemoryPersister>) -> Self {
+        Self { files }
+    }
+}
+
+#[async_trait]
+impl TopicStorage for MemoryTopicStorage {
+    async fn load(&self, topic: &mut Topic, _state: TopicState) -> Result<(), IggyError> {
+        // The in-memory storage doesn't outlive the server, so there's never a topic to load.
+        Err(IggyError::TopicIdNotFound(topic.topic_id, topic.stream_id))
+    }
+
+    async fn save(&self, topic: &Topic) -> Result<(), IggyError> {
+        for (_, partition) in topic.partitions.iter() {
+            let partition = partition.write().await;
+            partition.persist().await?;
+        }
+
+        info!("Saved topic {topic} in memory.");
+        Ok(())
+    }
+
+    async fn delete(&self, topic: &Topic) -> Result<(), IggyError> {
+        let files_count = self.files.delete_all(&topic.path);
+        info!(
+            "Deleted topic with ID: {} for stream with ID: {} along with {files_count} files from memory.",
+            topic.topic_id, topic.stream_id
+        );
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::streaming::batching::appendable_batch_info::AppendableBatchInfo;
-use crate::streaming::models::messages::RetainedMessage;
+use crate::streaming::models::messages::{PolledBatches, RetainedMessage};
 use crate::streaming::polling_consumer::PollingConsumer;
 use crate::streaming::sizeable::Sizeable;
 use crate::streaming::topics::topic::Topic;
@@ -7,9 +7,11 @@ use crate::streaming::utils::file::folder_size;
 use crate::streaming::utils::hash;
 use iggy::error::IggyError;
 use iggy::locking::IggySharedMutFn;
-use iggy::messages::poll_messages::{PollingKind, PollingStrategy};
-use iggy::messages::send_messages::{Message, Partitioning, PartitioningKind};
+use iggy::messages::message_filter::MessageFilter;
+use iggy::messages::poll_messages::{IsolationLevel, PollingKind, PollingStrategy};
+use iggy::messages::send_messages::{CompressedMessages, Message, Partitioning, PartitioningKind};
 use iggy::models::messages::PolledMessages;
+use iggy::utils::duration::IggyDuration;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
 use std::collections::HashMap;
@@ -28,7 +30,8 @@ impl Topic {
         partition_id: u32,
         strategy: PollingStrategy,
         count: u32,
-    ) -> Result<PolledMessages, IggyError> {
+        isolation: IsolationLevel,
+    ) -> Result<(PolledMessages, Option<u64>), IggyError> {
         if !self.has_partitions() {
             return Err(IggyError::NoPartitions(self.topic_id, self.stream_id));
         }
@@ -57,10 +60,104 @@ impl Topic {
             PollingKind::Next => partition.get_next_messages(consumer, count).await,
         }?;
 
+        let (messages, last_scanned_offset) =
+            partition.filter_visible_messages(messages, isolation)?;
         let messages = messages
             .into_iter()
             .map(|msg| msg.to_polled_message())
             .collect::<Result<Vec<_>, IggyError>>()?;
+        Ok((
+            PolledMessages {
+                partition_id,
+                current_offset: partition.current_offset,
+                messages,
+            },
+            last_scanned_offset,
+        ))
+    }
+
+    /// Returns the batches which can be sent as they are stored in the segment log files,
+    /// or `None` if the messages have to be loaded, e.g. they're cached or not persisted yet.
+    pub async fn get_batches(
+        &self,
+        consumer: PollingConsumer,
+        partition_id: u32,
+        strategy: PollingStrategy,
+        count: u32,
+    ) -> Result<Option<PolledBatches>, IggyError> {
+        let partition = self.get_partition(partition_id)?;
+        let partition = partition.read().await;
+        partition.get_batches(consumer, strategy, count).await
+    }
+
+    pub async fn get_filtered_messages(
+        &self,
+        consumer: PollingConsumer,
+        partition_id: u32,
+        strategy: PollingStrategy,
+        count: u32,
+        filter: &MessageFilter,
+        isolation: IsolationLevel,
+    ) -> Result<(PolledMessages, Option<u64>), IggyError> {
+        if !self.has_partitions() {
+            return Err(IggyError::NoPartitions(self.topic_id, self.stream_id));
+        }
+
+        let partition = self.partitions.get(&partition_id);
+        if partition.is_none() {
+            return Err(IggyError::PartitionNotFound(
+                partition_id,
+                self.topic_id,
+                self.stream_id,
+            ));
+        }
+
+        let partition = partition.unwrap();
+        let partition = partition.read().await;
+        let (messages, last_scanned_offset) = partition
+            .get_filtered_messages(consumer, strategy, count, filter, isolation)
+            .await?;
+
+        let messages = messages
+            .into_iter()
+            .map(|msg| msg.to_polled_message())
+            .collect::<Result<Vec<_>, IggyError>>()?;
+        Ok((
+            PolledMessages {
+                partition_id,
+                current_offset: partition.current_offset,
+                messages,
+            },
+            last_scanned_offset,
+        ))
+    }
+
+    pub async fn lease_messages(
+        &self,
+        consumer: PollingConsumer,
+        partition_id: u32,
+        count: u32,
+        visibility_timeout: IggyDuration,
+        isolation: IsolationLevel,
+    ) -> Result<PolledMessages, IggyError> {
+        let PollingConsumer::ConsumerGroup(consumer_group_id, member_id) = consumer else {
+            return Err(IggyError::InvalidConsumerGroupId);
+        };
+
+        let partition = self.get_partition(partition_id)?;
+        let partition = partition.read().await;
+        let messages = partition
+            .lease_messages(
+                consumer_group_id,
+                member_id,
+                count,
+                visibility_timeout,
+                isolation,
+            )
+            .await?
+            .into_iter()
+            .map(|msg| msg.to_polled_message())
+            .collect::<Result<Vec<_>, IggyError>>()?;
         Ok(PolledMessages {
             partition_id,
             current_offset: partition.current_offset,
@@ -68,6 +165,51 @@ impl Topic {
         })
     }
 
+    pub async fn register_failed_delivery(
+        &self,
+        partition_id: u32,
+        offset: u64,
+        max_delivery_attempts: u32,
+    ) -> Result<Option<(Arc<RetainedMessage>, u32)>, IggyError> {
+        self.partitions
+            .get(&partition_id)
+            .ok_or_else(|| {
+                IggyError::PartitionNotFound(partition_id, self.topic_id, self.stream_id)
+            })?
+            .write()
+            .await
+            .register_failed_delivery(offset, max_delivery_attempts)
+            .await
+    }
+
+    pub async fn mark_as_poisoned(&self, partition_id: u32, offset: u64) -> Result<(), IggyError> {
+        self.partitions
+            .get(&partition_id)
+            .ok_or_else(|| {
+                IggyError::PartitionNotFound(partition_id, self.topic_id, self.stream_id)
+            })?
+            .write()
+            .await
+            .mark_as_poisoned(offset)
+            .await
+    }
+
+    pub async fn cancel_dead_letter(
+        &self,
+        partition_id: u32,
+        offset: u64,
+    ) -> Result<(), IggyError> {
+        self.partitions
+            .get(&partition_id)
+            .ok_or_else(|| {
+                IggyError::PartitionNotFound(partition_id, self.topic_id, self.stream_id)
+            })?
+            .write()
+            .await
+            .cancel_dead_letter(offset);
+        Ok(())
+    }
+
     pub async fn append_messages(
         &self,
         batch_size: u64,
@@ -86,21 +228,37 @@ impl Topic {
             return Ok(());
         }
 
-        let partition_id = match partitioning.kind {
-            PartitioningKind::Balanced => self.get_next_partition_id(),
-            PartitioningKind::PartitionId => {
-                u32::from_le_bytes(partitioning.value[..partitioning.length as usize].try_into()?)
-            }
-            PartitioningKind::MessagesKey => {
-                self.calculate_partition_id_by_messages_key_hash(&partitioning.value)
-            }
-        };
-
+        let partition_id = self.get_partition_id(&partitioning)?;
         let appendable_batch_info = AppendableBatchInfo::new(batch_size, partition_id);
         self.append_messages_to_partition(appendable_batch_info, messages)
             .await
     }
 
+    pub async fn append_compressed_messages(
+        &self,
+        partitioning: Partitioning,
+        compressed_messages: CompressedMessages,
+    ) -> Result<(), IggyError> {
+        if !self.has_partitions() {
+            return Err(IggyError::NoPartitions(self.topic_id, self.stream_id));
+        }
+
+        if self.is_full() {
+            return Err(IggyError::TopicFull(self.topic_id, self.stream_id));
+        }
+
+        let partition_id = self.get_partition_id(&partitioning)?;
+        self.partitions
+            .get(&partition_id)
+            .ok_or_else(|| {
+                IggyError::PartitionNotFound(partition_id, self.topic_id, self.stream_id)
+            })?
+            .write()
+            .await
+            .append_compressed_messages(compressed_messages)
+            .await
+    }
+
     pub async fn flush_unsaved_buffer(
         &self,
         partition_id: u32,
@@ -109,7 +267,7 @@ impl Topic {
         let partition = self.partitions.get(&partition_id);
         partition
             .ok_or_else(|| {
-                IggyError::PartitionNotFound(partition_id, self.stream_id, self.stream_id)
+                IggyError::PartitionNotFound(partition_id, self.topic_id, self.stream_id)
             })?
             .write()
             .await
@@ -139,6 +297,19 @@ impl Topic {
         Ok(())
     }
 
+    pub(crate) fn get_partition_id(&self, partitioning: &Partitioning) -> Result<u32, IggyError> {
+        let partition_id = match partitioning.kind {
+            PartitioningKind::Balanced => self.get_next_partition_id(),
+            PartitioningKind::PartitionId => {
+                u32::from_le_bytes(partitioning.value[..partitioning.length as usize].try_into()?)
+            }
+            PartitioningKind::MessagesKey => {
+                self.calculate_partition_id_by_messages_key_hash(&partitioning.value)
+            }
+        };
+        Ok(partition_id)
+    }
+
     fn get_next_partition_id(&self) -> u32 {
         let mut partition_id = self.current_partition_id.fetch_add(1, Ordering::SeqCst);
         let partitions_count = self.partitions.len() as u32;
@@ -1,9 +1,11 @@
 pub mod consumer_group;
 pub mod consumer_groups;
 pub mod consumer_offsets;
+pub mod memory_storage;
 pub mod messages;
 pub mod partitions;
 pub mod persistence;
 pub mod segments;
 pub mod storage;
 pub mod topic;
+pub mod transactions;
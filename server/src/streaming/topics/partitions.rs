@@ -5,7 +5,7 @@ use iggy::locking::IggySharedMut;
 use iggy::locking::IggySharedMutFn;
 use iggy::utils::timestamp::IggyTimestamp;
 
-const MAX_PARTITIONS_COUNT: u32 = 100_000;
+pub(crate) const MAX_PARTITIONS_COUNT: u32 = 100_000;
 
 impl Topic {
     pub fn has_partitions(&self) -> bool {
@@ -36,6 +36,7 @@ impl Topic {
                 self.config.clone(),
                 self.storage.clone(),
                 self.message_expiry,
+                Topic::get_compression_algorithm(self.compression_algorithm, &self.config),
                 self.messages_count_of_parent_stream.clone(),
                 self.messages_count.clone(),
                 self.size_of_parent_stream.clone(),
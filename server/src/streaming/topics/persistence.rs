@@ -30,6 +30,7 @@ impl Topic {
             for segment in partition.get_segments_mut() {
                 saved_messages_number += segment.persist_messages().await?;
             }
+            partition.save_producer_sequences().await?;
         }
 
         Ok(saved_messages_number)
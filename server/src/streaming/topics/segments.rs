@@ -1,5 +1,10 @@
+use crate::streaming::partitions::compaction::get_tombstone_expiry;
 use crate::streaming::topics::topic::Topic;
+use crate::streaming::utils::keyring::Keyring;
+use iggy::error::IggyError;
 use iggy::locking::IggySharedMutFn;
+use iggy::utils::duration::IggyDuration;
+use iggy::utils::timestamp::IggyTimestamp;
 
 impl Topic {
     pub async fn get_segments_count(&self) -> u32 {
@@ -10,4 +15,68 @@ impl Topic {
 
         segments_count
     }
+
+    /// Closes the open segments of the partitions which are older than the configured `segment.max_age`.
+    /// Returns the number of the closed segments.
+    pub async fn close_aged_segments(&self, now: IggyTimestamp) -> Result<u32, IggyError> {
+        let mut closed_segments = 0;
+        for partition in self.partitions.values() {
+            if partition.write().await.close_aged_segment(now).await? {
+                closed_segments += 1;
+            }
+        }
+
+        Ok(closed_segments)
+    }
+
+    /// Compacts the closed segments of the partitions, keeping only the newest message for each key.
+    /// The messages are read holding only the read lock of the partition, while the write lock is held
+    /// just to update the compaction index and to replace the files of each compacted segment.
+    /// Returns the number of the removed messages.
+    pub async fn compact_segments(
+        &self,
+        tombstone_retention: IggyDuration,
+        now: IggyTimestamp,
+    ) -> Result<u64, IggyError> {
+        let tombstone_expiry = get_tombstone_expiry(tombstone_retention, now);
+        let mut removed_messages = 0;
+        for partition in self.partitions.values() {
+            let message_keys = partition.read().await.read_unindexed_message_keys().await?;
+            let segments_to_compact = {
+                let mut partition = partition.write().await;
+                partition.index_message_keys(message_keys, tombstone_expiry);
+                partition.get_segments_to_compact()
+            };
+
+            for start_offset in segments_to_compact {
+                let compacted_segment = partition
+                    .read()
+                    .await
+                    .prepare_segment_compaction(start_offset, tombstone_expiry)
+                    .await?;
+                removed_messages += partition
+                    .write()
+                    .await
+                    .complete_segment_compaction(start_offset, compacted_segment, tombstone_expiry)
+                    .await?;
+            }
+        }
+
+        Ok(removed_messages)
+    }
+
+    /// Re-encrypts the messages of the closed segments with the key currently used for the topic.
+    pub async fn reencrypt_segments(&self, keyring: &Keyring) -> Result<u64, IggyError> {
+        let key_id = keyring.get_key_id(self.stream_id, self.topic_id);
+        let mut reencrypted_messages = 0;
+        for partition in self.partitions.values() {
+            reencrypted_messages += partition
+                .write()
+                .await
+                .reencrypt_segments(keyring, key_id)
+                .await?;
+        }
+
+        Ok(reencrypted_messages)
+    }
 }
@@ -44,7 +44,12 @@ impl TopicStorage for FileTopicStorage {
         topic.message_expiry = message_expiry;
         topic.max_topic_size = max_topic_size;
         topic.compression_algorithm = state.compression_algorithm;
+        let compression_algorithm =
+            Topic::get_compression_algorithm(state.compression_algorithm, &topic.config);
         topic.replication_factor = state.replication_factor.unwrap_or(1);
+        topic.dead_letter_queue = state.dead_letter_queue;
+        topic.cleanup_policy = state.cleanup_policy;
+        topic.max_messages = state.max_messages;
 
         let dir_entries = fs::read_dir(&topic.partitions_path).await
             .with_context(|| format!("Failed to read partition with ID: {} for stream with ID: {} for topic with ID: {} and path: {}",
@@ -91,6 +96,7 @@ impl TopicStorage for FileTopicStorage {
                 topic.config.clone(),
                 topic.storage.clone(),
                 message_expiry,
+                compression_algorithm,
                 topic.messages_count_of_parent_stream.clone(),
                 topic.messages_count.clone(),
                 topic.size_of_parent_stream.clone(),
@@ -144,6 +150,7 @@ impl TopicStorage for FileTopicStorage {
                     topic.config.clone(),
                     topic.storage.clone(),
                     message_expiry,
+                    compression_algorithm,
                     topic.messages_count_of_parent_stream.clone(),
                     topic.messages_count.clone(),
                     topic.size_of_parent_stream.clone(),
@@ -196,6 +203,7 @@ impl TopicStorage for FileTopicStorage {
                 consumer_group.id,
                 &consumer_group.name,
                 topic.get_partitions_count(),
+                consumer_group.mode,
             );
             topic
                 .consumer_groups_ids
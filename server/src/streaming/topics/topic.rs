@@ -8,6 +8,8 @@ use iggy::compression::compression_algorithm::CompressionAlgorithm;
 use iggy::consumer::{Consumer, ConsumerKind};
 use iggy::error::IggyError;
 use iggy::locking::IggySharedMut;
+use iggy::models::cleanup_policy::CleanupPolicy;
+use iggy::models::dead_letter_queue::DeadLetterQueue;
 use iggy::utils::byte_size::IggyByteSize;
 use iggy::utils::expiry::IggyExpiry;
 use iggy::utils::timestamp::IggyTimestamp;
@@ -43,6 +45,10 @@ pub struct Topic {
     pub compression_algorithm: CompressionAlgorithm,
     pub max_topic_size: MaxTopicSize,
     pub replication_factor: u8,
+    pub dead_letter_queue: Option<DeadLetterQueue>,
+    pub cleanup_policy: CleanupPolicy,
+    /// The maximum number of messages retained by the topic, 0 means unlimited.
+    pub max_messages: u64,
     pub created_at: IggyTimestamp,
 }
 
@@ -115,6 +121,9 @@ impl Topic {
             max_topic_size: Topic::get_max_topic_size(max_topic_size, &config)?,
             compression_algorithm,
             replication_factor,
+            dead_letter_queue: None,
+            cleanup_policy: CleanupPolicy::default(),
+            max_messages: 0,
             config,
             created_at: IggyTimestamp::now(),
         };
@@ -153,6 +162,17 @@ impl Topic {
         matches!(self.max_topic_size, MaxTopicSize::Unlimited)
     }
 
+    /// Returns the number of messages exceeding the maximum number of messages retained by the topic.
+    pub fn get_excess_messages_count(&self) -> u64 {
+        if self.max_messages == 0 {
+            return 0;
+        }
+
+        self.messages_count
+            .load(Ordering::SeqCst)
+            .saturating_sub(self.max_messages)
+    }
+
     pub fn get_size(&self) -> IggyByteSize {
         IggyByteSize::from(self.size_bytes.load(Ordering::SeqCst))
     }
@@ -188,7 +208,10 @@ impl Topic {
                 ))
             }
             ConsumerKind::ConsumerGroup => {
-                let consumer_group = self.get_consumer_group(&consumer.id)?.read().await;
+                let consumer_group = self.get_consumer_group(&consumer.id)?;
+                self.expire_departed_consumer_group_members(consumer_group)
+                    .await?;
+                let consumer_group = consumer_group.read().await;
                 if let Some(partition_id) = partition_id {
                     return Ok((
                         PollingConsumer::consumer_group(consumer_group.group_id, client_id),
@@ -234,6 +257,16 @@ impl Topic {
             _ => message_expiry,
         }
     }
+
+    pub fn get_compression_algorithm(
+        compression_algorithm: CompressionAlgorithm,
+        config: &SystemConfig,
+    ) -> CompressionAlgorithm {
+        match config.compression.allow_override {
+            true => compression_algorithm,
+            false => config.compression.default_algorithm,
+        }
+    }
 }
 
 impl fmt::Display for Topic {
@@ -244,7 +277,9 @@ impl fmt::Display for Topic {
         write!(f, "path: {}, ", self.path)?;
         write!(f, "partitions count: {}, ", self.partitions.len())?;
         write!(f, "message expiry: {}, ", self.message_expiry)?;
+        write!(f, "compression algorithm: {}, ", self.compression_algorithm)?;
         write!(f, "max topic size: {}, ", self.max_topic_size)?;
+        write!(f, "max messages: {}, ", self.max_messages)?;
         write!(f, "replication factor: {}, ", self.replication_factor)
     }
 }
@@ -306,4 +341,22 @@ mod tests {
             assert_eq!(partition.segments.len(), 1);
         }
     }
+
+    #[test]
+    fn compression_algorithm_should_be_overridden_by_topic_only_when_allowed() {
+        let mut config = SystemConfig::default();
+        config.compression.default_algorithm = CompressionAlgorithm::Zstd;
+
+        config.compression.allow_override = false;
+        assert_eq!(
+            Topic::get_compression_algorithm(CompressionAlgorithm::Gzip, &config),
+            CompressionAlgorithm::Zstd
+        );
+
+        config.compression.allow_override = true;
+        assert_eq!(
+            Topic::get_compression_algorithm(CompressionAlgorithm::Gzip, &config),
+            CompressionAlgorithm::Gzip
+        );
+    }
 }
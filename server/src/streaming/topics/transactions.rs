@@ -0,0 +1,51 @@
+use crate::streaming::batching::appendable_batch_info::AppendableBatchInfo;
+use crate::streaming::topics::topic::Topic;
+use iggy::error::IggyError;
+use iggy::locking::IggySharedMutFn;
+use iggy::messages::send_messages::{Message, Partitioning};
+
+impl Topic {
+    /// Appends the messages sent within the transaction and returns the ID of the partition they were appended to,
+    /// so that the transaction can be completed in all the partitions it has touched.
+    pub async fn append_transactional_messages(
+        &self,
+        batch_size: u64,
+        partitioning: Partitioning,
+        messages: Vec<Message>,
+        transaction_id: u64,
+    ) -> Result<u32, IggyError> {
+        if !self.has_partitions() {
+            return Err(IggyError::NoPartitions(self.topic_id, self.stream_id));
+        }
+
+        if self.is_full() {
+            return Err(IggyError::TopicFull(self.topic_id, self.stream_id));
+        }
+
+        let partition_id = self.get_partition_id(&partitioning)?;
+        if messages.is_empty() {
+            return Ok(partition_id);
+        }
+
+        let partition = self.get_partition(partition_id)?;
+        let mut partition = partition.write().await;
+        partition.register_transaction(transaction_id);
+        partition
+            .append_messages(AppendableBatchInfo::new(batch_size, partition_id), messages)
+            .await?;
+        Ok(partition_id)
+    }
+
+    pub async fn complete_transaction(
+        &self,
+        partition_id: u32,
+        transaction_id: u64,
+        committed: bool,
+    ) -> Result<(), IggyError> {
+        self.get_partition(partition_id)?
+            .write()
+            .await
+            .complete_transaction(transaction_id, committed)
+            .await
+    }
+}
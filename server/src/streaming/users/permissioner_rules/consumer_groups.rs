@@ -55,4 +55,13 @@ impl Permissioner {
     ) -> Result<(), IggyError> {
         self.get_topic(user_id, stream_id, topic_id)
     }
+
+    pub fn heartbeat_consumer_group(
+        &self,
+        user_id: u32,
+        stream_id: u32,
+        topic_id: u32,
+    ) -> Result<(), IggyError> {
+        self.get_topic(user_id, stream_id, topic_id)
+    }
 }
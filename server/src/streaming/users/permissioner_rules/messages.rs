@@ -114,4 +114,22 @@ impl Permissioner {
 
         Err(IggyError::Unauthorized)
     }
+
+    pub fn nack_message(
+        &self,
+        user_id: u32,
+        stream_id: u32,
+        topic_id: u32,
+    ) -> Result<(), IggyError> {
+        self.poll_messages(user_id, stream_id, topic_id)
+    }
+
+    pub fn ack_message(
+        &self,
+        user_id: u32,
+        stream_id: u32,
+        topic_id: u32,
+    ) -> Result<(), IggyError> {
+        self.poll_messages(user_id, stream_id, topic_id)
+    }
 }
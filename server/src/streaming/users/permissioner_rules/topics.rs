@@ -104,6 +104,33 @@ impl Permissioner {
         self.manage_topic(user_id, stream_id, topic_id)
     }
 
+    pub fn set_dead_letter_queue(
+        &self,
+        user_id: u32,
+        stream_id: u32,
+        topic_id: u32,
+    ) -> Result<(), IggyError> {
+        self.manage_topic(user_id, stream_id, topic_id)
+    }
+
+    pub fn set_cleanup_policy(
+        &self,
+        user_id: u32,
+        stream_id: u32,
+        topic_id: u32,
+    ) -> Result<(), IggyError> {
+        self.manage_topic(user_id, stream_id, topic_id)
+    }
+
+    pub fn set_max_topic_messages(
+        &self,
+        user_id: u32,
+        stream_id: u32,
+        topic_id: u32,
+    ) -> Result<(), IggyError> {
+        self.manage_topic(user_id, stream_id, topic_id)
+    }
+
     fn manage_topic(&self, user_id: u32, stream_id: u32, topic_id: u32) -> Result<(), IggyError> {
         if let Some(global_permissions) = self.users_permissions.get(&user_id) {
             if global_permissions.manage_streams || global_permissions.manage_topics {
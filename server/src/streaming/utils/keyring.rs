@@ -0,0 +1,275 @@
+use crate::configs::system::EncryptionConfig;
+use iggy::error::IggyError;
+use iggy::utils::crypto::{Aes256GcmEncryptor, Encryptor};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use tracing::error;
+
+/// The size of the key ID prepended to the encrypted data.
+pub const KEY_ID_SIZE: usize = 4;
+/// The size of the nonce and the authentication tag added by AES-256-GCM, i.e. the size of the encrypted empty data.
+const ENCRYPTION_OVERHEAD: usize = 12 + 16;
+
+/// The set of the encryption keys identified by their IDs, used for the server-side encryption.
+/// The data is encrypted with the active key, or with the key selected for its stream or topic,
+/// and tagged with the ID of the key, so that it can be decrypted once the key is rotated.
+/// The data encrypted before the keys were tagged is decrypted with the first key that authenticates it.
+pub struct Keyring {
+    keys: BTreeMap<u32, Aes256GcmEncryptor>,
+    active_key_id: u32,
+    stream_keys: HashMap<u32, u32>,
+    topic_keys: HashMap<(u32, u32), u32>,
+}
+
+impl Debug for Keyring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keyring")
+            .field("key_ids", &self.keys.keys().collect::<Vec<_>>())
+            .field("active_key_id", &self.active_key_id)
+            .field("stream_keys", &self.stream_keys)
+            .field("topic_keys", &self.topic_keys)
+            .finish()
+    }
+}
+
+impl Keyring {
+    /// Creates the keyring from the active key, the additional keys and the ones from the keyring file.
+    pub fn from_config(config: &EncryptionConfig) -> Result<Self, IggyError> {
+        let mut keys = BTreeMap::new();
+        keys.insert(
+            config.active_key_id,
+            Aes256GcmEncryptor::from_base64_key(&config.key)?,
+        );
+
+        let mut entries = config
+            .keys
+            .split(',')
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        if !config.keyring_file.is_empty() {
+            let keyring_file = std::fs::read_to_string(&config.keyring_file).map_err(|error| {
+                error!(
+                    "Cannot read the keyring file: {}. Error: {error}",
+                    config.keyring_file
+                );
+                IggyError::InvalidEncryptionKey
+            })?;
+            entries.extend(keyring_file.lines().map(str::to_owned));
+        }
+
+        for entry in entries.iter().map(|entry| entry.trim()) {
+            if entry.is_empty() {
+                continue;
+            }
+
+            let (key_id, key) = parse_key_entry(entry)?;
+            if keys.contains_key(&key_id) {
+                error!("Encryption key with ID: {key_id} is defined more than once.");
+                return Err(IggyError::InvalidEncryptionKey);
+            }
+            keys.insert(key_id, Aes256GcmEncryptor::from_base64_key(key)?);
+        }
+
+        let mut stream_keys = HashMap::new();
+        let mut topic_keys = HashMap::new();
+        for entry in config.topic_keys.split(',').map(|entry| entry.trim()) {
+            if entry.is_empty() {
+                continue;
+            }
+
+            let (resource, key_id) = parse_topic_key_entry(entry)?;
+            if !keys.contains_key(&key_id) {
+                error!("Encryption key with ID: {key_id} selected for: {resource} does not exist.");
+                return Err(IggyError::InvalidEncryptionKey);
+            }
+
+            match resource.split_once('/') {
+                Some((stream_id, topic_id)) => {
+                    let (Ok(stream_id), Ok(topic_id)) =
+                        (stream_id.parse::<u32>(), topic_id.parse::<u32>())
+                    else {
+                        error!("Invalid topic for the encryption key: {resource}.");
+                        return Err(IggyError::InvalidEncryptionKey);
+                    };
+                    topic_keys.insert((stream_id, topic_id), key_id);
+                }
+                None => {
+                    let Ok(stream_id) = resource.parse::<u32>() else {
+                        error!("Invalid stream for the encryption key: {resource}.");
+                        return Err(IggyError::InvalidEncryptionKey);
+                    };
+                    stream_keys.insert(stream_id, key_id);
+                }
+            }
+        }
+
+        Ok(Self {
+            keys,
+            active_key_id: config.active_key_id,
+            stream_keys,
+            topic_keys,
+        })
+    }
+
+    pub fn active_key_id(&self) -> u32 {
+        self.active_key_id
+    }
+
+    /// Returns the ID of the key used to encrypt the data of the topic.
+    pub fn get_key_id(&self, stream_id: u32, topic_id: u32) -> u32 {
+        self.topic_keys
+            .get(&(stream_id, topic_id))
+            .or_else(|| self.stream_keys.get(&stream_id))
+            .copied()
+            .unwrap_or(self.active_key_id)
+    }
+
+    /// Encrypts the data with the key and prepends the key ID to it.
+    pub fn encrypt_with(&self, key_id: u32, data: &[u8]) -> Result<Vec<u8>, IggyError> {
+        let Some(key) = self.keys.get(&key_id) else {
+            error!("Encryption key with ID: {key_id} does not exist.");
+            return Err(IggyError::InvalidEncryptionKey);
+        };
+
+        let encrypted_data = key.encrypt(data)?;
+        let mut tagged_data = Vec::with_capacity(KEY_ID_SIZE + encrypted_data.len());
+        tagged_data.extend(key_id.to_le_bytes());
+        tagged_data.extend(encrypted_data);
+        Ok(tagged_data)
+    }
+
+    /// Returns the ID of the key the data is tagged with, if any.
+    /// The data encrypted before the keys were tagged might happen to start with the ID of the existing key,
+    /// so the ID is only a hint, the data is authenticated when decrypted.
+    pub fn get_data_key_id(&self, data: &[u8]) -> Option<u32> {
+        if data.len() < KEY_ID_SIZE + ENCRYPTION_OVERHEAD {
+            return None;
+        }
+
+        let key_id = u32::from_le_bytes(data[..KEY_ID_SIZE].try_into().ok()?);
+        self.keys.contains_key(&key_id).then_some(key_id)
+    }
+}
+
+impl Encryptor for Keyring {
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, IggyError> {
+        self.encrypt_with(self.active_key_id, data)
+    }
+
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, IggyError> {
+        if let Some(key_id) = self.get_data_key_id(data) {
+            if let Ok(decrypted_data) = self.keys[&key_id].decrypt(&data[KEY_ID_SIZE..]) {
+                return Ok(decrypted_data);
+            }
+        }
+
+        if data.len() < ENCRYPTION_OVERHEAD {
+            return Err(IggyError::CannotDecryptData);
+        }
+
+        self.keys
+            .values()
+            .find_map(|key| key.decrypt(data).ok())
+            .ok_or(IggyError::CannotDecryptData)
+    }
+}
+
+/// Parses the `<key ID>:<base64 encoded key>` entry.
+fn parse_key_entry(entry: &str) -> Result<(u32, &str), IggyError> {
+    let Some((key_id, key)) = entry.split_once(':') else {
+        error!("Invalid encryption key entry, expected: `<key ID>:<base64 encoded key>`.");
+        return Err(IggyError::InvalidEncryptionKey);
+    };
+
+    let Ok(key_id) = key_id.trim().parse::<u32>() else {
+        error!("Invalid encryption key ID: {key_id}.");
+        return Err(IggyError::InvalidEncryptionKey);
+    };
+
+    Ok((key_id, key.trim()))
+}
+
+/// Parses the `<stream ID>=<key ID>` or `<stream ID>/<topic ID>=<key ID>` entry.
+fn parse_topic_key_entry(entry: &str) -> Result<(&str, u32), IggyError> {
+    let Some((resource, key_id)) = entry.split_once('=') else {
+        error!("Invalid topic encryption key entry: {entry}, expected: `<stream ID>[/<topic ID>]=<key ID>`.");
+        return Err(IggyError::InvalidEncryptionKey);
+    };
+
+    let Ok(key_id) = key_id.trim().parse::<u32>() else {
+        error!("Invalid encryption key ID: {key_id} selected for: {resource}.");
+        return Err(IggyError::InvalidEncryptionKey);
+    };
+
+    Ok((resource.trim(), key_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iggy::utils::text::as_base64;
+
+    fn create_config(active_key_id: u32, keys: &str, topic_keys: &str) -> EncryptionConfig {
+        EncryptionConfig {
+            enabled: true,
+            key: as_base64(&[active_key_id as u8; 32]),
+            active_key_id,
+            keys: keys.to_owned(),
+            keyring_file: "".to_owned(),
+            topic_keys: topic_keys.to_owned(),
+            ..EncryptionConfig::default()
+        }
+    }
+
+    #[test]
+    fn given_rotated_key_data_encrypted_with_the_previous_key_should_be_decrypted() {
+        let previous_keyring = Keyring::from_config(&create_config(1, "", "")).unwrap();
+        let data = b"Hello World!";
+        let encrypted_data = previous_keyring.encrypt(data).unwrap();
+        assert_eq!(previous_keyring.get_data_key_id(&encrypted_data), Some(1));
+
+        let keys = format!("1:{}", as_base64(&[1; 32]));
+        let keyring = Keyring::from_config(&create_config(2, &keys, "")).unwrap();
+        let decrypted_data = keyring.decrypt(&encrypted_data).unwrap();
+        assert_eq!(data, decrypted_data.as_slice());
+        let encrypted_data = keyring.encrypt(data).unwrap();
+        assert_eq!(keyring.get_data_key_id(&encrypted_data), Some(2));
+    }
+
+    #[test]
+    fn given_untagged_data_it_should_be_decrypted_with_the_matching_key() {
+        let data = b"Hello World!";
+        let encrypted_data = Aes256GcmEncryptor::new(&[1; 32])
+            .unwrap()
+            .encrypt(data)
+            .unwrap();
+        let keys = format!("1:{}", as_base64(&[1; 32]));
+        let keyring = Keyring::from_config(&create_config(2, &keys, "")).unwrap();
+        let decrypted_data = keyring.decrypt(&encrypted_data).unwrap();
+        assert_eq!(data, decrypted_data.as_slice());
+    }
+
+    #[test]
+    fn given_removed_key_data_should_not_be_decrypted() {
+        let previous_keyring = Keyring::from_config(&create_config(1, "", "")).unwrap();
+        let encrypted_data = previous_keyring.encrypt(b"Hello World!").unwrap();
+        let keyring = Keyring::from_config(&create_config(2, "", "")).unwrap();
+        let error = keyring.decrypt(&encrypted_data).err().unwrap();
+        assert_eq!(error.as_code(), IggyError::CannotDecryptData.as_code());
+    }
+
+    #[test]
+    fn key_selected_for_topic_should_take_precedence_over_the_stream_and_active_ones() {
+        let keys = format!("2:{},3:{}", as_base64(&[2; 32]), as_base64(&[3; 32]));
+        let keyring = Keyring::from_config(&create_config(1, &keys, "1=2, 1/2=3")).unwrap();
+        assert_eq!(keyring.get_key_id(1, 2), 3);
+        assert_eq!(keyring.get_key_id(1, 1), 2);
+        assert_eq!(keyring.get_key_id(2, 2), 1);
+    }
+
+    #[test]
+    fn given_unknown_key_selected_for_topic_keyring_should_not_be_created() {
+        let result = Keyring::from_config(&create_config(1, "", "1/2=2"));
+        assert!(result.is_err());
+    }
+}
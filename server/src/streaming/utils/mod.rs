@@ -2,4 +2,5 @@ pub mod crypto;
 pub mod file;
 pub mod hash;
 pub mod head_tail_buf;
+pub mod keyring;
 pub mod random_id;
@@ -1,8 +1,16 @@
+#[cfg(target_os = "linux")]
+use crate::streaming::models::messages::FileRegion;
 use bytes::{BufMut, BytesMut};
 use iggy::error::IggyError;
 use std::mem::size_of;
+#[cfg(target_os = "linux")]
+use tokio::io::Interest;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+#[cfg(target_os = "linux")]
+use tokio::net::TcpStream;
 use tracing::debug;
+#[cfg(target_os = "linux")]
+use tracing::error;
 
 const STATUS_OK: &[u8] = &[0; 4];
 
@@ -37,6 +45,70 @@ where
     send_response(stream, STATUS_OK, payload).await
 }
 
+/// Sends the payload followed by the regions of the files, which are copied to the socket by the kernel using `sendfile`,
+/// thus the stored batches are never loaded into memory. The files of the regions are already open,
+/// so that the response is never interrupted when the segment is deleted in the meantime.
+#[cfg(target_os = "linux")]
+pub(crate) async fn send_ok_response_with_regions(
+    stream: &mut TcpStream,
+    payload: &[u8],
+    regions: &[FileRegion],
+) -> Result<(), IggyError> {
+    let length = payload.len() as u64 + regions.iter().map(|region| region.length).sum::<u64>();
+    // The response is rejected before anything is written, as its length wouldn't fit the header.
+    let Ok(length) = u32::try_from(length) else {
+        error!("Cannot send the response of {length} bytes, which exceeds the maximum length.");
+        return Err(IggyError::TooManyMessages);
+    };
+    debug!(
+        "Sending response with status: {:?} and {} file regions...",
+        STATUS_OK,
+        regions.len()
+    );
+    stream
+        .write_all(&[STATUS_OK, &length.to_le_bytes(), payload].concat())
+        .await?;
+    for region in regions {
+        send_file_region(stream, region).await?;
+    }
+    debug!("Sent response with status: {:?}", STATUS_OK);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+async fn send_file_region(stream: &TcpStream, region: &FileRegion) -> Result<(), IggyError> {
+    use std::os::fd::AsRawFd;
+
+    let socket_fd = stream.as_raw_fd();
+    let file_fd = region.file.as_raw_fd();
+    let end = region.position + region.length;
+    let mut offset = region.position as libc::off_t;
+    while (offset as u64) < end {
+        stream.writable().await?;
+        let remaining = (end - offset as u64) as usize;
+        let result = stream.try_io(Interest::WRITABLE, || {
+            // SAFETY: both descriptors are owned by the stream and the file, which outlive the call.
+            match unsafe { libc::sendfile(socket_fd, file_fd, &mut offset, remaining) } {
+                -1 => Err(std::io::Error::last_os_error()),
+                sent => Ok(sent as usize),
+            }
+        });
+        match result {
+            Ok(0) => {
+                error!(
+                    "Cannot send the region: {}...{end} of file: {}, the file has been truncated.",
+                    region.position, region.path
+                );
+                return Err(IggyError::CannotReadBatchPayload);
+            }
+            Ok(_) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(error) => return Err(IggyError::from(error)),
+        }
+    }
+    Ok(())
+}
+
 pub(crate) async fn send_error_response<T>(
     stream: &mut T,
     error: IggyError,
@@ -1,4 +1,6 @@
 use crate::binary::sender::Sender;
+#[cfg(target_os = "linux")]
+use crate::streaming::models::messages::FileRegion;
 use crate::tcp::sender;
 use async_trait::async_trait;
 use iggy::error::IggyError;
@@ -29,4 +31,13 @@ impl Sender for TcpSender {
     async fn send_error_response(&mut self, error: IggyError) -> Result<(), IggyError> {
         sender::send_error_response(&mut self.stream, error).await
     }
+
+    #[cfg(target_os = "linux")]
+    async fn send_ok_response_with_regions(
+        &mut self,
+        payload: &[u8],
+        regions: &[FileRegion],
+    ) -> Result<(), IggyError> {
+        sender::send_ok_response_with_regions(&mut self.stream, payload, regions).await
+    }
 }
@@ -0,0 +1,54 @@
+mod verifier;
+
+use clap::Parser;
+use server::configs::system::SystemConfig;
+use std::error::Error;
+use std::sync::Arc;
+use tracing::info;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry};
+
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, about, long_about = None)]
+pub struct SegmentVerifierArgs {
+    /// Path to the server data, the server must not be running while the segments are verified.
+    #[arg(long, default_value = "local_data")]
+    pub path: String,
+
+    /// ID of the stream to verify, all the streams are verified by default.
+    #[arg(long)]
+    pub stream_id: Option<u32>,
+
+    /// ID of the topic to verify, all the topics of the stream are verified by default.
+    #[arg(long, requires = "stream_id")]
+    pub topic_id: Option<u32>,
+
+    /// Truncate the corrupt tails of the segment logs and rebuild their index and time index files.
+    #[arg(long, default_value_t = false)]
+    pub repair: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args = SegmentVerifierArgs::parse();
+
+    // The report is printed to the standard output, so the logs are written to the standard error.
+    Registry::default()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(EnvFilter::try_from_default_env().unwrap_or(EnvFilter::new("WARN")))
+        .init();
+    let config = Arc::new(SystemConfig {
+        path: args.path.clone(),
+        ..Default::default()
+    });
+    info!("Segment verifier has started...");
+    let report = verifier::verify(config, &args).await?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    info!("Segment verifier has finished.");
+    if !report.is_valid() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use crate::SegmentVerifierArgs;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::error::IggyError;
+use iggy::utils::expiry::IggyExpiry;
+use serde::Serialize;
+use server::configs::system::SystemConfig;
+use server::streaming::persistence::persister::FilePersister;
+use server::streaming::segments::segment::{Segment, LOG_EXTENSION};
+use server::streaming::segments::verification::SegmentVerification;
+use server::streaming::storage::SystemStorage;
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use tokio::fs;
+use tracing::{info, warn};
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub path: String,
+    pub repair: bool,
+    pub segments_count: usize,
+    pub invalid_segments_count: usize,
+    pub repaired_segments_count: usize,
+    pub segments: Vec<SegmentReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SegmentReport {
+    pub stream_id: u32,
+    pub topic_id: u32,
+    pub partition_id: u32,
+    #[serde(flatten)]
+    pub verification: SegmentVerification,
+    pub repaired: bool,
+}
+
+impl Report {
+    /// Returns true if all the segments are valid, or all their issues have been repaired.
+    pub fn is_valid(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            segment.verification.is_valid()
+                || (segment.repaired && segment.verification.is_repairable())
+        })
+    }
+}
+
+pub async fn verify(
+    config: Arc<SystemConfig>,
+    args: &SegmentVerifierArgs,
+) -> Result<Report, IggyError> {
+    let storage = Arc::new(SystemStorage::new(config.clone(), Arc::new(FilePersister)));
+    let mut report = Report {
+        path: config.get_system_path(),
+        repair: args.repair,
+        segments_count: 0,
+        invalid_segments_count: 0,
+        repaired_segments_count: 0,
+        segments: Vec::new(),
+    };
+
+    let stream_ids = match args.stream_id {
+        Some(stream_id) => vec![stream_id],
+        None => get_ids(&config.get_streams_path()).await?,
+    };
+    for stream_id in stream_ids {
+        let topic_ids = match args.topic_id {
+            Some(topic_id) => vec![topic_id],
+            None => get_ids(&config.get_topics_path(stream_id)).await?,
+        };
+        for topic_id in topic_ids {
+            let partition_ids = get_ids(&config.get_partitions_path(stream_id, topic_id)).await?;
+            for partition_id in partition_ids {
+                let partition_path = config.get_partition_path(stream_id, topic_id, partition_id);
+                for start_offset in get_start_offsets(&partition_path).await? {
+                    let segment = Segment::create(
+                        stream_id,
+                        topic_id,
+                        partition_id,
+                        start_offset,
+                        config.clone(),
+                        storage.clone(),
+                        IggyExpiry::NeverExpire,
+                        CompressionAlgorithm::None,
+                        Arc::new(AtomicU64::new(0)),
+                        Arc::new(AtomicU64::new(0)),
+                        Arc::new(AtomicU64::new(0)),
+                        Arc::new(AtomicU64::new(0)),
+                        Arc::new(AtomicU64::new(0)),
+                        Arc::new(AtomicU64::new(0)),
+                    );
+                    let segment_report = verify_segment(&storage, &segment, args.repair).await?;
+                    report.segments_count += 1;
+                    if !segment_report.verification.is_valid() {
+                        report.invalid_segments_count += 1;
+                    }
+                    if segment_report.repaired {
+                        report.repaired_segments_count += 1;
+                    }
+                    report.segments.push(segment_report);
+                }
+            }
+        }
+    }
+
+    info!(
+        "Verified {} segments, found {} invalid and repaired {} segments.",
+        report.segments_count, report.invalid_segments_count, report.repaired_segments_count
+    );
+    Ok(report)
+}
+
+async fn verify_segment(
+    storage: &SystemStorage,
+    segment: &Segment,
+    repair: bool,
+) -> Result<SegmentReport, IggyError> {
+    let verification = storage.segment.verify(segment).await?;
+    let mut repaired = false;
+    if !verification.is_valid() {
+        warn!(
+            "Segment: {} has {} issues.",
+            segment.log_path,
+            verification.issues.len()
+        );
+        if repair && (verification.has_corrupt_tail() || verification.has_index_mismatch()) {
+            storage.segment.repair(segment, &verification).await?;
+            repaired = true;
+        }
+    }
+
+    Ok(SegmentReport {
+        stream_id: segment.stream_id,
+        topic_id: segment.topic_id,
+        partition_id: segment.partition_id,
+        verification,
+        repaired,
+    })
+}
+
+/// Returns the sorted numeric IDs of the directories (streams, topics or partitions) in the given path.
+async fn get_ids(path: &str) -> Result<Vec<u32>, IggyError> {
+    let mut ids = Vec::new();
+    if !Path::new(path).exists() {
+        return Ok(ids);
+    }
+
+    let mut dir_entries = fs::read_dir(path).await?;
+    while let Some(dir_entry) = dir_entries.next_entry().await? {
+        if !dir_entry.metadata().await?.is_dir() {
+            continue;
+        }
+
+        if let Some(id) = dir_entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        {
+            ids.push(id);
+        }
+    }
+    ids.sort();
+    Ok(ids)
+}
+
+/// Returns the sorted start offsets of the segments stored locally, the offloaded ones are skipped.
+async fn get_start_offsets(partition_path: &str) -> Result<Vec<u64>, IggyError> {
+    let mut start_offsets = Vec::new();
+    let mut dir_entries = fs::read_dir(partition_path).await?;
+    while let Some(dir_entry) = dir_entries.next_entry().await? {
+        let path = dir_entry.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some(LOG_EXTENSION) {
+            continue;
+        }
+
+        if let Some(start_offset) = path
+            .file_stem()
+            .and_then(|file_stem| file_stem.to_str())
+            .and_then(|file_stem| file_stem.parse::<u64>().ok())
+        {
+            start_offsets.push(start_offset);
+        }
+    }
+    start_offsets.sort();
+    Ok(start_offsets)
+}
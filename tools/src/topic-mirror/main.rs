@@ -0,0 +1,108 @@
+mod mirror;
+
+use clap::Parser;
+use iggy::client::{Client, UserClient};
+use iggy::clients::client::IggyClient;
+use iggy::error::IggyError;
+use iggy::utils::duration::IggyDuration;
+use std::error::Error;
+use std::sync::Arc;
+use tracing::info;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry};
+
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, about, long_about = None)]
+pub struct TopicMirrorArgs {
+    /// TCP address of the source server, the messages are mirrored from.
+    #[arg(long, default_value = "127.0.0.1:8090")]
+    pub source_address: String,
+
+    #[arg(long, default_value = "iggy")]
+    pub source_username: String,
+
+    #[arg(long, default_value = "iggy")]
+    pub source_password: String,
+
+    /// TCP address of the target server, the messages are mirrored to.
+    #[arg(long)]
+    pub target_address: String,
+
+    #[arg(long, default_value = "iggy")]
+    pub target_username: String,
+
+    #[arg(long, default_value = "iggy")]
+    pub target_password: String,
+
+    /// Regex matching the names of the streams to mirror, use `^name$` for the exact match.
+    #[arg(long, default_value = ".*")]
+    pub streams: String,
+
+    /// Regex matching the names of the topics to mirror within the selected streams.
+    #[arg(long, default_value = ".*")]
+    pub topics: String,
+
+    /// Name of the consumer, which offsets are stored on the source server as the mirroring checkpoints.
+    #[arg(long, default_value = "iggy-mirror")]
+    pub consumer_name: String,
+
+    /// Maximum number of messages polled from the source partition and sent to the target at once.
+    #[arg(long, default_value_t = 1000)]
+    pub batch_size: u32,
+
+    /// Interval between the polls once the source partition has been fully mirrored.
+    #[arg(long, default_value = "10 ms")]
+    pub poll_interval: IggyDuration,
+
+    /// Interval of discovering the new topics and partitions on the source server.
+    #[arg(long, default_value = "30 s")]
+    pub discovery_interval: IggyDuration,
+
+    /// Interval of reporting the number of messages not yet mirrored for each topic.
+    #[arg(long, default_value = "10 s")]
+    pub lag_interval: IggyDuration,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args = TopicMirrorArgs::parse();
+
+    Registry::default()
+        .with(tracing_subscriber::fmt::layer())
+        .with(EnvFilter::try_from_default_env().unwrap_or(EnvFilter::new("INFO")))
+        .init();
+    let source = connect(
+        &args.source_address,
+        &args.source_username,
+        &args.source_password,
+    )
+    .await?;
+    let target = connect(
+        &args.target_address,
+        &args.target_username,
+        &args.target_password,
+    )
+    .await?;
+    info!(
+        "Topic mirror has started, source: {}, target: {}...",
+        args.source_address, args.target_address
+    );
+    mirror::Mirror::new(args, source, target)?.run().await?;
+    info!("Topic mirror has finished.");
+    Ok(())
+}
+
+async fn connect(
+    address: &str,
+    username: &str,
+    password: &str,
+) -> Result<Arc<IggyClient>, IggyError> {
+    let client = IggyClient::builder()
+        .with_tcp()
+        .with_server_address(address.to_string())
+        .build()?;
+    client.connect().await?;
+    client.login_user(username, password).await?;
+    Ok(Arc::new(client))
+}
@@ -0,0 +1,371 @@
+use crate::TopicMirrorArgs;
+use anyhow::Result;
+use futures_util::StreamExt;
+use iggy::client::{ConsumerOffsetClient, PartitionClient, StreamClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::clients::consumer::{AutoCommit, IggyConsumer};
+use iggy::clients::producer::IggyProducer;
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::PollingStrategy;
+use iggy::messages::send_messages::{Message, Partitioning};
+use iggy::models::topic::TopicDetails;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Continuously copies the selected topics from the source to the target server.
+/// Each source partition is mirrored by the dedicated task to the partition with the same ID on the target,
+/// preserving the message IDs and headers. Once the batch is sent to the target, its last offset is stored
+/// as the consumer offset on the source, so the mirroring resumes from the checkpoint after the restart
+/// and the messages are delivered at least once.
+pub struct Mirror {
+    args: TopicMirrorArgs,
+    source: Arc<IggyClient>,
+    target: Arc<IggyClient>,
+    streams: Regex,
+    topics: Regex,
+    mirrored_topics: BTreeMap<(String, String), MirroredTopic>,
+}
+
+struct MirroredTopic {
+    producer: Arc<IggyProducer>,
+    partitions: BTreeMap<u32, MirroredPartition>,
+}
+
+struct MirroredPartition {
+    /// The offset of the next message to be mirrored.
+    next_offset: Arc<AtomicU64>,
+    task: JoinHandle<()>,
+}
+
+impl Mirror {
+    pub fn new(
+        args: TopicMirrorArgs,
+        source: Arc<IggyClient>,
+        target: Arc<IggyClient>,
+    ) -> Result<Self> {
+        Ok(Self {
+            streams: Regex::new(&args.streams)?,
+            topics: Regex::new(&args.topics)?,
+            args,
+            source,
+            target,
+            mirrored_topics: BTreeMap::new(),
+        })
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        let mut discovery_interval =
+            tokio::time::interval(self.args.discovery_interval.get_duration());
+        let mut lag_interval = tokio::time::interval(self.args.lag_interval.get_duration());
+        loop {
+            tokio::select! {
+                _ = discovery_interval.tick() => {
+                    if let Err(error) = self.discover().await {
+                        error!("Cannot discover the topics to mirror. {error}");
+                    }
+                }
+                _ = lag_interval.tick() => {
+                    if let Err(error) = self.report_lag().await {
+                        error!("Cannot report the mirroring lag. {error}");
+                    }
+                }
+                _ = tokio::signal::ctrl_c() => {
+                    info!("Stopping the topic mirror...");
+                    break;
+                }
+            }
+        }
+
+        for topic in self.mirrored_topics.values() {
+            for partition in topic.partitions.values() {
+                partition.task.abort();
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts mirroring the source partitions of the selected topics, which are not mirrored yet,
+    /// and stops mirroring the topics which no longer exist on the source.
+    async fn discover(&mut self) -> Result<()> {
+        let mut discovered_topics = HashSet::new();
+        for stream in self.source.get_streams().await? {
+            if !self.streams.is_match(&stream.name) {
+                continue;
+            }
+
+            let stream_id = Identifier::named(&stream.name)?;
+            for topic in self.source.get_topics(&stream_id).await? {
+                if !self.topics.is_match(&topic.name) {
+                    continue;
+                }
+
+                let topic_id = Identifier::named(&topic.name)?;
+                let Some(topic) = self.source.get_topic(&stream_id, &topic_id).await? else {
+                    continue;
+                };
+
+                discovered_topics.insert((stream.name.clone(), topic.name.clone()));
+                self.mirror_topic(&stream.name, &topic).await?;
+            }
+        }
+
+        self.mirrored_topics.retain(|(stream, topic), mirrored_topic| {
+            if discovered_topics.contains(&(stream.clone(), topic.clone())) {
+                return true;
+            }
+
+            info!("Topic: {topic} in stream: {stream} no longer exists on the source, stopping the mirroring.");
+            for partition in mirrored_topic.partitions.values() {
+                partition.task.abort();
+            }
+            false
+        });
+        Ok(())
+    }
+
+    async fn mirror_topic(&mut self, stream: &str, topic: &TopicDetails) -> Result<()> {
+        let key = (stream.to_string(), topic.name.clone());
+        let mirrored_partitions_count = self
+            .mirrored_topics
+            .get(&key)
+            .map(|mirrored_topic| mirrored_topic.partitions.len() as u32)
+            .unwrap_or_default();
+        if mirrored_partitions_count >= topic.partitions_count {
+            return Ok(());
+        }
+
+        self.create_target_topic(stream, topic).await?;
+        if !self.mirrored_topics.contains_key(&key) {
+            let mut producer = self
+                .target
+                .producer(stream, &topic.name)?
+                .batch_size(self.args.batch_size)
+                .without_send_interval()
+                .do_not_create_stream_if_not_exists()
+                .do_not_create_topic_if_not_exists()
+                .build();
+            producer.init().await?;
+            info!(
+                "Started mirroring topic: {} in stream: {stream}.",
+                topic.name
+            );
+            self.mirrored_topics.insert(
+                key.clone(),
+                MirroredTopic {
+                    producer: Arc::new(producer),
+                    partitions: BTreeMap::new(),
+                },
+            );
+        }
+
+        let Some(mirrored_topic) = self.mirrored_topics.get(&key) else {
+            return Ok(());
+        };
+
+        let producer = mirrored_topic.producer.clone();
+        let missing_partitions = topic
+            .partitions
+            .iter()
+            .map(|partition| partition.id)
+            .filter(|partition_id| !mirrored_topic.partitions.contains_key(partition_id))
+            .collect::<Vec<_>>();
+        for partition_id in missing_partitions {
+            let next_offset = self
+                .get_checkpoint(stream, &topic.name, partition_id)
+                .await?;
+            let mut consumer = self
+                .source
+                .consumer(&self.args.consumer_name, stream, &topic.name, partition_id)?
+                .polling_strategy(PollingStrategy::next())
+                .auto_commit(AutoCommit::Disabled)
+                .batch_size(self.args.batch_size)
+                .poll_interval(self.args.poll_interval)
+                .build();
+            consumer.init().await?;
+            let next_offset = Arc::new(AtomicU64::new(next_offset));
+            let task = tokio::spawn(mirror_partition(
+                consumer,
+                partition_id,
+                producer.clone(),
+                self.source.clone(),
+                Consumer::new(Identifier::named(&self.args.consumer_name)?),
+                next_offset.clone(),
+                self.args.batch_size as usize,
+            ));
+            if let Some(mirrored_topic) = self.mirrored_topics.get_mut(&key) {
+                mirrored_topic
+                    .partitions
+                    .insert(partition_id, MirroredPartition { next_offset, task });
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the stream and the topic on the target server if they don't exist yet,
+    /// or the missing partitions, as the messages are mirrored to the partitions with the same IDs.
+    async fn create_target_topic(&self, stream: &str, topic: &TopicDetails) -> Result<()> {
+        let stream_id = Identifier::named(stream)?;
+        let topic_id = Identifier::named(&topic.name)?;
+        if self.target.get_stream(&stream_id).await?.is_none() {
+            info!("Creating stream: {stream} on the target.");
+            self.target.create_stream(stream, None).await?;
+        }
+
+        // The replication factor is not copied, as the target server may run a different cluster.
+        let Some(target_topic) = self.target.get_topic(&stream_id, &topic_id).await? else {
+            info!(
+                "Creating topic: {} in stream: {stream} on the target.",
+                topic.name
+            );
+            self.target
+                .create_topic(
+                    &stream_id,
+                    &topic.name,
+                    topic.partitions_count,
+                    topic.compression_algorithm,
+                    None,
+                    None,
+                    topic.message_expiry,
+                    topic.max_topic_size,
+                )
+                .await?;
+            return Ok(());
+        };
+
+        if target_topic.partitions_count < topic.partitions_count {
+            let partitions_count = topic.partitions_count - target_topic.partitions_count;
+            info!(
+                "Creating {partitions_count} partition(s) for topic: {} in stream: {stream} on the target.",
+                topic.name
+            );
+            self.target
+                .create_partitions(&stream_id, &topic_id, partitions_count)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the offset of the next message to be mirrored based on the offset stored on the source.
+    async fn get_checkpoint(&self, stream: &str, topic: &str, partition_id: u32) -> Result<u64> {
+        let offset = self
+            .source
+            .get_consumer_offset(
+                &Consumer::new(Identifier::named(&self.args.consumer_name)?),
+                &Identifier::named(stream)?,
+                &Identifier::named(topic)?,
+                Some(partition_id),
+            )
+            .await?;
+        Ok(offset.map(|offset| offset.stored_offset + 1).unwrap_or(0))
+    }
+
+    async fn report_lag(&self) -> Result<()> {
+        for ((stream, topic), mirrored_topic) in &self.mirrored_topics {
+            let Some(details) = self
+                .source
+                .get_topic(&Identifier::named(stream)?, &Identifier::named(topic)?)
+                .await?
+            else {
+                continue;
+            };
+
+            let mut total_lag = 0;
+            let mut partitions_lag = Vec::with_capacity(mirrored_topic.partitions.len());
+            for partition in &details.partitions {
+                let Some(mirrored_partition) = mirrored_topic.partitions.get(&partition.id) else {
+                    continue;
+                };
+
+                let end_offset = if partition.current_offset == 0 && partition.messages_count == 0 {
+                    0
+                } else {
+                    partition.current_offset + 1
+                };
+                let lag = end_offset
+                    .saturating_sub(mirrored_partition.next_offset.load(Ordering::SeqCst));
+                total_lag += lag;
+                partitions_lag.push(format!("{}: {lag}", partition.id));
+            }
+
+            info!(
+                "Mirroring lag for topic: {topic} in stream: {stream} is {total_lag} message(s), partitions: [{}].",
+                partitions_lag.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Sends the messages polled from the source partition to the same partition on the target,
+/// then checkpoints the offset of the last sent message on the source, retrying until both succeed.
+async fn mirror_partition(
+    consumer: IggyConsumer,
+    partition_id: u32,
+    producer: Arc<IggyProducer>,
+    source: Arc<IggyClient>,
+    checkpoint_consumer: Consumer,
+    next_offset: Arc<AtomicU64>,
+    batch_size: usize,
+) {
+    let stream_id = consumer.stream().clone();
+    let topic_id = consumer.topic().clone();
+    let partitioning = Arc::new(Partitioning::partition_id(partition_id));
+    let mut batches = consumer.ready_chunks(batch_size.max(1));
+    while let Some(batch) = batches.next().await {
+        let mut messages = Vec::with_capacity(batch.len());
+        let mut last_offset = None;
+        for received_message in batch {
+            match received_message {
+                Ok(received_message) => {
+                    let message = received_message.message;
+                    last_offset = Some(message.offset);
+                    messages.push(Message::new(
+                        Some(message.id),
+                        message.payload,
+                        message.headers,
+                    ));
+                }
+                Err(error) => {
+                    error!("Cannot poll the messages from partition: {partition_id} of topic: {topic_id} in stream: {stream_id}. {error}");
+                }
+            }
+        }
+
+        let Some(last_offset) = last_offset else {
+            continue;
+        };
+
+        while let Err(error) = producer
+            .send_with_partitioning(messages.clone(), Some(partitioning.clone()))
+            .await
+        {
+            warn!("Cannot send the messages to partition: {partition_id} of topic: {topic_id} in stream: {stream_id}, retrying in {RETRY_INTERVAL:?}. {error}");
+            sleep(RETRY_INTERVAL).await;
+        }
+
+        while let Err(error) = source
+            .store_consumer_offset(
+                &checkpoint_consumer,
+                &stream_id,
+                &topic_id,
+                Some(partition_id),
+                last_offset,
+            )
+            .await
+        {
+            warn!("Cannot store the offset: {last_offset} for partition: {partition_id} of topic: {topic_id} in stream: {stream_id}, retrying in {RETRY_INTERVAL:?}. {error}");
+            sleep(RETRY_INTERVAL).await;
+        }
+
+        next_offset.store(last_offset + 1, Ordering::SeqCst);
+    }
+}